@@ -0,0 +1,1362 @@
+//! API client for the SWEeM REST API.
+//!
+//! This module provides an async HTTP client for communicating with the backend.
+//! All methods are non-blocking and designed to run in a separate Tokio task.
+//! Supports full CRUD operations for Clients, Projects, and Users.
+
+#![allow(dead_code)]
+
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use reqwest::{Certificate, Client, ClientBuilder};
+use uuid::Uuid;
+
+use crate::models::{
+    ChangeRecord, ClientDto, CreateClientDto, CreateProjectDto, CreateUserDto, PaginatedResult,
+    ProblemDetails, ProjectDto, UpdateClientDto, UpdateProjectDto, UpdateUserDto, UserDto,
+};
+
+/// Default API base URL
+pub const DEFAULT_BASE_URL: &str = "http://localhost:5094";
+
+/// Default timeout for API requests
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The health check gives up quickly so an unroutable host is reported fast
+pub const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Health endpoint probed before falling back to a projects request
+pub const DEFAULT_HEALTH_PATH: &str = "/health";
+
+/// Wait assumed when a 429 response has no usable Retry-After header
+pub const DEFAULT_RETRY_AFTER: Duration = Duration::from_secs(5);
+
+/// Longest Retry-After honoured, so a bogus date can't stall the worker
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(300);
+
+/// Check that a user-supplied base URL is an absolute http(s) URL.
+///
+/// Returns the trimmed URL without a trailing slash, ready for `ApiClient::new`.
+pub fn validate_base_url(url: &str) -> Result<String, String> {
+    let trimmed = url.trim().trim_end_matches('/');
+    let parsed = reqwest::Url::parse(trimmed).map_err(|e| format!("Invalid URL: {}", e))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err("URL must start with http:// or https://".to_string());
+    }
+    if parsed.host_str().is_none() {
+        return Err("URL must include a host".to_string());
+    }
+    Ok(trimmed.to_string())
+}
+
+/// "localhost:5094" for "http://localhost:5094/api"; the port only when
+/// the URL names one
+pub fn url_host(url: &str) -> Option<String> {
+    let parsed = reqwest::Url::parse(url).ok()?;
+    let host = parsed.host_str()?;
+    Some(match parsed.port() {
+        Some(port) => format!("{}:{}", host, port),
+        None => host.to_string(),
+    })
+}
+
+/// HTTPS settings beyond the system trust store (`--ca-cert` and `--insecure`)
+#[derive(Debug, Clone, Default)]
+pub struct TlsConfig {
+    /// Extra root certificates, e.g. an internal CA
+    pub ca_certs: Vec<Certificate>,
+    /// Accept any server certificate
+    pub insecure: bool,
+}
+
+impl TlsConfig {
+    /// Read the PEM bundle at `ca_cert`, if one is given
+    pub fn load(ca_cert: Option<&Path>, insecure: bool) -> Result<Self> {
+        let ca_certs = match ca_cert {
+            Some(path) => {
+                let pem = std::fs::read(path)
+                    .with_context(|| format!("Failed to read CA certificate {}", path.display()))?;
+                let certs = Certificate::from_pem_bundle(&pem)
+                    .with_context(|| format!("Invalid CA certificate {}", path.display()))?;
+                if certs.is_empty() {
+                    anyhow::bail!("No certificates found in {}", path.display());
+                }
+                certs
+            }
+            None => Vec::new(),
+        };
+        Ok(Self { ca_certs, insecure })
+    }
+
+    fn apply(&self, mut builder: ClientBuilder) -> ClientBuilder {
+        for cert in &self.ca_certs {
+            builder = builder.add_root_certificate(cert.clone());
+        }
+        builder.danger_accept_invalid_certs(self.insecure)
+    }
+}
+
+/// Page size used when fetching whole collections
+const FETCH_ALL_PAGE_SIZE: i32 = 100;
+
+/// Most projects sent in one batch create request
+pub const BATCH_SIZE: usize = 100;
+
+/// Outcome of one batch create request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChunkProgress {
+    /// Which chunk finished, counting from 1
+    pub chunk: usize,
+    pub chunks: usize,
+    pub created: usize,
+    pub failed: usize,
+}
+
+/// A whole collection gathered by `fetch_all_pages`, with the server's
+/// paging metadata from the last page
+#[derive(Debug, Clone, PartialEq)]
+pub struct FetchedAll<T> {
+    pub items: Vec<T>,
+    /// Server total, when it reports a usable one
+    pub total_count: Option<usize>,
+    /// Number of pages on the server
+    pub total_pages: i32,
+    /// Pages fetched
+    pub pages: i32,
+    /// The page that failed and ended the load early, if one did
+    pub failed: Option<PageFailure>,
+    /// Paging the server got wrong, which ended the load early
+    pub anomaly: Option<PageAnomaly>,
+}
+
+/// Why a full load stopped before its last page
+#[derive(Debug, Clone, PartialEq)]
+pub struct PageFailure {
+    /// First page not loaded
+    pub page: i32,
+    /// Last page the server reported
+    pub last_page: i32,
+    pub error: ApiError,
+}
+
+/// A page that contradicts the ones before it. Paging past it would only
+/// pile up repeats or skip items, so the load stops there.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PageAnomaly {
+    /// The server answered with a different page than the one asked for
+    WrongPage { requested: i32, echoed: i32 },
+    /// A page brought back items an earlier page already had
+    RepeatedItems { page: i32, repeated: usize },
+    /// The total count changed between pages
+    TotalChanged { page: i32, from: i32, to: i32 },
+}
+
+impl std::fmt::Display for PageAnomaly {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::WrongPage { requested, echoed } => {
+                write!(f, "asked for page {}, the server sent page {}", requested, echoed)
+            }
+            Self::RepeatedItems { page, repeated } => {
+                let plural = if *repeated == 1 { "" } else { "s" };
+                write!(f, "page {} repeated {} item{} already loaded", page, repeated, plural)
+            }
+            Self::TotalChanged { page, from, to } => {
+                write!(f, "the total changed from {} to {} at page {}", from, to, page)
+            }
+        }
+    }
+}
+
+impl<T> FetchedAll<T> {
+    /// Whether fewer items arrived than the server says it has
+    pub fn is_partial(&self) -> bool {
+        self.stopped_early() || self.total_count.is_some_and(|total| self.items.len() < total)
+    }
+
+    /// Whether a failed or contradictory page ended the load
+    pub fn stopped_early(&self) -> bool {
+        self.failed.is_some() || self.anomaly.is_some()
+    }
+}
+
+impl<T> From<Vec<T>> for FetchedAll<T> {
+    /// A complete single-page collection
+    fn from(items: Vec<T>) -> Self {
+        Self { total_count: Some(items.len()), total_pages: 1, pages: 1, failed: None, anomaly: None, items }
+    }
+}
+
+/// The first way `result`, the answer for `page`, contradicts the pages
+/// before it: the echoed page number, ids already `seen`, or the total
+/// reported by page 1
+fn page_anomaly<T, K>(
+    page: i32,
+    result: &PaginatedResult<T>,
+    first_total: Option<i32>,
+    seen: &HashSet<K>,
+    key: impl Fn(&T) -> K,
+) -> Option<PageAnomaly>
+where
+    K: Eq + std::hash::Hash,
+{
+    if result.page != page {
+        return Some(PageAnomaly::WrongPage { requested: page, echoed: result.page });
+    }
+    let mut ids = HashSet::new();
+    let mut repeated = 0;
+    for id in result.items().iter().map(key) {
+        if seen.contains(&id) || !ids.insert(id) {
+            repeated += 1;
+        }
+    }
+    if repeated > 0 {
+        return Some(PageAnomaly::RepeatedItems { page, repeated });
+    }
+    match first_total {
+        Some(from) if from != result.total_count => Some(PageAnomaly::TotalChanged { page, from, to: result.total_count }),
+        _ => None,
+    }
+}
+
+/// Collect every page of a paginated endpoint, reporting progress after each
+/// page. A failure after the first page ends the load with what arrived so far,
+/// and so does a page that contradicts the earlier ones (`PageAnomaly`); that
+/// page is left out.
+async fn fetch_all_pages<T, K, F, Fut>(
+    mut fetch_page: F,
+    key: impl Fn(&T) -> K,
+    mut progress: impl FnMut(usize, Option<usize>),
+) -> Result<FetchedAll<T>, ApiError>
+where
+    T: Clone,
+    K: Eq + std::hash::Hash,
+    F: FnMut(i32) -> Fut,
+    Fut: std::future::Future<Output = Result<PaginatedResult<T>, ApiError>>,
+{
+    let mut all =
+        FetchedAll { items: Vec::new(), total_count: None, total_pages: 1, pages: 0, failed: None, anomaly: None };
+    let mut seen = HashSet::new();
+    let mut first_total = None;
+
+    loop {
+        let page = all.pages + 1;
+        let result = match fetch_page(page).await {
+            Ok(result) => result,
+            // Nothing to show yet, or worth retrying from the start
+            Err(error) if page == 1 || matches!(error, ApiError::RateLimited { .. }) => return Err(error),
+            // Keep what arrived; the caller reports the pages that didn't
+            Err(error) => {
+                all.failed = Some(PageFailure { page, last_page: all.total_pages.max(page), error });
+                return Ok(all);
+            }
+        };
+        if let Some(anomaly) = page_anomaly(page, &result, first_total, &seen, &key) {
+            all.total_pages = all.total_pages.max(page);
+            all.anomaly = Some(anomaly);
+            return Ok(all);
+        }
+        first_total.get_or_insert(result.total_count);
+        seen.extend(result.items().iter().map(&key));
+        all.pages += 1;
+        all.items.extend(result.items().to_vec());
+
+        // Servers that don't report a usable total get an open-ended count
+        all.total_count = usize::try_from(result.total_count).ok().filter(|&t| t >= all.items.len());
+        all.total_pages = result.total_pages.max(all.pages);
+        progress(all.items.len(), all.total_count);
+
+        if !result.has_next {
+            break;
+        }
+    }
+
+    Ok(all)
+}
+
+/// API client for the SWEeM backend
+#[derive(Debug, Clone)]
+pub struct ApiClient {
+    client: Client,
+    base_url: String,
+    health_timeout: Duration,
+    health_path: String,
+    /// Probe that worked last time, shared between clones
+    health_probe: Arc<OnceLock<HealthProbe>>,
+    /// Receives a record of every call when tracing is on
+    trace: Option<TraceSink>,
+    /// End of the latest rate limit, shared between clones
+    rate_limited_until: Arc<Mutex<Option<Instant>>>,
+    tls: TlsConfig,
+}
+
+/// How the server's health is checked
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HealthProbe {
+    /// The dedicated health endpoint
+    Endpoint,
+    /// First page of projects, for servers without a health endpoint
+    Projects,
+}
+
+impl ApiClient {
+    /// Create a new API client with the specified base URL
+    pub fn new(base_url: impl Into<String>) -> Result<Self> {
+        Self::with_token(base_url, None, DEFAULT_TIMEOUT, TlsConfig::default())
+    }
+
+    /// Create a new API client that authenticates with a bearer token, gives
+    /// up on requests after `timeout` and verifies HTTPS servers per `tls`
+    pub fn with_token(
+        base_url: impl Into<String>,
+        token: Option<&str>,
+        timeout: Duration,
+        tls: TlsConfig,
+    ) -> Result<Self> {
+        let mut headers = reqwest::header::HeaderMap::new();
+        if let Some(token) = token {
+            let mut value = reqwest::header::HeaderValue::from_str(&format!("Bearer {}", token))
+                .context("Invalid API token")?;
+            value.set_sensitive(true);
+            headers.insert(reqwest::header::AUTHORIZATION, value);
+        }
+
+        let client = tls
+            .apply(Client::builder())
+            .timeout(timeout)
+            .default_headers(headers)
+            .build()
+            .context("Failed to create HTTP client")?;
+
+        Ok(Self {
+            client,
+            base_url: base_url.into(),
+            health_timeout: HEALTH_CHECK_TIMEOUT.min(timeout),
+            health_path: DEFAULT_HEALTH_PATH.to_string(),
+            health_probe: Arc::new(OnceLock::new()),
+            trace: None,
+            rate_limited_until: Arc::new(Mutex::new(None)),
+            tls,
+        })
+    }
+
+    /// A client for another server that keeps this one's health path, TLS
+    /// settings and tracing
+    pub fn retarget(&self, base_url: impl Into<String>, token: Option<&str>, timeout: Duration) -> Result<Self> {
+        let mut client =
+            Self::with_token(base_url, token, timeout, self.tls.clone())?.with_health_path(self.health_path.clone());
+        client.trace = self.trace.clone();
+        Ok(client)
+    }
+
+    /// Time left before the server accepts requests again after a 429
+    pub fn rate_limit_remaining(&self) -> Option<Duration> {
+        let until = (*self.rate_limited_until.lock().unwrap())?;
+        until.checked_duration_since(Instant::now()).filter(|d| !d.is_zero())
+    }
+
+    /// Report every request to `sink` (`--trace-api`)
+    pub fn with_trace(mut self, sink: impl Fn(&ApiTrace) + Send + Sync + 'static) -> Self {
+        self.trace = Some(TraceSink(Arc::new(sink)));
+        self
+    }
+
+    /// Override how long the health check waits for an answer
+    pub fn with_health_timeout(mut self, timeout: Duration) -> Self {
+        self.health_timeout = timeout;
+        self
+    }
+
+    /// Use a health endpoint other than `/health`
+    pub fn with_health_path(mut self, path: impl Into<String>) -> Self {
+        self.health_path = path.into();
+        self
+    }
+
+    /// Whether server certificates go unchecked (`--insecure`)
+    pub fn is_insecure(&self) -> bool {
+        self.tls.insecure
+    }
+
+    pub fn health_path(&self) -> &str {
+        &self.health_path
+    }
+
+    /// Create a new API client with the default base URL
+    pub fn with_default_url() -> Result<Self> {
+        Self::new(DEFAULT_BASE_URL)
+    }
+
+    // ============================================
+    // Projects CRUD
+    // ============================================
+
+    /// Fetch all projects with pagination
+    pub async fn fetch_projects(
+        &self,
+        page: i32,
+        page_size: i32,
+    ) -> Result<PaginatedResult<ProjectDto>, ApiError> {
+        let url = format!(
+            "{}/projects?page={}&pageSize={}",
+            self.base_url, page, page_size
+        );
+        let response = self.send(self.client.get(&url)).await?;
+        decode(response).await
+    }
+
+    /// Fetch all projects (unpaginated, fetches all pages)
+    pub async fn fetch_all_projects(&self) -> Result<Vec<ProjectDto>, ApiError> {
+        Ok(self.fetch_all_projects_with_progress(|_, _| {}).await?.items)
+    }
+
+    /// Fetch all projects, reporting `(loaded, total)` after each page
+    pub async fn fetch_all_projects_with_progress(
+        &self,
+        progress: impl FnMut(usize, Option<usize>),
+    ) -> Result<FetchedAll<ProjectDto>, ApiError> {
+        fetch_all_pages(|page| self.fetch_projects(page, FETCH_ALL_PAGE_SIZE), |p| p.id, progress).await
+    }
+
+    /// Fetch a single project by id
+    pub async fn fetch_project(&self, id: Uuid) -> Result<ProjectDto, ApiError> {
+        let url = format!("{}/projects/{}", self.base_url, id);
+        let response = self.send(self.client.get(&url)).await?;
+        decode(response).await
+    }
+
+    /// Change records of a project, in the order the server keeps them
+    pub async fn fetch_project_history(&self, id: Uuid) -> Result<Vec<ChangeRecord>, ApiError> {
+        let url = format!("{}/projects/{}/history", self.base_url, id);
+        let response = self.send(self.client.get(&url)).await?;
+        decode(response).await
+    }
+
+    /// Create a new project
+    pub async fn create_project(&self, project: &CreateProjectDto) -> Result<Uuid, ApiError> {
+        let url = format!("{}/projects", self.base_url);
+        let response = self.send(self.client.post(&url).json(project)).await?;
+        decode(response).await
+    }
+
+    /// Create projects in chunks of `BATCH_SIZE`, failing with the first
+    /// error if any of them could not be created
+    pub async fn create_projects_batch(&self, projects: &[CreateProjectDto]) -> Result<Vec<Uuid>, ApiError> {
+        self.create_projects_chunked(projects, |_, _| {}).await.into_iter().collect()
+    }
+
+    /// Create projects through `POST /projects/batch`, carrying on past failed
+    /// chunks. Servers without the batch endpoint get one create per project.
+    ///
+    /// Returns one result per project, in order; `progress` sees each chunk's
+    /// summary and results as it finishes.
+    pub async fn create_projects_chunked(
+        &self,
+        projects: &[CreateProjectDto],
+        mut progress: impl FnMut(&ChunkProgress, &[Result<Uuid, ApiError>]),
+    ) -> Vec<Result<Uuid, ApiError>> {
+        let chunks = projects.len().div_ceil(BATCH_SIZE);
+        let mut results = Vec::with_capacity(projects.len());
+        let mut batch_supported = true;
+
+        for (i, chunk) in projects.chunks(BATCH_SIZE).enumerate() {
+            let mut chunk_results = Vec::with_capacity(chunk.len());
+            let batch = if batch_supported { Some(self.post_projects_batch(chunk).await) } else { None };
+            match batch {
+                Some(Ok(ids)) => chunk_results.extend(ids.into_iter().map(Ok)),
+                Some(Err(e)) if e.status() != Some(404) => chunk_results.extend(chunk.iter().map(|_| Err(e.clone()))),
+                // No batch endpoint on this server
+                _ => {
+                    batch_supported = false;
+                    for project in chunk {
+                        chunk_results.push(self.create_project(project).await);
+                    }
+                }
+            }
+
+            let created = chunk_results.iter().filter(|r| r.is_ok()).count();
+            let summary = ChunkProgress {
+                chunk: i + 1,
+                chunks,
+                created,
+                failed: chunk_results.len() - created,
+            };
+            progress(&summary, &chunk_results);
+            results.extend(chunk_results);
+        }
+        results
+    }
+
+    /// One batch request; the server creates all of the projects or none
+    async fn post_projects_batch(&self, projects: &[CreateProjectDto]) -> Result<Vec<Uuid>, ApiError> {
+        let url = format!("{}/projects/batch", self.base_url);
+        let response = self.send(self.client.post(&url).json(projects)).await?;
+        let ids: Vec<Uuid> = decode(response).await?;
+        if ids.len() != projects.len() {
+            return Err(ApiError::Decode(format!(
+                "expected {} ids from the batch endpoint, got {}",
+                projects.len(),
+                ids.len()
+            )));
+        }
+        Ok(ids)
+    }
+
+    /// Update an existing project
+    pub async fn update_project(&self, id: Uuid, project: &UpdateProjectDto) -> Result<ProjectDto, ApiError> {
+        let url = format!("{}/projects/{}", self.base_url, id);
+        let response = self.send(self.client.put(&url).json(project)).await?;
+        decode(response).await
+    }
+
+    /// Delete a project
+    pub async fn delete_project(&self, id: Uuid) -> Result<Uuid, ApiError> {
+        let url = format!("{}/projects/{}", self.base_url, id);
+        let response = self.send(self.client.delete(&url)).await?;
+        decode(response).await
+    }
+
+    // ============================================
+    // Clients CRUD
+    // ============================================
+
+    /// Fetch all clients with pagination
+    pub async fn fetch_clients(
+        &self,
+        page: i32,
+        page_size: i32,
+    ) -> Result<PaginatedResult<ClientDto>, ApiError> {
+        let url = format!(
+            "{}/clients?page={}&pageSize={}",
+            self.base_url, page, page_size
+        );
+        let response = self.send(self.client.get(&url)).await?;
+        decode(response).await
+    }
+
+    /// Fetch all clients (unpaginated, fetches all pages)
+    pub async fn fetch_all_clients(&self) -> Result<Vec<ClientDto>, ApiError> {
+        Ok(self.fetch_all_clients_with_progress(|_, _| {}).await?.items)
+    }
+
+    /// Fetch all clients, reporting `(loaded, total)` after each page
+    pub async fn fetch_all_clients_with_progress(
+        &self,
+        progress: impl FnMut(usize, Option<usize>),
+    ) -> Result<FetchedAll<ClientDto>, ApiError> {
+        fetch_all_pages(|page| self.fetch_clients(page, FETCH_ALL_PAGE_SIZE), |c| c.id, progress).await
+    }
+
+    /// Fetch a single client by id
+    pub async fn fetch_client(&self, id: Uuid) -> Result<ClientDto, ApiError> {
+        let url = format!("{}/clients/{}", self.base_url, id);
+        let response = self.send(self.client.get(&url)).await?;
+        decode(response).await
+    }
+
+    /// Create a new client
+    pub async fn create_client(&self, client_dto: &CreateClientDto) -> Result<Uuid, ApiError> {
+        let url = format!("{}/clients", self.base_url);
+        let response = self.send(self.client.post(&url).json(client_dto)).await?;
+        decode(response).await
+    }
+
+    /// Update an existing client
+    pub async fn update_client(&self, id: Uuid, client_dto: &UpdateClientDto) -> Result<ClientDto, ApiError> {
+        let url = format!("{}/clients/{}", self.base_url, id);
+        let response = self.send(self.client.put(&url).json(client_dto)).await?;
+        decode(response).await
+    }
+
+    /// Delete a client
+    pub async fn delete_client(&self, id: Uuid) -> Result<Uuid, ApiError> {
+        let url = format!("{}/clients/{}", self.base_url, id);
+        let response = self.send(self.client.delete(&url)).await?;
+        decode(response).await
+    }
+
+    // ============================================
+    // Users CRUD
+    // ============================================
+
+    /// Fetch all users with pagination
+    pub async fn fetch_users(
+        &self,
+        page: i32,
+        page_size: i32,
+    ) -> Result<PaginatedResult<UserDto>, ApiError> {
+        let url = format!(
+            "{}/users?page={}&pageSize={}",
+            self.base_url, page, page_size
+        );
+        let response = self.send(self.client.get(&url)).await?;
+        decode(response).await
+    }
+
+    /// Fetch all users (unpaginated, fetches all pages)
+    pub async fn fetch_all_users(&self) -> Result<Vec<UserDto>, ApiError> {
+        Ok(self.fetch_all_users_with_progress(|_, _| {}).await?.items)
+    }
+
+    /// Fetch all users, reporting `(loaded, total)` after each page
+    pub async fn fetch_all_users_with_progress(
+        &self,
+        progress: impl FnMut(usize, Option<usize>),
+    ) -> Result<FetchedAll<UserDto>, ApiError> {
+        fetch_all_pages(|page| self.fetch_users(page, FETCH_ALL_PAGE_SIZE), |u| u.id, progress).await
+    }
+
+    /// Fetch a single user by id
+    pub async fn fetch_user(&self, id: Uuid) -> Result<UserDto, ApiError> {
+        let url = format!("{}/users/{}", self.base_url, id);
+        let response = self.send(self.client.get(&url)).await?;
+        decode(response).await
+    }
+
+    /// Create a new user
+    pub async fn create_user(&self, user: &CreateUserDto) -> Result<Uuid, ApiError> {
+        let url = format!("{}/users", self.base_url);
+        let response = self.send(self.client.post(&url).json(user)).await?;
+        decode(response).await
+    }
+
+    /// Update an existing user
+    pub async fn update_user(&self, id: Uuid, user: &UpdateUserDto) -> Result<UserDto, ApiError> {
+        let url = format!("{}/users/{}", self.base_url, id);
+        let response = self.send(self.client.put(&url).json(user)).await?;
+        decode(response).await
+    }
+
+    /// Delete a user
+    pub async fn delete_user(&self, id: Uuid) -> Result<Uuid, ApiError> {
+        let url = format!("{}/users/{}", self.base_url, id);
+        let response = self.send(self.client.delete(&url)).await?;
+        decode(response).await
+    }
+
+    // ============================================
+    // Utility
+    // ============================================
+
+    /// Health check, returning the round-trip latency.
+    ///
+    /// Tries the health endpoint first and falls back to fetching one project
+    /// when the server doesn't have one; whichever works is remembered.
+    pub async fn health_check(&self) -> Result<Duration, ApiError> {
+        match self.health_probe.get() {
+            Some(probe) => self.probe(*probe).await,
+            None => {
+                let (probe, latency) = match self.probe(HealthProbe::Endpoint).await {
+                    Err(e) if e.status() == Some(404) => {
+                        (HealthProbe::Projects, self.probe(HealthProbe::Projects).await?)
+                    }
+                    result => (HealthProbe::Endpoint, result?),
+                };
+                self.health_probe.set(probe).ok();
+                Ok(latency)
+            }
+        }
+    }
+
+    /// Send a request, turning transport failures and error statuses into `ApiError`
+    async fn send(&self, request: reqwest::RequestBuilder) -> Result<reqwest::Response, ApiError> {
+        let (client, request) = request.build_split();
+        let request = request.map_err(|e| ApiError::Connection(e.to_string()))?;
+        let trace = self.trace.as_ref().map(|_| ApiTrace::start(&request));
+
+        let started = Instant::now();
+        let result = client.execute(request).await.map_err(ApiError::from_transport);
+        if let (Some(sink), Some(mut trace)) = (&self.trace, trace) {
+            trace.elapsed = started.elapsed();
+            trace.outcome = match &result {
+                Ok(response) => Ok(response.status().as_u16()),
+                Err(e) => Err(e.to_string()),
+            };
+            (sink.0)(&trace);
+        }
+
+        let response = result?;
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+
+        if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            let header = response.headers().get(reqwest::header::RETRY_AFTER);
+            let retry_after = parse_retry_after(header.and_then(|v| v.to_str().ok()), chrono::Utc::now());
+            *self.rate_limited_until.lock().unwrap() = Some(Instant::now() + retry_after);
+            return Err(ApiError::RateLimited { retry_after });
+        }
+
+        // Error bodies are RFC 7807 problem details when the API produced them
+        let problem = response.json::<ProblemDetails>().await.ok();
+        Err(ApiError::Http {
+            status: status.as_u16(),
+            problem,
+        })
+    }
+
+    async fn probe(&self, probe: HealthProbe) -> Result<Duration, ApiError> {
+        let started = Instant::now();
+        match probe {
+            // Any 2xx will do; the body is not inspected
+            HealthProbe::Endpoint => {
+                let url = format!("{}/{}", self.base_url, self.health_path.trim_start_matches('/'));
+                self.send(self.client.get(&url).timeout(self.health_timeout)).await?;
+            }
+            HealthProbe::Projects => {
+                let url = format!("{}/projects?page=1&pageSize=1", self.base_url);
+                let response = self.send(self.client.get(&url).timeout(self.health_timeout)).await?;
+                decode::<PaginatedResult<ProjectDto>>(response).await?;
+            }
+        }
+        Ok(started.elapsed())
+    }
+}
+
+/// How long a Retry-After header asks us to wait: delay-seconds or an HTTP-date
+fn parse_retry_after(value: Option<&str>, now: chrono::DateTime<chrono::Utc>) -> Duration {
+    let Some(value) = value.map(str::trim) else {
+        return DEFAULT_RETRY_AFTER;
+    };
+    let wait = match value.parse::<u64>() {
+        Ok(seconds) => Duration::from_secs(seconds),
+        Err(_) => match chrono::DateTime::parse_from_rfc2822(value) {
+            // A date in the past means "now"
+            Ok(date) => (date.with_timezone(&chrono::Utc) - now).to_std().unwrap_or(Duration::ZERO),
+            Err(_) => DEFAULT_RETRY_AFTER,
+        },
+    };
+    wait.min(MAX_RETRY_AFTER)
+}
+
+/// Callback receiving API traces
+#[derive(Clone)]
+struct TraceSink(Arc<dyn Fn(&ApiTrace) + Send + Sync>);
+
+impl std::fmt::Debug for TraceSink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("TraceSink")
+    }
+}
+
+/// Longest request body kept in a trace
+const TRACE_BODY_LIMIT: usize = 200;
+
+/// Record of one HTTP call, for `--trace-api`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ApiTrace {
+    pub method: String,
+    /// Path and query, without the base URL
+    pub path: String,
+    /// Request body with secrets redacted and long bodies truncated
+    pub body: Option<String>,
+    /// Response status, or why no response arrived
+    pub outcome: Result<u16, String>,
+    pub elapsed: Duration,
+}
+
+impl ApiTrace {
+    fn start(request: &reqwest::Request) -> Self {
+        let url = request.url();
+        let path = match url.query() {
+            Some(query) => format!("{}?{}", url.path(), query),
+            None => url.path().to_string(),
+        };
+        let body = request
+            .body()
+            .and_then(|b| b.as_bytes())
+            .map(|bytes| truncate(&redact_secrets(&String::from_utf8_lossy(bytes)), TRACE_BODY_LIMIT));
+        Self {
+            method: request.method().to_string(),
+            path,
+            body,
+            outcome: Err("no response".to_string()),
+            elapsed: Duration::ZERO,
+        }
+    }
+
+    /// One-line summary for the System Log: "PUT /projects/ab12… → 200 in 130ms"
+    pub fn compact(&self) -> String {
+        let path: Vec<String> = self
+            .path
+            .split('/')
+            .map(|segment| match Uuid::parse_str(segment) {
+                Ok(_) => format!("{}…", &segment[..4]),
+                Err(_) => segment.to_string(),
+            })
+            .collect();
+        let outcome = match &self.outcome {
+            Ok(status) => status.to_string(),
+            Err(reason) => format!("failed ({})", reason),
+        };
+        format!("{} {} → {} in {}ms", self.method, path.join("/"), outcome, self.elapsed.as_millis())
+    }
+
+    /// Full line including the request body, for stderr
+    pub fn detailed(&self) -> String {
+        match &self.body {
+            Some(body) => format!("{} {}", self.compact(), body),
+            None => self.compact(),
+        }
+    }
+}
+
+/// Replace the value of every `password` field in a JSON body with "***".
+///
+/// Bodies that aren't JSON are not echoed at all, so a malformed payload can
+/// never leak a credential into a log.
+pub fn redact_secrets(body: &str) -> String {
+    fn redact(value: &mut serde_json::Value) {
+        match value {
+            serde_json::Value::Object(map) => {
+                for (key, field) in map.iter_mut() {
+                    if key.to_lowercase().contains("password") {
+                        *field = serde_json::Value::String("***".to_string());
+                    } else {
+                        redact(field);
+                    }
+                }
+            }
+            serde_json::Value::Array(items) => items.iter_mut().for_each(redact),
+            _ => {}
+        }
+    }
+
+    match serde_json::from_str::<serde_json::Value>(body) {
+        Ok(mut value) => {
+            redact(&mut value);
+            value.to_string()
+        }
+        Err(_) => format!("<{} bytes, not JSON>", body.len()),
+    }
+}
+
+fn truncate(text: &str, limit: usize) -> String {
+    match text.char_indices().nth(limit) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+/// Parse a successful response body
+async fn decode<T: serde::de::DeserializeOwned>(response: reqwest::Response) -> Result<T, ApiError> {
+    response.json().await.map_err(|e| {
+        if e.is_timeout() {
+            ApiError::Timeout
+        } else {
+            ApiError::Decode(e.to_string())
+        }
+    })
+}
+
+/// Why an API call failed
+#[derive(Debug, Clone, PartialEq)]
+pub enum ApiError {
+    /// The server could not be reached
+    Connection(String),
+    /// The server did not answer within the client timeout
+    Timeout,
+    /// The server answered with an error status
+    Http {
+        status: u16,
+        problem: Option<ProblemDetails>,
+    },
+    /// The response body was not what the client expected
+    Decode(String),
+    /// The server refused the request with 429 Too Many Requests
+    RateLimited { retry_after: Duration },
+}
+
+impl ApiError {
+    fn from_transport(error: reqwest::Error) -> Self {
+        if error.is_timeout() {
+            return ApiError::Timeout;
+        }
+        // The innermost cause ("Connection refused", "dns error") says more than the wrapper
+        let mut cause: &dyn std::error::Error = &error;
+        while let Some(source) = cause.source() {
+            cause = source;
+        }
+        ApiError::Connection(cause.to_string())
+    }
+
+    /// Short reason for a connection failure, for the status bar
+    pub fn connection_reason(&self) -> Option<&'static str> {
+        match self {
+            ApiError::Timeout => Some("timed out"),
+            ApiError::Connection(reason) if reason.to_lowercase().contains("refused") => Some("refused"),
+            ApiError::Connection(_) => Some("unreachable"),
+            _ => None,
+        }
+    }
+
+    /// A few words on what went wrong, e.g. "connection refused" or "HTTP 503"
+    pub fn summary(&self) -> String {
+        match self {
+            ApiError::Connection(_) | ApiError::Timeout => match self.connection_reason() {
+                Some("refused") => "connection refused".to_string(),
+                Some("timed out") => "timed out".to_string(),
+                _ => "server unreachable".to_string(),
+            },
+            ApiError::Http { status, .. } => format!("HTTP {}", status),
+            ApiError::Decode(_) => "unexpected response".to_string(),
+            ApiError::RateLimited { .. } => "rate limited".to_string(),
+        }
+    }
+
+    /// Whether the server is unreachable, as opposed to rejecting the request
+    pub fn is_connection(&self) -> bool {
+        matches!(self, ApiError::Connection(_) | ApiError::Timeout)
+    }
+
+    /// HTTP status of an error response
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            ApiError::Http { status, .. } => Some(*status),
+            ApiError::RateLimited { .. } => Some(429),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ApiError::Connection(reason) => write!(f, "Cannot reach the API ({})", reason),
+            ApiError::Timeout => write!(f, "The API did not respond in time"),
+            ApiError::Http { status, problem } => {
+                let reason = problem
+                    .as_ref()
+                    .and_then(|p| p.detail.as_deref().or(p.title.as_deref()))
+                    .map(str::to_string)
+                    .or_else(|| {
+                        reqwest::StatusCode::from_u16(*status)
+                            .ok()
+                            .and_then(|s| s.canonical_reason())
+                            .map(str::to_string)
+                    })
+                    .unwrap_or_else(|| "Request failed".to_string());
+                write!(f, "{} (HTTP {})", reason, status)
+            }
+            ApiError::Decode(reason) => write!(f, "Unexpected response from the API ({})", reason),
+            ApiError::RateLimited { retry_after } => {
+                write!(f, "Rate limited by the API (retry in {}s)", retry_after.as_secs())
+            }
+        }
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_api::MockApi;
+
+    #[test]
+    fn test_validate_base_url() {
+        assert_eq!(
+            validate_base_url(" http://localhost:5094/ ").unwrap(),
+            "http://localhost:5094"
+        );
+        assert!(validate_base_url("https://api.example.com/v1").is_ok());
+        assert!(validate_base_url("localhost:5094").is_err());
+        assert!(validate_base_url("ftp://example.com").is_err());
+        assert!(validate_base_url("").is_err());
+    }
+
+    #[test]
+    fn test_tls_config_loads_ca_bundles_and_configures_the_client() {
+        let ca = Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/testdata/ca.pem"));
+        let tls = TlsConfig::load(Some(ca), false).unwrap();
+        assert_eq!(tls.ca_certs.len(), 1);
+        assert!(!format!("{:?}", tls.apply(Client::builder())).contains("danger_accept_invalid_certs"));
+        assert!(TlsConfig::load(None, false).unwrap().ca_certs.is_empty());
+
+        let insecure = TlsConfig::load(None, true).unwrap();
+        assert!(format!("{:?}", insecure.apply(Client::builder())).contains("danger_accept_invalid_certs: true"));
+        let client = ApiClient::with_token("https://staging.example.com", None, DEFAULT_TIMEOUT, insecure).unwrap();
+        assert!(client.is_insecure());
+        assert!(client.retarget("https://other.example.com", None, DEFAULT_TIMEOUT).unwrap().is_insecure());
+        assert!(!ApiClient::new("https://staging.example.com").unwrap().is_insecure());
+
+        let missing = TlsConfig::load(Some(Path::new("/nonexistent/ca.pem")), false).unwrap_err();
+        assert_eq!(missing.to_string(), "Failed to read CA certificate /nonexistent/ca.pem");
+        let not_pem = Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml"));
+        let empty = TlsConfig::load(Some(not_pem), false).unwrap_err();
+        assert!(empty.to_string().starts_with("No certificates found in "), "{}", empty);
+    }
+
+    #[test]
+    fn test_url_host() {
+        assert_eq!(url_host("http://localhost:5094/api").as_deref(), Some("localhost:5094"));
+        assert_eq!(url_host("https://sweem.example.com").as_deref(), Some("sweem.example.com"));
+        assert_eq!(url_host("https://sweem.example.com:443").as_deref(), Some("sweem.example.com"));
+        assert_eq!(url_host("not a url"), None);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_all_pages_reports_progress() {
+        let page = |page: i32, total_count: i32| PaginatedResult {
+            items: Some(vec![page * 10, page * 10 + 1]),
+            page,
+            page_size: 2,
+            total_count,
+            total_pages: 3,
+            has_previous: page > 1,
+            has_next: page < 3,
+        };
+
+        let mut reports = Vec::new();
+        let all = fetch_all_pages(|n| async move { Ok(page(n, 6)) }, |&i| i, |loaded, total| {
+            reports.push((loaded, total))
+        })
+        .await
+        .unwrap();
+        assert_eq!(all.items, vec![10, 11, 20, 21, 30, 31]);
+        assert_eq!((all.total_count, all.total_pages, all.pages), (Some(6), 3, 3));
+        assert!(!all.is_partial());
+        assert_eq!(reports, vec![(2, Some(6)), (4, Some(6)), (6, Some(6))]);
+
+        // A total smaller than what was already fetched is not trusted
+        let mut reports = Vec::new();
+        let all = fetch_all_pages(|n| async move { Ok(page(n, 0)) }, |&i| i, |loaded, total| {
+            reports.push((loaded, total))
+        })
+        .await
+        .unwrap();
+        assert_eq!(reports.last(), Some(&(6, None)));
+        assert!(!all.is_partial());
+
+        // A larger one means items went missing on the way
+        let all = fetch_all_pages(|n| async move { Ok(page(n, 9)) }, |&i| i, |_, _| {}).await.unwrap();
+        assert!(all.is_partial());
+
+        // With nothing fetched yet, or a rate limit to wait out, the failure stands
+        let failing = |n: i32, error: ApiError| async move { if n < 2 { Ok(page(n, 6)) } else { Err(error) } };
+        let first = fetch_all_pages(|n| failing(n + 1, ApiError::Timeout), |&i| i, |_, _| {}).await;
+        assert_eq!(first.unwrap_err(), ApiError::Timeout);
+        let limited = ApiError::RateLimited { retry_after: Duration::from_secs(1) };
+        assert!(fetch_all_pages(|n| failing(n, limited.clone()), |&i| i, |_, _| {}).await.is_err());
+        let partial = fetch_all_pages(|n| failing(n, ApiError::Timeout), |&i| i, |_, _| {}).await.unwrap();
+        assert_eq!(partial.items, vec![10, 11]);
+        assert!(partial.is_partial());
+    }
+
+    #[test]
+    fn test_api_error_display() {
+        assert_eq!(ApiError::Timeout.to_string(), "The API did not respond in time");
+        assert_eq!(
+            ApiError::Http { status: 404, problem: None }.to_string(),
+            "Not Found (HTTP 404)"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_errors_are_classified() {
+        let mock = MockApi::start(|req| match req.path.as_str() {
+            p if p.starts_with("/projects/") => {
+                (404, r#"{"title":"Not Found","detail":"Project not found"}"#.to_string())
+            }
+            p if p.starts_with("/clients") => (200, "not json".to_string()),
+            _ => (500, String::new()),
+        })
+        .await;
+        let client = ApiClient::new(&mock.url).unwrap();
+
+        let err = client.delete_project(Uuid::nil()).await.unwrap_err();
+        assert_eq!(err.status(), Some(404));
+        assert_eq!(err.to_string(), "Project not found (HTTP 404)");
+
+        let err = client.fetch_clients(1, 10).await.unwrap_err();
+        assert!(matches!(err, ApiError::Decode(_)));
+
+        let err = client.fetch_users(1, 10).await.unwrap_err();
+        assert!(matches!(err, ApiError::Http { status: 500, problem: None }));
+
+        drop(mock);
+        let offline = ApiClient::new("http://127.0.0.1:9").unwrap();
+        let err = offline.health_check().await.unwrap_err();
+        assert_eq!(err.connection_reason(), Some("refused"));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_single_entities() {
+        let id = Uuid::from_u128(42);
+        let mock = MockApi::start(move |req| {
+            let client = ClientDto {
+                id,
+                name: Some("Acme".to_string()),
+                address: None,
+                projects_total: 0,
+                projects_completed: 0,
+            };
+            match req.path.as_str() {
+                "/clients/00000000-0000-0000-0000-00000000002a" => (200, serde_json::to_string(&client).unwrap()),
+                _ => (404, r#"{"title":"Not Found"}"#.to_string()),
+            }
+        })
+        .await;
+        let client = ApiClient::new(&mock.url).unwrap();
+
+        assert_eq!(client.fetch_client(id).await.unwrap().display_name(), "Acme");
+        assert_eq!(client.fetch_project(id).await.unwrap_err().status(), Some(404));
+        assert_eq!(client.fetch_user(id).await.unwrap_err().status(), Some(404));
+
+        let paths: Vec<String> = mock.requests().into_iter().map(|r| r.path).collect();
+        assert_eq!(paths[1], format!("/projects/{}", id));
+        assert_eq!(paths[2], format!("/users/{}", id));
+    }
+
+    #[tokio::test]
+    async fn test_client_update_round_trip_keeps_counters() {
+        let id = Uuid::from_u128(7);
+        // Stores what it is sent, like the server's mapper does
+        let mock = MockApi::start(move |req| {
+            let sent: UpdateClientDto = serde_json::from_str(&req.body).unwrap();
+            let stored = ClientDto {
+                id,
+                name: sent.name,
+                address: sent.address,
+                projects_total: sent.projects_total,
+                projects_completed: sent.projects_completed,
+            };
+            (200, serde_json::to_string(&stored).unwrap())
+        })
+        .await;
+        let client = ApiClient::new(&mock.url).unwrap();
+        let fetched = ClientDto {
+            id,
+            name: Some("Acme".to_string()),
+            address: None,
+            projects_total: 5,
+            projects_completed: 2,
+        };
+
+        let dto = UpdateClientDto { name: Some("Acme Corp".to_string()), ..UpdateClientDto::from_client(&fetched) };
+        let updated = client.update_client(id, &dto).await.unwrap();
+        assert_eq!(updated.display_name(), "Acme Corp");
+        assert_eq!(updated.server_counts(), fetched.server_counts());
+
+        let body: serde_json::Value = serde_json::from_str(&mock.requests()[0].body).unwrap();
+        assert_eq!((body["projectsTotal"].as_i64(), body["projectsCompleted"].as_i64()), (Some(5), Some(2)));
+    }
+
+    /// Answers batch requests with one id per project sent
+    fn batch_ids(req: &crate::mock_api::Request) -> (u16, String) {
+        let sent: Vec<serde_json::Value> = serde_json::from_str(&req.body).unwrap();
+        let ids: Vec<Uuid> = sent.iter().map(|_| Uuid::new_v4()).collect();
+        (201, serde_json::to_string(&ids).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_batch_create_chunk_boundaries() {
+        let mock = MockApi::start(batch_ids).await;
+        let client = ApiClient::new(&mock.url).unwrap();
+
+        for (count, sizes) in [
+            (0, vec![]),
+            (1, vec![1]),
+            (100, vec![100]),
+            (101, vec![100, 1]),
+            (250, vec![100, 100, 50]),
+        ] {
+            let before = mock.requests().len();
+            let projects = vec![CreateProjectDto::default(); count];
+            let mut chunks = Vec::new();
+            let ids = client
+                .create_projects_chunked(&projects, |chunk, _| chunks.push(*chunk))
+                .await;
+            assert_eq!(ids.len(), count);
+            assert!(ids.iter().all(|id| id.is_ok()));
+
+            let requests = &mock.requests()[before..];
+            assert!(requests.iter().all(|r| r.method == "POST" && r.path == "/projects/batch"));
+            let sent: Vec<usize> = requests
+                .iter()
+                .map(|r| serde_json::from_str::<Vec<serde_json::Value>>(&r.body).unwrap().len())
+                .collect();
+            assert_eq!(sent, sizes);
+            let created: Vec<usize> = chunks.iter().map(|c| c.created).collect();
+            assert_eq!(created, sizes);
+            assert!(chunks.iter().enumerate().all(|(i, c)| c.chunk == i + 1 && c.chunks == sizes.len()));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_batch_create_continues_past_failed_chunk() {
+        let calls = std::sync::atomic::AtomicUsize::new(0);
+        let mock = MockApi::start(move |req| match calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst) {
+            1 => (400, r#"{"title":"Bad Request","detail":"Manager not found"}"#.to_string()),
+            _ => batch_ids(req),
+        })
+        .await;
+        let client = ApiClient::new(&mock.url).unwrap();
+
+        let projects = vec![CreateProjectDto::default(); 230];
+        let mut chunks = Vec::new();
+        let results = client
+            .create_projects_chunked(&projects, |chunk, _| chunks.push(*chunk))
+            .await;
+        assert!(results[..100].iter().all(|r| r.is_ok()));
+        assert!(results[100..200].iter().all(|r| r.as_ref().unwrap_err().status() == Some(400)));
+        assert!(results[200..].iter().all(|r| r.is_ok()));
+        let summary: Vec<(usize, usize)> = chunks.iter().map(|c| (c.created, c.failed)).collect();
+        assert_eq!(summary, vec![(100, 0), (0, 100), (30, 0)]);
+
+        let err = client.create_projects_batch(&projects).await;
+        assert!(err.is_ok(), "later calls go through: {:?}", err.err());
+    }
+
+    #[tokio::test]
+    async fn test_batch_create_falls_back_without_endpoint() {
+        let mock = MockApi::start(|req| match req.path.as_str() {
+            "/projects/batch" => (404, r#"{"title":"Not Found"}"#.to_string()),
+            _ => (201, format!("\"{}\"", Uuid::new_v4())),
+        })
+        .await;
+        let client = ApiClient::new(&mock.url).unwrap();
+
+        let projects = vec![CreateProjectDto::default(); 102];
+        let ids = client.create_projects_batch(&projects).await.unwrap();
+        assert_eq!(ids.len(), 102);
+
+        // The batch endpoint is only tried once per call
+        let paths: Vec<String> = mock.requests().into_iter().map(|r| r.path).collect();
+        assert_eq!(paths.iter().filter(|p| *p == "/projects/batch").count(), 1);
+        assert_eq!(paths.iter().filter(|p| *p == "/projects").count(), 102);
+    }
+
+    #[tokio::test]
+    async fn test_request_and_health_check_timeouts() {
+        let mock = MockApi::start_slow(Duration::from_millis(300), |_| {
+            (200, crate::mock_api::page::<ProjectDto>(&[]))
+        })
+        .await;
+
+        // The configured request timeout applies to data calls
+        let impatient = ApiClient::with_token(&mock.url, None, Duration::from_millis(50), TlsConfig::default()).unwrap();
+        assert_eq!(impatient.fetch_projects(1, 10).await.unwrap_err(), ApiError::Timeout);
+
+        // The health check has its own, shorter budget
+        let patient = ApiClient::with_token(&mock.url, None, Duration::from_secs(5), TlsConfig::default())
+            .unwrap()
+            .with_health_timeout(Duration::from_millis(50));
+        let err = patient.health_check().await.unwrap_err();
+        assert_eq!(err.connection_reason(), Some("timed out"));
+        assert!(patient.fetch_projects(1, 10).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_health_check_prefers_endpoint_and_remembers_fallback() {
+        let with_endpoint = MockApi::start(|req| match req.path.as_str() {
+            "/api/ping" => (204, String::new()),
+            _ => (500, String::new()),
+        })
+        .await;
+        let client = ApiClient::new(&with_endpoint.url).unwrap().with_health_path("/api/ping");
+        client.health_check().await.unwrap();
+        client.health_check().await.unwrap();
+        assert!(with_endpoint.requests().iter().all(|r| r.path == "/api/ping"));
+
+        let without = MockApi::start(|req| match req.path.as_str() {
+            "/health" => (404, String::new()),
+            _ => (200, crate::mock_api::page::<ProjectDto>(&[])),
+        })
+        .await;
+        let client = ApiClient::new(&without.url).unwrap();
+        client.health_check().await.unwrap();
+        client.clone().health_check().await.unwrap();
+        let paths: Vec<String> = without.requests().into_iter().map(|r| r.path).collect();
+        assert_eq!(paths.iter().filter(|p| *p == "/health").count(), 1);
+        assert_eq!(paths.len(), 3);
+    }
+
+    #[test]
+    fn test_redact_secrets() {
+        let user = CreateUserDto {
+            name: Some("Kim".to_string()),
+            login: Some("kim".to_string()),
+            password: Some("hunter2".to_string()),
+            ..CreateUserDto::default()
+        };
+        let redacted = redact_secrets(&serde_json::to_string(&user).unwrap());
+        assert!(!redacted.contains("hunter2"));
+        assert!(redacted.contains(r#""password":"***""#));
+        assert!(redacted.contains(r#""login":"kim""#));
+
+        let nested = redact_secrets(r#"{"rows":[{"newPassword":"x1"}],"auth":{"PASSWORD":"x2"}}"#);
+        assert!(!nested.contains("x1") && !nested.contains("x2"));
+
+        assert_eq!(redact_secrets("password=hunter2"), "<16 bytes, not JSON>");
+    }
+
+    #[test]
+    fn test_trace_compact_shortens_ids() {
+        let trace = ApiTrace {
+            method: "PUT".to_string(),
+            path: "/projects/ab12cdef-0000-0000-0000-000000000000".to_string(),
+            body: None,
+            outcome: Ok(200),
+            elapsed: Duration::from_millis(130),
+        };
+        assert_eq!(trace.compact(), "PUT /projects/ab12… → 200 in 130ms");
+        assert_eq!(truncate("abcdef", 3), "abc…");
+        assert_eq!(truncate("abc", 3), "abc");
+    }
+
+    #[tokio::test]
+    async fn test_trace_reports_each_call() {
+        let mock = MockApi::start(|_| (201, format!("\"{}\"", Uuid::nil()))).await;
+        let traces = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = traces.clone();
+        let client = ApiClient::new(&mock.url)
+            .unwrap()
+            .with_trace(move |trace| sink.lock().unwrap().push(trace.clone()));
+
+        let user = CreateUserDto {
+            password: Some("hunter2".to_string()),
+            ..CreateUserDto::default()
+        };
+        client.create_user(&user).await.unwrap();
+
+        let traces = traces.lock().unwrap();
+        assert_eq!(traces.len(), 1);
+        assert_eq!(traces[0].method, "POST");
+        assert_eq!(traces[0].path, "/users");
+        assert_eq!(traces[0].outcome, Ok(201));
+        let body = traces[0].body.as_deref().unwrap();
+        assert!(body.contains("***") && !body.contains("hunter2"));
+    }
+
+    #[test]
+    fn test_parse_retry_after() {
+        let now = chrono::DateTime::parse_from_rfc2822("Wed, 21 Oct 2015 07:28:00 GMT")
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+        assert_eq!(parse_retry_after(Some("20"), now), Duration::from_secs(20));
+        assert_eq!(parse_retry_after(Some("Wed, 21 Oct 2015 07:28:30 GMT"), now), Duration::from_secs(30));
+        assert_eq!(parse_retry_after(Some("Wed, 21 Oct 2015 07:00:00 GMT"), now), Duration::ZERO);
+        assert_eq!(parse_retry_after(Some("soon"), now), DEFAULT_RETRY_AFTER);
+        assert_eq!(parse_retry_after(None, now), DEFAULT_RETRY_AFTER);
+        assert_eq!(parse_retry_after(Some("86400"), now), MAX_RETRY_AFTER);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_is_reported_and_remembered() {
+        let mock = MockApi::start(|_| (429, String::new())).await;
+        let client = ApiClient::new(&mock.url).unwrap();
+        assert_eq!(client.rate_limit_remaining(), None);
+
+        let err = client.fetch_users(1, 10).await.unwrap_err();
+        assert_eq!(err, ApiError::RateLimited { retry_after: Duration::from_secs(1) });
+        assert_eq!(err.status(), Some(429));
+        assert_eq!(err.to_string(), "Rate limited by the API (retry in 1s)");
+        assert!(client.clone().rate_limit_remaining().is_some());
+    }
+}
@@ -0,0 +1,320 @@
+//! Project filtering.
+//!
+//! One `ProjectFilter` lives on the App and every view that shows projects
+//! renders through `apply`, so the radar, the details panel and the
+//! navigation keys always agree on what is visible.
+//!
+//! Under the user's criteria sit two layers that clearing the filter leaves
+//! alone: the archive layer hides projects completed long ago, and the mine
+//! layer ('~') keeps only the projects the logged-in user manages.
+
+use chrono::NaiveDate;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::models::{is_valid_date, ClientDto, ProjectDto, ProjectStatus, UserDto};
+
+/// Projects completed more than this many days ago are archived by default
+pub const DEFAULT_ARCHIVE_DAYS: u32 = 90;
+
+/// Whether `project` was completed more than `after_days` days before `today`
+pub fn is_archived(project: &ProjectDto, today: NaiveDate, after_days: u32) -> bool {
+    project
+        .actual_end_date
+        .filter(|done| is_valid_date(*done))
+        .is_some_and(|done| (today - done).num_days() > i64::from(after_days))
+}
+
+/// The projects that are not archived; `after_days` 0 keeps them all
+pub fn without_archived(projects: &[ProjectDto], today: NaiveDate, after_days: u32) -> Vec<ProjectDto> {
+    projects
+        .iter()
+        .filter(|p| after_days == 0 || !is_archived(p, today, after_days))
+        .cloned()
+        .collect()
+}
+
+/// Filter layer switched on at login (`default_filter` in the config)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DefaultFilter {
+    #[default]
+    None,
+    /// Only the projects the logged-in user manages
+    Mine,
+}
+
+/// What a filter needs besides the project itself
+#[derive(Debug, Clone, Copy)]
+pub struct FilterDeps<'a> {
+    pub clients: &'a [ClientDto],
+    pub users: &'a [UserDto],
+    /// Day statuses are computed for
+    pub today: NaiveDate,
+}
+
+/// Criteria a project must meet to be shown; `None` means any
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProjectFilter {
+    pub status: Option<ProjectStatus>,
+    pub client_id: Option<Uuid>,
+    pub manager_id: Option<Uuid>,
+    /// Case-insensitive match on the project, client or manager name
+    pub text: Option<String>,
+    /// Planned end on this day, picked on the agenda strip
+    pub due: Option<NaiveDate>,
+    /// Archive layer: hide projects completed more than this many days ago
+    pub archive_after_days: Option<u32>,
+    /// Mine layer: only projects this user manages
+    pub mine: Option<Uuid>,
+}
+
+impl ProjectFilter {
+    /// Whether any of the user's criteria is set; the layers don't count
+    pub fn is_active(&self) -> bool {
+        *self != self.cleared()
+    }
+
+    /// No criteria, keeping the layers
+    pub fn cleared(&self) -> Self {
+        Self {
+            archive_after_days: self.archive_after_days,
+            mine: self.mine,
+            ..Self::default()
+        }
+    }
+
+    /// How many projects the archive layer hides
+    pub fn archived_hidden(&self, projects: &[ProjectDto], deps: &FilterDeps) -> usize {
+        let Some(days) = self.archive_after_days else {
+            return 0;
+        };
+        projects.iter().filter(|p| is_archived(p, deps.today, days)).count()
+    }
+
+    /// Whether `project` passes every criterion
+    pub fn matches(&self, project: &ProjectDto, deps: &FilterDeps) -> bool {
+        if self.archive_after_days.is_some_and(|days| is_archived(project, deps.today, days)) {
+            return false;
+        }
+        if self.mine.is_some_and(|id| project.manager_id != id) {
+            return false;
+        }
+        if self.status.is_some_and(|s| project.status(deps.today) != s) {
+            return false;
+        }
+        if self.client_id.is_some_and(|id| project.client_id != id) {
+            return false;
+        }
+        if self.manager_id.is_some_and(|id| project.manager_id != id) {
+            return false;
+        }
+        if self.due.is_some_and(|day| project.planned_end_date != day) {
+            return false;
+        }
+        match self.text.as_deref().map(str::trim) {
+            None | Some("") => true,
+            Some(text) => {
+                let needle = text.to_lowercase();
+                let client = deps.clients.iter().find(|c| c.id == project.client_id);
+                let manager = deps.users.iter().find(|u| u.id == project.manager_id);
+                [
+                    Some(project.display_name()),
+                    client.map(|c| c.display_name()),
+                    manager.map(|u| u.display_name()),
+                ]
+                .into_iter()
+                .flatten()
+                .any(|name| name.to_lowercase().contains(&needle))
+            }
+        }
+    }
+
+    /// Indices of the projects that pass, in their original order
+    pub fn apply(&self, projects: &[ProjectDto], deps: &FilterDeps) -> Vec<usize> {
+        projects
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| self.matches(p, deps))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Short summary for the status bar, e.g. "mine · LATE · Acme · \"apollo\""
+    pub fn describe(&self, deps: &FilterDeps) -> String {
+        let mut parts = Vec::new();
+        if self.mine.is_some() {
+            parts.push("mine".to_string());
+        }
+        if let Some(status) = self.status {
+            parts.push(status.label().to_string());
+        }
+        if let Some(id) = self.client_id {
+            let client = deps.clients.iter().find(|c| c.id == id);
+            parts.push(client.map_or("Unknown client", |c| c.display_name()).to_string());
+        }
+        if let Some(id) = self.manager_id {
+            let manager = deps.users.iter().find(|u| u.id == id);
+            parts.push(manager.map_or("Unknown manager", |u| u.display_name()).to_string());
+        }
+        if let Some(text) = self.text.as_deref().filter(|t| !t.trim().is_empty()) {
+            parts.push(format!("\"{}\"", text.trim()));
+        }
+        if let Some(day) = self.due {
+            parts.push(format!("due {}", day));
+        }
+        parts.join(" · ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Role;
+
+    fn id(n: u128) -> Uuid {
+        Uuid::from_u128(n)
+    }
+
+    fn date(s: &str) -> NaiveDate {
+        s.parse().unwrap()
+    }
+
+    fn project(n: u128, name: &str, client: u128, manager: u128, end: &str, done: bool) -> ProjectDto {
+        ProjectDto {
+            id: id(n),
+            client_id: id(client),
+            name: Some(name.to_string()),
+            start_date: date("2024-01-01"),
+            planned_end_date: date(end),
+            actual_end_date: done.then(|| date(end)),
+            manager_id: id(manager),
+        }
+    }
+
+    fn fixture() -> (Vec<ProjectDto>, Vec<ClientDto>, Vec<UserDto>) {
+        let client = |n: u128, name: &str| ClientDto {
+            id: id(n),
+            name: Some(name.to_string()),
+            address: None,
+            projects_total: 0,
+            projects_completed: 0,
+        };
+        let user = |n: u128, name: &str| UserDto {
+            id: id(n),
+            name: Some(name.to_string()),
+            login: None,
+            role: Role::Manager,
+        };
+        let clients = vec![client(1, "Acme"), client(2, "Globex")];
+        let users = vec![user(10, "Kim"), user(11, "Lee")];
+        let projects = vec![
+            project(100, "Apollo", 1, 10, "2024-06-01", false),
+            project(101, "Gemini", 1, 11, "2024-04-01", false),
+            project(102, "Mercury", 2, 10, "2024-03-01", true),
+            project(103, "Skylab", 2, 11, "2024-09-01", false),
+        ];
+        (projects, clients, users)
+    }
+
+    #[test]
+    fn test_empty_filter_shows_everything() {
+        let (projects, clients, users) = fixture();
+        let deps = FilterDeps { clients: &clients, users: &users, today: date("2024-05-10") };
+        let filter = ProjectFilter::default();
+        assert!(!filter.is_active());
+        assert_eq!(filter.apply(&projects, &deps), vec![0, 1, 2, 3]);
+        assert_eq!(filter.describe(&deps), "");
+    }
+
+    #[test]
+    fn test_criteria_combine() {
+        let (projects, clients, users) = fixture();
+        let deps = FilterDeps { clients: &clients, users: &users, today: date("2024-05-10") };
+
+        let late = ProjectFilter { status: Some(ProjectStatus::Overdue), ..Default::default() };
+        assert_eq!(late.apply(&projects, &deps), vec![1]);
+        let done = ProjectFilter { status: Some(ProjectStatus::Completed), ..Default::default() };
+        assert_eq!(done.apply(&projects, &deps), vec![2]);
+
+        let globex = ProjectFilter { client_id: Some(id(2)), ..Default::default() };
+        assert_eq!(globex.apply(&projects, &deps), vec![2, 3]);
+        let lee_at_globex = ProjectFilter { manager_id: Some(id(11)), ..globex.clone() };
+        assert_eq!(lee_at_globex.apply(&projects, &deps), vec![3]);
+        assert_eq!(lee_at_globex.describe(&deps), "Globex · Lee");
+
+        let due = ProjectFilter { due: Some(date("2024-06-01")), ..Default::default() };
+        assert_eq!(due.apply(&projects, &deps), vec![0]);
+        assert_eq!(due.describe(&deps), "due 2024-06-01");
+
+        let nothing = ProjectFilter {
+            status: Some(ProjectStatus::Completed),
+            client_id: Some(id(1)),
+            ..Default::default()
+        };
+        assert!(nothing.apply(&projects, &deps).is_empty());
+    }
+
+    #[test]
+    fn test_text_matches_project_client_and_manager_names() {
+        let (projects, clients, users) = fixture();
+        let deps = FilterDeps { clients: &clients, users: &users, today: date("2024-05-10") };
+        let text = |t: &str| ProjectFilter { text: Some(t.to_string()), ..Default::default() };
+
+        assert_eq!(text("APOL").apply(&projects, &deps), vec![0]);
+        assert_eq!(text("globex").apply(&projects, &deps), vec![2, 3]);
+        assert_eq!(text("kim").apply(&projects, &deps), vec![0, 2]);
+        assert_eq!(text("  ").apply(&projects, &deps), vec![0, 1, 2, 3]);
+        assert!(text("zzz").apply(&projects, &deps).is_empty());
+    }
+
+    #[test]
+    fn test_archive_cutoff_boundary_day() {
+        let mut done = project(1, "Mercury", 1, 10, "2024-02-10", true);
+        let today = date("2024-05-10");
+        // Completed exactly 90 days ago: still shown; one more day and it's archived
+        assert_eq!((today - done.actual_end_date.unwrap()).num_days(), 90);
+        assert!(!is_archived(&done, today, 90));
+        assert!(is_archived(&done, date("2024-05-11"), 90));
+
+        // Open and placeholder-dated projects are never archived
+        let open = project(2, "Apollo", 1, 10, "2020-01-01", false);
+        assert!(!is_archived(&open, today, 90));
+        done.actual_end_date = Some(date("0001-01-01"));
+        assert!(!is_archived(&done, today, 90));
+    }
+
+    #[test]
+    fn test_archive_layer_survives_clearing() {
+        let (projects, clients, users) = fixture();
+        let deps = FilterDeps { clients: &clients, users: &users, today: date("2024-06-10") };
+        let archive = ProjectFilter { archive_after_days: Some(90), ..Default::default() };
+        assert!(!archive.is_active());
+        // Mercury was completed on 2024-03-01, 101 days before
+        assert_eq!(archive.apply(&projects, &deps), vec![0, 1, 3]);
+        assert_eq!(archive.archived_hidden(&projects, &deps), 1);
+
+        let acme = ProjectFilter { client_id: Some(id(1)), ..archive.clone() };
+        assert!(acme.is_active());
+        assert_eq!(acme.cleared(), archive);
+        assert_eq!(ProjectFilter::default().archived_hidden(&projects, &deps), 0);
+
+        let today = deps.today;
+        assert_eq!(without_archived(&projects, today, 90).len(), 3);
+        assert_eq!(without_archived(&projects, today, 0).len(), 4);
+    }
+
+    #[test]
+    fn test_mine_layer_survives_clearing() {
+        let (projects, clients, users) = fixture();
+        let deps = FilterDeps { clients: &clients, users: &users, today: date("2024-05-10") };
+        let mine = ProjectFilter { mine: Some(id(10)), ..ProjectFilter::default() };
+        assert!(!mine.is_active());
+        assert_eq!(mine.apply(&projects, &deps), vec![0, 2]);
+
+        let narrowed = ProjectFilter { client_id: Some(id(2)), ..mine.clone() };
+        assert_eq!(narrowed.apply(&projects, &deps), vec![2]);
+        assert_eq!(narrowed.cleared(), mine);
+        assert_eq!(narrowed.describe(&deps), "mine · Globex");
+    }
+}
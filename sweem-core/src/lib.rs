@@ -0,0 +1,14 @@
+//! Shared core of the SWEeM tools.
+//!
+//! `models` holds the DTOs the REST API exchanges, with their validation and
+//! the status rules derived from project dates; `filter` narrows a project
+//! list the way the TUI's filter bar does. Both are plain data and pull in
+//! no HTTP or terminal dependencies. The async `ApiClient` in `api` is
+//! behind the `client` feature.
+
+#[cfg(feature = "client")]
+pub mod api;
+pub mod filter;
+#[cfg(any(test, feature = "mock"))]
+pub mod mock_api;
+pub mod models;
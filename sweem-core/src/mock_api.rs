@@ -0,0 +1,156 @@
+//! Minimal in-process HTTP server standing in for the SWEeM API in tests.
+//!
+//! Each request is answered by a routing closure and recorded so tests can
+//! assert on what the client sent. Connections are closed after every
+//! response, which keeps the parser trivial.
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// A request received by the mock server
+#[derive(Debug, Clone, PartialEq)]
+pub struct Request {
+    pub method: String,
+    /// Path including the query string
+    pub path: String,
+    pub body: String,
+}
+
+/// Canned response: status code and JSON body.
+///
+/// 429 responses carry `Retry-After: 1` so rate-limit retries stay quick.
+pub type Response = (u16, String);
+
+/// Running mock server; stops when dropped
+pub struct MockApi {
+    pub url: String,
+    requests: Arc<Mutex<Vec<Request>>>,
+    task: tokio::task::JoinHandle<()>,
+}
+
+impl MockApi {
+    /// Start a server answering every request with `route`
+    pub async fn start<F>(route: F) -> Self
+    where
+        F: Fn(&Request) -> Response + Send + Sync + 'static,
+    {
+        Self::start_slow(Duration::ZERO, route).await
+    }
+
+    /// Start a server that waits `delay` before answering each request
+    pub async fn start_slow<F>(delay: Duration, route: F) -> Self
+    where
+        F: Fn(&Request) -> Response + Send + Sync + 'static,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let route = Arc::new(route);
+
+        let log = requests.clone();
+        let task = tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let log = log.clone();
+                let route = route.clone();
+                tokio::spawn(async move {
+                    serve(&mut stream, &log, route.as_ref(), delay).await;
+                });
+            }
+        });
+
+        Self { url, requests, task }
+    }
+
+    /// Every request received so far, in order
+    pub fn requests(&self) -> Vec<Request> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+impl Drop for MockApi {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Wrap items in the API's pagination envelope (single page)
+pub fn page<T: serde::Serialize>(items: &[T]) -> String {
+    serde_json::json!({
+        "items": items,
+        "page": 1,
+        "pageSize": items.len().max(1),
+        "totalCount": items.len(),
+        "totalPages": 1,
+        "hasPrevious": false,
+        "hasNext": false,
+    })
+    .to_string()
+}
+
+/// Read one request, answer it and close the connection
+async fn serve<F>(
+    stream: &mut TcpStream,
+    log: &Mutex<Vec<Request>>,
+    route: &F,
+    delay: Duration,
+) -> Option<()>
+where
+    F: Fn(&Request) -> Response,
+{
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 4096];
+
+    // Read until the end of the headers, then the declared body length
+    let header_end = loop {
+        let n = stream.read(&mut chunk).await.ok()?;
+        if n == 0 {
+            return None;
+        }
+        buf.extend_from_slice(&chunk[..n]);
+        if let Some(pos) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos + 4;
+        }
+    };
+
+    let head = String::from_utf8_lossy(&buf[..header_end]).to_string();
+    let content_length = head
+        .lines()
+        .find_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            name.eq_ignore_ascii_case("content-length")
+                .then(|| value.trim().parse::<usize>().ok())?
+        })
+        .unwrap_or(0);
+    while buf.len() < header_end + content_length {
+        let n = stream.read(&mut chunk).await.ok()?;
+        if n == 0 {
+            break;
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    }
+
+    let mut request_line = head.lines().next()?.split_whitespace();
+    let request = Request {
+        method: request_line.next()?.to_string(),
+        path: request_line.next()?.to_string(),
+        body: String::from_utf8_lossy(&buf[header_end..]).to_string(),
+    };
+
+    let (status, body) = route(&request);
+    log.lock().unwrap().push(request);
+    tokio::time::sleep(delay).await;
+
+    let retry_after = if status == 429 { "Retry-After: 1\r\n" } else { "" };
+    let response = format!(
+        "HTTP/1.1 {} Mock\r\nContent-Type: application/json\r\nContent-Length: {}\r\n{}Connection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        retry_after,
+        body
+    );
+    stream.write_all(response.as_bytes()).await.ok()?;
+    stream.shutdown().await.ok()
+}
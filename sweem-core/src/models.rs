@@ -0,0 +1,907 @@
+//! Domain models for the SWEeM API.
+//!
+//! These structs match the OpenAPI schema and use serde for JSON deserialization.
+//! DateOnly from C# is mapped to NaiveDate in Rust.
+//! Includes both read DTOs and write DTOs for CRUD operations.
+
+#![allow(dead_code)]
+
+use chrono::{DateTime, NaiveDate, Datelike, Months, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// User role enumeration (Manager = 0, Admin = 1)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(from = "i32", into = "i32")]
+pub enum Role {
+    #[default]
+    Manager = 0,
+    Admin = 1,
+}
+
+impl Role {
+    /// Get all available roles
+    pub fn all() -> &'static [Role] {
+        &[Role::Manager, Role::Admin]
+    }
+
+    /// Cycle to the next role
+    pub fn next(&self) -> Self {
+        match self {
+            Role::Manager => Role::Admin,
+            Role::Admin => Role::Manager,
+        }
+    }
+
+    /// Whether this role may add clients
+    pub fn can_create_clients(&self) -> bool {
+        *self == Role::Admin
+    }
+
+    /// Whether this role may remove clients
+    pub fn can_delete_clients(&self) -> bool {
+        *self == Role::Admin
+    }
+}
+
+impl From<i32> for Role {
+    fn from(value: i32) -> Self {
+        match value {
+            1 => Role::Admin,
+            _ => Role::Manager,
+        }
+    }
+}
+
+impl From<Role> for i32 {
+    fn from(role: Role) -> Self {
+        role as i32
+    }
+}
+
+impl std::fmt::Display for Role {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Role::Manager => write!(f, "Manager"),
+            Role::Admin => write!(f, "Admin"),
+        }
+    }
+}
+
+// ============================================
+// Client DTOs
+// ============================================
+
+/// Client data transfer object (read)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClientDto {
+    pub id: Uuid,
+    pub name: Option<String>,
+    pub address: Option<String>,
+    pub projects_total: i32,
+    pub projects_completed: i32,
+}
+
+impl ClientDto {
+    pub fn display_name(&self) -> &str {
+        self.name.as_deref().unwrap_or("Unnamed Client")
+    }
+
+    /// The server's (completed, total) project counters
+    pub fn server_counts(&self) -> (i32, i32) {
+        (self.projects_completed, self.projects_total)
+    }
+}
+
+/// (completed, total) projects of a client, counted from the loaded projects
+pub fn client_project_counts(projects: &[ProjectDto], client_id: Uuid) -> (i32, i32) {
+    let client_projects: Vec<_> = projects.iter().filter(|p| p.client_id == client_id).collect();
+    let total = client_projects.len() as i32;
+    let completed = client_projects.iter().filter(|p| p.is_completed()).count() as i32;
+    (completed, total)
+}
+
+/// Create client DTO (write)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateClientDto {
+    pub name: Option<String>,
+    pub address: Option<String>,
+    #[serde(default)]
+    pub projects_total: i32,
+    #[serde(default)]
+    pub projects_completed: i32,
+}
+
+impl CreateClientDto {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn validate(&self) -> Result<(), &'static str> {
+        if self.name.as_ref().is_none_or(|n| n.trim().is_empty()) {
+            return Err("Name is required");
+        }
+        Ok(())
+    }
+}
+
+/// Update client DTO (write). The server stores the counters as sent, so
+/// they always carry the fetched values (see `from_client`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateClientDto {
+    pub name: Option<String>,
+    pub address: Option<String>,
+    #[serde(default)]
+    pub projects_total: i32,
+    #[serde(default)]
+    pub projects_completed: i32,
+}
+
+impl UpdateClientDto {
+    pub fn from_client(client: &ClientDto) -> Self {
+        Self {
+            name: client.name.clone(),
+            address: client.address.clone(),
+            projects_total: client.projects_total,
+            projects_completed: client.projects_completed,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), &'static str> {
+        if self.name.as_ref().is_none_or(|n| n.trim().is_empty()) {
+            return Err("Name is required");
+        }
+        Ok(())
+    }
+}
+
+// ============================================
+// Project DTOs
+// ============================================
+
+/// Dates before this year are placeholders (the backend's `0001-01-01`), not real dates
+pub const MIN_VALID_YEAR: i32 = 2000;
+
+/// Whether `date` is a real date rather than an unset placeholder
+pub fn is_valid_date(date: NaiveDate) -> bool {
+    date.year() >= MIN_VALID_YEAR
+}
+
+/// The first six hex digits of `id`, for naming something that isn't loaded
+pub fn short_id(id: Uuid) -> String {
+    format!("{}…", &id.simple().to_string()[..6])
+}
+
+/// Lifecycle of a project, derived from its dates by `ProjectDto::status`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProjectStatus {
+    /// Starts in the future
+    Pending,
+    /// Running and within its deadline
+    Active,
+    /// Past its planned end and not completed
+    Overdue,
+    /// Has a real actual end date
+    Completed,
+    /// Start or planned end is a placeholder date
+    Unscheduled,
+}
+
+impl ProjectStatus {
+    /// Every status, in the order pickers offer them
+    pub const ALL: [ProjectStatus; 5] = [
+        ProjectStatus::Active,
+        ProjectStatus::Pending,
+        ProjectStatus::Overdue,
+        ProjectStatus::Completed,
+        ProjectStatus::Unscheduled,
+    ];
+
+    /// Short label used in the UI and reports
+    pub fn label(self) -> &'static str {
+        match self {
+            ProjectStatus::Pending => "PLANNED",
+            ProjectStatus::Active => "ACTIVE",
+            ProjectStatus::Overdue => "LATE",
+            ProjectStatus::Completed => "DONE",
+            ProjectStatus::Unscheduled => "UNSCHEDULED",
+        }
+    }
+}
+
+impl std::fmt::Display for ProjectStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
+/// Project data transfer object (read)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectDto {
+    pub id: Uuid,
+    pub client_id: Uuid,
+    pub name: Option<String>,
+    pub start_date: NaiveDate,
+    pub planned_end_date: NaiveDate,
+    pub actual_end_date: Option<NaiveDate>,
+    pub manager_id: Uuid,
+}
+
+impl ProjectDto {
+    pub fn display_name(&self) -> &str {
+        self.name.as_deref().unwrap_or("Unnamed Project")
+    }
+
+    /// Calculate project duration in days
+    pub fn duration_days(&self) -> i64 {
+        (self.planned_end_date - self.start_date).num_days()
+    }
+
+    /// Whether both the start and the planned end are real dates
+    pub fn has_valid_dates(&self) -> bool {
+        is_valid_date(self.start_date) && is_valid_date(self.planned_end_date)
+    }
+
+    /// Check if project is completed
+    /// FIX: Ignore default C# dates (year < 2000)
+    pub fn is_completed(&self) -> bool {
+        self.actual_end_date.is_some_and(is_valid_date)
+    }
+
+    /// Where the project stands on `today`. Completion wins over everything,
+    /// then placeholder dates, then the schedule.
+    pub fn status(&self, today: NaiveDate) -> ProjectStatus {
+        if self.is_completed() {
+            ProjectStatus::Completed
+        } else if !self.has_valid_dates() {
+            ProjectStatus::Unscheduled
+        } else if self.start_date > today {
+            ProjectStatus::Pending
+        } else if today > self.planned_end_date {
+            ProjectStatus::Overdue
+        } else {
+            ProjectStatus::Active
+        }
+    }
+
+    /// Days the project has been in its `status` on `today`: since the start
+    /// while active, past the planned end while late, since the actual end
+    /// once done, and until the start while planned. `None` when unscheduled;
+    /// never negative.
+    pub fn days_in_state(&self, today: NaiveDate) -> Option<i64> {
+        let days = match self.status(today) {
+            ProjectStatus::Active => today - self.start_date,
+            ProjectStatus::Overdue => today - self.planned_end_date,
+            ProjectStatus::Completed => today - self.actual_end_date?,
+            ProjectStatus::Pending => self.start_date - today,
+            ProjectStatus::Unscheduled => return None,
+        };
+        Some(days.num_days().max(0))
+    }
+
+    /// Status with its age, e.g. "LATE for 12 days" or "PLANNED, starts in 1 day"
+    pub fn state_age_label(&self, today: NaiveDate) -> String {
+        let status = self.status(today);
+        let Some(days) = self.days_in_state(today) else {
+            return status.label().to_string();
+        };
+        let span = if days == 1 { "1 day".to_string() } else { format!("{} days", days) };
+        match (status, days) {
+            (ProjectStatus::Pending, 0) => format!("{}, starts today", status.label()),
+            (ProjectStatus::Pending, _) => format!("{}, starts in {}", status.label(), span),
+            (_, 0) => format!("{} since today", status.label()),
+            _ => format!("{} for {}", status.label(), span),
+        }
+    }
+
+    /// Status as of today
+    pub fn current_status(&self) -> ProjectStatus {
+        self.status(chrono::Local::now().date_naive())
+    }
+
+    /// Check if project hasn't started yet
+    pub fn is_pending(&self) -> bool {
+        self.current_status() == ProjectStatus::Pending
+    }
+
+    /// Check if project is overdue
+    pub fn is_overdue(&self) -> bool {
+        self.current_status() == ProjectStatus::Overdue
+    }
+
+    /// Short status label: DONE, UNSCHEDULED, LATE, PLANNED or ACTIVE
+    pub fn status_label(&self) -> &'static str {
+        self.current_status().label()
+    }
+
+    /// Status label as of a given day (for reproducible reports)
+    pub fn status_label_on(&self, today: NaiveDate) -> &'static str {
+        self.status(today).label()
+    }
+}
+
+/// Order projects are kept in after every load (`project_sort` in the config)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProjectSort {
+    /// Start date, then name, then id
+    #[default]
+    Start,
+    /// Planned end date, then name, then id
+    End,
+    /// Name (case-insensitive), then start date, then id
+    Name,
+    /// Whatever order the API returns
+    Server,
+}
+
+impl ProjectSort {
+    /// Sort `projects` in place; ties always fall back to the id, so the
+    /// result doesn't depend on the order the server sent
+    pub fn sort(self, projects: &mut [ProjectDto]) {
+        let name = |p: &ProjectDto| p.display_name().to_lowercase();
+        match self {
+            ProjectSort::Start => projects.sort_by_cached_key(|p| (p.start_date, name(p), p.id)),
+            ProjectSort::End => projects.sort_by_cached_key(|p| (p.planned_end_date, name(p), p.id)),
+            ProjectSort::Name => projects.sort_by_cached_key(|p| (name(p), p.start_date, p.id)),
+            ProjectSort::Server => {}
+        }
+    }
+
+    /// What the order is by, e.g. "end date"
+    pub fn describe(self) -> &'static str {
+        match self {
+            ProjectSort::Start => "start date",
+            ProjectSort::End => "end date",
+            ProjectSort::Name => "name",
+            ProjectSort::Server => "server order",
+        }
+    }
+}
+
+/// Create project DTO (write)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateProjectDto {
+    pub client_id: Uuid,
+    pub name: Option<String>,
+    pub start_date: NaiveDate,
+    pub planned_end_date: NaiveDate,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub actual_end_date: Option<NaiveDate>,
+    pub manager_id: Uuid,
+}
+
+impl Default for CreateProjectDto {
+    fn default() -> Self {
+        let today = chrono::Local::now().date_naive();
+        Self {
+            client_id: Uuid::nil(),
+            name: None,
+            start_date: today,
+            planned_end_date: today + chrono::Duration::days(30),
+            actual_end_date: None,
+            manager_id: Uuid::nil(),
+        }
+    }
+}
+
+impl CreateProjectDto {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn validate(&self) -> Result<(), &'static str> {
+        if self.name.as_ref().is_none_or(|n| n.trim().is_empty()) {
+            return Err("Name is required");
+        }
+        if self.client_id.is_nil() {
+            return Err("Client is required");
+        }
+        if self.manager_id.is_nil() {
+            return Err("Manager is required");
+        }
+        if !is_valid_date(self.start_date) || !is_valid_date(self.planned_end_date) {
+            return Err("Start and end dates are required");
+        }
+        if self.planned_end_date < self.start_date {
+            return Err("End date must be after start date");
+        }
+        Ok(())
+    }
+
+    /// Dates that pass `validate` but are probably mistyped, judged from today
+    pub fn validate_soft(&self) -> Vec<Warning> {
+        self.validate_soft_at(chrono::Local::now().date_naive())
+    }
+
+    /// `validate_soft` as of `today`
+    pub fn validate_soft_at(&self, today: NaiveDate) -> Vec<Warning> {
+        let mut warnings = Vec::new();
+        if self.start_date < today - Months::new(12) {
+            warnings.push(Warning::StartLongAgo);
+        }
+        if self.planned_end_date > today + Months::new(36) {
+            warnings.push(Warning::EndFarAhead);
+        }
+        if self.planned_end_date > self.start_date + Months::new(24) {
+            warnings.push(Warning::LongDuration);
+        }
+        warnings
+    }
+}
+
+/// A project date that is allowed but looks like a typo, from `validate_soft`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Warning {
+    /// Start more than a year in the past
+    StartLongAgo,
+    /// Planned end more than three years ahead
+    EndFarAhead,
+    /// Runs for more than two years
+    LongDuration,
+}
+
+impl std::fmt::Display for Warning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Warning::StartLongAgo => "Starts more than a year ago",
+            Warning::EndFarAhead => "Ends more than 3 years from now",
+            Warning::LongDuration => "Runs longer than 2 years",
+        })
+    }
+}
+
+/// Update project DTO (write)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateProjectDto {
+    pub client_id: Uuid,
+    pub name: Option<String>,
+    pub start_date: NaiveDate,
+    pub planned_end_date: NaiveDate,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub actual_end_date: Option<NaiveDate>,
+    pub manager_id: Uuid,
+}
+
+impl UpdateProjectDto {
+    pub fn from_project(project: &ProjectDto) -> Self {
+        Self {
+            client_id: project.client_id,
+            name: project.name.clone(),
+            start_date: project.start_date,
+            planned_end_date: project.planned_end_date,
+            actual_end_date: project.actual_end_date,
+            manager_id: project.manager_id,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), &'static str> {
+        if self.name.as_ref().is_none_or(|n| n.trim().is_empty()) {
+            return Err("Name is required");
+        }
+        if self.client_id.is_nil() {
+            return Err("Client is required");
+        }
+        if self.manager_id.is_nil() {
+            return Err("Manager is required");
+        }
+        if !is_valid_date(self.start_date) || !is_valid_date(self.planned_end_date) {
+            return Err("Start and end dates are required");
+        }
+        if self.planned_end_date < self.start_date {
+            return Err("End date must be after start date");
+        }
+        Ok(())
+    }
+}
+
+// ============================================
+// User DTOs
+// ============================================
+
+/// User data transfer object (read)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserDto {
+    pub id: Uuid,
+    pub name: Option<String>,
+    pub login: Option<String>,
+    pub role: Role,
+}
+
+impl UserDto {
+    pub fn display_name(&self) -> &str {
+        self.name.as_deref().unwrap_or("Unnamed User")
+    }
+
+    /// Check if user is a manager (can be assigned to projects)
+    pub fn is_manager(&self) -> bool {
+        self.role == Role::Manager
+    }
+}
+
+/// Create user DTO (write)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateUserDto {
+    pub name: Option<String>,
+    pub login: Option<String>,
+    pub password: Option<String>,
+    #[serde(default)]
+    pub role: Role,
+}
+
+impl CreateUserDto {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn validate(&self) -> Result<(), &'static str> {
+        if self.name.as_ref().is_none_or(|n| n.trim().is_empty()) {
+            return Err("Name is required");
+        }
+        if self.login.as_ref().is_none_or(|l| l.trim().is_empty()) {
+            return Err("Login is required");
+        }
+        if self.password.as_ref().is_none_or(|p| p.is_empty()) {
+            return Err("Password is required");
+        }
+        if self.password.as_ref().is_some_and(|p| p.len() < 4) {
+            return Err("Password must be at least 4 characters");
+        }
+        Ok(())
+    }
+}
+
+/// Update user DTO (write)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateUserDto {
+    pub name: Option<String>,
+    pub login: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+    #[serde(default)]
+    pub role: Role,
+}
+
+impl UpdateUserDto {
+    pub fn from_user(user: &UserDto) -> Self {
+        Self {
+            name: user.name.clone(),
+            login: user.login.clone(),
+            password: None, // Don't include existing password
+            role: user.role,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), &'static str> {
+        if self.name.as_ref().is_none_or(|n| n.trim().is_empty()) {
+            return Err("Name is required");
+        }
+        if self.login.as_ref().is_none_or(|l| l.trim().is_empty()) {
+            return Err("Login is required");
+        }
+        // Password is optional for updates
+        if let Some(ref p) = self.password {
+            if !p.is_empty() && p.len() < 4 {
+                return Err("Password must be at least 4 characters");
+            }
+        }
+        Ok(())
+    }
+}
+
+// ============================================
+// Snapshots
+// ============================================
+
+/// Fingerprint of an entity's server state, used to detect concurrent edits
+pub fn snapshot_hash<T: Serialize>(value: &T) -> u64 {
+    use std::hash::{Hash, Hasher};
+
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    serde_json::to_string(value).unwrap_or_default().hash(&mut hasher);
+    hasher.finish()
+}
+
+// ============================================
+// Pagination
+// ============================================
+
+/// Generic paginated result wrapper
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PaginatedResult<T> {
+    pub items: Option<Vec<T>>,
+    pub page: i32,
+    pub page_size: i32,
+    pub total_count: i32,
+    pub total_pages: i32,
+    pub has_previous: bool,
+    pub has_next: bool,
+}
+
+impl<T> PaginatedResult<T> {
+    pub fn items(&self) -> &[T] {
+        self.items.as_deref().unwrap_or(&[])
+    }
+}
+
+// ============================================
+// History
+// ============================================
+
+/// One change from a project's audit log (`GET /projects/{id}/history`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeRecord {
+    pub timestamp: DateTime<Utc>,
+    /// Who made the change, when the server knows
+    pub user: Option<String>,
+    pub field: String,
+    pub old: Option<String>,
+    pub new: Option<String>,
+}
+
+// ============================================
+// Error handling
+// ============================================
+
+/// Problem details for API error responses
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProblemDetails {
+    #[serde(rename = "type")]
+    pub problem_type: Option<String>,
+    pub title: Option<String>,
+    pub status: Option<i32>,
+    pub detail: Option<String>,
+    pub instance: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_role_serialization() {
+        assert_eq!(Role::from(0), Role::Manager);
+        assert_eq!(Role::from(1), Role::Admin);
+        assert_eq!(i32::from(Role::Manager), 0);
+        assert_eq!(i32::from(Role::Admin), 1);
+    }
+
+    #[test]
+    fn test_only_admins_create_or_delete_clients() {
+        assert!(Role::Admin.can_create_clients() && Role::Admin.can_delete_clients());
+        assert!(!Role::Manager.can_create_clients() && !Role::Manager.can_delete_clients());
+    }
+
+    #[test]
+    fn test_soft_validation_flags_unlikely_dates() {
+        let today = NaiveDate::from_ymd_opt(2025, 6, 15).unwrap();
+        let dto = |start: (i32, u32, u32), end: (i32, u32, u32)| CreateProjectDto {
+            start_date: NaiveDate::from_ymd_opt(start.0, start.1, start.2).unwrap(),
+            planned_end_date: NaiveDate::from_ymd_opt(end.0, end.1, end.2).unwrap(),
+            ..CreateProjectDto::new()
+        };
+        assert!(dto((2024, 6, 15), (2026, 6, 15)).validate_soft_at(today).is_empty());
+        // The year typed as 2023 instead of 2025
+        assert_eq!(
+            dto((2023, 6, 1), (2025, 9, 1)).validate_soft_at(today),
+            [Warning::StartLongAgo, Warning::LongDuration]
+        );
+        assert_eq!(dto((2025, 7, 1), (2028, 7, 1)).validate_soft_at(today), [Warning::EndFarAhead, Warning::LongDuration]);
+        assert_eq!(Warning::EndFarAhead.to_string(), "Ends more than 3 years from now");
+    }
+
+    #[test]
+    fn test_project_duration() {
+        let project = ProjectDto {
+            id: Uuid::new_v4(),
+            client_id: Uuid::new_v4(),
+            name: Some("Test".to_string()),
+            start_date: NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+            planned_end_date: NaiveDate::from_ymd_opt(2024, 1, 31).unwrap(),
+            actual_end_date: None,
+            manager_id: Uuid::new_v4(),
+        };
+        assert_eq!(project.duration_days(), 30);
+    }
+
+    #[test]
+    fn test_project_sort_ignores_server_order() {
+        let day = |s: &str| s.parse::<NaiveDate>().unwrap();
+        let project = |n: u128, name: &str, start: &str, end: &str| ProjectDto {
+            id: Uuid::from_u128(n),
+            client_id: Uuid::nil(),
+            name: Some(name.to_string()),
+            start_date: day(start),
+            planned_end_date: day(end),
+            actual_end_date: None,
+            manager_id: Uuid::nil(),
+        };
+        let projects = vec![
+            project(1, "gemini", "2024-02-01", "2024-03-01"),
+            project(2, "Apollo", "2024-02-01", "2024-09-01"),
+            project(3, "Apollo", "2024-02-01", "2024-04-01"),
+            project(4, "Mercury", "2024-01-01", "2024-12-01"),
+        ];
+        let order = |sort: ProjectSort, mut projects: Vec<ProjectDto>| {
+            sort.sort(&mut projects);
+            projects.iter().map(|p| p.id.as_u128()).collect::<Vec<_>>()
+        };
+
+        let mut shuffled = projects.clone();
+        shuffled.reverse();
+        for sort in [ProjectSort::Start, ProjectSort::End, ProjectSort::Name] {
+            assert_eq!(order(sort, projects.clone()), order(sort, shuffled.clone()), "{:?}", sort);
+        }
+        assert_eq!(order(ProjectSort::Start, projects.clone()), vec![4, 2, 3, 1]);
+        assert_eq!(order(ProjectSort::End, projects.clone()), vec![1, 3, 2, 4]);
+        assert_eq!(order(ProjectSort::Name, projects.clone()), vec![2, 3, 1, 4]);
+        assert_eq!(order(ProjectSort::Server, shuffled), vec![4, 3, 2, 1]);
+    }
+
+    #[test]
+    fn test_status_edge_days() {
+        let day = |s: &str| s.parse::<NaiveDate>().unwrap();
+        let project = |start: &str, end: &str, done: Option<&str>| ProjectDto {
+            id: Uuid::nil(),
+            client_id: Uuid::nil(),
+            name: None,
+            start_date: day(start),
+            planned_end_date: day(end),
+            actual_end_date: done.map(day),
+            manager_id: Uuid::nil(),
+        };
+        let today = day("2024-05-10");
+
+        let starts_today = project("2024-05-10", "2024-06-01", None);
+        assert_eq!(starts_today.status(today), ProjectStatus::Active);
+        assert_eq!(project("2024-05-11", "2024-06-01", None).status(today), ProjectStatus::Pending);
+        let due_today = project("2024-04-01", "2024-05-10", None);
+        assert_eq!(due_today.status(today), ProjectStatus::Active);
+        assert_eq!(project("2024-04-01", "2024-05-09", None).status(today), ProjectStatus::Overdue);
+        assert_eq!(
+            project("2024-01-01", "2024-02-01", Some("2024-04-01")).status(today),
+            ProjectStatus::Completed,
+            "completed after being overdue"
+        );
+        assert_eq!(
+            project("2024-06-01", "2024-07-01", Some("2024-05-01")).status(today),
+            ProjectStatus::Completed,
+            "completed before starting"
+        );
+        assert_eq!(project("0001-01-01", "0001-01-01", None).status(today), ProjectStatus::Unscheduled);
+        assert_eq!(
+            project("2024-01-01", "2024-02-01", Some("0001-01-01")).status(today),
+            ProjectStatus::Overdue,
+            "a placeholder actual end doesn't complete"
+        );
+    }
+
+    #[test]
+    fn test_days_in_state_per_status() {
+        let day = |s: &str| s.parse::<NaiveDate>().unwrap();
+        let project = |start: &str, end: &str, done: Option<&str>| ProjectDto {
+            id: Uuid::nil(),
+            client_id: Uuid::nil(),
+            name: None,
+            start_date: day(start),
+            planned_end_date: day(end),
+            actual_end_date: done.map(day),
+            manager_id: Uuid::nil(),
+        };
+        let today = day("2024-05-10");
+
+        let active = project("2024-05-01", "2024-06-01", None);
+        assert_eq!(active.days_in_state(today), Some(9));
+        assert_eq!(active.state_age_label(today), "ACTIVE for 9 days");
+        let started_today = project("2024-05-10", "2024-06-01", None);
+        assert_eq!(started_today.state_age_label(today), "ACTIVE since today");
+
+        let late = project("2024-01-01", "2024-04-28", None);
+        assert_eq!(late.days_in_state(today), Some(12));
+        assert_eq!(late.state_age_label(today), "LATE for 12 days");
+        assert_eq!(project("2024-01-01", "2024-05-09", None).state_age_label(today), "LATE for 1 day");
+
+        let done = project("2024-01-01", "2024-06-01", Some("2024-05-03"));
+        assert_eq!(done.days_in_state(today), Some(7));
+        assert_eq!(done.state_age_label(today), "DONE for 7 days");
+        assert_eq!(project("2024-01-01", "2024-06-01", Some("2024-05-10")).state_age_label(today), "DONE since today");
+        // Finished "in the future" by a typo: no negative age
+        assert_eq!(project("2024-01-01", "2024-06-01", Some("2025-05-10")).days_in_state(today), Some(0));
+
+        let planned = project("2024-05-13", "2024-06-01", None);
+        assert_eq!(planned.days_in_state(today), Some(3));
+        assert_eq!(planned.state_age_label(today), "PLANNED, starts in 3 days");
+        assert_eq!(project("2024-05-11", "2024-06-01", None).state_age_label(today), "PLANNED, starts in 1 day");
+
+        let unscheduled = project("0001-01-01", "0001-01-01", None);
+        assert_eq!(unscheduled.days_in_state(today), None);
+        assert_eq!(unscheduled.state_age_label(today), "UNSCHEDULED");
+    }
+
+    #[test]
+    fn test_status_serialization() {
+        assert_eq!(serde_json::to_string(&ProjectStatus::Overdue).unwrap(), "\"overdue\"");
+        assert_eq!(
+            serde_json::from_str::<ProjectStatus>("\"pending\"").unwrap(),
+            ProjectStatus::Pending
+        );
+        assert_eq!(ProjectStatus::Completed.to_string(), "DONE");
+        for status in ProjectStatus::ALL {
+            let json = serde_json::to_string(&status).unwrap();
+            assert_eq!(serde_json::from_str::<ProjectStatus>(&json).unwrap(), status);
+        }
+    }
+
+    #[test]
+    fn test_placeholder_dates() {
+        let mut project = ProjectDto {
+            id: Uuid::new_v4(),
+            client_id: Uuid::new_v4(),
+            name: Some("Legacy".to_string()),
+            start_date: NaiveDate::from_ymd_opt(1, 1, 1).unwrap(),
+            planned_end_date: NaiveDate::from_ymd_opt(1, 1, 1).unwrap(),
+            actual_end_date: Some(NaiveDate::from_ymd_opt(1, 1, 1).unwrap()),
+            manager_id: Uuid::new_v4(),
+        };
+        let today = NaiveDate::from_ymd_opt(2024, 5, 10).unwrap();
+        assert!(!project.has_valid_dates());
+        assert!(!project.is_completed());
+        assert!(!project.is_overdue());
+        assert_eq!(project.status_label_on(today), "UNSCHEDULED");
+
+        project.planned_end_date = NaiveDate::from_ymd_opt(2024, 6, 1).unwrap();
+        assert!(!project.has_valid_dates(), "one placeholder is enough");
+        project.start_date = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        assert!(project.has_valid_dates());
+        assert_eq!(project.status_label_on(today), "ACTIVE");
+    }
+
+    #[test]
+    fn test_create_client_validation() {
+        let mut dto = CreateClientDto::new();
+        assert!(dto.validate().is_err());
+
+        dto.name = Some("Test Client".to_string());
+        assert!(dto.validate().is_ok());
+    }
+
+    #[test]
+    fn test_create_user_validation() {
+        let mut dto = CreateUserDto::new();
+        assert!(dto.validate().is_err());
+
+        dto.name = Some("Test User".to_string());
+        assert!(dto.validate().is_err());
+
+        dto.login = Some("testuser".to_string());
+        assert!(dto.validate().is_err());
+
+        dto.password = Some("pass".to_string());
+        assert!(dto.validate().is_ok());
+    }
+
+    #[test]
+    fn test_change_record_deserialization() {
+        let json = r#"[
+            {"timestamp": "2024-05-01T12:30:00Z", "user": "ada", "field": "name", "old": "Apollo", "new": "Artemis"},
+            {"timestamp": "2024-05-02T08:00:00+02:00", "field": "actualEndDate", "old": null, "new": "2024-05-02"}
+        ]"#;
+        let records: Vec<ChangeRecord> = serde_json::from_str(json).unwrap();
+        assert_eq!(records[0].user.as_deref(), Some("ada"));
+        assert_eq!(records[0].new.as_deref(), Some("Artemis"));
+        assert_eq!(records[1].timestamp.to_rfc3339(), "2024-05-02T06:00:00+00:00");
+        assert_eq!((records[1].user.as_ref(), records[1].old.as_ref()), (None, None));
+    }
+}
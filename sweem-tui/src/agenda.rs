@@ -0,0 +1,186 @@
+//! Two-week agenda strip under the radar.
+//!
+//! `agenda` counts the deadlines landing on each of the next days and keeps
+//! the worst status among them; it is pure so the day bucketing can be
+//! tested. `AgendaWidget` draws the strip as one row of day columns, each
+//! with its day of the month and a count badge coloured by that status.
+
+use chrono::{Datelike, NaiveDate};
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::Widget;
+
+use crate::models::{ProjectDto, ProjectStatus};
+use crate::theme::{colors, status_color, styles};
+
+/// Days on the strip, starting today
+pub const AGENDA_DAYS: usize = 14;
+
+/// Widest radar range the strip shows at; zoomed out further, single days
+/// are lost on the radar and the strip is hidden
+pub const AGENDA_MAX_RANGE_DAYS: f64 = 90.0;
+
+/// Deadlines landing on one day
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AgendaDay {
+    pub date: NaiveDate,
+    pub count: usize,
+    /// Most urgent status among them, `None` on an empty day
+    pub worst: Option<ProjectStatus>,
+}
+
+/// How bad a status is for a deadline, worst highest
+fn severity(status: ProjectStatus) -> u8 {
+    match status {
+        ProjectStatus::Overdue => 4,
+        ProjectStatus::Active => 3,
+        ProjectStatus::Pending => 2,
+        ProjectStatus::Unscheduled => 1,
+        ProjectStatus::Completed => 0,
+    }
+}
+
+/// One entry per day from `today` for `days` days, counting the shown
+/// projects whose planned end falls on it. Placeholder-dated projects never
+/// land on the strip.
+pub fn agenda(projects: &[ProjectDto], visible: &[usize], today: NaiveDate, days: usize) -> Vec<AgendaDay> {
+    let mut strip: Vec<AgendaDay> = today
+        .iter_days()
+        .take(days)
+        .map(|date| AgendaDay { date, count: 0, worst: None })
+        .collect();
+    for project in visible.iter().map(|&i| &projects[i]).filter(|p| p.has_valid_dates()) {
+        let offset = (project.planned_end_date - today).num_days();
+        let Some(day) = usize::try_from(offset).ok().and_then(|i| strip.get_mut(i)) else {
+            continue;
+        };
+        let status = project.status(today);
+        day.count += 1;
+        if day.worst.is_none_or(|worst| severity(status) > severity(worst)) {
+            day.worst = Some(status);
+        }
+    }
+    strip
+}
+
+/// The strip, with the cursor's column highlighted while it has focus
+pub struct AgendaWidget<'a> {
+    days: &'a [AgendaDay],
+    cursor: Option<usize>,
+}
+
+impl<'a> AgendaWidget<'a> {
+    pub fn new(days: &'a [AgendaDay]) -> Self {
+        Self { days, cursor: None }
+    }
+
+    /// Highlight this day, for when the strip has focus
+    pub fn cursor(mut self, cursor: Option<usize>) -> Self {
+        self.cursor = cursor;
+        self
+    }
+}
+
+impl Widget for AgendaWidget<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.is_empty() || self.days.is_empty() {
+            return;
+        }
+        buf.set_style(area, Style::default().bg(colors::BG_MEDIUM));
+        let column = (area.width / self.days.len() as u16).max(1);
+        for (i, day) in self.days.iter().enumerate() {
+            let x = area.x + i as u16 * column;
+            if x >= area.right() {
+                break;
+            }
+            let mut date_style = if i == 0 { styles::text().add_modifier(Modifier::BOLD) } else { styles::text_dim() };
+            let (badge, mut badge_style) = match day.worst {
+                Some(status) => {
+                    (day.count.to_string(), Style::default().fg(status_color(status)).add_modifier(Modifier::BOLD))
+                }
+                None => ("·".to_string(), styles::text_hint()),
+            };
+            if self.cursor == Some(i) {
+                date_style = date_style.add_modifier(Modifier::REVERSED);
+                badge_style = badge_style.add_modifier(Modifier::REVERSED);
+            }
+            // Narrow columns drop the date and keep the badge
+            let spans = if column >= 5 {
+                vec![
+                    Span::styled(format!("{:>3}", day.date.day()), date_style),
+                    Span::styled(format!(" {}", badge), badge_style),
+                ]
+            } else {
+                vec![Span::styled(format!("{:>width$}", badge, width = column as usize), badge_style)]
+            };
+            let width = column.min(area.right() - x);
+            buf.set_line(x, area.y, &Line::from(spans), width);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{date, project, ProjectFixture};
+
+    #[test]
+    fn test_deadlines_land_on_their_day() {
+        let today = date("2024-05-10");
+        let projects = vec![
+            project(1).starts("2024-05-01").ends("2024-05-10"),
+            project(2).starts("2024-06-01").ends("2024-05-12"),
+            project(3).starts("2024-05-01").ends("2024-05-12"),
+            project(4).starts("2024-05-01").ends("2024-05-23"),
+            project(5).starts("2024-05-01").ends("2024-05-24"),
+            project(6).starts("2024-04-01").ends("2024-05-09"),
+            project(7).starts("2024-04-01").ends("2024-05-12").done(),
+        ];
+        let strip = agenda(&projects, &[0, 1, 2, 3, 4, 5, 6], today, AGENDA_DAYS);
+        assert_eq!(strip.len(), AGENDA_DAYS);
+        assert_eq!(strip[0].date, today);
+        assert_eq!((strip[0].count, strip[0].worst), (1, Some(ProjectStatus::Active)));
+        // The worst of planned, active and done
+        assert_eq!((strip[2].count, strip[2].worst), (3, Some(ProjectStatus::Active)));
+        // The last day is today + 13; yesterday and day 14 are off the strip
+        assert_eq!((strip[13].date, strip[13].count), (date("2024-05-23"), 1));
+        assert_eq!(strip.iter().map(|d| d.count).sum::<usize>(), 5);
+    }
+
+    #[test]
+    fn test_empty_days_and_hidden_projects() {
+        let today = date("2024-05-10");
+        let projects = vec![
+            project(1).starts("2024-05-01").ends("2024-05-11"),
+            project(2).starts("0001-01-01").ends("0001-01-01"),
+        ];
+        let strip = agenda(&projects, &[1], today, 3);
+        assert!(strip.iter().all(|d| d.count == 0 && d.worst.is_none()));
+        assert_eq!(strip[2].date, date("2024-05-12"));
+        assert!(agenda(&projects, &[0], today, 0).is_empty());
+        assert_eq!(agenda(&projects, &[0, 1], today, 3)[1].count, 1);
+    }
+
+    #[test]
+    fn test_strip_draws_one_column_per_day() {
+        let today = date("2024-05-10");
+        let projects = vec![project(1).starts("2024-05-01").ends("2024-05-12")];
+        let strip = agenda(&projects, &[0], today, AGENDA_DAYS);
+        let row = |width: u16| {
+            let area = Rect::new(0, 0, width, 1);
+            let mut buf = Buffer::empty(area);
+            AgendaWidget::new(&strip).render(area, &mut buf);
+            (0..width).map(|x| buf[(x, 0)].symbol().to_string()).collect::<String>()
+        };
+        assert!(row(84).starts_with(" 10 ·  11 ·  12 1  13 · "));
+        // Too narrow for dates: just the badges
+        assert_eq!(row(28).trim_end(), " · · 1 · · · · · · · · · · ·");
+        let area = Rect::new(0, 0, 84, 1);
+        let mut buf = Buffer::empty(area);
+        AgendaWidget::new(&strip).cursor(Some(2)).render(area, &mut buf);
+        assert!(buf[(14, 0)].modifier.contains(Modifier::REVERSED));
+        assert!(!buf[(8, 0)].modifier.contains(Modifier::REVERSED));
+    }
+}
@@ -1,477 +1,203 @@
-//! API client for the SWEeM REST API.
+//! Messages between the TUI and its API worker.
 //!
-//! This module provides an async HTTP client for communicating with the backend.
-//! All methods are non-blocking and designed to run in a separate Tokio task.
-//! Supports full CRUD operations for Clients, Projects, and Users.
+//! The HTTP client lives in `sweem_core::api` and is re-exported here, so the
+//! rest of the TUI keeps using `crate::api` for both.
 
 #![allow(dead_code)]
 
-use anyhow::{Context, Result};
-use reqwest::Client;
+use std::time::Duration;
+
 use uuid::Uuid;
 
+use crate::import::ImportRow;
 use crate::models::{
-    ClientDto, CreateClientDto, CreateProjectDto, CreateUserDto, PaginatedResult, ProjectDto,
-    UpdateClientDto, UpdateProjectDto, UpdateUserDto, UserDto,
+    snapshot_hash, ChangeRecord, ClientDto, CreateClientDto, CreateProjectDto, CreateUserDto, PaginatedResult,
+    ProjectDto, UpdateClientDto, UpdateProjectDto, UpdateUserDto, UserDto,
 };
 
-/// Default API base URL
-pub const DEFAULT_BASE_URL: &str = "http://localhost:5094";
-
-/// API client for the SWEeM backend
-#[derive(Debug, Clone)]
-pub struct ApiClient {
-    client: Client,
-    base_url: String,
+pub use sweem_core::api::*;
+
+/// What the worker was doing when a call failed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ApiOperation {
+    Connect,
+    Load(EntityType),
+    Fetch(EntityType, Uuid),
+    Create(EntityType),
+    Update(EntityType),
+    Delete(EntityType, Uuid),
+    /// A project's change history
+    History(Uuid),
 }
 
-impl ApiClient {
-    /// Create a new API client with the specified base URL
-    pub fn new(base_url: impl Into<String>) -> Result<Self> {
-        let client = Client::builder()
-            .timeout(std::time::Duration::from_secs(30))
-            .build()
-            .context("Failed to create HTTP client")?;
-
-        Ok(Self {
-            client,
-            base_url: base_url.into(),
-        })
-    }
-
-    /// Create a new API client with the default base URL
-    pub fn with_default_url() -> Result<Self> {
-        Self::new(DEFAULT_BASE_URL)
-    }
-
-    // ============================================
-    // Projects CRUD
-    // ============================================
-
-    /// Fetch all projects with pagination
-    pub async fn fetch_projects(
-        &self,
-        page: i32,
-        page_size: i32,
-    ) -> Result<PaginatedResult<ProjectDto>> {
-        let url = format!(
-            "{}/projects?page={}&pageSize={}",
-            self.base_url, page, page_size
-        );
-
-        let response = self
-            .client
-            .get(&url)
-            .send()
-            .await
-            .context("Failed to send request to projects endpoint")?;
-
-        if !response.status().is_success() {
-            anyhow::bail!(
-                "API error: {} - {}",
-                response.status(),
-                response.text().await.unwrap_or_default()
-            );
-        }
-
-        response
-            .json()
-            .await
-            .context("Failed to parse projects response")
-    }
-
-    /// Fetch all projects (unpaginated, fetches all pages)
-    pub async fn fetch_all_projects(&self) -> Result<Vec<ProjectDto>> {
-        let mut all_projects = Vec::new();
-        let mut page = 1;
-        let page_size = 100;
-
-        loop {
-            let result = self.fetch_projects(page, page_size).await?;
-            all_projects.extend(result.items().to_vec());
-
-            if !result.has_next {
-                break;
-            }
-            page += 1;
-        }
-
-        Ok(all_projects)
-    }
-
-    /// Create a new project
-    pub async fn create_project(&self, project: &CreateProjectDto) -> Result<Uuid> {
-        let url = format!("{}/projects", self.base_url);
-
-        let response = self
-            .client
-            .post(&url)
-            .json(project)
-            .send()
-            .await
-            .context("Failed to send create project request")?;
-
-        if !response.status().is_success() {
-            anyhow::bail!(
-                "API error: {} - {}",
-                response.status(),
-                response.text().await.unwrap_or_default()
-            );
-        }
-
-        response
-            .json()
-            .await
-            .context("Failed to parse create project response")
-    }
-
-    /// Update an existing project
-    pub async fn update_project(&self, id: Uuid, project: &UpdateProjectDto) -> Result<ProjectDto> {
-        let url = format!("{}/projects/{}", self.base_url, id);
-
-        let response = self
-            .client
-            .put(&url)
-            .json(project)
-            .send()
-            .await
-            .context("Failed to send update project request")?;
-
-        if !response.status().is_success() {
-            anyhow::bail!(
-                "API error: {} - {}",
-                response.status(),
-                response.text().await.unwrap_or_default()
-            );
-        }
-
-        response
-            .json()
-            .await
-            .context("Failed to parse update project response")
-    }
-
-    /// Delete a project
-    pub async fn delete_project(&self, id: Uuid) -> Result<Uuid> {
-        let url = format!("{}/projects/{}", self.base_url, id);
-
-        let response = self
-            .client
-            .delete(&url)
-            .send()
-            .await
-            .context("Failed to send delete project request")?;
-
-        if !response.status().is_success() {
-            anyhow::bail!(
-                "API error: {} - {}",
-                response.status(),
-                response.text().await.unwrap_or_default()
-            );
-        }
-
-        response
-            .json()
-            .await
-            .context("Failed to parse delete project response")
+impl ApiOperation {
+    /// Whether the operation was a user-initiated change
+    pub fn is_mutation(&self) -> bool {
+        matches!(self, ApiOperation::Create(_) | ApiOperation::Update(_) | ApiOperation::Delete(..))
     }
+}
 
-    // ============================================
-    // Clients CRUD
-    // ============================================
-
-    /// Fetch all clients with pagination
-    pub async fn fetch_clients(
-        &self,
-        page: i32,
-        page_size: i32,
-    ) -> Result<PaginatedResult<ClientDto>> {
-        let url = format!(
-            "{}/clients?page={}&pageSize={}",
-            self.base_url, page, page_size
-        );
-
-        let response = self
-            .client
-            .get(&url)
-            .send()
-            .await
-            .context("Failed to send request to clients endpoint")?;
-
-        if !response.status().is_success() {
-            anyhow::bail!(
-                "API error: {} - {}",
-                response.status(),
-                response.text().await.unwrap_or_default()
-            );
+impl std::fmt::Display for ApiOperation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ApiOperation::Connect => write!(f, "Connect"),
+            ApiOperation::Load(entity) => write!(f, "Load {}s", entity),
+            ApiOperation::Fetch(entity, _) => write!(f, "Fetch {}", entity),
+            ApiOperation::Create(entity) => write!(f, "Create {}", entity),
+            ApiOperation::Update(entity) => write!(f, "Update {}", entity),
+            ApiOperation::Delete(entity, _) => write!(f, "Delete {}", entity),
+            ApiOperation::History(_) => write!(f, "Load history"),
         }
-
-        response
-            .json()
-            .await
-            .context("Failed to parse clients response")
     }
+}
 
-    /// Fetch all clients (unpaginated, fetches all pages)
-    pub async fn fetch_all_clients(&self) -> Result<Vec<ClientDto>> {
-        let mut all_clients = Vec::new();
-        let mut page = 1;
-        let page_size = 100;
-
-        loop {
-            let result = self.fetch_clients(page, page_size).await?;
-            all_clients.extend(result.items().to_vec());
+/// Messages sent from API worker to the main TUI thread
+#[derive(Debug, Clone)]
+pub enum ApiMessage {
+    /// Projects data has been loaded
+    ProjectsLoaded(FetchedAll<ProjectDto>),
+    /// Clients data has been loaded
+    ClientsLoaded(FetchedAll<ClientDto>),
+    /// Users data has been loaded
+    UsersLoaded(FetchedAll<UserDto>),
+    /// One page of projects has been loaded (lazy loading)
+    ProjectsPage(PaginatedResult<ProjectDto>),
+    /// One page of clients has been loaded (lazy loading)
+    ClientsPage(PaginatedResult<ClientDto>),
+    /// One page of users has been loaded (lazy loading)
+    UsersPage(PaginatedResult<UserDto>),
+    /// A single entity was fetched fresh from the server
+    FetchedOne(FetchedEntity),
+    /// A single entity was reloaded after a mutation; replaces or adds the cached copy
+    OneLoaded(FetchedEntity),
+    /// A project's change history arrived
+    HistoryLoaded(Uuid, Vec<ChangeRecord>),
+    /// Items fetched so far by a full collection load: `(entity, loaded, total)`
+    LoadProgress(EntityType, usize, Option<usize>),
+    /// How the latest fetch of a collection went: `status` is `None` when no
+    /// response arrived, `duration` includes every page
+    FetchStats {
+        entity: EntityType,
+        status: Option<u16>,
+        duration: Duration,
+        items: usize,
+    },
+    /// An API call failed
+    Failed(ApiOperation, ApiError),
+    /// A non-API error occurred in the worker
+    Error(String),
+    /// The worker refused to send a mutation (read-only mode)
+    Refused(String),
+    /// A traced API call (`--trace-api`)
+    Trace(String),
+    /// Something went partly wrong, for the log rather than a popup
+    Warning(String),
+    /// The server is rate limiting; the worker retries after this long
+    RateLimited(Duration),
+    /// API connection status: `Some(latency)` when reachable
+    ConnectionStatus(Option<Duration>),
+    /// Entity created successfully
+    Created(EntityType, Uuid),
+    /// Entity updated successfully
+    Updated(EntityType, Uuid),
+    /// Entity deleted successfully
+    Deleted(EntityType, Uuid),
+    /// One row of an import was processed (`error` is set if it failed)
+    ImportProgress {
+        done: usize,
+        total: usize,
+        line: usize,
+        error: Option<String>,
+    },
+    /// A batch create request finished
+    BatchProgress(EntityType, ChunkProgress),
+    /// An import or batch create finished; `failures` lists the line (or
+    /// position, for a batch) and reason of each failed row
+    ImportFinished {
+        entity_type: EntityType,
+        created: usize,
+        failures: Vec<(usize, String)>,
+    },
+    /// The worker has stopped; nothing else follows
+    WorkerStopped,
+}
 
-            if !result.has_next {
-                break;
+impl ApiMessage {
+    /// What this outcome left out of date in the local cache, if anything
+    pub fn stale(&self) -> Option<Stale> {
+        match self {
+            ApiMessage::Created(entity_type, id) | ApiMessage::Updated(entity_type, id) => {
+                Some(Stale::One(*entity_type, *id))
             }
-            page += 1;
-        }
-
-        Ok(all_clients)
-    }
-
-    /// Create a new client
-    pub async fn create_client(&self, client_dto: &CreateClientDto) -> Result<Uuid> {
-        let url = format!("{}/clients", self.base_url);
-
-        let response = self
-            .client
-            .post(&url)
-            .json(client_dto)
-            .send()
-            .await
-            .context("Failed to send create client request")?;
-
-        if !response.status().is_success() {
-            anyhow::bail!(
-                "API error: {} - {}",
-                response.status(),
-                response.text().await.unwrap_or_default()
-            );
-        }
-
-        response
-            .json()
-            .await
-            .context("Failed to parse create client response")
-    }
-
-    /// Update an existing client
-    pub async fn update_client(&self, id: Uuid, client_dto: &UpdateClientDto) -> Result<ClientDto> {
-        let url = format!("{}/clients/{}", self.base_url, id);
-
-        let response = self
-            .client
-            .put(&url)
-            .json(client_dto)
-            .send()
-            .await
-            .context("Failed to send update client request")?;
-
-        if !response.status().is_success() {
-            anyhow::bail!(
-                "API error: {} - {}",
-                response.status(),
-                response.text().await.unwrap_or_default()
-            );
-        }
-
-        response
-            .json()
-            .await
-            .context("Failed to parse update client response")
-    }
-
-    /// Delete a client
-    pub async fn delete_client(&self, id: Uuid) -> Result<Uuid> {
-        let url = format!("{}/clients/{}", self.base_url, id);
-
-        let response = self
-            .client
-            .delete(&url)
-            .send()
-            .await
-            .context("Failed to send delete client request")?;
-
-        if !response.status().is_success() {
-            anyhow::bail!(
-                "API error: {} - {}",
-                response.status(),
-                response.text().await.unwrap_or_default()
-            );
-        }
-
-        response
-            .json()
-            .await
-            .context("Failed to parse delete client response")
-    }
-
-    // ============================================
-    // Users CRUD
-    // ============================================
-
-    /// Fetch all users with pagination
-    pub async fn fetch_users(&self, page: i32, page_size: i32) -> Result<PaginatedResult<UserDto>> {
-        let url = format!(
-            "{}/users?page={}&pageSize={}",
-            self.base_url, page, page_size
-        );
-
-        let response = self
-            .client
-            .get(&url)
-            .send()
-            .await
-            .context("Failed to send request to users endpoint")?;
-
-        if !response.status().is_success() {
-            anyhow::bail!(
-                "API error: {} - {}",
-                response.status(),
-                response.text().await.unwrap_or_default()
-            );
-        }
-
-        response
-            .json()
-            .await
-            .context("Failed to parse users response")
-    }
-
-    /// Fetch all users (unpaginated, fetches all pages)
-    pub async fn fetch_all_users(&self) -> Result<Vec<UserDto>> {
-        let mut all_users = Vec::new();
-        let mut page = 1;
-        let page_size = 100;
-
-        loop {
-            let result = self.fetch_users(page, page_size).await?;
-            all_users.extend(result.items().to_vec());
-
-            if !result.has_next {
-                break;
+            ApiMessage::Deleted(entity_type, _) => Stale::after_delete(*entity_type),
+            ApiMessage::ImportFinished { entity_type, .. } => Some(Stale::All(*entity_type)),
+            // Deleted elsewhere already; the local copy is stale either way
+            ApiMessage::Failed(
+                ApiOperation::Delete(entity_type, _) | ApiOperation::Fetch(entity_type, _),
+                error,
+            ) if error.status() == Some(404) =>
+            {
+                Stale::after_delete(*entity_type)
             }
-            page += 1;
+            _ => None,
         }
-
-        Ok(all_users)
     }
+}
 
-    /// Create a new user
-    pub async fn create_user(&self, user: &CreateUserDto) -> Result<Uuid> {
-        let url = format!("{}/users", self.base_url);
-
-        let response = self
-            .client
-            .post(&url)
-            .json(user)
-            .send()
-            .await
-            .context("Failed to send create user request")?;
-
-        if !response.status().is_success() {
-            anyhow::bail!(
-                "API error: {} - {}",
-                response.status(),
-                response.text().await.unwrap_or_default()
-            );
-        }
+/// Cached data a mutation made out of date
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stale {
+    /// A single entity changed; reload just that one
+    One(EntityType, Uuid),
+    /// The whole collection may have changed
+    All(EntityType),
+}
 
-        response
-            .json()
-            .await
-            .context("Failed to parse create user response")
+impl Stale {
+    /// Deleted projects are dropped from the cache directly, but deleting a
+    /// client or user can cascade to projects the cache knows nothing about
+    fn after_delete(entity_type: EntityType) -> Option<Self> {
+        (entity_type != EntityType::Project).then_some(Stale::All(entity_type))
     }
+}
 
-    /// Update an existing user
-    pub async fn update_user(&self, id: Uuid, user: &UpdateUserDto) -> Result<UserDto> {
-        let url = format!("{}/users/{}", self.base_url, id);
-
-        let response = self
-            .client
-            .put(&url)
-            .json(user)
-            .send()
-            .await
-            .context("Failed to send update user request")?;
+/// A freshly fetched entity of any type
+#[derive(Debug, Clone)]
+pub enum FetchedEntity {
+    Client(ClientDto),
+    Project(ProjectDto),
+    User(UserDto),
+}
 
-        if !response.status().is_success() {
-            anyhow::bail!(
-                "API error: {} - {}",
-                response.status(),
-                response.text().await.unwrap_or_default()
-            );
+impl FetchedEntity {
+    pub fn entity_type(&self) -> EntityType {
+        match self {
+            FetchedEntity::Client(_) => EntityType::Client,
+            FetchedEntity::Project(_) => EntityType::Project,
+            FetchedEntity::User(_) => EntityType::User,
         }
-
-        response
-            .json()
-            .await
-            .context("Failed to parse update user response")
     }
 
-    /// Delete a user
-    pub async fn delete_user(&self, id: Uuid) -> Result<Uuid> {
-        let url = format!("{}/users/{}", self.base_url, id);
-
-        let response = self
-            .client
-            .delete(&url)
-            .send()
-            .await
-            .context("Failed to send delete user request")?;
-
-        if !response.status().is_success() {
-            anyhow::bail!(
-                "API error: {} - {}",
-                response.status(),
-                response.text().await.unwrap_or_default()
-            );
+    pub fn id(&self) -> Uuid {
+        match self {
+            FetchedEntity::Client(c) => c.id,
+            FetchedEntity::Project(p) => p.id,
+            FetchedEntity::User(u) => u.id,
         }
-
-        response
-            .json()
-            .await
-            .context("Failed to parse delete user response")
     }
 
-    // ============================================
-    // Utility
-    // ============================================
-
-    /// Health check - attempts to fetch first page of projects
-    pub async fn health_check(&self) -> Result<bool> {
-        match self.fetch_projects(1, 1).await {
-            Ok(_) => Ok(true),
-            Err(_) => Ok(false),
+    /// Fingerprint of the server copy (see `models::snapshot_hash`)
+    pub fn snapshot(&self) -> u64 {
+        match self {
+            FetchedEntity::Client(c) => snapshot_hash(c),
+            FetchedEntity::Project(p) => snapshot_hash(p),
+            FetchedEntity::User(u) => snapshot_hash(u),
         }
     }
 }
 
-/// Messages sent from API worker to the main TUI thread
-#[derive(Debug, Clone)]
-pub enum ApiMessage {
-    /// Projects data has been loaded
-    ProjectsLoaded(Vec<ProjectDto>),
-    /// Clients data has been loaded
-    ClientsLoaded(Vec<ClientDto>),
-    /// Users data has been loaded
-    UsersLoaded(Vec<UserDto>),
-    /// An error occurred during API communication
-    Error(String),
-    /// API connection status changed
-    ConnectionStatus(bool),
-    /// Entity created successfully
-    Created(EntityType, Uuid),
-    /// Entity updated successfully
-    Updated(EntityType),
-    /// Entity deleted successfully
-    Deleted(EntityType, Uuid),
-}
-
 /// Entity types for CRUD operations
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum EntityType {
     Client,
     Project,
@@ -489,7 +215,7 @@ impl std::fmt::Display for EntityType {
 }
 
 /// Commands sent from TUI to the API worker
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum ApiCommand {
     /// Request to refresh all data
     RefreshAll,
@@ -499,10 +225,24 @@ pub enum ApiCommand {
     RefreshClients,
     /// Request to refresh users only
     RefreshUsers,
+    /// Fetch a single entity by id
+    FetchOne(EntityType, Uuid),
+    /// Reload a single entity into the cache after it changed
+    RefreshOne(EntityType, Uuid),
+    /// Fetch a project's change history
+    FetchHistory(Uuid),
+    /// Fetch one page of a collection: page number and page size
+    FetchPage(EntityType, i32, i32),
     /// Check API connection status
     CheckConnection,
     /// Shutdown the API worker
     Shutdown,
+    /// Create validated import rows, through the batch endpoint where there is one
+    Import(Vec<ImportRow>),
+    /// Create many projects at once in chunks of `BATCH_SIZE`
+    CreateProjectsBatch(Vec<CreateProjectDto>),
+    /// Point the worker at a different API server and refresh everything
+    SetBaseUrl { url: String, token: Option<String> },
     // CRUD Commands
     /// Create a new client
     CreateClient(CreateClientDto),
@@ -523,3 +263,55 @@ pub enum ApiCommand {
     /// Delete a user
     DeleteUser(Uuid),
 }
+
+impl ApiCommand {
+    /// Whether the command creates, updates or deletes data on the server
+    pub fn is_mutating(&self) -> bool {
+        matches!(
+            self,
+            ApiCommand::CreateClient(_)
+                | ApiCommand::UpdateClient(..)
+                | ApiCommand::DeleteClient(_)
+                | ApiCommand::CreateProject(_)
+                | ApiCommand::UpdateProject(..)
+                | ApiCommand::DeleteProject(_)
+                | ApiCommand::CreateUser(_)
+                | ApiCommand::UpdateUser(..)
+                | ApiCommand::DeleteUser(_)
+                | ApiCommand::Import(_)
+                | ApiCommand::CreateProjectsBatch(_)
+        )
+    }
+}
+
+/// Id tying a command to the messages it produces and to its log lines
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RequestId(Uuid);
+
+impl RequestId {
+    pub fn generate() -> Self {
+        Self(Uuid::new_v4())
+    }
+}
+
+/// Short form for log lines: '#' and the first four hex digits
+impl std::fmt::Display for RequestId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{}", &self.0.simple().to_string()[..4])
+    }
+}
+
+/// A command on its way to the worker
+#[derive(Debug, Clone, PartialEq)]
+pub struct Request {
+    pub id: RequestId,
+    pub command: ApiCommand,
+}
+
+/// A worker message, with the id of the command that produced it; trace
+/// lines from the HTTP client carry none
+#[derive(Debug, Clone)]
+pub struct Response {
+    pub request: Option<RequestId>,
+    pub message: ApiMessage,
+}
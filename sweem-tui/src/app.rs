@@ -6,22 +6,50 @@
 
 #![allow(dead_code)]
 
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::PathBuf;
 use std::time::{Duration, Instant};
 
-use chrono::NaiveDate;
-use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use chrono::{DateTime, Local, NaiveDate};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::layout::Rect;
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::api::{ApiCommand, ApiMessage, EntityType};
+use crate::agenda::{self, AgendaDay, AGENDA_DAYS, AGENDA_MAX_RANGE_DAYS};
+use crate::api::{
+    self, ApiCommand, ApiError, ApiMessage, ApiOperation, EntityType, FetchedAll, FetchedEntity, Request, RequestId,
+    Response, Stale,
+};
+use crate::calendar::{self, CalendarState, Event};
+use crate::config::{Profile, DEFAULT_PAGE_SIZE};
+use crate::dates::{self, format_date, DateFormat, DayCount, WeekStart};
+use crate::diff::{diff_entities, diff_update, Badge, Diff, FieldChange};
+use crate::filter::{self, DefaultFilter, FilterDeps, ProjectFilter, DEFAULT_ARCHIVE_DAYS};
+use crate::form::{FormField, FormInput, FormState, FormType, Staleness};
+use crate::import::{self, ImportRow};
 use crate::models::{
-    ClientDto, CreateClientDto, CreateProjectDto, CreateUserDto, ProjectDto, Role, UpdateClientDto,
-    UpdateProjectDto, UpdateUserDto, UserDto,
+    client_project_counts, short_id, snapshot_hash, ChangeRecord, ClientDto, PaginatedResult, ProjectDto, ProjectSort,
+    ProjectStatus, Role, UpdateClientDto, UpdateProjectDto, UpdateUserDto, UserDto,
 };
-use crate::particles::ParticleSystem;
-use crate::radar::RadarState;
+use crate::particles::{CelebrationState, ParticleMode, ParticleSystem};
+use crate::radar::{self, PendingDateEdit, RadarState};
+use crate::report;
+use crate::passwords;
+use crate::presets::{FilterPreset, Presets};
+use crate::sanitize::{sanitize_client, sanitize_project, sanitize_user, Outcome};
+use crate::search::{self, Hit};
+use crate::snapshot::{self, SnapshotFormat};
+use crate::state::{Selections, UiState, STATE_VERSION};
+use crate::suggest;
+use crate::ui::Splash;
+use crate::upcoming::{self, Thresholds, UPCOMING};
+use crate::user_groups;
+use crate::widgets::TextInput;
+use crate::workload::{self, Workload};
 
 /// Active tab in the application
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
 pub enum Tab {
     /// Clients list view
     Clients,
@@ -33,22 +61,26 @@ pub enum Tab {
 }
 
 impl Tab {
-    /// Move to the next tab
-    pub fn next(&self) -> Self {
-        match self {
-            Tab::Clients => Tab::Timeline,
-            Tab::Timeline => Tab::Users,
-            Tab::Users => Tab::Clients,
+    /// Tabs visible to a user with the given role (everything when not logged in)
+    pub fn visible(role: Option<Role>) -> &'static [Tab] {
+        match role {
+            Some(Role::Manager) => &[Tab::Clients, Tab::Timeline],
+            _ => &[Tab::Clients, Tab::Timeline, Tab::Users],
         }
     }
 
-    /// Move to the previous tab
-    pub fn previous(&self) -> Self {
-        match self {
-            Tab::Clients => Tab::Users,
-            Tab::Timeline => Tab::Clients,
-            Tab::Users => Tab::Timeline,
-        }
+    /// Move to the next tab, skipping tabs the role cannot see
+    pub fn next(&self, role: Option<Role>) -> Self {
+        let tabs = Self::visible(role);
+        let idx = tabs.iter().position(|t| t == self).unwrap_or(0);
+        tabs[(idx + 1) % tabs.len()]
+    }
+
+    /// Move to the previous tab, skipping tabs the role cannot see
+    pub fn previous(&self, role: Option<Role>) -> Self {
+        let tabs = Self::visible(role);
+        let idx = tabs.iter().position(|t| t == self).unwrap_or(0);
+        tabs[(idx + tabs.len() - 1) % tabs.len()]
     }
 
     /// Get the display name of the tab
@@ -61,8 +93,57 @@ impl Tab {
     }
 }
 
-/// Input mode for the application
+/// Which of a tab's panes takes the navigation keys (Ctrl+W cycles them)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Pane {
+    /// The radar, or the tab's list
+    #[default]
+    Primary,
+    /// The upcoming deadlines (Timeline) or the selected client's projects (Clients)
+    Secondary,
+    /// The agenda strip under the radar (Timeline)
+    Agenda,
+}
+
+/// What Enter does on the Timeline (`timeline_enter_action` in the config)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EnterAction {
+    /// Show the selected project's details in a popup
+    #[default]
+    Details,
+    /// Open the edit form, like 'e'
+    Edit,
+    /// Zoom the radar out to the selected blip and flash it, like '.'
+    Center,
+}
+
+impl EnterAction {
+    /// What Enter does, for the help overlay
+    pub fn describe(self) -> &'static str {
+        match self {
+            EnterAction::Details => "Show project details (Timeline)",
+            EnterAction::Edit => "Edit project (Timeline)",
+            EnterAction::Center => "Bring project into view (Timeline)",
+        }
+    }
+
+    /// Status bar hints for the Timeline
+    fn hints(self, read_only: bool) -> &'static str {
+        match (self, read_only) {
+            (EnterAction::Details, true) => "? help · Enter details · r refresh · q quit · ^E profile",
+            (EnterAction::Edit, true) => "? help · r refresh · q quit · ^E profile",
+            (EnterAction::Center, true) => "? help · Enter center · r refresh · q quit · ^E profile",
+            (EnterAction::Details, false) => "? help · Enter details · c create · e edit · d delete · q quit · ^E profile",
+            (EnterAction::Edit, false) => "? help · Enter edit · c create · d delete · q quit · ^E profile",
+            (EnterAction::Center, false) => "? help · Enter center · c create · e edit · d delete · q quit · ^E profile",
+        }
+    }
+}
+
+/// Input mode for the application
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum InputMode {
     /// Normal navigation mode
     #[default]
@@ -71,548 +152,327 @@ pub enum InputMode {
     Editing,
     /// Confirmation dialog (delete)
     Confirming,
+    /// Typing into the search overlay (Ctrl+F)
+    Searching,
 }
 
-/// Type of form being displayed
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub enum FormType {
-    /// Creating a new client
-    CreateClient,
-    /// Editing an existing client
-    EditClient(Uuid),
-    /// Creating a new project
-    CreateProject,
-    /// Editing an existing project
-    EditProject(Uuid),
-    /// Creating a new user
-    CreateUser,
-    /// Editing an existing user
-    EditUser(Uuid),
-}
-
-/// Form field types for different entities
+/// Something open on top of the tab that Esc closes, one per press.
+///
+/// The variants are in priority order: `App::layers` lists the open ones
+/// topmost first, and the topmost also takes every other key.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum FormField {
-    // Client fields
-    ClientName,
-    ClientAddress,
-    // Project fields
-    ProjectName,
-    ProjectClient,
-    ProjectManager,
-    ProjectStartDate,
-    ProjectEndDate,
-    // User fields
-    UserName,
-    UserLogin,
-    UserPassword,
-    UserRole,
-    // Form buttons
-    SubmitButton,
-    CancelButton,
-}
-
-impl FormField {
-    /// Get all fields for client form
-    pub fn client_fields() -> &'static [FormField] {
-        &[
-            FormField::ClientName,
-            FormField::ClientAddress,
-            FormField::SubmitButton,
-            FormField::CancelButton,
-        ]
-    }
+pub enum Layer {
+    ErrorPopup,
+    ImportPrompt,
+    UrlPrompt,
+    DateJump,
+    FilterPopup,
+    /// The preset picker's name field, while saving a preset
+    PresetName,
+    PresetPicker,
+    InlineEdit,
+    MovePicker,
+    /// Review of an edit before it is sent; the form is still underneath
+    EditReview,
+    ProfileSwitcher,
+    Setup,
+    Help,
+    Details,
+    History,
+    ConfirmDialog,
+    /// An open selector dropdown inside the form
+    Dropdown,
+    Form,
+    Search,
+    /// An unsaved date shift on the radar
+    DateEdit,
+    Marks,
+    /// The calendar's list of a day's events
+    DayPopup,
+    Calendar,
+    /// A focused secondary pane or agenda strip
+    Pane,
+    Workload,
+    /// An active project filter on the Timeline
+    Filter,
+}
 
-    /// Get all fields for project form
-    pub fn project_fields() -> &'static [FormField] {
-        &[
-            FormField::ProjectName,
-            FormField::ProjectClient,
-            FormField::ProjectManager,
-            FormField::ProjectStartDate,
-            FormField::ProjectEndDate,
-            FormField::SubmitButton,
-            FormField::CancelButton,
-        ]
-    }
+/// Scroll position of popup text that may not fit the terminal.
+///
+/// How far it can scroll depends on the wrapped text and the popup's size;
+/// `ui::layout` fits it to both before every frame.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PopupScroll {
+    offset: u16,
+    /// Largest useful offset, as of the last fit
+    max: u16,
+    /// Visible lines, as of the last fit
+    page: u16,
+}
 
-    /// Get all fields for user form
-    pub fn user_fields() -> &'static [FormField] {
-        &[
-            FormField::UserName,
-            FormField::UserLogin,
-            FormField::UserPassword,
-            FormField::UserRole,
-            FormField::SubmitButton,
-            FormField::CancelButton,
-        ]
+impl PopupScroll {
+    /// Fit to `lines` of content shown `visible` at a time
+    pub fn fit(&mut self, lines: usize, visible: u16) {
+        let lines = u16::try_from(lines).unwrap_or(u16::MAX);
+        self.max = lines.saturating_sub(visible);
+        self.page = visible.max(1);
+        self.offset = self.offset.min(self.max);
     }
 
-    /// Get display label for the field
-    pub fn label(&self) -> &'static str {
-        match self {
-            FormField::ClientName => "Name",
-            FormField::ClientAddress => "Address",
-            FormField::ProjectName => "Name",
-            FormField::ProjectClient => "Client",
-            FormField::ProjectManager => "Manager",
-            FormField::ProjectStartDate => "Start Date",
-            FormField::ProjectEndDate => "End Date",
-            FormField::UserName => "Name",
-            FormField::UserLogin => "Login",
-            FormField::UserPassword => "Password",
-            FormField::UserRole => "Role",
-            FormField::SubmitButton => "Save",
-            FormField::CancelButton => "Cancel",
-        }
-    }
-
-    /// Check if this is a text input field
-    pub fn is_text_input(&self) -> bool {
-        matches!(
-            self,
-            FormField::ClientName
-                | FormField::ClientAddress
-                | FormField::ProjectName
-                | FormField::UserName
-                | FormField::UserLogin
-                | FormField::UserPassword
-        )
+    /// First line shown
+    pub fn offset(&self) -> u16 {
+        self.offset
     }
 
-    /// Check if this is a date picker field
-    pub fn is_date_picker(&self) -> bool {
-        matches!(
-            self,
-            FormField::ProjectStartDate | FormField::ProjectEndDate
-        )
+    /// Whether the text is taller than the popup, as of the last fit
+    pub fn overflows(&self) -> bool {
+        self.max > 0
     }
 
-    /// Check if this is a dropdown/selector field
-    pub fn is_selector(&self) -> bool {
-        matches!(
-            self,
-            FormField::ProjectClient | FormField::ProjectManager | FormField::UserRole
-        )
+    /// Scroll for PageUp/PageDown, and for ↑↓ and j/k when `lines` is set.
+    /// Returns whether the key was used.
+    pub fn handle_key(&mut self, key: KeyEvent, lines: bool) -> bool {
+        let delta = match key.code {
+            KeyCode::PageDown => i32::from(self.page),
+            KeyCode::PageUp => -i32::from(self.page),
+            KeyCode::Down | KeyCode::Char('j') if lines => 1,
+            KeyCode::Up | KeyCode::Char('k') if lines => -1,
+            _ => return false,
+        };
+        let offset = (i32::from(self.offset) + delta).clamp(0, i32::from(self.max));
+        self.offset = offset as u16;
+        true
     }
+}
 
-    /// Check if this is a button
-    pub fn is_button(&self) -> bool {
-        matches!(self, FormField::SubmitButton | FormField::CancelButton)
+/// A confirm dialog button; `confirm_default` in the config picks the one
+/// focused when a single-item dialog opens
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConfirmButton {
+    #[default]
+    No,
+    Yes,
+    /// Multi-select deletes only: keep going past items that fail
+    #[serde(skip)]
+    All,
+}
+
+impl ConfirmButton {
+    pub fn label(self) -> &'static str {
+        match self {
+            ConfirmButton::No => "[ No ]",
+            ConfirmButton::Yes => "[ Yes ]",
+            ConfirmButton::All => "[ Yes to all ]",
+        }
     }
 }
 
-/// State for the form modal
+/// Confirmation dialog state
 #[derive(Debug, Clone)]
-pub struct FormState {
-    /// Type of form
-    pub form_type: FormType,
-    /// Currently focused field index
-    pub focused_field: usize,
-    /// Current fields for the form
-    pub fields: Vec<FormField>,
-    /// Validation error message
-    pub error: Option<String>,
-    // Client form data
-    pub client_name: String,
-    pub client_address: String,
-    // Project form data
-    pub project_name: String,
-    pub project_client_idx: usize,
-    pub project_manager_idx: usize,
-    pub project_start_date: String,
-    pub project_end_date: String,
-    // User form data
-    pub user_name: String,
-    pub user_login: String,
-    pub user_password: String,
-    pub user_role: Role,
-    /// Whether dropdown is open
-    pub dropdown_open: bool,
-}
-
-impl FormState {
-    /// Create a new client creation form
-    pub fn new_create_client() -> Self {
-        Self {
-            form_type: FormType::CreateClient,
-            focused_field: 0,
-            fields: FormField::client_fields().to_vec(),
-            error: None,
-            client_name: String::new(),
-            client_address: String::new(),
-            project_name: String::new(),
-            project_client_idx: 0,
-            project_manager_idx: 0,
-            project_start_date: String::new(),
-            project_end_date: String::new(),
-            user_name: String::new(),
-            user_login: String::new(),
-            user_password: String::new(),
-            user_role: Role::Manager,
-            dropdown_open: false,
-        }
-    }
-
-    /// Create an edit client form
-    pub fn new_edit_client(client: &ClientDto) -> Self {
-        Self {
-            form_type: FormType::EditClient(client.id),
-            focused_field: 0,
-            fields: FormField::client_fields().to_vec(),
-            error: None,
-            client_name: client.name.clone().unwrap_or_default(),
-            client_address: client.address.clone().unwrap_or_default(),
-            project_name: String::new(),
-            project_client_idx: 0,
-            project_manager_idx: 0,
-            project_start_date: String::new(),
-            project_end_date: String::new(),
-            user_name: String::new(),
-            user_login: String::new(),
-            user_password: String::new(),
-            user_role: Role::Manager,
-            dropdown_open: false,
-        }
-    }
-
-    /// Create a new project creation form
-    pub fn new_create_project() -> Self {
-        let today = chrono::Local::now().date_naive();
-        let end_date = today + chrono::Duration::days(30);
-        Self {
-            form_type: FormType::CreateProject,
-            focused_field: 0,
-            fields: FormField::project_fields().to_vec(),
-            error: None,
-            client_name: String::new(),
-            client_address: String::new(),
-            project_name: String::new(),
-            project_client_idx: 0,
-            project_manager_idx: 0,
-            project_start_date: today.format("%Y-%m-%d").to_string(),
-            project_end_date: end_date.format("%Y-%m-%d").to_string(),
-            user_name: String::new(),
-            user_login: String::new(),
-            user_password: String::new(),
-            user_role: Role::Manager,
-            dropdown_open: false,
-        }
-    }
-
-    /// Create an edit project form
-    pub fn new_edit_project(
-        project: &ProjectDto,
-        clients: &[ClientDto],
-        users: &[UserDto],
-    ) -> Self {
-        let client_idx = clients
-            .iter()
-            .position(|c| c.id == project.client_id)
-            .unwrap_or(0);
-        let manager_idx = users
-            .iter()
-            .position(|u| u.id == project.manager_id)
-            .unwrap_or(0);
-        Self {
-            form_type: FormType::EditProject(project.id),
-            focused_field: 0,
-            fields: FormField::project_fields().to_vec(),
-            error: None,
-            client_name: String::new(),
-            client_address: String::new(),
-            project_name: project.name.clone().unwrap_or_default(),
-            project_client_idx: client_idx,
-            project_manager_idx: manager_idx,
-            project_start_date: project.start_date.format("%Y-%m-%d").to_string(),
-            project_end_date: project.planned_end_date.format("%Y-%m-%d").to_string(),
-            user_name: String::new(),
-            user_login: String::new(),
-            user_password: String::new(),
-            user_role: Role::Manager,
-            dropdown_open: false,
-        }
-    }
-
-    /// Create a new user creation form
-    pub fn new_create_user() -> Self {
-        Self {
-            form_type: FormType::CreateUser,
-            focused_field: 0,
-            fields: FormField::user_fields().to_vec(),
-            error: None,
-            client_name: String::new(),
-            client_address: String::new(),
-            project_name: String::new(),
-            project_client_idx: 0,
-            project_manager_idx: 0,
-            project_start_date: String::new(),
-            project_end_date: String::new(),
-            user_name: String::new(),
-            user_login: String::new(),
-            user_password: String::new(),
-            user_role: Role::Manager,
-            dropdown_open: false,
-        }
-    }
-
-    /// Create an edit user form
-    pub fn new_edit_user(user: &UserDto) -> Self {
+pub struct ConfirmDialog {
+    /// Title of the dialog
+    pub title: String,
+    /// Message to display
+    pub message: String,
+    /// What happens when the user confirms
+    pub action: ConfirmAction,
+    /// The focused button
+    pub focus: ConfirmButton,
+    /// Scroll position of a message taller than the dialog
+    pub scroll: PopupScroll,
+}
+
+impl ConfirmDialog {
+    pub fn new_delete(entity_type: EntityType, entity_id: Uuid, name: &str) -> Self {
         Self {
-            form_type: FormType::EditUser(user.id),
-            focused_field: 0,
-            fields: FormField::user_fields().to_vec(),
-            error: None,
-            client_name: String::new(),
-            client_address: String::new(),
-            project_name: String::new(),
-            project_client_idx: 0,
-            project_manager_idx: 0,
-            project_start_date: String::new(),
-            project_end_date: String::new(),
-            user_name: user.name.clone().unwrap_or_default(),
-            user_login: user.login.clone().unwrap_or_default(),
-            user_password: String::new(),
-            user_role: user.role,
-            dropdown_open: false,
-        }
-    }
-
-    /// Get the current focused field
-    pub fn current_field(&self) -> FormField {
-        self.fields[self.focused_field]
-    }
-
-    /// Move to the next field
-    pub fn next_field(&mut self) {
-        self.focused_field = (self.focused_field + 1) % self.fields.len();
-        self.dropdown_open = false;
-    }
-
-    /// Move to the previous field
-    pub fn prev_field(&mut self) {
-        self.focused_field = self
-            .focused_field
-            .checked_sub(1)
-            .unwrap_or(self.fields.len() - 1);
-        self.dropdown_open = false;
-    }
-
-    /// Get mutable reference to the current text field (not date pickers or selectors)
-    pub fn current_text_mut(&mut self) -> Option<&mut String> {
-        match self.current_field() {
-            FormField::ClientName => Some(&mut self.client_name),
-            FormField::ClientAddress => Some(&mut self.client_address),
-            FormField::ProjectName => Some(&mut self.project_name),
-            FormField::UserName => Some(&mut self.user_name),
-            FormField::UserLogin => Some(&mut self.user_login),
-            FormField::UserPassword => Some(&mut self.user_password),
-            // Date picker fields - use arrow keys instead of text input
-            FormField::ProjectStartDate | FormField::ProjectEndDate => None,
-            _ => None,
+            title: format!("Delete {}", entity_type),
+            message: format!(
+                "Are you sure you want to delete \"{}\"?\nThis action cannot be undone.",
+                name
+            ),
+            action: ConfirmAction::Delete(entity_type, entity_id),
+            focus: ConfirmButton::No,
+            scroll: PopupScroll::default(),
         }
     }
 
-    /// Handle character input
-    pub fn handle_char(&mut self, c: char) {
-        if let Some(text) = self.current_text_mut() {
-            text.push(c);
+    pub fn new_overwrite(entity_type: EntityType, update: ApiCommand) -> Self {
+        Self {
+            title: format!("{} Changed", entity_type),
+            message: format!(
+                "This {} changed on the server since you opened it.\nSave anyway and overwrite those changes?",
+                entity_type.to_string().to_lowercase()
+            ),
+            action: ConfirmAction::Overwrite(Box::new(update)),
+            focus: ConfirmButton::No,
+            scroll: PopupScroll::default(),
         }
     }
 
-    /// Handle backspace
-    pub fn handle_backspace(&mut self) {
-        if let Some(text) = self.current_text_mut() {
-            text.pop();
+    pub fn new_quit(in_flight: usize) -> Self {
+        let operations = if in_flight == 1 { "operation" } else { "operations" };
+        Self {
+            title: "Quit".to_string(),
+            message: format!(
+                "{} {} in progress — quit anyway?\nUnfinished changes may or may not be saved.",
+                in_flight, operations
+            ),
+            action: ConfirmAction::Quit,
+            focus: ConfirmButton::No,
+            scroll: PopupScroll::default(),
         }
     }
 
-    /// Increment the current date field by one day
-    pub fn increment_date(&mut self) {
-        match self.current_field() {
-            FormField::ProjectStartDate => {
-                self.project_start_date =
-                    Self::add_days_to_date_string(&self.project_start_date, 1);
-            }
-            FormField::ProjectEndDate => {
-                self.project_end_date = Self::add_days_to_date_string(&self.project_end_date, 1);
-            }
-            _ => {}
+    /// Delete every marked item; `items` are their ids and names
+    pub fn new_batch_delete(entity_type: EntityType, items: Vec<(Uuid, String)>) -> Self {
+        const LISTED: usize = 5;
+        let mut names: Vec<String> = items.iter().take(LISTED).map(|(_, name)| format!("\"{}\"", name)).collect();
+        if items.len() > LISTED {
+            names.push(format!("and {} more", items.len() - LISTED));
+        }
+        Self {
+            title: format!("Delete {} {}s", items.len(), entity_type),
+            message: format!(
+                "Delete {} items?\n{}\nYes stops at the first failure; Yes to all (a) carries on \
+                 and sums up at the end.",
+                items.len(),
+                names.join(", ")
+            ),
+            action: ConfirmAction::DeleteMany(entity_type, items),
+            focus: ConfirmButton::No,
+            scroll: PopupScroll::default(),
         }
     }
 
-    /// Decrement the current date field by one day
-    pub fn decrement_date(&mut self) {
-        match self.current_field() {
-            FormField::ProjectStartDate => {
-                self.project_start_date =
-                    Self::add_days_to_date_string(&self.project_start_date, -1);
-            }
-            FormField::ProjectEndDate => {
-                self.project_end_date = Self::add_days_to_date_string(&self.project_end_date, -1);
-            }
-            _ => {}
+    /// The buttons, left to right
+    pub fn buttons(&self) -> &'static [ConfirmButton] {
+        match self.action {
+            ConfirmAction::DeleteMany(..) => &[ConfirmButton::No, ConfirmButton::Yes, ConfirmButton::All],
+            _ => &[ConfirmButton::No, ConfirmButton::Yes],
         }
     }
 
-    /// Add days to a date string in YYYY-MM-DD format
-    fn add_days_to_date_string(date_str: &str, days: i64) -> String {
-        NaiveDate::parse_from_str(date_str, "%Y-%m-%d")
-            .map(|d| {
-                (d + chrono::Duration::days(days))
-                    .format("%Y-%m-%d")
-                    .to_string()
-            })
-            .unwrap_or_else(|_| {
-                // If parsing fails, use today's date
-                chrono::Local::now()
-                    .date_naive()
-                    .format("%Y-%m-%d")
-                    .to_string()
-            })
+    /// Move the focus `step` buttons to the right, wrapping around
+    fn move_focus(&mut self, step: isize) {
+        let buttons = self.buttons();
+        let at = buttons.iter().position(|b| *b == self.focus).unwrap_or(0) as isize;
+        self.focus = buttons[(at + step).rem_euclid(buttons.len() as isize) as usize];
     }
+}
 
-    /// Build CreateClientDto from form state
-    pub fn build_create_client(&self) -> CreateClientDto {
-        CreateClientDto {
-            name: Some(self.client_name.clone()),
-            address: if self.client_address.is_empty() {
-                None
-            } else {
-                Some(self.client_address.clone())
-            },
-            projects_total: 0,
-            projects_completed: 0,
-        }
-    }
+/// Action guarded by a confirm dialog
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfirmAction {
+    /// Delete an entity
+    Delete(EntityType, Uuid),
+    /// Delete the marked entities, with their names, one after another
+    DeleteMany(EntityType, Vec<(Uuid, String)>),
+    /// Quit while mutations are still in flight
+    Quit,
+    /// Create the rows of a clean import dry run
+    Import(Vec<ImportRow>),
+    /// Send an update even though the server copy changed since the form opened
+    Overwrite(Box<ApiCommand>),
+}
 
-    /// Build UpdateClientDto from form state
-    pub fn build_update_client(&self) -> UpdateClientDto {
-        UpdateClientDto {
-            name: Some(self.client_name.clone()),
-            address: if self.client_address.is_empty() {
-                None
-            } else {
-                Some(self.client_address.clone())
-            },
-            projects_total: 0,
-            projects_completed: 0,
-        }
-    }
-
-    /// Build CreateProjectDto from form state
-    pub fn build_create_project(
-        &self,
-        clients: &[ClientDto],
-        users: &[UserDto],
-    ) -> CreateProjectDto {
-        let client_id = clients
-            .get(self.project_client_idx)
-            .map(|c| c.id)
-            .unwrap_or(Uuid::nil());
-        let manager_id = users
-            .get(self.project_manager_idx)
-            .map(|u| u.id)
-            .unwrap_or(Uuid::nil());
-        let start_date = NaiveDate::parse_from_str(&self.project_start_date, "%Y-%m-%d")
-            .unwrap_or_else(|_| chrono::Local::now().date_naive());
-        let end_date = NaiveDate::parse_from_str(&self.project_end_date, "%Y-%m-%d")
-            .unwrap_or_else(|_| start_date + chrono::Duration::days(30));
-
-        CreateProjectDto {
-            client_id,
-            name: Some(self.project_name.clone()),
-            start_date,
-            planned_end_date: end_date,
-            actual_end_date: None,
-            manager_id,
-        }
-    }
-
-    /// Build UpdateProjectDto from form state
-    pub fn build_update_project(
-        &self,
-        clients: &[ClientDto],
-        users: &[UserDto],
-    ) -> UpdateProjectDto {
-        let client_id = clients
-            .get(self.project_client_idx)
-            .map(|c| c.id)
-            .unwrap_or(Uuid::nil());
-        let manager_id = users
-            .get(self.project_manager_idx)
-            .map(|u| u.id)
-            .unwrap_or(Uuid::nil());
-        let start_date = NaiveDate::parse_from_str(&self.project_start_date, "%Y-%m-%d")
-            .unwrap_or_else(|_| chrono::Local::now().date_naive());
-        let end_date = NaiveDate::parse_from_str(&self.project_end_date, "%Y-%m-%d")
-            .unwrap_or_else(|_| start_date + chrono::Duration::days(30));
-
-        UpdateProjectDto {
-            client_id,
-            name: Some(self.project_name.clone()),
-            start_date,
-            planned_end_date: end_date,
-            actual_end_date: None,
-            manager_id,
-        }
-    }
-
-    /// Build CreateUserDto from form state
-    pub fn build_create_user(&self) -> CreateUserDto {
-        CreateUserDto {
-            name: Some(self.user_name.clone()),
-            login: Some(self.user_login.clone()),
-            password: Some(self.user_password.clone()),
-            role: self.user_role,
-        }
-    }
-
-    /// Build UpdateUserDto from form state
-    pub fn build_update_user(&self) -> UpdateUserDto {
-        UpdateUserDto {
-            name: Some(self.user_name.clone()),
-            login: Some(self.user_login.clone()),
-            password: if self.user_password.is_empty() {
-                None
-            } else {
-                Some(self.user_password.clone())
-            },
-            role: self.user_role,
-        }
+/// The command deleting one entity
+fn delete_command(entity_type: EntityType, id: Uuid) -> ApiCommand {
+    match entity_type {
+        EntityType::Client => ApiCommand::DeleteClient(id),
+        EntityType::Project => ApiCommand::DeleteProject(id),
+        EntityType::User => ApiCommand::DeleteUser(id),
     }
 }
 
-/// Confirmation dialog state
-#[derive(Debug, Clone)]
-pub struct ConfirmDialog {
-    /// Title of the dialog
-    pub title: String,
-    /// Message to display
-    pub message: String,
-    /// Entity type being deleted
+/// A multi-select delete, sent one item at a time
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchDelete {
     pub entity_type: EntityType,
-    /// Entity ID being deleted
-    pub entity_id: Uuid,
-    /// Whether "Yes" is focused (false = "No" is focused)
-    pub yes_focused: bool,
+    /// Id and name of the item being deleted
+    pub current: (Uuid, String),
+    /// Items not sent yet
+    pending: VecDeque<(Uuid, String)>,
+    pub total: usize,
+    pub deleted: usize,
+    /// Name and reason of each failed delete
+    pub failures: Vec<(String, String)>,
+    /// Keep going past failures ("Yes to all")
+    skip_errors: bool,
 }
 
-impl ConfirmDialog {
-    pub fn new_delete(entity_type: EntityType, entity_id: Uuid, name: &str) -> Self {
-        Self {
-            title: format!("Delete {}", entity_type),
-            message: format!(
-                "Are you sure you want to delete \"{}\"?\nThis action cannot be undone.",
-                name
-            ),
-            entity_type,
-            entity_id,
-            yes_focused: false,
+impl BatchDelete {
+    /// None when there is nothing to delete
+    fn new(entity_type: EntityType, items: Vec<(Uuid, String)>, skip_errors: bool) -> Option<Self> {
+        let total = items.len();
+        let mut pending = VecDeque::from(items);
+        let current = pending.pop_front()?;
+        Some(Self { entity_type, current, pending, total, deleted: 0, failures: Vec::new(), skip_errors })
+    }
+
+    fn is_current(&self, entity_type: EntityType, id: Uuid) -> bool {
+        self.entity_type == entity_type && self.current.0 == id
+    }
+
+    /// Position of the current item, e.g. "3/12"
+    fn position(&self) -> String {
+        format!("{}/{}", self.total - self.pending.len(), self.total)
+    }
+
+    fn command(&self) -> ApiCommand {
+        delete_command(self.entity_type, self.current.0)
+    }
+
+    /// Move on to the next item; false when none are left
+    fn advance(&mut self) -> bool {
+        match self.pending.pop_front() {
+            Some(next) => {
+                self.current = next;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// "10 deleted, 2 failed", with the items never sent after a stop
+    pub fn summary(&self) -> String {
+        let mut summary = format!("{} deleted, {} failed", self.deleted, self.failures.len());
+        if !self.pending.is_empty() {
+            summary.push_str(&format!(", {} not attempted", self.pending.len()));
         }
+        summary
     }
 }
 
+/// Why the UI asked for a fresh copy of one entity
+#[derive(Debug, Clone, PartialEq)]
+enum PendingFetch {
+    /// Open the edit form from the fresh copy
+    Edit(EntityType, Uuid),
+    /// Send this update if the server copy is unchanged
+    Submit(Box<ApiCommand>),
+}
+
+/// A project's change history ('i' on the Timeline)
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistoryPopup {
+    pub project_id: Uuid,
+    pub name: String,
+    /// Newest first; `None` while loading, the reason when the fetch failed
+    pub records: Option<Result<Vec<ChangeRecord>, String>>,
+    pub scroll: PopupScroll,
+}
+
+/// Field changes of an edit form, shown before it is sent (`confirm_edits`)
+#[derive(Debug, Clone)]
+pub struct EditReview {
+    pub title: String,
+    pub changes: Vec<FieldChange>,
+    /// The update to send on Enter, and the log line announcing it
+    command: ApiCommand,
+    intent: &'static str,
+}
+
 /// Error popup state
 #[derive(Debug, Clone)]
 pub struct ErrorPopup {
@@ -624,6 +484,8 @@ pub struct ErrorPopup {
     pub shown_at: Instant,
     /// Auto-dismiss duration (None for manual dismiss)
     pub auto_dismiss: Option<Duration>,
+    /// Scroll position of a message taller than the popup
+    pub scroll: PopupScroll,
 }
 
 impl ErrorPopup {
@@ -633,6 +495,7 @@ impl ErrorPopup {
             message: message.into(),
             shown_at: Instant::now(),
             auto_dismiss: Some(Duration::from_secs(5)),
+            scroll: PopupScroll::default(),
         }
     }
 
@@ -645,831 +508,7827 @@ impl ErrorPopup {
     }
 }
 
-/// Log entry for the message area
-#[derive(Debug, Clone)]
-pub struct LogEntry {
-    pub timestamp: Instant,
+/// Frames a toast stays on screen (about 3 seconds at 30 FPS)
+pub const TOAST_FRAMES: u64 = 90;
+
+/// How long "new" and "changed" badges stay up after a reload
+pub const BADGE_DURATION: Duration = Duration::from_secs(30);
+
+/// How long the radar rings a day jumped to with 'J'
+pub const JUMP_MARK_DURATION: Duration = Duration::from_secs(3);
+
+/// Frames a newly created entity is highlighted (about 1 second at 30 FPS)
+pub const FLASH_FRAMES: u64 = 30;
+
+/// Frames per on/off step of that highlight
+const FLASH_PULSE_FRAMES: u64 = 5;
+
+/// Most toasts shown at once; the oldest is dropped when another arrives
+const MAX_TOASTS: usize = 4;
+
+/// Short-lived notification shown in the top-right corner
+#[derive(Debug, Clone, PartialEq)]
+pub struct Toast {
     pub message: String,
     pub level: LogLevel,
+    /// Frame on which the toast appeared
+    pub born: u64,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum LogLevel {
-    Info,
-    Success,
-    Warning,
-    Error,
+impl Toast {
+    /// Frames since the toast appeared
+    pub fn age(&self, frame: u64) -> u64 {
+        frame.wrapping_sub(self.born)
+    }
 }
 
-impl LogEntry {
-    pub fn info(message: impl Into<String>) -> Self {
-        Self {
-            timestamp: Instant::now(),
-            message: message.into(),
-            level: LogLevel::Info,
+/// Toasts currently on screen, oldest first
+#[derive(Debug, Default)]
+pub struct ToastQueue {
+    toasts: VecDeque<Toast>,
+}
+
+impl ToastQueue {
+    pub fn push(&mut self, level: LogLevel, message: impl Into<String>, frame: u64) {
+        if self.toasts.len() == MAX_TOASTS {
+            self.toasts.pop_front();
         }
+        self.toasts.push_back(Toast {
+            message: message.into(),
+            level,
+            born: frame,
+        });
     }
 
-    pub fn success(message: impl Into<String>) -> Self {
-        Self {
-            timestamp: Instant::now(),
-            message: message.into(),
-            level: LogLevel::Success,
-        }
+    /// Drop toasts that have outlived `TOAST_FRAMES`
+    pub fn expire(&mut self, frame: u64) {
+        self.toasts.retain(|t| t.age(frame) < TOAST_FRAMES);
     }
 
-    pub fn warning(message: impl Into<String>) -> Self {
-        Self {
-            timestamp: Instant::now(),
-            message: message.into(),
-            level: LogLevel::Warning,
-        }
+    pub fn iter(&self) -> impl Iterator<Item = &Toast> {
+        self.toasts.iter()
     }
 
-    pub fn error(message: impl Into<String>) -> Self {
+    pub fn is_empty(&self) -> bool {
+        self.toasts.is_empty()
+    }
+}
+
+/// Frames averaged for the measured frame rate
+const FRAME_SAMPLES: usize = 60;
+
+/// Frame timing for the F12 overlay: a ring buffer of the intervals between
+/// recent frames, plus the latest render time and channel depths
+#[derive(Debug, Clone)]
+pub struct FrameStats {
+    /// Frame rate asked for (`--fps` or `fps` in the config)
+    pub target_fps: u32,
+    intervals: VecDeque<Duration>,
+    last_start: Option<Instant>,
+    /// How long the latest frame took to draw
+    pub render_time: Duration,
+    /// API messages waiting for the UI
+    pub api_queue: usize,
+    /// Commands waiting for the worker
+    pub command_queue: usize,
+}
+
+impl Default for FrameStats {
+    fn default() -> Self {
         Self {
-            timestamp: Instant::now(),
-            message: message.into(),
-            level: LogLevel::Error,
+            target_fps: crate::config::DEFAULT_FPS,
+            intervals: VecDeque::with_capacity(FRAME_SAMPLES),
+            last_start: None,
+            render_time: Duration::ZERO,
+            api_queue: 0,
+            command_queue: 0,
         }
     }
 }
 
-/// Main application state
-#[derive(Debug)]
-pub struct App {
-    /// Whether the application should quit
-    pub should_quit: bool,
-
-    /// Currently active tab
-    pub active_tab: Tab,
+impl FrameStats {
+    /// Note a frame that started drawing at `start` and took `render_time`
+    pub fn record(&mut self, start: Instant, render_time: Duration) {
+        if let Some(last) = self.last_start {
+            if self.intervals.len() == FRAME_SAMPLES {
+                self.intervals.pop_front();
+            }
+            self.intervals.push_back(start.saturating_duration_since(last));
+        }
+        self.last_start = Some(start);
+        self.render_time = render_time;
+    }
 
-    /// Current input mode
-    pub input_mode: InputMode,
+    /// Average time between the recent frames
+    pub fn average_interval(&self) -> Option<Duration> {
+        let count = u32::try_from(self.intervals.len()).ok().filter(|&n| n > 0)?;
+        Some(self.intervals.iter().sum::<Duration>() / count)
+    }
 
-    /// Projects data
-    pub projects: Vec<ProjectDto>,
+    /// Frames per second measured over the recent frames
+    pub fn fps(&self) -> Option<f64> {
+        self.average_interval()
+            .filter(|d| !d.is_zero())
+            .map(|d| 1.0 / d.as_secs_f64())
+    }
+}
 
-    /// Clients data
-    pub clients: Vec<ClientDto>,
+/// Filter popup (F on the Timeline): a draft applied on Enter
+#[derive(Debug, Clone, Default)]
+pub struct FilterPopup {
+    pub draft: ProjectFilter,
+    /// Focused row, an index into `FilterPopup::ROWS`
+    pub row: usize,
+    /// The Text row being typed; copied into the draft as it changes
+    pub text: TextInput,
+}
 
-    /// Users data
-    pub users: Vec<UserDto>,
+impl FilterPopup {
+    pub const ROWS: [&'static str; 4] = ["Status", "Client", "Manager", "Text"];
+    pub const TEXT_ROW: usize = 3;
 
-    /// Timeline widget state
-    pub radar_state: RadarState,
+    pub fn new(draft: ProjectFilter) -> Self {
+        let text = TextInput::new(draft.text.clone().unwrap_or_default());
+        Self { draft, row: 0, text }
+    }
+}
 
-    /// Particle system for background animation
-    pub particle_system: ParticleSystem,
+/// Search overlay (Ctrl+F): the query and the highlighted result
+#[derive(Debug, Clone, Default)]
+pub struct SearchOverlay {
+    pub query: TextInput,
+    /// Index into the current hits
+    pub selected: usize,
+}
 
-    /// Current error popup (if any)
-    pub error_popup: Option<ErrorPopup>,
+/// Inline rename (n): the selected entity's name edited in place, without
+/// the modal form
+#[derive(Debug, Clone, PartialEq)]
+pub struct InlineEdit {
+    pub entity: EntityType,
+    pub id: Uuid,
+    pub buffer: TextInput,
+}
 
-    /// Current form state (if any)
-    pub form_state: Option<FormState>,
+/// Move-to-client picker (m on a client's project): the clients narrowed by
+/// what is typed
+#[derive(Debug, Clone, PartialEq)]
+pub struct MovePicker {
+    pub project: Uuid,
+    pub query: TextInput,
+    /// Index into the current targets
+    pub selected: usize,
+}
 
-    /// Current confirm dialog (if any)
-    pub confirm_dialog: Option<ConfirmDialog>,
+/// Shown for a referenced client or user while it is fetched
+const LOADING_NAME: &str = "loading…";
 
-    /// Log messages
-    pub logs: Vec<LogEntry>,
-    /// Maximum number of log entries to keep
-    max_logs: usize,
+/// Where fetching a referenced client or user that no list holds stands
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Lookup {
+    /// Referenced by loaded data; the next `lookup_fetches` sends it
+    Wanted,
+    Loading,
+    /// The server says it doesn't exist
+    Missing,
+    /// The fetch failed for another reason; retried after the next list load
+    Failed,
+}
 
-    /// Selected index in lists (clients/users views)
-    pub list_selected: usize,
+/// Step through `None` followed by `options`, wrapping at both ends
+fn cycle<T: Copy + PartialEq>(options: &[T], current: Option<T>, forward: bool) -> Option<T> {
+    let len = options.len() + 1;
+    let position = current.and_then(|c| options.iter().position(|o| *o == c)).map_or(0, |i| i + 1);
+    let next = if forward { (position + 1) % len } else { (position + len - 1) % len };
+    next.checked_sub(1).map(|i| options[i])
+}
 
-    /// API connection status
-    pub api_connected: bool,
+/// First-run setup screen, shown while the API has never been reachable
+#[derive(Debug, Clone)]
+pub struct SetupState {
+    /// API base URL being edited
+    pub url: TextInput,
+    /// Whether the Retry button (rather than the URL field) has focus
+    pub retry_focused: bool,
+    /// Why the last attempt failed (or why the URL was rejected)
+    pub error: Option<String>,
+    /// Whether a connection attempt is in progress
+    pub retrying: bool,
+}
 
-    /// Last data refresh time
-    pub last_refresh: Option<Instant>,
+/// The saved filter presets ('P')
+#[derive(Debug, Clone, Default)]
+pub struct PresetPicker {
+    pub selected: usize,
+    /// Name being typed to save the current filter ('s')
+    pub naming: Option<TextInput>,
+}
 
-    /// Whether data is currently loading
-    pub is_loading: bool,
+/// The "Set API URL" prompt ('U'), pre-filled with the current base URL
+#[derive(Debug, Clone, Default)]
+pub struct UrlPrompt {
+    pub url: TextInput,
+    /// Why the typed URL was rejected
+    pub error: Option<String>,
+}
 
-    /// Frame counter for animations
-    pub frame_count: u64,
+impl SetupState {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: TextInput::new(url),
+            retry_focused: false,
+            error: None,
+            retrying: false,
+        }
+    }
+}
 
-    /// Show help overlay
-    pub show_help: bool,
+/// How the latest fetch of one collection went
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FetchStats {
+    /// HTTP status, or None when no response arrived
+    pub status: Option<u16>,
+    pub duration: Duration,
+    pub items: usize,
 }
 
-impl Default for App {
-    fn default() -> Self {
-        Self::new()
+impl FetchStats {
+    /// "projects: 200 OK · 1.4s · 300 items"
+    pub fn describe(&self, entity_type: EntityType) -> String {
+        let status = match self.status {
+            Some(code) => match reqwest::StatusCode::from_u16(code).ok().and_then(|s| s.canonical_reason()) {
+                Some(reason) => format!("{} {}", code, reason),
+                None => code.to_string(),
+            },
+            None => "no response".to_string(),
+        };
+        let plural = if self.items == 1 { "" } else { "s" };
+        format!(
+            "{}s: {} · {:.1}s · {} item{}",
+            entity_type.to_string().to_lowercase(),
+            status,
+            self.duration.as_secs_f64(),
+            format_count(self.items),
+            plural
+        )
     }
 }
 
-impl App {
-    /// Create a new application instance
-    pub fn new() -> Self {
-        let mut app = Self {
-            should_quit: false,
-            active_tab: Tab::Timeline,
-            input_mode: InputMode::Normal,
-            projects: Vec::new(),
-            clients: Vec::new(),
-            users: Vec::new(),
-            radar_state: RadarState::default(),
-            particle_system: ParticleSystem::default(),
-            error_popup: None,
-            form_state: None,
-            confirm_dialog: None,
-            logs: Vec::new(),
-            max_logs: 100,
-            list_selected: 0,
-            api_connected: false,
-            last_refresh: None,
-            is_loading: true,
-            frame_count: 0,
-            show_help: false,
-        };
+/// Whether a collection has loaded, so an empty list can say why it's empty
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum LoadState {
+    /// Nothing has arrived yet
+    #[default]
+    NotLoaded,
+    /// The last load succeeded with this many items
+    Loaded(usize),
+    /// The last load failed; a short reason such as "connection refused"
+    Failed(String),
+}
 
-        app.log(LogEntry::info("SWEeM TUI initialized"));
-        app.log(LogEntry::info("Connecting to API..."));
-        app
+/// Pagination state of one entity collection
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PageInfo {
+    /// Last page loaded (0 = nothing yet)
+    pub page: i32,
+    /// Total number of items on the server
+    pub total_count: usize,
+    /// Whether the server has more pages
+    pub has_next: bool,
+    /// Number of pages on the server
+    pub total_pages: i32,
+    /// Whether a page request is in flight
+    pub loading: bool,
+    /// Items fetched so far by a full load in progress, with the server total if known
+    pub fetched: Option<(usize, Option<usize>)>,
+    /// A full load ended with fewer items than the server's total
+    pub partial: bool,
+}
+
+impl PageInfo {
+    /// Record a page's metadata
+    fn update<T>(&mut self, result: &PaginatedResult<T>) {
+        self.page = result.page;
+        self.total_count = result.total_count.max(0) as usize;
+        self.has_next = result.has_next;
+        self.total_pages = result.total_pages.max(1);
+        self.loading = false;
+        self.fetched = None;
     }
 
-    /// Add a log entry
-    pub fn log(&mut self, entry: LogEntry) {
-        self.logs.push(entry);
-        if self.logs.len() > self.max_logs {
-            self.logs.remove(0);
+    /// State after a full load (eager fetch), keeping the server's totals
+    fn complete<T>(all: &FetchedAll<T>) -> Self {
+        Self {
+            page: all.pages.max(1),
+            total_count: all.total_count.unwrap_or(all.items.len()),
+            has_next: false,
+            total_pages: all.total_pages.max(1),
+            loading: false,
+            fetched: None,
+            partial: all.is_partial(),
         }
     }
+}
 
-    /// Show an error popup
-    pub fn show_error(&mut self, title: impl Into<String>, message: impl Into<String>) {
-        let title = title.into();
-        let message = message.into();
-        self.log(LogEntry::error(format!("{}: {}", title, message)));
-        self.error_popup = Some(ErrorPopup::new(title, message));
+/// Pagination state for every entity type
+#[derive(Debug, Clone, Default)]
+pub struct Paging {
+    pub projects: PageInfo,
+    pub clients: PageInfo,
+    pub users: PageInfo,
+}
+
+impl Paging {
+    pub fn get(&self, entity_type: EntityType) -> &PageInfo {
+        match entity_type {
+            EntityType::Project => &self.projects,
+            EntityType::Client => &self.clients,
+            EntityType::User => &self.users,
+        }
     }
 
-    /// Dismiss the current error popup
-    pub fn dismiss_error(&mut self) {
-        self.error_popup = None;
+    pub fn get_mut(&mut self, entity_type: EntityType) -> &mut PageInfo {
+        match entity_type {
+            EntityType::Project => &mut self.projects,
+            EntityType::Client => &mut self.clients,
+            EntityType::User => &mut self.users,
+        }
     }
 
-    /// Open create form for current tab
-    pub fn open_create_form(&mut self) {
-        let form = match self.active_tab {
-            Tab::Clients => FormState::new_create_client(),
-            Tab::Timeline => FormState::new_create_project(),
-            Tab::Users => FormState::new_create_user(),
-        };
-        self.form_state = Some(form);
-        self.input_mode = InputMode::Editing;
+    /// Whether any collection has a request in flight
+    pub fn any_loading(&self) -> bool {
+        self.projects.loading || self.clients.loading || self.users.loading
     }
 
-    /// Open edit form for selected item
-    pub fn open_edit_form(&mut self) {
-        let form = match self.active_tab {
-            Tab::Clients => {
-                if let Some(client) = self.clients.get(self.list_selected) {
-                    Some(FormState::new_edit_client(client))
-                } else {
-                    None
-                }
-            }
-            Tab::Timeline => {
-                if let Some(idx) = self.radar_state.selected_index {
-                    if let Some(project) = self.projects.get(idx) {
-                        Some(FormState::new_edit_project(
-                            project,
-                            &self.clients,
-                            &self.users,
-                        ))
-                    } else {
-                        None
-                    }
-                } else {
-                    None
-                }
+    fn set_loading(&mut self, loading: bool) {
+        for info in [&mut self.projects, &mut self.clients, &mut self.users] {
+            info.loading = loading;
+            if !loading {
+                info.fetched = None;
             }
-            Tab::Users => {
-                if let Some(user) = self.users.get(self.list_selected) {
-                    Some(FormState::new_edit_user(user))
-                } else {
-                    None
-                }
-            }
-        };
+        }
+    }
+}
 
-        if let Some(form) = form {
-            self.form_state = Some(form);
-            self.input_mode = InputMode::Editing;
+/// Consecutive log lines closer together than this can collapse into one
+pub const COALESCE_WINDOW_MS: i64 = 2000;
+
+/// Log entry for the message area
+#[derive(Debug, Clone)]
+pub struct LogEntry {
+    /// Wall-clock time, to line up with server logs
+    pub timestamp: DateTime<Local>,
+    pub message: String,
+    pub level: LogLevel,
+    /// The command this line is about, shown as a short "#3f2a" tag
+    pub request: Option<RequestId>,
+    /// How many lines this entry stands for, shown as "×47" past one
+    pub count: usize,
+    /// Lines folded into this one, oldest first, listed under it while the
+    /// System Log is expanded ('v')
+    pub details: Vec<LogEntry>,
+}
+
+/// Lines of a bulk operation, held out of the System Log until it finishes
+/// and then kept as the details of its summary line
+#[derive(Debug, Clone)]
+pub struct LogGroup {
+    pub title: String,
+    /// Lines tagged with this request join the group; others log as usual
+    pub request: RequestId,
+    pub entries: VecDeque<LogEntry>,
+}
+
+/// "Import line # failed" for "Import line 9 failed: timeout": the part
+/// before the first colon with its numbers blanked, for lines numbered
+/// like that; `None` for anything else
+fn log_label(message: &str) -> Option<String> {
+    let (label, _) = message.split_once(':')?;
+    if !label.contains(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+    let mut blanked = String::new();
+    for c in label.chars() {
+        match c.is_ascii_digit() {
+            true if blanked.ends_with('#') => {}
+            true => blanked.push('#'),
+            false => blanked.push(c),
         }
     }
+    Some(blanked)
+}
 
-    /// Open delete confirmation dialog
-    pub fn open_delete_confirm(&mut self) {
-        let dialog = match self.active_tab {
-            Tab::Clients => {
-                if let Some(client) = self.clients.get(self.list_selected) {
-                    Some(ConfirmDialog::new_delete(
-                        EntityType::Client,
-                        client.id,
-                        client.display_name(),
-                    ))
-                } else {
-                    None
-                }
-            }
-            Tab::Timeline => {
-                if let Some(idx) = self.radar_state.selected_index {
-                    if let Some(project) = self.projects.get(idx) {
-                        Some(ConfirmDialog::new_delete(
-                            EntityType::Project,
-                            project.id,
-                            project.display_name(),
-                        ))
-                    } else {
-                        None
-                    }
-                } else {
-                    None
-                }
-            }
-            Tab::Users => {
-                if let Some(user) = self.users.get(self.list_selected) {
-                    Some(ConfirmDialog::new_delete(
-                        EntityType::User,
-                        user.id,
-                        user.display_name(),
-                    ))
-                } else {
-                    None
-                }
-            }
-        };
+/// Append `entry` to `log`, folding it into the last line when they coalesce
+fn append_log(log: &mut VecDeque<LogEntry>, entry: LogEntry) {
+    match log.back_mut() {
+        Some(last) if last.coalesces(&entry) => last.absorb(entry),
+        _ => log.push_back(entry),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogLevel {
+    Info,
+    Success,
+    Warning,
+    Error,
+}
 
-        if let Some(dialog) = dialog {
-            self.confirm_dialog = Some(dialog);
-            self.input_mode = InputMode::Confirming;
+impl LogLevel {
+    /// Fixed-width name for exported lines
+    pub fn label(self) -> &'static str {
+        match self {
+            LogLevel::Info => "INFO ",
+            LogLevel::Success => "OK   ",
+            LogLevel::Warning => "WARN ",
+            LogLevel::Error => "ERROR",
         }
     }
+}
 
-    /// Close the current form
-    pub fn close_form(&mut self) {
-        self.form_state = None;
-        self.input_mode = InputMode::Normal;
+impl LogEntry {
+    fn new(level: LogLevel, message: impl Into<String>) -> Self {
+        Self {
+            timestamp: Local::now(),
+            message: message.into(),
+            level,
+            request: None,
+            count: 1,
+            details: Vec::new(),
+        }
     }
 
-    /// Close the confirm dialog
-    pub fn close_confirm(&mut self) {
-        self.confirm_dialog = None;
-        self.input_mode = InputMode::Normal;
+    pub fn info(message: impl Into<String>) -> Self {
+        Self::new(LogLevel::Info, message)
     }
 
-    /// Handle API messages
-    pub fn handle_api_message(&mut self, message: ApiMessage) {
-        match message {
-            ApiMessage::ProjectsLoaded(projects) => {
-                let count = projects.len();
-                self.projects = projects;
-                self.is_loading = false;
-                self.last_refresh = Some(Instant::now());
-                self.log(LogEntry::success(format!("Loaded {} projects", count)));
+    pub fn success(message: impl Into<String>) -> Self {
+        Self::new(LogLevel::Success, message)
+    }
 
-                // Auto-center timeline on first project or today when projects are loaded
-                if !self.projects.is_empty() && self.radar_state.selected_index.is_none() {
-                    self.radar_state.selected_index = Some(0);
-                }
-            }
-            ApiMessage::ClientsLoaded(clients) => {
-                let count = clients.len();
-                self.clients = clients;
-                self.log(LogEntry::success(format!("Loaded {} clients", count)));
-            }
-            ApiMessage::UsersLoaded(users) => {
-                let count = users.len();
-                self.users = users;
-                self.log(LogEntry::success(format!("Loaded {} users", count)));
-            }
-            ApiMessage::Error(error) => {
-                self.is_loading = false;
-                self.show_error("API Error", error);
-            }
-            ApiMessage::ConnectionStatus(connected) => {
-                let was_connected = self.api_connected;
-                self.api_connected = connected;
+    pub fn warning(message: impl Into<String>) -> Self {
+        Self::new(LogLevel::Warning, message)
+    }
 
-                if connected && !was_connected {
-                    self.log(LogEntry::success("Connected to API"));
-                } else if !connected && was_connected {
-                    self.log(LogEntry::warning("Disconnected from API"));
-                }
-            }
-            ApiMessage::Created(entity_type, id) => {
-                self.log(LogEntry::success(format!(
-                    "{} created ({})",
-                    entity_type,
-                    &id.to_string()[..8]
-                )));
-                self.close_form();
-            }
-            ApiMessage::Updated(entity_type) => {
-                self.log(LogEntry::success(format!("{} updated", entity_type)));
-                self.close_form();
-            }
-            ApiMessage::Deleted(entity_type, id) => {
-                self.log(LogEntry::success(format!(
-                    "{} deleted ({})",
-                    entity_type,
-                    &id.to_string()[..8]
-                )));
-                self.close_confirm();
-            }
-        }
+    pub fn error(message: impl Into<String>) -> Self {
+        Self::new(LogLevel::Error, message)
     }
 
-    /// Handle key events and return optional API command
-    pub fn handle_key(&mut self, key: KeyEvent) -> Option<ApiCommand> {
-        // Handle error popup dismissal
-        if self.error_popup.is_some() {
-            if matches!(key.code, KeyCode::Esc | KeyCode::Enter | KeyCode::Char(' ')) {
-                self.dismiss_error();
-            }
-            return None;
-        }
+    /// Whether `next` folds into this line: same level and request, logged
+    /// within `COALESCE_WINDOW_MS`, and the same message or a label that only
+    /// differs in its numbers ("Import line 3 failed: …", "Import line 9 failed: …")
+    pub fn coalesces(&self, next: &LogEntry) -> bool {
+        let gap = (next.timestamp - self.timestamp).num_milliseconds();
+        let same_label = || log_label(&self.message).is_some_and(|label| Some(label) == log_label(&next.message));
+        self.level == next.level
+            && self.request == next.request
+            && (0..=COALESCE_WINDOW_MS).contains(&gap)
+            && (self.message == next.message || same_label())
+    }
 
-        // Handle help overlay
-        if self.show_help {
-            if matches!(key.code, KeyCode::Esc | KeyCode::Char('?') | KeyCode::Enter) {
-                self.show_help = false;
-            }
-            return None;
+    /// Fold `next` in: its message and time win, the counts add up, and a
+    /// message it replaces is kept under `details`
+    fn absorb(&mut self, mut next: LogEntry) {
+        let mut details = std::mem::take(&mut self.details);
+        next.count += self.count;
+        let previous = std::mem::replace(self, next);
+        if previous.message != self.message {
+            details.push(previous);
         }
+        details.append(&mut self.details);
+        self.details = details;
+    }
 
-        // Handle based on input mode
-        match self.input_mode {
-            InputMode::Normal => self.handle_normal_key(key),
-            InputMode::Editing => self.handle_editing_key(key),
-            InputMode::Confirming => self.handle_confirming_key(key),
+    /// The message, with "×N" when it stands for several lines
+    pub fn text(&self) -> String {
+        match self.count {
+            1 => self.message.clone(),
+            n => format!("{} ×{}", self.message, n),
         }
     }
 
-    /// Handle keys in normal mode
-    fn handle_normal_key(&mut self, key: KeyEvent) -> Option<ApiCommand> {
-        // Global shortcuts
-        match key.code {
-            KeyCode::Char('q') | KeyCode::Char('Q') => {
-                self.should_quit = true;
-                return Some(ApiCommand::Shutdown);
-            }
-            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                self.should_quit = true;
-                return Some(ApiCommand::Shutdown);
-            }
-            KeyCode::Char('?') => {
-                self.show_help = true;
-                return None;
-            }
-            KeyCode::Char('p') => {
-                self.particle_system.toggle_mode();
-                let mode = self.particle_system.mode().name();
-                self.log(LogEntry::info(format!("Particle mode: {}", mode)));
-                return None;
-            }
-            KeyCode::Char('r') => {
-                self.is_loading = true;
-                self.log(LogEntry::info("Refreshing data..."));
-                return Some(ApiCommand::RefreshAll);
-            }
-            KeyCode::Tab => {
-                self.active_tab = self.active_tab.next();
-                self.list_selected = 0;
-                return None;
-            }
-            KeyCode::BackTab => {
-                self.active_tab = self.active_tab.previous();
-                self.list_selected = 0;
-                return None;
-            }
-            // CRUD shortcuts
-            KeyCode::Char('c') => {
-                self.open_create_form();
-                return None;
-            }
-            KeyCode::Char('e') => {
-                self.open_edit_form();
-                return None;
-            }
-            KeyCode::Char('d') | KeyCode::Delete => {
-                self.open_delete_confirm();
-                return None;
-            }
-            _ => {}
-        }
+    /// Tag the line with the command it belongs to
+    pub fn with_request(mut self, request: RequestId) -> Self {
+        self.request = Some(request);
+        self
+    }
 
-        // Tab-specific shortcuts
-        match self.active_tab {
-            Tab::Timeline => self.handle_timeline_key(key),
-            Tab::Clients => self.handle_list_key(key, self.clients.len()),
-            Tab::Users => self.handle_list_key(key, self.users.len()),
+    /// "14:03:22", as the System Log shows it
+    pub fn clock(&self) -> String {
+        self.timestamp.format("%H:%M:%S").to_string()
+    }
+
+    /// One line of an exported log: full date, milliseconds and UTC offset,
+    /// level, request tag and message
+    pub fn export_line(&self) -> String {
+        let mut line = format!("{} {}", self.timestamp.format("%Y-%m-%d %H:%M:%S%.3f %:z"), self.level.label());
+        if let Some(request) = self.request {
+            line.push_str(&format!(" {}", request));
         }
+        line.push(' ');
+        line.push_str(&self.text());
+        line
+    }
+}
 
-        None
+/// Default file name for a log exported at `now`
+pub fn log_export_path(now: DateTime<Local>) -> PathBuf {
+    PathBuf::from(format!("sweem-log-{}.log", now.format("%Y-%m-%d-%H%M%S")))
+}
+
+/// A pending refresh blocks another 'r' for this long
+const REFRESH_DEBOUNCE: Duration = Duration::from_millis(3000);
+
+/// Whether a manual refresh may be sent now. A refresh still pending after
+/// the debounce window is assumed lost and may be retried.
+fn refresh_allowed(requested_at: Option<Instant>, pending: bool, now: Instant) -> bool {
+    match requested_at {
+        Some(at) if pending => now.duration_since(at) >= REFRESH_DEBOUNCE,
+        _ => true,
     }
+}
 
-    /// Handle keys in editing mode (form)
-    fn handle_editing_key(&mut self, key: KeyEvent) -> Option<ApiCommand> {
-        if self.form_state.is_none() {
-            self.input_mode = InputMode::Normal;
-            return None;
+/// Rows before the end of the loaded data at which lazy mode fetches the next page
+const LOAD_AHEAD: usize = 5;
+
+/// Merge a fetched page into a collection: page 1 replaces, later pages append
+fn merge_page<T: Clone>(items: &mut Vec<T>, page: &PaginatedResult<T>, id: impl Fn(&T) -> Uuid) {
+    if page.page <= 1 {
+        items.clear();
+    }
+    for item in page.items() {
+        if !items.iter().any(|existing| id(existing) == id(item)) {
+            items.push(item.clone());
         }
+    }
+}
 
-        match key.code {
-            KeyCode::Esc => {
-                self.close_form();
-                return None;
-            }
-            KeyCode::Tab => {
-                if let Some(form) = &mut self.form_state {
-                    form.next_field();
-                }
-                return None;
-            }
-            KeyCode::BackTab => {
-                if let Some(form) = &mut self.form_state {
-                    form.prev_field();
-                }
-                return None;
-            }
-            KeyCode::Enter => {
-                return self.handle_form_submit();
+/// Format a count with thousands separators ("4,000")
+pub fn format_count(n: usize) -> String {
+    let digits = n.to_string();
+    let mut out = String::new();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            out.push(',');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// A short age: seconds under a minute, then minutes ("12s", "14m")
+fn format_age(age: Duration) -> String {
+    let secs = age.as_secs();
+    if secs < 60 {
+        format!("{}s", secs)
+    } else {
+        format!("{}m", secs / 60)
+    }
+}
+
+/// Main application state
+#[derive(Debug)]
+pub struct App {
+    /// Whether the application should quit
+    pub should_quit: bool,
+
+    /// Currently active tab
+    pub active_tab: Tab,
+
+    /// Current input mode
+    pub input_mode: InputMode,
+
+    /// Projects data
+    pub projects: Vec<ProjectDto>,
+
+    /// Clients data
+    pub clients: Vec<ClientDto>,
+
+    /// Users data
+    pub users: Vec<UserDto>,
+
+    /// Timeline widget state
+    pub radar_state: RadarState,
+    /// Where the radar canvas sits on screen (inside its border), set by
+    /// `ui::layout` before each frame for resolving mouse clicks; `None`
+    /// while it isn't on screen
+    pub radar_area: Option<Rect>,
+
+    /// Particle system for background animation
+    pub particle_system: ParticleSystem,
+
+    /// Current error popup (if any)
+    pub error_popup: Option<ErrorPopup>,
+
+    /// Current form state (if any)
+    pub form_state: Option<FormState>,
+
+    /// Current confirm dialog (if any)
+    pub confirm_dialog: Option<ConfirmDialog>,
+    /// Button focused when a single-item confirm dialog opens (`confirm_default`)
+    pub confirm_default: ConfirmButton,
+    /// Review the changed fields before an edit is sent (`confirm_edits`)
+    pub confirm_edits: bool,
+    /// Changes of the submitted edit form awaiting Enter
+    pub edit_review: Option<EditReview>,
+    /// Items marked with Space for a multi-select delete
+    pub marked: HashSet<Uuid>,
+    /// Multi-select delete in progress
+    pub batch_delete: Option<BatchDelete>,
+
+    /// Notifications in the top-right corner
+    pub toasts: ToastQueue,
+
+    /// Confetti over a project that was just completed
+    pub celebration: Option<CelebrationState>,
+
+    /// Project whose quick-complete update is in flight
+    completing: Option<Uuid>,
+
+    /// Entity just created, to be selected once a reload brings it in
+    pending_focus: Option<(EntityType, Uuid)>,
+
+    /// Entity being highlighted after creation, and the frame it started
+    flash: Option<(Uuid, u64)>,
+
+    /// Badges from the last reloads, with when each one expires
+    badges: HashMap<Uuid, (Badge, Instant)>,
+
+    /// Skip purely decorative animations (`reduced_motion` in the config)
+    pub reduced_motion: bool,
+
+    /// Whether the terminal has focus; stays true on terminals that never
+    /// report focus changes
+    pub has_focus: bool,
+
+    /// Show the whole System Log on a short terminal instead of one line, and
+    /// the lines folded into each entry ('v')
+    pub log_expanded: bool,
+    /// Frame timing, drawn in a corner while `show_frame_stats` (F12) is on
+    pub frame_stats: FrameStats,
+    pub show_frame_stats: bool,
+
+    /// Id the next command sent to the worker will carry
+    pub next_request: RequestId,
+    /// The command whose response is being handled; lines logged meanwhile are tagged with it
+    responding_to: Option<RequestId>,
+
+    /// Lowercase logins typed into the user forms (`lowercase_logins` in the config)
+    pub lowercase_logins: bool,
+    /// Length of passwords generated with Ctrl+G (`password_length` in the config)
+    pub password_length: usize,
+    /// Text for the event loop to put on the clipboard
+    pub clipboard: Option<String>,
+
+    /// How dates are shown and typed (`date_format` in the config)
+    pub date_format: DateFormat,
+
+    /// First column of the date picker calendar
+    pub week_start: WeekStart,
+
+    /// Calendar or working days, for schedules and days left (`use_working_days`)
+    pub day_count: DayCount,
+
+    /// Length of a new project, in `day_count` days (`default_project_duration_days`)
+    pub project_duration_days: i64,
+
+    /// Order projects are kept in (`project_sort` in the config)
+    pub project_sort: ProjectSort,
+
+    /// Width of radar snapshots, in columns
+    pub snapshot_width: u16,
+
+    /// Whether radar snapshots keep their colours
+    pub snapshot_format: SnapshotFormat,
+
+    /// Whether anything moves on its own; see `set_animations`
+    animations: bool,
+
+    /// Particle mode to bring back when animations are switched on again
+    paused_particle_mode: Option<ParticleMode>,
+
+    /// Log messages, oldest first
+    pub logs: VecDeque<LogEntry>,
+    /// The bulk operation whose lines are being held back, if any
+    pub log_group: Option<LogGroup>,
+    /// Maximum number of log entries to keep (`max_logs`)
+    pub max_logs: usize,
+
+    /// Selected index in lists (clients/users views)
+    pub list_selected: usize,
+
+    /// API connection status
+    pub api_connected: bool,
+
+    /// Whether the API has been reachable at least once this session
+    pub ever_connected: bool,
+
+    /// Whether a connection failure popup was shown since the last successful connect
+    connection_error_shown: bool,
+
+    /// Why the API is unreachable ("timed out", "refused"), when known
+    pub disconnect_reason: Option<&'static str>,
+
+    /// Round trip of the last successful health check
+    pub latency: Option<Duration>,
+
+    /// When the worker retries after the API rate limited it
+    rate_limited_until: Option<Instant>,
+
+    /// API base URL the worker is talking to
+    pub api_url: String,
+
+    /// Bearer token sent to the API (from the active profile)
+    pub api_token: Option<String>,
+
+    /// Server profiles from the config file, sorted by name
+    pub profiles: Vec<(String, Profile)>,
+
+    /// Name of the profile in use (None when connected by plain URL)
+    pub active_profile: Option<String>,
+
+    /// Selected row in the profile switcher (if open)
+    pub profile_switcher: Option<usize>,
+
+    /// Whether create/edit/delete are disabled
+    pub read_only: bool,
+
+    /// HTTPS certificates go unchecked (`--insecure`)
+    pub insecure: bool,
+
+    /// Login the TUI runs as (config `login`), matched against the loaded users
+    pub login: Option<String>,
+
+    /// The user `login` names, once the users have loaded
+    pub current_user: Option<UserDto>,
+
+    /// Mutations sent to the worker that haven't been answered yet
+    pub in_flight: usize,
+
+    /// Single-entity fetch the UI is waiting on
+    pending_fetch: Option<PendingFetch>,
+
+    /// Saved selections still waiting for their collections to load
+    pending_selections: Selections,
+
+    /// The radar zoom came from saved UI state, so loading data shouldn't refit it
+    range_restored: bool,
+
+    /// Path being typed into the import prompt (if open)
+    pub import_prompt: Option<TextInput>,
+
+    /// Date being typed into the jump-to-date prompt ('J'), if open
+    pub date_jump: Option<TextInput>,
+
+    /// Day ringed on the radar after a jump, and until when
+    jump_mark: Option<(NaiveDate, Instant)>,
+
+    /// The "Set API URL" prompt (if open)
+    pub url_prompt: Option<UrlPrompt>,
+
+    /// Saved filters, and the picker listing them ('P')
+    pub presets: Presets,
+    pub preset_picker: Option<PresetPicker>,
+    /// Where presets are written when they change; None keeps them in memory
+    pub presets_path: Option<std::path::PathBuf>,
+
+    /// Which projects the radar shows and steps through
+    pub project_filter: ProjectFilter,
+
+    /// Cutoff `H` hides completed projects at (`archive_after_days` in the config)
+    pub archive_after_days: u32,
+    /// Layer switched on at login (`default_filter` in the config)
+    pub default_filter: DefaultFilter,
+    /// The user's worst overdue project has been shown; only once per session
+    my_overdue_shown: bool,
+
+    /// Filter popup (if open)
+    pub filter_popup: Option<FilterPopup>,
+
+    /// Search overlay (if open); input goes to it in `InputMode::Searching`
+    pub search: Option<SearchOverlay>,
+
+    /// Inline rename in progress; takes the keyboard like a prompt
+    pub inline_edit: Option<InlineEdit>,
+
+    /// Move-to-client picker (if open); takes the keyboard like a prompt
+    pub move_picker: Option<MovePicker>,
+
+    /// Referenced clients and users that aren't loaded, recorded by
+    /// `want_missing_names` as data arrives
+    lookups: HashMap<(EntityType, Uuid), Lookup>,
+
+    /// Cursor (manager row, month column) while the Users tab shows the
+    /// workload heatmap instead of the list
+    pub workload_cursor: Option<(usize, usize)>,
+    /// Users tab split into role sections ('b'); `list_selected` stays an
+    /// index into `users` and navigation follows the grouped order
+    pub users_grouped: bool,
+
+    /// Focused pane of each tab; tabs missing here have the primary one
+    pub focused_panes: HashMap<Tab, Pane>,
+    /// Row of the upcoming deadlines list, the Timeline's secondary pane
+    pub upcoming_row: usize,
+    /// Row of the client's project list, the Clients tab's secondary pane
+    pub client_project_row: usize,
+    /// Day under the agenda strip's cursor, the Timeline's third pane
+    pub agenda_day: usize,
+    /// Whether the agenda strip is drawn (`agenda_strip` in the config)
+    pub agenda_strip: bool,
+
+    /// Keep the window title on the counts and the connection (`terminal_title`)
+    pub terminal_title: bool,
+    /// Clients whose server project counters disagreed with the loaded
+    /// projects at the last check, so the warning isn't repeated every reload
+    pub count_mismatches: Vec<Uuid>,
+    /// Colour cut-offs for the upcoming deadlines list
+    pub deadline_thresholds: Thresholds,
+    /// How the latest fetch of each collection went, for the F12 overlay
+    pub fetch_stats: HashMap<EntityType, FetchStats>,
+    /// How the latest load of each collection went
+    pub load_states: HashMap<EntityType, LoadState>,
+    /// Fetches slower than this are logged as warnings (`slow_fetch_secs`)
+    pub slow_fetch: Option<Duration>,
+    /// Data older than this is flagged and refreshed (`stale_after_secs`)
+    pub stale_after: Option<Duration>,
+    /// The refresh whose staleness was last reported, so it is reported once
+    stale_warned: Option<Instant>,
+
+    /// Month view shown on the Timeline tab in place of the radar
+    pub calendar: Option<CalendarState>,
+
+    /// Fetch pages on demand instead of whole collections
+    pub lazy_loading: bool,
+
+    /// Keep only the current page of projects, turned with `n`/`N`
+    pub page_browsing: bool,
+
+    /// Items per requested page
+    pub page_size: i32,
+
+    /// Pagination metadata per entity
+    pub paging: Paging,
+
+    /// First-run setup screen (if shown)
+    pub setup: Option<SetupState>,
+
+    /// Last data refresh time
+    pub last_refresh: Option<Instant>,
+
+    /// Whether data is currently loading
+    pub is_loading: bool,
+
+    /// When the last manual refresh was sent
+    refresh_requested_at: Option<Instant>,
+
+    /// Frame counter for animations
+    pub frame_count: u64,
+
+    /// Startup splash, until the API first answers
+    pub splash: Option<Splash>,
+
+    /// Show help overlay
+    pub show_help: bool,
+    /// What Enter does on the Timeline
+    pub enter_action: EnterAction,
+    /// Zoom out to keep the selection in view while stepping through
+    /// projects ('o', `timeline_follow_selection` in the config)
+    pub follow_selection: bool,
+    /// Details popup for the selected project (Enter on the Timeline)
+    pub details_popup: bool,
+    /// Change history of a project ('i' on the Timeline)
+    pub history: Option<HistoryPopup>,
+    /// The server answered 404 for a history; don't ask again
+    history_unsupported: bool,
+    /// Scroll position of the help overlay
+    pub help_scroll: PopupScroll,
+}
+
+impl Default for App {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl App {
+    /// Create a new application instance
+    pub fn new() -> Self {
+        let mut app = Self {
+            should_quit: false,
+            active_tab: Tab::Timeline,
+            input_mode: InputMode::Normal,
+            projects: Vec::new(),
+            clients: Vec::new(),
+            users: Vec::new(),
+            radar_state: RadarState::default(),
+            radar_area: None,
+            particle_system: ParticleSystem::default(),
+            error_popup: None,
+            form_state: None,
+            confirm_dialog: None,
+            confirm_default: ConfirmButton::No,
+            confirm_edits: false,
+            edit_review: None,
+            marked: HashSet::new(),
+            batch_delete: None,
+            toasts: ToastQueue::default(),
+            celebration: None,
+            completing: None,
+            pending_focus: None,
+            flash: None,
+            badges: HashMap::new(),
+            reduced_motion: false,
+            has_focus: true,
+            frame_stats: FrameStats::default(),
+            log_expanded: false,
+            show_frame_stats: false,
+            next_request: RequestId::generate(),
+            responding_to: None,
+            lowercase_logins: true,
+            password_length: passwords::DEFAULT_LENGTH,
+            clipboard: None,
+            date_format: DateFormat::default(),
+            week_start: WeekStart::default(),
+            day_count: DayCount::default(),
+            project_duration_days: crate::config::DEFAULT_PROJECT_DURATION_DAYS.into(),
+            project_sort: ProjectSort::default(),
+            snapshot_width: snapshot::DEFAULT_WIDTH,
+            snapshot_format: SnapshotFormat::default(),
+            animations: true,
+            paused_particle_mode: None,
+            logs: VecDeque::new(),
+            log_group: None,
+            max_logs: crate::config::DEFAULT_MAX_LOGS,
+            list_selected: 0,
+            api_connected: false,
+            ever_connected: false,
+            connection_error_shown: false,
+            disconnect_reason: None,
+            latency: None,
+            rate_limited_until: None,
+            api_url: api::DEFAULT_BASE_URL.to_string(),
+            api_token: None,
+            profiles: Vec::new(),
+            active_profile: None,
+            profile_switcher: None,
+            read_only: false,
+            insecure: false,
+            login: None,
+            current_user: None,
+            in_flight: 0,
+            pending_fetch: None,
+            pending_selections: Selections::default(),
+            range_restored: false,
+            import_prompt: None,
+            date_jump: None,
+            jump_mark: None,
+            url_prompt: None,
+            presets: Presets::default(),
+            preset_picker: None,
+            presets_path: None,
+            project_filter: ProjectFilter::default(),
+            archive_after_days: DEFAULT_ARCHIVE_DAYS,
+            default_filter: DefaultFilter::None,
+            my_overdue_shown: false,
+            filter_popup: None,
+            search: None,
+            inline_edit: None,
+            move_picker: None,
+            lookups: HashMap::new(),
+            workload_cursor: None,
+            users_grouped: false,
+            focused_panes: HashMap::new(),
+            upcoming_row: 0,
+            client_project_row: 0,
+            agenda_day: 0,
+            agenda_strip: true,
+            terminal_title: true,
+            deadline_thresholds: Thresholds::default(),
+            fetch_stats: HashMap::new(),
+            load_states: HashMap::new(),
+            slow_fetch: Some(Duration::from_secs(crate::config::DEFAULT_SLOW_FETCH_SECS)),
+            stale_after: Some(Duration::from_secs(crate::config::DEFAULT_STALE_AFTER_SECS)),
+            stale_warned: None,
+            count_mismatches: Vec::new(),
+            calendar: None,
+            lazy_loading: false,
+            page_browsing: false,
+            page_size: DEFAULT_PAGE_SIZE,
+            paging: Paging::default(),
+            setup: None,
+            last_refresh: None,
+            is_loading: true,
+            refresh_requested_at: None,
+            frame_count: 0,
+            splash: None,
+            show_help: false,
+            enter_action: EnterAction::Details,
+            follow_selection: true,
+            details_popup: false,
+            history: None,
+            history_unsupported: false,
+            help_scroll: PopupScroll::default(),
+        };
+
+        app.log(LogEntry::info("SWEeM TUI initialized"));
+        app.log(LogEntry::info("Connecting to API..."));
+        app
+    }
+
+    /// Add a log entry, collapsing it into the last one when they coalesce;
+    /// lines of an open log group go to the group instead
+    pub fn log(&mut self, mut entry: LogEntry) {
+        entry.request = entry.request.or(self.responding_to);
+        if let Some(group) = self.log_group.as_mut().filter(|g| entry.request == Some(g.request)) {
+            append_log(&mut group.entries, entry);
+            return;
+        }
+        append_log(&mut self.logs, entry);
+        while self.logs.len() > self.max_logs.max(1) {
+            self.logs.pop_front();
+        }
+    }
+
+    /// Hold back the lines of `request` until `close_log_group`
+    pub fn open_log_group(&mut self, title: impl Into<String>, request: RequestId) {
+        self.log_group = Some(LogGroup { title: title.into(), request, entries: VecDeque::new() });
+    }
+
+    /// Log `summary` with the group's lines as its details, pointing at
+    /// them when there are any
+    pub fn close_log_group(&mut self, mut summary: LogEntry) {
+        if let Some(group) = self.log_group.take() {
+            if !group.entries.is_empty() {
+                summary.message.push_str(" — press v for details");
             }
-            KeyCode::Backspace => {
-                if let Some(form) = &mut self.form_state {
-                    form.handle_backspace();
+            summary.details = group.entries.into();
+        }
+        self.log(summary);
+    }
+
+    /// Show a toast that disappears on its own
+    pub fn toast(&mut self, level: LogLevel, message: impl Into<String>) {
+        self.toasts.push(level, message, self.frame_count);
+    }
+
+    /// Show an error popup
+    pub fn show_error(&mut self, title: impl Into<String>, message: impl Into<String>) {
+        let title = title.into();
+        let message = message.into();
+        self.log(LogEntry::error(format!("{}: {}", title, message)));
+        self.error_popup = Some(ErrorPopup::new(title, message));
+    }
+
+    /// Whether animations are running
+    pub fn animations(&self) -> bool {
+        self.animations
+    }
+
+    /// Freeze or resume everything that moves on its own: particles (forced
+    /// off), the radar sweep, toast slides and the completion confetti
+    pub fn set_animations(&mut self, on: bool) {
+        if on == self.animations {
+            return;
+        }
+        self.animations = on;
+        if on {
+            let mode = self.paused_particle_mode.take().unwrap_or_default();
+            self.particle_system.set_mode(mode);
+        } else {
+            self.paused_particle_mode = Some(self.particle_system.mode());
+            self.particle_system.set_mode(ParticleMode::None);
+            self.celebration = None;
+        }
+    }
+
+    /// The user's particle mode, even while animations hold it at None
+    pub fn particle_mode(&self) -> ParticleMode {
+        self.paused_particle_mode.unwrap_or(self.particle_system.mode())
+    }
+
+    /// Whether the next frames differ from this one, so the loop must keep
+    /// drawing at full rate
+    pub fn needs_frames(&self) -> bool {
+        let busy = self.animations || !self.toasts.is_empty() || self.celebration.is_some() || self.splash.is_some();
+        self.has_focus && busy
+    }
+
+    /// Pause particles and the radar sweep while the terminal is in the
+    /// background; input and API messages are still handled
+    pub fn set_focus(&mut self, focused: bool) {
+        self.has_focus = focused;
+    }
+
+    /// Snapshot of the UI worth restoring next launch
+    pub fn ui_state(&self) -> UiState {
+        UiState {
+            version: STATE_VERSION,
+            active_tab: self.active_tab,
+            range_days: self.radar_state.range_days,
+            particle_mode: self.particle_mode(),
+            selections: self.selections(),
+        }
+    }
+
+    /// Ids of the selected project and of the selected row on the active list tab
+    fn selections(&self) -> Selections {
+        let list_item = |tab: Tab| (self.active_tab == tab).then_some(self.list_selected);
+        Selections {
+            project: self
+                .radar_state
+                .selected_index
+                .and_then(|i| self.projects.get(i))
+                .map(|p| p.id),
+            client: list_item(Tab::Clients).and_then(|i| self.clients.get(i)).map(|c| c.id),
+            user: list_item(Tab::Users).and_then(|i| self.users.get(i)).map(|u| u.id),
+        }
+    }
+
+    /// Move the selections to wherever their entities ended up after a reload
+    /// or reorder; entities that disappeared leave the index alone
+    fn follow_selections(&mut self, before: &Selections) {
+        if let Some(index) = before.project.and_then(|id| self.projects.iter().position(|p| p.id == id)) {
+            self.radar_state.selected_index = Some(index);
+        }
+        let list_index = match self.active_tab {
+            Tab::Clients => before.client.and_then(|id| self.clients.iter().position(|c| c.id == id)),
+            Tab::Users => before.user.and_then(|id| self.users.iter().position(|u| u.id == id)),
+            Tab::Timeline => None,
+        };
+        if let Some(index) = list_index {
+            self.list_selected = index;
+        }
+    }
+
+    /// Apply saved UI state; selections are resolved once their data arrives
+    pub fn restore_ui_state(&mut self, state: UiState) {
+        self.active_tab = state.active_tab;
+        self.radar_state.range_days = state.range_days;
+        self.range_restored = true;
+        match &mut self.paused_particle_mode {
+            Some(paused) => *paused = state.particle_mode,
+            None => self.particle_system.set_mode(state.particle_mode),
+        }
+        self.pending_selections = state.selections;
+        self.restore_selections();
+    }
+
+    /// Turn saved selection ids into indices, dropping ids that no longer exist
+    fn restore_selections(&mut self) {
+        if !self.projects.is_empty() {
+            if let Some(id) = self.pending_selections.project.take() {
+                if let Some(index) = self.projects.iter().position(|p| p.id == id) {
+                    self.radar_state.selected_index = Some(index);
                 }
-                return None;
             }
-            KeyCode::Up => {
-                if let Some(form) = &mut self.form_state {
-                    let field = form.current_field();
-                    if field.is_date_picker() {
-                        // Date picker: Up increases the date
-                        form.increment_date();
-                    } else {
-                        match field {
-                            FormField::ProjectClient => {
-                                if form.project_client_idx > 0 {
-                                    form.project_client_idx -= 1;
-                                }
-                            }
-                            FormField::ProjectManager => {
-                                if form.project_manager_idx > 0 {
-                                    form.project_manager_idx -= 1;
-                                }
-                            }
-                            FormField::UserRole => {
-                                form.user_role = form.user_role.next();
-                            }
-                            _ => {}
-                        }
+        }
+        // The list selection is shared, so only the active list tab's entry applies
+        if !self.clients.is_empty() {
+            if let Some(id) = self.pending_selections.client.take() {
+                if let Some(index) = self.clients.iter().position(|c| c.id == id) {
+                    if self.active_tab == Tab::Clients {
+                        self.list_selected = index;
                     }
                 }
-                return None;
             }
-            KeyCode::Down => {
-                if let Some(form) = &mut self.form_state {
-                    let field = form.current_field();
-                    if field.is_date_picker() {
-                        // Date picker: Down decreases the date
-                        form.decrement_date();
-                    } else {
-                        match field {
-                            FormField::ProjectClient => {
-                                if form.project_client_idx < self.clients.len().saturating_sub(1) {
-                                    form.project_client_idx += 1;
-                                }
-                            }
-                            FormField::ProjectManager => {
-                                if form.project_manager_idx < self.users.len().saturating_sub(1) {
-                                    form.project_manager_idx += 1;
-                                }
-                            }
-                            FormField::UserRole => {
-                                form.user_role = form.user_role.next();
-                            }
-                            _ => {}
-                        }
+        }
+        if !self.users.is_empty() {
+            if let Some(id) = self.pending_selections.user.take() {
+                if let Some(index) = self.users.iter().position(|u| u.id == id) {
+                    if self.active_tab == Tab::Users {
+                        self.list_selected = index;
                     }
                 }
-                return None;
             }
-            KeyCode::Left => {
-                if let Some(form) = &mut self.form_state {
-                    if form.current_field().is_date_picker() {
-                        // Date picker: Left decreases by 7 days (one week)
-                        for _ in 0..7 {
-                            form.decrement_date();
-                        }
-                    }
-                }
-                return None;
+        }
+    }
+
+    /// Time until the worker retries a rate-limited request, while waiting
+    pub fn rate_limit_remaining(&self) -> Option<Duration> {
+        self.rate_limited_until?
+            .checked_duration_since(Instant::now())
+            .filter(|d| !d.is_zero())
+    }
+
+    /// Dismiss the current error popup
+    pub fn dismiss_error(&mut self) {
+        self.error_popup = None;
+    }
+
+    /// Edit form for `project`, with the configured dates
+    fn edit_project_form(&self, project: &ProjectDto) -> FormState {
+        FormState::new_edit_project(project, &self.clients, &self.users, self.date_format, self.day_count)
+    }
+
+    /// Open create form for current tab
+    pub fn open_create_form(&mut self) {
+        let form = match self.active_tab {
+            Tab::Clients => FormState::new_create_client(),
+            Tab::Timeline => {
+                FormState::new_create_project(self.date_format, self.day_count, self.project_duration_days)
             }
-            KeyCode::Right => {
-                if let Some(form) = &mut self.form_state {
-                    if form.current_field().is_date_picker() {
-                        // Date picker: Right increases by 7 days (one week)
-                        for _ in 0..7 {
-                            form.increment_date();
-                        }
+            Tab::Users => FormState::new_create_user(),
+        };
+        self.form_state = Some(form);
+        self.input_mode = InputMode::Editing;
+    }
+
+    /// Open the edit form on the cached copy of `id`
+    fn open_cached_edit_form(&mut self, entity_type: EntityType, id: Uuid) {
+        let form = match entity_type {
+            EntityType::Client => self.clients.iter().find(|c| c.id == id).map(FormState::new_edit_client),
+            EntityType::Project => self
+                .projects
+                .iter()
+                .find(|p| p.id == id)
+                .map(|project| self.edit_project_form(project)),
+            EntityType::User => self.users.iter().find(|u| u.id == id).map(FormState::new_edit_user),
+        };
+        if let Some(form) = form {
+            self.form_state = Some(form);
+            self.input_mode = InputMode::Editing;
+        }
+    }
+
+    /// Open edit form for selected item
+    pub fn open_edit_form(&mut self) {
+        let form = match self.active_tab {
+            Tab::Clients => self
+                .clients
+                .get(self.list_selected)
+                .map(FormState::new_edit_client),
+            Tab::Timeline => self
+                .radar_state
+                .selected_index
+                .and_then(|idx| self.projects.get(idx))
+                .map(|project| self.edit_project_form(project)),
+            Tab::Users => self.users.get(self.list_selected).map(FormState::new_edit_user),
+        };
+
+        if let Some(form) = form {
+            self.form_state = Some(form);
+            self.input_mode = InputMode::Editing;
+        }
+    }
+
+    /// Ids and names of the active tab's items, in list order
+    fn tab_items(&self) -> Vec<(Uuid, String)> {
+        match self.active_tab {
+            Tab::Clients => self.clients.iter().map(|c| (c.id, c.display_name().to_string())).collect(),
+            Tab::Timeline => self.projects.iter().map(|p| (p.id, p.display_name().to_string())).collect(),
+            Tab::Users => self.users.iter().map(|u| (u.id, u.display_name().to_string())).collect(),
+        }
+    }
+
+    /// Id and name of the active tab's highlighted item
+    pub fn selected_item(&self) -> Option<(Uuid, String)> {
+        match self.active_tab {
+            Tab::Clients => self.clients.get(self.list_selected).map(|c| (c.id, c.display_name().to_string())),
+            Tab::Timeline => self
+                .radar_state
+                .selected_index
+                .and_then(|idx| self.projects.get(idx))
+                .map(|p| (p.id, p.display_name().to_string())),
+            Tab::Users => self.users.get(self.list_selected).map(|u| (u.id, u.display_name().to_string())),
+        }
+    }
+
+    /// Highlight the active tab's item with `id`, or failing that the first
+    /// one called `name`; false when neither is there
+    pub fn reselect(&mut self, id: Uuid, name: &str) -> bool {
+        let items = self.tab_items();
+        let found = items.iter().position(|(i, _)| *i == id).or_else(|| items.iter().position(|(_, n)| n == name));
+        let Some(index) = found else { return false };
+        match self.active_tab {
+            Tab::Timeline => self.radar_state.selected_index = Some(index),
+            Tab::Clients | Tab::Users => self.list_selected = index,
+        }
+        true
+    }
+
+    /// Whether keys go into a password field, which recordings must not keep
+    pub fn typing_secret(&self) -> bool {
+        self.input_mode == InputMode::Editing
+            && self.form_state.as_ref().is_some_and(|f| f.current_field() == FormField::UserPassword)
+    }
+
+    /// Mark or unmark the highlighted item for a multi-select delete (Space)
+    fn toggle_mark(&mut self) {
+        let Some((id, _)) = self.selected_item() else { return };
+        if !self.marked.remove(&id) {
+            self.marked.insert(id);
+        }
+    }
+
+    /// Open delete confirmation dialog: for the marked items of the active
+    /// tab if there are any, else for the highlighted one
+    pub fn open_delete_confirm(&mut self) {
+        let entity_type = self.active_entity_type();
+        let marked: Vec<(Uuid, String)> =
+            self.tab_items().into_iter().filter(|(id, _)| self.marked.contains(id)).collect();
+        let dialog = if marked.is_empty() {
+            match self.selected_item() {
+                Some((id, name)) => ConfirmDialog::new_delete(entity_type, id, &name),
+                None => return,
+            }
+        } else {
+            ConfirmDialog::new_batch_delete(entity_type, marked)
+        };
+        self.ask(dialog);
+    }
+
+    /// Show a confirm dialog; single-item ones open on `confirm_default`
+    fn ask(&mut self, mut dialog: ConfirmDialog) {
+        if !matches!(dialog.action, ConfirmAction::DeleteMany(..)) {
+            dialog.focus = self.confirm_default;
+        }
+        self.confirm_dialog = Some(dialog);
+        self.input_mode = InputMode::Confirming;
+    }
+
+    /// Refetch the collection behind a stale edit form (Ctrl+R)
+    fn reload_stale_form(&mut self) -> Option<ApiCommand> {
+        let form = self.form_state.as_mut()?;
+        if form.nav().staleness != Staleness::Stale {
+            return None;
+        }
+        form.nav_mut().staleness = Staleness::Reloading;
+        let command = match form.form_type() {
+            FormType::EditClient(_) => ApiCommand::RefreshClients,
+            FormType::EditProject(_) => ApiCommand::RefreshProjects,
+            FormType::EditUser(_) => ApiCommand::RefreshUsers,
+            _ => return None,
+        };
+        self.log(LogEntry::info("Reloading..."));
+        Some(command)
+    }
+
+    /// Re-prefill a reloading edit form from freshly loaded data, or close it
+    /// if the item is gone
+    fn refill_reloading_form(&mut self) {
+        let Some(form) = &self.form_state else { return };
+        if form.nav().staleness != Staleness::Reloading {
+            return;
+        }
+        let fresh = match form.form_type() {
+            FormType::EditClient(id) => self
+                .clients
+                .iter()
+                .find(|c| c.id == id)
+                .map(|c| (FormState::new_edit_client(c), snapshot_hash(c))),
+            FormType::EditProject(id) => self
+                .projects
+                .iter()
+                .find(|p| p.id == id)
+                .map(|p| (self.edit_project_form(p), snapshot_hash(p))),
+            FormType::EditUser(id) => self
+                .users
+                .iter()
+                .find(|u| u.id == id)
+                .map(|u| (FormState::new_edit_user(u), snapshot_hash(u))),
+            _ => return,
+        };
+        match fresh {
+            Some((mut form, snapshot)) => {
+                form.nav_mut().snapshot = Some(snapshot);
+                self.form_state = Some(form);
+                self.log(LogEntry::info("Form reloaded with the latest data"));
+            }
+            None => {
+                self.close_form();
+                self.log(LogEntry::warning("The item was removed elsewhere"));
+            }
+        }
+    }
+
+    /// Close the current form
+    pub fn close_form(&mut self) {
+        self.form_state = None;
+        self.input_mode = InputMode::Normal;
+    }
+
+    /// Close the confirm dialog
+    pub fn close_confirm(&mut self) {
+        self.confirm_dialog = None;
+        // A dialog raised over a form hands the keyboard back to it
+        self.input_mode = if self.form_state.is_some() {
+            InputMode::Editing
+        } else {
+            InputMode::Normal
+        };
+    }
+
+    /// Log what the next command is about to do, tagged with its request id
+    fn log_intent(&mut self, message: impl Into<String>) {
+        let id = self.next_request;
+        self.log(LogEntry::info(message).with_request(id));
+    }
+
+    /// Wrap a command for the worker under `next_request`, then draw a fresh id
+    pub fn stamp(&mut self, command: ApiCommand) -> Request {
+        let id = std::mem::replace(&mut self.next_request, RequestId::generate());
+        Request { id, command }
+    }
+
+    /// Handle a worker response, tagging the lines it logs with its request id.
+    ///
+    /// A mutation sent in reply (an update held back for a freshness check)
+    /// continues the same request; anything else gets a new id.
+    pub fn handle_response(&mut self, response: Response) -> Option<Request> {
+        self.responding_to = response.request;
+        let command = self.handle_api_message(response.message);
+        self.responding_to = None;
+        match (command, response.request) {
+            (Some(command), Some(id)) if command.is_mutating() => Some(Request { id, command }),
+            (command, _) => command.map(|command| self.stamp(command)),
+        }
+    }
+
+    /// Handle API messages, returning a follow-up command if one is needed
+    pub fn handle_api_message(&mut self, message: ApiMessage) -> Option<ApiCommand> {
+        let message = self.sanitize_message(message)?;
+        let data_arrived = matches!(
+            message,
+            ApiMessage::ProjectsLoaded(_)
+                | ApiMessage::ClientsLoaded(_)
+                | ApiMessage::UsersLoaded(_)
+                | ApiMessage::ProjectsPage(_)
+                | ApiMessage::ClientsPage(_)
+                | ApiMessage::UsersPage(_)
+                | ApiMessage::OneLoaded(_)
+        );
+        let selected = self.selections();
+        let mut command = None;
+        // Any answer from the server ends the splash; chatter about the request doesn't
+        let chatter = matches!(
+            message,
+            ApiMessage::Trace(_)
+                | ApiMessage::Warning(_)
+                | ApiMessage::RateLimited(_)
+                | ApiMessage::LoadProgress(..)
+                | ApiMessage::FetchStats { .. }
+                | ApiMessage::WorkerStopped
+        );
+        if !chatter {
+            self.splash = None;
+        }
+
+        match message {
+            ApiMessage::FetchedOne(entity) => command = self.handle_fetched(entity),
+            ApiMessage::OneLoaded(entity) => {
+                self.lookups.remove(&(entity.entity_type(), entity.id()));
+                self.upsert_cached(entity);
+                self.project_sort.sort(&mut self.projects);
+            }
+            ApiMessage::ProjectsLoaded(all) => {
+                let info = PageInfo::complete(&all);
+                let failed = all.stopped_early();
+                let previous = std::mem::replace(&mut self.projects, all.items);
+                self.project_sort.sort(&mut self.projects);
+                if self.paging.projects.page > 0 {
+                    self.show_changes(diff_entities(&previous, &self.projects, self.date_format));
+                }
+                self.paging.projects = info;
+                self.is_loading = false;
+                self.last_refresh = Some(Instant::now());
+                self.log_loaded(EntityType::Project, self.projects.len(), failed);
+                self.mark_loaded(EntityType::Project);
+                self.reconcile_client_counts();
+
+                if self.radar_state.selected_index.is_none() {
+                    self.auto_center_timeline();
+                }
+                self.focus_my_overdue();
+            }
+            ApiMessage::ClientsLoaded(all) => {
+                let info = PageInfo::complete(&all);
+                let failed = all.stopped_early();
+                let previous = std::mem::replace(&mut self.clients, all.items);
+                if self.paging.clients.page > 0 {
+                    self.show_changes(diff_entities(&previous, &self.clients, self.date_format));
+                }
+                self.paging.clients = info;
+                self.forget_failed_lookups(EntityType::Client);
+                self.log_loaded(EntityType::Client, self.clients.len(), failed);
+                self.mark_loaded(EntityType::Client);
+                self.reconcile_client_counts();
+            }
+            ApiMessage::UsersLoaded(all) => {
+                let info = PageInfo::complete(&all);
+                let failed = all.stopped_early();
+                let previous = std::mem::replace(&mut self.users, all.items);
+                if self.paging.users.page > 0 {
+                    self.show_changes(diff_entities(&previous, &self.users, self.date_format));
+                }
+                self.paging.users = info;
+                self.forget_failed_lookups(EntityType::User);
+                self.log_loaded(EntityType::User, self.users.len(), failed);
+                let first_load = *self.load_state(EntityType::User) == LoadState::NotLoaded;
+                self.mark_loaded(EntityType::User);
+                self.identify_current_user(first_load);
+            }
+            ApiMessage::ProjectsPage(page) => {
+                if self.page_browsing {
+                    // Only the browsed page is kept; a new page starts without a selection
+                    if page.page != self.paging.projects.page {
+                        self.radar_state.selected_index = None;
                     }
+                    self.projects = page.items().to_vec();
+                } else {
+                    merge_page(&mut self.projects, &page, |p| p.id);
                 }
-                return None;
+                self.project_sort.sort(&mut self.projects);
+                self.paging.projects.update(&page);
+                self.is_loading = false;
+                self.last_refresh = Some(Instant::now());
+                self.log_page_loaded(EntityType::Project, self.projects.len());
+                self.mark_loaded(EntityType::Project);
+                if self.radar_state.selected_index.is_none() {
+                    self.auto_center_timeline();
+                }
+                self.focus_my_overdue();
             }
-            KeyCode::Char(c) => {
-                if let Some(form) = &mut self.form_state {
-                    form.handle_char(c);
+            ApiMessage::LoadProgress(entity_type, loaded, total) => {
+                self.paging.get_mut(entity_type).fetched = Some((loaded, total));
+            }
+            ApiMessage::FetchStats { entity, status, duration, items } => {
+                let stats = FetchStats { status, duration, items };
+                if self.slow_fetch.is_some_and(|limit| duration > limit) {
+                    self.log(LogEntry::warning(format!("Slow fetch: {}", stats.describe(entity))));
                 }
-                return None;
+                self.fetch_stats.insert(entity, stats);
+            }
+            ApiMessage::ClientsPage(page) => {
+                merge_page(&mut self.clients, &page, |c| c.id);
+                self.paging.clients.update(&page);
+                self.log_page_loaded(EntityType::Client, self.clients.len());
+                self.mark_loaded(EntityType::Client);
+            }
+            ApiMessage::UsersPage(page) => {
+                merge_page(&mut self.users, &page, |u| u.id);
+                self.paging.users.update(&page);
+                self.log_page_loaded(EntityType::User, self.users.len());
+                self.mark_loaded(EntityType::User);
+                // Later pages may still hold the login
+                self.identify_current_user(!page.has_next);
+            }
+            ApiMessage::Failed(ApiOperation::Delete(entity_type, id), error) if self.in_batch(entity_type, id) => {
+                command = self.batch_item_done(entity_type, id, Some(error));
+            }
+            ApiMessage::HistoryLoaded(id, mut records) => {
+                if let Some(popup) = self.history.as_mut().filter(|p| p.project_id == id) {
+                    records.sort_by_key(|r| std::cmp::Reverse(r.timestamp));
+                    popup.records = Some(Ok(records));
+                }
+            }
+            ApiMessage::Failed(ApiOperation::History(id), error) => self.history_failed(id, error),
+            ApiMessage::Failed(operation, error) => {
+                self.mark_load_failed(operation, &error);
+                self.handle_failure(operation, error);
+            }
+            ApiMessage::Trace(line) => self.log(LogEntry::info(line)),
+            ApiMessage::WorkerStopped => self.log(LogEntry::info("API worker stopped")),
+            ApiMessage::Warning(line) => self.log(LogEntry::warning(line)),
+            ApiMessage::RateLimited(retry_after) => {
+                if self.rate_limit_remaining().is_none() {
+                    self.log(LogEntry::warning(format!(
+                        "Rate limited by the API, retrying in {}s",
+                        retry_after.as_secs()
+                    )));
+                }
+                self.rate_limited_until = Some(Instant::now() + retry_after);
+            }
+            ApiMessage::Refused(error) => {
+                self.in_flight = self.in_flight.saturating_sub(1);
+                self.show_error("API Error", error);
+            }
+            ApiMessage::Error(error) => {
+                self.is_loading = false;
+                self.paging.set_loading(false);
+                if self.setup.is_some() {
+                    // The setup screen already explains the failure
+                    self.log(LogEntry::error(error));
+                } else {
+                    self.show_error("API Error", error);
+                }
+            }
+            ApiMessage::ConnectionStatus(latency) => {
+                let connected = latency.is_some();
+                let was_connected = self.api_connected;
+                self.api_connected = connected;
+                self.latency = latency;
+
+                if connected && !was_connected {
+                    self.log(LogEntry::success(format!("Connected to {}", self.api_url)));
+                } else if !connected && was_connected {
+                    self.log(LogEntry::warning("Disconnected from API"));
+                }
+
+                if connected {
+                    self.ever_connected = true;
+                    self.connection_error_shown = false;
+                    self.disconnect_reason = None;
+                    self.setup = None;
+                } else if !self.ever_connected {
+                    let url = self.api_url.clone();
+                    let setup = self.setup.get_or_insert_with(|| SetupState::new(url.clone()));
+                    setup.retrying = false;
+                    setup.error = Some(format!("Cannot connect to {}", url));
+                }
+            }
+            ApiMessage::Created(entity_type, id) => {
+                self.in_flight = self.in_flight.saturating_sub(1);
+                self.log(LogEntry::success(format!(
+                    "{} created ({})",
+                    entity_type,
+                    &id.to_string()[..8]
+                )));
+                self.toast(LogLevel::Success, format!("{} created", entity_type));
+                self.pending_focus = Some((entity_type, id));
+                self.close_form();
+            }
+            ApiMessage::Updated(entity_type, _) => {
+                self.in_flight = self.in_flight.saturating_sub(1);
+                self.log(LogEntry::success(format!("{} updated", entity_type)));
+                self.toast(LogLevel::Success, format!("{} updated", entity_type));
+                if entity_type == EntityType::Project {
+                    if let Some(id) = self.completing.take() {
+                        self.celebrate(id);
+                    }
+                }
+                self.close_form();
+            }
+            ApiMessage::ImportProgress { done, total, line, error } => match error {
+                Some(error) => {
+                    self.log(LogEntry::error(format!("Import line {} failed: {}", line, error)));
+                }
+                None if done % 10 == 0 || done == total => {
+                    self.log(LogEntry::info(format!("Imported {}/{}", done, total)));
+                }
+                None => {}
+            },
+            ApiMessage::BatchProgress(entity_type, chunk) => {
+                let what = entity_type.to_string().to_lowercase();
+                if chunk.failed == 0 {
+                    self.log(LogEntry::info(format!(
+                        "Batch {}/{}: {} {}s created",
+                        chunk.chunk, chunk.chunks, chunk.created, what
+                    )));
+                } else {
+                    self.log(LogEntry::warning(format!(
+                        "Batch {}/{}: {} {}s created, {} failed",
+                        chunk.chunk, chunk.chunks, chunk.created, what, chunk.failed
+                    )));
+                }
+            }
+            ApiMessage::ImportFinished { entity_type, created, failures } => {
+                self.in_flight = self.in_flight.saturating_sub(1);
+                let summary = format!("Import finished: {} ok, {} failed", created, failures.len());
+                self.close_log_group(match failures.len() {
+                    0 => LogEntry::success(summary),
+                    _ => LogEntry::warning(summary),
+                });
+                if failures.is_empty() {
+                    self.toast(
+                        LogLevel::Success,
+                        format!("{} {}s imported", format_count(created), entity_type.to_string().to_lowercase()),
+                    );
+                } else {
+                    let lines: Vec<String> = failures.iter().map(|(line, _)| line.to_string()).collect();
+                    self.show_error(
+                        "Import Incomplete",
+                        format!(
+                            "{} {}s created, {} failed (lines {})",
+                            created,
+                            entity_type,
+                            failures.len(),
+                            lines.join(", ")
+                        ),
+                    );
+                }
+            }
+            ApiMessage::Deleted(entity_type, id) if self.in_batch(entity_type, id) => {
+                command = self.batch_item_done(entity_type, id, None);
+            }
+            ApiMessage::Deleted(entity_type, id) => {
+                self.in_flight = self.in_flight.saturating_sub(1);
+                self.log(LogEntry::success(format!(
+                    "{} deleted ({})",
+                    entity_type,
+                    &id.to_string()[..8]
+                )));
+                self.toast(LogLevel::Success, format!("{} deleted", entity_type));
+                self.remove_cached(entity_type, id);
+                self.close_confirm();
+            }
+        }
+
+        if data_arrived {
+            self.want_missing_names();
+            self.follow_selections(&selected);
+            self.refill_reloading_form();
+            self.restore_selections();
+            self.keep_selection_visible();
+            self.resolve_pending_focus();
+        }
+        if command.as_ref().is_some_and(|c| c.is_mutating()) {
+            self.in_flight += 1;
+        }
+        command
+    }
+
+    /// Whether this is the item a multi-select delete is waiting on
+    fn in_batch(&self, entity_type: EntityType, id: Uuid) -> bool {
+        self.batch_delete.as_ref().is_some_and(|b| b.is_current(entity_type, id))
+    }
+
+    /// Log how one item of a multi-select delete went and send the next
+    /// delete, or sum up when the batch is done or stopped by a failure
+    fn batch_item_done(&mut self, entity_type: EntityType, id: Uuid, error: Option<ApiError>) -> Option<ApiCommand> {
+        self.in_flight = self.in_flight.saturating_sub(1);
+        let batch = self.batch_delete.as_mut()?;
+        let (_, name) = batch.current.clone();
+        let position = batch.position();
+        let failed = match error {
+            // Someone else deleted it first; the outcome is what the user wanted
+            Some(error) if error.status() != Some(404) => {
+                batch.failures.push((name.clone(), error.to_string()));
+                let line = format!("{} \"{}\" not deleted ({}): {}", entity_type, name, position, error);
+                self.log(LogEntry::error(line));
+                true
+            }
+            _ => {
+                batch.deleted += 1;
+                self.remove_cached(entity_type, id);
+                self.log(LogEntry::success(format!("{} \"{}\" deleted ({})", entity_type, name, position)));
+                false
+            }
+        };
+
+        let batch = self.batch_delete.as_mut()?;
+        if (!failed || batch.skip_errors) && batch.advance() {
+            return Some(batch.command());
+        }
+        let batch = self.batch_delete.take()?;
+        let what = format!("{}s", entity_type.to_string().to_lowercase());
+        if batch.failures.is_empty() {
+            self.log(LogEntry::success(format!("Deleted {} {}", batch.deleted, what)));
+            self.toast(LogLevel::Success, format!("{} {} deleted", format_count(batch.deleted), what));
+        } else {
+            let mut message = batch.summary();
+            self.log(LogEntry::warning(format!("Deleting {}: {}", what, message)));
+            for (name, reason) in &batch.failures {
+                message.push_str(&format!("\n\"{}\": {}", name, reason));
+            }
+            self.show_error("Delete Incomplete", message);
+        }
+        None
+    }
+
+    /// Act on a freshly fetched entity: open the edit form, or check a pending
+    /// update against the snapshot the form was opened with
+    fn handle_fetched(&mut self, fresh: FetchedEntity) -> Option<ApiCommand> {
+        match self.pending_fetch.take()? {
+            PendingFetch::Edit(_, id) if id == fresh.id() => {
+                let snapshot = fresh.snapshot();
+                let mut form = match &fresh {
+                    FetchedEntity::Client(c) => FormState::new_edit_client(c),
+                    FetchedEntity::Project(p) => self.edit_project_form(p),
+                    FetchedEntity::User(u) => FormState::new_edit_user(u),
+                };
+                form.nav_mut().snapshot = Some(snapshot);
+                self.upsert_cached(fresh);
+                self.form_state = Some(form);
+                self.input_mode = InputMode::Editing;
+                None
+            }
+            PendingFetch::Edit(..) => None,
+            PendingFetch::Submit(update) => {
+                let form = self.form_state.as_mut()?;
+                if form.nav().snapshot == Some(fresh.snapshot()) {
+                    return Some(*update);
+                }
+                form.nav_mut().submitting = false;
+                let (entity_type, _) = form.form_type().edited()?;
+                self.log(LogEntry::warning(format!("{} changed on the server", entity_type)));
+                self.ask(ConfirmDialog::new_overwrite(entity_type, *update));
+                None
+            }
+        }
+    }
+
+    /// Swap a cached list entry for its fresh copy, adding it if it's new
+    fn upsert_cached(&mut self, fresh: FetchedEntity) {
+        fn upsert<T>(items: &mut Vec<T>, id: Uuid, fresh: T, id_of: impl Fn(&T) -> Uuid) {
+            match items.iter_mut().find(|item| id_of(item) == id) {
+                Some(slot) => *slot = fresh,
+                None => items.push(fresh),
+            }
+        }
+        let id = fresh.id();
+        match fresh {
+            FetchedEntity::Client(c) => upsert(&mut self.clients, id, c, |c| c.id),
+            FetchedEntity::Project(p) => upsert(&mut self.projects, id, p, |p| p.id),
+            FetchedEntity::User(u) => upsert(&mut self.users, id, u, |u| u.id),
+        }
+    }
+
+    /// Drop a deleted entity from its cached list, keeping the selection on
+    /// the same item (or the one that took its place)
+    fn remove_cached(&mut self, entity_type: EntityType, id: Uuid) {
+        fn remove<T>(items: &mut Vec<T>, id: Uuid, id_of: impl Fn(&T) -> Uuid) -> Option<usize> {
+            let index = items.iter().position(|item| id_of(item) == id)?;
+            items.remove(index);
+            Some(index)
+        }
+        let (removed, len) = match entity_type {
+            EntityType::Client => (remove(&mut self.clients, id, |c| c.id), self.clients.len()),
+            EntityType::Project => (remove(&mut self.projects, id, |p| p.id), self.projects.len()),
+            EntityType::User => (remove(&mut self.users, id, |u| u.id), self.users.len()),
+        };
+        let Some(removed) = removed else {
+            return;
+        };
+        match entity_type {
+            EntityType::Project => {
+                self.radar_state.selected_index = match self.radar_state.selected_index {
+                    Some(i) if i > removed => Some(i - 1),
+                    Some(i) if i >= len => len.checked_sub(1),
+                    selected => selected,
+                };
+            }
+            EntityType::Client if self.active_tab == Tab::Clients => {
+                self.list_selected = self.list_selected.min(len.saturating_sub(1));
+            }
+            EntityType::User if self.active_tab == Tab::Users => {
+                self.list_selected = self.list_selected.min(len.saturating_sub(1));
+            }
+            _ => {}
+        }
+    }
+
+    /// Ask for a fresh copy of the selected item before editing it
+    fn request_edit(&mut self) -> Option<ApiCommand> {
+        let (entity_type, id) = match self.active_tab {
+            Tab::Clients if self.focused_pane() == Pane::Secondary => {
+                let index = self.selected_client_project()?;
+                (EntityType::Project, self.projects[index].id)
+            }
+            Tab::Clients => (EntityType::Client, self.clients.get(self.list_selected)?.id),
+            Tab::Timeline => {
+                let index = self.radar_state.selected_index?;
+                (EntityType::Project, self.projects.get(index)?.id)
+            }
+            Tab::Users => (EntityType::User, self.users.get(self.list_selected)?.id),
+        };
+        self.pending_fetch = Some(PendingFetch::Edit(entity_type, id));
+        Some(ApiCommand::FetchOne(entity_type, id))
+    }
+
+    /// Start renaming the selected entity in place
+    fn start_rename(&mut self) {
+        let selected = match self.active_tab {
+            Tab::Clients => self.clients.get(self.list_selected).map(|c| (EntityType::Client, c.id, c.display_name())),
+            Tab::Timeline => self
+                .radar_state
+                .selected_index
+                .and_then(|i| self.projects.get(i))
+                .map(|p| (EntityType::Project, p.id, p.display_name())),
+            // The list isn't showing behind the heatmap
+            Tab::Users if self.workload_cursor.is_some() => None,
+            Tab::Users => self.users.get(self.list_selected).map(|u| (EntityType::User, u.id, u.display_name())),
+        };
+        if let Some((entity, id, name)) = selected {
+            let buffer = TextInput::new(name);
+            self.inline_edit = Some(InlineEdit { entity, id, buffer });
+        }
+    }
+
+    /// Handle keys while renaming inline
+    fn handle_inline_edit_key(&mut self, key: KeyEvent) -> Option<ApiCommand> {
+        let edit = self.inline_edit.as_mut()?;
+        match key.code {
+            KeyCode::Enter => return self.submit_rename(),
+            _ => {
+                edit.buffer.handle_key(key);
+            }
+        }
+        None
+    }
+
+    /// Clients a project can move to: every other client whose name contains
+    /// the picker's query, with the matching range
+    pub fn move_targets(&self) -> Vec<(usize, std::ops::Range<usize>)> {
+        let Some(picker) = &self.move_picker else {
+            return Vec::new();
+        };
+        let current = self.projects.iter().find(|p| p.id == picker.project).map(|p| p.client_id);
+        let query = picker.query.value().trim();
+        self.clients
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| Some(c.id) != current)
+            .filter_map(|(i, c)| match query {
+                "" => Some((i, 0..0)),
+                _ => search::find_ignore_case(c.display_name(), query).map(|range| (i, range)),
+            })
+            .collect()
+    }
+
+    fn handle_move_picker_key(&mut self, key: KeyEvent) -> Option<ApiCommand> {
+        let last = self.move_targets().len().saturating_sub(1);
+        let picker = self.move_picker.as_mut()?;
+        match key.code {
+            KeyCode::Enter => return self.submit_move(),
+            KeyCode::Down => picker.selected = (picker.selected + 1).min(last),
+            KeyCode::Up => picker.selected = picker.selected.saturating_sub(1),
+            _ => {
+                if picker.query.handle_key(key) {
+                    picker.selected = 0;
+                }
+            }
+        }
+        None
+    }
+
+    /// Send an update that changes only the project's client
+    fn submit_move(&mut self) -> Option<ApiCommand> {
+        let targets = self.move_targets();
+        let picker = self.move_picker.as_ref()?;
+        let &(client_index, _) = targets.get(picker.selected)?;
+        let picker = self.move_picker.take()?;
+        let Some(project) = self.projects.iter().find(|p| p.id == picker.project) else {
+            self.log(LogEntry::warning("Cannot move: the project is gone"));
+            return None;
+        };
+        let client = &self.clients[client_index];
+        let dto = UpdateProjectDto { client_id: client.id, ..UpdateProjectDto::from_project(project) };
+        let intent = format!("Moving {} to {}...", project.display_name(), client.display_name());
+        self.log_intent(intent);
+        Some(ApiCommand::UpdateProject(picker.project, dto))
+    }
+
+    /// Send an update that changes only the name; an invalid name keeps the
+    /// field open
+    fn submit_rename(&mut self) -> Option<ApiCommand> {
+        let edit = self.inline_edit.take()?;
+        let new = edit.buffer.value().trim().to_string();
+        let renamed = match edit.entity {
+            EntityType::Project => {
+                let project = self.projects.iter().find(|p| p.id == edit.id);
+                project.map(|p| {
+                    let dto = UpdateProjectDto { name: Some(new.clone()), ..UpdateProjectDto::from_project(p) };
+                    (p.display_name().to_string(), dto.validate().map(|()| ApiCommand::UpdateProject(edit.id, dto)))
+                })
+            }
+            EntityType::Client => {
+                let client = self.clients.iter().find(|c| c.id == edit.id);
+                client.map(|c| {
+                    let dto = UpdateClientDto { name: Some(new.clone()), ..UpdateClientDto::from_client(c) };
+                    (c.display_name().to_string(), dto.validate().map(|()| ApiCommand::UpdateClient(edit.id, dto)))
+                })
+            }
+            EntityType::User => {
+                let user = self.users.iter().find(|u| u.id == edit.id);
+                user.map(|u| {
+                    let dto = UpdateUserDto { name: Some(new.clone()), ..UpdateUserDto::from_user(u) };
+                    (u.display_name().to_string(), dto.validate().map(|()| ApiCommand::UpdateUser(edit.id, dto)))
+                })
+            }
+        };
+        let Some((old, update)) = renamed else {
+            self.log(LogEntry::warning(format!("Cannot rename: the {} is gone", edit.entity.to_string().to_lowercase())));
+            return None;
+        };
+        match update {
+            _ if old == new => {
+                self.log(LogEntry::info("Name unchanged"));
+                None
+            }
+            Ok(command) => {
+                self.log_intent(format!("Renaming {} to {}...", old, new));
+                Some(command)
+            }
+            Err(e) => {
+                self.log(LogEntry::warning(e));
+                self.inline_edit = Some(edit);
+                None
+            }
+        }
+    }
+
+    /// Route an edit form's update through a freshness check when the form
+    /// knows which server copy it was filled from
+    fn verify_before_update(&mut self, update: ApiCommand) -> Option<ApiCommand> {
+        let form = self.form_state.as_ref()?;
+        let (Some(_), Some((entity_type, id))) = (form.nav().snapshot, form.form_type().edited()) else {
+            return Some(update);
+        };
+        self.pending_fetch = Some(PendingFetch::Submit(Box::new(update)));
+        Some(ApiCommand::FetchOne(entity_type, id))
+    }
+
+    /// React to a failed API call according to what kind of failure it was
+    fn handle_failure(&mut self, operation: ApiOperation, error: ApiError) {
+        // A name lookup only changes what the name shows
+        if let ApiOperation::Fetch(entity_type, id) = operation {
+            if let Some(lookup) = self.lookups.get_mut(&(entity_type, id)) {
+                *lookup = if error.status() == Some(404) { Lookup::Missing } else { Lookup::Failed };
+                self.log(LogEntry::warning(format!("Could not look up {} {}: {}", entity_type, short_id(id), error)));
+                return;
+            }
+        }
+
+        if operation == ApiOperation::Update(EntityType::Project) {
+            self.completing = None;
+        }
+        if matches!(operation, ApiOperation::Create(_) | ApiOperation::Update(_) | ApiOperation::Fetch(..)) {
+            if let Some(form) = &mut self.form_state {
+                form.nav_mut().submitting = false;
+            }
+        }
+
+        // Someone else deleted it first; the outcome is what the user wanted
+        if let (ApiOperation::Delete(entity_type, id), Some(404)) = (operation, error.status()) {
+            self.log(LogEntry::info(format!("{} already deleted elsewhere", entity_type)));
+            self.handle_api_message(ApiMessage::Deleted(entity_type, id));
+            return;
+        }
+
+        if let ApiOperation::Fetch(entity_type, _) = operation {
+            let pending = self.pending_fetch.take();
+            if error.status() == Some(404) {
+                self.log(LogEntry::warning(format!("{} was removed elsewhere", entity_type)));
+                if let ApiOperation::Fetch(_, id) = operation {
+                    self.remove_cached(entity_type, id);
+                }
+                if matches!(pending, Some(PendingFetch::Submit(_))) {
+                    self.close_form();
+                }
+                return;
+            }
+            if let Some(PendingFetch::Edit(entity_type, id)) = pending {
+                // Offline or flaky: edit the cached copy rather than refusing
+                self.log(LogEntry::warning(format!("Editing cached copy: {}", error)));
+                self.open_cached_edit_form(entity_type, id);
+                return;
+            }
+        }
+
+        if operation.is_mutation() {
+            self.in_flight = self.in_flight.saturating_sub(1);
+        } else {
+            self.is_loading = false;
+            self.paging.set_loading(false);
+        }
+
+        let message = format!("{} failed: {}", operation, error);
+
+        // The item changed or vanished on the server; offer to reload the form
+        if let (ApiOperation::Update(_), Some(404 | 409)) = (operation, error.status()) {
+            if let Some(form) = &mut self.form_state {
+                form.nav_mut().staleness = Staleness::Stale;
+                form.nav_mut().error = None;
+                self.log(LogEntry::warning(message));
+                return;
+            }
+        }
+
+        // Validation problems belong in the still-open form, next to the fields
+        let rejected = matches!(error.status(), Some(400 | 422));
+        if rejected && matches!(operation, ApiOperation::Create(_) | ApiOperation::Update(_)) {
+            if let Some(form) = &mut self.form_state {
+                form.nav_mut().error = Some(error.to_string());
+                self.log(LogEntry::error(message));
+                return;
+            }
+        }
+
+        if error.is_connection() {
+            if self.api_connected {
+                self.log(LogEntry::warning("Disconnected from API"));
+            }
+            self.api_connected = false;
+            self.latency = None;
+            self.disconnect_reason = error.connection_reason();
+            if let (Some(setup), Some(reason)) = (&mut self.setup, self.disconnect_reason) {
+                setup.error = Some(format!("Cannot connect to {} ({})", self.api_url, reason));
+            }
+            // One popup per outage; repeats only go to the log
+            if self.connection_error_shown || self.setup.is_some() {
+                self.log(LogEntry::error(message));
+                return;
+            }
+            self.connection_error_shown = true;
+        } else if self.setup.is_some() {
+            // The setup screen already explains the failure
+            self.log(LogEntry::error(message));
+            return;
+        } else if matches!(error, ApiError::RateLimited { .. }) && !operation.is_mutation() {
+            // Still limited after the worker's retry; the next refresh will try again
+            self.log(LogEntry::warning(message));
+            return;
+        }
+        self.show_error("API Error", message);
+    }
+
+    /// The open layers, topmost first; see `Layer` for the order
+    pub fn layers(&self) -> Vec<Layer> {
+        let timeline = self.active_tab == Tab::Timeline;
+        let form = self.form_state.as_ref();
+        [
+            (Layer::ErrorPopup, self.error_popup.is_some()),
+            (Layer::ImportPrompt, self.import_prompt.is_some()),
+            (Layer::UrlPrompt, self.url_prompt.is_some()),
+            (Layer::DateJump, self.date_jump.is_some()),
+            (Layer::FilterPopup, self.filter_popup.is_some()),
+            (Layer::PresetName, self.preset_picker.as_ref().is_some_and(|p| p.naming.is_some())),
+            (Layer::PresetPicker, self.preset_picker.is_some()),
+            (Layer::InlineEdit, self.inline_edit.is_some()),
+            (Layer::MovePicker, self.move_picker.is_some()),
+            (Layer::EditReview, self.edit_review.is_some()),
+            (Layer::ProfileSwitcher, self.profile_switcher.is_some()),
+            (Layer::Setup, self.setup.is_some()),
+            (Layer::Help, self.show_help),
+            (Layer::Details, self.details_popup),
+            (Layer::History, self.history.is_some()),
+            (Layer::ConfirmDialog, self.input_mode == InputMode::Confirming && self.confirm_dialog.is_some()),
+            (Layer::Dropdown, form.is_some_and(|f| f.nav().dropdown_open)),
+            (Layer::Form, form.is_some()),
+            (Layer::Search, self.input_mode == InputMode::Searching && self.search.is_some()),
+            (Layer::DateEdit, timeline && self.radar_state.pending_edit.is_some()),
+            (Layer::Marks, !self.marked.is_empty()),
+            (Layer::DayPopup, timeline && self.calendar.as_ref().is_some_and(|c| c.day_popup.is_some())),
+            (Layer::Calendar, timeline && self.calendar.is_some()),
+            (Layer::Pane, self.focused_pane() != Pane::Primary),
+            (Layer::Workload, self.active_tab == Tab::Users && self.workload_cursor.is_some()),
+            (Layer::Filter, timeline && self.project_filter.is_active()),
+        ]
+        .into_iter()
+        .filter_map(|(layer, open)| open.then_some(layer))
+        .collect()
+    }
+
+    /// Close the topmost layer, and only that one (Esc)
+    pub fn handle_escape(&mut self) {
+        let Some(&layer) = self.layers().first() else {
+            return;
+        };
+        match layer {
+            Layer::ErrorPopup => self.dismiss_error(),
+            Layer::ImportPrompt => self.import_prompt = None,
+            Layer::UrlPrompt => self.url_prompt = None,
+            Layer::DateJump => self.date_jump = None,
+            Layer::FilterPopup => self.filter_popup = None,
+            Layer::PresetName => {
+                if let Some(picker) = &mut self.preset_picker {
+                    picker.naming = None;
+                }
+            }
+            Layer::PresetPicker => self.preset_picker = None,
+            Layer::InlineEdit => self.inline_edit = None,
+            Layer::MovePicker => self.move_picker = None,
+            // Back to the form as it was
+            Layer::EditReview => self.edit_review = None,
+            Layer::ProfileSwitcher => self.profile_switcher = None,
+            Layer::Setup => {
+                // Continue offline into the normal UI
+                self.setup = None;
+                self.log(LogEntry::warning("Setup skipped - working offline"));
+            }
+            Layer::Help => self.show_help = false,
+            Layer::Details => self.details_popup = false,
+            Layer::History => self.history = None,
+            Layer::ConfirmDialog => self.decline_confirm(),
+            Layer::Dropdown => {
+                if let Some(form) = &mut self.form_state {
+                    form.nav_mut().dropdown_open = false;
+                }
+            }
+            // Even while it is being saved
+            Layer::Form => self.close_form(),
+            Layer::Search => self.close_search(),
+            Layer::DateEdit => self.discard_date_edit(),
+            Layer::Marks => self.marked.clear(),
+            Layer::DayPopup => {
+                if let Some(calendar) = &mut self.calendar {
+                    calendar.day_popup = None;
+                }
+            }
+            Layer::Calendar => self.calendar = None,
+            Layer::Pane => self.focus_pane(Pane::Primary),
+            Layer::Workload => self.workload_cursor = None,
+            Layer::Filter => self.set_project_filter(self.project_filter.cleared()),
+        }
+    }
+
+    /// Handle key events and return optional API command
+    pub fn handle_key(&mut self, key: KeyEvent) -> Option<ApiCommand> {
+        // The frame-time overlay is for diagnosing, so it toggles from anywhere
+        if key.code == KeyCode::F(12) {
+            self.show_frame_stats = !self.show_frame_stats;
+            return None;
+        }
+
+        // Nothing behind the splash is on screen yet; only quitting makes sense
+        if self.splash.is_some() {
+            let ctrl_c = key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL);
+            if ctrl_c || key.code == KeyCode::Char('q') {
+                self.should_quit = true;
+                return Some(ApiCommand::Shutdown);
+            }
+            return None;
+        }
+
+        // Esc closes the topmost layer and nothing under it
+        if key.code == KeyCode::Esc {
+            self.handle_escape();
+            return None;
+        }
+
+        // Popups and prompts take every key while they are on top
+        match self.layers().first() {
+            Some(Layer::ErrorPopup) => {
+                // Reading further down keeps it open
+                if let Some(popup) = &mut self.error_popup {
+                    if popup.scroll.handle_key(key, true) {
+                        popup.auto_dismiss = None;
+                    } else if matches!(key.code, KeyCode::Enter | KeyCode::Char(' ')) {
+                        self.dismiss_error();
+                    }
+                }
+                return None;
+            }
+            Some(Layer::ImportPrompt) => {
+                self.handle_import_prompt_key(key);
+                return None;
+            }
+            Some(Layer::UrlPrompt) => return self.handle_url_prompt_key(key),
+            Some(Layer::DateJump) => {
+                self.handle_date_jump_key(key);
+                return None;
+            }
+            Some(Layer::FilterPopup) => {
+                self.handle_filter_popup_key(key);
+                return None;
+            }
+            Some(Layer::PresetName | Layer::PresetPicker) => {
+                self.handle_preset_picker_key(key);
+                return None;
+            }
+            Some(Layer::InlineEdit) => {
+                let cmd = self.handle_inline_edit_key(key);
+                return self.track_mutation(cmd);
+            }
+            Some(Layer::MovePicker) => {
+                let cmd = self.handle_move_picker_key(key);
+                return self.track_mutation(cmd);
+            }
+            Some(Layer::EditReview) => {
+                let cmd = self.handle_edit_review_key(key);
+                return self.track_mutation(cmd);
+            }
+            _ => {}
+        }
+
+        // Ctrl+E opens the profile switcher from anywhere outside a form
+        if key.code == KeyCode::Char('e')
+            && key.modifiers.contains(KeyModifiers::CONTROL)
+            && self.input_mode == InputMode::Normal
+        {
+            self.open_profile_switcher();
+            return None;
+        }
+
+        // Ctrl+F searches everything from anywhere outside a form
+        if key.code == KeyCode::Char('f')
+            && key.modifiers.contains(KeyModifiers::CONTROL)
+            && self.input_mode == InputMode::Normal
+        {
+            self.search = Some(SearchOverlay::default());
+            self.input_mode = InputMode::Searching;
+            return None;
+        }
+
+        match self.layers().first() {
+            Some(Layer::ProfileSwitcher) => return self.handle_profile_switcher_key(key),
+            // Setup screen owns the keyboard until the API is reachable
+            Some(Layer::Setup) => return self.handle_setup_key(key),
+            Some(Layer::Help) => {
+                if matches!(key.code, KeyCode::Char('?') | KeyCode::Enter) {
+                    self.show_help = false;
+                } else {
+                    self.help_scroll.handle_key(key, true);
+                }
+                return None;
+            }
+            Some(Layer::Details) => {
+                if key.code == KeyCode::Enter {
+                    self.details_popup = false;
+                }
+                return None;
+            }
+            Some(Layer::History) => {
+                if matches!(key.code, KeyCode::Enter | KeyCode::Char('i')) {
+                    self.history = None;
+                } else if let Some(popup) = &mut self.history {
+                    popup.scroll.handle_key(key, true);
+                }
+                return None;
+            }
+            _ => {}
+        }
+
+        // Handle based on input mode
+        let cmd = match self.input_mode {
+            InputMode::Normal => self.handle_normal_key(key),
+            InputMode::Editing => self.handle_editing_key(key),
+            InputMode::Confirming => self.handle_confirming_key(key),
+            InputMode::Searching => {
+                self.handle_search_key(key);
+                None
+            }
+        };
+        self.track_mutation(cmd)
+    }
+
+    /// Count a mutating command as in flight; the last line of defence, too,
+    /// so nothing mutating leaves the UI in read-only mode
+    fn track_mutation(&mut self, cmd: Option<ApiCommand>) -> Option<ApiCommand> {
+        match cmd {
+            Some(cmd) if self.read_only && cmd.is_mutating() => {
+                self.close_form();
+                self.close_confirm();
+                self.log(LogEntry::warning("Read-only mode: change discarded"));
+                None
+            }
+            Some(cmd) if cmd.is_mutating() => {
+                self.in_flight += 1;
+                Some(cmd)
+            }
+            cmd => cmd,
+        }
+    }
+
+    /// Role of the logged-in user, if any
+    pub fn role(&self) -> Option<Role> {
+        self.current_user.as_ref().map(|user| user.role)
+    }
+
+    /// Record the authenticated user and leave any tab their role cannot see
+    pub fn set_current_user(&mut self, user: UserDto) {
+        self.log(LogEntry::success(format!(
+            "Logged in as {} ({})",
+            user.display_name(),
+            user.role
+        )));
+        let user_id = user.id;
+        self.current_user = Some(user);
+        if !Tab::visible(self.role()).contains(&self.active_tab) {
+            self.active_tab = Tab::Timeline;
+            self.list_selected = 0;
+        }
+        if self.default_filter == DefaultFilter::Mine || self.project_filter.mine.is_some() {
+            self.project_filter.mine = Some(user_id);
+            self.keep_selection_visible();
+        }
+        self.focus_my_overdue();
+    }
+
+    /// Find the configured `login` among the loaded users and take on its
+    /// role; after the `first_load` of users, warn if nobody has it
+    fn identify_current_user(&mut self, first_load: bool) {
+        let Some(login) = self.login.clone() else { return };
+        let user = self.users.iter().find(|u| u.login.as_deref().is_some_and(|l| l.eq_ignore_ascii_case(&login)));
+        match user.cloned() {
+            Some(user) => {
+                let known = self.current_user.as_ref().map(|u| (u.id, u.role));
+                if known != Some((user.id, user.role)) {
+                    self.set_current_user(user);
+                }
+            }
+            None if first_load && self.current_user.is_none() => {
+                self.log(LogEntry::warning(format!("No user with login '{}'; role limits are off", login)));
+            }
+            None => {}
+        }
+    }
+
+    /// Active and overdue projects the logged-in user manages; None without a login
+    pub fn my_project_counts(&self) -> Option<(usize, usize)> {
+        let user = self.current_user.as_ref()?;
+        let today = chrono::Local::now().date_naive();
+        let mine = self.projects.iter().filter(|p| p.manager_id == user.id);
+        Some(mine.fold((0, 0), |(active, overdue), p| match p.status(today) {
+            ProjectStatus::Active => (active + 1, overdue),
+            ProjectStatus::Overdue => (active, overdue + 1),
+            _ => (active, overdue),
+        }))
+    }
+
+    /// One line on every view setting that differs from the defaults, e.g.
+    /// "Filter: LATE · Acme │ Sort: end date │ 37 hidden"; None when all are default
+    pub fn breadcrumb(&self) -> Option<String> {
+        let mut parts = Vec::new();
+        let deps = self.filter_deps();
+        let filter = ProjectFilter { archive_after_days: None, ..self.project_filter.clone() };
+        if filter != ProjectFilter::default() {
+            parts.push(format!("Filter: {}", filter.describe(&deps)));
+        }
+        if self.project_filter.archive_after_days != self.default_archive_layer() {
+            parts.push(match self.project_filter.archive_after_days {
+                Some(days) => format!("Archive: older than {} days hidden", days),
+                None => "Archive: shown".to_string(),
+            });
+        }
+        if self.project_sort != ProjectSort::default() {
+            parts.push(format!("Sort: {}", self.project_sort.describe()));
+        }
+        if parts.is_empty() {
+            return None;
+        }
+        let hidden = self.projects.len() - self.visible_projects().len();
+        if hidden > 0 {
+            parts.push(format!("{} hidden", format_count(hidden)));
+        }
+        Some(parts.join(" │ "))
+    }
+
+    /// The archive layer as configured (`archive_after_days`)
+    fn default_archive_layer(&self) -> Option<u32> {
+        (self.archive_after_days > 0).then_some(self.archive_after_days)
+    }
+
+    /// Put the filter, its layers and the sort back to their defaults (Ctrl+X)
+    fn reset_view(&mut self) {
+        if self.breadcrumb().is_none() {
+            return;
+        }
+        let mine = match self.default_filter {
+            DefaultFilter::Mine => self.current_user.as_ref().map(|user| user.id),
+            DefaultFilter::None => None,
+        };
+        self.project_filter = ProjectFilter {
+            archive_after_days: self.default_archive_layer(),
+            mine,
+            ..ProjectFilter::default()
+        };
+        self.project_sort = ProjectSort::default();
+        let selected = self.selections();
+        self.project_sort.sort(&mut self.projects);
+        self.follow_selections(&selected);
+        self.keep_selection_visible();
+        self.log(LogEntry::info("View reset to defaults"));
+    }
+
+    /// Show only the logged-in user's projects, or everyone's again ('~')
+    fn toggle_mine(&mut self) {
+        let Some(user_id) = self.current_user.as_ref().map(|user| user.id) else {
+            return;
+        };
+        self.project_filter.mine = match self.project_filter.mine {
+            Some(_) => None,
+            None => Some(user_id),
+        };
+        self.keep_selection_visible();
+        if self.project_filter.mine.is_some() {
+            let shown = self.visible_projects().len();
+            self.log(LogEntry::info(format!("Showing only your projects ({} of {})", shown, self.projects.len())));
+        } else {
+            self.log(LogEntry::info("Showing everyone's projects"));
+        }
+    }
+
+    /// Once both the login and the projects are in, select the user's project
+    /// furthest past its deadline, if any, and center the radar on it
+    fn focus_my_overdue(&mut self) {
+        if self.my_overdue_shown || self.projects.is_empty() {
+            return;
+        }
+        let Some(user_id) = self.current_user.as_ref().map(|user| user.id) else {
+            return;
+        };
+        self.my_overdue_shown = true;
+        let today = chrono::Local::now().date_naive();
+        let worst = self
+            .visible_projects()
+            .into_iter()
+            .filter(|&i| self.projects[i].manager_id == user_id)
+            .filter(|&i| self.projects[i].status(today) == ProjectStatus::Overdue)
+            .min_by_key(|&i| (self.projects[i].planned_end_date, self.projects[i].id));
+        let Some(index) = worst else {
+            return;
+        };
+        self.radar_state.selected_index = Some(index);
+        self.center_selected();
+        let project = &self.projects[index];
+        let late = (today - project.planned_end_date).num_days();
+        self.log(LogEntry::warning(format!("Your project {} is {} days overdue", project.display_name(), late)));
+    }
+
+    /// Log why and return true if the user's role fails the `allowed` check
+    /// for clients; `action` only names it in the log
+    fn blocked_by_role(&mut self, allowed: fn(&Role) -> bool, action: &str) -> bool {
+        let Some(role) = self.role() else { return false };
+        let forbidden = self.active_tab == Tab::Clients && !allowed(&role);
+        if forbidden {
+            self.log(LogEntry::warning(format!("{}s cannot {} clients - ask an Admin", role, action)));
+        }
+        forbidden
+    }
+
+    /// Log a warning and return true if read-only mode blocks `action`
+    fn blocked_by_read_only(&mut self, action: &str) -> bool {
+        if self.read_only {
+            self.log(LogEntry::warning(format!("Read-only mode: cannot {}", action)));
+        }
+        self.read_only
+    }
+
+    /// Write the Markdown status report for the loaded data, including archived
+    /// projects only while they are shown
+    pub fn export_report(&mut self) {
+        let today = chrono::Local::now().date_naive();
+        let path = report::default_path(today);
+        let projects = self.exported_projects();
+        match report::write(&path, &projects, &self.clients, &self.users, &*self, today, self.date_format) {
+            Ok(()) => {
+                let shown = std::path::absolute(&path).unwrap_or(path);
+                self.log(LogEntry::success(format!("Report written to {}", shown.display())));
+            }
+            Err(e) => self.show_error("Report Failed", format!("{:#}", e)),
+        }
+    }
+
+    /// Write every line still in the System Log, with timestamps, to a file
+    pub fn export_log(&mut self) {
+        let path = log_export_path(Local::now());
+        let lines = self.logs.len();
+        match self.write_log(&path) {
+            Ok(()) => {
+                let shown = std::path::absolute(&path).unwrap_or(path);
+                self.log(LogEntry::success(format!("Log ({} lines) written to {}", lines, shown.display())));
+            }
+            Err(e) => self.show_error("Log Export Failed", format!("Failed to write {}: {}", path.display(), e)),
+        }
+    }
+
+    /// The System Log as `export_line`s, oldest first, each followed by
+    /// its details indented
+    fn write_log(&self, path: &std::path::Path) -> std::io::Result<()> {
+        let mut text = String::new();
+        for entry in &self.logs {
+            text.push_str(&(entry.export_line() + "\n"));
+            for detail in &entry.details {
+                text.push_str(&format!("    {}\n", detail.export_line()));
+            }
+        }
+        std::fs::write(path, text)
+    }
+
+    /// Write every project on the radar, at the current range, to a text file;
+    /// archived projects are left out while `H` hides them
+    pub fn export_snapshot(&mut self) {
+        let today = chrono::Local::now().date_naive();
+        let path = snapshot::default_path(today, self.snapshot_format);
+        let written = snapshot::write(
+            &path,
+            &self.exported_projects(),
+            &self.clients,
+            self.radar_state.range_days,
+            today,
+            self.snapshot_width,
+            self.snapshot_format,
+        );
+        match written {
+            Ok(()) => {
+                let shown = std::path::absolute(&path).unwrap_or(path);
+                self.log(LogEntry::success(format!("Radar snapshot written to {}", shown.display())));
+            }
+            Err(e) => self.show_error("Snapshot Failed", format!("{:#}", e)),
+        }
+    }
+
+    /// Entity type that the active tab creates
+    pub fn active_entity_type(&self) -> EntityType {
+        match self.active_tab {
+            Tab::Clients => EntityType::Client,
+            Tab::Timeline => EntityType::Project,
+            Tab::Users => EntityType::User,
+        }
+    }
+
+    /// Handle keys in the import path prompt
+    fn handle_import_prompt_key(&mut self, key: KeyEvent) {
+        let Some(path) = self.import_prompt.as_mut() else {
+            return;
+        };
+        match key.code {
+            KeyCode::Enter => {
+                let path = path.take();
+                self.import_prompt = None;
+                self.plan_import(path.trim());
+            }
+            _ => {
+                path.handle_key(key);
+            }
+        }
+    }
+
+    /// Handle keys in the jump-to-date prompt; a date that doesn't read
+    /// keeps it open
+    fn handle_date_jump_key(&mut self, key: KeyEvent) {
+        let Some(input) = self.date_jump.as_mut() else {
+            return;
+        };
+        if key.code != KeyCode::Enter {
+            input.handle_key(key);
+            return;
+        }
+        let today = chrono::Local::now().date_naive();
+        match dates::parse_date_input(input.value(), self.date_format, today) {
+            Some(date) => {
+                self.date_jump = None;
+                self.jump_to_date(date, today);
+            }
+            None => {
+                let message = format!("Not a date: '{}' (try 2025-03-10, +2w or tomorrow)", input.value().trim());
+                self.log(LogEntry::warning(message));
+            }
+        }
+    }
+
+    /// Zoom the radar to `date` and ring it for a moment. Past days all sit
+    /// in the radar's centre, so those open in the calendar instead.
+    fn jump_to_date(&mut self, date: NaiveDate, today: NaiveDate) {
+        self.discard_date_edit();
+        if date < today {
+            self.calendar = Some(CalendarState::new(date));
+            let shown = format_date(&date, self.date_format);
+            self.log(LogEntry::info(format!("{} has passed; showing it in the calendar", shown)));
+            return;
+        }
+        self.radar_state.show_day((date - today).num_days());
+        self.jump_mark = Some((date, Instant::now() + JUMP_MARK_DURATION));
+    }
+
+    /// Day to ring on the radar, while a jump to it is recent
+    pub fn jump_mark(&self) -> Option<NaiveDate> {
+        self.jump_mark.filter(|(_, until)| *until > Instant::now()).map(|(date, _)| date)
+    }
+
+    /// Handle keys in the "Set API URL" prompt; a rejected URL stays open
+    /// with its error and leaves the connection alone
+    fn handle_url_prompt_key(&mut self, key: KeyEvent) -> Option<ApiCommand> {
+        let prompt = self.url_prompt.as_mut()?;
+        match key.code {
+            KeyCode::Enter => match api::validate_base_url(prompt.url.value()) {
+                Ok(url) => {
+                    self.url_prompt = None;
+                    return Some(self.set_base_url(url));
+                }
+                Err(e) => prompt.error = Some(e),
+            },
+            _ => {
+                if prompt.url.handle_key(key) {
+                    prompt.error = None;
+                }
+            }
+        }
+        None
+    }
+
+    /// Point the worker at `url` with the current token, outside any profile
+    fn set_base_url(&mut self, url: String) -> ApiCommand {
+        self.clear_server_data();
+        self.api_url = url.clone();
+        self.active_profile = None;
+        self.log(LogEntry::info(format!("Switching to {}", url)));
+        ApiCommand::SetBaseUrl {
+            url,
+            token: self.api_token.clone(),
+        }
+    }
+
+    /// Drop everything loaded from the current server before switching
+    fn clear_server_data(&mut self) {
+        self.projects.clear();
+        self.clients.clear();
+        self.users.clear();
+        self.current_user = None;
+        self.lookups.clear();
+        self.marked.clear();
+        self.batch_delete = None;
+        self.load_states.clear();
+        self.list_selected = 0;
+        self.radar_state.selected_index = None;
+        self.last_refresh = None;
+        self.is_loading = true;
+        self.paging = Paging::default();
+    }
+
+    /// Entities matching the search overlay's query
+    pub fn search_hits(&self) -> Vec<Hit> {
+        let query = self.search.as_ref().map_or("", |s| s.query.value());
+        // Users are only searched by those who may open their tab
+        let users: &[UserDto] = if Tab::visible(self.role()).contains(&Tab::Users) { &self.users } else { &[] };
+        search::search(query, &self.projects, &self.clients, users)
+    }
+
+    fn close_search(&mut self) {
+        self.search = None;
+        self.input_mode = InputMode::Normal;
+    }
+
+    /// Handle keys in the search overlay
+    fn handle_search_key(&mut self, key: KeyEvent) {
+        let hits = self.search_hits();
+        let Some(overlay) = self.search.as_mut() else {
+            self.input_mode = InputMode::Normal;
+            return;
+        };
+        match key.code {
+            KeyCode::Down => overlay.selected = (overlay.selected + 1).min(hits.len().saturating_sub(1)),
+            KeyCode::Up => overlay.selected = overlay.selected.saturating_sub(1),
+            KeyCode::Enter => {
+                if let Some(hit) = hits.get(overlay.selected) {
+                    let hit = hit.clone();
+                    self.close_search();
+                    self.go_to_hit(&hit);
+                }
+            }
+            _ => {
+                if overlay.query.handle_key(key) {
+                    overlay.selected = 0;
+                }
+            }
+        }
+    }
+
+    /// Show a search hit on its tab, selected and flashing; projects are
+    /// selected on the radar, clearing a filter that hides them
+    fn go_to_hit(&mut self, hit: &Hit) {
+        match hit.entity_type {
+            EntityType::Project => {
+                self.active_tab = Tab::Timeline;
+                self.calendar = None;
+                self.discard_date_edit();
+                if !self.visible_projects().contains(&hit.index) {
+                    self.set_project_filter(self.project_filter.cleared());
+                }
+                if !self.visible_projects().contains(&hit.index) {
+                    self.log(LogEntry::info(format!("{} is archived (H to show it)", hit.name)));
+                    return;
+                }
+                self.radar_state.selected_index = Some(hit.index);
+                self.radar_state.reveal(&self.projects[hit.index]);
+            }
+            EntityType::Client => {
+                self.active_tab = Tab::Clients;
+                self.list_selected = hit.index;
+            }
+            EntityType::User => {
+                self.active_tab = Tab::Users;
+                self.workload_cursor = None;
+                self.list_selected = hit.index;
+            }
+        }
+        self.flash = Some((hit.id, self.frame_count));
+    }
+
+    /// What the project filter needs to resolve names and statuses
+    pub fn filter_deps(&self) -> FilterDeps<'_> {
+        FilterDeps {
+            clients: &self.clients,
+            users: &self.users,
+            today: chrono::Local::now().date_naive(),
+        }
+    }
+
+    /// Indices of the projects that pass the filter
+    pub fn visible_projects(&self) -> Vec<usize> {
+        self.project_filter.apply(&self.projects, &self.filter_deps())
+    }
+
+    /// Replace the project filter, moving the selection off anything it hides
+    pub fn set_project_filter(&mut self, filter: ProjectFilter) {
+        self.project_filter = filter;
+        self.want_missing_names();
+        self.keep_selection_visible();
+        if self.project_filter.is_active() {
+            let summary = self.project_filter.describe(&self.filter_deps());
+            let shown = self.visible_projects().len();
+            let total = self.projects.len();
+            self.log(LogEntry::info(format!("Filter: {} ({} of {} projects)", summary, shown, total)));
+        } else {
+            self.log(LogEntry::info("Filter cleared"));
+        }
+    }
+
+    /// Keys in the preset picker, or in its name field while saving
+    fn handle_preset_picker_key(&mut self, key: KeyEvent) {
+        let Some(picker) = &mut self.preset_picker else {
+            return;
+        };
+        if let Some(name) = &mut picker.naming {
+            match key.code {
+                KeyCode::Enter => {
+                    let name = name.value().trim().to_string();
+                    if !name.is_empty() {
+                        picker.naming = None;
+                        self.save_preset(name);
+                    }
+                }
+                _ => {
+                    name.handle_key(key);
+                }
+            }
+            return;
+        }
+        let last = self.presets.len().saturating_sub(1);
+        match key.code {
+            KeyCode::Char('P') => self.preset_picker = None,
+            KeyCode::Char('j') | KeyCode::Down => picker.selected = (picker.selected + 1).min(last),
+            KeyCode::Char('k') | KeyCode::Up => picker.selected = picker.selected.saturating_sub(1),
+            KeyCode::Char('s') => picker.naming = Some(TextInput::default()),
+            KeyCode::Enter => {
+                let selected = picker.selected;
+                self.apply_preset(selected);
+            }
+            KeyCode::Char('d') => {
+                let selected = picker.selected;
+                self.delete_preset(selected);
+            }
+            _ => {}
+        }
+    }
+
+    /// Replace the filter's criteria with the preset's, keeping the layers
+    fn apply_preset(&mut self, index: usize) {
+        let Some(preset) = self.presets.presets.get(index) else {
+            return;
+        };
+        let name = preset.name.clone();
+        let (filter, skipped) = preset.apply(&self.project_filter, &self.filter_deps());
+        for clause in skipped {
+            self.log(LogEntry::warning(format!("Preset \"{}\": its {} no longer exists, skipped", name, clause)));
+        }
+        self.preset_picker = None;
+        self.set_project_filter(filter);
+    }
+
+    /// Save the current filter's criteria as `name`, replacing a preset of that name
+    fn save_preset(&mut self, name: String) {
+        self.presets.save(FilterPreset::new(name.as_str(), &self.project_filter));
+        if let Some(picker) = &mut self.preset_picker {
+            picker.selected = self.presets.presets.iter().position(|p| p.name == name).unwrap_or(0);
+        }
+        if self.write_presets() {
+            self.log(LogEntry::success(format!("Saved filter preset \"{}\"", name)));
+        }
+    }
+
+    fn delete_preset(&mut self, index: usize) {
+        if index >= self.presets.len() {
+            return;
+        }
+        let preset = self.presets.presets.remove(index);
+        if let Some(picker) = &mut self.preset_picker {
+            picker.selected = picker.selected.min(self.presets.len().saturating_sub(1));
+        }
+        if self.write_presets() {
+            self.log(LogEntry::info(format!("Deleted filter preset \"{}\"", preset.name)));
+        }
+    }
+
+    /// Write the presets file, if there is one; false after showing why it failed
+    fn write_presets(&mut self) -> bool {
+        let Some(path) = &self.presets_path else {
+            return true;
+        };
+        match self.presets.save_to(path) {
+            Ok(()) => true,
+            Err(e) => {
+                self.show_error("Presets Not Saved", format!("{:#}", e));
+                false
+            }
+        }
+    }
+
+    /// Open the selected project's change history and ask the server for it
+    fn open_history(&mut self) -> Option<ApiCommand> {
+        let (project_id, name) = self.selected_item()?;
+        if self.history_unsupported {
+            self.toast(LogLevel::Warning, "History is not supported by this server");
+            return None;
+        }
+        self.history = Some(HistoryPopup { project_id, name, records: None, scroll: PopupScroll::default() });
+        Some(ApiCommand::FetchHistory(project_id))
+    }
+
+    /// A 404 means the server has no audit log: say so once and stop asking
+    fn history_failed(&mut self, id: Uuid, error: ApiError) {
+        if error.status() == Some(404) {
+            self.history = None;
+            if !self.history_unsupported {
+                self.history_unsupported = true;
+                self.log(LogEntry::warning("History not supported by this server"));
+            }
+            return;
+        }
+        self.log(LogEntry::error(format!("{} failed: {}", ApiOperation::History(id), error)));
+        if let Some(popup) = self.history.as_mut().filter(|p| p.project_id == id) {
+            popup.records = Some(Err(error.summary()));
+        }
+    }
+
+    /// Show or hide projects completed before the archive cutoff
+    fn toggle_archived(&mut self) {
+        let days = match self.project_filter.archive_after_days {
+            Some(_) => None,
+            None if self.archive_after_days == 0 => Some(DEFAULT_ARCHIVE_DAYS),
+            None => Some(self.archive_after_days),
+        };
+        self.project_filter.archive_after_days = days;
+        self.keep_selection_visible();
+        match days {
+            Some(days) => {
+                let hidden = self.project_filter.archived_hidden(&self.projects, &self.filter_deps());
+                self.log(LogEntry::info(format!(
+                    "Hiding {} projects completed more than {} days ago",
+                    format_count(hidden),
+                    days
+                )));
+            }
+            None => self.log(LogEntry::info("Showing archived projects")),
+        }
+    }
+
+    /// Projects for reports and snapshots: archived ones only while they're shown
+    fn exported_projects(&self) -> Vec<ProjectDto> {
+        let today = chrono::Local::now().date_naive();
+        let days = self.project_filter.archive_after_days.unwrap_or(0);
+        filter::without_archived(&self.projects, today, days)
+    }
+
+    /// Jump to the Timeline showing only the selected client's projects
+    fn show_client_projects(&mut self) {
+        let Some(client_id) = self.clients.get(self.list_selected).map(|c| c.id) else {
+            return;
+        };
+        self.set_project_filter(ProjectFilter {
+            client_id: Some(client_id),
+            ..self.project_filter.cleared()
+        });
+        self.active_tab = Tab::Timeline;
+
+        let visible = self.visible_projects();
+        self.radar_state.selected_index = visible.first().copied();
+        self.radar_state.fit_range(visible.iter().map(|&i| &self.projects[i]));
+    }
+
+    /// Select the first visible project if the selected one is filtered out
+    fn keep_selection_visible(&mut self) {
+        if self.project_filter == ProjectFilter::default() {
+            return;
+        }
+        let visible = self.visible_projects();
+        if self.radar_state.selected_index.is_none_or(|i| !visible.contains(&i)) {
+            self.radar_state.selected_index = visible.first().copied();
+        }
+    }
+
+    /// Warn when a client's server project counters disagree with the
+    /// projects loaded for it; only with both collections fully loaded
+    fn reconcile_client_counts(&mut self) {
+        let complete = |page: &PageInfo| page.page > 0 && !page.has_next;
+        if self.page_browsing || !complete(&self.paging.projects) || !complete(&self.paging.clients) {
+            return;
+        }
+        let mismatched: Vec<&ClientDto> = self
+            .clients
+            .iter()
+            .filter(|c| c.server_counts() != client_project_counts(&self.projects, c.id))
+            .collect();
+        let ids: Vec<Uuid> = mismatched.iter().map(|c| c.id).collect();
+        if ids == self.count_mismatches {
+            return;
+        }
+        if let Some(first) = mismatched.first() {
+            let (completed, total) = first.server_counts();
+            let (local_completed, local_total) = client_project_counts(&self.projects, first.id);
+            let others = match mismatched.len() {
+                1 => String::new(),
+                n => format!(" and {} more", n - 1),
+            };
+            let message = format!(
+                "Project counts differ from the server for {}{}: server {}/{}, loaded {}/{} (the list shows the loaded ones)",
+                first.display_name(),
+                others,
+                completed,
+                total,
+                local_completed,
+                local_total
+            );
+            self.log(LogEntry::warning(message));
+        }
+        self.count_mismatches = ids;
+    }
+
+    /// Badge and log what a reload changed since the previous load
+    fn show_changes(&mut self, diff: Diff) {
+        let until = Instant::now() + BADGE_DURATION;
+        for id in diff.added {
+            self.badges.insert(id, (Badge::New, until));
+        }
+        for change in diff.changed {
+            self.log(LogEntry::info(change.describe()));
+            self.badges.insert(change.id, (Badge::Changed, until));
+        }
+        for label in diff.removed {
+            self.log(LogEntry::warning(format!("{} was removed", label)));
+        }
+    }
+
+    /// Badge shown next to an entity, until it expires
+    pub fn badge(&self, id: Uuid) -> Option<Badge> {
+        self.badges
+            .get(&id)
+            .filter(|(_, until)| *until > Instant::now())
+            .map(|(badge, _)| *badge)
+    }
+
+    /// Every badge still showing
+    pub fn active_badges(&self) -> Vec<(Uuid, Badge)> {
+        let now = Instant::now();
+        self.badges
+            .iter()
+            .filter(|(_, (_, until))| *until > now)
+            .map(|(id, (badge, _))| (*id, *badge))
+            .collect()
+    }
+
+    /// Select and highlight a just-created entity once a reload has brought
+    /// it in; until then the focus stays pending
+    fn resolve_pending_focus(&mut self) {
+        let Some((entity_type, id)) = self.pending_focus else {
+            return;
+        };
+        let index = match entity_type {
+            EntityType::Project => self.projects.iter().position(|p| p.id == id),
+            EntityType::Client => self.clients.iter().position(|c| c.id == id),
+            EntityType::User => self.users.iter().position(|u| u.id == id),
+        };
+        let Some(index) = index else {
+            return;
+        };
+        self.pending_focus = None;
+
+        match entity_type {
+            EntityType::Project => {
+                if !self.visible_projects().contains(&index) {
+                    self.log(LogEntry::info("The new project is hidden by the filter (Esc to clear)"));
+                    return;
+                }
+                self.radar_state.selected_index = Some(index);
+                self.radar_state.reveal(&self.projects[index]);
+            }
+            // The list selection belongs to whichever list tab is showing
+            EntityType::Client if self.active_tab == Tab::Clients => self.list_selected = index,
+            EntityType::User if self.active_tab == Tab::Users => self.list_selected = index,
+            EntityType::Client | EntityType::User => {}
+        }
+        self.flash = Some((id, self.frame_count));
+    }
+
+    /// Entity drawn highlighted this frame: a pulse for `FLASH_FRAMES` after
+    /// it was created, held steady when animations are off
+    pub fn flashing(&self) -> Option<Uuid> {
+        let (id, since) = self.flash?;
+        let age = self.frame_count.saturating_sub(since);
+        let steady = !self.animations || self.reduced_motion;
+        (age < FLASH_FRAMES && (steady || (age / FLASH_PULSE_FRAMES).is_multiple_of(2))).then_some(id)
+    }
+
+    /// Handle keys in the filter popup
+    fn handle_filter_popup_key(&mut self, key: KeyEvent) {
+        let Some(popup) = self.filter_popup.as_mut() else {
+            return;
+        };
+        let on_text = popup.row == FilterPopup::TEXT_ROW;
+        match key.code {
+            KeyCode::Enter => {
+                let draft = std::mem::take(&mut popup.draft);
+                self.filter_popup = None;
+                self.set_project_filter(draft);
+            }
+            KeyCode::Delete => {
+                self.filter_popup = None;
+                self.set_project_filter(self.project_filter.cleared());
+            }
+            KeyCode::Down | KeyCode::Tab => popup.row = (popup.row + 1) % FilterPopup::ROWS.len(),
+            KeyCode::Up | KeyCode::BackTab => {
+                popup.row = (popup.row + FilterPopup::ROWS.len() - 1) % FilterPopup::ROWS.len();
+            }
+            KeyCode::Left | KeyCode::Right if !on_text => {
+                let forward = key.code == KeyCode::Right;
+                let draft = &mut popup.draft;
+                match popup.row {
+                    0 => draft.status = cycle(&ProjectStatus::ALL, draft.status, forward),
+                    1 => {
+                        let ids: Vec<Uuid> = self.clients.iter().map(|c| c.id).collect();
+                        draft.client_id = cycle(&ids, draft.client_id, forward);
+                    }
+                    _ => {
+                        let ids: Vec<Uuid> = self.users.iter().map(|u| u.id).collect();
+                        draft.manager_id = cycle(&ids, draft.manager_id, forward);
+                    }
+                }
+            }
+            _ if on_text && popup.text.handle_key(key) => {
+                popup.draft.text = Some(popup.text.value().to_string()).filter(|t| !t.is_empty());
+            }
+            KeyCode::Backspace => match popup.row {
+                0 => popup.draft.status = None,
+                1 => popup.draft.client_id = None,
+                _ => popup.draft.manager_id = None,
+            },
+            _ => {}
+        }
+    }
+
+    /// Dry-run an import file for the active tab and ask before creating anything
+    pub fn plan_import(&mut self, path: &str) {
+        let entity_type = self.active_entity_type();
+        let plan = match import::plan_file(path.as_ref(), entity_type, &self.clients, &self.users) {
+            Ok(plan) => plan,
+            Err(e) => {
+                self.show_error("Import Failed", format!("{:#}", e));
+                return;
+            }
+        };
+
+        self.log(LogEntry::info(format!("Import dry run: {}", plan.summary())));
+        for (line, reason) in &plan.invalid {
+            self.log(LogEntry::warning(format!("Import line {}: {}", line, reason)));
+        }
+        if let Err(e) = import::ensure_clean(&plan) {
+            self.show_error("Import Rejected", e.to_string());
+            return;
+        }
+
+        let count = plan.valid.len();
+        self.ask(ConfirmDialog {
+            title: format!("Import {}s", entity_type),
+            message: format!("{}\nCreate {} {}s now?", plan.summary(), count, entity_type),
+            action: ConfirmAction::Import(plan.valid),
+            focus: ConfirmButton::No,
+            scroll: PopupScroll::default(),
+        });
+    }
+
+    /// Open the profile switcher with the active profile selected
+    pub fn open_profile_switcher(&mut self) {
+        if self.profiles.is_empty() {
+            let location = crate::config::Config::path()
+                .map(|p| p.display().to_string())
+                .unwrap_or_else(|| "the config file".to_string());
+            self.log(LogEntry::warning(format!("No profiles configured in {}", location)));
+            return;
+        }
+
+        let selected = self
+            .active_profile
+            .as_ref()
+            .and_then(|active| self.profiles.iter().position(|(name, _)| name == active))
+            .unwrap_or(0);
+        self.profile_switcher = Some(selected);
+    }
+
+    /// Switch to the profile at `index`, dropping data loaded from the old server
+    fn switch_profile(&mut self, index: usize) -> Option<ApiCommand> {
+        let (name, profile) = self.profiles.get(index)?.clone();
+
+        self.clear_server_data();
+        self.api_url = profile.url.clone();
+        self.api_token = profile.token.clone();
+        self.active_profile = Some(name.clone());
+        if let Some(setup) = &mut self.setup {
+            setup.url.set_value(profile.url.as_str());
+            setup.error = None;
+            setup.retrying = true;
+        }
+
+        self.log(LogEntry::info(format!("Switching to profile {} ({})", name, profile.url)));
+        Some(ApiCommand::SetBaseUrl {
+            url: profile.url,
+            token: profile.token,
+        })
+    }
+
+    /// Handle keys in the profile switcher
+    fn handle_profile_switcher_key(&mut self, key: KeyEvent) -> Option<ApiCommand> {
+        let selected = self.profile_switcher?;
+        let last = self.profiles.len().saturating_sub(1);
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down => {
+                self.profile_switcher = Some((selected + 1).min(last));
+            }
+            KeyCode::Char('k') | KeyCode::Up => {
+                self.profile_switcher = Some(selected.saturating_sub(1));
+            }
+            KeyCode::Enter => {
+                self.profile_switcher = None;
+                return self.switch_profile(selected);
+            }
+            _ => {}
+        }
+        None
+    }
+
+    /// Handle keys on the first-run setup screen
+    fn handle_setup_key(&mut self, key: KeyEvent) -> Option<ApiCommand> {
+        if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            self.should_quit = true;
+            return Some(ApiCommand::Shutdown);
+        }
+
+        let setup = self.setup.as_mut()?;
+        match key.code {
+            KeyCode::Tab | KeyCode::BackTab => {
+                setup.retry_focused = !setup.retry_focused;
+            }
+            KeyCode::Char('q') if setup.retry_focused => {
+                self.should_quit = true;
+                return Some(ApiCommand::Shutdown);
+            }
+            KeyCode::Enter => {
+                if setup.retrying {
+                    return None;
+                }
+                match api::validate_base_url(setup.url.value()) {
+                    Ok(url) => {
+                        setup.url.set_value(url.as_str());
+                        setup.error = None;
+                        setup.retrying = true;
+                        self.api_url = url.clone();
+                        self.is_loading = true;
+                        self.log(LogEntry::info(format!("Connecting to {}...", url)));
+                        return Some(ApiCommand::SetBaseUrl {
+                            url,
+                            token: self.api_token.clone(),
+                        });
+                    }
+                    Err(e) => setup.error = Some(e),
+                }
+            }
+            _ if !setup.retry_focused => {
+                setup.url.handle_key(key);
+            }
+            _ => {}
+        }
+        None
+    }
+
+    /// Handle keys in normal mode
+    fn handle_normal_key(&mut self, key: KeyEvent) -> Option<ApiCommand> {
+        // An unsaved date shift is saved (Esc drops it) before anything else
+        if self.active_tab == Tab::Timeline && self.radar_state.pending_edit.is_some() && key.code == KeyCode::Enter {
+            return self.commit_date_edit();
+        }
+
+        // Global shortcuts
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Char('Q') => {
+                if self.in_flight > 0 {
+                    self.ask(ConfirmDialog::new_quit(self.in_flight));
+                    return None;
+                }
+                self.should_quit = true;
+                return Some(ApiCommand::Shutdown);
+            }
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.should_quit = true;
+                return Some(ApiCommand::Shutdown);
+            }
+            KeyCode::Char('w') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                let order = [Pane::Primary, Pane::Secondary, Pane::Agenda];
+                let at = order.iter().position(|p| *p == self.focused_pane()).unwrap_or(0);
+                let next = (1..order.len())
+                    .map(|step| order[(at + step) % order.len()])
+                    .find(|p| self.has_pane(*p))
+                    .unwrap_or(Pane::Primary);
+                self.focus_pane(next);
+                return None;
+            }
+            KeyCode::Char('h') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.focus_pane(Pane::Primary);
+                return None;
+            }
+            KeyCode::Char('l') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.focus_pane(Pane::Secondary);
+                return None;
+            }
+            KeyCode::Char('x') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.reset_view();
+                return None;
+            }
+            KeyCode::Char('?') => {
+                self.show_help = true;
+                self.help_scroll = PopupScroll::default();
+                return None;
+            }
+            KeyCode::Char('p') => {
+                if !self.animations {
+                    self.log(LogEntry::info("Animations are off (A to turn them on)"));
+                    return None;
+                }
+                self.particle_system.toggle_mode();
+                let mode = self.particle_system.mode().name();
+                self.log(LogEntry::info(format!("Particle mode: {}", mode)));
+                return None;
+            }
+            KeyCode::Char('A') => {
+                self.set_animations(!self.animations);
+                let state = if self.animations { "on" } else { "off" };
+                self.log(LogEntry::info(format!("Animations {}", state)));
+                return None;
+            }
+            KeyCode::Char('I') => {
+                if !self.blocked_by_read_only("import") && !self.blocked_by_role(Role::can_create_clients, "create") {
+                    self.import_prompt = Some(TextInput::default());
+                }
+                return None;
+            }
+            KeyCode::Char('U') => {
+                self.url_prompt = Some(UrlPrompt { url: TextInput::new(self.api_url.as_str()), error: None });
+                return None;
+            }
+            KeyCode::Char('M') => {
+                let entity_type = self.active_entity_type();
+                return self.load_more(entity_type, true);
+            }
+            KeyCode::PageDown if self.browsing_projects() => {
+                return self.browse_page(1);
+            }
+            KeyCode::PageUp if self.browsing_projects() => {
+                return self.browse_page(-1);
+            }
+            KeyCode::Char('n') => {
+                if !self.blocked_by_read_only("rename") {
+                    self.start_rename();
+                }
+                return None;
+            }
+            KeyCode::Char('R') => {
+                self.export_report();
+                return None;
+            }
+            KeyCode::Char('H') => {
+                self.toggle_archived();
+                return None;
+            }
+            KeyCode::Char('~') => {
+                self.toggle_mine();
+                return None;
+            }
+            KeyCode::Char('i') if self.active_tab == Tab::Timeline => {
+                return self.open_history();
+            }
+            KeyCode::Char('S') => {
+                self.export_snapshot();
+                return None;
+            }
+            KeyCode::Char('L') => {
+                self.export_log();
+                return None;
+            }
+            KeyCode::Char('v') => {
+                self.log_expanded = !self.log_expanded;
+                return None;
+            }
+            KeyCode::Char(' ') => {
+                self.toggle_mark();
+                return None;
+            }
+            KeyCode::Char('r') => {
+                let now = Instant::now();
+                if !refresh_allowed(self.refresh_requested_at, self.paging.any_loading(), now) {
+                    self.log(LogEntry::info("Refresh already in progress"));
+                    return None;
+                }
+                self.log(LogEntry::info("Refreshing data..."));
+                return Some(self.begin_refresh(now));
+            }
+            KeyCode::Tab => {
+                self.active_tab = self.active_tab.next(self.role());
+                self.list_selected = 0;
+                return None;
+            }
+            KeyCode::BackTab => {
+                self.active_tab = self.active_tab.previous(self.role());
+                self.list_selected = 0;
+                return None;
+            }
+            // CRUD shortcuts
+            KeyCode::Char('c') => {
+                if !self.blocked_by_read_only("create") && !self.blocked_by_role(Role::can_create_clients, "create") {
+                    self.open_create_form();
+                }
+                return None;
+            }
+            KeyCode::Char('e') => {
+                if self.blocked_by_read_only("edit") {
+                    return None;
+                }
+                return self.request_edit();
+            }
+            KeyCode::Char('d') | KeyCode::Delete => {
+                if !self.blocked_by_read_only("delete") && !self.blocked_by_role(Role::can_delete_clients, "delete") {
+                    self.open_delete_confirm();
+                }
+                return None;
+            }
+            KeyCode::Char('x') if self.active_tab == Tab::Timeline => {
+                if self.blocked_by_read_only("complete") {
+                    return None;
+                }
+                return self.quick_complete();
+            }
+            _ => {}
+        }
+
+        // Tab-specific shortcuts
+        match self.active_tab {
+            Tab::Timeline if self.calendar.is_some() => self.handle_calendar_key(key),
+            Tab::Timeline if self.focused_pane() == Pane::Secondary => self.handle_upcoming_key(key),
+            Tab::Timeline if self.focused_pane() == Pane::Agenda => self.handle_agenda_key(key),
+            Tab::Timeline if key.code == KeyCode::Enter => return self.timeline_enter(),
+            Tab::Timeline => self.handle_timeline_key(key),
+            Tab::Clients if self.focused_pane() == Pane::Secondary => self.handle_client_project_key(key),
+            Tab::Clients if key.code == KeyCode::Enter => self.show_client_projects(),
+            Tab::Clients => self.handle_list_key(key, self.clients.len()),
+            Tab::Users if self.workload_cursor.is_some() => self.handle_workload_key(key),
+            Tab::Users if key.code == KeyCode::Char('w') => self.workload_cursor = Some((0, 0)),
+            Tab::Users if key.code == KeyCode::Char('b') => self.users_grouped = !self.users_grouped,
+            Tab::Users if self.users_grouped => self.handle_grouped_users_key(key),
+            Tab::Users => self.handle_list_key(key, self.users.len()),
+        }
+
+        self.auto_load_more()
+    }
+
+    /// Mark the selected project as finished today
+    fn quick_complete(&mut self) -> Option<ApiCommand> {
+        let project = self.radar_state.selected_index.and_then(|i| self.projects.get(i))?;
+        if project.is_completed() {
+            self.log(LogEntry::info(format!("{} is already complete", project.display_name())));
+            return None;
+        }
+
+        let mut dto = UpdateProjectDto::from_project(project);
+        dto.actual_end_date = Some(chrono::Local::now().date_naive());
+        let id = project.id;
+        self.log_intent(format!("Completing {}...", project.display_name()));
+        self.completing = Some(id);
+        Some(ApiCommand::UpdateProject(id, dto))
+    }
+
+    /// Preview moving the selected project's deadline by a day (a week with
+    /// Shift); with Ctrl the start moves along with it
+    fn shift_dates(&mut self, key: KeyEvent) {
+        if self.blocked_by_read_only("edit") {
+            return;
+        }
+        let Some(project) = self.radar_state.selected_index.and_then(|i| self.projects.get(i)) else {
+            return;
+        };
+        if !project.has_valid_dates() {
+            let message = format!("{} has no dates to shift", project.display_name());
+            self.log(LogEntry::warning(message));
+            return;
+        }
+
+        let direction = if key.code == KeyCode::Left { -1 } else { 1 };
+        let days = if key.modifiers.contains(KeyModifiers::SHIFT) {
+            self.radar_state.held = None;
+            direction * 7
+        } else {
+            self.radar_state.repeat_step(direction, Instant::now())
+        };
+        let whole = key.modifiers.contains(KeyModifiers::CONTROL);
+        let mut edit = self
+            .radar_state
+            .pending_edit
+            .filter(|e| e.project_id == project.id)
+            .unwrap_or_else(|| PendingDateEdit::new(project));
+        if edit.shift(days, whole) {
+            self.radar_state.pending_edit = Some(edit);
+        } else {
+            self.log(LogEntry::warning("The deadline can't move before the start date"));
+        }
+    }
+
+    /// Save the previewed date shift
+    fn commit_date_edit(&mut self) -> Option<ApiCommand> {
+        let edit = self.radar_state.pending_edit.take()?;
+        let project = self.projects.iter().find(|p| p.id == edit.project_id)?;
+        let days = (edit.planned_end_date - project.planned_end_date).num_days();
+        if days == 0 && edit.start_date == project.start_date {
+            self.log(LogEntry::info("No date change to save"));
+            return None;
+        }
+
+        let summary = if edit.start_date != project.start_date {
+            format!(
+                "{}: moved {:+}d to {} – {}",
+                project.display_name(),
+                days,
+                format_date(&edit.start_date, self.date_format),
+                format_date(&edit.planned_end_date, self.date_format)
+            )
+        } else {
+            format!(
+                "{}: deadline {} → {} ({:+}d)",
+                project.display_name(),
+                format_date(&project.planned_end_date, self.date_format),
+                format_date(&edit.planned_end_date, self.date_format),
+                days
+            )
+        };
+        let dto = UpdateProjectDto::from_project(&edit.apply(project));
+        self.log_intent(summary);
+        Some(ApiCommand::UpdateProject(edit.project_id, dto))
+    }
+
+    /// Drop an unsaved date shift, if there is one
+    fn discard_date_edit(&mut self) {
+        if self.radar_state.pending_edit.take().is_some() {
+            self.log(LogEntry::info("Date change discarded"));
+        }
+    }
+
+    /// Start a confetti burst over a project's radar blip
+    fn celebrate(&mut self, id: Uuid) {
+        if self.reduced_motion || !self.animations {
+            return;
+        }
+        if let Some(project) = self.projects.iter().find(|p| p.id == id) {
+            let origin = radar::blip_position(project, self.radar_state.range_days);
+            self.celebration = Some(CelebrationState::new(origin, self.frame_count));
+        }
+    }
+
+    /// Refresh commands for the cache entries made stale since the last frame.
+    /// Single changes reload just that entity; a collection reload covers every
+    /// single change in it. Projects reload along with clients or users, since
+    /// deleting those can cascade.
+    pub fn refresh_after_mutations(&mut self, stale: &[Stale]) -> Vec<ApiCommand> {
+        let reload = |entity_type| stale.contains(&Stale::All(entity_type));
+        let reload_projects = reload(EntityType::Project) || reload(EntityType::Client) || reload(EntityType::User);
+
+        let mut commands = Vec::new();
+        if reload_projects {
+            commands.push(ApiCommand::RefreshProjects);
+            self.paging.projects.loading = true;
+        }
+        if reload(EntityType::Client) {
+            commands.push(ApiCommand::RefreshClients);
+            self.paging.clients.loading = true;
+        }
+        if reload(EntityType::User) {
+            commands.push(ApiCommand::RefreshUsers);
+            self.paging.users.loading = true;
+        }
+        for entry in stale {
+            let Stale::One(entity_type, id) = *entry else {
+                continue;
+            };
+            let covered = reload(entity_type) || (entity_type == EntityType::Project && reload_projects);
+            let command = ApiCommand::RefreshOne(entity_type, id);
+            if !covered && !commands.contains(&command) {
+                commands.push(command);
+            }
+        }
+        commands
+    }
+
+    /// Name of client `id`: from the loaded list, else "loading…" while it is
+    /// fetched, or "deleted (ab12cd…)" once the server says it's gone
+    pub fn resolve_client_name(&self, id: Uuid) -> String {
+        let client = self.clients.iter().find(|c| c.id == id);
+        self.resolve(EntityType::Client, id, client.map(|c| c.display_name()))
+    }
+
+    /// Name of user `id`, like `resolve_client_name`
+    pub fn resolve_user_name(&self, id: Uuid) -> String {
+        let user = self.users.iter().find(|u| u.id == id);
+        self.resolve(EntityType::User, id, user.map(|u| u.display_name()))
+    }
+
+    fn resolve(&self, entity_type: EntityType, id: Uuid, loaded: Option<&str>) -> String {
+        if let Some(name) = loaded {
+            return name.to_string();
+        }
+        if id.is_nil() {
+            return "none".to_string();
+        }
+        // Not recorded yet: the list that may hold it hasn't arrived
+        match self.lookups.get(&(entity_type, id)).copied().unwrap_or(Lookup::Wanted) {
+            Lookup::Wanted | Lookup::Loading => LOADING_NAME.to_string(),
+            Lookup::Missing => format!("deleted ({})", short_id(id)),
+            Lookup::Failed => format!("unknown ({})", short_id(id)),
+        }
+    }
+
+    /// Record the clients and managers that projects or the filter point at
+    /// but no loaded list holds; a list that hasn't arrived yet is waited for
+    fn want_missing_names(&mut self) {
+        let mut wanted = Vec::new();
+        if *self.load_state(EntityType::Client) != LoadState::NotLoaded {
+            let loaded: HashSet<Uuid> = self.clients.iter().map(|c| c.id).collect();
+            let referenced = self.projects.iter().map(|p| p.client_id).chain(self.project_filter.client_id);
+            wanted.extend(referenced.filter(|id| !loaded.contains(id)).map(|id| (EntityType::Client, id)));
+        }
+        if *self.load_state(EntityType::User) != LoadState::NotLoaded {
+            let loaded: HashSet<Uuid> = self.users.iter().map(|u| u.id).collect();
+            let referenced = self.projects.iter().map(|p| p.manager_id).chain(self.project_filter.manager_id);
+            wanted.extend(referenced.filter(|id| !loaded.contains(id)).map(|id| (EntityType::User, id)));
+        }
+        for key in wanted.into_iter().filter(|(_, id)| !id.is_nil()) {
+            self.lookups.entry(key).or_insert(Lookup::Wanted);
+        }
+    }
+
+    /// One-shot reloads for the names recorded since the last call
+    pub fn lookup_fetches(&mut self) -> Vec<ApiCommand> {
+        let mut commands = Vec::new();
+        for (&(entity_type, id), lookup) in self.lookups.iter_mut() {
+            if *lookup == Lookup::Wanted {
+                *lookup = Lookup::Loading;
+                commands.push(ApiCommand::RefreshOne(entity_type, id));
+            }
+        }
+        commands
+    }
+
+    /// A fresh list may hold what a failed lookup was after
+    fn forget_failed_lookups(&mut self, entity_type: EntityType) {
+        self.lookups.retain(|&(t, _), lookup| t != entity_type || *lookup != Lookup::Failed);
+    }
+
+    /// In lazy mode, fetch the next page when the view nears the end of the loaded data
+    fn auto_load_more(&mut self) -> Option<ApiCommand> {
+        if !self.lazy_loading || self.browsing_projects() {
+            return None;
+        }
+
+        let near_end = |selected: usize, len: usize| selected + LOAD_AHEAD >= len;
+        let needed = match self.active_tab {
+            Tab::Clients => near_end(self.list_selected, self.clients.len()),
+            Tab::Users => near_end(self.list_selected, self.users.len()),
+            Tab::Timeline => {
+                let selected = self.radar_state.selected_index.unwrap_or(0);
+                // The radar shows deadlines up to `range_days` ahead; loaded data must cover them
+                let horizon = chrono::Local::now().date_naive()
+                    + chrono::Duration::days(self.radar_state.range_days as i64);
+                let covered = self
+                    .projects
+                    .iter()
+                    .filter(|p| p.has_valid_dates())
+                    .map(|p| p.planned_end_date)
+                    .max();
+                near_end(selected, self.projects.len()) || covered.is_none_or(|d| d < horizon)
+            }
+        };
+
+        if needed {
+            self.load_more(self.active_entity_type(), false)
+        } else {
+            None
+        }
+    }
+
+    /// Request the next page of an entity collection
+    pub fn load_more(&mut self, entity_type: EntityType, explicit: bool) -> Option<ApiCommand> {
+        if self.page_browsing && entity_type == EntityType::Project {
+            return self.browse_page(1);
+        }
+        let info = self.paging.get(entity_type);
+        if info.loading {
+            return None;
+        }
+        if !info.has_next {
+            if explicit {
+                self.log(LogEntry::info(format!("All {}s are loaded", entity_type)));
+            }
+            return None;
+        }
+
+        let next = info.page + 1;
+        self.paging.get_mut(entity_type).loading = true;
+        self.log(LogEntry::info(format!("Loading more {}s (page {})...", entity_type, next)));
+        Some(ApiCommand::FetchPage(entity_type, next, self.page_size))
+    }
+
+    /// Whether the Timeline is showing one browsed page of projects
+    fn browsing_projects(&self) -> bool {
+        self.page_browsing && self.active_tab == Tab::Timeline
+    }
+
+    /// Replace the browsed project page with the one `delta` pages away
+    fn browse_page(&mut self, delta: i32) -> Option<ApiCommand> {
+        let info = self.paging.projects;
+        if info.loading {
+            return None;
+        }
+        let page = info.page.max(1) + delta;
+        if page < 1 {
+            self.log(LogEntry::info("Already on the first page"));
+            return None;
+        }
+        if page > info.total_pages.max(1) {
+            self.log(LogEntry::info("Already on the last page"));
+            return None;
+        }
+
+        self.paging.projects.loading = true;
+        self.log(LogEntry::info(format!("Loading project page {}...", page)));
+        Some(ApiCommand::FetchPage(EntityType::Project, page, self.page_size))
+    }
+
+    /// "page 3/128 · 100 per page" while browsing project pages
+    pub fn project_page_label(&self) -> Option<String> {
+        if !self.page_browsing {
+            return None;
+        }
+        let info = &self.paging.projects;
+        Some(format!("page {}/{} · {} per page", info.page.max(1), info.total_pages.max(1), self.page_size))
+    }
+
+    /// Log progress after a lazily loaded page
+    fn toast_loaded(&mut self, entity_type: EntityType, count: usize) {
+        let noun = entity_type.to_string().to_lowercase();
+        let plural = if count == 1 { "" } else { "s" };
+        self.toast(LogLevel::Info, format!("{} {}{} loaded", format_count(count), noun, plural));
+    }
+
+    /// Clean the entities in `message` before they are stored (see
+    /// `sanitize`), warning about what was fixed or dropped; `None` when a
+    /// single entity had to be dropped
+    fn sanitize_message(&mut self, mut message: ApiMessage) -> Option<ApiMessage> {
+        match &mut message {
+            ApiMessage::ProjectsLoaded(all) => {
+                let dropped = self.sanitize_items(
+                    EntityType::Project,
+                    &mut all.items,
+                    sanitize_project,
+                    ProjectDto::display_name,
+                );
+                // Dropped items aren't missing from the load
+                all.total_count = all.total_count.map(|total| total.saturating_sub(dropped));
+            }
+            ApiMessage::ClientsLoaded(all) => {
+                let dropped = self.sanitize_items(
+                    EntityType::Client,
+                    &mut all.items,
+                    sanitize_client,
+                    ClientDto::display_name,
+                );
+                all.total_count = all.total_count.map(|total| total.saturating_sub(dropped));
+            }
+            ApiMessage::UsersLoaded(all) => {
+                let dropped = self.sanitize_items(
+                    EntityType::User,
+                    &mut all.items,
+                    sanitize_user,
+                    UserDto::display_name,
+                );
+                all.total_count = all.total_count.map(|total| total.saturating_sub(dropped));
+            }
+            ApiMessage::ProjectsPage(page) => {
+                if let Some(items) = page.items.as_mut() {
+                    let dropped =
+                        self.sanitize_items(EntityType::Project, items, sanitize_project, ProjectDto::display_name);
+                    page.total_count = (page.total_count - dropped as i32).max(0);
+                }
+            }
+            ApiMessage::ClientsPage(page) => {
+                if let Some(items) = page.items.as_mut() {
+                    let dropped =
+                        self.sanitize_items(EntityType::Client, items, sanitize_client, ClientDto::display_name);
+                    page.total_count = (page.total_count - dropped as i32).max(0);
+                }
+            }
+            ApiMessage::UsersPage(page) => {
+                if let Some(items) = page.items.as_mut() {
+                    let dropped =
+                        self.sanitize_items(EntityType::User, items, sanitize_user, UserDto::display_name);
+                    page.total_count = (page.total_count - dropped as i32).max(0);
+                }
+            }
+            ApiMessage::FetchedOne(entity) | ApiMessage::OneLoaded(entity) => {
+                let entity_type = entity.entity_type();
+                let (outcome, name) = match entity {
+                    FetchedEntity::Project(p) => (sanitize_project(p), p.display_name().to_string()),
+                    FetchedEntity::Client(c) => (sanitize_client(c), c.display_name().to_string()),
+                    FetchedEntity::User(u) => (sanitize_user(u), u.display_name().to_string()),
+                };
+                match outcome {
+                    Outcome::Clean => {}
+                    Outcome::Fixed(fixes) => {
+                        self.log(LogEntry::warning(format!("{} \"{}\": {}", entity_type, name, fixes.join(", "))));
+                    }
+                    Outcome::Dropped => {
+                        let noun = entity_type.to_string().to_lowercase();
+                        self.log(LogEntry::warning(format!("Ignored a {} with a nil id", noun)));
+                        return None;
+                    }
+                }
+            }
+            _ => {}
+        }
+        Some(message)
+    }
+
+    /// Sanitize loaded `items` in place, dropping those with a nil id;
+    /// returns how many were dropped
+    fn sanitize_items<T>(
+        &mut self,
+        entity_type: EntityType,
+        items: &mut Vec<T>,
+        sanitize: fn(&mut T) -> Outcome,
+        name: fn(&T) -> &str,
+    ) -> usize {
+        let mut warnings = Vec::new();
+        let mut dropped = 0;
+        items.retain_mut(|item| match sanitize(item) {
+            Outcome::Clean => true,
+            Outcome::Fixed(fixes) => {
+                warnings.push(format!("{} \"{}\": {}", entity_type, name(item), fixes.join(", ")));
+                true
+            }
+            Outcome::Dropped => {
+                dropped += 1;
+                false
+            }
+        });
+        if dropped > 0 {
+            let noun = entity_type.to_string().to_lowercase();
+            let plural = if dropped == 1 { "" } else { "s" };
+            warnings.push(format!("Dropped {} {}{} with a nil id", dropped, noun, plural));
+        }
+        for warning in warnings {
+            self.log(LogEntry::warning(warning));
+        }
+        dropped
+    }
+
+    /// Log and toast a full load, warning when it came up short of the
+    /// server's total; a load stopped by a failed or contradictory page is
+    /// reported by the worker's warning that follows
+    fn log_loaded(&mut self, entity_type: EntityType, count: usize, failed: bool) {
+        let info = *self.paging.get(entity_type);
+        let noun = format!("{}s", entity_type).to_lowercase();
+        if info.partial && !failed {
+            self.log(LogEntry::warning(format!(
+                "Loaded {} of {} {}; the server reports more",
+                format_count(count),
+                format_count(info.total_count),
+                noun
+            )));
+        } else if !failed {
+            self.log(LogEntry::success(format!("Loaded {} {}", count, noun)));
+        }
+        self.toast_loaded(entity_type, count);
+    }
+
+    /// How the latest load of a collection went
+    pub fn load_state(&self, entity_type: EntityType) -> &LoadState {
+        self.load_states.get(&entity_type).unwrap_or(&LoadState::NotLoaded)
+    }
+
+    /// Record a successful load, counting what the collection now holds
+    fn mark_loaded(&mut self, entity_type: EntityType) {
+        let count = match entity_type {
+            EntityType::Project => self.projects.len(),
+            EntityType::Client => self.clients.len(),
+            EntityType::User => self.users.len(),
+        };
+        self.load_states.insert(entity_type, LoadState::Loaded(count));
+    }
+
+    /// Record a failed collection load; an unreachable server fails all three
+    fn mark_load_failed(&mut self, operation: ApiOperation, error: &ApiError) {
+        let failed = match operation {
+            ApiOperation::Load(entity_type) => vec![entity_type],
+            ApiOperation::Connect => vec![EntityType::Project, EntityType::Client, EntityType::User],
+            _ => return,
+        };
+        for entity_type in failed {
+            self.load_states.insert(entity_type, LoadState::Failed(error.summary()));
+        }
+    }
+
+    fn log_page_loaded(&mut self, entity_type: EntityType, loaded: usize) {
+        let total = self.paging.get(entity_type).total_count;
+        self.log(LogEntry::success(format!(
+            "Loaded {} of {} {}s",
+            format_count(loaded),
+            format_count(total),
+            entity_type
+        )));
+    }
+
+    /// Handle keys in editing mode (form)
+    fn handle_editing_key(&mut self, key: KeyEvent) -> Option<ApiCommand> {
+        if self.form_state.is_none() {
+            self.input_mode = InputMode::Normal;
+            return None;
+        }
+
+        // One save at a time: only Esc (closing the form) gets through until
+        // the server answers
+        if self.form_state.as_ref().is_some_and(|f| f.nav().submitting) {
+            return None;
+        }
+
+        if key.code == KeyCode::Char('r') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            return self.reload_stale_form();
+        }
+        if key.code == KeyCode::Char('g') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            self.generate_password();
+            return None;
+        }
+
+        if self.form_state.as_mut().is_some_and(|form| form.handle_text_key(key)) {
+            return None;
+        }
+
+        match key.code {
+            KeyCode::Tab => {
+                // A suggested project name is taken first; the next Tab moves on
+                let suggestion = self.name_suggestion();
+                match (&mut self.form_state, suggestion) {
+                    (Some(FormState::Project(form)), Some(rest)) => {
+                        let name = format!("{}{}", form.name.value(), rest);
+                        form.name.set_value(name);
+                    }
+                    (Some(form), _) => form.next_field(),
+                    (None, _) => {}
+                }
+                return None;
+            }
+            KeyCode::BackTab => {
+                if let Some(form) = &mut self.form_state {
+                    form.prev_field();
+                }
+                return None;
+            }
+            KeyCode::Enter => {
+                let command = self.handle_form_submit();
+                if let (Some(_), Some(form)) = (&command, &mut self.form_state) {
+                    form.nav_mut().submitting = true;
+                }
+                return command;
+            }
+            KeyCode::Up => {
+                match &mut self.form_state {
+                    Some(FormState::Project(form)) => match form.current_field() {
+                        // Date picker: Up increases the date
+                        FormField::ProjectStartDate | FormField::ProjectEndDate => form.increment_date(),
+                        FormField::ProjectClient => form.client_idx = form.client_idx.saturating_sub(1),
+                        FormField::ProjectManager => form.manager_idx = form.manager_idx.saturating_sub(1),
+                        _ => {}
+                    },
+                    Some(FormState::User(form)) if form.current_field() == FormField::UserRole => {
+                        form.role = form.role.next();
+                    }
+                    _ => {}
+                }
+                return None;
+            }
+            KeyCode::Down => {
+                match &mut self.form_state {
+                    Some(FormState::Project(form)) => match form.current_field() {
+                        // Date picker: Down decreases the date
+                        FormField::ProjectStartDate | FormField::ProjectEndDate => form.decrement_date(),
+                        FormField::ProjectClient => {
+                            let last = self.clients.len().saturating_sub(1);
+                            form.client_idx = (form.client_idx + 1).min(last);
+                        }
+                        FormField::ProjectManager => {
+                            let last = self.users.len().saturating_sub(1);
+                            form.manager_idx = (form.manager_idx + 1).min(last);
+                        }
+                        _ => {}
+                    },
+                    Some(FormState::User(form)) if form.current_field() == FormField::UserRole => {
+                        form.role = form.role.next();
+                    }
+                    _ => {}
+                }
+                return None;
+            }
+            KeyCode::Left => {
+                // Date picker: Left goes back a week
+                if let Some(FormState::Project(form)) = &mut self.form_state {
+                    form.step_week(-1);
+                }
+                return None;
+            }
+            KeyCode::Right => {
+                // Date picker: Right goes forward a week
+                if let Some(FormState::Project(form)) = &mut self.form_state {
+                    form.step_week(1);
+                }
+                return None;
+            }
+            _ => {}
+        }
+
+        None
+    }
+
+    /// Rest of the project name being typed, as suggested from the chosen
+    /// client's projects; shown as ghost text and taken with Tab
+    pub fn name_suggestion(&self) -> Option<String> {
+        let Some(FormState::Project(form)) = &self.form_state else { return None };
+        if form.current_field() != FormField::ProjectName || !form.name.cursor_at_end() {
+            return None;
+        }
+        let client = self.clients.get(form.client_idx);
+        let projects: Vec<&ProjectDto> = self
+            .projects
+            .iter()
+            .filter(|p| client.is_some_and(|c| c.id == p.client_id) && Some(p.id) != form.editing)
+            .collect();
+        suggest::suggest(form.name.value(), client.map(|c| c.display_name()), &projects)
+    }
+
+    /// Fill the focused password field with a generated password, shown
+    /// until focus leaves it, and copy it for handing to the user (Ctrl+G)
+    fn generate_password(&mut self) {
+        let Some(FormState::User(form)) = &mut self.form_state else { return };
+        if form.current_field() != FormField::UserPassword {
+            return;
+        }
+        let password = passwords::generate(self.password_length, &mut rand::thread_rng());
+        form.password.set_value(password.as_str());
+        form.password.reveal();
+        self.clipboard = Some(password);
+        self.log(LogEntry::info(format!(
+            "Generated a {}-character password and copied it to the clipboard",
+            self.password_length
+        )));
+    }
+
+    /// Handle form submission
+    /// The first Save with soft warnings only points them out; the next one,
+    /// with the same warnings, goes ahead
+    fn warnings_confirmed(&mut self) -> bool {
+        let Some(form) = &mut self.form_state else {
+            return true;
+        };
+        let warnings = form.project_warnings(&self.clients, &self.users, &self.projects);
+        let Some(form) = (match form {
+            FormState::Project(form) => Some(form),
+            _ => None,
+        }) else {
+            return true;
+        };
+        if warnings.is_empty() || form.acknowledged == warnings {
+            return true;
+        }
+        form.acknowledged = warnings;
+        form.nav_mut().error = None;
+        false
+    }
+
+    fn handle_form_submit(&mut self) -> Option<ApiCommand> {
+        // Extract data we need from form before borrowing self mutably
+        let form = self.form_state.as_ref()?;
+
+        // Check if cancel button is focused
+        if form.current_field() == FormField::CancelButton {
+            self.close_form();
+            return None;
+        }
+
+        // On text input and date picker fields, Enter moves to next field instead of submitting
+        if form.current_field().is_text_input() || form.current_field().is_date_picker() {
+            if let Some(form) = &mut self.form_state {
+                form.next_field();
+            }
+            return None;
+        }
+
+        // Only submit if on submit button
+        if form.current_field() != FormField::SubmitButton {
+            return None;
+        }
+
+        let lowercase = self.lowercase_logins;
+        let built = match form {
+            FormState::Client(form) => match form.editing {
+                None => {
+                    let dto = form.build_create();
+                    dto.validate().map(|_| (ApiCommand::CreateClient(dto), "Creating client..."))
+                }
+                Some(id) => match self.clients.iter().find(|c| c.id == id) {
+                    Some(client) => {
+                        let dto = form.build_update(client);
+                        dto.validate().map(|_| (ApiCommand::UpdateClient(id, dto), "Updating client..."))
+                    }
+                    None => Err("Client is no longer loaded; Esc and refresh (r)"),
+                },
+            },
+            FormState::Project(form) => match form.editing {
+                None => {
+                    let dto = form.build_create(&self.clients, &self.users);
+                    dto.validate().map(|_| (ApiCommand::CreateProject(dto), "Creating project..."))
+                }
+                Some(id) => {
+                    let dto = form.build_update(&self.clients, &self.users);
+                    dto.validate().map(|_| (ApiCommand::UpdateProject(id, dto), "Updating project..."))
+                }
+            },
+            FormState::User(form) => match form.editing {
+                None => {
+                    let dto = form.build_create(lowercase);
+                    dto.validate().map(|_| (ApiCommand::CreateUser(dto), "Creating user..."))
+                }
+                Some(id) => {
+                    let dto = form.build_update(lowercase);
+                    dto.validate().map(|_| (ApiCommand::UpdateUser(id, dto), "Updating user..."))
+                }
+            },
+        };
+        let (command, intent) = match built {
+            Ok(built) => built,
+            Err(error) => {
+                if let Some(form) = &mut self.form_state {
+                    form.nav_mut().error = Some(error.to_string());
+                }
+                return None;
+            }
+        };
+        let is_project = matches!(command, ApiCommand::CreateProject(_) | ApiCommand::UpdateProject(..));
+        if is_project && !self.warnings_confirmed() {
+            return None;
+        }
+        if self.confirm_edits {
+            if let Some((title, changes)) = self.edit_changes(&command) {
+                self.edit_review = Some(EditReview { title, changes, command, intent });
+                return None;
+            }
+        }
+        self.log_intent(intent);
+        self.verify_before_update(command)
+    }
+
+    /// What an update changes on the entity as loaded, with the popup title;
+    /// `None` for creates and for edits that change nothing
+    fn edit_changes(&self, command: &ApiCommand) -> Option<(String, Vec<FieldChange>)> {
+        let format = self.date_format;
+        let (title, changes) = match command {
+            ApiCommand::UpdateClient(id, dto) => {
+                let client = self.clients.iter().find(|c| c.id == *id)?;
+                let old = UpdateClientDto::from_client(client);
+                (format!("Edit client {}", client.display_name()), diff_update(&old, dto, self, format))
+            }
+            ApiCommand::UpdateProject(id, dto) => {
+                let project = self.projects.iter().find(|p| p.id == *id)?;
+                let old = UpdateProjectDto::from_project(project);
+                (format!("Edit project {}", project.display_name()), diff_update(&old, dto, self, format))
+            }
+            ApiCommand::UpdateUser(id, dto) => {
+                let user = self.users.iter().find(|u| u.id == *id)?;
+                let old = UpdateUserDto::from_user(user);
+                (format!("Edit user {}", user.display_name()), diff_update(&old, dto, self, format))
+            }
+            _ => return None,
+        };
+        (!changes.is_empty()).then_some((title, changes))
+    }
+
+    /// Enter sends the reviewed edit; Esc goes back to the form as it was
+    fn handle_edit_review_key(&mut self, key: KeyEvent) -> Option<ApiCommand> {
+        match key.code {
+            KeyCode::Enter => {
+                let review = self.edit_review.take()?;
+                self.log_intent(review.intent);
+                self.verify_before_update(review.command)
+            }
+            _ => None,
+        }
+    }
+
+    /// Handle keys in confirming mode (delete dialog)
+    fn handle_confirming_key(&mut self, key: KeyEvent) -> Option<ApiCommand> {
+        if self.confirm_dialog.is_none() {
+            self.input_mode = InputMode::Normal;
+            return None;
+        }
+
+        if let Some(dialog) = &mut self.confirm_dialog {
+            if dialog.scroll.handle_key(key, true) {
+                return None;
+            }
+        }
+
+        match key.code {
+            KeyCode::Char('n') | KeyCode::Char('N') => {
+                self.decline_confirm();
+                return None;
+            }
+            KeyCode::Left | KeyCode::Right | KeyCode::Tab => {
+                if let Some(dialog) = &mut self.confirm_dialog {
+                    dialog.move_focus(if key.code == KeyCode::Left { -1 } else { 1 });
+                }
+                return None;
+            }
+            KeyCode::Enter => {
+                return match self.confirm_dialog.as_ref().map(|d| d.focus) {
+                    Some(ConfirmButton::Yes) => self.confirm(false),
+                    Some(ConfirmButton::All) => self.confirm(true),
+                    _ => {
+                        self.decline_confirm();
+                        None
+                    }
+                };
+            }
+            KeyCode::Char('y') | KeyCode::Char('Y') => {
+                return self.confirm(false);
+            }
+            KeyCode::Char('a') | KeyCode::Char('A')
+                if self.confirm_dialog.as_ref().is_some_and(|d| d.buttons().contains(&ConfirmButton::All)) =>
+            {
+                return self.confirm(true);
+            }
+            _ => {}
+        }
+
+        None
+    }
+
+    /// Close the confirm dialog without acting on it
+    fn decline_confirm(&mut self) {
+        let declined_overwrite = self
+            .confirm_dialog
+            .as_ref()
+            .is_some_and(|d| matches!(d.action, ConfirmAction::Overwrite(_)));
+        self.close_confirm();
+        if declined_overwrite {
+            // Offer to pull in the other change instead
+            if let Some(form) = &mut self.form_state {
+                form.nav_mut().staleness = Staleness::Stale;
+            }
+        }
+    }
+
+    /// Carry out the action of the open confirm dialog; `skip_errors` lets a
+    /// multi-select delete carry on past failures
+    fn confirm(&mut self, skip_errors: bool) -> Option<ApiCommand> {
+        let action = self.confirm_dialog.as_ref()?.action.clone();
+        match action {
+            ConfirmAction::Delete(entity_type, id) => {
+                self.log_intent(format!("Deleting {}...", entity_type));
+                Some(delete_command(entity_type, id))
+            }
+            ConfirmAction::DeleteMany(entity_type, items) => {
+                self.close_confirm();
+                self.marked.clear();
+                let batch = BatchDelete::new(entity_type, items, skip_errors)?;
+                self.log_intent(format!(
+                    "Deleting {} {}s{}...",
+                    batch.total,
+                    entity_type.to_string().to_lowercase(),
+                    if skip_errors { ", skipping failures" } else { "" }
+                ));
+                let command = batch.command();
+                self.batch_delete = Some(batch);
+                Some(command)
+            }
+            ConfirmAction::Quit => {
+                self.close_confirm();
+                self.should_quit = true;
+                Some(ApiCommand::Shutdown)
+            }
+            ConfirmAction::Import(rows) => {
+                self.close_confirm();
+                self.log_intent(format!("Importing {} rows...", rows.len()));
+                self.open_log_group("Import", self.next_request);
+                Some(ApiCommand::Import(rows))
+            }
+            ConfirmAction::Overwrite(update) => {
+                self.close_confirm();
+                if let Some(form) = &mut self.form_state {
+                    form.nav_mut().submitting = true;
+                }
+                self.log(LogEntry::warning("Overwriting newer server changes..."));
+                Some(*update)
+            }
+        }
+    }
+
+    /// Whether the radar is on screen with nothing over it, so the mouse can use it
+    fn radar_takes_mouse(&self) -> bool {
+        self.active_tab == Tab::Timeline
+            && self.input_mode == InputMode::Normal
+            && self.calendar.is_none()
+            && self.splash.is_none()
+            && self.error_popup.is_none()
+            && self.import_prompt.is_none()
+            && self.url_prompt.is_none()
+            && self.date_jump.is_none()
+            && self.filter_popup.is_none()
+            && self.preset_picker.is_none()
+            && self.inline_edit.is_none()
+            && self.move_picker.is_none()
+            && self.profile_switcher.is_none()
+            && self.setup.is_none()
+            && !self.show_help
+            && !self.details_popup
+            && self.history.is_none()
+    }
+
+    /// A click on the radar selects the nearest blip; Ctrl+wheel zooms
+    pub fn handle_mouse(&mut self, mouse: MouseEvent) {
+        if !self.radar_takes_mouse() {
+            return;
+        }
+        match mouse.kind {
+            MouseEventKind::Down(MouseButton::Left) => {
+                let Some(inner) = self.radar_area else {
+                    return;
+                };
+                let Some(point) = radar::canvas_point(inner, mouse.column, mouse.row) else {
+                    return;
+                };
+                // About two cells either way counts as a hit
+                let reach = 2.0 * (200.0 / f64::from(inner.width)).max(200.0 / f64::from(inner.height));
+                let today = chrono::Local::now().date_naive();
+                let visible = self.visible_projects();
+                let range = self.radar_state.range_days;
+                if let Some(index) = radar::blip_at(&self.projects, &visible, range, today, point, reach) {
+                    self.discard_date_edit();
+                    self.focus_pane(Pane::Primary);
+                    self.radar_state.selected_index = Some(index);
+                }
+            }
+            MouseEventKind::ScrollUp if mouse.modifiers.contains(KeyModifiers::CONTROL) => self.radar_state.zoom_in(),
+            MouseEventKind::ScrollDown if mouse.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.radar_state.zoom_out()
+            }
+            _ => {}
+        }
+    }
+
+    /// Handle timeline-specific key events
+    fn handle_timeline_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Left | KeyCode::Right if key.modifiers.contains(KeyModifiers::ALT) => {
+                self.shift_dates(key);
+            }
+            KeyCode::Char('j') | KeyCode::Down | KeyCode::Right => {
+                self.discard_date_edit();
+                self.radar_state.select_next(&self.visible_projects());
+                self.follow_selection();
+            }
+            KeyCode::Char('k') | KeyCode::Up | KeyCode::Left => {
+                self.discard_date_edit();
+                self.radar_state.select_prev(&self.visible_projects());
+                self.follow_selection();
+            }
+            KeyCode::Char('o') => {
+                self.follow_selection = !self.follow_selection;
+                let state = if self.follow_selection { "on" } else { "off" };
+                self.log(LogEntry::info(format!("Radar follows the selection: {}", state)));
+                self.follow_selection();
+            }
+            KeyCode::Char('F') => {
+                self.filter_popup = Some(FilterPopup::new(self.project_filter.clone()));
+            }
+            KeyCode::Char('P') => {
+                self.preset_picker = Some(PresetPicker::default());
+            }
+            KeyCode::Char('+') | KeyCode::Char('=') => {
+                self.radar_state.zoom_in();
+            }
+            KeyCode::Char('-') => {
+                self.radar_state.zoom_out();
+            }
+            KeyCode::Char('z') => {
+                let visible = self.visible_projects();
+                let projects = visible.iter().map(|&i| &self.projects[i]);
+                if self.radar_state.expanded {
+                    self.radar_state.fit_range(projects);
+                } else {
+                    self.radar_state.expand_range(projects);
+                }
+            }
+            KeyCode::Char('m') => {
+                self.discard_date_edit();
+                self.calendar = Some(CalendarState::new(chrono::Local::now().date_naive()));
+            }
+            KeyCode::Char('J') => self.date_jump = Some(TextInput::default()),
+            KeyCode::Char('.') => self.center_selected(),
+            KeyCode::Char('g' | 'G' | '0' | '$') | KeyCode::Home | KeyCode::End => self.jump_to_edge(key.code),
+            KeyCode::Char('u') => self.focus_pane(Pane::Secondary),
+            _ => {}
+        }
+    }
+
+    /// The shown projects with the nearest deadlines, for the list under the details
+    pub fn upcoming(&self) -> Vec<usize> {
+        upcoming::upcoming(&self.projects, &self.visible_projects(), UPCOMING)
+    }
+
+    /// Select the project on row `row` of the upcoming list
+    fn select_upcoming(&mut self, row: usize) {
+        if let Some(&index) = self.upcoming().get(row) {
+            self.radar_state.selected_index = Some(index);
+        }
+    }
+
+    /// Move through the upcoming deadlines; Enter brings the project into view
+    fn handle_upcoming_key(&mut self, key: KeyEvent) {
+        let last = self.upcoming().len().saturating_sub(1);
+        let row = self.upcoming_row.min(last);
+        match key.code {
+            KeyCode::Char('u') => self.focus_pane(Pane::Primary),
+            KeyCode::Char('j') | KeyCode::Down => self.upcoming_row = (row + 1).min(last),
+            KeyCode::Char('k') | KeyCode::Up => self.upcoming_row = row.saturating_sub(1),
+            KeyCode::Enter => {
+                self.focus_pane(Pane::Primary);
+                self.select_upcoming(row);
+                self.center_selected();
+                return;
+            }
+            _ => {}
+        }
+        if self.focused_pane() == Pane::Secondary {
+            self.select_upcoming(self.upcoming_row);
+        }
+    }
+
+    /// The deadlines of the next two weeks, over the filter minus its day
+    pub fn agenda(&self) -> Vec<AgendaDay> {
+        let filter = ProjectFilter { due: None, ..self.project_filter.clone() };
+        let visible = filter.apply(&self.projects, &self.filter_deps());
+        agenda::agenda(&self.projects, &visible, chrono::Local::now().date_naive(), AGENDA_DAYS)
+    }
+
+    /// Whether the agenda strip is under the radar: on in the config, the
+    /// radar showing and zoomed in enough for single days to matter
+    pub fn agenda_shown(&self) -> bool {
+        self.agenda_strip && self.calendar.is_none() && self.radar_state.range_days <= AGENDA_MAX_RANGE_DAYS
+    }
+
+    /// Show only the projects due on the agenda cursor's day
+    fn pick_agenda_day(&mut self) {
+        if let Some(day) = self.agenda().get(self.agenda_day) {
+            self.project_filter.due = Some(day.date);
+            self.keep_selection_visible();
+        }
+    }
+
+    /// Step through the days on the strip, filtering the radar to each
+    fn handle_agenda_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Char('h') | KeyCode::Left => {
+                self.agenda_day = self.agenda_day.saturating_sub(1);
+                self.pick_agenda_day();
+            }
+            KeyCode::Char('l') | KeyCode::Right => {
+                self.agenda_day = (self.agenda_day + 1).min(AGENDA_DAYS - 1);
+                self.pick_agenda_day();
+            }
+            KeyCode::Enter => self.pick_agenda_day(),
+            _ => {}
+        }
+    }
+
+    /// The focused pane of the active tab; a pane with nothing in it (or
+    /// hidden behind the calendar) gives the focus back
+    pub fn focused_pane(&self) -> Pane {
+        match self.focused_panes.get(&self.active_tab) {
+            Some(&pane) if self.has_pane(pane) => pane,
+            _ => Pane::Primary,
+        }
+    }
+
+    /// Whether `pane` can take the focus on the active tab
+    fn has_pane(&self, pane: Pane) -> bool {
+        match pane {
+            Pane::Primary => true,
+            Pane::Secondary => self.has_secondary_pane(),
+            Pane::Agenda => self.active_tab == Tab::Timeline && self.agenda_shown(),
+        }
+    }
+
+    /// Whether the active tab has a second pane to focus
+    fn has_secondary_pane(&self) -> bool {
+        match self.active_tab {
+            Tab::Timeline => self.calendar.is_none() && !self.upcoming().is_empty(),
+            Tab::Clients => !self.client_projects().is_empty(),
+            Tab::Users => false,
+        }
+    }
+
+    /// Move the active tab's focus, starting a secondary list from its top
+    fn focus_pane(&mut self, pane: Pane) {
+        if pane != Pane::Primary && (!self.has_pane(pane) || self.focused_pane() == pane) {
+            return;
+        }
+        self.focused_panes.insert(self.active_tab, pane);
+        match (pane, self.active_tab) {
+            (Pane::Primary, _) => {}
+            (Pane::Agenda, _) => {
+                self.discard_date_edit();
+                self.agenda_day = 0;
+            }
+            (_, Tab::Timeline) => {
+                self.discard_date_edit();
+                self.upcoming_row = 0;
+                self.select_upcoming(0);
+            }
+            (_, Tab::Clients) => self.client_project_row = 0,
+            (_, Tab::Users) => {}
+        }
+    }
+
+    /// Indices of the selected client's projects, nearest planned end first
+    pub fn client_projects(&self) -> Vec<usize> {
+        let Some(client_id) = self.clients.get(self.list_selected).map(|c| c.id) else {
+            return Vec::new();
+        };
+        let mut indices: Vec<usize> = (0..self.projects.len())
+            .filter(|&i| self.projects[i].client_id == client_id)
+            .collect();
+        indices.sort_by_key(|&i| (self.projects[i].planned_end_date, self.projects[i].id));
+        indices
+    }
+
+    /// Index of the project under the Clients tab's project cursor
+    fn selected_client_project(&self) -> Option<usize> {
+        let projects = self.client_projects();
+        let row = self.client_project_row.min(projects.len().checked_sub(1)?);
+        Some(projects[row])
+    }
+
+    /// Move through the client's projects; Enter shows the project on the radar
+    fn handle_client_project_key(&mut self, key: KeyEvent) {
+        let projects = self.client_projects();
+        let last = projects.len().saturating_sub(1);
+        let row = self.client_project_row.min(last);
+        match key.code {
+            KeyCode::Char('m') if !self.blocked_by_read_only("move") => {
+                let project = self.projects[projects[row]].id;
+                self.move_picker = Some(MovePicker { project, query: TextInput::default(), selected: 0 });
+            }
+            KeyCode::Char('j') | KeyCode::Down => self.client_project_row = (row + 1).min(last),
+            KeyCode::Char('k') | KeyCode::Up => self.client_project_row = row.saturating_sub(1),
+            KeyCode::Char('g') => self.client_project_row = 0,
+            KeyCode::Char('G') => self.client_project_row = last,
+            KeyCode::Enter => {
+                let index = projects[row];
+                self.show_client_projects();
+                if !self.visible_projects().contains(&index) {
+                    let name = self.projects[index].display_name().to_string();
+                    self.log(LogEntry::info(format!("{} is archived (H to show it)", name)));
+                    return;
+                }
+                self.radar_state.selected_index = Some(index);
+                self.radar_state.reveal(&self.projects[index]);
+                self.flash = Some((self.projects[index].id, self.frame_count));
+            }
+            _ => {}
+        }
+    }
+
+    /// Enter on the radar, as set by `timeline_enter_action`
+    fn timeline_enter(&mut self) -> Option<ApiCommand> {
+        self.radar_state.selected_index?;
+        match self.enter_action {
+            EnterAction::Details => self.details_popup = true,
+            EnterAction::Edit => {
+                if !self.blocked_by_read_only("edit") {
+                    return self.request_edit();
+                }
+            }
+            EnterAction::Center => self.center_selected(),
+        }
+        None
+    }
+
+    /// Zoom out until the selected project's blip is inside the rim, and flash it
+    fn center_selected(&mut self) {
+        let Some(project) = self.radar_state.selected_index.and_then(|i| self.projects.get(i)) else {
+            return;
+        };
+        self.radar_state.reveal(project);
+        self.flash = Some((project.id, self.frame_count));
+    }
+
+    /// Zoom out just enough to keep the newly selected blip inside the rim,
+    /// when `follow_selection` is on; a blip already in view changes nothing
+    fn follow_selection(&mut self) {
+        if !self.follow_selection {
+            return;
+        }
+        if let Some(project) = self.radar_state.selected_index.and_then(|i| self.projects.get(i)) {
+            self.radar_state.reveal(project);
+        }
+    }
+
+    /// Select the first or last shown project ('g'/'G'), the one starting
+    /// earliest ('0'/Home) or the one ending last ('$'/End), and zoom out to it
+    /// the way '.' does
+    fn jump_to_edge(&mut self, code: KeyCode) {
+        let visible = self.visible_projects();
+        let projects = &self.projects;
+        let scheduled = visible.iter().copied().filter(|&i| projects[i].has_valid_dates());
+        let index = match code {
+            KeyCode::Char('g') => visible.first().copied(),
+            KeyCode::Char('G') => visible.last().copied(),
+            KeyCode::Char('0') | KeyCode::Home => scheduled.min_by_key(|&i| (projects[i].start_date, projects[i].id)),
+            _ => scheduled.max_by_key(|&i| (projects[i].planned_end_date, std::cmp::Reverse(projects[i].id))),
+        };
+        let Some(index) = index else {
+            return;
+        };
+        self.discard_date_edit();
+        self.radar_state.selected_index = Some(index);
+        self.center_selected();
+    }
+
+    /// Starts and deadlines of the shown projects on `day`
+    pub fn calendar_events(&self, day: chrono::NaiveDate) -> Vec<Event> {
+        calendar::events_on(&self.projects, &self.visible_projects(), day)
+    }
+
+    /// Move around the month view, or through the day popup while it's open
+    fn handle_calendar_key(&mut self, key: KeyEvent) {
+        let Some(state) = self.calendar.as_ref() else {
+            return;
+        };
+        let events = self.calendar_events(state.cursor);
+        let Some(state) = self.calendar.as_mut() else {
+            return;
+        };
+
+        if let Some(selected) = state.day_popup {
+            match key.code {
+                KeyCode::Char('j') | KeyCode::Down => {
+                    state.day_popup = Some((selected + 1).min(events.len().saturating_sub(1)));
+                }
+                KeyCode::Char('k') | KeyCode::Up => state.day_popup = Some(selected.saturating_sub(1)),
+                KeyCode::Enter => {
+                    if let Some(event) = events.get(selected) {
+                        self.jump_to_calendar_event(*event);
+                    }
+                }
+                _ => {}
+            }
+            return;
+        }
+
+        match key.code {
+            KeyCode::Char('h') | KeyCode::Left => state.move_days(-1),
+            KeyCode::Char('l') | KeyCode::Right => state.move_days(1),
+            KeyCode::Char('k') | KeyCode::Up => state.move_days(-7),
+            KeyCode::Char('j') | KeyCode::Down => state.move_days(7),
+            KeyCode::Char('<') => state.move_months(-1),
+            KeyCode::Char('>') => state.move_months(1),
+            KeyCode::Char('t') => state.cursor = chrono::Local::now().date_naive(),
+            KeyCode::Char('m') => self.calendar = None,
+            KeyCode::Enter if events.is_empty() => {
+                let day = format_date(&state.cursor, self.date_format);
+                self.log(LogEntry::info(format!("Nothing starts or is due on {}", day)));
+            }
+            KeyCode::Enter => state.day_popup = Some(0),
+            _ => {}
+        }
+    }
+
+    /// Leave the month view with the event's project selected on the radar
+    fn jump_to_calendar_event(&mut self, event: Event) {
+        let Some(project) = self.projects.get(event.project) else {
+            return;
+        };
+        self.calendar = None;
+        self.radar_state.selected_index = Some(event.project);
+        self.radar_state.reveal(project);
+    }
+
+    // Обнови jump_to_selected_project
+    fn jump_to_selected_project(&mut self) {
+        if let Some(idx) = self.radar_state.selected_index {
+            if let Some(project) = self.projects.get(idx) {
+                // Use approximate viewport width
+                let viewport_width = 100u16;
+                self.radar_state
+                    .jump_to_project(project, &self.projects, viewport_width);
+            }
+        }
+    }
+
+    /// Start the radar on the active project due soonest, fitting the zoom to
+    /// the open projects unless the user's own zoom was restored
+    fn auto_center_timeline(&mut self) {
+        if self.projects.is_empty() {
+            return;
+        }
+        self.radar_state.selected_index = Some(radar::earliest_active(&self.projects).unwrap_or(0));
+        if !self.range_restored {
+            self.radar_state.fit_range(&self.projects);
+        }
+    }
+
+    /// Open projects per manager and month, for the heatmap
+    pub fn workload(&self) -> Workload {
+        let today = chrono::Local::now().date_naive();
+        Workload::compute(&self.projects, &self.users, today, workload::MONTHS)
+    }
+
+    /// Move the heatmap cursor, or close the heatmap with `w`/Esc
+    fn handle_workload_key(&mut self, key: KeyEvent) {
+        let Some((row, col)) = self.workload_cursor else {
+            return;
+        };
+        let workload = self.workload();
+        let last_row = workload.rows.len().saturating_sub(1);
+        let last_col = workload.months.len().saturating_sub(1);
+        self.workload_cursor = match key.code {
+            KeyCode::Char('w') => None,
+            KeyCode::Char('j') | KeyCode::Down => Some(((row + 1).min(last_row), col)),
+            KeyCode::Char('k') | KeyCode::Up => Some((row.saturating_sub(1), col)),
+            KeyCode::Char('l') | KeyCode::Right => Some((row, (col + 1).min(last_col))),
+            KeyCode::Char('h') | KeyCode::Left => Some((row, col.saturating_sub(1))),
+            _ => Some((row.min(last_row), col.min(last_col))),
+        };
+    }
+
+    /// Handle list view key events
+    /// `handle_list_key` for the grouped Users tab: the same keys, moving
+    /// through the role sections and over their headers
+    fn handle_grouped_users_key(&mut self, key: KeyEvent) {
+        let rows = user_groups::group_rows(&self.users);
+        let next = match key.code {
+            KeyCode::Char('j') | KeyCode::Down => user_groups::step(&rows, self.list_selected, 1),
+            KeyCode::Char('k') | KeyCode::Up => user_groups::step(&rows, self.list_selected, -1),
+            KeyCode::Char('g') => user_groups::edge(&rows, false),
+            KeyCode::Char('G') => user_groups::edge(&rows, true),
+            _ => None,
+        };
+        if let Some(index) = next {
+            self.list_selected = index;
+        }
+    }
+
+    fn handle_list_key(&mut self, key: KeyEvent, total: usize) {
+        if total == 0 {
+            return;
+        }
+
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down => {
+                self.list_selected = (self.list_selected + 1) % total;
+            }
+            KeyCode::Char('k') | KeyCode::Up => {
+                self.list_selected = self.list_selected.checked_sub(1).unwrap_or(total - 1);
+            }
+            KeyCode::Char('g') => {
+                self.list_selected = 0;
+            }
+            KeyCode::Char('G') => {
+                self.list_selected = total.saturating_sub(1);
+            }
+            _ => {}
+        }
+    }
+
+    /// Update animations and check the data's age (called every frame);
+    /// returns a refresh when the data has gone stale
+    pub fn tick(&mut self, width: u16, height: u16) -> Option<ApiCommand> {
+        self.frame_count = self.frame_count.wrapping_add(1);
+
+        if self.animations && self.has_focus {
+            // Update particles
+            self.particle_system.update(width, height);
+
+            // Update timeline animations (goyslop effects!)
+            self.radar_state.tick();
+        }
+
+        self.toasts.expire(self.frame_count);
+        if !self.badges.is_empty() {
+            let now = Instant::now();
+            self.badges.retain(|_, (_, until)| *until > now);
+        }
+        if self.flash.is_some_and(|(_, since)| self.frame_count.saturating_sub(since) >= FLASH_FRAMES) {
+            self.flash = None;
+        }
+        if self.jump_mark.is_some() && self.jump_mark().is_none() {
+            self.jump_mark = None;
+        }
+
+        if let Some(celebration) = &mut self.celebration {
+            celebration.update();
+            if celebration.is_finished(self.frame_count) {
+                self.celebration = None;
+            }
+        }
+
+        // Auto-dismiss error popup
+        if let Some(ref popup) = self.error_popup {
+            if popup.should_dismiss() {
+                self.error_popup = None;
+            }
+        }
+
+        self.check_staleness(Instant::now())
+    }
+
+    /// Window title, e.g. "SWEeM — 42 projects · 3 overdue · connected", with
+    /// the data's age once it is stale
+    pub fn window_title(&self, today: NaiveDate, now: Instant) -> String {
+        let projects = self.projects.len();
+        let mut parts = vec![format!("{} project{}", projects, if projects == 1 { "" } else { "s" })];
+        let overdue = self.projects.iter().filter(|p| p.status(today) == ProjectStatus::Overdue).count();
+        if overdue > 0 {
+            parts.push(format!("{} overdue", overdue));
+        }
+        parts.push(if self.api_connected { "connected" } else { "offline" }.to_string());
+        if let Some(age) = self.stale_age(now) {
+            parts.push(format!("data {} old", format_age(age)));
+        }
+        format!("SWEeM — {}", parts.join(" · "))
+    }
+
+    /// Human-readable age of the last successful refresh (e.g. "12s ago")
+    pub fn refresh_age(&self) -> Option<String> {
+        self.last_refresh.map(|t| format!("{} ago", format_age(t.elapsed())))
+    }
+
+    /// How old the data is at `now`, when that is past `stale_after`
+    pub fn stale_age(&self, now: Instant) -> Option<Duration> {
+        let age = now.saturating_duration_since(self.last_refresh?);
+        (age > self.stale_after?).then_some(age)
+    }
+
+    /// Warn once when the data goes stale, and refresh it if the API is
+    /// there to ask; a failed refresh isn't retried until data comes in
+    fn check_staleness(&mut self, now: Instant) -> Option<ApiCommand> {
+        let age = self.stale_age(now)?;
+        if self.stale_warned.is_some() && self.stale_warned == self.last_refresh {
+            return None;
+        }
+        self.stale_warned = self.last_refresh;
+        let age = format_age(age);
+        if !self.api_connected {
+            self.log(LogEntry::warning(format!("Data not refreshed for {} and the API is unreachable", age)));
+            return None;
+        }
+        self.log(LogEntry::warning(format!("Data not refreshed for {}; refreshing", age)));
+        if !refresh_allowed(self.refresh_requested_at, self.paging.any_loading(), now) {
+            return None;
+        }
+        Some(self.begin_refresh(now))
+    }
+
+    /// Mark everything loading for a full refresh
+    fn begin_refresh(&mut self, now: Instant) -> ApiCommand {
+        self.refresh_requested_at = Some(now);
+        self.paging.set_loading(true);
+        self.is_loading = true;
+        ApiCommand::RefreshAll
+    }
+
+    /// Key hints for the status bar, matching whatever currently receives input
+    pub fn key_hints(&self) -> &'static str {
+        if let Some(popup) = &self.error_popup {
+            return if popup.scroll.overflows() {
+                "j/k PgUp/PgDn scroll · Esc/Enter dismiss"
+            } else {
+                "Esc/Enter dismiss"
+            };
+        }
+        if self.profile_switcher.is_some() {
+            return "j/k select · Enter switch · Esc cancel";
+        }
+        if self.import_prompt.is_some() {
+            return "Enter dry run · Esc cancel";
+        }
+        if self.url_prompt.is_some() {
+            return "Enter connect · Esc cancel";
+        }
+        if self.date_jump.is_some() {
+            return "Enter jump · Esc cancel";
+        }
+        if self.filter_popup.is_some() {
+            return "↑↓ field · ←→ choose · Enter apply · Del clear all · Esc cancel";
+        }
+        if let Some(picker) = &self.preset_picker {
+            return match picker.naming {
+                Some(_) => "Enter save · Esc cancel",
+                None => "j/k select · Enter apply · s save current · d delete · Esc close",
+            };
+        }
+        if self.setup.is_some() {
+            return "Enter retry · Tab switch · Esc skip · Ctrl+C quit";
+        }
+        if self.details_popup {
+            return "Esc/Enter close";
+        }
+        if let Some(popup) = &self.history {
+            return if popup.scroll.overflows() { "j/k PgUp/PgDn scroll · Esc/i close" } else { "Esc/i close" };
+        }
+        if self.show_help {
+            return if self.help_scroll.overflows() {
+                "j/k PgUp/PgDn scroll · Esc/? close help"
+            } else {
+                "Esc/? close help"
+            };
+        }
+        if self.radar_state.pending_edit.is_some() && self.active_tab == Tab::Timeline {
+            return "Alt+←→ shift · Enter save · Esc discard";
+        }
+        if let Some(calendar) = self.calendar.as_ref().filter(|_| self.active_tab == Tab::Timeline) {
+            return if calendar.day_popup.is_some() {
+                "j/k select · Enter jump to project · Esc close"
+            } else {
+                "←→↑↓ day · </> month · t today · Enter day · m/Esc radar"
+            };
+        }
+        if self.inline_edit.is_some() {
+            return "type the new name · Enter rename · Esc cancel";
+        }
+        if self.move_picker.is_some() {
+            return "type to narrow · ↑↓ select · Enter move · Esc cancel";
+        }
+        if self.focused_pane() == Pane::Agenda {
+            return "←→ show the projects due that day · Esc/^W back to the radar";
+        }
+        if self.focused_pane() == Pane::Secondary {
+            return match self.active_tab {
+                Tab::Timeline => "j/k select · Enter show on radar · u/Esc/^W back to the radar",
+                _ => "j/k select · Enter show on radar · e edit · m move · Esc/^W back to the clients",
+            };
+        }
+        if self.workload_cursor.is_some() && self.active_tab == Tab::Users {
+            return "hjkl move · w/Esc back to the list";
+        }
+        match self.input_mode {
+            InputMode::Normal if self.active_tab == Tab::Timeline => self.enter_action.hints(self.read_only),
+            InputMode::Normal if self.read_only => "? help · r refresh · q quit · ^E profile",
+            InputMode::Normal => "? help · c create · e edit · d delete · q quit · ^E profile",
+            InputMode::Editing
+                if self.form_state.as_ref().is_some_and(|f| f.nav().staleness == Staleness::Stale) =>
+            {
+                "^R reload · Esc cancel"
+            }
+            InputMode::Editing if self.name_suggestion().is_some() => "Tab accept · Enter submit · Esc cancel",
+            InputMode::Editing if self.typing_secret() => "^G generate · Tab next · Enter submit · Esc cancel",
+            InputMode::Editing => "Tab next · Enter submit · Esc cancel",
+            InputMode::Confirming if self.confirm_dialog.as_ref().is_some_and(|d| d.buttons().len() > 2) => {
+                "y/a/n · a = yes to all · ←→ switch"
+            }
+            InputMode::Confirming if self.confirm_dialog.as_ref().is_some_and(|d| d.scroll.overflows()) => {
+                "y/n · ←→ switch · j/k scroll"
+            }
+            InputMode::Confirming => "y/n · ←→ switch",
+            InputMode::Searching => "type to search · ↑↓ select · Enter go · Esc close",
+        }
+    }
+}
+
+/// Exports name what isn't loaded the way the screen does, except that a
+/// file can't wait for a lookup to finish
+impl report::Names for App {
+    fn client_name(&self, id: Uuid) -> String {
+        settled(self.resolve_client_name(id), id)
+    }
+
+    fn user_name(&self, id: Uuid) -> String {
+        settled(self.resolve_user_name(id), id)
+    }
+}
+
+fn settled(name: String, id: Uuid) -> String {
+    if name == LOADING_NAME {
+        format!("unknown ({})", short_id(id))
+    } else {
+        name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use crate::form::ClientForm;
+    use crate::test_support::{client, project, user, ClientFixture, ProjectFixture, UserFixture};
+    use crate::models::Warning;
+
+    fn ctrl(c: char) -> KeyEvent {
+        KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL)
+    }
+
+    fn profile(url: &str) -> Profile {
+        Profile {
+            url: url.to_string(),
+            token: None,
+        }
+    }
+
+    #[test]
+    fn test_profile_switch_resets_data_and_retargets_worker() {
+        let mut app = App::new();
+        app.profiles = vec![
+            ("dev".to_string(), profile("http://localhost:5094")),
+            ("prod".to_string(), profile("https://sweem.example.com")),
+        ];
+        app.active_profile = Some("dev".to_string());
+        app.handle_api_message(ApiMessage::ClientsLoaded(Vec::new().into()));
+        app.is_loading = false;
+
+        assert!(app.handle_key(ctrl('e')).is_none());
+        assert_eq!(app.profile_switcher, Some(0));
+        assert!(app.handle_key(key(KeyCode::Down)).is_none());
+
+        match app.handle_key(key(KeyCode::Enter)) {
+            Some(ApiCommand::SetBaseUrl { url, token }) => {
+                assert_eq!(url, "https://sweem.example.com");
+                assert_eq!(token, None);
+            }
+            other => panic!("expected SetBaseUrl, got {:?}", other),
+        }
+        assert_eq!(app.profile_switcher, None);
+        assert_eq!(app.active_profile.as_deref(), Some("prod"));
+        assert_eq!(app.api_url, "https://sweem.example.com");
+        assert!(app.is_loading);
+        assert!(app.last_refresh.is_none());
+    }
+
+    #[test]
+    fn test_url_prompt_retargets_only_valid_urls() {
+        let mut app = App::new();
+        app.active_profile = Some("dev".to_string());
+        app.api_token = Some("secret".to_string());
+        app.handle_api_message(ApiMessage::ClientsLoaded(vec![client(7).named("Acme").address("1 Road")].into()));
+        app.is_loading = false;
+
+        assert!(app.handle_key(key(KeyCode::Char('U'))).is_none());
+        assert_eq!(app.url_prompt.as_ref().unwrap().url.value(), api::DEFAULT_BASE_URL);
+        for _ in 0..4 {
+            app.handle_key(key(KeyCode::Backspace));
+        }
+        app.handle_key(key(KeyCode::Char('x')));
+
+        // A bad URL keeps the prompt open with its error and the old data
+        assert!(app.handle_key(key(KeyCode::Enter)).is_none());
+        let prompt = app.url_prompt.as_ref().unwrap();
+        assert_eq!(prompt.url.value(), "http://localhost:x");
+        assert!(prompt.error.as_deref().unwrap().starts_with("Invalid URL"));
+        assert_eq!(app.clients.len(), 1);
+        app.handle_key(key(KeyCode::Backspace));
+        assert!(app.url_prompt.as_ref().unwrap().error.is_none());
+
+        for c in "9000/".chars() {
+            app.handle_key(key(KeyCode::Char(c)));
+        }
+        match app.handle_key(key(KeyCode::Enter)) {
+            Some(ApiCommand::SetBaseUrl { url, token }) => {
+                assert_eq!(url, "http://localhost:9000");
+                assert_eq!(token.as_deref(), Some("secret"));
+            }
+            other => panic!("expected SetBaseUrl, got {:?}", other),
+        }
+        assert!(app.url_prompt.is_none());
+        assert_eq!(app.api_url, "http://localhost:9000");
+        assert_eq!(app.active_profile, None);
+        assert!(app.clients.is_empty() && app.is_loading);
+    }
+
+    #[test]
+    fn test_read_only_never_produces_mutating_commands() {
+        let mut app = App::new();
+        app.read_only = true;
+        app.clients = vec![client(1).named("Acme")];
+        app.active_tab = Tab::Clients;
+
+        let keys = [
+            key(KeyCode::Char('c')),
+            key(KeyCode::Char('e')),
+            key(KeyCode::Char('d')),
+            key(KeyCode::Delete),
+            key(KeyCode::Char('y')),
+            key(KeyCode::Enter),
+            key(KeyCode::Tab),
+        ];
+        for k in keys {
+            let cmd = app.handle_key(k);
+            assert!(!cmd.is_some_and(|c| c.is_mutating()), "{:?} produced a mutation", k);
+        }
+        assert!(app.form_state.is_none());
+        assert!(app.confirm_dialog.is_none());
+
+        // Even a dialog opened behind the guard's back cannot submit
+        app.active_tab = Tab::Clients;
+        app.confirm_dialog = Some(ConfirmDialog::new_delete(EntityType::Client, Uuid::new_v4(), "Acme"));
+        app.input_mode = InputMode::Confirming;
+        assert!(app.handle_key(key(KeyCode::Char('y'))).is_none());
+
+        let mut form = ClientForm::new_create();
+        form.name.set_value("Acme");
+        app.form_state = Some(FormState::Client(form));
+        app.input_mode = InputMode::Editing;
+        for _ in 0..10 {
+            let cmd = app.handle_key(key(KeyCode::Enter));
+            assert!(!cmd.is_some_and(|c| c.is_mutating()));
+        }
+    }
+
+    fn saved_state(user: Option<Uuid>) -> UiState {
+        UiState {
+            version: STATE_VERSION,
+            active_tab: Tab::Users,
+            range_days: 180.0,
+            particle_mode: crate::particles::ParticleMode::Starfield,
+            selections: Selections {
+                project: Some(Uuid::new_v4()),
+                client: None,
+                user,
+            },
+        }
+    }
+
+    #[test]
+    fn test_restored_selection_waits_for_data() {
+        let users = vec![user(1).role(Role::Admin), user(2)];
+        let mut app = App::new();
+        app.restore_ui_state(saved_state(Some(users[1].id)));
+        assert_eq!(app.active_tab, Tab::Users);
+        assert_eq!(app.radar_state.range_days, 180.0);
+        assert_eq!(app.list_selected, 0);
+
+        app.handle_api_message(ApiMessage::UsersLoaded(users.clone().into()));
+        assert_eq!(app.list_selected, 1);
+        assert_eq!(app.ui_state().selections.user, Some(users[1].id));
+        assert_eq!(app.ui_state().particle_mode, crate::particles::ParticleMode::Starfield);
+
+        // Resolved once; later refreshes keep the user's own navigation
+        app.list_selected = 0;
+        app.handle_api_message(ApiMessage::UsersLoaded(users.into()));
+        assert_eq!(app.list_selected, 0);
+    }
+
+    #[test]
+    fn test_restored_selection_of_missing_entity_is_dropped() {
+        let mut app = App::new();
+        app.restore_ui_state(saved_state(Some(Uuid::new_v4())));
+        app.handle_api_message(ApiMessage::UsersLoaded(vec![user(1).role(Role::Admin)].into()));
+        assert_eq!(app.list_selected, 0);
+        assert_eq!(app.pending_selections.user, None);
+        assert_eq!(app.ui_state().selections.project, None);
+    }
+
+    #[test]
+    fn test_tab_cycling_hides_users_from_managers() {
+        assert_eq!(Tab::Timeline.next(None), Tab::Users);
+        assert_eq!(Tab::Timeline.next(Some(Role::Admin)), Tab::Users);
+        assert_eq!(Tab::Timeline.next(Some(Role::Manager)), Tab::Clients);
+        assert_eq!(Tab::Clients.previous(Some(Role::Manager)), Tab::Timeline);
+
+        let mut app = App::new();
+        app.active_tab = Tab::Users;
+        app.set_current_user(user(1));
+        assert_eq!(app.active_tab, Tab::Timeline);
+        app.handle_key(key(KeyCode::Tab));
+        app.handle_key(key(KeyCode::Tab));
+        assert_eq!(app.active_tab, Tab::Timeline);
+    }
+
+    #[test]
+    fn test_current_user_comes_from_the_configured_login() {
+        let mut app = App::new();
+        app.login = Some("KIM".to_string());
+        app.active_tab = Tab::Users;
+        let kim = user(1).named("Kim").login("kim");
+        let ada = user(2).named("Ada").login("ada").role(Role::Admin);
+        app.handle_api_message(ApiMessage::UsersLoaded(vec![ada, kim.clone()].into()));
+        assert_eq!(app.current_user.as_ref().map(|u| u.id), Some(kim.id));
+        assert_eq!(app.role(), Some(Role::Manager));
+        assert_eq!(app.active_tab, Tab::Timeline);
+        assert_eq!(app.logs.back().unwrap().message, "Logged in as Kim (Manager)");
+
+        // A promotion on the server shows up with the next load
+        app.handle_api_message(ApiMessage::UsersLoaded(vec![kim.clone().role(Role::Admin)].into()));
+        assert_eq!(app.role(), Some(Role::Admin));
+
+        let mut stranger = App::new();
+        stranger.login = Some("nobody".to_string());
+        stranger.handle_api_message(ApiMessage::UsersLoaded(vec![kim].into()));
+        assert!(stranger.current_user.is_none());
+        assert_eq!(stranger.logs.back().unwrap().message, "No user with login 'nobody'; role limits are off");
+        // Without a login nobody is limited
+        assert_eq!(App::new().role(), None);
+    }
+
+    #[test]
+    fn test_managers_cannot_create_or_delete_clients() {
+        let mut app = App::new();
+        app.set_current_user(user(1));
+        app.active_tab = Tab::Clients;
+        app.clients = vec![client(1).named("Acme")];
+
+        app.handle_key(key(KeyCode::Char('c')));
+        assert!(app.form_state.is_none());
+        assert!(app.logs.back().unwrap().message.contains("Managers cannot create"));
+        app.handle_key(key(KeyCode::Char('d')));
+        assert!(app.confirm_dialog.is_none());
+
+        // Editing is still allowed
+        assert!(matches!(app.handle_key(key(KeyCode::Char('e'))), Some(ApiCommand::FetchOne(..))));
+
+        let mut admin = App::new();
+        admin.set_current_user(user(1).role(Role::Admin));
+        admin.active_tab = Tab::Clients;
+        admin.handle_key(key(KeyCode::Char('c')));
+        assert!(admin.form_state.is_some());
+    }
+
+    #[test]
+    fn test_quit_confirms_while_mutations_in_flight() {
+        let mut app = App::new();
+        let id = Uuid::new_v4();
+        app.confirm_dialog = Some(ConfirmDialog::new_delete(EntityType::Project, id, "Apollo"));
+        app.input_mode = InputMode::Confirming;
+        assert!(matches!(
+            app.handle_key(key(KeyCode::Char('y'))),
+            Some(ApiCommand::DeleteProject(_))
+        ));
+        assert_eq!(app.in_flight, 1);
+
+        // Quitting now asks first; "n" keeps the app running
+        app.close_confirm();
+        assert!(app.handle_key(key(KeyCode::Char('q'))).is_none());
+        assert!(!app.should_quit);
+        assert_eq!(app.confirm_dialog.as_ref().unwrap().action, ConfirmAction::Quit);
+        app.handle_key(key(KeyCode::Char('n')));
+        assert!(!app.should_quit);
+
+        // Once the response arrives, 'q' quits straight away
+        app.handle_api_message(ApiMessage::Deleted(EntityType::Project, id));
+        assert_eq!(app.in_flight, 0);
+        assert!(matches!(app.handle_key(key(KeyCode::Char('q'))), Some(ApiCommand::Shutdown)));
+        assert!(app.should_quit);
+    }
+
+    #[test]
+    fn test_non_mutation_errors_keep_the_quit_confirmation() {
+        let mut app = App::new();
+        app.in_flight = 1;
+        app.handle_api_message(ApiMessage::Error("Invalid API URL: relative URL without a base".to_string()));
+        assert_eq!(app.in_flight, 1);
+        app.dismiss_error();
+        app.handle_key(key(KeyCode::Char('q')));
+        assert!(!app.should_quit);
+        assert_eq!(app.confirm_dialog.as_ref().unwrap().action, ConfirmAction::Quit);
+
+        // A refused mutation does end one
+        app.close_confirm();
+        app.handle_api_message(ApiMessage::Refused("Read-only mode: refusing to modify data".to_string()));
+        assert_eq!(app.in_flight, 0);
+    }
+
+    #[test]
+    fn test_log_export_line_format() {
+        let mut entry = LogEntry::warning("Slow fetch");
+        entry.timestamp = Local.with_ymd_and_hms(2024, 3, 5, 14, 3, 22).unwrap() + chrono::Duration::milliseconds(7);
+        let offset = entry.timestamp.format("%:z").to_string();
+        assert_eq!(entry.clock(), "14:03:22");
+        assert_eq!(entry.export_line(), format!("2024-03-05 14:03:22.007 {} WARN  Slow fetch", offset));
+
+        let request = RequestId::generate();
+        let entry = LogEntry { level: LogLevel::Error, ..entry }.with_request(request);
+        assert!(entry.export_line().ends_with(&format!(" ERROR {} Slow fetch", request)));
+        let path = log_export_path(entry.timestamp);
+        assert_eq!(path, PathBuf::from("sweem-log-2024-03-05-140322.log"));
+    }
+
+    #[test]
+    fn test_log_keeps_max_logs_and_exports_all_of_them() {
+        let mut app = App::new();
+        app.max_logs = 3;
+        for i in 0..5 {
+            app.log(LogEntry::info(format!("line {}", i)));
+        }
+        let messages: Vec<&str> = app.logs.iter().map(|l| l.message.as_str()).collect();
+        assert_eq!(messages, ["line 2", "line 3", "line 4"]);
+
+        let path = std::env::temp_dir().join(format!("sweem-log-{}.log", Uuid::new_v4()));
+        app.write_log(&path).unwrap();
+        let text = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].ends_with(" INFO  line 2"));
+        assert!(lines[2].ends_with(" INFO  line 4"));
+    }
+
+    #[test]
+    fn test_log_coalescing_rules() {
+        let at = |ms: i64, level: LogLevel, message: &str| LogEntry {
+            timestamp: Local.with_ymd_and_hms(2024, 3, 5, 14, 0, 0).unwrap() + chrono::Duration::milliseconds(ms),
+            ..LogEntry::new(level, message)
+        };
+        let created = at(0, LogLevel::Success, "Project created");
+        assert!(created.coalesces(&at(COALESCE_WINDOW_MS, LogLevel::Success, "Project created")));
+        // Too late, another level or another request
+        assert!(!created.coalesces(&at(COALESCE_WINDOW_MS + 1, LogLevel::Success, "Project created")));
+        assert!(!created.coalesces(&at(10, LogLevel::Info, "Project created")));
+        let request = RequestId::generate();
+        assert!(!created.coalesces(&at(10, LogLevel::Success, "Project created").with_request(request)));
+
+        // Numbered lines of one series, but not unnumbered ones that share a label
+        let failed = at(0, LogLevel::Error, "Import line 3 failed: timeout");
+        assert!(failed.coalesces(&at(10, LogLevel::Error, "Import line 12 failed: 409 Conflict")));
+        assert!(!failed.coalesces(&at(10, LogLevel::Error, "Import line 3 rejected: timeout")));
+        let slow = at(0, LogLevel::Warning, "Slow fetch: projects: 200 OK");
+        assert!(!slow.coalesces(&at(10, LogLevel::Warning, "Slow fetch: users: 503")));
+        assert!(!at(0, LogLevel::Info, "line 1").coalesces(&at(10, LogLevel::Info, "line 2")));
+
+        let mut app = App::new();
+        app.logs.clear();
+        for _ in 0..47 {
+            app.log(LogEntry::success("Project created"));
+        }
+        app.log(LogEntry::error("Import line 3 failed: timeout"));
+        app.log(LogEntry::error("Import line 9 failed: timeout"));
+        app.log(LogEntry::info("Done"));
+        assert_eq!(app.logs.len(), 3);
+        assert_eq!(app.logs[0].text(), "Project created ×47");
+        assert!(app.logs[0].details.is_empty(), "repeats of one message keep no details");
+        assert_eq!(app.logs[1].text(), "Import line 9 failed: timeout ×2");
+        assert_eq!(app.logs[1].details[0].message, "Import line 3 failed: timeout");
+        assert!(app.logs[1].export_line().ends_with(" ERROR Import line 9 failed: timeout ×2"));
+    }
+
+    #[test]
+    fn test_log_group_summarizes_a_bulk_operation() {
+        let mut app = App::new();
+        let before = app.logs.len();
+        let request = app.next_request;
+        app.open_log_group("Import", request);
+        app.log(LogEntry::info("Unrelated"));
+        for line in 1..=3 {
+            app.log(LogEntry::info(format!("Imported {}/3", line)).with_request(request));
+        }
+        app.log(LogEntry::error("Import line 2 failed: 409 Conflict").with_request(request));
+        assert_eq!(app.logs.len(), before + 1);
+        assert_eq!(app.log_group.as_ref().unwrap().entries.len(), 4);
+
+        app.handle_response(Response {
+            request: Some(request),
+            message: ApiMessage::ImportFinished {
+                entity_type: EntityType::Client,
+                created: 2,
+                failures: vec![(2, "409 Conflict".to_string())],
+            },
+        });
+        assert!(app.log_group.is_none());
+        let summary = app.logs.iter().find(|l| l.message.starts_with("Import finished")).unwrap();
+        assert_eq!(summary.message, "Import finished: 2 ok, 1 failed — press v for details");
+        assert_eq!(summary.level, LogLevel::Warning);
+        assert_eq!(summary.details.len(), 4);
+        assert_eq!(summary.details[3].level, LogLevel::Error);
+
+        // Exported with the details under the summary
+        let path = std::env::temp_dir().join(format!("sweem-log-{}.log", Uuid::new_v4()));
+        app.write_log(&path).unwrap();
+        let text = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+        assert!(text.contains("press v for details\n    "));
+        assert!(text.contains("Imported 3/3"));
+    }
+
+    #[test]
+    fn test_import_dry_run_then_confirm() {
+        let path = std::env::temp_dir().join(format!("sweem-import-{}.csv", Uuid::new_v4()));
+        std::fs::write(&path, "name,address\nAcme,1 Main St\nGlobex,\n").unwrap();
+
+        let mut app = App::new();
+        app.active_tab = Tab::Clients;
+        app.handle_key(key(KeyCode::Char('I')));
+        for c in path.display().to_string().chars() {
+            app.handle_key(key(KeyCode::Char(c)));
+        }
+        assert!(app.handle_key(key(KeyCode::Enter)).is_none());
+        std::fs::remove_file(&path).ok();
+
+        assert!(app.import_prompt.is_none());
+        assert!(app.logs.iter().any(|l| l.message == "Import dry run: 2 valid, 0 invalid"));
+        match app.handle_key(key(KeyCode::Char('y'))) {
+            Some(ApiCommand::Import(rows)) => {
+                assert_eq!(rows.iter().map(|r| r.line).collect::<Vec<_>>(), vec![2, 3]);
+            }
+            other => panic!("expected Import, got {:?}", other),
+        }
+        assert_eq!(app.in_flight, 1);
+
+        app.handle_api_message(ApiMessage::ImportFinished {
+            entity_type: EntityType::Client,
+            created: 1,
+            failures: vec![(3, "API error: 409".to_string())],
+        });
+        assert_eq!(app.in_flight, 0);
+        assert!(app.error_popup.as_ref().unwrap().message.contains("lines 3"));
+    }
+
+    #[test]
+    fn test_import_rejects_file_with_invalid_rows() {
+        let path = std::env::temp_dir().join(format!("sweem-import-{}.json", Uuid::new_v4()));
+        std::fs::write(&path, r#"[{"name": "Apollo", "client": "Nobody"}]"#).unwrap();
+
+        let mut app = App::new();
+        app.plan_import(&path.display().to_string());
+        std::fs::remove_file(&path).ok();
+
+        assert!(app.confirm_dialog.is_none());
+        assert!(app.error_popup.is_some());
+        assert!(app.logs.iter().any(|l| l.message.contains("Import line 1: No client named 'Nobody'")));
+    }
+
+    #[test]
+    fn test_profile_switcher_needs_profiles() {
+        let mut app = App::new();
+        app.handle_key(ctrl('e'));
+        assert_eq!(app.profile_switcher, None);
+        assert!(app.logs.back().unwrap().message.contains("No profiles"));
+    }
+
+    #[test]
+    fn test_key_hints_follow_input_mode() {
+        let mut app = App::new();
+        assert!(app.key_hints().contains("c create"));
+
+        app.open_create_form();
+        assert_eq!(app.key_hints(), "Tab next · Enter submit · Esc cancel");
+        app.close_form();
+
+        app.confirm_dialog = Some(ConfirmDialog::new_delete(EntityType::User, Uuid::nil(), "x"));
+        app.input_mode = InputMode::Confirming;
+        assert_eq!(app.key_hints(), "y/n · ←→ switch");
+
+        app.show_error("API Error", "boom");
+        assert_eq!(app.key_hints(), "Esc/Enter dismiss");
+    }
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn test_setup_screen_on_first_failed_connection() {
+        let mut app = App::new();
+        app.api_url = "http://nowhere:1".to_string();
+        app.handle_api_message(ApiMessage::ConnectionStatus(None));
+        app.handle_api_message(ApiMessage::Error("Cannot connect to API".to_string()));
+
+        let setup = app.setup.as_ref().expect("setup screen shown");
+        assert_eq!(setup.url.value(), "http://nowhere:1");
+        assert!(app.error_popup.is_none());
+
+        // Replace the URL and retry
+        for _ in 0..setup.url.value().len() {
+            app.handle_key(key(KeyCode::Backspace));
+        }
+        for c in "http://localhost:9000".chars() {
+            app.handle_key(key(KeyCode::Char(c)));
+        }
+        match app.handle_key(key(KeyCode::Enter)) {
+            Some(ApiCommand::SetBaseUrl { url, .. }) => assert_eq!(url, "http://localhost:9000"),
+            other => panic!("unexpected command: {:?}", other),
+        }
+        assert_eq!(app.api_url, "http://localhost:9000");
+
+        app.handle_api_message(ApiMessage::ConnectionStatus(Some(Duration::from_millis(42))));
+        assert!(app.setup.is_none());
+
+        // Later disconnects don't bring the setup screen back
+        app.handle_api_message(ApiMessage::ConnectionStatus(None));
+        assert!(app.setup.is_none());
+    }
+
+    #[test]
+    fn test_setup_rejects_invalid_url() {
+        let mut app = App::new();
+        app.setup = Some(SetupState::new("not a url"));
+        assert!(app.handle_key(key(KeyCode::Enter)).is_none());
+        assert!(app.setup.as_ref().unwrap().error.is_some());
+    }
+
+    fn client_page(page: i32, ids: std::ops::Range<u128>, total: i32) -> PaginatedResult<ClientDto> {
+        let items: Vec<ClientDto> = ids
+            // Shifted past the nil id, which loading drops
+            .map(|n| client(n + 1).named(&format!("Client {}", n)))
+            .collect();
+        PaginatedResult {
+            page_size: items.len() as i32,
+            items: Some(items),
+            page,
+            total_count: total,
+            total_pages: 3,
+            has_previous: page > 1,
+            has_next: page < 3,
+        }
+    }
+
+    fn lazy_clients_app() -> App {
+        let mut app = App::new();
+        app.lazy_loading = true;
+        app.active_tab = Tab::Clients;
+        app.handle_api_message(ApiMessage::ClientsPage(client_page(1, 0..20, 50)));
+        app
+    }
+
+    #[test]
+    fn test_pages_append_and_track_metadata() {
+        let mut app = lazy_clients_app();
+        assert_eq!(app.clients.len(), 20);
+        assert_eq!(app.paging.clients.total_count, 50);
+        assert!(app.paging.clients.has_next);
+
+        // Overlapping ids (items shifted by a concurrent insert) are not duplicated
+        app.handle_api_message(ApiMessage::ClientsPage(client_page(2, 18..40, 50)));
+        assert_eq!(app.clients.len(), 40);
+        assert_eq!(app.paging.clients.page, 2);
+
+        // A fresh page 1 (refresh) replaces the collection
+        app.handle_api_message(ApiMessage::ClientsPage(client_page(1, 0..20, 50)));
+        assert_eq!(app.clients.len(), 20);
+    }
+
+    #[test]
+    fn test_load_more_key_requests_next_page_once() {
+        let mut app = lazy_clients_app();
+        match app.handle_key(key(KeyCode::Char('M'))) {
+            Some(ApiCommand::FetchPage(EntityType::Client, 2, DEFAULT_PAGE_SIZE)) => {}
+            other => panic!("unexpected command: {:?}", other),
+        }
+        // Already loading: no duplicate request
+        assert!(app.handle_key(key(KeyCode::Char('M'))).is_none());
+    }
+
+    #[test]
+    fn test_scrolling_near_end_loads_next_page() {
+        let mut app = lazy_clients_app();
+        app.list_selected = 10;
+        assert!(app.handle_key(key(KeyCode::Char('j'))).is_none());
+
+        app.list_selected = 14;
+        match app.handle_key(key(KeyCode::Char('j'))) {
+            Some(ApiCommand::FetchPage(EntityType::Client, 2, DEFAULT_PAGE_SIZE)) => {}
+            other => panic!("unexpected command: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_no_load_after_last_page() {
+        let mut app = lazy_clients_app();
+        app.handle_api_message(ApiMessage::ClientsPage(client_page(3, 20..50, 50)));
+        assert!(!app.paging.clients.has_next);
+
+        app.list_selected = 48;
+        assert!(app.handle_key(key(KeyCode::Char('j'))).is_none());
+        assert!(app.handle_key(key(KeyCode::Char('M'))).is_none());
+    }
+
+    fn project_page(page: i32, ids: std::ops::Range<u128>) -> PaginatedResult<ProjectDto> {
+        PaginatedResult {
+            items: Some(ids.map(|n| project(n + 1).around_today(-10, 10)).collect()),
+            page,
+            page_size: 10,
+            total_count: 30,
+            total_pages: 3,
+            has_previous: page > 1,
+            has_next: page < 3,
+        }
+    }
+
+    fn browsing_app() -> App {
+        let mut app = App::new();
+        app.page_browsing = true;
+        app.page_size = 10;
+        app.active_tab = Tab::Timeline;
+        app.handle_api_message(ApiMessage::ProjectsPage(project_page(1, 0..10)));
+        app
+    }
+
+    #[test]
+    fn test_browsing_keeps_only_the_current_page() {
+        let mut app = browsing_app();
+        assert_eq!(app.projects.len(), 10);
+        assert_eq!(app.project_page_label().as_deref(), Some("page 1/3 · 10 per page"));
+        assert!(app.radar_state.selected_index.is_some());
+
+        app.handle_api_message(ApiMessage::ProjectsPage(project_page(2, 10..20)));
+        assert_eq!(app.projects.len(), 10);
+        assert!(app.projects.iter().all(|p| p.id >= Uuid::from_u128(10)));
+        assert_eq!(app.project_page_label().as_deref(), Some("page 2/3 · 10 per page"));
+        // The new page got its own selection, not a leftover index
+        let selected = app.radar_state.selected_index.unwrap();
+        assert!(app.projects[selected].id >= Uuid::from_u128(10));
+    }
+
+    #[test]
+    fn test_dropped_page_items_leave_the_total() {
+        let mut app = browsing_app();
+        assert_eq!(app.paging.projects.total_count, 30);
+        let mut page = project_page(2, 10..20);
+        page.items.as_mut().unwrap()[0].id = Uuid::nil();
+        app.handle_api_message(ApiMessage::ProjectsPage(page));
+        assert_eq!(app.projects.len(), 9);
+        assert_eq!(app.paging.projects.total_count, 29);
+        assert_eq!(app.logs.back().unwrap().message, "Loaded 9 of 29 Projects");
+        assert!(app.logs.iter().any(|l| l.message == "Dropped 1 project with a nil id"));
+    }
+
+    #[test]
+    fn test_browse_keys_request_neighbouring_pages_within_bounds() {
+        let mut app = browsing_app();
+        assert!(app.handle_key(key(KeyCode::PageUp)).is_none());
+        assert!(app.logs.back().unwrap().message.contains("first page"));
+
+        match app.handle_key(key(KeyCode::PageDown)) {
+            Some(ApiCommand::FetchPage(EntityType::Project, 2, 10)) => {}
+            other => panic!("unexpected command: {:?}", other),
+        }
+        // One request at a time
+        assert!(app.handle_key(key(KeyCode::PageDown)).is_none());
+
+        app.handle_api_message(ApiMessage::ProjectsPage(project_page(3, 20..30)));
+        assert!(app.handle_key(key(KeyCode::PageDown)).is_none());
+        assert!(app.logs.back().unwrap().message.contains("last page"));
+        match app.handle_key(key(KeyCode::PageUp)) {
+            Some(ApiCommand::FetchPage(EntityType::Project, 2, 10)) => {}
+            other => panic!("unexpected command: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_refresh_allowed_debounce() {
+        let now = Instant::now();
+        let recent = now - Duration::from_millis(100);
+        let stale = now - REFRESH_DEBOUNCE;
+
+        assert!(refresh_allowed(None, false, now));
+        assert!(refresh_allowed(Some(recent), false, now));
+        assert!(!refresh_allowed(Some(recent), true, now));
+        // A refresh pending past the window is assumed lost
+        assert!(refresh_allowed(Some(stale), true, now));
+    }
+
+    #[test]
+    fn test_repeated_refresh_key_is_debounced() {
+        let mut app = App::new();
+        assert!(matches!(app.handle_key(key(KeyCode::Char('r'))), Some(ApiCommand::RefreshAll)));
+        assert!(app.handle_key(key(KeyCode::Char('r'))).is_none());
+        assert_eq!(app.logs.back().unwrap().message, "Refresh already in progress");
+
+        // Once the data arrives a new refresh goes through
+        app.handle_api_message(ApiMessage::ProjectsLoaded(Vec::new().into()));
+        app.handle_api_message(ApiMessage::ClientsLoaded(Vec::new().into()));
+        app.handle_api_message(ApiMessage::UsersLoaded(Vec::new().into()));
+        assert!(matches!(app.handle_key(key(KeyCode::Char('r'))), Some(ApiCommand::RefreshAll)));
+    }
+
+    #[test]
+    fn test_stale_threshold() {
+        let mut app = App::new();
+        let loaded = Instant::now();
+        let minutes = |m: u64| loaded + Duration::from_secs(m * 60);
+        assert_eq!(app.stale_age(minutes(60)), None);
+
+        app.last_refresh = Some(loaded);
+        assert_eq!(app.stale_age(minutes(10)), None);
+        let past = minutes(10) + Duration::from_secs(1);
+        assert_eq!(app.stale_age(past), Some(Duration::from_secs(601)));
+        app.stale_after = None;
+        assert_eq!(app.stale_age(minutes(60)), None);
+    }
+
+    #[test]
+    fn test_stale_data_warns_once_and_refreshes() {
+        let mut app = App::new();
+        app.api_connected = true;
+        let loaded = Instant::now();
+        app.last_refresh = Some(loaded);
+        let at = |m: u64| loaded + Duration::from_secs(m * 60);
+        let warnings = |app: &App| app.logs.iter().filter(|l| l.message.starts_with("Data not refreshed")).count();
+
+        assert!(app.check_staleness(at(9)).is_none());
+        assert!(matches!(app.check_staleness(at(11)), Some(ApiCommand::RefreshAll)));
+        assert!(app.is_loading);
+        assert_eq!(app.logs.back().unwrap().message, "Data not refreshed for 11m; refreshing");
+        // Still stale while the refresh is out: no second warning or request
+        assert!(app.check_staleness(at(12)).is_none());
+        assert!(app.check_staleness(at(30)).is_none());
+        assert_eq!(warnings(&app), 1);
+
+        // Fresh data re-arms it
+        app.last_refresh = Some(at(30));
+        app.paging.set_loading(false);
+        assert!(app.check_staleness(at(35)).is_none());
+        assert!(app.check_staleness(at(45)).is_some());
+        assert_eq!(warnings(&app), 2);
+    }
+
+    #[test]
+    fn test_stale_data_while_disconnected_only_warns() {
+        let mut app = App::new();
+        app.is_loading = false;
+        let loaded = Instant::now();
+        app.last_refresh = Some(loaded);
+        assert!(app.check_staleness(loaded + Duration::from_secs(900)).is_none());
+        assert!(!app.is_loading);
+        let note = &app.logs.back().unwrap().message;
+        assert_eq!(note, "Data not refreshed for 15m and the API is unreachable");
+        assert!(app.check_staleness(loaded + Duration::from_secs(1000)).is_none());
+        assert_eq!(app.logs.iter().filter(|l| l.message.starts_with("Data not refreshed")).count(), 1);
+    }
+
+    #[test]
+    fn test_mutation_refreshes_are_coalesced() {
+        let mut app = App::new();
+        assert!(app.refresh_after_mutations(&[]).is_empty());
+
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let commands = app.refresh_after_mutations(&[
+            Stale::One(EntityType::Project, a),
+            Stale::One(EntityType::Client, b),
+            Stale::One(EntityType::Project, a),
+        ]);
+        assert_eq!(
+            commands,
+            vec![ApiCommand::RefreshOne(EntityType::Project, a), ApiCommand::RefreshOne(EntityType::Client, b)]
+        );
+        assert!(!app.paging.projects.loading);
+
+        let commands = app.refresh_after_mutations(&[
+            Stale::One(EntityType::Project, a),
+            Stale::All(EntityType::Client),
+            Stale::One(EntityType::Client, b),
+            Stale::One(EntityType::User, b),
+        ]);
+        assert_eq!(
+            commands,
+            vec![
+                ApiCommand::RefreshProjects,
+                ApiCommand::RefreshClients,
+                ApiCommand::RefreshOne(EntityType::User, b),
+            ]
+        );
+        assert!(app.paging.projects.loading && app.paging.clients.loading);
+        assert!(!app.paging.users.loading);
+    }
+
+    #[test]
+    fn test_one_loaded_replaces_or_inserts() {
+        let mut app = App::new();
+        app.projects = vec![project(1)];
+
+        app.handle_api_message(ApiMessage::OneLoaded(FetchedEntity::Project(project(1).named("Renamed"))));
+        assert_eq!(app.projects.len(), 1);
+        assert_eq!(app.projects[0].display_name(), "Renamed");
+
+        app.handle_api_message(ApiMessage::OneLoaded(FetchedEntity::Project(project(2))));
+        assert_eq!(app.projects.len(), 2);
+        assert!(app.projects.iter().any(|p| p.id == Uuid::from_u128(2)));
+    }
+
+    #[test]
+    fn test_selection_follows_entity_through_reorder() {
+        let mut app = App::new();
+        let starting = |n, start| project(n).around_today(start, start + 30);
+        app.handle_api_message(ApiMessage::ProjectsLoaded(vec![starting(1, -20), starting(2, -10), starting(3, 0)].into()));
+        app.radar_state.selected_index = app.projects.iter().position(|p| p.id == Uuid::from_u128(2));
+
+        // Project 2 moves to the front, and the server sends the rest in another order
+        app.handle_api_message(ApiMessage::ProjectsLoaded(vec![starting(3, 0), starting(1, -20), starting(2, -30)].into()));
+        let order: Vec<u128> = app.projects.iter().map(|p| p.id.as_u128()).collect();
+        assert_eq!(order, vec![2, 1, 3]);
+        assert_eq!(app.radar_state.selected_index, Some(0));
+
+        app.active_tab = Tab::Clients;
+        app.handle_api_message(ApiMessage::ClientsLoaded(vec![client(1), client(2), client(3)].into()));
+        app.list_selected = 2;
+        app.handle_api_message(ApiMessage::ClientsLoaded(vec![client(3), client(1), client(2)].into()));
+        assert_eq!(app.list_selected, 0);
+
+        let (kim, ada) = (user(1), user(2).role(Role::Admin));
+        app.active_tab = Tab::Users;
+        app.handle_api_message(ApiMessage::UsersLoaded(vec![ada.clone(), kim.clone()].into()));
+        app.list_selected = 1;
+        app.handle_api_message(ApiMessage::UsersLoaded(vec![kim, ada].into()));
+        assert_eq!(app.list_selected, 0);
+    }
+
+    #[test]
+    fn test_deleted_project_leaves_cache_without_reload() {
+        let mut app = App::new();
+        let ids: Vec<Uuid> = (1..=3).map(Uuid::from_u128).collect();
+        app.projects = (1..=3).map(project).collect();
+        app.radar_state.selected_index = Some(2);
+
+        let message = ApiMessage::Deleted(EntityType::Project, ids[0]);
+        assert_eq!(message.stale(), None);
+        app.handle_api_message(message);
+        assert_eq!(app.projects.iter().map(|p| p.id).collect::<Vec<_>>(), vec![ids[1], ids[2]]);
+        assert_eq!(app.radar_state.selected_index, Some(1));
+
+        app.handle_api_message(ApiMessage::Deleted(EntityType::Project, ids[2]));
+        assert_eq!(app.radar_state.selected_index, Some(0));
+        app.handle_api_message(ApiMessage::Deleted(EntityType::Project, ids[1]));
+        assert_eq!(app.radar_state.selected_index, None);
+    }
+
+    #[test]
+    fn test_load_progress_cleared_when_loaded() {
+        let mut app = App::new();
+        app.handle_api_message(ApiMessage::LoadProgress(EntityType::Project, 300, Some(1250)));
+        assert_eq!(app.paging.projects.fetched, Some((300, Some(1250))));
+
+        app.handle_api_message(ApiMessage::ProjectsLoaded(Vec::new().into()));
+        assert_eq!(app.paging.projects.fetched, None);
+    }
+
+    fn problem(status: u16, detail: &str) -> ApiError {
+        ApiError::Http {
+            status,
+            problem: Some(crate::models::ProblemDetails {
+                problem_type: None,
+                title: None,
+                status: Some(status as i32),
+                detail: Some(detail.to_string()),
+                instance: None,
+            }),
+        }
+    }
+
+    #[test]
+    fn test_connection_failures_show_one_popup_per_outage() {
+        let mut app = App::new();
+        app.api_connected = true;
+        let refused = || ApiError::Connection("connection refused".to_string());
+
+        app.handle_api_message(ApiMessage::Failed(ApiOperation::Load(EntityType::Project), refused()));
+        assert!(!app.api_connected);
+        assert!(app.error_popup.is_some());
+        app.dismiss_error();
+
+        assert_eq!(app.disconnect_reason, Some("refused"));
+
+        app.handle_api_message(ApiMessage::Failed(ApiOperation::Load(EntityType::Client), ApiError::Timeout));
+        assert!(app.error_popup.is_none());
+        assert_eq!(app.disconnect_reason, Some("timed out"));
+
+        // Reconnecting re-arms the popup
+        app.handle_api_message(ApiMessage::ConnectionStatus(Some(Duration::from_millis(42))));
+        app.handle_api_message(ApiMessage::Failed(ApiOperation::Connect, refused()));
+        assert!(app.error_popup.is_some());
+    }
+
+    #[test]
+    fn test_rate_limit_shows_countdown_instead_of_popup() {
+        let mut app = App::new();
+        app.api_connected = true;
+        app.handle_api_message(ApiMessage::RateLimited(Duration::from_secs(20)));
+        let remaining = app.rate_limit_remaining().unwrap();
+        assert!(remaining > Duration::from_secs(19) && remaining <= Duration::from_secs(20));
+
+        let limited = ApiError::RateLimited { retry_after: Duration::from_secs(20) };
+        app.handle_api_message(ApiMessage::Failed(ApiOperation::Load(EntityType::Project), limited.clone()));
+        assert!(app.error_popup.is_none());
+        assert!(app.api_connected);
+
+        // A change that still couldn't be applied after the retry is worth interrupting for
+        app.in_flight = 1;
+        app.handle_api_message(ApiMessage::Failed(ApiOperation::Create(EntityType::Client), limited));
+        assert!(app.error_popup.is_some());
+    }
+
+    #[test]
+    fn test_toasts_announce_results_and_expire() {
+        let mut app = App::new();
+        app.in_flight = 1;
+        app.handle_api_message(ApiMessage::Created(EntityType::Project, Uuid::new_v4()));
+        app.handle_api_message(ApiMessage::ClientsLoaded(Vec::new().into()));
+        let messages: Vec<&str> = app.toasts.iter().map(|t| t.message.as_str()).collect();
+        assert_eq!(messages, ["Project created", "0 clients loaded"]);
+
+        for _ in 0..TOAST_FRAMES {
+            app.tick(80, 24);
+        }
+        assert!(app.toasts.is_empty());
+
+        for n in 0..10 {
+            app.toast(LogLevel::Info, n.to_string());
+        }
+        assert_eq!(app.toasts.iter().count(), MAX_TOASTS);
+        assert_eq!(app.toasts.iter().next().unwrap().message, "6");
+    }
+
+    #[test]
+    fn test_ancient_projects_do_not_stretch_the_radar() {
+        let mut app = App::new();
+        let projects = vec![
+            // A finished 2019-style legacy project and one far-future outlier that's done
+            project(1).around_today(-2500, -2200).done(),
+            project(2).around_today(-30, 40),
+            project(3).around_today(-10, 12),
+            project(4).around_today(5, 120),
+            project(5).around_today(-400, 900).done(),
+        ];
+        app.handle_api_message(ApiMessage::ProjectsLoaded(projects.into()));
+
+        // Starts on the active project due soonest, zoomed to the open deadlines
+        let selected = app.radar_state.selected_index.map(|i| app.projects[i].id);
+        assert_eq!(selected, Some(Uuid::from_u128(3)));
+        let range = app.radar_state.range_days;
+        assert!((120.0..=radar::MAX_RANGE_DAYS).contains(&range), "range {}", range);
+        for project in app.projects.iter().filter(|p| !p.is_completed()) {
+            let (x, y) = radar::blip_position(project, range);
+            assert!(x.hypot(y) < 95.0, "{} sits on the rim", project.display_name());
+        }
+
+        // Expanding reaches the outlier; pressing again fits back
+        app.handle_key(key(KeyCode::Char('z')));
+        assert!(app.radar_state.range_days > 900.0);
+        app.handle_key(key(KeyCode::Char('z')));
+        assert_eq!(app.radar_state.range_days, range);
+    }
+
+    #[test]
+    fn test_filter_popup_limits_radar_navigation() {
+        let mut app = App::new();
+        app.projects = vec![
+            project(1).around_today(-30, 40),
+            project(2).around_today(-30, -5),
+            project(3).around_today(-10, 12),
+            project(4).around_today(-60, -20),
+        ];
+        app.radar_state.selected_index = Some(0);
+
+        app.handle_key(key(KeyCode::Char('F')));
+        assert!(app.filter_popup.is_some());
+        // Any → ACTIVE → PLANNED → LATE
+        for _ in 0..3 {
+            app.handle_key(key(KeyCode::Right));
+        }
+        app.handle_key(key(KeyCode::Enter));
+        assert!(app.filter_popup.is_none());
+        assert_eq!(app.project_filter.status, Some(ProjectStatus::Overdue));
+        assert_eq!(app.visible_projects(), vec![1, 3]);
+        assert_eq!(app.radar_state.selected_index, Some(1), "selection left the hidden project");
+
+        app.handle_key(key(KeyCode::Char('j')));
+        assert_eq!(app.radar_state.selected_index, Some(3));
+        app.handle_key(key(KeyCode::Char('j')));
+        assert_eq!(app.radar_state.selected_index, Some(1));
+
+        // Esc keeps the applied filter; Del clears it
+        app.handle_key(key(KeyCode::Char('F')));
+        app.handle_key(key(KeyCode::Esc));
+        assert!(app.project_filter.is_active());
+        app.handle_key(key(KeyCode::Char('F')));
+        app.handle_key(key(KeyCode::Delete));
+        assert!(!app.project_filter.is_active());
+        assert_eq!(app.visible_projects(), vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn test_enter_on_client_shows_its_projects() {
+        let mut app = App::new();
+        app.active_tab = Tab::Clients;
+        app.clients = vec![client(7).named("Acme").address("1 Road")];
+        let mut theirs = project(2).around_today(-3, 200);
+        theirs.client_id = app.clients[0].id;
+        app.projects = vec![project(1).around_today(-30, 40), theirs];
+        app.radar_state.selected_index = Some(0);
+
+        app.handle_key(key(KeyCode::Enter));
+        assert_eq!(app.active_tab, Tab::Timeline);
+        assert_eq!(app.project_filter.client_id, Some(app.clients[0].id));
+        assert_eq!(app.radar_state.selected_index, Some(1));
+        assert!(app.radar_state.range_days > 200.0, "zoomed to fit the client's deadline");
+
+        app.handle_key(key(KeyCode::Esc));
+        assert!(!app.project_filter.is_active());
+        assert_eq!(app.visible_projects(), vec![0, 1]);
+    }
+
+    #[test]
+    fn test_filter_popup_text_row() {
+        let mut app = App::new();
+        app.projects = vec![project(1).around_today(-30, 40), project(2).around_today(-3, 9)];
+        app.handle_key(key(KeyCode::Char('F')));
+        app.handle_key(key(KeyCode::Up));
+        for c in "p2x".chars() {
+            app.handle_key(key(KeyCode::Char(c)));
+        }
+        app.handle_key(key(KeyCode::Backspace));
+        app.handle_key(key(KeyCode::Enter));
+        assert_eq!(app.project_filter.text.as_deref(), Some("p2"));
+        assert_eq!(app.visible_projects(), vec![1]);
+    }
+
+    #[test]
+    fn test_restored_zoom_is_not_refitted() {
+        let mut app = App::new();
+        let mut state = app.ui_state();
+        state.range_days = 42.0;
+        app.restore_ui_state(state);
+        app.handle_api_message(ApiMessage::ProjectsLoaded(vec![project(1).around_today(-5, 200)].into()));
+        assert_eq!(app.radar_state.range_days, 42.0);
+    }
+
+    #[test]
+    fn test_alt_arrows_preview_and_save_a_date_shift() {
+        let id = Uuid::from_u128(1);
+        let mut app = App::new();
+        app.projects = vec![project(1).named("Apollo").ends("2099-01-01")];
+        app.radar_state.selected_index = Some(0);
+        let alt = |code, extra| KeyEvent::new(code, KeyModifiers::ALT | extra);
+        let date = |s: &str| s.parse::<chrono::NaiveDate>().unwrap();
+
+        assert!(app.handle_key(alt(KeyCode::Right, KeyModifiers::SHIFT)).is_none());
+        assert!(app.handle_key(alt(KeyCode::Left, KeyModifiers::NONE)).is_none());
+        let edit = app.radar_state.pending_edit.unwrap();
+        assert_eq!(edit.planned_end_date, date("2099-01-07"));
+        assert_eq!(edit.start_date, date("2024-01-01"));
+        // Nothing is saved until Enter
+        assert_eq!(app.projects[0].planned_end_date, date("2099-01-01"));
+
+        match app.handle_key(key(KeyCode::Enter)) {
+            Some(ApiCommand::UpdateProject(target, dto)) => {
+                assert_eq!(target, id);
+                assert_eq!(dto.planned_end_date, date("2099-01-07"));
+                assert_eq!(dto.start_date, date("2024-01-01"));
+            }
+            other => panic!("expected UpdateProject, got {:?}", other),
+        }
+        assert!(app.radar_state.pending_edit.is_none());
+        assert!(app.logs.back().unwrap().message.contains("2099-01-01 → 2099-01-07 (+6d)"));
+    }
+
+    #[test]
+    fn test_date_shift_moves_whole_project_and_keeps_end_after_start() {
+        let mut app = App::new();
+        app.projects = vec![project(1).named("Apollo").ends("2024-01-02")];
+        app.radar_state.selected_index = Some(0);
+        let alt = |code, extra| KeyEvent::new(code, KeyModifiers::ALT | extra);
+
+        app.handle_key(alt(KeyCode::Left, KeyModifiers::NONE));
+        assert_eq!(app.radar_state.pending_edit.unwrap().planned_end_date.to_string(), "2024-01-01");
+        // A deadline before the start is refused
+        app.handle_key(alt(KeyCode::Left, KeyModifiers::NONE));
+        assert_eq!(app.radar_state.pending_edit.unwrap().planned_end_date.to_string(), "2024-01-01");
+        assert!(app.logs.back().unwrap().message.contains("before the start date"));
+
+        app.handle_key(alt(KeyCode::Left, KeyModifiers::CONTROL | KeyModifiers::SHIFT));
+        let edit = app.radar_state.pending_edit.unwrap();
+        assert_eq!(edit.start_date.to_string(), "2023-12-25");
+        assert_eq!(edit.planned_end_date.to_string(), "2023-12-25");
+
+        // Esc drops the preview without sending anything
+        assert!(app.handle_key(key(KeyCode::Esc)).is_none());
+        assert!(app.radar_state.pending_edit.is_none());
+
+        app.read_only = true;
+        app.handle_key(alt(KeyCode::Right, KeyModifiers::NONE));
+        assert!(app.radar_state.pending_edit.is_none());
+    }
+
+    #[test]
+    fn test_created_project_is_selected_and_flashed_once_loaded() {
+        let mut app = App::new();
+        app.projects = vec![project(1).around_today(-5, 5), project(2).around_today(-5, 8)];
+        app.radar_state.selected_index = Some(0);
+        let new = project(3).around_today(0, 200);
+
+        app.handle_api_message(ApiMessage::Created(EntityType::Project, new.id));
+        // A reload without the new project keeps waiting for it
+        app.handle_api_message(ApiMessage::ProjectsLoaded(app.projects.clone().into()));
+        assert_eq!(app.radar_state.selected_index, Some(0));
+        assert_eq!(app.flashing(), None);
+
+        app.handle_api_message(ApiMessage::OneLoaded(FetchedEntity::Project(new.clone())));
+        let selected = app.radar_state.selected_index.unwrap();
+        assert_eq!(app.projects[selected].id, new.id);
+        assert!(app.radar_state.range_days >= 200.0);
+        assert_eq!(app.flashing(), Some(new.id));
+
+        // The pulse blinks, then stops for good
+        for _ in 0..FLASH_PULSE_FRAMES {
+            app.tick(80, 24);
+        }
+        assert_eq!(app.flashing(), None);
+        for _ in 0..FLASH_FRAMES {
+            app.tick(80, 24);
+        }
+        assert!(app.flash.is_none());
+
+        // Later reloads leave the selection to the user
+        app.radar_state.selected_index = Some(0);
+        app.handle_api_message(ApiMessage::ProjectsLoaded(app.projects.clone().into()));
+        assert_eq!(app.radar_state.selected_index, Some(0));
+    }
+
+    #[test]
+    fn test_created_client_is_selected_on_the_clients_tab() {
+        let mut app = App::new();
+        app.active_tab = Tab::Clients;
+        app.handle_api_message(ApiMessage::ClientsPage(client_page(1, 0..20, 20)));
+        let id = Uuid::from_u128(20);
+
+        app.handle_api_message(ApiMessage::Created(EntityType::Client, id));
+        app.handle_api_message(ApiMessage::ClientsPage(client_page(1, 0..20, 20)));
+        assert_eq!(app.list_selected, 19);
+        assert_eq!(app.flashing(), Some(id));
+    }
+
+    #[test]
+    fn test_reload_badges_and_logs_what_changed() {
+        let mut app = App::new();
+        let alpha = project(1).around_today(-5, 5);
+        let beta = project(2).around_today(-5, 8);
+        app.handle_api_message(ApiMessage::ProjectsLoaded(vec![alpha.clone(), beta.clone()].into()));
+        // The first load has nothing to compare with
+        assert!(app.active_badges().is_empty());
+
+        let mut moved = alpha.clone();
+        moved.planned_end_date += chrono::Duration::days(14);
+        let gamma = project(3).around_today(0, 20);
+        app.handle_api_message(ApiMessage::ProjectsLoaded(vec![moved.clone(), gamma.clone()].into()));
+
+        assert_eq!(app.badge(alpha.id), Some(Badge::Changed));
+        assert_eq!(app.badge(gamma.id), Some(Badge::New));
+        assert_eq!(app.badge(beta.id), None);
+        let messages: Vec<&str> = app.logs.iter().map(|l| l.message.as_str()).collect();
+        let expected = format!("Project P1: planned end {} → {}", alpha.planned_end_date, moved.planned_end_date);
+        assert!(messages.contains(&expected.as_str()));
+        assert!(app.logs.iter().any(|l| l.level == LogLevel::Warning && l.message == "Project P2 was removed"));
+
+        // Expired badges are dropped on the next tick
+        for (_, until) in app.badges.values_mut() {
+            *until = Instant::now();
+        }
+        app.tick(80, 24);
+        assert!(app.badges.is_empty());
+    }
+
+    #[test]
+    fn test_archive_toggle_hides_long_completed_projects() {
+        let mut app = App::new();
+        app.active_tab = Tab::Timeline;
+        app.projects = vec![project(1).around_today(-400, -200).done(), project(2).around_today(-5, 5)];
+        app.radar_state.selected_index = Some(0);
+        assert_eq!(app.visible_projects(), vec![0, 1]);
+
+        app.handle_key(key(KeyCode::Char('H')));
+        assert_eq!(app.project_filter.archive_after_days, Some(DEFAULT_ARCHIVE_DAYS));
+        assert_eq!(app.visible_projects(), vec![1]);
+        assert_eq!(app.radar_state.selected_index, Some(1));
+        assert_eq!(app.exported_projects().len(), 1);
+
+        // Clearing the filter keeps the archive layer
+        app.set_project_filter(ProjectFilter { text: Some("P".to_string()), ..app.project_filter.cleared() });
+        app.handle_key(key(KeyCode::Esc));
+        assert_eq!(app.project_filter.archive_after_days, Some(DEFAULT_ARCHIVE_DAYS));
+
+        app.handle_key(key(KeyCode::Char('H')));
+        assert_eq!(app.visible_projects(), vec![0, 1]);
+        assert_eq!(app.exported_projects().len(), 2);
+    }
+
+    #[test]
+    fn test_quick_complete_celebrates_on_success() {
+        let id = Uuid::from_u128(1);
+        let mut app = App::new();
+        app.projects = vec![project(1).named("Apollo").ends("2099-01-01")];
+        app.radar_state.selected_index = Some(0);
+
+        match app.handle_key(key(KeyCode::Char('x'))) {
+            Some(ApiCommand::UpdateProject(target, dto)) => {
+                assert_eq!(target, id);
+                assert_eq!(dto.actual_end_date, Some(chrono::Local::now().date_naive()));
+            }
+            other => panic!("expected UpdateProject, got {:?}", other),
+        }
+        assert!(app.celebration.is_none());
+
+        app.handle_api_message(ApiMessage::Updated(EntityType::Project, Uuid::new_v4()));
+        let burst = app.celebration.as_ref().unwrap();
+        assert_eq!(burst.origin, radar::blip_position(&app.projects[0], app.radar_state.range_days));
+
+        for _ in 0..crate::particles::CELEBRATION_FRAMES {
+            app.tick(80, 24);
+        }
+        assert!(app.celebration.is_none());
+
+        // Ordinary edits and failed completions don't celebrate
+        app.handle_api_message(ApiMessage::Updated(EntityType::Project, Uuid::new_v4()));
+        assert!(app.celebration.is_none());
+        app.handle_key(key(KeyCode::Char('x')));
+        app.handle_api_message(ApiMessage::Failed(ApiOperation::Update(EntityType::Project), ApiError::Timeout));
+        app.dismiss_error();
+        app.handle_api_message(ApiMessage::Updated(EntityType::Project, Uuid::new_v4()));
+        assert!(app.celebration.is_none());
+    }
+
+    #[test]
+    fn test_animation_toggle_pauses_particles_and_remembers_mode() {
+        let mut app = App::new();
+        app.particle_system.set_mode(ParticleMode::Starfield);
+        app.handle_key(key(KeyCode::Char('A')));
+        assert!(!app.animations());
+        assert_eq!(app.particle_system.mode(), ParticleMode::None);
+        assert_eq!(app.ui_state().particle_mode, ParticleMode::Starfield);
+
+        let angle = app.radar_state.scan_angle;
+        app.tick(80, 24);
+        assert_eq!(app.radar_state.scan_angle, angle);
+        assert!(!app.needs_frames());
+
+        app.handle_key(key(KeyCode::Char('p')));
+        app.handle_key(key(KeyCode::Char('A')));
+        assert_eq!(app.particle_system.mode(), ParticleMode::Starfield);
+        assert!(app.needs_frames());
+    }
+
+    #[test]
+    fn test_reduced_motion_skips_celebration() {
+        let mut app = App::new();
+        app.reduced_motion = true;
+        app.projects = vec![project(1).named("Apollo").ends("2099-01-01")];
+        app.radar_state.selected_index = Some(0);
+        assert!(app.handle_key(key(KeyCode::Char('x'))).is_some());
+        app.handle_api_message(ApiMessage::Updated(EntityType::Project, Uuid::new_v4()));
+        assert!(app.celebration.is_none());
+    }
+
+    #[test]
+    fn test_delete_of_missing_entity_counts_as_success() {
+        let mut app = App::new();
+        let id = Uuid::new_v4();
+        app.in_flight = 1;
+        let message = ApiMessage::Failed(ApiOperation::Delete(EntityType::Client, id), problem(404, "gone"));
+        assert_eq!(message.stale(), Some(Stale::All(EntityType::Client)));
+
+        app.handle_api_message(message);
+        assert!(app.error_popup.is_none());
+        assert_eq!(app.in_flight, 0);
+    }
+
+    #[test]
+    fn test_validation_error_keeps_form_open() {
+        let mut app = App::new();
+        app.active_tab = Tab::Clients;
+        app.open_create_form();
+        app.in_flight = 1;
+
+        app.handle_api_message(ApiMessage::Failed(
+            ApiOperation::Create(EntityType::Client),
+            problem(400, "Name must be unique"),
+        ));
+        assert!(app.error_popup.is_none());
+        assert_eq!(app.input_mode, InputMode::Editing);
+        assert_eq!(
+            app.form_state.as_ref().unwrap().nav().error.as_deref(),
+            Some("Name must be unique (HTTP 400)")
+        );
+        assert_eq!(app.in_flight, 0);
+
+        // Other statuses still surface as a popup
+        app.handle_api_message(ApiMessage::Failed(ApiOperation::Create(EntityType::Client), problem(409, "Conflict")));
+        assert!(app.error_popup.is_some());
+    }
+
+    #[test]
+    fn test_form_ignores_input_while_saving() {
+        let mut app = App::new();
+        app.active_tab = Tab::Clients;
+        app.open_create_form();
+        for c in "Acme".chars() {
+            app.handle_key(key(KeyCode::Char(c)));
+        }
+        let form = app.form_state.as_mut().unwrap();
+        form.nav_mut().focused_field = form.nav().fields.iter().position(|f| *f == FormField::SubmitButton).unwrap();
+
+        assert!(matches!(app.handle_key(key(KeyCode::Enter)), Some(ApiCommand::CreateClient(_))));
+        assert!(app.form_state.as_ref().unwrap().nav().submitting);
+        // A second Enter, or any other key, waits for the answer
+        assert!(app.handle_key(key(KeyCode::Enter)).is_none());
+        app.handle_key(key(KeyCode::Tab));
+        assert_eq!(app.form_state.as_ref().unwrap().current_field(), FormField::SubmitButton);
+
+        // A failure unlocks the form with the error
+        app.handle_api_message(ApiMessage::Failed(
+            ApiOperation::Create(EntityType::Client),
+            problem(422, "Name must be unique"),
+        ));
+        let form = app.form_state.as_ref().unwrap();
+        assert!(!form.nav().submitting);
+        assert!(form.nav().error.is_some());
+
+        assert!(matches!(app.handle_key(key(KeyCode::Enter)), Some(ApiCommand::CreateClient(_))));
+        app.handle_api_message(ApiMessage::Created(EntityType::Client, Uuid::new_v4()));
+        assert!(app.form_state.is_none());
+    }
+
+    #[test]
+    fn test_unlikely_dates_need_a_second_save() {
+        let mut app = App::new();
+        app.clients = vec![client(7).named("Acme").address("1 Road")];
+        app.users = vec![user(1)];
+        app.open_create_form();
+        let today = chrono::Local::now().date_naive();
+        let Some(FormState::Project(form)) = &mut app.form_state else { panic!("no project form") };
+        form.name.set_value("Apollo");
+        form.start_date = format_date(&(today - chrono::Duration::days(600)), form.date_format);
+        form.end_date = format_date(&(today + chrono::Duration::days(30)), form.date_format);
+        form.nav.focused_field = form.nav.fields.iter().position(|f| *f == FormField::SubmitButton).unwrap();
+
+        assert!(app.handle_key(key(KeyCode::Enter)).is_none());
+        let Some(FormState::Project(form)) = &app.form_state else { panic!("no project form") };
+        assert_eq!(form.acknowledged, [Warning::StartLongAgo]);
+        assert!(matches!(app.handle_key(key(KeyCode::Enter)), Some(ApiCommand::CreateProject(_))));
+
+        // Editing an old project without touching its dates doesn't warn
+        let old = project(1).around_today(-900, -100).done();
+        app.projects = vec![old.clone()];
+        let form = FormState::new_edit_project(&old, &app.clients, &app.users, app.date_format, app.day_count);
+        assert!(form.project_warnings(&app.clients, &app.users, &app.projects).is_empty());
+    }
+
+    /// Open the edit form for `acme` from a fresh fetch and focus Save
+    fn edit_acme(app: &mut App) {
+        app.active_tab = Tab::Clients;
+        app.clients = vec![client(7).named("Acme").address("1 Road")];
+        assert_eq!(
+            app.handle_key(key(KeyCode::Char('e'))),
+            Some(ApiCommand::FetchOne(EntityType::Client, Uuid::from_u128(7)))
+        );
+        assert!(app.form_state.is_none());
+
+        let acme_corp = client(7).named("Acme Corp").address("1 Road");
+        app.handle_api_message(ApiMessage::FetchedOne(FetchedEntity::Client(acme_corp)));
+        let Some(FormState::Client(form)) = &mut app.form_state else { panic!("no client form") };
+        assert_eq!(form.name.value(), "Acme Corp");
+        assert!(form.nav.snapshot.is_some());
+        form.nav.focused_field = form.nav.fields.iter().position(|f| *f == FormField::SubmitButton).unwrap();
+        // The list shows the fresh copy too
+        assert_eq!(app.clients[0].display_name(), "Acme Corp");
+    }
+
+    #[test]
+    fn test_unchanged_server_copy_submits_update() {
+        let mut app = App::new();
+        edit_acme(&mut app);
+
+        let verify = app.handle_key(key(KeyCode::Enter));
+        assert_eq!(verify, Some(ApiCommand::FetchOne(EntityType::Client, Uuid::from_u128(7))));
+        assert_eq!(app.in_flight, 0);
+
+        let acme_corp = client(7).named("Acme Corp").address("1 Road");
+        let update = app.handle_api_message(ApiMessage::FetchedOne(FetchedEntity::Client(acme_corp)));
+        assert!(matches!(update, Some(ApiCommand::UpdateClient(..))));
+        assert_eq!(app.in_flight, 1);
+    }
+
+    #[test]
+    fn test_client_edit_keeps_server_counters() {
+        let mut app = App::new();
+        app.active_tab = Tab::Clients;
+        let mut client = client(7).named("Acme").address("1 Road");
+        client.projects_total = 5;
+        client.projects_completed = 2;
+        app.clients = vec![client.clone()];
+        app.handle_key(key(KeyCode::Char('e')));
+        app.handle_api_message(ApiMessage::FetchedOne(FetchedEntity::Client(client.clone())));
+        let Some(FormState::Client(form)) = &mut app.form_state else { panic!("no client form") };
+        form.name.set_value("Acme Corp");
+        form.nav.focused_field = form.nav.fields.iter().position(|f| *f == FormField::SubmitButton).unwrap();
+
+        app.handle_key(key(KeyCode::Enter));
+        match app.handle_api_message(ApiMessage::FetchedOne(FetchedEntity::Client(client))) {
+            Some(ApiCommand::UpdateClient(_, dto)) => {
+                assert_eq!(dto.name.as_deref(), Some("Acme Corp"));
+                assert_eq!((dto.projects_completed, dto.projects_total), (2, 5));
+            }
+            other => panic!("expected an update, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_confirm_edits_reviews_changes_before_sending() {
+        let mut app = App::new();
+        app.confirm_edits = true;
+        edit_acme(&mut app);
+        let Some(FormState::Client(form)) = &mut app.form_state else { panic!("no client form") };
+        form.address.set_value("2 Lane");
+
+        assert!(app.handle_key(key(KeyCode::Enter)).is_none());
+        let review = app.edit_review.as_ref().expect("no review");
+        assert_eq!(review.title, "Edit client Acme Corp");
+        assert_eq!(review.changes, [FieldChange { field: "address", old: "1 Road".into(), new: "2 Lane".into() }]);
+
+        // Esc keeps the form as typed
+        assert!(app.handle_key(key(KeyCode::Esc)).is_none());
+        assert!(app.edit_review.is_none());
+        let Some(FormState::Client(form)) = &app.form_state else { panic!("form closed") };
+        assert_eq!(form.address.value(), "2 Lane");
+
+        app.handle_key(key(KeyCode::Enter));
+        let verify = app.handle_key(key(KeyCode::Enter));
+        assert_eq!(verify, Some(ApiCommand::FetchOne(EntityType::Client, Uuid::from_u128(7))));
+        assert!(app.edit_review.is_none());
+    }
+
+    #[test]
+    fn test_confirm_edits_skips_unchanged_forms() {
+        let mut app = App::new();
+        app.confirm_edits = true;
+        edit_acme(&mut app);
+        let verify = app.handle_key(key(KeyCode::Enter));
+        assert_eq!(verify, Some(ApiCommand::FetchOne(EntityType::Client, Uuid::from_u128(7))));
+        assert!(app.edit_review.is_none());
+    }
+
+    #[test]
+    fn test_counter_mismatch_is_logged_once() {
+        let mut app = App::new();
+        let mut client = client(7).named("Acme").address("1 Road");
+        client.projects_total = 3;
+        let mut project = project(1).around_today(-10, 20);
+        project.client_id = client.id;
+        let warnings = |app: &App| app.logs.iter().filter(|l| l.level == LogLevel::Warning).count();
+
+        app.handle_api_message(ApiMessage::ClientsLoaded(vec![client.clone()].into()));
+        assert_eq!(warnings(&app), 0);
+        app.handle_api_message(ApiMessage::ProjectsLoaded(vec![project.clone()].into()));
+        assert_eq!(warnings(&app), 1);
+        assert!(app.logs.iter().any(|l| l.message.contains("Acme: server 0/3, loaded 0/1")));
+
+        // A reload with the same disagreement stays quiet
+        app.handle_api_message(ApiMessage::ClientsLoaded(vec![client.clone()].into()));
+        app.handle_api_message(ApiMessage::ProjectsLoaded(vec![project.clone()].into()));
+        assert_eq!(warnings(&app), 1);
+
+        client.projects_total = 1;
+        app.handle_api_message(ApiMessage::ClientsLoaded(vec![client].into()));
+        assert!(app.count_mismatches.is_empty());
+        assert_eq!(warnings(&app), 1);
+    }
+
+    #[test]
+    fn test_changed_server_copy_asks_before_overwriting() {
+        let mut app = App::new();
+        edit_acme(&mut app);
+        app.handle_key(key(KeyCode::Enter));
+
+        let changed = ApiMessage::FetchedOne(FetchedEntity::Client(client(7).named("Acme Ltd").address("1 Road")));
+        assert!(app.handle_api_message(changed.clone()).is_none());
+        assert!(matches!(
+            app.confirm_dialog.as_ref().unwrap().action,
+            ConfirmAction::Overwrite(_)
+        ));
+
+        // Declining returns to the form and offers a reload
+        app.handle_key(key(KeyCode::Char('n')));
+        assert_eq!(app.input_mode, InputMode::Editing);
+        assert_eq!(app.form_state.as_ref().unwrap().nav().staleness, Staleness::Stale);
+
+        // Accepting sends the update as typed
+        app.handle_key(key(KeyCode::Enter));
+        app.handle_api_message(changed);
+        match app.handle_key(key(KeyCode::Char('y'))) {
+            Some(ApiCommand::UpdateClient(_, dto)) => assert_eq!(dto.name.as_deref(), Some("Acme Corp")),
+            other => panic!("unexpected command: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_edit_falls_back_to_cached_copy_when_fetch_fails() {
+        let mut app = App::new();
+        app.active_tab = Tab::Clients;
+        app.clients = vec![client(7).named("Acme").address("1 Road")];
+        app.handle_key(key(KeyCode::Char('e')));
+        app.handle_api_message(ApiMessage::Failed(
+            ApiOperation::Fetch(EntityType::Client, Uuid::from_u128(7)),
+            ApiError::Timeout,
+        ));
+
+        let Some(FormState::Client(form)) = &app.form_state else { panic!("no client form") };
+        assert_eq!(form.name.value(), "Acme");
+        assert!(form.nav.snapshot.is_none());
+        assert!(app.error_popup.is_none());
+    }
+
+    #[test]
+    fn test_workload_heatmap_keys() {
+        let mut app = App::new();
+        app.active_tab = Tab::Users;
+        app.users = (1..=2).map(|n| user(n).named(&format!("M{}", n))).collect();
+        app.projects = vec![project(1).around_today(-10, 40)];
+
+        app.handle_key(key(KeyCode::Char('w')));
+        assert_eq!(app.workload_cursor, Some((0, 0)));
+        assert_eq!(app.key_hints(), "hjkl move · w/Esc back to the list");
+
+        // Two managers plus the row for the project's unknown manager
+        for _ in 0..5 {
+            app.handle_key(key(KeyCode::Char('j')));
+        }
+        for _ in 0..10 {
+            app.handle_key(key(KeyCode::Right));
+        }
+        assert_eq!(app.workload_cursor, Some((2, workload::MONTHS - 1)));
+        app.handle_key(key(KeyCode::Char('h')));
+        app.handle_key(key(KeyCode::Up));
+        assert_eq!(app.workload_cursor, Some((1, workload::MONTHS - 2)));
+        assert_eq!(app.list_selected, 0);
+        assert_eq!(app.workload().cell(2, 0), &[0]);
+
+        app.handle_key(key(KeyCode::Esc));
+        assert_eq!(app.workload_cursor, None);
+        app.handle_key(key(KeyCode::Char('j')));
+        assert_eq!(app.list_selected, 1);
+    }
+
+    #[test]
+    fn test_tab_takes_the_suggested_project_name() {
+        let mut app = App::new();
+        app.active_tab = Tab::Timeline;
+        app.clients = vec![client(7).named("Acme").address("1 Road")];
+        let mut phase = project(1).around_today(-10, 10);
+        phase.client_id = Uuid::from_u128(7);
+        phase.name = Some("Acme — Phase 2".to_string());
+        // Other clients' projects don't count
+        let mut other = project(2).around_today(-10, 10);
+        other.name = Some("Acme — Phase 9".to_string());
+        app.projects = vec![phase, other];
+
+        app.handle_key(key(KeyCode::Char('c')));
+        assert_eq!(app.name_suggestion(), None);
+        app.handle_key(key(KeyCode::Char('A')));
+        app.handle_key(key(KeyCode::Char('c')));
+        assert_eq!(app.name_suggestion().as_deref(), Some("me — Phase 3"));
+        assert!(app.key_hints().starts_with("Tab accept"));
+        // Typing on narrows it
+        app.handle_key(key(KeyCode::Char('m')));
+        assert_eq!(app.name_suggestion().as_deref(), Some("e — Phase 3"));
+
+        app.handle_key(key(KeyCode::Tab));
+        let form = app.form_state.as_ref().unwrap();
+        assert_eq!(form.current_field(), FormField::ProjectName);
+        let FormState::Project(project) = form else { panic!("no project form") };
+        assert_eq!(project.name.value(), "Acme — Phase 3");
+        assert_eq!(app.name_suggestion(), None);
+        app.handle_key(key(KeyCode::Tab));
+        assert_eq!(app.form_state.as_ref().unwrap().current_field(), FormField::ProjectClient);
+    }
+
+    #[test]
+    fn test_ctrl_g_generates_a_password() {
+        let mut app = App::new();
+        app.active_tab = Tab::Users;
+        app.password_length = 20;
+        app.handle_key(key(KeyCode::Char('c')));
+        // Only the Password field takes it
+        app.handle_key(ctrl('g'));
+        assert!(app.clipboard.is_none());
+        app.handle_key(key(KeyCode::Tab));
+        app.handle_key(key(KeyCode::Tab));
+        assert!(app.typing_secret());
+        assert!(app.key_hints().starts_with("^G generate"));
+
+        app.handle_key(ctrl('g'));
+        let Some(FormState::User(form)) = &app.form_state else { panic!("no user form") };
+        let password = form.password.value().to_string();
+        assert_eq!(password.len(), 20);
+        assert_eq!(app.clipboard.as_deref(), Some(password.as_str()));
+        let note = &app.logs.back().unwrap().message;
+        assert_eq!(note, "Generated a 20-character password and copied it to the clipboard");
+
+        // Another press replaces it
+        app.clipboard = None;
+        app.handle_key(ctrl('g'));
+        let Some(FormState::User(form)) = &app.form_state else { panic!("no user form") };
+        assert_ne!(form.password.value(), password);
+        assert!(app.clipboard.is_some());
+    }
+
+    #[test]
+    fn test_grouped_users_navigation_skips_headers() {
+        let mut app = App::new();
+        app.active_tab = Tab::Users;
+        app.users = vec![user(1).named("Zed"), user(2).named("Ann").role(Role::Admin), user(3).named("Bea")];
+
+        app.handle_key(key(KeyCode::Char('b')));
+        assert!(app.users_grouped);
+        // Ann (the only admin), then Bea and Zed under the Managers header
+        app.handle_key(key(KeyCode::Char('g')));
+        assert_eq!(app.list_selected, 1);
+        app.handle_key(key(KeyCode::Char('j')));
+        assert_eq!(app.list_selected, 2);
+        app.handle_key(key(KeyCode::Char('j')));
+        assert_eq!(app.list_selected, 0);
+        app.handle_key(key(KeyCode::Char('j')));
+        assert_eq!(app.list_selected, 1);
+        app.handle_key(key(KeyCode::Char('k')));
+        assert_eq!(app.list_selected, 0);
+        app.handle_key(key(KeyCode::Char('G')));
+        assert_eq!(app.list_selected, 0);
+        assert_eq!(app.selected_item().map(|(id, _)| id), Some(Uuid::from_u128(1)));
+
+        // Back to list order
+        app.handle_key(key(KeyCode::Char('b')));
+        app.handle_key(key(KeyCode::Char('j')));
+        assert_eq!(app.list_selected, 1);
+    }
+
+    #[test]
+    fn test_calendar_navigation_and_jump() {
+        let mut app = App::new();
+        app.active_tab = Tab::Timeline;
+        app.projects = vec![project(1).around_today(-20, 0), project(2).around_today(0, 30)];
+        let today = chrono::Local::now().date_naive();
+
+        app.handle_key(key(KeyCode::Char('m')));
+        assert_eq!(app.calendar.as_ref().map(|c| c.cursor), Some(today));
+        app.handle_key(key(KeyCode::Char('>')));
+        app.handle_key(key(KeyCode::Down));
+        app.handle_key(key(KeyCode::Left));
+        let moved = calendar::shift_month(today, 1) + chrono::Duration::days(6);
+        assert_eq!(app.calendar.as_ref().unwrap().cursor, moved);
+
+        // An empty day just says so
+        app.handle_key(key(KeyCode::Enter));
+        assert_eq!(app.calendar.as_ref().unwrap().day_popup, None);
+        assert!(app.logs.back().unwrap().message.starts_with("Nothing starts or is due on"));
+
+        app.handle_key(key(KeyCode::Char('t')));
+        app.handle_key(key(KeyCode::Enter));
+        assert_eq!(app.calendar.as_ref().unwrap().day_popup, Some(0));
+        assert_eq!(app.key_hints(), "j/k select · Enter jump to project · Esc close");
+        app.handle_key(key(KeyCode::Char('j')));
+        app.handle_key(key(KeyCode::Char('j')));
+        assert_eq!(app.calendar.as_ref().unwrap().day_popup, Some(1));
+
+        // Project 2 starts today, project 1 is due today: starts come first
+        app.handle_key(key(KeyCode::Char('k')));
+        app.handle_key(key(KeyCode::Enter));
+        assert!(app.calendar.is_none());
+        assert_eq!(app.radar_state.selected_index, Some(1));
+    }
+
+    #[test]
+    fn test_search_overlay_jumps_to_hits() {
+        let mut app = App::new();
+        app.projects = vec![project(1).around_today(-5, 20), project(2).around_today(-5, 30)];
+        app.clients = vec![client(10).named("Acme").address("1 P2 Plaza")];
+        app.set_project_filter(ProjectFilter { text: Some("P1".to_string()), ..app.project_filter.cleared() });
+
+        app.handle_key(ctrl('f'));
+        assert_eq!(app.input_mode, InputMode::Searching);
+        for c in "p2".chars() {
+            app.handle_key(key(KeyCode::Char(c)));
+        }
+        // Typing 'q' searches rather than quitting
+        app.handle_key(key(KeyCode::Char('q')));
+        assert!(!app.should_quit && app.search_hits().is_empty());
+        app.handle_key(key(KeyCode::Backspace));
+        assert_eq!(app.search_hits().len(), 2);
+
+        // The filter hiding P2 is cleared to show it
+        app.handle_key(key(KeyCode::Enter));
+        assert_eq!(app.input_mode, InputMode::Normal);
+        assert!(app.search.is_none());
+        assert_eq!(app.active_tab, Tab::Timeline);
+        assert!(!app.project_filter.is_active());
+        assert_eq!(app.radar_state.selected_index, Some(1));
+        assert_eq!(app.flashing(), Some(Uuid::from_u128(2)));
+
+        app.handle_key(ctrl('f'));
+        for c in "p2".chars() {
+            app.handle_key(key(KeyCode::Char(c)));
+        }
+        app.handle_key(key(KeyCode::Down));
+        app.handle_key(key(KeyCode::Down));
+        app.handle_key(key(KeyCode::Enter));
+        assert_eq!(app.active_tab, Tab::Clients);
+        assert_eq!(app.list_selected, 0);
+
+        app.handle_key(ctrl('f'));
+        app.handle_key(key(KeyCode::Esc));
+        assert_eq!((app.input_mode, app.active_tab), (InputMode::Normal, Tab::Clients));
+    }
+
+    #[test]
+    fn test_search_leaves_out_users_a_manager_cannot_open() {
+        let mut app = App::new();
+        app.users = vec![user(1).named("Ada").login("ada"), user(2).named("Kim").login("kim")];
+        app.clients = vec![client(10).named("Adaline")];
+        app.set_current_user(app.users[1].clone());
+
+        app.handle_key(ctrl('f'));
+        for c in "ada".chars() {
+            app.handle_key(key(KeyCode::Char(c)));
+        }
+        let hits = app.search_hits();
+        assert_eq!(hits.iter().map(|h| h.entity_type).collect::<Vec<_>>(), [EntityType::Client]);
+        app.handle_key(key(KeyCode::Enter));
+        assert_eq!(app.active_tab, Tab::Clients);
+
+        // Admins still find and open users
+        app.set_current_user(app.users[0].clone().role(Role::Admin));
+        app.handle_key(ctrl('f'));
+        for c in "ada".chars() {
+            app.handle_key(key(KeyCode::Char(c)));
+        }
+        let users = app.search_hits().iter().position(|h| h.entity_type == EntityType::User).unwrap();
+        for _ in 0..users {
+            app.handle_key(key(KeyCode::Down));
+        }
+        app.handle_key(key(KeyCode::Enter));
+        assert_eq!((app.active_tab, app.list_selected), (Tab::Users, 0));
+    }
+
+    #[test]
+    fn test_inline_rename_sends_only_the_new_name() {
+        let mut app = App::new();
+        app.active_tab = Tab::Timeline;
+        let mut project = project(1).around_today(-5, 20);
+        project.client_id = Uuid::from_u128(10);
+        project.manager_id = Uuid::from_u128(20);
+        app.projects = vec![project.clone()];
+        app.radar_state.selected_index = Some(0);
+
+        app.handle_key(key(KeyCode::Char('n')));
+        assert_eq!(app.inline_edit.as_ref().map(|e| e.buffer.value()), Some("P1"));
+        // Letters go to the field, not to the shortcuts
+        app.handle_key(key(KeyCode::Char('q')));
+        assert!(!app.should_quit);
+        for _ in 0..3 {
+            app.handle_key(key(KeyCode::Backspace));
+        }
+
+        // An empty name keeps the field open
+        assert!(app.handle_key(key(KeyCode::Enter)).is_none());
+        assert_eq!(app.logs.back().unwrap().message, "Name is required");
+        assert!(app.inline_edit.is_some());
+
+        for c in " Apollo ".chars() {
+            app.handle_key(key(KeyCode::Char(c)));
+        }
+        match app.handle_key(key(KeyCode::Enter)) {
+            Some(ApiCommand::UpdateProject(id, dto)) => {
+                assert_eq!(id, project.id);
+                assert_eq!(dto.name.as_deref(), Some("Apollo"));
+                assert_eq!((dto.start_date, dto.planned_end_date), (project.start_date, project.planned_end_date));
+                assert_eq!((dto.client_id, dto.manager_id), (project.client_id, project.manager_id));
             }
-            _ => {}
+            other => panic!("unexpected command: {:?}", other),
         }
+        assert!(app.inline_edit.is_none());
 
-        None
+        // Users: Esc cancels, an unchanged name sends nothing
+        app.active_tab = Tab::Users;
+        app.users = vec![user(20).named("Kim")];
+        app.handle_key(key(KeyCode::Char('n')));
+        app.handle_key(key(KeyCode::Esc));
+        assert!(app.inline_edit.is_none());
+        app.handle_key(key(KeyCode::Char('n')));
+        assert!(app.handle_key(key(KeyCode::Enter)).is_none());
+        assert_eq!(app.logs.back().unwrap().message, "Name unchanged");
+
+        app.read_only = true;
+        app.handle_key(key(KeyCode::Char('n')));
+        assert!(app.inline_edit.is_none());
     }
 
-    /// Handle form submission
-    fn handle_form_submit(&mut self) -> Option<ApiCommand> {
-        // Extract data we need from form before borrowing self mutably
-        let form = self.form_state.as_ref()?;
+    #[test]
+    fn test_losing_focus_pauses_animations_but_not_input() {
+        let mut app = App::new();
+        app.set_animations(true);
+        assert!(app.needs_frames());
 
-        // Check if cancel button is focused
-        if form.current_field() == FormField::CancelButton {
-            self.close_form();
-            return None;
-        }
+        app.set_focus(false);
+        assert!(!app.needs_frames());
+        let angle = app.radar_state.scan_angle;
+        app.tick(80, 24);
+        assert_eq!(app.radar_state.scan_angle, angle);
+        app.handle_key(key(KeyCode::Tab));
+        assert_eq!(app.active_tab, Tab::Users);
 
-        // On text input and date picker fields, Enter moves to next field instead of submitting
-        if form.current_field().is_text_input() || form.current_field().is_date_picker() {
-            if let Some(form) = &mut self.form_state {
-                form.next_field();
-            }
-            return None;
-        }
+        app.set_focus(true);
+        app.tick(80, 24);
+        assert_ne!(app.radar_state.scan_angle, angle);
+        assert!(app.needs_frames());
+    }
 
-        // Only submit if on submit button
-        if form.current_field() != FormField::SubmitButton {
-            return None;
+    #[test]
+    fn test_frame_stats_average_recent_frames() {
+        let mut stats = FrameStats::default();
+        let start = Instant::now();
+        stats.record(start, Duration::from_millis(2));
+        assert_eq!(stats.fps(), None);
+
+        stats.record(start + Duration::from_millis(50), Duration::from_millis(3));
+        stats.record(start + Duration::from_millis(150), Duration::from_millis(4));
+        assert_eq!(stats.average_interval(), Some(Duration::from_millis(75)));
+        assert!((stats.fps().unwrap() - 1000.0 / 75.0).abs() < 1e-9);
+        assert_eq!(stats.render_time, Duration::from_millis(4));
+
+        // Only the latest FRAME_SAMPLES intervals count
+        let mut at = start + Duration::from_millis(150);
+        for _ in 0..FRAME_SAMPLES {
+            at += Duration::from_millis(20);
+            stats.record(at, Duration::ZERO);
         }
+        assert_eq!(stats.average_interval(), Some(Duration::from_millis(20)));
+        assert!((stats.fps().unwrap() - 50.0).abs() < 1e-9);
+    }
 
-        // Clone the form type to avoid borrow issues
-        let form_type = form.form_type.clone();
+    #[test]
+    fn test_f12_toggles_frame_stats_anywhere() {
+        let mut app = App::new();
+        app.open_create_form();
+        app.handle_key(key(KeyCode::F(12)));
+        assert!(app.show_frame_stats);
+        assert_eq!(app.input_mode, InputMode::Editing);
+        app.handle_key(key(KeyCode::F(12)));
+        assert!(!app.show_frame_stats);
+    }
 
-        match form_type {
-            FormType::CreateClient => {
-                let form = self.form_state.as_ref()?;
-                let dto = form.build_create_client();
-                if let Err(e) = dto.validate() {
-                    if let Some(f) = &mut self.form_state {
-                        f.error = Some(e.to_string());
-                    }
-                    return None;
-                }
-                self.log(LogEntry::info("Creating client..."));
-                Some(ApiCommand::CreateClient(dto))
-            }
-            FormType::EditClient(id) => {
-                let form = self.form_state.as_ref()?;
-                let dto = form.build_update_client();
-                if let Err(e) = dto.validate() {
-                    if let Some(f) = &mut self.form_state {
-                        f.error = Some(e.to_string());
-                    }
-                    return None;
-                }
-                self.log(LogEntry::info("Updating client..."));
-                Some(ApiCommand::UpdateClient(id, dto))
-            }
-            FormType::CreateProject => {
-                let form = self.form_state.as_ref()?;
-                let dto = form.build_create_project(&self.clients, &self.users);
-                if let Err(e) = dto.validate() {
-                    if let Some(f) = &mut self.form_state {
-                        f.error = Some(e.to_string());
-                    }
-                    return None;
-                }
-                self.log(LogEntry::info("Creating project..."));
-                Some(ApiCommand::CreateProject(dto))
-            }
-            FormType::EditProject(id) => {
-                let form = self.form_state.as_ref()?;
-                let dto = form.build_update_project(&self.clients, &self.users);
-                if let Err(e) = dto.validate() {
-                    if let Some(f) = &mut self.form_state {
-                        f.error = Some(e.to_string());
-                    }
-                    return None;
-                }
-                self.log(LogEntry::info("Updating project..."));
-                Some(ApiCommand::UpdateProject(id, dto))
-            }
-            FormType::CreateUser => {
-                let form = self.form_state.as_ref()?;
-                let dto = form.build_create_user();
-                if let Err(e) = dto.validate() {
-                    if let Some(f) = &mut self.form_state {
-                        f.error = Some(e.to_string());
-                    }
-                    return None;
-                }
-                self.log(LogEntry::info("Creating user..."));
-                Some(ApiCommand::CreateUser(dto))
-            }
-            FormType::EditUser(id) => {
-                let form = self.form_state.as_ref()?;
-                let dto = form.build_update_user();
-                if let Err(e) = dto.validate() {
-                    if let Some(f) = &mut self.form_state {
-                        f.error = Some(e.to_string());
-                    }
-                    return None;
-                }
-                self.log(LogEntry::info("Updating user..."));
-                Some(ApiCommand::UpdateUser(id, dto))
-            }
+    #[test]
+    fn test_intent_and_outcome_lines_share_a_request_id() {
+        let mut app = App::new();
+        let id = Uuid::new_v4();
+        app.confirm_dialog = Some(ConfirmDialog::new_delete(EntityType::Project, id, "Apollo"));
+        app.input_mode = InputMode::Confirming;
+        let command = app.handle_key(key(KeyCode::Char('y'))).unwrap();
+        let request = app.stamp(command);
+        assert_ne!(app.next_request, request.id);
+        let intent = app.logs.back().unwrap();
+        assert_eq!((intent.message.as_str(), intent.request), ("Deleting Project...", Some(request.id)));
+
+        let outcome = ApiMessage::Deleted(EntityType::Project, id);
+        assert!(app.handle_response(Response { request: Some(request.id), message: outcome }).is_none());
+        assert_eq!(app.logs.back().unwrap().request, Some(request.id));
+        // Lines logged outside a response stay untagged
+        app.log(LogEntry::info("Idle"));
+        assert_eq!(app.logs.back().unwrap().request, None);
+    }
+
+    #[test]
+    fn test_update_after_freshness_check_keeps_the_request_id() {
+        let mut app = App::new();
+        edit_acme(&mut app);
+        let verify = app.handle_key(key(KeyCode::Enter)).unwrap();
+        let verify = app.stamp(verify);
+        assert_eq!(app.logs.back().unwrap().request, Some(verify.id));
+
+        let fetched = ApiMessage::FetchedOne(FetchedEntity::Client(client(7).named("Acme Corp").address("1 Road")));
+        let update = app.handle_response(Response { request: Some(verify.id), message: fetched }).unwrap();
+        assert!(matches!(update.command, ApiCommand::UpdateClient(..)));
+        assert_eq!(update.id, verify.id);
+
+        // Refreshes asked for in reply are requests of their own
+        let loaded = ApiMessage::ConnectionStatus(None);
+        let next = app.next_request;
+        if let Some(refresh) = app.handle_response(Response { request: Some(update.id), message: loaded }) {
+            assert_eq!(refresh.id, next);
         }
     }
 
-    /// Handle keys in confirming mode (delete dialog)
-    fn handle_confirming_key(&mut self, key: KeyEvent) -> Option<ApiCommand> {
-        if self.confirm_dialog.is_none() {
-            self.input_mode = InputMode::Normal;
-            return None;
+    #[test]
+    fn test_popup_scroll_stays_within_the_rendered_text() {
+        let mut scroll = PopupScroll::default();
+        // Before the first fit there is nothing to scroll
+        assert!(!scroll.handle_key(key(KeyCode::Left), true));
+        assert!(scroll.handle_key(key(KeyCode::PageDown), true));
+        scroll.fit(30, 10);
+        assert_eq!(scroll.offset(), 0);
+
+        assert!(scroll.overflows());
+        scroll.handle_key(key(KeyCode::PageDown), true);
+        scroll.handle_key(key(KeyCode::PageDown), true);
+        scroll.handle_key(key(KeyCode::PageDown), true);
+        assert_eq!(scroll.offset(), 20);
+        // j/k only when the popup leaves them free
+        assert!(!scroll.handle_key(key(KeyCode::Char('k')), false));
+        scroll.handle_key(key(KeyCode::Char('k')), true);
+        assert_eq!(scroll.offset(), 19);
+        // A taller terminal shows it all
+        scroll.fit(30, 40);
+        assert_eq!(scroll.offset(), 0);
+        assert!(!scroll.overflows());
+    }
+
+    #[test]
+    fn test_confirm_dialog_scrolls_without_answering() {
+        let mut app = App::new();
+        app.confirm_dialog = Some(ConfirmDialog::new_delete(EntityType::Project, Uuid::new_v4(), "Apollo"));
+        app.input_mode = InputMode::Confirming;
+        app.confirm_dialog.as_mut().unwrap().scroll.fit(12, 4);
+        assert!(app.handle_key(key(KeyCode::PageDown)).is_none());
+        assert!(app.handle_key(key(KeyCode::Char('j'))).is_none());
+        assert!(app.confirm_dialog.is_some());
+        assert_eq!(app.key_hints(), "y/n · ←→ switch · j/k scroll");
+        assert!(matches!(app.handle_key(key(KeyCode::Char('y'))), Some(ApiCommand::DeleteProject(_))));
+    }
+
+    #[test]
+    fn test_load_state_tracks_each_collection() {
+        let refused = || ApiError::Connection("tcp connect error: Connection refused".to_string());
+        let failed = |entity_type| ApiMessage::Failed(ApiOperation::Load(entity_type), problem(503, "down"));
+        let mut app = App::new();
+        assert_eq!(*app.load_state(EntityType::Client), LoadState::NotLoaded);
+
+        // Error, then success
+        app.handle_api_message(failed(EntityType::Client));
+        assert_eq!(*app.load_state(EntityType::Client), LoadState::Failed("HTTP 503".to_string()));
+        assert_eq!(*app.load_state(EntityType::User), LoadState::NotLoaded);
+        app.handle_api_message(ApiMessage::ClientsLoaded(Vec::new().into()));
+        assert_eq!(*app.load_state(EntityType::Client), LoadState::Loaded(0));
+
+        // Success, then error
+        app.handle_api_message(ApiMessage::UsersLoaded(vec![user(1).role(Role::Admin)].into()));
+        assert_eq!(*app.load_state(EntityType::User), LoadState::Loaded(1));
+        app.handle_api_message(failed(EntityType::User));
+        assert_eq!(*app.load_state(EntityType::User), LoadState::Failed("HTTP 503".to_string()));
+
+        // An unreachable server fails every collection; pages count what is held
+        app.handle_api_message(ApiMessage::Failed(ApiOperation::Connect, refused()));
+        for entity_type in [EntityType::Project, EntityType::Client, EntityType::User] {
+            assert_eq!(*app.load_state(entity_type), LoadState::Failed("connection refused".to_string()));
         }
+        app.handle_api_message(ApiMessage::ClientsPage(client_page(1, 0..20, 50)));
+        assert_eq!(*app.load_state(EntityType::Client), LoadState::Loaded(20));
+        app.handle_api_message(ApiMessage::Failed(ApiOperation::Load(EntityType::Client), ApiError::Timeout));
+        assert_eq!(*app.load_state(EntityType::Client), LoadState::Failed("timed out".to_string()));
 
-        match key.code {
-            KeyCode::Esc | KeyCode::Char('n') | KeyCode::Char('N') => {
-                self.close_confirm();
-                return None;
-            }
-            KeyCode::Left | KeyCode::Right | KeyCode::Tab => {
-                if let Some(dialog) = &mut self.confirm_dialog {
-                    dialog.yes_focused = !dialog.yes_focused;
-                }
-                return None;
-            }
-            KeyCode::Enter => {
-                if let Some(dialog) = &self.confirm_dialog {
-                    if dialog.yes_focused {
-                        let cmd = match dialog.entity_type {
-                            EntityType::Client => ApiCommand::DeleteClient(dialog.entity_id),
-                            EntityType::Project => ApiCommand::DeleteProject(dialog.entity_id),
-                            EntityType::User => ApiCommand::DeleteUser(dialog.entity_id),
-                        };
-                        self.log(LogEntry::info(format!(
-                            "Deleting {}...",
-                            dialog.entity_type
-                        )));
-                        return Some(cmd);
-                    } else {
-                        self.close_confirm();
-                    }
-                }
-                return None;
-            }
-            KeyCode::Char('y') | KeyCode::Char('Y') => {
-                if let Some(dialog) = &self.confirm_dialog {
-                    let cmd = match dialog.entity_type {
-                        EntityType::Client => ApiCommand::DeleteClient(dialog.entity_id),
-                        EntityType::Project => ApiCommand::DeleteProject(dialog.entity_id),
-                        EntityType::User => ApiCommand::DeleteUser(dialog.entity_id),
-                    };
-                    self.log(LogEntry::info(format!(
-                        "Deleting {}...",
-                        dialog.entity_type
-                    )));
-                    return Some(cmd);
-                }
-                return None;
+        // Other failures leave it alone, and a new server starts over
+        app.handle_api_message(ApiMessage::Failed(ApiOperation::Create(EntityType::Project), refused()));
+        assert_eq!(*app.load_state(EntityType::Project), LoadState::Failed("connection refused".to_string()));
+        app.clear_server_data();
+        assert_eq!(*app.load_state(EntityType::Project), LoadState::NotLoaded);
+    }
+
+    #[test]
+    fn test_confirm_default_focuses_single_item_dialogs() {
+        let mut app = App::new();
+        app.active_tab = Tab::Clients;
+        app.clients = vec![client(7).named("Acme").address("1 Road")];
+        app.handle_key(key(KeyCode::Char('d')));
+        assert_eq!(app.confirm_dialog.as_ref().unwrap().focus, ConfirmButton::No);
+        assert!(app.handle_key(key(KeyCode::Enter)).is_none());
+        assert!(app.confirm_dialog.is_none());
+
+        app.confirm_default = ConfirmButton::Yes;
+        app.handle_key(key(KeyCode::Char('d')));
+        assert_eq!(app.confirm_dialog.as_ref().unwrap().focus, ConfirmButton::Yes);
+        // Two buttons, so 'a' means nothing and the focus wraps
+        assert!(app.handle_key(key(KeyCode::Char('a'))).is_none());
+        app.handle_key(key(KeyCode::Right));
+        app.handle_key(key(KeyCode::Right));
+        assert!(matches!(app.handle_key(key(KeyCode::Enter)), Some(ApiCommand::DeleteClient(_))));
+    }
+
+    /// Clients 1 to `n`, named "Client 1" and so on, on the Clients tab
+    fn app_with_clients(n: u128) -> App {
+        let mut app = App::new();
+        app.active_tab = Tab::Clients;
+        app.clients = (1..=n)
+            .map(|i| client(i).named(&format!("Client {}", i)).address("1 Road"))
+            .collect();
+        app
+    }
+
+    /// Mark clients 1, 2 and 4 and ask to delete them
+    fn mark_and_delete(app: &mut App) {
+        for (row, mark) in [true, true, false, true].into_iter().enumerate() {
+            app.list_selected = row;
+            if mark {
+                app.handle_key(key(KeyCode::Char(' ')));
             }
-            _ => {}
         }
+        app.handle_key(key(KeyCode::Char('d')));
+    }
 
-        None
+    fn delete_failed(id: u128) -> ApiMessage {
+        ApiMessage::Failed(ApiOperation::Delete(EntityType::Client, Uuid::from_u128(id)), problem(500, "boom"))
     }
 
-    /// Handle timeline-specific key events
-    fn handle_timeline_key(&mut self, key: KeyEvent) {
-        match key.code {
-            KeyCode::Char('j') | KeyCode::Down | KeyCode::Right => {
-                self.radar_state.select_next(self.projects.len());
-            }
-            KeyCode::Char('k') | KeyCode::Up | KeyCode::Left => {
-                self.radar_state.select_prev(self.projects.len());
-            }
-            KeyCode::Char('+') | KeyCode::Char('=') => {
-                self.radar_state.zoom_in();
-            }
-            KeyCode::Char('-') => {
-                self.radar_state.zoom_out();
-            }
-            _ => {}
+    #[test]
+    fn test_marked_items_are_deleted_one_by_one() {
+        let mut app = app_with_clients(4);
+        app.confirm_default = ConfirmButton::Yes;
+        app.list_selected = 1;
+        app.handle_key(key(KeyCode::Char(' ')));
+        app.handle_key(key(KeyCode::Char(' ')));
+        assert!(app.marked.is_empty(), "Space toggles");
+        app.handle_key(key(KeyCode::Char(' ')));
+        app.handle_key(key(KeyCode::Esc));
+        assert!(app.marked.is_empty());
+
+        mark_and_delete(&mut app);
+        let dialog = app.confirm_dialog.as_ref().unwrap();
+        assert_eq!(dialog.title, "Delete 3 Clients");
+        assert!(dialog.message.starts_with("Delete 3 items?\n\"Client 1\", \"Client 2\", \"Client 4\"\n"));
+        // Batches always open on No
+        assert_eq!(dialog.focus, ConfirmButton::No);
+        assert_eq!(app.key_hints(), "y/a/n · a = yes to all · ←→ switch");
+        app.handle_key(key(KeyCode::Left));
+        assert_eq!(app.confirm_dialog.as_ref().unwrap().focus, ConfirmButton::All);
+
+        assert_eq!(app.handle_key(key(KeyCode::Enter)), Some(ApiCommand::DeleteClient(Uuid::from_u128(1))));
+        assert!(app.confirm_dialog.is_none());
+        assert!(app.marked.is_empty());
+        assert_eq!(app.in_flight, 1);
+
+        // A failure is logged and skipped
+        let next = app.handle_api_message(delete_failed(1));
+        assert_eq!(next, Some(ApiCommand::DeleteClient(Uuid::from_u128(2))));
+        assert_eq!(app.logs.back().unwrap().level, LogLevel::Error);
+        assert!(app.error_popup.is_none());
+        let next = app.handle_api_message(ApiMessage::Deleted(EntityType::Client, Uuid::from_u128(2)));
+        assert_eq!(next, Some(ApiCommand::DeleteClient(Uuid::from_u128(4))));
+        assert_eq!(app.logs.back().unwrap().message, "Client \"Client 2\" deleted (2/3)");
+        assert_eq!(app.in_flight, 1);
+
+        // Gone already counts as deleted
+        let gone = ApiOperation::Delete(EntityType::Client, Uuid::from_u128(4));
+        let gone = ApiMessage::Failed(gone, problem(404, "gone"));
+        assert!(app.handle_api_message(gone).is_none());
+        assert!(app.batch_delete.is_none());
+        assert_eq!(app.in_flight, 0);
+        let names: Vec<&str> = app.clients.iter().map(|c| c.display_name()).collect();
+        assert_eq!(names, ["Client 1", "Client 3"]);
+        let popup = app.error_popup.as_ref().unwrap();
+        assert_eq!(popup.title, "Delete Incomplete");
+        assert!(popup.message.starts_with("2 deleted, 1 failed\n\"Client 1\": "), "{}", popup.message);
+    }
+
+    #[test]
+    fn test_plain_yes_stops_a_batch_at_the_first_failure() {
+        let mut app = app_with_clients(4);
+        mark_and_delete(&mut app);
+        assert_eq!(app.handle_key(key(KeyCode::Char('y'))), Some(ApiCommand::DeleteClient(Uuid::from_u128(1))));
+        let next = app.handle_api_message(ApiMessage::Deleted(EntityType::Client, Uuid::from_u128(1)));
+        assert_eq!(next, Some(ApiCommand::DeleteClient(Uuid::from_u128(2))));
+        assert!(app.handle_api_message(delete_failed(2)).is_none());
+        assert!(app.batch_delete.is_none());
+        assert!(app.error_popup.as_ref().unwrap().message.starts_with("1 deleted, 1 failed, 1 not attempted\n"));
+
+        // With every item gone, the batch ends with a toast instead
+        let mut app = app_with_clients(4);
+        mark_and_delete(&mut app);
+        app.handle_key(key(KeyCode::Char('a')));
+        for id in [1, 2, 4] {
+            app.handle_api_message(ApiMessage::Deleted(EntityType::Client, Uuid::from_u128(id)));
         }
+        assert!(app.error_popup.is_none());
+        assert_eq!(app.logs.back().unwrap().message, "Deleted 3 clients");
+        assert_eq!(app.clients.len(), 1);
     }
 
-    // Обнови jump_to_selected_project
-    fn jump_to_selected_project(&mut self) {
-        if let Some(idx) = self.radar_state.selected_index {
-            if let Some(project) = self.projects.get(idx) {
-                // Use approximate viewport width
-                let viewport_width = 100u16;
-                self.radar_state
-                    .jump_to_project(project, &self.projects, viewport_width);
+    #[test]
+    fn test_timeline_enter_follows_the_configured_action() {
+        let mut app = App::new();
+        app.projects = vec![project(1).around_today(-10, 400)];
+        assert!(app.handle_key(key(KeyCode::Enter)).is_none());
+        assert!(!app.details_popup, "nothing selected");
+
+        app.radar_state.selected_index = Some(0);
+        app.handle_key(key(KeyCode::Enter));
+        assert!(app.details_popup);
+        assert_eq!(app.key_hints(), "Esc/Enter close");
+        app.handle_key(key(KeyCode::Esc));
+        assert!(!app.details_popup);
+
+        app.enter_action = EnterAction::Edit;
+        assert!(app.key_hints().starts_with("? help · Enter edit"));
+        assert_eq!(
+            app.handle_key(key(KeyCode::Enter)),
+            Some(ApiCommand::FetchOne(EntityType::Project, Uuid::from_u128(1)))
+        );
+        app.read_only = true;
+        assert!(app.handle_key(key(KeyCode::Enter)).is_none());
+
+        app.enter_action = EnterAction::Center;
+        let range = app.radar_state.range_days;
+        app.handle_key(key(KeyCode::Enter));
+        assert!(app.radar_state.range_days > range);
+        assert_eq!(app.flashing(), Some(Uuid::from_u128(1)));
+        assert!(!app.details_popup);
+    }
+
+    #[test]
+    fn test_dot_brings_the_selection_into_view() {
+        let mut app = App::new();
+        app.projects = vec![project(1).around_today(-10, 400)];
+        app.radar_state.selected_index = Some(0);
+        app.handle_key(key(KeyCode::Char('.')));
+        assert!(app.radar_state.range_days >= 400.0);
+        assert_eq!(app.flashing(), Some(Uuid::from_u128(1)));
+    }
+
+    #[test]
+    fn test_radar_follows_selection_only_when_on() {
+        let mut app = App::new();
+        app.projects = vec![
+            project(1).around_today(-30, 10),
+            project(2).around_today(-10, 100),
+            project(3).around_today(-10, 5),
+        ];
+        app.radar_state.selected_index = Some(0);
+        app.radar_state.range_days = 30.0;
+
+        // In view: nothing moves
+        app.handle_key(key(KeyCode::Char('k')));
+        assert_eq!(app.radar_state.selected_index, Some(2));
+        assert_eq!(app.radar_state.range_days, 30.0);
+        // Past the rim: zoom out just enough
+        app.handle_key(key(KeyCode::Char('k')));
+        assert_eq!(app.radar_state.range_days, 112.0);
+
+        app.radar_state.range_days = 30.0;
+        app.handle_key(key(KeyCode::Char('o')));
+        assert!(!app.follow_selection);
+        app.handle_key(key(KeyCode::Char('j')));
+        app.handle_key(key(KeyCode::Char('k')));
+        assert_eq!(app.radar_state.selected_index, Some(1));
+        assert_eq!(app.radar_state.range_days, 30.0);
+
+        // '.' still brings it into view, as does turning follow back on
+        app.handle_key(key(KeyCode::Char('.')));
+        assert_eq!(app.radar_state.range_days, 112.0);
+        app.radar_state.range_days = 30.0;
+        app.handle_key(key(KeyCode::Char('o')));
+        assert_eq!(app.radar_state.range_days, 112.0);
+    }
+
+    #[test]
+    fn test_edge_keys_select_and_zoom_out() {
+        let mut app = App::new();
+        app.projects = vec![
+            project(1).around_today(-30, 10),
+            project(2).around_today(-90, 20).done(),
+            project(3).around_today(5, 200),
+            project(4).around_today(-10, 50),
+        ];
+        app.radar_state.range_days = 14.0;
+
+        app.handle_key(key(KeyCode::Char('G')));
+        assert_eq!(app.radar_state.selected_index, Some(3));
+        // The deadline 50 days out, rounded up a week past the rim
+        assert_eq!(app.radar_state.range_days, 63.0);
+        assert_eq!(app.flashing(), Some(Uuid::from_u128(4)));
+        app.handle_key(key(KeyCode::Char('g')));
+        assert_eq!(app.radar_state.selected_index, Some(0));
+
+        app.handle_key(key(KeyCode::Char('$')));
+        assert_eq!(app.radar_state.selected_index, Some(2));
+        assert_eq!(app.radar_state.range_days, 210.0);
+
+        // The earliest start is a finished project, whose blip sits in the centre
+        app.handle_key(key(KeyCode::Home));
+        assert_eq!(app.radar_state.selected_index, Some(1));
+        assert_eq!(app.radar_state.range_days, 210.0);
+        app.handle_key(key(KeyCode::End));
+        assert_eq!(app.radar_state.selected_index, Some(2));
+    }
+
+    #[test]
+    fn test_upcoming_list_focus_selects_and_jumps() {
+        let mut app = App::new();
+        app.projects = vec![
+            project(1).around_today(-10, 200),
+            project(2).around_today(-10, 5),
+            project(3).around_today(-10, 2).done(),
+            project(4).around_today(-10, 30),
+        ];
+        app.radar_state.selected_index = Some(0);
+        app.handle_key(key(KeyCode::Char('u')));
+        assert_eq!(app.focused_pane(), Pane::Secondary);
+        assert_eq!(app.radar_state.selected_index, Some(1));
+        assert_eq!(app.key_hints(), "j/k select · Enter show on radar · u/Esc/^W back to the radar");
+
+        app.handle_key(key(KeyCode::Char('j')));
+        app.handle_key(key(KeyCode::Char('j')));
+        app.handle_key(key(KeyCode::Char('j')));
+        assert_eq!(app.upcoming_row, 2);
+        assert_eq!(app.radar_state.selected_index, Some(0));
+
+        app.radar_state.range_days = 14.0;
+        app.handle_key(key(KeyCode::Enter));
+        assert_eq!(app.focused_pane(), Pane::Primary);
+        assert_eq!(app.radar_state.selected_index, Some(0));
+        assert!(app.radar_state.range_days >= 200.0);
+        assert_eq!(app.flashing(), Some(Uuid::from_u128(1)));
+        assert!(!app.details_popup);
+
+        // Nothing to focus without open deadlines
+        app.projects.clear();
+        app.handle_key(key(KeyCode::Char('u')));
+        assert_eq!(app.focused_pane(), Pane::Primary);
+    }
+
+    #[test]
+    fn test_unloaded_names_are_fetched_once() {
+        let mut app = App::new();
+        let gone = Uuid::from_u128(0xab12cd00_0000_4000_8000_000000000000);
+        let mut project = project(1).around_today(-5, 10);
+        (project.client_id, project.manager_id) = (Uuid::from_u128(7), gone);
+        app.handle_api_message(ApiMessage::ProjectsLoaded(vec![project].into()));
+        // Drawing doesn't ask for anything, and the lists aren't in yet
+        assert_eq!(app.resolve_client_name(Uuid::from_u128(7)), "loading…");
+        assert_eq!(app.resolve_user_name(gone), "loading…");
+        assert_eq!(app.resolve_user_name(Uuid::nil()), "none");
+        assert!(app.lookup_fetches().is_empty());
+
+        app.handle_api_message(ApiMessage::ClientsLoaded(Vec::new().into()));
+        app.handle_api_message(ApiMessage::UsersLoaded(Vec::new().into()));
+        let mut fetches = app.lookup_fetches();
+        fetches.sort_by_key(|c| format!("{:?}", c));
+        assert_eq!(
+            fetches,
+            [ApiCommand::RefreshOne(EntityType::Client, Uuid::from_u128(7)), ApiCommand::RefreshOne(EntityType::User, gone)]
+        );
+        // Another load while they are fetched asks for nothing new
+        app.handle_api_message(ApiMessage::UsersLoaded(Vec::new().into()));
+        assert!(app.lookup_fetches().is_empty());
+
+        app.handle_api_message(ApiMessage::OneLoaded(FetchedEntity::Client(client(7).named("Acme").address("1 Road"))));
+        assert_eq!(app.resolve_client_name(Uuid::from_u128(7)), "Acme");
+        app.handle_api_message(ApiMessage::Failed(ApiOperation::Fetch(EntityType::User, gone), problem(404, "gone")));
+        assert_eq!(app.resolve_user_name(gone), "deleted (ab12cd…)");
+        assert!(app.error_popup.is_none());
+        assert!(app.lookup_fetches().is_empty());
+        assert_eq!(report::Names::user_name(&app, Uuid::from_u128(5)), "unknown (000000…)");
+        assert!(app.lookup_fetches().is_empty());
+
+        // A filter can point at a client no list holds
+        let filter = ProjectFilter { client_id: Some(Uuid::from_u128(9)), ..Default::default() };
+        app.set_project_filter(filter);
+        assert_eq!(app.lookup_fetches(), [ApiCommand::RefreshOne(EntityType::Client, Uuid::from_u128(9))]);
+    }
+
+    #[test]
+    fn test_client_project_pane_edits_and_moves_projects() {
+        let mut app = App::new();
+        app.active_tab = Tab::Clients;
+        app.clients = vec![client(7).named("Acme"), client(98).named("Globex"), client(99).named("Initech")];
+        let apollo = project(1).around_today(-10, 20).client(7);
+        app.projects = vec![apollo.clone()];
+        app.handle_key(ctrl('w'));
+
+        // e edits the project, not the client
+        assert_eq!(app.handle_key(key(KeyCode::Char('e'))), Some(ApiCommand::FetchOne(EntityType::Project, apollo.id)));
+        app.handle_api_message(ApiMessage::Failed(
+            ApiOperation::Fetch(EntityType::Project, apollo.id),
+            ApiError::Timeout,
+        ));
+        assert_eq!(app.form_state.as_ref().unwrap().form_type(), FormType::EditProject(apollo.id));
+        app.handle_key(key(KeyCode::Esc));
+
+        // m picks another client by name; the current one isn't offered
+        app.handle_key(key(KeyCode::Char('m')));
+        assert_eq!(app.move_targets().len(), 2);
+        for c in "tech".chars() {
+            app.handle_key(key(KeyCode::Char(c)));
+        }
+        assert_eq!(app.move_targets(), [(2, 3..7)]);
+        match app.handle_key(key(KeyCode::Enter)) {
+            Some(ApiCommand::UpdateProject(id, dto)) => {
+                assert_eq!(id, apollo.id);
+                let expected = UpdateProjectDto { client_id: Uuid::from_u128(99), ..UpdateProjectDto::from_project(&apollo) };
+                assert_eq!(dto, expected);
             }
+            other => panic!("unexpected command: {:?}", other),
         }
+        assert!(app.move_picker.is_none());
+        assert_eq!(app.in_flight, 1);
     }
 
-    // Обнови auto_center_timeline
-    fn auto_center_timeline(&mut self) {
-        if self.projects.is_empty() {
-            self.radar_state.center_on_today(&self.projects, 100);
-            return;
+    #[test]
+    fn test_ctrl_w_cycles_panes_per_tab() {
+        let mut app = App::new();
+        app.clients = vec![client(7).named("Acme").address("1 Road"), client(7).named("Globex").address("1 Road")];
+        app.clients[1].id = Uuid::from_u128(99);
+        app.projects = vec![
+            project(1).around_today(-10, 20),
+            project(2).around_today(-10, 5),
+            project(3).around_today(-10, 40).done(),
+        ];
+        for project in &mut app.projects {
+            project.client_id = Uuid::from_u128(7);
         }
+        app.radar_state.selected_index = Some(0);
+        assert_eq!(app.focused_pane(), Pane::Primary);
+
+        // Timeline: the radar, then the upcoming deadlines
+        app.handle_key(ctrl('w'));
+        assert_eq!(app.focused_pane(), Pane::Secondary);
+        assert_eq!(app.radar_state.selected_index, Some(1));
+        app.handle_key(ctrl('w'));
+        assert_eq!(app.focused_pane(), Pane::Agenda);
+        app.handle_key(ctrl('w'));
+        assert_eq!(app.focused_pane(), Pane::Primary);
+        app.handle_key(ctrl('l'));
+        assert_eq!(app.focused_pane(), Pane::Secondary);
+
+        // Clients: the list, then the selected client's projects
+        app.active_tab = Tab::Clients;
+        assert_eq!(app.focused_pane(), Pane::Primary);
+        app.handle_key(ctrl('w'));
+        assert_eq!(app.focused_pane(), Pane::Secondary);
+        assert_eq!(app.client_projects(), [1, 0, 2]);
+        app.handle_key(key(KeyCode::Char('j')));
+        app.handle_key(key(KeyCode::Char('j')));
+        app.handle_key(key(KeyCode::Char('j')));
+        assert_eq!(app.client_project_row, 2);
+        assert_eq!(app.list_selected, 0);
+        app.handle_key(key(KeyCode::Char('k')));
+        assert_eq!(app.client_project_row, 1);
+
+        // Each tab keeps its own focus
+        app.active_tab = Tab::Timeline;
+        assert_eq!(app.focused_pane(), Pane::Secondary);
+        app.handle_key(ctrl('h'));
+        assert_eq!(app.focused_pane(), Pane::Primary);
+
+        // Enter jumps to the project on the radar
+        app.active_tab = Tab::Clients;
+        app.handle_key(key(KeyCode::Enter));
+        assert_eq!(app.active_tab, Tab::Timeline);
+        assert_eq!(app.radar_state.selected_index, Some(0));
+        assert_eq!(app.flashing(), Some(Uuid::from_u128(1)));
+
+        // A client without projects has nothing to focus, nor does Users
+        app.active_tab = Tab::Clients;
+        app.handle_key(key(KeyCode::Esc));
+        app.handle_key(key(KeyCode::Char('j')));
+        assert_eq!(app.list_selected, 1);
+        app.handle_key(ctrl('w'));
+        assert_eq!(app.focused_pane(), Pane::Primary);
+        app.active_tab = Tab::Users;
+        app.handle_key(ctrl('w'));
+        assert_eq!(app.focused_pane(), Pane::Primary);
+    }
+
+    #[test]
+    fn test_agenda_strip_filters_to_a_day() {
+        let mut app = App::new();
+        app.projects = vec![
+            project(1).around_today(-10, 1),
+            project(2).around_today(-10, 1),
+            project(3).around_today(-10, 20),
+        ];
+        app.radar_state.selected_index = Some(2);
+        let days = app.agenda();
+        assert_eq!(days.iter().map(|d| d.count).collect::<Vec<_>>()[..3], [0, 2, 0]);
 
-        let idx = self.radar_state.selected_index.unwrap_or(0);
-        if let Some(project) = self.projects.get(idx) {
-            self.radar_state
-                .jump_to_project(project, &self.projects, 100);
+        // The radar, the upcoming deadlines, then the strip
+        app.handle_key(ctrl('w'));
+        app.handle_key(ctrl('w'));
+        assert_eq!(app.focused_pane(), Pane::Agenda);
+        assert_eq!(app.agenda_day, 0);
+        app.handle_key(key(KeyCode::Right));
+        assert_eq!(app.project_filter.due, Some(days[1].date));
+        assert_eq!(app.visible_projects(), [0, 1]);
+        assert_eq!(app.radar_state.selected_index, Some(0));
+        // The strip keeps counting the other days
+        assert_eq!(app.agenda()[1].count, 2);
+        assert_eq!(app.agenda().iter().map(|d| d.count).sum::<usize>(), 2);
+        for _ in 0..20 {
+            app.handle_key(key(KeyCode::Right));
         }
+        assert_eq!(app.agenda_day, AGENDA_DAYS - 1);
+        assert!(app.visible_projects().is_empty());
+
+        // Esc leaves the strip, and again clears the day
+        app.handle_key(key(KeyCode::Esc));
+        assert_eq!(app.focused_pane(), Pane::Primary);
+        app.handle_key(key(KeyCode::Esc));
+        assert_eq!(app.project_filter.due, None);
+
+        // Zoomed out past the strip's range it can't be focused
+        app.radar_state.range_days = AGENDA_MAX_RANGE_DAYS + 7.0;
+        app.handle_key(ctrl('w'));
+        app.handle_key(ctrl('w'));
+        assert_eq!(app.focused_pane(), Pane::Primary);
     }
 
-    /// Handle list view key events
-    fn handle_list_key(&mut self, key: KeyEvent, total: usize) {
-        if total == 0 {
-            return;
+    fn change(minute: u32, field: &str) -> ChangeRecord {
+        ChangeRecord {
+            timestamp: format!("2024-05-01T12:{:02}:00Z", minute).parse().unwrap(),
+            user: Some("ada".to_string()),
+            field: field.to_string(),
+            old: None,
+            new: Some("x".to_string()),
         }
+    }
 
-        match key.code {
-            KeyCode::Char('j') | KeyCode::Down => {
-                self.list_selected = (self.list_selected + 1) % total;
-            }
-            KeyCode::Char('k') | KeyCode::Up => {
-                self.list_selected = self.list_selected.checked_sub(1).unwrap_or(total - 1);
-            }
-            KeyCode::Char('g') => {
-                self.list_selected = 0;
-            }
-            KeyCode::Char('G') => {
-                self.list_selected = total.saturating_sub(1);
-            }
-            _ => {}
+    #[test]
+    fn test_history_popup_shows_newest_first() {
+        let mut app = App::new();
+        let id = Uuid::from_u128(1);
+        app.projects = vec![project(1).around_today(-10, 400)];
+        assert!(app.handle_key(key(KeyCode::Char('i'))).is_none(), "nothing selected");
+
+        app.radar_state.selected_index = Some(0);
+        assert_eq!(app.handle_key(key(KeyCode::Char('i'))), Some(ApiCommand::FetchHistory(id)));
+        assert_eq!(app.history.as_ref().unwrap().records, None);
+        // Another project's history arriving late is dropped
+        app.handle_api_message(ApiMessage::HistoryLoaded(Uuid::from_u128(2), vec![change(0, "name")]));
+        assert_eq!(app.history.as_ref().unwrap().records, None);
+
+        app.handle_api_message(ApiMessage::HistoryLoaded(id, vec![change(5, "name"), change(40, "plannedEndDate")]));
+        let Some(Ok(records)) = &app.history.as_ref().unwrap().records else { panic!("history not loaded") };
+        let fields: Vec<&str> = records.iter().map(|r| r.field.as_str()).collect();
+        assert_eq!(fields, ["plannedEndDate", "name"]);
+        assert_eq!(app.key_hints(), "Esc/i close");
+        app.handle_key(key(KeyCode::Char('j')));
+        assert!(app.history.is_some(), "scroll keys stay in the popup");
+        app.handle_key(key(KeyCode::Esc));
+        assert!(app.history.is_none());
+
+        // Other failures stay in the popup
+        app.handle_key(key(KeyCode::Char('i')));
+        app.handle_api_message(ApiMessage::Failed(ApiOperation::History(id), problem(503, "down")));
+        assert_eq!(app.history.as_ref().unwrap().records, Some(Err("HTTP 503".to_string())));
+    }
+
+    #[test]
+    fn test_history_404_is_logged_once() {
+        let mut app = App::new();
+        let id = Uuid::from_u128(1);
+        app.projects = vec![project(1).around_today(-10, 400)];
+        app.radar_state.selected_index = Some(0);
+        let unsupported = |app: &App| app.logs.iter().filter(|l| l.message.contains("not supported")).count();
+
+        app.handle_key(key(KeyCode::Char('i')));
+        app.handle_api_message(ApiMessage::Failed(ApiOperation::History(id), problem(404, "Not Found")));
+        assert!(app.history.is_none() && app.error_popup.is_none());
+        assert_eq!(unsupported(&app), 1);
+
+        // No second request, and no second log line
+        assert!(app.handle_key(key(KeyCode::Char('i'))).is_none());
+        app.handle_api_message(ApiMessage::Failed(ApiOperation::History(id), problem(404, "Not Found")));
+        assert_eq!(unsupported(&app), 1);
+    }
+
+    /// P1 mine and 20 days late, P2 mine and 5 days late, P3 someone else's and
+    /// 40 days late, P4 mine and running
+    fn my_projects(me: Uuid) -> Vec<ProjectDto> {
+        let me = me.as_u128();
+        vec![
+            project(1).around_today(-100, -20).manager(me),
+            project(2).around_today(-100, -5).manager(me),
+            project(3).around_today(-100, -40),
+            project(4).around_today(-10, 30).manager(me),
+        ]
+    }
+
+    #[test]
+    fn test_mine_filter_needs_a_login() {
+        let mut app = App::new();
+        let me = user(1);
+        app.projects = my_projects(me.id);
+        app.handle_key(key(KeyCode::Char('~')));
+        assert_eq!(app.project_filter.mine, None);
+        assert_eq!(app.my_project_counts(), None);
+        assert_eq!(app.visible_projects().len(), 4);
+
+        app.set_current_user(me.clone());
+        assert_eq!(app.my_project_counts(), Some((1, 2)));
+        app.handle_key(key(KeyCode::Char('~')));
+        assert_eq!(app.visible_projects(), [0, 1, 3]);
+        // Esc clears the criteria but not the layer
+        app.set_project_filter(ProjectFilter { text: Some("P4".to_string()), ..app.project_filter.cleared() });
+        app.handle_key(key(KeyCode::Esc));
+        assert_eq!(app.project_filter.mine, Some(me.id));
+        app.handle_key(key(KeyCode::Char('~')));
+        assert_eq!(app.visible_projects().len(), 4);
+    }
+
+    #[test]
+    fn test_login_selects_my_worst_overdue_project() {
+        let mut app = App::new();
+        app.default_filter = DefaultFilter::Mine;
+        let me = user(1);
+        app.handle_api_message(ApiMessage::ProjectsLoaded(my_projects(me.id).into()));
+        assert_eq!(app.radar_state.selected_index, Some(3), "the running project before login");
+
+        app.set_current_user(me.clone());
+        assert_eq!(app.project_filter.mine, Some(me.id));
+        assert_eq!(app.radar_state.selected_index, Some(0), "P1 is the furthest past its deadline");
+        assert_eq!(app.flashing(), Some(Uuid::from_u128(1)));
+        assert!(app.logs.iter().any(|l| l.message == "Your project P1 is 20 days overdue"));
+
+        // Only at startup, not on every reload
+        app.radar_state.selected_index = Some(3);
+        app.handle_api_message(ApiMessage::ProjectsLoaded(my_projects(me.id).into()));
+        assert_eq!(app.radar_state.selected_index, Some(3));
+    }
+
+    fn type_text(app: &mut App, text: &str) {
+        for c in text.chars() {
+            app.handle_key(key(KeyCode::Char(c)));
         }
     }
 
-    /// Update animations (called every frame)
-    pub fn tick(&mut self, width: u16, height: u16) {
-        self.frame_count = self.frame_count.wrapping_add(1);
+    #[test]
+    fn test_window_title() {
+        let mut app = App::new();
+        let today = chrono::Local::now().date_naive();
+        let now = Instant::now();
+        assert_eq!(app.window_title(today, now), "SWEeM — 0 projects · offline");
 
-        // Update particles
-        self.particle_system.update(width, height);
+        app.projects = vec![project(1).around_today(-10, 10)];
+        app.handle_api_message(ApiMessage::ConnectionStatus(Some(Duration::from_millis(5))));
+        assert_eq!(app.window_title(today, now), "SWEeM — 1 project · connected");
 
-        // Update timeline animations (goyslop effects!)
-        self.radar_state.tick();
+        app.projects.push(project(2).around_today(-30, -3));
+        app.projects.push(project(3).around_today(-30, -5).done());
+        assert_eq!(app.window_title(today, now), "SWEeM — 3 projects · 1 overdue · connected");
 
-        // Auto-dismiss error popup
-        if let Some(ref popup) = self.error_popup {
-            if popup.should_dismiss() {
-                self.error_popup = None;
-            }
+        app.last_refresh = Some(now);
+        assert_eq!(app.window_title(today, now), "SWEeM — 3 projects · 1 overdue · connected");
+        let later = now + Duration::from_secs(14 * 60);
+        assert_eq!(app.window_title(today, later), "SWEeM — 3 projects · 1 overdue · connected · data 14m old");
+    }
+
+    #[test]
+    fn test_jump_to_a_date() {
+        let mut app = App::new();
+        let today = chrono::Local::now().date_naive();
+        app.projects = vec![project(1).around_today(-3, 20)];
+        app.handle_key(key(KeyCode::Char('J')));
+        assert_eq!(app.layers(), [Layer::DateJump]);
+        assert_eq!(app.key_hints(), "Enter jump · Esc cancel");
+
+        // A date that doesn't read keeps the prompt
+        type_text(&mut app, "soon");
+        app.handle_key(key(KeyCode::Enter));
+        assert!(app.date_jump.is_some());
+        assert!(app.logs.back().unwrap().message.starts_with("Not a date: 'soon'"));
+
+        app.date_jump = Some(TextInput::new("+6w"));
+        app.handle_key(key(KeyCode::Enter));
+        assert!(app.date_jump.is_none());
+        assert_eq!(app.radar_state.range_days, 126.0);
+        assert_eq!(app.jump_mark(), Some(today + chrono::Duration::weeks(6)));
+        app.jump_mark = app.jump_mark.map(|(date, _)| (date, Instant::now()));
+        app.tick(80, 24);
+        assert_eq!(app.jump_mark(), None);
+        assert!(app.jump_mark.is_none());
+
+        // The radar has no past; the calendar does
+        app.handle_key(key(KeyCode::Char('J')));
+        type_text(&mut app, &(today - chrono::Duration::days(40)).to_string());
+        app.handle_key(key(KeyCode::Enter));
+        assert_eq!(app.calendar.as_ref().map(|c| c.cursor), Some(today - chrono::Duration::days(40)));
+        assert_eq!(app.jump_mark(), None);
+        assert_eq!(app.radar_state.range_days, 126.0);
+
+        app.handle_key(key(KeyCode::Esc));
+        app.handle_key(key(KeyCode::Char('J')));
+        app.handle_key(key(KeyCode::Esc));
+        assert!(app.layers().is_empty());
+    }
+
+    #[test]
+    fn test_filter_presets_save_apply_and_delete() {
+        let path = std::env::temp_dir()
+            .join(format!("sweem-presets-{}", Uuid::new_v4()))
+            .join("filter-presets.toml");
+        let mut app = App::new();
+        app.presets_path = Some(path.clone());
+        app.clients = vec![client(7).named("Acme").address("1 Road")];
+        app.projects = vec![project(1).around_today(-10, 40)];
+        let acme_id = app.clients[0].id;
+        app.set_project_filter(ProjectFilter {
+            status: Some(ProjectStatus::Active),
+            client_id: Some(acme_id),
+            manager_id: Some(Uuid::from_u128(99)),
+            text: Some("launch".to_string()),
+            ..app.project_filter.cleared()
+        });
+        let saved = app.project_filter.clone();
+
+        app.handle_key(key(KeyCode::Char('P')));
+        app.handle_key(key(KeyCode::Char('s')));
+        assert_eq!(app.key_hints(), "Enter save · Esc cancel");
+        type_text(&mut app, "Acme now");
+        app.handle_key(key(KeyCode::Enter));
+        assert_eq!(app.presets.len(), 1);
+        assert!(app.preset_picker.as_ref().unwrap().naming.is_none());
+
+        // A restart reads the same presets back
+        let reloaded = Presets::load_from(&path).unwrap();
+        assert_eq!(reloaded, app.presets);
+
+        app.handle_key(key(KeyCode::Esc));
+        app.handle_key(key(KeyCode::Esc));
+        assert!(!app.project_filter.is_active());
+        app.presets = reloaded;
+        app.handle_key(key(KeyCode::Char('P')));
+        app.handle_key(key(KeyCode::Enter));
+        assert!(app.preset_picker.is_none());
+        // The manager is unknown, so that clause is skipped
+        assert_eq!(app.project_filter, ProjectFilter { manager_id: None, ..saved });
+        assert!(app.logs.iter().any(|l| l.message == "Preset \"Acme now\": its manager no longer exists, skipped"));
+
+        app.handle_key(key(KeyCode::Char('P')));
+        app.handle_key(key(KeyCode::Char('d')));
+        assert!(app.presets.is_empty());
+        let remaining = Presets::load_from(&path);
+        std::fs::remove_dir_all(path.parent().unwrap()).ok();
+        assert!(remaining.unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_breadcrumb_lists_non_default_view_state() {
+        let mut app = App::new();
+        app.archive_after_days = 90;
+        app.project_filter.archive_after_days = Some(90);
+        app.clients = vec![client(7).named("Acme").address("1 Road")];
+        app.projects = vec![
+            project(1).around_today(-10, 40),
+            project(2).around_today(-10, 20).client(7),
+            project(3).around_today(-400, -200).done(),
+        ];
+        assert_eq!(app.breadcrumb(), None, "hiding archived projects is the default");
+
+        app.set_project_filter(ProjectFilter { client_id: Some(app.clients[0].id), ..app.project_filter.cleared() });
+        app.project_sort = ProjectSort::End;
+        assert_eq!(app.breadcrumb().as_deref(), Some("Filter: Acme │ Sort: end date │ 2 hidden"));
+        app.handle_key(key(KeyCode::Char('H')));
+        let shown = "Filter: Acme │ Archive: shown │ Sort: end date │ 2 hidden";
+        assert_eq!(app.breadcrumb().as_deref(), Some(shown));
+
+        app.radar_state.selected_index = Some(1);
+        app.handle_key(ctrl('x'));
+        assert_eq!(app.breadcrumb(), None);
+        assert_eq!(app.project_filter.archive_after_days, Some(90));
+        assert_eq!(app.project_sort, ProjectSort::Start);
+        let selected = app.selected_item().map(|(id, _)| id);
+        assert_eq!(selected, Some(Uuid::from_u128(2)), "the selection follows the re-sort");
+    }
+
+    #[test]
+    fn test_splash_only_lets_you_quit() {
+        let mut app = App::new();
+        app.splash = Some(Splash::new("http://api"));
+        assert!(app.handle_key(key(KeyCode::Char('r'))).is_none());
+        assert!(app.handle_key(key(KeyCode::Char('?'))).is_none());
+        assert!(!app.show_help);
+
+        // Request chatter doesn't end it; a real answer does
+        app.handle_api_message(ApiMessage::Trace("GET /health".to_string()));
+        assert!(app.splash.is_some());
+        assert_eq!(app.handle_key(ctrl('c')), Some(ApiCommand::Shutdown));
+        app.handle_api_message(ApiMessage::Failed(ApiOperation::Connect, ApiError::Timeout));
+        assert!(app.splash.is_none());
+    }
+
+    /// Press Esc until nothing is open, checking what is left after each press
+    fn escape_layers(app: &mut App, expected: &[Layer]) {
+        assert_eq!(app.layers(), expected);
+        for open in (0..expected.len()).rev() {
+            assert!(app.handle_key(key(KeyCode::Esc)).is_none());
+            assert_eq!(app.layers(), expected[expected.len() - open..], "after closing {:?}", expected[0]);
         }
     }
 
-    /// Get the status bar text
-    pub fn status_text(&self) -> String {
-        let connection = if self.api_connected {
-            "Connected"
-        } else {
-            "Disconnected"
-        };
+    #[test]
+    fn test_esc_closes_popups_over_a_form_one_at_a_time() {
+        let mut app = App::new();
+        edit_acme(&mut app);
+        app.form_state.as_mut().unwrap().nav_mut().dropdown_open = true;
+        app.confirm_dialog = Some(ConfirmDialog::new_delete(EntityType::Client, Uuid::from_u128(7), "Acme"));
+        app.input_mode = InputMode::Confirming;
+        app.show_help = true;
+        app.show_error("API Error", "boom");
+
+        escape_layers(
+            &mut app,
+            &[Layer::ErrorPopup, Layer::Help, Layer::ConfirmDialog, Layer::Dropdown, Layer::Form],
+        );
+        assert_eq!(app.input_mode, InputMode::Normal);
+        // Nothing left to close: Esc is a no-op
+        assert!(app.handle_key(key(KeyCode::Esc)).is_none());
+        assert_eq!(app.active_tab, Tab::Clients);
+    }
 
-        let loading = if self.is_loading { " [Loading...]" } else { "" };
+    #[test]
+    fn test_esc_leaves_the_form_under_a_review_or_confirm() {
+        let mut app = App::new();
+        app.confirm_edits = true;
+        edit_acme(&mut app);
+        let Some(FormState::Client(form)) = &mut app.form_state else { panic!("no client form") };
+        form.address.set_value("2 Lane");
+        app.handle_key(key(KeyCode::Enter));
+        escape_layers(&mut app, &[Layer::EditReview, Layer::Form]);
 
-        let last_refresh = self
-            .last_refresh
-            .map(|t| {
-                let secs = t.elapsed().as_secs();
-                if secs < 60 {
-                    format!(" ({}s ago)", secs)
-                } else {
-                    format!(" ({}m ago)", secs / 60)
-                }
-            })
-            .unwrap_or_default();
+        edit_acme(&mut app);
+        app.confirm_dialog = Some(ConfirmDialog::new_delete(EntityType::Client, Uuid::from_u128(7), "Acme"));
+        app.input_mode = InputMode::Confirming;
+        app.handle_key(key(KeyCode::Esc));
+        assert_eq!(app.layers(), [Layer::Form]);
+        assert_eq!(app.input_mode, InputMode::Editing, "the form has the keyboard again");
+    }
 
-        format!(
-            "{}{}{} | {} | ?: Help | c: Create | e: Edit | d: Delete | q: Quit",
-            connection,
-            loading,
-            last_refresh,
-            self.active_tab.name()
-        )
+    #[test]
+    fn test_esc_on_the_timeline_unwinds_from_the_top() {
+        let mut app = App::new();
+        app.projects = vec![project(1).around_today(-10, 10)];
+        app.set_project_filter(ProjectFilter { text: Some("project".into()), ..Default::default() });
+        let mut calendar = CalendarState::new(chrono::Local::now().date_naive());
+        calendar.day_popup = Some(0);
+        app.calendar = Some(calendar);
+        app.marked.insert(Uuid::from_u128(1));
+        app.history = Some(HistoryPopup {
+            project_id: Uuid::from_u128(1),
+            name: "Project 1".into(),
+            records: None,
+            scroll: PopupScroll::default(),
+        });
+
+        escape_layers(&mut app, &[Layer::History, Layer::Marks, Layer::DayPopup, Layer::Calendar, Layer::Filter]);
+        assert!(app.marked.is_empty());
+        assert!(!app.project_filter.is_active());
+    }
+
+    #[test]
+    fn test_esc_in_pickers_and_panes() {
+        let mut app = App::new();
+        app.active_tab = Tab::Clients;
+        app.clients = vec![client(7).named("Acme").address("1 Road")];
+        let mut theirs = project(1).around_today(-3, 20);
+        theirs.client_id = app.clients[0].id;
+        app.projects = vec![theirs];
+        app.focus_pane(Pane::Secondary);
+        app.preset_picker = Some(PresetPicker { naming: Some(TextInput::default()), ..Default::default() });
+        escape_layers(&mut app, &[Layer::PresetName, Layer::PresetPicker, Layer::Pane]);
+
+        app.active_tab = Tab::Users;
+        app.workload_cursor = Some((0, 0));
+        app.profile_switcher = Some(0);
+        escape_layers(&mut app, &[Layer::ProfileSwitcher, Layer::Workload]);
+    }
+
+    #[test]
+    fn test_esc_skips_setup_but_nothing_under_it() {
+        let mut app = App::new();
+        app.setup = Some(SetupState::new("http://api"));
+        app.details_popup = true;
+        escape_layers(&mut app, &[Layer::Setup, Layer::Details]);
+        assert_eq!(app.logs.back().unwrap().message, "Setup skipped - working offline");
+    }
+
+    #[test]
+    fn test_loaded_data_is_sanitized() {
+        let mut app = App::new();
+        let mut ghost = project(1).around_today(-5, 10);
+        ghost.id = Uuid::nil();
+        let mut rogue = project(2).around_today(-5, 10);
+        rogue.name = Some("Rogue\x1b[31m".to_string());
+        let projects = vec![ghost, rogue, project(3).around_today(-5, 10)];
+        app.handle_api_message(ApiMessage::ProjectsLoaded(projects.into()));
+        let names: Vec<_> = app.projects.iter().map(|p| p.display_name()).collect();
+        assert_eq!(names, ["P3", "Rogue[31m"]);
+        let warnings: Vec<_> = app.logs.iter().filter(|l| l.level == LogLevel::Warning).map(|l| &l.message).collect();
+        let fixed = "Project \"Rogue[31m\": control chars removed from name";
+        assert_eq!(warnings, [fixed, "Dropped 1 project with a nil id"]);
+
+        // A single entity with a nil id is ignored
+        app.handle_api_message(ApiMessage::OneLoaded(FetchedEntity::Client(client(0).named("Nobody"))));
+        assert!(app.clients.is_empty());
+        assert_eq!(app.logs.back().unwrap().message, "Ignored a client with a nil id");
+        app.handle_api_message(ApiMessage::OneLoaded(FetchedEntity::Client(client(7).named(&"A".repeat(500)))));
+        assert_eq!(app.clients[0].display_name().chars().count(), crate::sanitize::MAX_TEXT_CHARS);
+        let cut = format!("Client \"{}…\": name cut to 200 chars", "A".repeat(199));
+        assert_eq!(app.logs.back().unwrap().message, cut);
     }
 }
@@ -0,0 +1,339 @@
+//! Month calendars.
+//!
+//! `weeks` lays a month's days out in rows; the date picker's mini calendar
+//! and the Timeline's month view both draw from it. The month view marks
+//! project starts and deadlines on their days with `CalendarWidget`.
+
+use chrono::{Datelike, Months, NaiveDate, Weekday};
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Modifier, Style};
+use ratatui::widgets::{Block, Borders, Widget};
+
+use crate::dates::WeekStart;
+use crate::models::{ProjectDto, ProjectStatus};
+use crate::theme::{colors, palette_color, status_color, styles};
+
+/// Events listed in a day cell before the rest is summed up
+pub const EVENTS_PER_DAY: usize = 2;
+
+/// Number of days in `date`'s month
+pub fn days_in_month(date: NaiveDate) -> u32 {
+    date.with_day(1)
+        .and_then(|first| first.checked_add_months(Months::new(1)))
+        .and_then(|next| next.pred_opt())
+        .map_or(31, |last| last.day())
+}
+
+/// Day-of-month numbers laid out in calendar rows, blanks outside the month
+pub fn weeks(date: NaiveDate, week_start: WeekStart) -> Vec<[Option<u32>; 7]> {
+    let first_of_month = date.with_day(1).unwrap_or(date);
+    let mut weeks = Vec::new();
+    let mut column = week_start.column(first_of_month.weekday());
+    let mut week = [None; 7];
+    for day in 1..=days_in_month(date) {
+        week[column] = Some(day);
+        column += 1;
+        if column == 7 {
+            weeks.push(week);
+            week = [None; 7];
+            column = 0;
+        }
+    }
+    if column > 0 {
+        weeks.push(week);
+    }
+    weeks
+}
+
+/// The same day `months` months later (or earlier), clamped to the length of
+/// the target month
+pub fn shift_month(date: NaiveDate, months: i32) -> NaiveDate {
+    let first = date.with_day(1).unwrap_or(date);
+    let shifted = if months >= 0 {
+        first.checked_add_months(Months::new(months as u32))
+    } else {
+        first.checked_sub_months(Months::new(months.unsigned_abs()))
+    };
+    let Some(first) = shifted else {
+        return date;
+    };
+    first.with_day(date.day().min(days_in_month(first))).unwrap_or(first)
+}
+
+/// Style for a plain calendar day: Saturdays blue, Sundays red
+pub fn weekday_style(weekday: Weekday) -> Style {
+    match weekday {
+        Weekday::Sat => Style::default().fg(colors::BLUE),
+        Weekday::Sun => Style::default().fg(colors::RED),
+        _ => styles::text(),
+    }
+}
+
+/// What happens to a project on a calendar day
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventKind {
+    Start,
+    Deadline,
+}
+
+impl EventKind {
+    pub fn marker(self) -> &'static str {
+        match self {
+            EventKind::Start => "⌁",
+            EventKind::Deadline => "⚑",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            EventKind::Start => "starts",
+            EventKind::Deadline => "due",
+        }
+    }
+}
+
+/// A project start or deadline falling on a day
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Event {
+    /// Index into the project list
+    pub project: usize,
+    pub kind: EventKind,
+}
+
+/// Starts and deadlines of the `visible` projects on `day`, starts first.
+/// Projects without real dates have nothing to show.
+pub fn events_on(projects: &[ProjectDto], visible: &[usize], day: NaiveDate) -> Vec<Event> {
+    let scheduled = || {
+        visible
+            .iter()
+            .copied()
+            .filter(|&i| projects.get(i).is_some_and(ProjectDto::has_valid_dates))
+    };
+    let starts = scheduled()
+        .filter(|&i| projects[i].start_date == day)
+        .map(|project| Event { project, kind: EventKind::Start });
+    let deadlines = scheduled()
+        .filter(|&i| projects[i].planned_end_date == day)
+        .map(|project| Event { project, kind: EventKind::Deadline });
+    starts.chain(deadlines).collect()
+}
+
+/// Colour a project is drawn in, matching its radar blip
+pub fn project_color(projects: &[ProjectDto], index: usize, today: NaiveDate) -> ratatui::style::Color {
+    match projects[index].status(today) {
+        ProjectStatus::Active => palette_color(projects[index].id),
+        other => status_color(other),
+    }
+}
+
+/// Month view state: the day under the cursor and, while the day popup is
+/// open, the event selected in it
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalendarState {
+    pub cursor: NaiveDate,
+    pub day_popup: Option<usize>,
+}
+
+impl CalendarState {
+    pub fn new(cursor: NaiveDate) -> Self {
+        Self { cursor, day_popup: None }
+    }
+
+    pub fn move_days(&mut self, days: i64) {
+        self.cursor += chrono::Duration::days(days);
+    }
+
+    pub fn move_months(&mut self, months: i32) {
+        self.cursor = shift_month(self.cursor, months);
+    }
+}
+
+/// The cursor's month as a grid of day cells listing starts and deadlines
+pub struct CalendarWidget<'a> {
+    projects: &'a [ProjectDto],
+    visible: &'a [usize],
+    state: &'a CalendarState,
+    week_start: WeekStart,
+    today: NaiveDate,
+}
+
+impl<'a> CalendarWidget<'a> {
+    pub fn new(projects: &'a [ProjectDto], visible: &'a [usize], state: &'a CalendarState) -> Self {
+        Self {
+            projects,
+            visible,
+            state,
+            week_start: WeekStart::default(),
+            today: chrono::Local::now().date_naive(),
+        }
+    }
+
+    pub fn week_start(mut self, week_start: WeekStart) -> Self {
+        self.week_start = week_start;
+        self
+    }
+
+    /// Day highlighted as today; defaults to the local date
+    pub fn today(mut self, today: NaiveDate) -> Self {
+        self.today = today;
+        self
+    }
+
+    /// Draw one day cell: the day number, then its first events
+    fn render_day(&self, day: NaiveDate, column: usize, area: Rect, buf: &mut Buffer) {
+        let is_cursor = day == self.state.cursor;
+        if is_cursor {
+            buf.set_style(area, Style::default().bg(colors::BG_HIGHLIGHT));
+        }
+        let number_style = if is_cursor {
+            Style::default().fg(colors::BG_DARK).bg(colors::BLUE).add_modifier(Modifier::BOLD)
+        } else if day == self.today {
+            Style::default().fg(colors::YELLOW).add_modifier(Modifier::BOLD)
+        } else {
+            weekday_style(self.week_start.weekday(column))
+        };
+        buf.set_string(area.x, area.y, format!("{:>2}", day.day()), number_style);
+
+        let events = events_on(self.projects, self.visible, day);
+        let room = (area.height as usize).saturating_sub(1);
+        let shown = events.len().min(EVENTS_PER_DAY).min(room);
+        let width = area.width.saturating_sub(1) as usize;
+        for (line, event) in events.iter().take(shown).enumerate() {
+            let project = &self.projects[event.project];
+            let text = format!("{} {}", event.kind.marker(), project.display_name());
+            let style = Style::default().fg(project_color(self.projects, event.project, self.today));
+            buf.set_stringn(area.x, area.y + 1 + line as u16, text, width, style);
+        }
+        if events.len() > shown && room > shown {
+            let more = format!("+{} more", events.len() - shown);
+            buf.set_stringn(area.x, area.y + 1 + shown as u16, more, width, styles::text_dim());
+        }
+    }
+}
+
+impl Widget for CalendarWidget<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let cursor = self.state.cursor;
+        let block = Block::default()
+            .title(format!(" {} ", cursor.format("%B %Y")))
+            .title_style(styles::title_accent())
+            .borders(Borders::ALL)
+            .border_style(styles::border())
+            .style(Style::default().bg(colors::BG_DARK));
+        let inner = block.inner(area);
+        block.render(area, buf);
+        if inner.width < 14 || inner.height < 3 {
+            return;
+        }
+
+        let cell_width = inner.width / 7;
+        for column in 0..7 {
+            let weekday = self.week_start.weekday(column);
+            let style = match weekday {
+                Weekday::Sat | Weekday::Sun => weekday_style(weekday),
+                _ => styles::text_dim(),
+            };
+            let x = inner.x + column as u16 * cell_width;
+            buf.set_stringn(x, inner.y, weekday.to_string(), cell_width as usize, style);
+        }
+
+        let weeks = weeks(cursor, self.week_start);
+        let cell_height = ((inner.height - 1) / weeks.len() as u16).max(1);
+        for (row, week) in weeks.iter().enumerate() {
+            let y = inner.y + 1 + row as u16 * cell_height;
+            if y >= inner.bottom() {
+                break;
+            }
+            for (column, day) in week.iter().enumerate() {
+                let Some(day) = day.and_then(|d| cursor.with_day(d)) else {
+                    continue;
+                };
+                let cell = Rect::new(inner.x + column as u16 * cell_width, y, cell_width, cell_height)
+                    .intersection(inner);
+                self.render_day(day, column, cell, buf);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{date, project, ProjectFixture};
+
+    #[test]
+    fn test_calendar_week_layout() {
+        // June 2024 starts on a Saturday and ends on a Sunday
+        let june = date("2024-06-15");
+        let monday = weeks(june, WeekStart::Monday);
+        assert_eq!(monday.len(), 5);
+        assert_eq!(monday[0], [None, None, None, None, None, Some(1), Some(2)]);
+        assert_eq!(monday[4][6], Some(30));
+
+        let sunday = weeks(june, WeekStart::Sunday);
+        assert_eq!(sunday.len(), 6);
+        assert_eq!(sunday[0], [None, None, None, None, None, None, Some(1)]);
+        assert_eq!(sunday[1][0], Some(2));
+        assert_eq!(sunday[5], [Some(30), None, None, None, None, None, None]);
+
+        // February 2021 fills exactly four Monday weeks
+        assert_eq!(weeks(date("2021-02-10"), WeekStart::Monday).len(), 4);
+        assert_eq!(days_in_month(date("2024-02-10")), 29);
+    }
+
+    #[test]
+    fn test_shift_month_clamps_the_day() {
+        assert_eq!(shift_month(date("2024-01-31"), 1), date("2024-02-29"));
+        assert_eq!(shift_month(date("2024-03-31"), -1), date("2024-02-29"));
+        assert_eq!(shift_month(date("2024-12-15"), 1), date("2025-01-15"));
+        assert_eq!(shift_month(date("2024-01-15"), -13), date("2022-12-15"));
+    }
+
+    #[test]
+    fn test_events_on_a_day() {
+        let mut unscheduled = project(3).starts("2024-06-10").ends("2024-06-10");
+        unscheduled.start_date = NaiveDate::MIN;
+        let projects = vec![
+            project(0).starts("2024-06-01").ends("2024-06-10"),
+            project(1).starts("2024-06-10").ends("2024-06-20"),
+            project(2).starts("2024-06-10").ends("2024-06-10"),
+            unscheduled,
+        ];
+        let all = [0, 1, 2, 3];
+        let kinds = |visible: &[usize], day: &str| -> Vec<(usize, EventKind)> {
+            events_on(&projects, visible, date(day)).iter().map(|e| (e.project, e.kind)).collect()
+        };
+        assert_eq!(
+            kinds(&all, "2024-06-10"),
+            [(1, EventKind::Start), (2, EventKind::Start), (0, EventKind::Deadline), (2, EventKind::Deadline)]
+        );
+        assert_eq!(kinds(&[0, 2], "2024-06-10"), [(2, EventKind::Start), (0, EventKind::Deadline), (2, EventKind::Deadline)]);
+        assert!(kinds(&all, "2024-06-11").is_empty());
+    }
+
+    #[test]
+    fn test_month_view_lists_two_events_per_day() {
+        let projects = vec![
+            project(0).starts("2024-06-03").ends("2024-06-12"),
+            project(1).starts("2024-06-12").ends("2024-06-30"),
+            project(2).starts("2024-05-01").ends("2024-06-12"),
+        ];
+        let state = CalendarState::new(date("2024-06-12"));
+        let area = Rect::new(0, 0, 84, 32);
+        let mut buf = Buffer::empty(area);
+        CalendarWidget::new(&projects, &[0, 1, 2], &state)
+            .today(date("2024-06-01"))
+            .render(area, &mut buf);
+        let text: String = (0..area.height)
+            .map(|y| (0..area.width).map(|x| buf[(x, y)].symbol()).collect::<String>() + "\n")
+            .collect();
+
+        assert!(text.contains("June 2024"));
+        assert!(text.contains("⌁ P0"));
+        assert!(text.contains("⌁ P1"));
+        assert!(text.contains("+1 more"));
+        assert!(!text.contains("⚑ P2"));
+        assert!(text.contains("⚑ P1"));
+    }
+}
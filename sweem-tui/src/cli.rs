@@ -0,0 +1,604 @@
+//! Headless subcommands for scripting.
+//!
+//! `sweem-tui list projects --overdue` and friends talk to the API directly
+//! and print to stdout without ever touching the terminal, so they can run
+//! from cron jobs and shell scripts. Errors are returned to `main`, which
+//! maps them to a non-zero exit code.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use chrono::NaiveDate;
+use uuid::Uuid;
+
+use crate::api::{ApiClient, EntityType};
+use crate::dates::DateFormat;
+use crate::models::{is_valid_date, CreateClientDto, CreateProjectDto, CreateUserDto, Role};
+use crate::filter;
+use crate::import;
+use crate::report;
+
+/// A headless subcommand
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    /// Print every entity of a kind as a table or JSON
+    List {
+        entity: EntityType,
+        json: bool,
+        /// Only overdue projects
+        overdue: bool,
+    },
+    CreateClient(CreateClientDto),
+    CreateProject(CreateProjectDto),
+    CreateUser(CreateUserDto),
+    /// Delete by id; refuses to run without `--yes`
+    Delete { entity: EntityType, id: Uuid, yes: bool },
+    /// Write the Markdown status report (`--report <path>`); archived
+    /// projects are left out unless `--include-archived` is given
+    Report { path: PathBuf, include_archived: bool },
+    /// Dry-run an import file, then create its rows with `--yes`
+    Import { entity: EntityType, path: PathBuf, yes: bool },
+}
+
+impl Command {
+    /// Whether `word` starts a subcommand rather than being the API URL
+    pub fn is_subcommand(word: &str) -> bool {
+        matches!(word, "list" | "create" | "delete" | "import")
+    }
+
+    /// Parse a subcommand and its options (everything after the subcommand name)
+    pub fn parse(name: &str, args: impl IntoIterator<Item = String>) -> Result<Self> {
+        let mut args = args.into_iter();
+        let entity = parse_entity(args.next().as_deref())?;
+        let path = match name {
+            "import" => Some(PathBuf::from(args.next().context("Missing file to import")?)),
+            _ => None,
+        };
+        let mut options = Options::parse(args)?;
+
+        let command = match name {
+            "list" => {
+                let overdue = options.flag("overdue");
+                if overdue && entity != EntityType::Project {
+                    bail!("--overdue only applies to projects");
+                }
+                Command::List {
+                    entity,
+                    json: options.flag("json"),
+                    overdue,
+                }
+            }
+            "create" => match entity {
+                EntityType::Client => Command::CreateClient(CreateClientDto {
+                    name: options.take("name"),
+                    address: options.take("address"),
+                    ..CreateClientDto::default()
+                }),
+                EntityType::Project => Command::CreateProject(CreateProjectDto {
+                    name: options.take("name"),
+                    client_id: options.uuid("client")?.unwrap_or_default(),
+                    manager_id: options.uuid("manager")?.unwrap_or_default(),
+                    start_date: options.date("start")?.context("--start is required")?,
+                    planned_end_date: options.date("end")?.context("--end is required")?,
+                    actual_end_date: None,
+                }),
+                EntityType::User => Command::CreateUser(CreateUserDto {
+                    name: options.take("name"),
+                    login: options.take("login"),
+                    password: options.take("password"),
+                    role: match options.take("role").as_deref() {
+                        None | Some("manager") => Role::Manager,
+                        Some("admin") => Role::Admin,
+                        Some(other) => bail!("Unknown role: {} (expected manager or admin)", other),
+                    },
+                }),
+            },
+            "delete" => Command::Delete {
+                entity,
+                id: options.uuid("id")?.context("--id is required")?,
+                yes: options.flag("yes"),
+            },
+            "import" => Command::Import {
+                entity,
+                path: path.unwrap_or_default(),
+                yes: options.flag("yes"),
+            },
+            other => bail!("Unknown command: {}", other),
+        };
+
+        options.finish()?;
+        Ok(command)
+    }
+
+    /// Whether the command changes data on the server
+    pub fn is_mutating(&self) -> bool {
+        match self {
+            Command::List { .. } | Command::Report { .. } => false,
+            Command::Import { yes, .. } => *yes,
+            _ => true,
+        }
+    }
+}
+
+/// Run a subcommand, writing its output to `out`. `date_format` and the
+/// archive cutoff only affect the Markdown report; tables and JSON stay
+/// complete and ISO for scripts.
+pub async fn run(
+    client: &ApiClient,
+    command: &Command,
+    date_format: DateFormat,
+    archive_after_days: u32,
+    out: &mut impl Write,
+) -> Result<()> {
+    match command {
+        Command::List { entity, json, overdue } => list(client, *entity, *json, *overdue, out).await,
+        Command::CreateClient(dto) => {
+            dto.validate().map_err(anyhow::Error::msg)?;
+            let id = client.create_client(dto).await?;
+            writeln!(out, "{}", id)?;
+            Ok(())
+        }
+        Command::CreateProject(dto) => {
+            dto.validate().map_err(anyhow::Error::msg)?;
+            let id = client.create_project(dto).await?;
+            writeln!(out, "{}", id)?;
+            Ok(())
+        }
+        Command::CreateUser(dto) => {
+            dto.validate().map_err(anyhow::Error::msg)?;
+            let id = client.create_user(dto).await?;
+            writeln!(out, "{}", id)?;
+            Ok(())
+        }
+        Command::Delete { entity, id, yes } => {
+            if !yes {
+                bail!("Refusing to delete {} {} without --yes", entity, id);
+            }
+            let deleted = match entity {
+                EntityType::Client => client.delete_client(*id).await?,
+                EntityType::Project => client.delete_project(*id).await?,
+                EntityType::User => client.delete_user(*id).await?,
+            };
+            writeln!(out, "{}", deleted)?;
+            Ok(())
+        }
+        Command::Report { path, include_archived } => {
+            let (projects, clients, users) = tokio::join!(
+                client.fetch_all_projects(),
+                client.fetch_all_clients(),
+                client.fetch_all_users()
+            );
+            let today = chrono::Local::now().date_naive();
+            let mut projects = projects?;
+            if !include_archived {
+                projects = filter::without_archived(&projects, today, archive_after_days);
+            }
+            let (clients, users) = (clients?, users?);
+            let names = report::Loaded { clients: &clients, users: &users };
+            report::write(path, &projects, &clients, &users, &names, today, date_format)?;
+            writeln!(out, "Report written to {}", path.display())?;
+            Ok(())
+        }
+        Command::Import { entity, path, yes } => import_file(client, *entity, path, *yes, out).await,
+    }
+}
+
+/// Plan an import, print the dry run and create the rows if confirmed
+async fn import_file(
+    client: &ApiClient,
+    entity: EntityType,
+    path: &Path,
+    yes: bool,
+    out: &mut impl Write,
+) -> Result<()> {
+    let (clients, users) = tokio::join!(client.fetch_all_clients(), client.fetch_all_users());
+    let plan = import::plan_file(path, entity, &clients?, &users?)?;
+
+    writeln!(out, "Dry run: {}", plan.summary())?;
+    for (line, reason) in &plan.invalid {
+        writeln!(out, "  line {}: {}", line, reason)?;
+    }
+    import::ensure_clean(&plan)?;
+    if !yes {
+        writeln!(out, "Nothing imported; re-run with --yes to create {} rows", plan.valid.len())?;
+        return Ok(());
+    }
+
+    let total = plan.valid.len();
+    let failures = import::execute(
+        client,
+        &plan.valid,
+        |done, row, result| {
+            // Progress output is best effort; failures are reported below either way
+            let _ = match result {
+                Ok(id) => writeln!(out, "[{}/{}] line {}: created {}", done, total, row.line, id),
+                Err(e) => writeln!(out, "[{}/{}] line {}: FAILED {:#}", done, total, row.line, e),
+            };
+        },
+        |_| {},
+    )
+    .await;
+
+    if !failures.is_empty() {
+        let lines: Vec<String> = failures.iter().map(|(line, _)| line.to_string()).collect();
+        bail!(
+            "{} of {} rows failed (lines {})",
+            failures.len(),
+            total,
+            lines.join(", ")
+        );
+    }
+    writeln!(out, "Imported {} rows", total)?;
+    Ok(())
+}
+
+/// ISO date for a table cell; placeholder dates are left blank
+fn date_cell(date: NaiveDate) -> String {
+    if is_valid_date(date) { date.to_string() } else { String::new() }
+}
+
+/// Fetch and print one entity collection
+async fn list(
+    client: &ApiClient,
+    entity: EntityType,
+    json: bool,
+    overdue: bool,
+    out: &mut impl Write,
+) -> Result<()> {
+    match entity {
+        EntityType::Project => {
+            let mut projects = client.fetch_all_projects().await?;
+            if overdue {
+                projects.retain(|p| p.is_overdue());
+            }
+            if json {
+                return write_json(out, &projects);
+            }
+            let rows = projects
+                .iter()
+                .map(|p| {
+                    vec![
+                        p.id.to_string(),
+                        p.display_name().to_string(),
+                        date_cell(p.start_date),
+                        date_cell(p.planned_end_date),
+                        p.status_label().to_string(),
+                    ]
+                })
+                .collect();
+            write_table(out, &["ID", "NAME", "START", "END", "STATUS"], rows)
+        }
+        EntityType::Client => {
+            let clients = client.fetch_all_clients().await?;
+            if json {
+                return write_json(out, &clients);
+            }
+            let rows = clients
+                .iter()
+                .map(|c| {
+                    vec![
+                        c.id.to_string(),
+                        c.display_name().to_string(),
+                        c.address.clone().unwrap_or_default(),
+                        format!("{}/{}", c.projects_completed, c.projects_total),
+                    ]
+                })
+                .collect();
+            write_table(out, &["ID", "NAME", "ADDRESS", "DONE"], rows)
+        }
+        EntityType::User => {
+            let users = client.fetch_all_users().await?;
+            if json {
+                return write_json(out, &users);
+            }
+            let rows = users
+                .iter()
+                .map(|u| {
+                    vec![
+                        u.id.to_string(),
+                        u.display_name().to_string(),
+                        u.login.clone().unwrap_or_default(),
+                        u.role.to_string(),
+                    ]
+                })
+                .collect();
+            write_table(out, &["ID", "NAME", "LOGIN", "ROLE"], rows)
+        }
+    }
+}
+
+fn write_json<T: serde::Serialize>(out: &mut impl Write, items: &T) -> Result<()> {
+    serde_json::to_writer_pretty(&mut *out, items)?;
+    writeln!(out)?;
+    Ok(())
+}
+
+/// Print rows as left-aligned columns separated by two spaces
+fn write_table(out: &mut impl Write, headers: &[&str], rows: Vec<Vec<String>>) -> Result<()> {
+    let mut widths: Vec<usize> = headers.iter().map(|h| h.chars().count()).collect();
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let header_row: Vec<String> = headers.iter().map(|h| h.to_string()).collect();
+    for row in std::iter::once(&header_row).chain(&rows) {
+        let line: Vec<String> = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect();
+        writeln!(out, "{}", line.join("  ").trim_end())?;
+    }
+    Ok(())
+}
+
+fn parse_entity(word: Option<&str>) -> Result<EntityType> {
+    match word {
+        Some("client" | "clients") => Ok(EntityType::Client),
+        Some("project" | "projects") => Ok(EntityType::Project),
+        Some("user" | "users") => Ok(EntityType::User),
+        Some(other) => bail!("Unknown entity: {} (expected clients, projects or users)", other),
+        None => bail!("Missing entity (clients, projects or users)"),
+    }
+}
+
+/// `--key value` options and `--flag` switches, consumed as the command is built
+struct Options {
+    values: Vec<(String, Option<String>)>,
+}
+
+impl Options {
+    /// Flags that never take a value
+    const FLAGS: &'static [&'static str] = &["json", "overdue", "yes"];
+
+    fn parse(args: impl Iterator<Item = String>) -> Result<Self> {
+        let mut values = Vec::new();
+        let mut args = args.peekable();
+        while let Some(arg) = args.next() {
+            let key = arg
+                .strip_prefix("--")
+                .with_context(|| format!("Unexpected argument: {}", arg))?
+                .to_string();
+            let value = if Self::FLAGS.contains(&key.as_str()) {
+                None
+            } else {
+                Some(args.next().with_context(|| format!("--{} requires a value", key))?)
+            };
+            values.push((key, value));
+        }
+        Ok(Self { values })
+    }
+
+    fn flag(&mut self, key: &str) -> bool {
+        let before = self.values.len();
+        self.values.retain(|(k, _)| k != key);
+        self.values.len() != before
+    }
+
+    fn take(&mut self, key: &str) -> Option<String> {
+        let idx = self.values.iter().position(|(k, _)| k == key)?;
+        self.values.remove(idx).1
+    }
+
+    fn uuid(&mut self, key: &str) -> Result<Option<Uuid>> {
+        self.take(key)
+            .map(|v| v.parse().with_context(|| format!("Invalid --{}: {}", key, v)))
+            .transpose()
+    }
+
+    fn date(&mut self, key: &str) -> Result<Option<NaiveDate>> {
+        self.take(key)
+            .map(|v| {
+                NaiveDate::parse_from_str(&v, "%Y-%m-%d")
+                    .with_context(|| format!("Invalid --{}: {} (expected YYYY-MM-DD)", key, v))
+            })
+            .transpose()
+    }
+
+    /// Fail on any option the command didn't use
+    fn finish(self) -> Result<()> {
+        match self.values.first() {
+            Some((key, _)) => bail!("Unknown option for this command: --{}", key),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_api::{self, MockApi};
+    use crate::models::ProjectDto;
+    use crate::test_support::{project, ProjectFixture};
+
+    fn parse(args: &[&str]) -> Result<Command> {
+        let mut args = args.iter().map(|s| s.to_string());
+        let name = args.next().unwrap();
+        Command::parse(&name, args)
+    }
+
+    async fn run_to_string(url: &str, command: &Command) -> Result<String> {
+        let client = ApiClient::new(url)?;
+        let mut out = Vec::new();
+        run(&client, command, DateFormat::Iso, 90, &mut out).await?;
+        Ok(String::from_utf8(out)?)
+    }
+
+    #[test]
+    fn test_parse_commands() {
+        assert_eq!(
+            parse(&["list", "projects", "--overdue", "--json"]).unwrap(),
+            Command::List { entity: EntityType::Project, json: true, overdue: true }
+        );
+        assert!(parse(&["list", "clients", "--overdue"]).is_err());
+        assert!(parse(&["list", "widgets"]).is_err());
+        assert!(parse(&["list", "users", "--colour", "red"]).is_err());
+
+        let client = Uuid::new_v4();
+        let manager = Uuid::new_v4();
+        let cmd = parse(&[
+            "create", "project", "--name", "Apollo",
+            "--client", &client.to_string(), "--manager", &manager.to_string(),
+            "--start", "2024-05-01", "--end", "2024-06-01",
+        ])
+        .unwrap();
+        match cmd {
+            Command::CreateProject(dto) => {
+                assert_eq!(dto.name.as_deref(), Some("Apollo"));
+                assert_eq!(dto.client_id, client);
+                assert_eq!(dto.manager_id, manager);
+                assert_eq!(dto.planned_end_date, NaiveDate::from_ymd_opt(2024, 6, 1).unwrap());
+            }
+            other => panic!("unexpected command: {:?}", other),
+        }
+        assert!(parse(&["create", "project", "--name", "X", "--start", "2024-13-01"]).is_err());
+
+        assert!(parse(&["delete", "client"]).is_err());
+        assert!(!parse(&["list", "users"]).unwrap().is_mutating());
+        assert_eq!(
+            parse(&["import", "projects", "legacy.csv", "--yes"]).unwrap(),
+            Command::Import { entity: EntityType::Project, path: "legacy.csv".into(), yes: true }
+        );
+        assert!(parse(&["import", "projects"]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_import_dry_run_and_partial_failure() {
+        let path = std::env::temp_dir().join(format!("sweem-import-{}.csv", Uuid::new_v4()));
+        std::fs::write(&path, "name,address\nAcme,\nDuplicate,\nGlobex,\n").unwrap();
+
+        let created = Uuid::new_v4();
+        let mock = MockApi::start(move |req| match req.method.as_str() {
+            "GET" => (200, mock_api::page::<ProjectDto>(&[])),
+            _ if req.body.contains("Duplicate") => (409, "{\"title\":\"Conflict\"}".to_string()),
+            _ => (201, format!("\"{}\"", created)),
+        })
+        .await;
+
+        let dry_run = Command::Import { entity: EntityType::Client, path: path.clone(), yes: false };
+        let output = run_to_string(&mock.url, &dry_run).await.unwrap();
+        assert!(output.contains("Dry run: 3 valid, 0 invalid"));
+        assert!(mock.requests().iter().all(|r| r.method == "GET"));
+
+        let import = Command::Import { entity: EntityType::Client, path: path.clone(), yes: true };
+        let err = run_to_string(&mock.url, &import).await.unwrap_err();
+        std::fs::remove_file(&path).ok();
+        assert_eq!(err.to_string(), "1 of 3 rows failed (lines 3)");
+
+        let posts: Vec<String> = mock
+            .requests()
+            .into_iter()
+            .filter(|r| r.method == "POST")
+            .map(|r| r.body)
+            .collect();
+        assert_eq!(posts.len(), 3);
+        assert!(posts[0].contains("Acme") && posts[2].contains("Globex"));
+    }
+
+    #[tokio::test]
+    async fn test_report_fetches_everything() {
+        let mock = MockApi::start(|_| (200, mock_api::page::<ProjectDto>(&[]))).await;
+        let path = std::env::temp_dir().join(format!("sweem-report-{}.md", Uuid::new_v4()));
+
+        let report = Command::Report { path: path.clone(), include_archived: false };
+        let output = run_to_string(&mock.url, &report).await.unwrap();
+        let text = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert!(output.contains(&path.display().to_string()));
+        assert!(text.starts_with("# SWEeM Status Report"));
+        let mut paths: Vec<String> = mock.requests().into_iter().map(|r| r.path).collect();
+        paths.sort();
+        assert!(paths[0].starts_with("/clients") && paths[1].starts_with("/projects") && paths[2].starts_with("/users"));
+    }
+
+    #[tokio::test]
+    async fn test_report_includes_archived_projects_only_when_asked() {
+        let archived = project(2).named("Mercury").starts("2020-01-01").ends("2021-01-01").done();
+        let projects = vec![project(1).named("Apollo").ends("2999-01-01"), archived];
+        let body = mock_api::page(&projects);
+        let mock = MockApi::start(move |req| match req.path.starts_with("/projects") {
+            true => (200, body.clone()),
+            false => (200, mock_api::page::<ProjectDto>(&[])),
+        })
+        .await;
+
+        for include_archived in [false, true] {
+            let path = std::env::temp_dir().join(format!("sweem-report-{}.md", Uuid::new_v4()));
+            let report = Command::Report { path: path.clone(), include_archived };
+            run_to_string(&mock.url, &report).await.unwrap();
+            let text = std::fs::read_to_string(&path).unwrap();
+            std::fs::remove_file(&path).ok();
+
+            assert!(text.contains("Apollo"));
+            assert_eq!(text.contains("Mercury"), include_archived);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_list_projects_table_and_json() {
+        let late = project(1).named("Late One").starts("2020-01-01").ends("2021-01-01");
+        let projects = vec![late, project(2).named("Future").ends("2999-01-01")];
+        let body = mock_api::page(&projects);
+        let mock = MockApi::start(move |_| (200, body.clone())).await;
+
+        let all = Command::List { entity: EntityType::Project, json: false, overdue: false };
+        let table = run_to_string(&mock.url, &all).await.unwrap();
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("ID"));
+        assert!(lines[1].contains("Late One") && lines[1].ends_with("LATE"));
+        assert!(lines[2].contains("Future") && lines[2].ends_with("ACTIVE"));
+
+        let overdue = Command::List { entity: EntityType::Project, json: true, overdue: true };
+        let json = run_to_string(&mock.url, &overdue).await.unwrap();
+        let parsed: Vec<ProjectDto> = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(parsed[0].display_name(), "Late One");
+    }
+
+    #[tokio::test]
+    async fn test_create_validates_before_sending() {
+        let id = Uuid::new_v4();
+        let mock = MockApi::start(move |_| (201, format!("\"{}\"", id))).await;
+
+        // Invalid DTOs never reach the server
+        let invalid = Command::CreateClient(CreateClientDto::default());
+        let err = run_to_string(&mock.url, &invalid).await.unwrap_err();
+        assert_eq!(err.to_string(), "Name is required");
+        assert!(mock.requests().is_empty());
+
+        let valid = Command::CreateClient(CreateClientDto {
+            name: Some("Acme".to_string()),
+            ..CreateClientDto::default()
+        });
+        assert_eq!(run_to_string(&mock.url, &valid).await.unwrap().trim(), id.to_string());
+
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!((requests[0].method.as_str(), requests[0].path.as_str()), ("POST", "/clients"));
+        assert!(requests[0].body.contains("\"name\":\"Acme\""));
+    }
+
+    #[tokio::test]
+    async fn test_delete_requires_yes_and_reports_failures() {
+        let mock = MockApi::start(|req| match req.method.as_str() {
+            "DELETE" => (404, "{\"title\":\"Not Found\"}".to_string()),
+            _ => (500, String::new()),
+        })
+        .await;
+        let id = Uuid::new_v4();
+
+        let unconfirmed = Command::Delete { entity: EntityType::User, id, yes: false };
+        assert!(run_to_string(&mock.url, &unconfirmed).await.is_err());
+        assert!(mock.requests().is_empty());
+
+        let confirmed = Command::Delete { entity: EntityType::User, id, yes: true };
+        let err = run_to_string(&mock.url, &confirmed).await.unwrap_err();
+        assert!(err.to_string().contains("404"), "{}", err);
+        assert_eq!(mock.requests()[0].path, format!("/users/{}", id));
+    }
+}
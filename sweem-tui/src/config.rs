@@ -0,0 +1,410 @@
+//! User configuration.
+//!
+//! Settings are read from `config.toml` in the platform config directory
+//! (`~/.config/sweem-tui/config.toml` on Linux). Every key is optional;
+//! a missing file simply yields the defaults.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+use crate::app::{ConfirmButton, EnterAction};
+use crate::dates::{DateFormat, DayCount, WeekStart};
+use crate::filter::{DefaultFilter, DEFAULT_ARCHIVE_DAYS};
+use crate::models::ProjectSort;
+use crate::passwords;
+use crate::snapshot::{self, SnapshotFormat};
+use crate::upcoming::{self, Thresholds};
+
+/// Default page size for lazy loading
+pub const DEFAULT_PAGE_SIZE: i32 = 100;
+
+/// Default frame rate while something on screen is animating
+pub const DEFAULT_FPS: u32 = 30;
+
+/// Frame rates outside this range are clamped
+pub const FPS_RANGE: std::ops::RangeInclusive<u32> = 5..=60;
+
+/// Time between frames at `fps`, clamped to `FPS_RANGE`
+pub fn frame_duration(fps: u32) -> Duration {
+    let fps = fps.clamp(*FPS_RANGE.start(), *FPS_RANGE.end());
+    Duration::from_millis(1000 / fps as u64)
+}
+
+/// Default HTTP request timeout in seconds
+pub const DEFAULT_REQUEST_TIMEOUT_SECS: u64 = 30;
+
+/// Default seconds a collection fetch may take before it is logged as slow
+pub const DEFAULT_SLOW_FETCH_SECS: u64 = 5;
+
+/// Default seconds after the last refresh at which data counts as stale
+pub const DEFAULT_STALE_AFTER_SECS: u64 = 600;
+
+/// Default span of a new project, in days
+pub const DEFAULT_PROJECT_DURATION_DAYS: u32 = 30;
+
+/// Longest `default_project_duration_days` the config accepts (ten years)
+pub const MAX_PROJECT_DURATION_DAYS: u32 = 3650;
+
+/// Default number of lines kept in the System Log
+pub const DEFAULT_MAX_LOGS: usize = 1000;
+
+/// Parsed contents of the config file
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// API base URL used when none is given on the command line
+    pub api_url: Option<String>,
+    /// Disable every create/edit/delete action
+    pub read_only: bool,
+    /// Your login on the server; once users load, its role decides what the
+    /// TUI offers (managers see no Users tab and can't add or delete clients)
+    pub login: Option<String>,
+    /// Named servers, e.g. `[profiles.staging]`
+    pub profiles: BTreeMap<String, Profile>,
+    /// Fetch one page at a time and load more on demand
+    pub lazy_loading: bool,
+    /// Page size used when `lazy_loading` or `page_browsing` is on
+    pub page_size: i32,
+    /// Keep only one page of projects in memory and turn pages with PageDown/PageUp
+    pub page_browsing: bool,
+    /// Timeout for API requests, in seconds
+    pub request_timeout_secs: u64,
+    /// Log a warning when fetching a collection takes longer, in seconds; 0 never warns
+    pub slow_fetch_secs: u64,
+    /// Warn and refresh when the data is older than this, in seconds; 0 never does
+    pub stale_after_secs: u64,
+    /// Lines kept in the System Log, and so in a log export (`L`)
+    pub max_logs: usize,
+    /// Health endpoint probed before falling back to a projects request
+    pub health_path: String,
+    /// Log every API call to the System Log (same as `--trace-api`)
+    pub trace_api: bool,
+    /// PEM bundle of extra CAs trusted for HTTPS (same as `--ca-cert`)
+    pub ca_cert: Option<PathBuf>,
+    /// Skip HTTPS certificate checks (same as `--insecure`)
+    pub insecure: bool,
+    /// Skip decorative animations such as the completion confetti
+    pub reduced_motion: bool,
+    /// Run animations at all; off freezes particles, the radar sweep and toasts
+    pub animations: bool,
+    /// Lowercase logins typed into the user forms, as the backend expects
+    pub lowercase_logins: bool,
+    /// How dates are shown and typed: `iso`, `eu` (dd.mm.yyyy) or `us` (mm/dd/yyyy)
+    pub date_format: DateFormat,
+    /// First column of the date picker calendar: `monday` or `sunday`
+    pub first_day_of_week: WeekStart,
+    /// Project order: `start` (default), `end`, `name` or `server` (as returned)
+    pub project_sort: ProjectSort,
+    /// Width of radar snapshots (`S`), in columns
+    pub snapshot_width: u16,
+    /// Radar snapshot file format: `text` or `ansi` (keeps the colours)
+    pub snapshot_format: SnapshotFormat,
+    /// Hide projects completed more than this many days ago; 0 shows them all
+    pub archive_after_days: u32,
+    /// Filter layer switched on at login: `none` (default) or `mine`
+    pub default_filter: DefaultFilter,
+    /// Frames per second while animating, 5 to 60 (same as `--fps`)
+    pub fps: u32,
+    /// What Enter does on the Timeline: `details` (default), `edit` or `center`
+    pub timeline_enter_action: EnterAction,
+    /// Button focused when a single-item confirm dialog opens: `no` (default) or `yes`
+    pub confirm_default: ConfirmButton,
+    /// Show the changed fields of an edit form for a last look before it is sent
+    pub confirm_edits: bool,
+    /// Zoom the radar out to keep the selected project in view while stepping through projects
+    pub timeline_follow_selection: bool,
+    /// Show the two-week agenda strip under the radar
+    pub agenda_strip: bool,
+    /// Capture the mouse: click a blip to select it, Ctrl+wheel to zoom
+    pub mouse: bool,
+    /// Upcoming deadlines this many days away or closer show red
+    pub deadline_urgent_days: u32,
+    /// Upcoming deadlines this many days away or closer show yellow
+    pub deadline_soon_days: u32,
+    /// Length of passwords generated in the user form (Ctrl+G)
+    pub password_length: usize,
+    /// Span the create form gives a new project, in days (working days with `use_working_days`)
+    pub default_project_duration_days: u32,
+    /// Count Monday to Friday only: new project spans, week steps in the date fields and days left
+    pub use_working_days: bool,
+    /// Show project counts and the connection in the window title; off leaves the title alone
+    pub terminal_title: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            api_url: None,
+            read_only: false,
+            login: None,
+            profiles: BTreeMap::new(),
+            lazy_loading: false,
+            page_size: DEFAULT_PAGE_SIZE,
+            page_browsing: false,
+            request_timeout_secs: DEFAULT_REQUEST_TIMEOUT_SECS,
+            slow_fetch_secs: DEFAULT_SLOW_FETCH_SECS,
+            stale_after_secs: DEFAULT_STALE_AFTER_SECS,
+            max_logs: DEFAULT_MAX_LOGS,
+            health_path: crate::api::DEFAULT_HEALTH_PATH.to_string(),
+            trace_api: false,
+            ca_cert: None,
+            insecure: false,
+            reduced_motion: false,
+            animations: true,
+            lowercase_logins: true,
+            date_format: DateFormat::Iso,
+            first_day_of_week: WeekStart::Monday,
+            project_sort: ProjectSort::Start,
+            snapshot_width: snapshot::DEFAULT_WIDTH,
+            snapshot_format: SnapshotFormat::Text,
+            archive_after_days: DEFAULT_ARCHIVE_DAYS,
+            default_filter: DefaultFilter::None,
+            fps: DEFAULT_FPS,
+            timeline_enter_action: EnterAction::Details,
+            confirm_default: ConfirmButton::No,
+            confirm_edits: false,
+            timeline_follow_selection: true,
+            agenda_strip: true,
+            mouse: true,
+            deadline_urgent_days: upcoming::DEFAULT_URGENT_DAYS as u32,
+            deadline_soon_days: upcoming::DEFAULT_SOON_DAYS as u32,
+            password_length: passwords::DEFAULT_LENGTH,
+            default_project_duration_days: DEFAULT_PROJECT_DURATION_DAYS,
+            use_working_days: false,
+            terminal_title: true,
+        }
+    }
+}
+
+/// A named API server the TUI can connect to
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Profile {
+    /// API base URL
+    pub url: String,
+    /// Bearer token sent with every request
+    #[serde(default)]
+    pub token: Option<String>,
+}
+
+/// Whether a profile name looks like a production server (guard rail colouring)
+pub fn is_production(name: &str) -> bool {
+    name.to_lowercase().contains("prod")
+}
+
+impl Config {
+    /// Location of the config file, if the platform has a config directory
+    pub fn path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("sweem-tui").join("config.toml"))
+    }
+
+    /// Load the config file, falling back to defaults when it doesn't exist
+    pub fn load() -> Result<Self> {
+        match Self::path() {
+            Some(path) if path.exists() => {
+                let text = std::fs::read_to_string(&path)
+                    .with_context(|| format!("Failed to read {}", path.display()))?;
+                Self::parse(&text).with_context(|| format!("Invalid config in {}", path.display()))
+            }
+            _ => Ok(Self::default()),
+        }
+    }
+
+    /// Look up a profile by name, listing the known ones if it doesn't exist
+    pub fn profile(&self, name: &str) -> Result<&Profile> {
+        self.profiles.get(name).with_context(|| {
+            let known: Vec<&str> = self.profiles.keys().map(String::as_str).collect();
+            if known.is_empty() {
+                format!("Unknown profile '{}' (no profiles configured)", name)
+            } else {
+                format!("Unknown profile '{}' (available: {})", name, known.join(", "))
+            }
+        })
+    }
+
+    /// Parse config from TOML text
+    pub fn parse(text: &str) -> Result<Self> {
+        let config: Self = toml::from_str(text)?;
+        if config.page_size < 1 {
+            anyhow::bail!("page_size must be at least 1");
+        }
+        if config.request_timeout_secs == 0 {
+            anyhow::bail!("request_timeout_secs must be at least 1");
+        }
+        if config.snapshot_width < snapshot::MIN_WIDTH {
+            anyhow::bail!("snapshot_width must be at least {}", snapshot::MIN_WIDTH);
+        }
+        if config.deadline_soon_days < config.deadline_urgent_days {
+            anyhow::bail!("deadline_soon_days must not be less than deadline_urgent_days");
+        }
+        if config.password_length < passwords::MIN_LENGTH {
+            anyhow::bail!("password_length must be at least {}", passwords::MIN_LENGTH);
+        }
+        if !(1..=MAX_PROJECT_DURATION_DAYS).contains(&config.default_project_duration_days) {
+            anyhow::bail!("default_project_duration_days must be between 1 and {}", MAX_PROJECT_DURATION_DAYS);
+        }
+        Ok(config)
+    }
+
+    /// Colour cut-offs for the upcoming deadlines list
+    pub fn deadline_thresholds(&self) -> Thresholds {
+        Thresholds {
+            urgent: self.deadline_urgent_days.into(),
+            soon: self.deadline_soon_days.into(),
+        }
+    }
+
+    /// Timeout applied to every API request
+    pub fn request_timeout(&self) -> Duration {
+        Duration::from_secs(self.request_timeout_secs)
+    }
+
+    /// Fetch time above which a warning is logged, if any
+    pub fn slow_fetch(&self) -> Option<Duration> {
+        (self.slow_fetch_secs > 0).then(|| Duration::from_secs(self.slow_fetch_secs))
+    }
+
+    /// How spans of days are counted
+    pub fn day_count(&self) -> DayCount {
+        if self.use_working_days {
+            DayCount::Working
+        } else {
+            DayCount::Calendar
+        }
+    }
+
+    /// Data age at which the status bar warns and a refresh is sent, if any
+    pub fn stale_after(&self) -> Option<Duration> {
+        (self.stale_after_secs > 0).then(|| Duration::from_secs(self.stale_after_secs))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_config() {
+        assert_eq!(Config::parse("").unwrap(), Config::default());
+
+        let config = Config::parse("api_url = \"http://staging:5094\"").unwrap();
+        assert_eq!(config.api_url.as_deref(), Some("http://staging:5094"));
+        assert!(!config.read_only);
+        assert!(Config::parse("read_only = true").unwrap().read_only);
+        assert_eq!(Config::parse("login = \"kim\"").unwrap().login.as_deref(), Some("kim"));
+
+        let lazy = Config::parse("lazy_loading = true\npage_size = 250").unwrap();
+        assert!(lazy.lazy_loading);
+        assert_eq!(lazy.page_size, 250);
+        assert_eq!(Config::default().page_size, DEFAULT_PAGE_SIZE);
+        assert!(Config::parse("page_size = 0").is_err());
+        assert!(Config::parse("page_browsing = true").unwrap().page_browsing);
+        assert_eq!(Config::parse("fps = 12").unwrap().fps, 12);
+
+        let slow = Config::parse("request_timeout_secs = 120").unwrap();
+        assert_eq!(slow.request_timeout(), Duration::from_secs(120));
+        assert!(Config::parse("request_timeout_secs = 0").is_err());
+        assert_eq!(Config::default().slow_fetch(), Some(Duration::from_secs(DEFAULT_SLOW_FETCH_SECS)));
+        assert_eq!(Config::parse("slow_fetch_secs = 0").unwrap().slow_fetch(), None);
+        assert_eq!(Config::default().stale_after(), Some(Duration::from_secs(600)));
+        assert_eq!(Config::parse("stale_after_secs = 0").unwrap().stale_after(), None);
+        assert_eq!(Config::parse("health_path = \"/api/ping\"").unwrap().health_path, "/api/ping");
+        assert!(Config::parse("trace_api = true").unwrap().trace_api);
+        let tls = Config::parse("ca_cert = \"/etc/ssl/internal.pem\"\ninsecure = true").unwrap();
+        assert_eq!(tls.ca_cert, Some(PathBuf::from("/etc/ssl/internal.pem")));
+        assert!(tls.insecure);
+        assert!(Config::parse("reduced_motion = true").unwrap().reduced_motion);
+        assert!(Config::default().animations);
+        assert!(!Config::parse("animations = false").unwrap().animations);
+        assert!(Config::default().lowercase_logins);
+        assert!(!Config::parse("lowercase_logins = false").unwrap().lowercase_logins);
+        assert_eq!(Config::parse("date_format = \"eu\"").unwrap().date_format, DateFormat::Eu);
+        assert_eq!(
+            Config::parse("first_day_of_week = \"sunday\"").unwrap().first_day_of_week,
+            WeekStart::Sunday
+        );
+        assert!(Config::parse("date_format = \"german\"").is_err());
+        assert_eq!(Config::parse("project_sort = \"name\"").unwrap().project_sort, ProjectSort::Name);
+        assert!(Config::parse("project_sort = \"random\"").is_err());
+        assert_eq!(Config::parse("confirm_default = \"yes\"").unwrap().confirm_default, ConfirmButton::Yes);
+        assert!(Config::parse("confirm_default = \"all\"").is_err());
+        let snapshots = Config::parse("snapshot_width = 120\nsnapshot_format = \"ansi\"").unwrap();
+        assert_eq!((snapshots.snapshot_width, snapshots.snapshot_format), (120, SnapshotFormat::Ansi));
+        assert!(Config::parse("snapshot_width = 10").is_err());
+        assert_eq!(Config::default().archive_after_days, 90);
+        assert_eq!(Config::parse("archive_after_days = 0").unwrap().archive_after_days, 0);
+        assert_eq!(Config::parse("default_filter = \"mine\"").unwrap().default_filter, DefaultFilter::Mine);
+        assert!(Config::parse("default_filter = \"theirs\"").is_err());
+        assert_eq!(Config::default().timeline_enter_action, EnterAction::Details);
+        let center = Config::parse("timeline_enter_action = \"center\"").unwrap();
+        assert_eq!(center.timeline_enter_action, EnterAction::Center);
+        assert!(Config::parse("timeline_enter_action = \"jump\"").is_err());
+        assert!(Config::default().timeline_follow_selection);
+        assert!(!Config::parse("timeline_follow_selection = false").unwrap().timeline_follow_selection);
+        assert!(!Config::default().confirm_edits);
+        assert!(Config::parse("confirm_edits = true").unwrap().confirm_edits);
+        assert!(Config::default().agenda_strip);
+        assert!(!Config::parse("agenda_strip = false").unwrap().agenda_strip);
+        assert!(!Config::parse("mouse = false").unwrap().mouse);
+        assert_eq!(Config::default().deadline_thresholds(), Thresholds::default());
+        let relaxed = Config::parse("deadline_urgent_days = 1\ndeadline_soon_days = 14").unwrap();
+        assert_eq!(relaxed.deadline_thresholds(), Thresholds { urgent: 1, soon: 14 });
+        assert!(Config::parse("deadline_urgent_days = 10").is_err());
+        assert_eq!(Config::default().password_length, 16);
+        assert_eq!(Config::parse("password_length = 24").unwrap().password_length, 24);
+        assert!(Config::parse("password_length = 4").is_err());
+    }
+
+    #[test]
+    fn test_project_duration_and_working_days() {
+        let config = Config::default();
+        assert_eq!((config.default_project_duration_days, config.day_count()), (30, DayCount::Calendar));
+        let config = Config::parse("default_project_duration_days = 14\nuse_working_days = true").unwrap();
+        assert_eq!((config.default_project_duration_days, config.day_count()), (14, DayCount::Working));
+        assert!(Config::parse("default_project_duration_days = 0").is_err());
+        assert!(Config::parse("default_project_duration_days = 3650").is_ok());
+        assert!(Config::parse("default_project_duration_days = 3651").is_err());
+        assert!(Config::parse("default_project_duration_days = 4000000000").is_err());
+        assert!(Config::default().terminal_title);
+        assert!(!Config::parse("terminal_title = false").unwrap().terminal_title);
+
+        assert!(Config::parse("api_url = 5").is_err());
+    }
+
+    #[test]
+    fn test_frame_duration_is_clamped() {
+        assert_eq!(frame_duration(DEFAULT_FPS), Duration::from_millis(33));
+        assert_eq!(frame_duration(10), Duration::from_millis(100));
+        assert_eq!(frame_duration(0), Duration::from_millis(200));
+        assert_eq!(frame_duration(240), Duration::from_millis(16));
+    }
+
+    #[test]
+    fn test_parse_profiles() {
+        let config = Config::parse(
+            r#"
+            [profiles.dev]
+            url = "http://localhost:5094"
+
+            [profiles.prod]
+            url = "https://sweem.example.com"
+            token = "secret"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.profiles.len(), 2);
+        assert_eq!(config.profile("dev").unwrap().token, None);
+        assert_eq!(config.profile("prod").unwrap().token.as_deref(), Some("secret"));
+
+        assert!(is_production("prod"));
+        assert!(is_production("EU-Production"));
+        assert!(!is_production("dev"));
+
+        let err = config.profile("staging").unwrap_err().to_string();
+        assert!(err.contains("available: dev, prod"), "{}", err);
+    }
+}
@@ -0,0 +1,324 @@
+//! Date display settings and day counting.
+//!
+//! Every date shown to the user or typed into a form goes through
+//! `format_date` / `parse_date` with the configured `DateFormat`. Machine
+//! output (`--json`, CSV import, the API itself) always stays ISO 8601.
+//!
+//! Spans of days are counted by `DayCount`: calendar days, or Monday to
+//! Friday only (`use_working_days`). Working days know no holidays.
+
+use chrono::{Datelike, Duration, Months, NaiveDate, Weekday};
+use serde::Deserialize;
+
+/// How dates are written in the UI and the Markdown report
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DateFormat {
+    /// 2024-05-31
+    #[default]
+    Iso,
+    /// 31.05.2024
+    Eu,
+    /// 05/31/2024
+    Us,
+}
+
+impl DateFormat {
+    fn pattern(self) -> &'static str {
+        match self {
+            DateFormat::Iso => "%Y-%m-%d",
+            DateFormat::Eu => "%d.%m.%Y",
+            DateFormat::Us => "%m/%d/%Y",
+        }
+    }
+}
+
+/// Write `date` in the configured format
+pub fn format_date(date: &NaiveDate, format: DateFormat) -> String {
+    date.format(format.pattern()).to_string()
+}
+
+/// Read a date typed in the configured format. ISO is accepted too, since it
+/// can't be mistaken for either of the others.
+pub fn parse_date(text: &str, format: DateFormat) -> Option<NaiveDate> {
+    let text = text.trim();
+    NaiveDate::parse_from_str(text, format.pattern())
+        .or_else(|_| NaiveDate::parse_from_str(text, DateFormat::Iso.pattern()))
+        .ok()
+}
+
+/// A date typed as in `parse_date`, or relative to `today`: "today",
+/// "tomorrow", "yesterday", or a signed number of days, weeks or months
+/// ("+10d", "-2w", "+3m"; a bare "+10" is days)
+pub fn parse_date_input(text: &str, format: DateFormat, today: NaiveDate) -> Option<NaiveDate> {
+    let text = text.trim().to_lowercase();
+    match text.as_str() {
+        "today" => return Some(today),
+        "tomorrow" => return today.succ_opt(),
+        "yesterday" => return today.pred_opt(),
+        _ => {}
+    }
+    let Some(sign) = text.chars().next().filter(|c| matches!(c, '+' | '-')) else {
+        return parse_date(&text, format);
+    };
+    let rest = &text[1..];
+    let (count, unit) = rest.split_at(rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len()));
+    let count: u32 = count.parse().ok()?;
+    let days = |n: i64| today.checked_add_signed(Duration::days(if sign == '-' { -n } else { n }));
+    match unit.trim() {
+        "" | "d" => days(count.into()),
+        "w" => days(i64::from(count) * 7),
+        "m" if sign == '-' => today.checked_sub_months(Months::new(count)),
+        "m" => today.checked_add_months(Months::new(count)),
+        _ => None,
+    }
+}
+
+/// First column of the calendar
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WeekStart {
+    #[default]
+    Monday,
+    Sunday,
+}
+
+impl WeekStart {
+    /// Column (0-6) of `weekday` in a calendar starting on this day
+    pub fn column(self, weekday: Weekday) -> usize {
+        match self {
+            WeekStart::Monday => weekday.num_days_from_monday() as usize,
+            WeekStart::Sunday => weekday.num_days_from_sunday() as usize,
+        }
+    }
+
+    /// Weekday shown in `column`
+    pub fn weekday(self, column: usize) -> Weekday {
+        let first = match self {
+            WeekStart::Monday => Weekday::Mon,
+            WeekStart::Sunday => Weekday::Sun,
+        };
+        (0..column).fold(first, |day, _| day.succ())
+    }
+}
+
+/// Whether `date` falls on Monday to Friday
+pub fn is_working_day(date: NaiveDate) -> bool {
+    !matches!(date.weekday(), Weekday::Sat | Weekday::Sun)
+}
+
+/// Working days stepped onto going from `a` to `b`: those after `a` up to
+/// and including `b`, or negated, those from `b` up to `a` when `b` comes
+/// first. Friday to Monday is one either way, Saturday to Sunday none.
+pub fn working_days_between(a: NaiveDate, b: NaiveDate) -> i64 {
+    if b < a {
+        let day = Duration::days(1);
+        return -working_days_between(b - day, a - day);
+    }
+    let days = (b - a).num_days();
+    // Every seven days in a row hold five working days
+    let rest = (1..=days % 7).filter(|&i| is_working_day(a + Duration::days(i))).count() as i64;
+    days / 7 * 5 + rest
+}
+
+/// The `n`th working day after `date` (before it for a negative `n`);
+/// `date` itself for 0
+pub fn add_working_days(date: NaiveDate, n: i64) -> NaiveDate {
+    let step = Duration::days(n.signum());
+    let mut date = date;
+    for _ in 0..n.abs() {
+        date += step;
+        while !is_working_day(date) {
+            date += step;
+        }
+    }
+    date
+}
+
+/// How spans of days are counted (`use_working_days` in the config)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DayCount {
+    #[default]
+    Calendar,
+    /// Monday to Friday only
+    Working,
+}
+
+impl DayCount {
+    /// Days counted from `a` to `b`, negative when `b` comes first
+    pub fn between(self, a: NaiveDate, b: NaiveDate) -> i64 {
+        match self {
+            DayCount::Calendar => (b - a).num_days(),
+            DayCount::Working => working_days_between(a, b),
+        }
+    }
+
+    /// `date` moved by `n` counted days, stopping at the last (or first)
+    /// representable date
+    pub fn add(self, date: NaiveDate, n: i64) -> NaiveDate {
+        match self {
+            DayCount::Calendar => Duration::try_days(n)
+                .and_then(|span| date.checked_add_signed(span))
+                .unwrap_or(if n < 0 { NaiveDate::MIN } else { NaiveDate::MAX }),
+            DayCount::Working => add_working_days(date, n),
+        }
+    }
+
+    /// Counted days in a week
+    pub fn week(self) -> i64 {
+        match self {
+            DayCount::Calendar => 7,
+            DayCount::Working => 5,
+        }
+    }
+
+    /// "14 days" or "14 wd"
+    pub fn label(self, days: i64) -> String {
+        match self {
+            DayCount::Calendar => format!("{} days", days),
+            DayCount::Working => format!("{} wd", days),
+        }
+    }
+
+    /// Unit after a short count: "d" or "wd"
+    pub fn suffix(self) -> &'static str {
+        match self {
+            DayCount::Calendar => "d",
+            DayCount::Working => "wd",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::date;
+
+    #[test]
+    fn test_formats() {
+        let d = date("2024-05-31");
+        assert_eq!(format_date(&d, DateFormat::Iso), "2024-05-31");
+        assert_eq!(format_date(&d, DateFormat::Eu), "31.05.2024");
+        assert_eq!(format_date(&d, DateFormat::Us), "05/31/2024");
+
+        for format in [DateFormat::Iso, DateFormat::Eu, DateFormat::Us] {
+            assert_eq!(parse_date(&format_date(&d, format), format), Some(d));
+            assert_eq!(parse_date(" 2024-05-31 ", format), Some(d));
+        }
+        assert_eq!(parse_date("31.05.2024", DateFormat::Us), None);
+        assert_eq!(parse_date("05/31/2024", DateFormat::Eu), None);
+    }
+
+    #[test]
+    fn test_relative_dates() {
+        let today = date("2024-05-31");
+        let parse = |text| parse_date_input(text, DateFormat::Eu, today);
+        assert_eq!(parse("today"), Some(today));
+        assert_eq!(parse(" Tomorrow "), Some(date("2024-06-01")));
+        assert_eq!(parse("yesterday"), Some(date("2024-05-30")));
+        assert_eq!(parse("+10d"), Some(date("2024-06-10")));
+        assert_eq!(parse("+10"), Some(date("2024-06-10")));
+        assert_eq!(parse("-2w"), Some(date("2024-05-17")));
+        assert_eq!(parse("+1m"), Some(date("2024-06-30")), "clamped to the month's end");
+        assert_eq!(parse("-3M"), Some(date("2024-02-29")));
+        // Absolute dates in the configured format, or ISO
+        assert_eq!(parse("10.03.2025"), Some(date("2025-03-10")));
+        assert_eq!(parse("2025-03-10"), Some(date("2025-03-10")));
+        for bad in ["", "+", "+d", "+3y", "3d", "-x", "next week", "03/10/2025"] {
+            assert_eq!(parse(bad), None, "{:?}", bad);
+        }
+    }
+
+    #[test]
+    fn test_week_start_columns() {
+        assert_eq!(WeekStart::Monday.column(Weekday::Mon), 0);
+        assert_eq!(WeekStart::Monday.column(Weekday::Sun), 6);
+        assert_eq!(WeekStart::Sunday.column(Weekday::Sun), 0);
+        assert_eq!(WeekStart::Sunday.column(Weekday::Sat), 6);
+        assert_eq!(WeekStart::Sunday.weekday(1), Weekday::Mon);
+        assert_eq!(WeekStart::Monday.weekday(6), Weekday::Sun);
+    }
+
+    #[test]
+    fn test_working_days_between() {
+        // 2024-05-27 is a Monday
+        let monday = date("2024-05-27");
+        assert_eq!(working_days_between(monday, monday), 0);
+        assert_eq!(working_days_between(monday, date("2024-05-31")), 4);
+        assert_eq!(working_days_between(monday, date("2024-06-01")), 4, "Saturday adds nothing");
+        assert_eq!(working_days_between(monday, date("2024-06-02")), 4);
+        assert_eq!(working_days_between(monday, date("2024-06-03")), 5);
+        assert_eq!(working_days_between(date("2024-05-31"), date("2024-06-03")), 1, "Friday to Monday");
+        assert_eq!(working_days_between(date("2024-06-01"), date("2024-06-02")), 0, "a weekend");
+        assert_eq!(working_days_between(date("2024-06-01"), date("2024-06-03")), 1);
+        assert_eq!(working_days_between(date("2024-06-03"), monday), -5);
+        assert_eq!(working_days_between(date("2024-06-02"), date("2024-05-31")), -1);
+        assert_eq!(working_days_between(date("2024-06-03"), date("2024-05-31")), -1, "Monday to Friday");
+        assert_eq!(working_days_between(date("2024-06-02"), date("2024-06-01")), 0);
+        // Across months, a leap day and a year
+        assert_eq!(working_days_between(date("2024-02-26"), date("2024-03-04")), 5);
+        assert_eq!(working_days_between(date("2024-01-01"), date("2025-01-01")), 262);
+    }
+
+    #[test]
+    fn test_working_days_between_matches_a_day_by_day_count() {
+        let start = date("2024-05-20");
+        for a in 0..14 {
+            for b in 0..60 {
+                let (a, b) = (start + Duration::days(a), start + Duration::days(b));
+                let working = |from: NaiveDate, to: NaiveDate| {
+                    from.iter_days().take_while(|d| *d < to).filter(|d| is_working_day(*d)).count() as i64
+                };
+                // After `a` through `b`, or from `b` up to `a`
+                let next = |d: NaiveDate| d.succ_opt().unwrap();
+                let expected = if b < a { -working(b, a) } else { working(next(a), next(b)) };
+                assert_eq!(working_days_between(a, b), expected, "{} to {}", a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn test_add_working_days() {
+        let friday = date("2024-05-31");
+        assert_eq!(add_working_days(friday, 0), friday);
+        assert_eq!(add_working_days(friday, 1), date("2024-06-03"));
+        assert_eq!(add_working_days(friday, 5), date("2024-06-07"));
+        assert_eq!(add_working_days(friday, -4), date("2024-05-27"));
+        assert_eq!(add_working_days(friday, -5), date("2024-05-24"));
+        assert_eq!(add_working_days(date("2024-06-03"), -1), friday, "Monday back to Friday");
+        // From a weekend, the first working day counts as one
+        assert_eq!(add_working_days(date("2024-06-01"), 1), date("2024-06-03"));
+        assert_eq!(add_working_days(date("2024-06-01"), 5), date("2024-06-07"));
+        assert_eq!(add_working_days(date("2024-06-02"), -1), friday);
+        assert_eq!(add_working_days(date("2024-01-01"), 262), date("2025-01-01"));
+    }
+
+    #[test]
+    fn test_add_working_days_inverts_between() {
+        let start = date("2024-05-20");
+        for offset in 0..7 {
+            let day = start + Duration::days(offset);
+            for n in -30..=30 {
+                let moved = add_working_days(day, n);
+                assert_eq!(working_days_between(day, moved), n, "{} + {}", day, n);
+                if n != 0 {
+                    assert!(is_working_day(moved), "{} + {} = {}", day, n, moved);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_day_counts() {
+        let (monday, next) = (date("2024-05-27"), date("2024-06-03"));
+        assert_eq!(DayCount::Calendar.between(monday, next), 7);
+        assert_eq!(DayCount::Working.between(monday, next), 5);
+        assert_eq!(DayCount::Calendar.add(monday, DayCount::Calendar.week()), next);
+        assert_eq!(DayCount::Working.add(monday, DayCount::Working.week()), next);
+        assert_eq!(DayCount::Working.add(monday, 14), date("2024-06-14"));
+        assert_eq!(DayCount::Calendar.add(monday, 4_000_000_000), NaiveDate::MAX);
+        assert_eq!(DayCount::Calendar.add(monday, i64::MIN), NaiveDate::MIN);
+        assert_eq!(DayCount::Calendar.label(14), "14 days");
+        assert_eq!(DayCount::Working.label(14), "14 wd");
+    }
+}
@@ -0,0 +1,303 @@
+//! Changes between two loads of a collection.
+//!
+//! A reload replaces a whole collection at once, so edits made by someone
+//! else would otherwise slip by unnoticed. `diff_entities` compares the
+//! previous and the new list by id; the App turns the result into badges and
+//! log lines. `diff_update` does the same for an edit form before it is
+//! sent, comparing the entity as loaded with what the form built.
+
+use ratatui::style::Style;
+use uuid::Uuid;
+
+use crate::dates::{format_date, DateFormat};
+use crate::models::{ClientDto, ProjectDto, UpdateClientDto, UpdateProjectDto, UpdateUserDto, UserDto};
+use crate::report::Names;
+use crate::theme::styles;
+
+/// An entity that can be compared across reloads
+pub trait Tracked {
+    fn id(&self) -> Uuid;
+    /// How the entity is named in log lines, e.g. "Project Alpha"
+    fn label(&self) -> String;
+    /// Fields worth reporting when they change, with their display names
+    fn fields(&self, format: DateFormat) -> Vec<(&'static str, String)>;
+}
+
+impl Tracked for ProjectDto {
+    fn id(&self) -> Uuid {
+        self.id
+    }
+
+    fn label(&self) -> String {
+        format!("Project {}", self.display_name())
+    }
+
+    fn fields(&self, format: DateFormat) -> Vec<(&'static str, String)> {
+        vec![
+            ("name", self.display_name().to_string()),
+            ("start", format_date(&self.start_date, format)),
+            ("planned end", format_date(&self.planned_end_date, format)),
+            ("completed", self.actual_end_date.map_or("no".to_string(), |d| format_date(&d, format))),
+        ]
+    }
+}
+
+impl Tracked for ClientDto {
+    fn id(&self) -> Uuid {
+        self.id
+    }
+
+    fn label(&self) -> String {
+        format!("Client {}", self.display_name())
+    }
+
+    fn fields(&self, _format: DateFormat) -> Vec<(&'static str, String)> {
+        vec![
+            ("name", self.display_name().to_string()),
+            ("address", self.address.clone().unwrap_or_default()),
+        ]
+    }
+}
+
+impl Tracked for UserDto {
+    fn id(&self) -> Uuid {
+        self.id
+    }
+
+    fn label(&self) -> String {
+        format!("User {}", self.display_name())
+    }
+
+    fn fields(&self, _format: DateFormat) -> Vec<(&'static str, String)> {
+        vec![
+            ("name", self.display_name().to_string()),
+            ("login", self.login.clone().unwrap_or_default()),
+            ("role", self.role.to_string()),
+        ]
+    }
+}
+
+/// Badge shown next to an entity for a while after a reload
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Badge {
+    New,
+    Changed,
+}
+
+impl Badge {
+    pub fn label(self) -> &'static str {
+        match self {
+            Badge::New => "NEW",
+            Badge::Changed => "CHANGED",
+        }
+    }
+
+    pub fn style(self) -> Style {
+        match self {
+            Badge::New => styles::success(),
+            Badge::Changed => styles::warning(),
+        }
+    }
+}
+
+/// An entity whose fields differ between the two loads
+#[derive(Debug, Clone, PartialEq)]
+pub struct Change {
+    pub id: Uuid,
+    /// Label before the change, so a rename reads "Project Old: name Old → New"
+    pub label: String,
+    /// One "field old → new" entry per changed field
+    pub fields: Vec<String>,
+}
+
+impl Change {
+    /// Log line, e.g. "Project Alpha: planned end 2024-06-01 → 2024-06-15"
+    pub fn describe(&self) -> String {
+        format!("{}: {}", self.label, self.fields.join(", "))
+    }
+}
+
+/// What happened to a collection between two loads
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Diff {
+    pub added: Vec<Uuid>,
+    pub changed: Vec<Change>,
+    /// Labels of the entities that are gone
+    pub removed: Vec<String>,
+}
+
+/// Compare two loads of the same collection by id
+pub fn diff_entities<T: Tracked>(old: &[T], new: &[T], format: DateFormat) -> Diff {
+    let mut diff = Diff::default();
+    for item in new {
+        let Some(before) = old.iter().find(|o| o.id() == item.id()) else {
+            diff.added.push(item.id());
+            continue;
+        };
+        let fields: Vec<String> = before
+            .fields(format)
+            .into_iter()
+            .zip(item.fields(format))
+            .filter(|((_, was), (_, now))| was != now)
+            .map(|((name, was), (_, now))| format!("{} {} → {}", name, was, now))
+            .collect();
+        if !fields.is_empty() {
+            diff.changed.push(Change { id: item.id(), label: before.label(), fields });
+        }
+    }
+    diff.removed = old
+        .iter()
+        .filter(|o| !new.iter().any(|n| n.id() == o.id()))
+        .map(Tracked::label)
+        .collect();
+    diff
+}
+
+/// A field an edit form is about to change
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldChange {
+    pub field: &'static str,
+    pub old: String,
+    pub new: String,
+}
+
+/// An update body whose fields can be reviewed before it is sent
+pub trait Reviewed {
+    /// Fields as shown in the review, with clients and managers by name.
+    /// Secrets are never shown, only whether one is being set.
+    fn review_fields(&self, names: &dyn Names, format: DateFormat) -> Vec<(&'static str, String)>;
+}
+
+impl Reviewed for UpdateProjectDto {
+    fn review_fields(&self, names: &dyn Names, format: DateFormat) -> Vec<(&'static str, String)> {
+        vec![
+            ("name", self.name.clone().unwrap_or_default()),
+            ("client", names.client_name(self.client_id)),
+            ("manager", names.user_name(self.manager_id)),
+            ("start", format_date(&self.start_date, format)),
+            ("planned end", format_date(&self.planned_end_date, format)),
+            ("completed", self.actual_end_date.map_or("no".to_string(), |d| format_date(&d, format))),
+        ]
+    }
+}
+
+impl Reviewed for UpdateClientDto {
+    fn review_fields(&self, _names: &dyn Names, _format: DateFormat) -> Vec<(&'static str, String)> {
+        vec![
+            ("name", self.name.clone().unwrap_or_default()),
+            ("address", self.address.clone().unwrap_or_default()),
+        ]
+    }
+}
+
+impl Reviewed for UpdateUserDto {
+    fn review_fields(&self, _names: &dyn Names, _format: DateFormat) -> Vec<(&'static str, String)> {
+        // A blank password field sends none, which keeps the current one
+        let password = match self.password.as_deref() {
+            Some(p) if !p.is_empty() => "(new password)",
+            _ => "(unchanged)",
+        };
+        vec![
+            ("name", self.name.clone().unwrap_or_default()),
+            ("login", self.login.clone().unwrap_or_default()),
+            ("role", self.role.to_string()),
+            ("password", password.to_string()),
+        ]
+    }
+}
+
+/// The fields `new` changes compared with `old`, in form order
+pub fn diff_update<T: Reviewed>(old: &T, new: &T, names: &dyn Names, format: DateFormat) -> Vec<FieldChange> {
+    old.review_fields(names, format)
+        .into_iter()
+        .zip(new.review_fields(names, format))
+        .filter(|((_, was), (_, now))| was != now)
+        .map(|((field, old), (_, new))| FieldChange { field, old, new })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{client, date, project, user, ClientFixture, ProjectFixture, UserFixture};
+
+    #[test]
+    fn test_diff_finds_added_changed_and_removed() {
+        let old = vec![
+            project(1).named("Alpha").ends("2024-06-01"),
+            project(2).named("Beta").ends("2024-07-01"),
+            project(3).named("Gamma").ends("2024-08-01"),
+        ];
+        let new = vec![
+            project(1).named("Alpha").ends("2024-06-15"),
+            project(2).named("Beta").ends("2024-07-01"),
+            project(4).named("Delta").ends("2024-09-01"),
+        ];
+        let diff = diff_entities(&old, &new, DateFormat::Iso);
+
+        assert_eq!(diff.added, vec![Uuid::from_u128(4)]);
+        assert_eq!(diff.removed, vec!["Project Gamma".to_string()]);
+        let [change] = diff.changed.as_slice() else { panic!("unexpected changes: {:?}", diff.changed) };
+        assert_eq!(change.id, Uuid::from_u128(1));
+        assert_eq!(change.describe(), "Project Alpha: planned end 2024-06-01 → 2024-06-15");
+    }
+
+    #[test]
+    fn test_diff_of_identical_loads_is_empty() {
+        let load = vec![project(1).named("Alpha").ends("2024-06-01"), project(2).named("Beta").ends("2024-07-01")];
+        let mut reordered = load.clone();
+        reordered.reverse();
+        assert_eq!(diff_entities(&load, &reordered, DateFormat::Iso), Diff::default());
+    }
+
+    #[test]
+    fn test_changes_use_the_date_format_and_list_every_field() {
+        let old = vec![project(1).named("Alpha").ends("2024-06-01")];
+        let renamed = project(1).named("Alpha II").ends("2024-06-02").done();
+        let diff = diff_entities(&old, &[renamed], DateFormat::Eu);
+        assert_eq!(
+            diff.changed[0].describe(),
+            "Project Alpha: name Alpha → Alpha II, planned end 01.06.2024 → 02.06.2024, completed no → 02.06.2024"
+        );
+    }
+
+    #[test]
+    fn test_diff_update_lists_changed_project_fields() {
+        let clients = vec![client(7).named("Acme"), client(8).named("Globex")];
+        let names = crate::report::Loaded { clients: &clients, users: &[] };
+        let loaded = project(1).named("Alpha").ends("2024-06-01").client(7);
+        let old = UpdateProjectDto::from_project(&loaded);
+        assert!(diff_update(&old, &old.clone(), &names, DateFormat::Iso).is_empty());
+
+        let new =
+            UpdateProjectDto { client_id: Uuid::from_u128(8), planned_end_date: date("2024-05-20"), ..old.clone() };
+        let changes = diff_update(&old, &new, &names, DateFormat::Iso);
+        let shown: Vec<_> = changes.iter().map(|c| (c.field, c.old.as_str(), c.new.as_str())).collect();
+        assert_eq!(shown, [("client", "Acme", "Globex"), ("planned end", "2024-06-01", "2024-05-20")]);
+    }
+
+    #[test]
+    fn test_diff_update_for_clients() {
+        let names = crate::report::Loaded { clients: &[], users: &[] };
+        let old = UpdateClientDto { name: Some("Acme".to_string()), address: None, ..Default::default() };
+        let new = UpdateClientDto { address: Some("1 Main St".to_string()), ..old.clone() };
+        let changes = diff_update(&old, &new, &names, DateFormat::Iso);
+        assert_eq!(changes, [FieldChange { field: "address", old: String::new(), new: "1 Main St".to_string() }]);
+    }
+
+    #[test]
+    fn test_diff_update_never_shows_passwords() {
+        let names = crate::report::Loaded { clients: &[], users: &[] };
+        let old = UpdateUserDto::from_user(&user(1).named("Lee"));
+        // A blank password keeps the current one
+        let blank = UpdateUserDto { password: Some(String::new()), ..old.clone() };
+        assert!(diff_update(&old, &blank, &names, DateFormat::Iso).is_empty());
+
+        let new =
+            UpdateUserDto { password: Some("hunter2".to_string()), login: Some("lee".to_string()), ..old.clone() };
+        let changes = diff_update(&old, &new, &names, DateFormat::Iso);
+        assert_eq!(changes.iter().map(|c| c.field).collect::<Vec<_>>(), ["login", "password"]);
+        assert_eq!((changes[1].old.as_str(), changes[1].new.as_str()), ("(unchanged)", "(new password)"));
+        assert!(changes.iter().all(|c| !c.old.contains("hunter2") && !c.new.contains("hunter2")));
+    }
+}
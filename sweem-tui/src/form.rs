@@ -0,0 +1,917 @@
+//! Create and edit forms for clients, projects and users.
+//!
+//! Each entity has its own form struct holding only its fields, plus a
+//! `FormNavigation` with the focus, error line and save state every form
+//! shares. `FormInput` gives them all the same Tab order and typing
+//! behaviour; `FormState` is the one open form.
+
+#![allow(dead_code)]
+
+use crossterm::event::KeyEvent;
+use uuid::Uuid;
+
+use crate::api::EntityType;
+use crate::dates::{format_date, parse_date, DayCount, DateFormat};
+use crate::models::{
+    is_valid_date, ClientDto, CreateClientDto, CreateProjectDto, CreateUserDto, ProjectDto, Role, UpdateClientDto,
+    UpdateProjectDto, UpdateUserDto, UserDto, Warning,
+};
+use crate::widgets::TextInput;
+
+/// Type of form being displayed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FormType {
+    /// Creating a new client
+    CreateClient,
+    /// Editing an existing client
+    EditClient(Uuid),
+    /// Creating a new project
+    CreateProject,
+    /// Editing an existing project
+    EditProject(Uuid),
+    /// Creating a new user
+    CreateUser,
+    /// Editing an existing user
+    EditUser(Uuid),
+}
+
+impl FormType {
+    /// The entity an edit form changes
+    pub fn edited(&self) -> Option<(EntityType, Uuid)> {
+        match *self {
+            FormType::EditClient(id) => Some((EntityType::Client, id)),
+            FormType::EditProject(id) => Some((EntityType::Project, id)),
+            FormType::EditUser(id) => Some((EntityType::User, id)),
+            _ => None,
+        }
+    }
+}
+
+/// Form field types for different entities
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FormField {
+    // Client fields
+    ClientName,
+    ClientAddress,
+    // Project fields
+    ProjectName,
+    ProjectClient,
+    ProjectManager,
+    ProjectStartDate,
+    ProjectEndDate,
+    // User fields
+    UserName,
+    UserLogin,
+    UserPassword,
+    UserRole,
+    // Form buttons
+    SubmitButton,
+    CancelButton,
+}
+
+impl FormField {
+    /// Get all fields for client form
+    pub fn client_fields() -> &'static [FormField] {
+        &[
+            FormField::ClientName,
+            FormField::ClientAddress,
+            FormField::SubmitButton,
+            FormField::CancelButton,
+        ]
+    }
+
+    /// Get all fields for project form
+    pub fn project_fields() -> &'static [FormField] {
+        &[
+            FormField::ProjectName,
+            FormField::ProjectClient,
+            FormField::ProjectManager,
+            FormField::ProjectStartDate,
+            FormField::ProjectEndDate,
+            FormField::SubmitButton,
+            FormField::CancelButton,
+        ]
+    }
+
+    /// Get all fields for user form
+    pub fn user_fields() -> &'static [FormField] {
+        &[
+            FormField::UserName,
+            FormField::UserLogin,
+            FormField::UserPassword,
+            FormField::UserRole,
+            FormField::SubmitButton,
+            FormField::CancelButton,
+        ]
+    }
+
+    /// Get display label for the field
+    pub fn label(&self) -> &'static str {
+        match self {
+            FormField::ClientName => "Name",
+            FormField::ClientAddress => "Address",
+            FormField::ProjectName => "Name",
+            FormField::ProjectClient => "Client",
+            FormField::ProjectManager => "Manager",
+            FormField::ProjectStartDate => "Start Date",
+            FormField::ProjectEndDate => "End Date",
+            FormField::UserName => "Name",
+            FormField::UserLogin => "Login",
+            FormField::UserPassword => "Password",
+            FormField::UserRole => "Role",
+            FormField::SubmitButton => "Save",
+            FormField::CancelButton => "Cancel",
+        }
+    }
+
+    /// Check if this is a text input field
+    pub fn is_text_input(&self) -> bool {
+        matches!(
+            self,
+            FormField::ClientName
+                | FormField::ClientAddress
+                | FormField::ProjectName
+                | FormField::UserName
+                | FormField::UserLogin
+                | FormField::UserPassword
+        )
+    }
+
+    /// Check if this is a date picker field
+    pub fn is_date_picker(&self) -> bool {
+        matches!(
+            self,
+            FormField::ProjectStartDate | FormField::ProjectEndDate
+        )
+    }
+
+    /// Check if this is a dropdown/selector field
+    pub fn is_selector(&self) -> bool {
+        matches!(
+            self,
+            FormField::ProjectClient | FormField::ProjectManager | FormField::UserRole
+        )
+    }
+
+    /// Check if this is a button
+    pub fn is_button(&self) -> bool {
+        matches!(self, FormField::SubmitButton | FormField::CancelButton)
+    }
+}
+
+/// Conflict state of an edit form
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Staleness {
+    /// The form matches what was loaded
+    #[default]
+    Fresh,
+    /// An update was rejected because the item changed or was removed
+    Stale,
+    /// Waiting for fresh data to re-prefill the form
+    Reloading,
+}
+
+/// What every form shares: the field order and focus, the error line, and
+/// where a save stands
+#[derive(Debug, Clone)]
+pub struct FormNavigation {
+    /// Currently focused field index
+    pub focused_field: usize,
+    /// Fields of the form, in Tab order
+    pub fields: Vec<FormField>,
+    /// Validation error message
+    pub error: Option<String>,
+    /// Whether dropdown is open
+    pub dropdown_open: bool,
+    /// Whether the server copy changed underneath an edit form
+    pub staleness: Staleness,
+    /// Fingerprint of the server copy an edit form was filled from
+    pub snapshot: Option<u64>,
+    /// A save is in flight; the form ignores input until it answers
+    pub submitting: bool,
+}
+
+impl FormNavigation {
+    fn new(fields: &[FormField]) -> Self {
+        Self {
+            focused_field: 0,
+            fields: fields.to_vec(),
+            error: None,
+            dropdown_open: false,
+            staleness: Staleness::Fresh,
+            snapshot: None,
+            submitting: false,
+        }
+    }
+}
+
+/// Focus and typing, the same for every form
+pub trait FormInput {
+    fn nav(&self) -> &FormNavigation;
+    fn nav_mut(&mut self) -> &mut FormNavigation;
+    /// The text behind `field`, when it is one of this form's text inputs
+    fn text_mut(&mut self, field: FormField) -> Option<&mut TextInput>;
+
+    /// Get the current focused field
+    fn current_field(&self) -> FormField {
+        let nav = self.nav();
+        nav.fields[nav.focused_field]
+    }
+
+    /// Mask again a password that was shown while it had focus
+    fn leave_field(&mut self) {
+        let field = self.current_field();
+        if let Some(text) = self.text_mut(field) {
+            text.hide();
+        }
+    }
+
+    /// Move to the next field, wrapping around to the first
+    fn next_field(&mut self) {
+        self.leave_field();
+        let nav = self.nav_mut();
+        nav.focused_field = (nav.focused_field + 1) % nav.fields.len();
+        nav.dropdown_open = false;
+    }
+
+    /// Move to the previous field, wrapping around to the last
+    fn prev_field(&mut self) {
+        self.leave_field();
+        let nav = self.nav_mut();
+        nav.focused_field = nav.focused_field.checked_sub(1).unwrap_or(nav.fields.len() - 1);
+        nav.dropdown_open = false;
+    }
+
+    /// Handle character input
+    fn handle_char(&mut self, c: char) {
+        let field = self.current_field();
+        if let Some(text) = self.text_mut(field) {
+            text.insert(c);
+        }
+    }
+
+    /// Handle backspace
+    fn handle_backspace(&mut self) {
+        let field = self.current_field();
+        if let Some(text) = self.text_mut(field) {
+            text.backspace();
+        }
+    }
+
+    /// Pass an editing key to the focused text field; false when no text
+    /// field has focus or it doesn't use the key
+    fn handle_text_key(&mut self, key: KeyEvent) -> bool {
+        let field = self.current_field();
+        self.text_mut(field).is_some_and(|text| text.handle_key(key))
+    }
+}
+
+/// Client create/edit form
+#[derive(Debug, Clone)]
+pub struct ClientForm {
+    pub nav: FormNavigation,
+    /// The client being edited; None for a new one
+    pub editing: Option<Uuid>,
+    pub name: TextInput,
+    pub address: TextInput,
+}
+
+impl ClientForm {
+    pub fn new_create() -> Self {
+        Self {
+            nav: FormNavigation::new(FormField::client_fields()),
+            editing: None,
+            name: TextInput::default(),
+            address: TextInput::default(),
+        }
+    }
+
+    pub fn new_edit(client: &ClientDto) -> Self {
+        Self {
+            editing: Some(client.id),
+            name: TextInput::new(client.name.clone().unwrap_or_default()),
+            address: TextInput::new(client.address.clone().unwrap_or_default()),
+            ..Self::new_create()
+        }
+    }
+
+    /// Build CreateClientDto from form state
+    pub fn build_create(&self) -> CreateClientDto {
+        CreateClientDto {
+            name: normalized(self.name.value()),
+            address: trimmed(self.address.value()),
+            projects_total: 0,
+            projects_completed: 0,
+        }
+    }
+
+    /// Build UpdateClientDto from form state, keeping the edited client's
+    /// project counters as the server last sent them
+    pub fn build_update(&self, client: &ClientDto) -> UpdateClientDto {
+        UpdateClientDto {
+            name: normalized(self.name.value()),
+            address: trimmed(self.address.value()),
+            ..UpdateClientDto::from_client(client)
+        }
+    }
+}
+
+impl FormInput for ClientForm {
+    fn nav(&self) -> &FormNavigation {
+        &self.nav
+    }
+
+    fn nav_mut(&mut self) -> &mut FormNavigation {
+        &mut self.nav
+    }
+
+    fn text_mut(&mut self, field: FormField) -> Option<&mut TextInput> {
+        match field {
+            FormField::ClientName => Some(&mut self.name),
+            FormField::ClientAddress => Some(&mut self.address),
+            _ => None,
+        }
+    }
+}
+
+/// Project create/edit form
+#[derive(Debug, Clone)]
+pub struct ProjectForm {
+    pub nav: FormNavigation,
+    /// The project being edited; None for a new one
+    pub editing: Option<Uuid>,
+    pub name: TextInput,
+    pub client_idx: usize,
+    pub manager_idx: usize,
+    pub start_date: String,
+    pub end_date: String,
+    /// Format the date fields are written and read in
+    pub date_format: DateFormat,
+    /// Days a week step moves the date fields by: 7, or 5 working days
+    pub day_count: DayCount,
+    /// Soft warnings already pointed out by a Save; the next Save with the
+    /// same ones goes ahead
+    pub acknowledged: Vec<Warning>,
+}
+
+impl ProjectForm {
+    /// A new project from today, ending `duration_days` counted days later
+    pub fn new_create(date_format: DateFormat, day_count: DayCount, duration_days: i64) -> Self {
+        let today = chrono::Local::now().date_naive();
+        let end_date = day_count.add(today, duration_days);
+        Self {
+            nav: FormNavigation::new(FormField::project_fields()),
+            editing: None,
+            name: TextInput::default(),
+            client_idx: 0,
+            manager_idx: 0,
+            start_date: format_date(&today, date_format),
+            end_date: format_date(&end_date, date_format),
+            date_format,
+            day_count,
+            acknowledged: Vec::new(),
+        }
+    }
+
+    pub fn new_edit(
+        project: &ProjectDto,
+        clients: &[ClientDto],
+        users: &[UserDto],
+        date_format: DateFormat,
+        day_count: DayCount,
+    ) -> Self {
+        let client_idx = clients
+            .iter()
+            .position(|c| c.id == project.client_id)
+            .unwrap_or(0);
+        let manager_idx = users
+            .iter()
+            .position(|u| u.id == project.manager_id)
+            .unwrap_or(0);
+        // Placeholder dates can't be stepped through day by day; start the pickers
+        // from today instead and tell the user why
+        let today = chrono::Local::now().date_naive();
+        let start = Some(project.start_date).filter(|d| is_valid_date(*d)).unwrap_or(today);
+        let end = Some(project.planned_end_date)
+            .filter(|d| is_valid_date(*d))
+            .unwrap_or(start + chrono::Duration::days(30));
+        let mut nav = FormNavigation::new(FormField::project_fields());
+        nav.error = (!project.has_valid_dates())
+            .then(|| "This project has no valid schedule; check the dates before saving".to_string());
+        Self {
+            nav,
+            editing: Some(project.id),
+            name: TextInput::new(project.name.clone().unwrap_or_default()),
+            client_idx,
+            manager_idx,
+            start_date: format_date(&start, date_format),
+            end_date: format_date(&end, date_format),
+            date_format,
+            day_count,
+            acknowledged: Vec::new(),
+        }
+    }
+
+    /// Move the focused date field by `days` calendar days
+    pub fn step_date(&mut self, days: i64) {
+        self.step_counted(days, DayCount::Calendar);
+    }
+
+    /// Move the focused date field by `weeks` weeks; a week of working days
+    /// with `day_count` set to them
+    pub fn step_week(&mut self, weeks: i64) {
+        self.step_counted(weeks * self.day_count.week(), self.day_count);
+    }
+
+    fn step_counted(&mut self, days: i64, count: DayCount) {
+        let format = self.date_format;
+        let date = match self.current_field() {
+            FormField::ProjectStartDate => &mut self.start_date,
+            FormField::ProjectEndDate => &mut self.end_date,
+            _ => return,
+        };
+        *date = add_days_to_date_string(date, days, count, format);
+    }
+
+    /// Increment the current date field by one day
+    pub fn increment_date(&mut self) {
+        self.step_date(1);
+    }
+
+    /// Decrement the current date field by one day
+    pub fn decrement_date(&mut self) {
+        self.step_date(-1);
+    }
+
+    /// Build CreateProjectDto from form state
+    pub fn build_create(&self, clients: &[ClientDto], users: &[UserDto]) -> CreateProjectDto {
+        let client_id = clients
+            .get(self.client_idx)
+            .map(|c| c.id)
+            .unwrap_or(Uuid::nil());
+        let manager_id = users
+            .get(self.manager_idx)
+            .map(|u| u.id)
+            .unwrap_or(Uuid::nil());
+        let start_date = parse_date(&self.start_date, self.date_format)
+            .unwrap_or_else(|| chrono::Local::now().date_naive());
+        let end_date = parse_date(&self.end_date, self.date_format)
+            .unwrap_or_else(|| start_date + chrono::Duration::days(30));
+
+        CreateProjectDto {
+            client_id,
+            name: normalized(self.name.value()),
+            start_date,
+            planned_end_date: end_date,
+            actual_end_date: None,
+            manager_id,
+        }
+    }
+
+    /// Build UpdateProjectDto from form state
+    pub fn build_update(&self, clients: &[ClientDto], users: &[UserDto]) -> UpdateProjectDto {
+        let dto = self.build_create(clients, users);
+        UpdateProjectDto {
+            client_id: dto.client_id,
+            name: dto.name,
+            start_date: dto.start_date,
+            planned_end_date: dto.planned_end_date,
+            actual_end_date: None,
+            manager_id: dto.manager_id,
+        }
+    }
+
+    /// Soft warnings for the dates as typed; an edit only warns once a date
+    /// differs from the project's
+    pub fn warnings(&self, clients: &[ClientDto], users: &[UserDto], projects: &[ProjectDto]) -> Vec<Warning> {
+        let dto = self.build_create(clients, users);
+        let unchanged = self
+            .editing
+            .and_then(|id| projects.iter().find(|p| p.id == id))
+            .is_some_and(|p| (p.start_date, p.planned_end_date) == (dto.start_date, dto.planned_end_date));
+        if unchanged {
+            Vec::new()
+        } else {
+            dto.validate_soft()
+        }
+    }
+}
+
+impl FormInput for ProjectForm {
+    fn nav(&self) -> &FormNavigation {
+        &self.nav
+    }
+
+    fn nav_mut(&mut self) -> &mut FormNavigation {
+        &mut self.nav
+    }
+
+    fn text_mut(&mut self, field: FormField) -> Option<&mut TextInput> {
+        match field {
+            FormField::ProjectName => Some(&mut self.name),
+            // Date picker fields - use arrow keys instead of text input
+            _ => None,
+        }
+    }
+}
+
+/// User create/edit form
+#[derive(Debug, Clone)]
+pub struct UserForm {
+    pub nav: FormNavigation,
+    /// The user being edited; None for a new one
+    pub editing: Option<Uuid>,
+    pub name: TextInput,
+    pub login: TextInput,
+    pub password: TextInput,
+    pub role: Role,
+}
+
+impl UserForm {
+    pub fn new_create() -> Self {
+        Self {
+            nav: FormNavigation::new(FormField::user_fields()),
+            editing: None,
+            name: TextInput::default(),
+            login: TextInput::default(),
+            password: TextInput::default().masked(),
+            role: Role::Manager,
+        }
+    }
+
+    pub fn new_edit(user: &UserDto) -> Self {
+        Self {
+            editing: Some(user.id),
+            name: TextInput::new(user.name.clone().unwrap_or_default()),
+            login: TextInput::new(user.login.clone().unwrap_or_default()),
+            role: user.role,
+            ..Self::new_create()
+        }
+    }
+
+    /// Build CreateUserDto from form state; `lowercase_login` follows the
+    /// `lowercase_logins` config switch
+    pub fn build_create(&self, lowercase_login: bool) -> CreateUserDto {
+        CreateUserDto {
+            name: normalized(self.name.value()),
+            login: normalized_login(self.login.value(), lowercase_login),
+            password: Some(self.password.value().to_string()),
+            role: self.role,
+        }
+    }
+
+    /// Build UpdateUserDto from form state
+    pub fn build_update(&self, lowercase_login: bool) -> UpdateUserDto {
+        UpdateUserDto {
+            name: normalized(self.name.value()),
+            login: normalized_login(self.login.value(), lowercase_login),
+            password: if self.password.is_empty() {
+                None
+            } else {
+                Some(self.password.value().to_string())
+            },
+            role: self.role,
+        }
+    }
+}
+
+impl FormInput for UserForm {
+    fn nav(&self) -> &FormNavigation {
+        &self.nav
+    }
+
+    fn nav_mut(&mut self) -> &mut FormNavigation {
+        &mut self.nav
+    }
+
+    fn text_mut(&mut self, field: FormField) -> Option<&mut TextInput> {
+        match field {
+            FormField::UserName => Some(&mut self.name),
+            FormField::UserLogin => Some(&mut self.login),
+            FormField::UserPassword => Some(&mut self.password),
+            _ => None,
+        }
+    }
+}
+
+/// State for the form modal
+#[derive(Debug, Clone)]
+pub enum FormState {
+    Client(ClientForm),
+    Project(ProjectForm),
+    User(UserForm),
+}
+
+impl FormState {
+    /// Create a new client creation form
+    pub fn new_create_client() -> Self {
+        Self::Client(ClientForm::new_create())
+    }
+
+    /// Create an edit client form
+    pub fn new_edit_client(client: &ClientDto) -> Self {
+        Self::Client(ClientForm::new_edit(client))
+    }
+
+    /// Create a new project creation form
+    pub fn new_create_project(date_format: DateFormat, day_count: DayCount, duration_days: i64) -> Self {
+        Self::Project(ProjectForm::new_create(date_format, day_count, duration_days))
+    }
+
+    /// Create an edit project form
+    pub fn new_edit_project(
+        project: &ProjectDto,
+        clients: &[ClientDto],
+        users: &[UserDto],
+        date_format: DateFormat,
+        day_count: DayCount,
+    ) -> Self {
+        Self::Project(ProjectForm::new_edit(project, clients, users, date_format, day_count))
+    }
+
+    /// Create a new user creation form
+    pub fn new_create_user() -> Self {
+        Self::User(UserForm::new_create())
+    }
+
+    /// Create an edit user form
+    pub fn new_edit_user(user: &UserDto) -> Self {
+        Self::User(UserForm::new_edit(user))
+    }
+
+    /// Which form this is, and what it edits
+    pub fn form_type(&self) -> FormType {
+        match self {
+            Self::Client(form) => form.editing.map_or(FormType::CreateClient, FormType::EditClient),
+            Self::Project(form) => form.editing.map_or(FormType::CreateProject, FormType::EditProject),
+            Self::User(form) => form.editing.map_or(FormType::CreateUser, FormType::EditUser),
+        }
+    }
+
+    /// Soft warnings for a project form's dates; other forms have none
+    pub fn project_warnings(&self, clients: &[ClientDto], users: &[UserDto], projects: &[ProjectDto]) -> Vec<Warning> {
+        match self {
+            Self::Project(form) => form.warnings(clients, users, projects),
+            _ => Vec::new(),
+        }
+    }
+}
+
+impl FormInput for FormState {
+    fn nav(&self) -> &FormNavigation {
+        match self {
+            Self::Client(form) => form.nav(),
+            Self::Project(form) => form.nav(),
+            Self::User(form) => form.nav(),
+        }
+    }
+
+    fn nav_mut(&mut self) -> &mut FormNavigation {
+        match self {
+            Self::Client(form) => form.nav_mut(),
+            Self::Project(form) => form.nav_mut(),
+            Self::User(form) => form.nav_mut(),
+        }
+    }
+
+    fn text_mut(&mut self, field: FormField) -> Option<&mut TextInput> {
+        match self {
+            Self::Client(form) => form.text_mut(field),
+            Self::Project(form) => form.text_mut(field),
+            Self::User(form) => form.text_mut(field),
+        }
+    }
+}
+
+/// `date` in `format` moved by `days` counted days; today when it doesn't parse
+fn add_days_to_date_string(date: &str, days: i64, count: DayCount, format: DateFormat) -> String {
+    let date = parse_date(date, format)
+        .map(|d| count.add(d, days))
+        .unwrap_or_else(|| chrono::Local::now().date_naive());
+    format_date(&date, format)
+}
+
+/// Trimmed text, or None when nothing but whitespace was typed
+fn trimmed(text: &str) -> Option<String> {
+    let text = text.trim();
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+/// A name or login with its whitespace trimmed and inner runs collapsed to
+/// one space; None when it was all whitespace
+fn normalized(text: &str) -> Option<String> {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    (!words.is_empty()).then(|| words.join(" "))
+}
+
+/// A normalized login, lowercased when the backend expects it
+fn normalized_login(login: &str, lowercase: bool) -> Option<String> {
+    normalized(login).map(|login| if lowercase { login.to_lowercase() } else { login })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{client, project, user, ClientFixture, ProjectFixture, UserFixture};
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    /// Focus `field`, which must be on the form
+    fn focus(form: &mut impl FormInput, field: FormField) {
+        let nav = form.nav_mut();
+        nav.focused_field = nav.fields.iter().position(|f| *f == field).unwrap();
+    }
+
+    #[test]
+    fn test_each_form_has_its_own_field_order() {
+        let order = |form: &mut FormState| {
+            (0..form.nav().fields.len())
+                .map(|_| {
+                    let field = form.current_field();
+                    form.next_field();
+                    field.label()
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(order(&mut FormState::new_create_client()), ["Name", "Address", "Save", "Cancel"]);
+        assert_eq!(
+            order(&mut FormState::new_create_project(DateFormat::Iso, DayCount::Calendar, 30)),
+            ["Name", "Client", "Manager", "Start Date", "End Date", "Save", "Cancel"]
+        );
+        assert_eq!(
+            order(&mut FormState::new_edit_user(&user(3).role(Role::Admin))),
+            ["Name", "Login", "Password", "Role", "Save", "Cancel"]
+        );
+        assert_eq!(FormState::new_edit_client(&client(7)).form_type(), FormType::EditClient(Uuid::from_u128(7)));
+        assert_eq!(FormState::new_create_user().form_type(), FormType::CreateUser);
+    }
+
+    #[test]
+    fn test_tab_wraps_around_both_ways() {
+        let mut form = FormState::new_create_client();
+        form.nav_mut().dropdown_open = true;
+        form.prev_field();
+        assert_eq!(form.current_field(), FormField::CancelButton);
+        assert!(!form.nav().dropdown_open);
+        form.next_field();
+        assert_eq!(form.current_field(), FormField::ClientName);
+
+        let mut form = UserForm::new_create();
+        for _ in 0..FormField::user_fields().len() {
+            form.next_field();
+        }
+        assert_eq!(form.current_field(), FormField::UserName);
+    }
+
+    #[test]
+    fn test_typing_only_reaches_text_fields() {
+        let mut form = FormState::new_create_project(DateFormat::Iso, DayCount::Calendar, 30);
+        let FormState::Project(before) = form.clone() else { unreachable!() };
+        for field in FormField::project_fields() {
+            focus(&mut form, *field);
+            form.handle_char('x');
+        }
+        let FormState::Project(project) = &form else { unreachable!() };
+        assert_eq!(project.name.value(), "x");
+        assert_eq!((&project.start_date, &project.end_date), (&before.start_date, &before.end_date));
+
+        let mut form = UserForm::new_create();
+        focus(&mut form, FormField::UserPassword);
+        form.handle_char('a');
+        form.handle_char('b');
+        form.handle_backspace();
+        // Editing keys work inside the focused field
+        form.handle_text_key(KeyEvent::new(KeyCode::Home, KeyModifiers::NONE));
+        form.handle_char('>');
+        focus(&mut form, FormField::UserRole);
+        assert!(!form.handle_text_key(KeyEvent::new(KeyCode::Left, KeyModifiers::NONE)));
+        form.handle_char('z');
+        form.handle_backspace();
+        assert_eq!((form.name.value(), form.login.value(), form.password.value()), ("", "", ">a"));
+    }
+
+    #[test]
+    fn test_date_stepping() {
+        let apollo = project(1).named("Apollo").ends("2099-01-01");
+        let mut form = ProjectForm::new_edit(&apollo, &[], &[], DateFormat::Iso, DayCount::Calendar);
+        // Only a focused date field moves
+        form.increment_date();
+        assert_eq!((form.start_date.as_str(), form.end_date.as_str()), ("2024-01-01", "2099-01-01"));
+
+        focus(&mut form, FormField::ProjectStartDate);
+        form.decrement_date();
+        assert_eq!(form.start_date, "2023-12-31");
+        form.step_date(7);
+        assert_eq!(form.start_date, "2024-01-07");
+        focus(&mut form, FormField::ProjectEndDate);
+        form.step_date(-7);
+        form.increment_date();
+        assert_eq!(form.end_date, "2098-12-26");
+
+        // Unreadable text starts over from today
+        form.end_date = "soon".to_string();
+        form.increment_date();
+        assert_eq!(form.end_date, chrono::Local::now().date_naive().to_string());
+    }
+
+    #[test]
+    fn test_working_day_schedules() {
+        let today = chrono::Local::now().date_naive();
+        let form = ProjectForm::new_create(DateFormat::Iso, DayCount::Calendar, 14);
+        assert_eq!(form.end_date, (today + chrono::Duration::days(14)).to_string());
+        let form = ProjectForm::new_create(DateFormat::Iso, DayCount::Working, 14);
+        assert_eq!(form.end_date, crate::dates::add_working_days(today, 14).to_string());
+
+        // A week is five working days; single steps stay calendar days
+        let apollo = project(1).named("Apollo").ends("2099-01-01");
+        let mut form = ProjectForm::new_edit(&apollo, &[], &[], DateFormat::Iso, DayCount::Working);
+        focus(&mut form, FormField::ProjectStartDate);
+        form.step_week(1);
+        assert_eq!(form.start_date, "2024-01-08");
+        for _ in 0..5 {
+            form.increment_date();
+        }
+        assert_eq!(form.start_date, "2024-01-13", "a Saturday");
+        form.step_week(-1);
+        assert_eq!(form.start_date, "2024-01-08");
+        form.day_count = DayCount::Calendar;
+        form.step_week(-1);
+        assert_eq!(form.start_date, "2024-01-01");
+    }
+
+    #[test]
+    fn test_project_form_uses_date_format() {
+        let apollo = project(1).named("Apollo").ends("2099-01-01");
+        let mut form = ProjectForm::new_edit(&apollo, &[], &[], DateFormat::Us, DayCount::Calendar);
+        assert_eq!(form.start_date, "01/01/2024");
+        assert_eq!(form.end_date, "01/01/2099");
+
+        focus(&mut form, FormField::ProjectStartDate);
+        form.decrement_date();
+        assert_eq!(form.start_date, "12/31/2023");
+        let dto = form.build_update(&[], &[]);
+        assert_eq!(dto.start_date, "2023-12-31".parse().unwrap());
+
+        form.date_format = DateFormat::Eu;
+        form.end_date = "15.03.2099".to_string();
+        assert_eq!(form.build_update(&[], &[]).planned_end_date, "2099-03-15".parse().unwrap());
+        assert!(form.nav.error.is_none());
+    }
+
+    #[test]
+    fn test_client_form_normalizes_text() {
+        let mut form = ClientForm::new_create();
+        form.name.set_value("  Acme \t  Corp\n");
+        form.address.set_value(" 12  Main St \n");
+        let dto = form.build_create();
+        assert_eq!(dto.name.as_deref(), Some("Acme Corp"));
+        assert_eq!(dto.address.as_deref(), Some("12  Main St"));
+
+        form.name.set_value(" \n ");
+        form.address.set_value("   ");
+        let dto = form.build_update(&client(7).named("Acme").address("1 Road"));
+        assert_eq!((dto.name, dto.address), (None, None));
+        assert!(form.build_create().validate().is_err());
+    }
+
+    #[test]
+    fn test_project_form_normalizes_name() {
+        let mut form = ProjectForm::new_create(DateFormat::Iso, DayCount::Calendar, 30);
+        form.name.set_value("\tMoon   Base ");
+        assert_eq!(form.build_create(&[], &[]).name.as_deref(), Some("Moon Base"));
+        assert_eq!(form.build_update(&[], &[]).name.as_deref(), Some("Moon Base"));
+
+        form.name.set_value("  ");
+        assert!(form.build_create(&[], &[]).name.is_none());
+        assert!(form.build_update(&[], &[]).validate().is_err());
+    }
+
+    #[test]
+    fn test_user_form_normalizes_name_and_login() {
+        let mut form = UserForm::new_create();
+        form.name.set_value(" Ada   Lovelace\n");
+        form.login.set_value("  ALove ");
+        form.password.set_value(" secret ");
+        let dto = form.build_create(true);
+        assert_eq!(dto.name.as_deref(), Some("Ada Lovelace"));
+        assert_eq!(dto.login.as_deref(), Some("alove"));
+        // Passwords are sent as typed
+        assert_eq!(dto.password.as_deref(), Some(" secret "));
+        assert_eq!(form.build_update(false).login.as_deref(), Some("ALove"));
+
+        form.login.set_value("\n");
+        assert!(form.build_update(true).login.is_none());
+        assert!(form.build_create(true).validate().is_err());
+    }
+
+    #[test]
+    fn test_edit_form_flags_placeholder_dates() {
+        let project = project(1).named("Apollo").starts("0001-01-01").ends("2099-01-01");
+        let form = ProjectForm::new_edit(&project, &[], &[], DateFormat::Iso, DayCount::Calendar);
+        assert!(form.nav.error.as_deref().unwrap().contains("no valid schedule"));
+
+        // The broken start is replaced, the real deadline kept
+        let today = chrono::Local::now().date_naive();
+        assert_eq!(form.start_date, today.to_string());
+        assert_eq!(form.end_date, "2099-01-01");
+    }
+}
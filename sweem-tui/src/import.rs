@@ -0,0 +1,439 @@
+//! Bulk import of clients, projects and users from CSV or JSON files.
+//!
+//! Files are planned before anything is sent: every row is parsed, checked
+//! with the DTO `validate()` methods and resolved against the loaded data
+//! (clients and managers may be given by name or UUID). Only once the dry
+//! run is clean are the create requests issued: projects through the batch
+//! endpoint, everything else one at a time.
+//!
+//! Schema (CSV header row, or keys of each object in a JSON array):
+//!
+//! - clients:  `name`, `address`
+//! - projects: `name`, `client`, `manager`, `start`, `end` (dates as YYYY-MM-DD)
+//! - users:    `name`, `login`, `password`, `role` (`manager` or `admin`)
+//!
+//! Rows are numbered by file line for CSV (the header is line 1) and by
+//! position for JSON.
+
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use chrono::NaiveDate;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::api::{ApiClient, ApiError, ChunkProgress, EntityType};
+use crate::models::{ClientDto, CreateClientDto, CreateProjectDto, CreateUserDto, Role, UserDto};
+
+/// An entity ready to be created
+#[derive(Debug, Clone, PartialEq)]
+pub enum NewEntity {
+    Client(CreateClientDto),
+    Project(CreateProjectDto),
+    User(CreateUserDto),
+}
+
+impl NewEntity {
+    pub fn entity_type(&self) -> EntityType {
+        match self {
+            NewEntity::Client(_) => EntityType::Client,
+            NewEntity::Project(_) => EntityType::Project,
+            NewEntity::User(_) => EntityType::User,
+        }
+    }
+}
+
+/// A valid row and where it came from
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportRow {
+    pub line: usize,
+    pub entity: NewEntity,
+}
+
+/// Result of the dry run
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImportPlan {
+    pub valid: Vec<ImportRow>,
+    /// Line number and reason for every rejected row
+    pub invalid: Vec<(usize, String)>,
+}
+
+impl ImportPlan {
+    /// One-line summary, e.g. "58 valid, 2 invalid (lines 4, 17)"
+    pub fn summary(&self) -> String {
+        if self.invalid.is_empty() {
+            return format!("{} valid, 0 invalid", self.valid.len());
+        }
+        let lines: Vec<String> = self.invalid.iter().map(|(line, _)| line.to_string()).collect();
+        format!(
+            "{} valid, {} invalid (lines {})",
+            self.valid.len(),
+            self.invalid.len(),
+            lines.join(", ")
+        )
+    }
+}
+
+/// One row as written in the file; which fields matter depends on the entity
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct Record {
+    name: Option<String>,
+    address: Option<String>,
+    client: Option<String>,
+    manager: Option<String>,
+    start: Option<String>,
+    end: Option<String>,
+    login: Option<String>,
+    password: Option<String>,
+    role: Option<String>,
+}
+
+/// Read a file (format chosen by extension) and plan the import
+pub fn plan_file(
+    path: &Path,
+    entity: EntityType,
+    clients: &[ClientDto],
+    users: &[UserDto],
+) -> Result<ImportPlan> {
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let is_json = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+    let records = if is_json { parse_json(&text)? } else { parse_csv(&text)? };
+    Ok(plan(records, entity, clients, users))
+}
+
+/// Parse CSV text into numbered records (unparseable rows become errors)
+fn parse_csv(text: &str) -> Result<Vec<(usize, Result<Record, String>)>> {
+    let mut reader = csv::ReaderBuilder::new().trim(csv::Trim::All).from_reader(text.as_bytes());
+    let headers = reader.headers().context("Failed to read CSV header")?.clone();
+
+    // A quoted field may span lines, so rows are numbered by where the
+    // reader found them rather than by counting
+    Ok(reader
+        .records()
+        .enumerate()
+        .map(|(i, row)| match row {
+            Ok(row) => {
+                let line = row.position().map_or(i + 2, |p| p.line() as usize);
+                (line, row.deserialize(Some(&headers)).map_err(|e| e.to_string()))
+            }
+            Err(e) => {
+                let line = e.position().map_or(i + 2, |p| p.line() as usize);
+                (line, Err(e.to_string()))
+            }
+        })
+        .collect())
+}
+
+/// Parse a JSON array of objects into numbered records
+fn parse_json(text: &str) -> Result<Vec<(usize, Result<Record, String>)>> {
+    let values: Vec<serde_json::Value> =
+        serde_json::from_str(text).context("Expected a JSON array of objects")?;
+    Ok(values
+        .into_iter()
+        .enumerate()
+        .map(|(i, value)| (i + 1, serde_json::from_value(value).map_err(|e| e.to_string())))
+        .collect())
+}
+
+/// Validate and resolve every record
+fn plan(
+    records: Vec<(usize, Result<Record, String>)>,
+    entity: EntityType,
+    clients: &[ClientDto],
+    users: &[UserDto],
+) -> ImportPlan {
+    let mut plan = ImportPlan::default();
+    for (line, record) in records {
+        match record.and_then(|r| build(r, entity, clients, users)) {
+            Ok(entity) => plan.valid.push(ImportRow { line, entity }),
+            Err(reason) => plan.invalid.push((line, reason)),
+        }
+    }
+    plan
+}
+
+/// Turn a record into a validated DTO
+fn build(
+    record: Record,
+    entity: EntityType,
+    clients: &[ClientDto],
+    users: &[UserDto],
+) -> Result<NewEntity, String> {
+    let entity = match entity {
+        EntityType::Client => {
+            let dto = CreateClientDto {
+                name: record.name,
+                address: record.address,
+                ..CreateClientDto::default()
+            };
+            dto.validate()?;
+            NewEntity::Client(dto)
+        }
+        EntityType::Project => {
+            let dto = CreateProjectDto {
+                name: record.name,
+                client_id: resolve(record.client.as_deref(), "client", clients, |c| (c.id, c.display_name()))?,
+                manager_id: resolve(record.manager.as_deref(), "manager", users, |u| (u.id, u.display_name()))?,
+                start_date: parse_date(record.start.as_deref(), "start")?,
+                planned_end_date: parse_date(record.end.as_deref(), "end")?,
+                actual_end_date: None,
+            };
+            dto.validate()?;
+            NewEntity::Project(dto)
+        }
+        EntityType::User => {
+            let role = match record.role.as_deref().map(str::to_lowercase).as_deref() {
+                None | Some("") | Some("manager") => Role::Manager,
+                Some("admin") => Role::Admin,
+                Some(other) => return Err(format!("Unknown role '{}'", other)),
+            };
+            let dto = CreateUserDto {
+                name: record.name,
+                login: record.login,
+                password: record.password,
+                role,
+            };
+            dto.validate()?;
+            NewEntity::User(dto)
+        }
+    };
+    Ok(entity)
+}
+
+/// Find a loaded entity by UUID or (case-insensitive, unambiguous) name
+fn resolve<T>(
+    value: Option<&str>,
+    field: &str,
+    items: &[T],
+    key: impl Fn(&T) -> (Uuid, &str),
+) -> Result<Uuid, String> {
+    let value = match value.map(str::trim) {
+        Some(v) if !v.is_empty() => v,
+        _ => return Err(format!("{} is required", capitalize(field))),
+    };
+
+    if let Ok(id) = value.parse::<Uuid>() {
+        return items
+            .iter()
+            .map(&key)
+            .find(|(item_id, _)| *item_id == id)
+            .map(|(id, _)| id)
+            .ok_or_else(|| format!("No {} with id {}", field, id));
+    }
+
+    let matches: Vec<Uuid> = items
+        .iter()
+        .map(&key)
+        .filter(|(_, name)| name.eq_ignore_ascii_case(value))
+        .map(|(id, _)| id)
+        .collect();
+    match matches.as_slice() {
+        [id] => Ok(*id),
+        [] => Err(format!("No {} named '{}'", field, value)),
+        _ => Err(format!("{} name '{}' is ambiguous - use the UUID", capitalize(field), value)),
+    }
+}
+
+fn parse_date(value: Option<&str>, field: &str) -> Result<NaiveDate, String> {
+    let value = value.map(str::trim).filter(|v| !v.is_empty());
+    let value = value.ok_or_else(|| format!("{} date is required", capitalize(field)))?;
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|_| format!("Invalid {} date '{}' (expected YYYY-MM-DD)", field, value))
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+/// Send one create request
+pub async fn create(client: &ApiClient, entity: &NewEntity) -> Result<Uuid, ApiError> {
+    match entity {
+        NewEntity::Client(dto) => client.create_client(dto).await,
+        NewEntity::Project(dto) => client.create_project(dto).await,
+        NewEntity::User(dto) => client.create_user(dto).await,
+    }
+}
+
+/// Create every row in order, reporting each outcome to `progress` and each
+/// batch request to `on_chunk`.
+///
+/// Returns the rows that failed with the reason, so callers can say exactly
+/// which lines need attention.
+pub async fn execute(
+    client: &ApiClient,
+    rows: &[ImportRow],
+    mut progress: impl FnMut(usize, &ImportRow, &Result<Uuid, ApiError>),
+    mut on_chunk: impl FnMut(&ChunkProgress),
+) -> Vec<(usize, String)> {
+    let mut failures = Vec::new();
+    let mut record = |done: usize, row: &ImportRow, result: &Result<Uuid, ApiError>| {
+        progress(done, row, result);
+        if let Err(e) = result {
+            failures.push((row.line, e.to_string()));
+        }
+    };
+
+    match batch_projects(rows) {
+        Some(projects) => {
+            let mut done = 0;
+            client
+                .create_projects_chunked(&projects, |chunk, results| {
+                    on_chunk(chunk);
+                    for result in results {
+                        record(done + 1, &rows[done], result);
+                        done += 1;
+                    }
+                })
+                .await;
+        }
+        None => {
+            for (i, row) in rows.iter().enumerate() {
+                let result = create(client, &row.entity).await;
+                record(i + 1, row, &result);
+            }
+        }
+    }
+    failures
+}
+
+/// The rows' DTOs if they are all projects, which can be created in batches
+fn batch_projects(rows: &[ImportRow]) -> Option<Vec<CreateProjectDto>> {
+    rows.iter()
+        .map(|row| match &row.entity {
+            NewEntity::Project(dto) => Some(dto.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Refuse to start when the dry run found problems
+pub fn ensure_clean(plan: &ImportPlan) -> Result<()> {
+    if plan.valid.is_empty() && plan.invalid.is_empty() {
+        bail!("The file contains no rows");
+    }
+    if !plan.invalid.is_empty() {
+        bail!("Fix the invalid rows before importing ({})", plan.summary());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{client, user, ClientFixture, UserFixture};
+
+    #[test]
+    fn test_plan_projects_from_csv() {
+        let clients = vec![client(1).named("Acme"), client(2).named("Twin"), client(3).named("twin")];
+        let users = vec![user(4).named("Kim").login("kim")];
+        let csv = format!(
+            "name,client,manager,start,end\n\
+             Apollo,acme,Kim,2024-05-01,2024-06-01\n\
+             Gemini,{},{},2024-05-01,2024-06-01\n\
+             Mercury,Nobody,Kim,2024-05-01,2024-06-01\n\
+             Skylab,Acme,Kim,2024-06-01,2024-05-01\n\
+             Vostok,Twin,Kim,2024-05-01,2024-06-01\n\
+             Soyuz,Acme,Kim,tomorrow,2024-06-01\n",
+            clients[0].id, users[0].id
+        );
+
+        let plan = plan(parse_csv(&csv).unwrap(), EntityType::Project, &clients, &users);
+        assert_eq!(plan.valid.len(), 2);
+        assert_eq!(plan.valid[0].line, 2);
+        match &plan.valid[1].entity {
+            NewEntity::Project(dto) => {
+                assert_eq!(dto.client_id, clients[0].id);
+                assert_eq!(dto.manager_id, users[0].id);
+            }
+            other => panic!("unexpected entity: {:?}", other),
+        }
+
+        let lines: Vec<usize> = plan.invalid.iter().map(|(line, _)| *line).collect();
+        assert_eq!(lines, vec![4, 5, 6, 7]);
+        assert_eq!(plan.invalid[0].1, "No client named 'Nobody'");
+        assert_eq!(plan.invalid[1].1, "End date must be after start date");
+        assert!(plan.invalid[2].1.contains("ambiguous"));
+        assert!(plan.invalid[3].1.contains("Invalid start date"));
+        assert_eq!(plan.summary(), "2 valid, 4 invalid (lines 4, 5, 6, 7)");
+    }
+
+    #[test]
+    fn test_csv_lines_follow_multi_line_fields() {
+        let csv = "name,address\n\
+                   Acme,\"1 Main St\n\
+                   Suite 4\n\
+                   Springfield\"\n\
+                   ,nowhere\n\
+                   Globex,2 Side St\n\
+                   Initech,\"3 Loop Rd\",extra\n";
+        let records = parse_csv(csv).unwrap();
+        let lines: Vec<usize> = records.iter().map(|(line, _)| *line).collect();
+        assert_eq!(lines, [2, 5, 6, 7]);
+
+        let plan = plan(records, EntityType::Client, &[], &[]);
+        assert_eq!(plan.valid.iter().map(|row| row.line).collect::<Vec<_>>(), [2, 6]);
+        assert_eq!(plan.summary(), "2 valid, 2 invalid (lines 5, 7)");
+    }
+
+    #[test]
+    fn test_plan_users_and_clients_from_json() {
+        let json = r#"[
+            {"name": "Kim", "login": "kim", "password": "secret", "role": "Admin"},
+            {"name": "Lee", "login": "lee", "password": "x"},
+            {"name": "Max", "login": "max", "password": "secret", "colour": "red"}
+        ]"#;
+        let plan = plan(parse_json(json).unwrap(), EntityType::User, &[], &[]);
+        assert_eq!(plan.valid.len(), 1);
+        assert!(matches!(&plan.valid[0].entity, NewEntity::User(dto) if dto.role == Role::Admin));
+        assert_eq!(plan.invalid[0], (2, "Password must be at least 4 characters".to_string()));
+        assert_eq!(plan.invalid[1].0, 3);
+        assert!(plan.invalid[1].1.contains("colour"));
+
+        let plan = plan_from_str("name,address\nAcme,1 Main St\n,nowhere\n", EntityType::Client);
+        assert_eq!(plan.summary(), "1 valid, 1 invalid (lines 3)");
+        assert!(ensure_clean(&plan).is_err());
+        assert!(parse_json("{}").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_execute_sends_projects_in_batches() {
+        let mock = crate::mock_api::MockApi::start(|req| match req.path.as_str() {
+            "/projects/batch" => {
+                let sent: Vec<serde_json::Value> = serde_json::from_str(&req.body).unwrap();
+                match sent.len() {
+                    100 => (201, serde_json::to_string(&vec![Uuid::new_v4(); 100]).unwrap()),
+                    _ => (409, r#"{"title":"Conflict"}"#.to_string()),
+                }
+            }
+            _ => (201, format!("\"{}\"", Uuid::new_v4())),
+        })
+        .await;
+        let client = ApiClient::new(&mock.url).unwrap();
+
+        let project = |line| ImportRow { line, entity: NewEntity::Project(CreateProjectDto::default()) };
+        let rows: Vec<ImportRow> = (2..105).map(project).collect();
+        let mut chunks = Vec::new();
+        let failures = execute(&client, &rows, |_, _, _| {}, |chunk| chunks.push(*chunk)).await;
+        assert_eq!(failures.iter().map(|(line, _)| *line).collect::<Vec<_>>(), vec![102, 103, 104]);
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(mock.requests().len(), 2);
+
+        // Other entities still go one request per row
+        let client_row = ImportRow { line: 2, entity: NewEntity::Client(CreateClientDto::default()) };
+        let failures = execute(&client, &[client_row], |_, _, _| {}, |_| panic!("no batches")).await;
+        assert!(failures.is_empty());
+        assert_eq!(mock.requests().last().unwrap().path, "/clients");
+    }
+
+    fn plan_from_str(csv: &str, entity: EntityType) -> ImportPlan {
+        plan(parse_csv(csv).unwrap(), entity, &[], &[])
+    }
+}
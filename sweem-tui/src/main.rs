@@ -3,31 +3,161 @@
 //! A modern TUI frontend with Kanagawa Dragon theme aesthetic,
 //! featuring floating ash particles and full CRUD operations.
 
+mod agenda;
 mod api;
 mod app;
-mod models;
+mod calendar;
+mod cli;
+mod config;
+mod dates;
+mod diff;
+mod form;
+mod import;
 mod particles;
-mod theme;
+mod passwords;
+mod presets;
 mod radar;
+mod recording;
+mod report;
+mod sanitize;
+mod screen;
+mod search;
+mod snapshot;
+mod state;
+mod suggest;
+#[cfg(test)]
+mod test_support;
+mod theme;
 mod ui;
+mod upcoming;
+mod user_groups;
+mod widgets;
+mod workload;
+
+// Shared with other tools; re-exported so `crate::models` and friends stay put
+use sweem_core::{filter, models};
+#[cfg(test)]
+use sweem_core::mock_api;
 
-use std::io::{self, stdout};
-use std::time::Duration;
+use std::future::Future;
+use std::io;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
 
 use anyhow::{Context, Result};
-use crossterm::{
-    event::{self, Event, KeyEventKind},
-    execute,
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
-};
+use crossterm::event::{self, Event, KeyEventKind};
 use ratatui::prelude::*;
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, watch};
+use uuid::Uuid;
+
+use api::{
+    ApiClient, ApiCommand, ApiError, ApiMessage, ApiOperation, EntityType, FetchedAll, FetchedEntity, Request, RequestId,
+    Response, TlsConfig,
+};
+use app::{format_count, App, LogEntry};
+use config::Config;
+use particles::{ParticleMode, ParticleSystem, DEFAULT_MAX_PARTICLES};
+use presets::Presets;
+use screen::{Screen, ScreenOptions};
+use state::UiState;
+
+/// Frame interval when nothing on screen is animating
+const IDLE_FRAME_DURATION: Duration = Duration::from_millis(250);
+
+/// How long to wait for in-flight requests when quitting
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Command line options
+#[derive(Debug, Default, PartialEq)]
+struct CliArgs {
+    /// API base URL (first positional argument)
+    api_url: Option<String>,
+    /// Fixed particle seed, so visual bugs can be reproduced exactly
+    seed: Option<u64>,
+    /// Named server profile from the config file
+    profile: Option<String>,
+    /// Disable every mutating action
+    read_only: bool,
+    /// Log every API call
+    trace_api: bool,
+    /// Extra CA bundle trusted for HTTPS
+    ca_cert: Option<PathBuf>,
+    /// Skip HTTPS certificate checks
+    insecure: bool,
+    /// Animation frame rate, overriding the config
+    fps: Option<u32>,
+    /// Draw inline on the normal screen, for terminals without an alternate screen
+    no_altscreen: bool,
+    /// Append every handled key to this JSONL file
+    record: Option<PathBuf>,
+    /// Feed the keys of this recording back in
+    replay: Option<PathBuf>,
+    /// Headless subcommand; the TUI doesn't start when set
+    command: Option<cli::Command>,
+}
+
+impl CliArgs {
+    /// Parse arguments (without the program name)
+    fn parse(args: impl IntoIterator<Item = String>) -> Result<Self> {
+        let mut parsed = Self::default();
+        let mut args = args.into_iter();
+        let mut include_archived = false;
 
-use api::{ApiClient, ApiCommand, ApiMessage, EntityType};
-use app::App;
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--seed" => {
+                    let value = args.next().context("--seed requires a value")?;
+                    let seed = value
+                        .parse()
+                        .with_context(|| format!("Invalid seed: {}", value))?;
+                    parsed.seed = Some(seed);
+                }
+                "--fps" => {
+                    let value = args.next().context("--fps requires a value")?;
+                    let fps = value
+                        .parse()
+                        .with_context(|| format!("Invalid frame rate: {}", value))?;
+                    parsed.fps = Some(fps);
+                }
+                "--read-only" => parsed.read_only = true,
+                "--trace-api" => parsed.trace_api = true,
+                "--ca-cert" => {
+                    parsed.ca_cert = Some(args.next().context("--ca-cert requires a path")?.into());
+                }
+                "--insecure" => parsed.insecure = true,
+                "--no-altscreen" => parsed.no_altscreen = true,
+                "--record" => parsed.record = Some(args.next().context("--record requires a path")?.into()),
+                "--replay" => parsed.replay = Some(args.next().context("--replay requires a path")?.into()),
+                "--report" => {
+                    let path = args.next().context("--report requires a path")?;
+                    parsed.command = Some(cli::Command::Report { path: path.into(), include_archived: false });
+                }
+                "--include-archived" => include_archived = true,
+                "--profile" => {
+                    parsed.profile = Some(args.next().context("--profile requires a name")?);
+                }
+                flag if flag.starts_with("--") => anyhow::bail!("Unknown option: {}", flag),
+                // The subcommand takes every remaining argument
+                word if cli::Command::is_subcommand(word) => {
+                    parsed.command = Some(cli::Command::parse(word, args.by_ref())?);
+                }
+                _ if parsed.api_url.is_none() => parsed.api_url = Some(arg),
+                _ => anyhow::bail!("Unexpected argument: {}", arg),
+            }
+        }
+
+        match &mut parsed.command {
+            Some(cli::Command::Report { include_archived: include, .. }) => *include = include_archived,
+            _ if include_archived => anyhow::bail!("--include-archived only applies to --report"),
+            _ => {}
+        }
+        if parsed.record.is_some() && parsed.replay.is_some() {
+            anyhow::bail!("--record and --replay can't be combined");
+        }
 
-/// Frame rate for animations (approximately 30 FPS)
-const FRAME_DURATION: Duration = Duration::from_millis(33);
+        Ok(parsed)
+    }
+}
 
 /// Main entry point
 #[tokio::main]
@@ -35,216 +165,702 @@ async fn main() -> Result<()> {
     // Initialize error handling
     color_eyre::install().ok();
 
-    // Parse command line arguments for API URL
-    let args: Vec<String> = std::env::args().collect();
-    let api_url = args
-        .get(1)
-        .map(|s| s.as_str())
-        .unwrap_or(api::DEFAULT_BASE_URL);
+    // Parse command line arguments and the config file
+    let args = match CliArgs::parse(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("error: {:#}", e);
+            std::process::exit(2);
+        }
+    };
+    let config = Config::load()?;
+
+    // Headless subcommands never touch the terminal
+    if let Some(command) = &args.command {
+        if let Err(e) = run_headless(&args, &config, command).await {
+            eprintln!("error: {:#}", e);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
 
     // Run the TUI
-    run_tui(api_url).await
+    run_tui(&args, &config).await
+}
+
+/// API server chosen from the command line, config file and profiles
+struct Server {
+    url: String,
+    token: Option<String>,
+    profile: Option<String>,
+    read_only: bool,
+    tls: TlsConfig,
+}
+
+impl Server {
+    /// Command line beats profile beats config file beats the built-in default
+    fn resolve(args: &CliArgs, config: &Config) -> Result<Self> {
+        let profile = match &args.profile {
+            Some(name) => Some((name, config.profile(name)?)),
+            None => None,
+        };
+
+        let url = args
+            .api_url
+            .as_deref()
+            .or(profile.map(|(_, p)| p.url.as_str()))
+            .or(config.api_url.as_deref())
+            .unwrap_or(api::DEFAULT_BASE_URL);
+
+        let ca_cert = args.ca_cert.as_deref().or(config.ca_cert.as_deref());
+        let tls = TlsConfig::load(ca_cert, args.insecure || config.insecure)?;
+
+        Ok(Self {
+            url: url.to_string(),
+            token: profile.and_then(|(_, p)| p.token.clone()),
+            profile: profile.map(|(name, _)| name.clone()),
+            read_only: args.read_only || config.read_only,
+            tls,
+        })
+    }
+}
+
+/// Run a headless subcommand against the API
+async fn run_headless(args: &CliArgs, config: &Config, command: &cli::Command) -> Result<()> {
+    let server = Server::resolve(args, config)?;
+    if server.read_only && command.is_mutating() {
+        anyhow::bail!("Read-only mode: refusing to modify data");
+    }
+
+    if server.tls.insecure {
+        eprintln!("warning: HTTPS certificates are not verified (--insecure)");
+    }
+    let mut client =
+        ApiClient::with_token(&server.url, server.token.as_deref(), config.request_timeout(), server.tls.clone())?;
+    if args.trace_api || config.trace_api {
+        // stdout carries the command's output, so traces go to stderr
+        client = client.with_trace(|trace| eprintln!("{}", trace.detailed()));
+    }
+    cli::run(&client, command, config.date_format, config.archive_after_days, &mut io::stdout().lock()).await
 }
 
 /// Run the TUI application
-async fn run_tui(api_url: &str) -> Result<()> {
-    // Setup terminal
-    enable_raw_mode().context("Failed to enable raw mode")?;
-    let mut stdout = stdout();
-    execute!(stdout, EnterAlternateScreen).context("Failed to enter alternate screen")?;
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend).context("Failed to create terminal")?;
+async fn run_tui(args: &CliArgs, config: &Config) -> Result<()> {
+    // Bad profiles, CA files or recordings are reported on the normal screen
+    let server = Server::resolve(args, config)?;
+    let mut keys = recording::Session {
+        recorder: args.record.as_deref().map(recording::Recorder::create).transpose()?,
+        replay: args.replay.as_deref().map(recording::load).transpose()?.map(recording::Replay::new),
+    };
+
+    // Setup terminal; a failed step leaves the terminal as it was
+    let options = ScreenOptions { inline: args.no_altscreen, mouse: config.mouse, title: config.terminal_title };
+    let (screen, mut terminal) = Screen::setup(options)?;
+    // Something on screen before the rest of startup and the first request
+    let splash = ui::Splash::new(&server.url);
+    terminal.draw(|frame| ui::render_splash(frame, &splash))?;
 
     // Create communication channels
-    let (api_tx, mut api_rx) = mpsc::channel::<ApiMessage>(32);
-    let (cmd_tx, mut cmd_rx) = mpsc::channel::<ApiCommand>(32);
+    let (api_tx, mut api_rx) = mpsc::channel::<Response>(32);
+    let (cmd_tx, mut cmd_rx) = mpsc::channel::<Request>(32);
 
     // Create API client and spawn worker task
-    let api_client = ApiClient::new(api_url)?;
+    let mut api_client =
+        ApiClient::with_token(&server.url, server.token.as_deref(), config.request_timeout(), server.tls.clone())?
+            .with_health_path(&config.health_path);
+    if args.trace_api || config.trace_api {
+        let trace_tx = api_tx.clone();
+        api_client = api_client.with_trace(move |trace| {
+            trace_tx.try_send(Response { request: None, message: ApiMessage::Trace(trace.compact()) }).ok();
+        });
+    }
     let api_client_clone = api_client.clone();
+    let options = WorkerOptions {
+        read_only: server.read_only,
+        lazy_loading: config.lazy_loading,
+        page_size: config.page_size,
+        page_browsing: config.page_browsing,
+        browse_page: 1,
+        timeout: config.request_timeout(),
+    };
+    let (stop_tx, stop_rx) = watch::channel(false);
     let api_task = tokio::spawn(async move {
-        run_api_worker(api_client_clone, api_tx, &mut cmd_rx).await
+        run_api_worker(api_client_clone, api_tx, &mut cmd_rx, options, stop_rx).await
     });
 
-    // Send initial refresh command
-    cmd_tx.send(ApiCommand::RefreshAll).await.ok();
-
     // Create application state
     let mut app = App::new();
+    app.api_url = server.url;
+    app.api_token = server.token;
+    app.read_only = server.read_only;
+    app.insecure = server.tls.insecure;
+    app.lazy_loading = config.lazy_loading;
+    app.page_browsing = config.page_browsing;
+    app.page_size = config.page_size;
+    app.reduced_motion = config.reduced_motion;
+    app.lowercase_logins = config.lowercase_logins;
+    app.login = config.login.clone();
+    app.password_length = config.password_length;
+    app.date_format = config.date_format;
+    app.week_start = config.first_day_of_week;
+    app.day_count = config.day_count();
+    app.project_duration_days = config.default_project_duration_days.into();
+    app.project_sort = config.project_sort;
+    app.enter_action = config.timeline_enter_action;
+    app.follow_selection = config.timeline_follow_selection;
+    app.agenda_strip = config.agenda_strip;
+    app.terminal_title = config.terminal_title;
+    app.deadline_thresholds = config.deadline_thresholds();
+    app.slow_fetch = config.slow_fetch();
+    app.stale_after = config.stale_after();
+    app.max_logs = config.max_logs;
+    app.confirm_default = config.confirm_default;
+    app.confirm_edits = config.confirm_edits;
+    app.snapshot_width = config.snapshot_width;
+    app.snapshot_format = config.snapshot_format;
+    app.archive_after_days = config.archive_after_days;
+    app.default_filter = config.default_filter;
+    app.project_filter.archive_after_days = (config.archive_after_days > 0).then_some(config.archive_after_days);
+    app.active_profile = server.profile;
+    app.profiles = config
+        .profiles
+        .iter()
+        .map(|(name, p)| (name.clone(), p.clone()))
+        .collect();
+    if let Some(seed) = args.seed {
+        app.particle_system =
+            ParticleSystem::with_seed(ParticleMode::default(), DEFAULT_MAX_PARTICLES, seed);
+    }
+    app.set_animations(config.animations);
+    app.splash = Some(splash);
+
+    // Send initial refresh command
+    cmd_tx.send(app.stamp(ApiCommand::RefreshAll)).await.ok();
+    app.frame_stats.target_fps = args.fps.unwrap_or(config.fps).clamp(*config::FPS_RANGE.start(), *config::FPS_RANGE.end());
+    // A bad state file only costs the user their layout
+    match UiState::load() {
+        Ok(Some(state)) => app.restore_ui_state(state),
+        Ok(None) => {}
+        Err(e) => app.log(LogEntry::warning(format!("Ignoring saved UI state: {:#}", e))),
+    }
+    app.presets_path = Presets::path();
+    if let Some(path) = &app.presets_path {
+        match Presets::load_from(path) {
+            Ok(presets) => app.presets = presets,
+            Err(e) => app.log(LogEntry::warning(format!("Ignoring filter presets: {:#}", e))),
+        }
+    }
 
     // Main event loop
-    let result = run_event_loop(&mut terminal, &mut app, &mut api_rx, &cmd_tx).await;
+    let result = run_event_loop(&mut terminal, &mut app, &mut api_rx, &cmd_tx, &mut keys).await;
 
     // Cleanup
-    disable_raw_mode().context("Failed to disable raw mode")?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen)
-        .context("Failed to leave alternate screen")?;
-    terminal.show_cursor().context("Failed to show cursor")?;
+    screen.restore(&mut terminal)?;
 
-    // Wait for API task to finish
-    api_task.abort();
+    if let Err(e) = app.ui_state().save() {
+        eprintln!("warning: {:#}", e);
+    }
+
+    // Reads in flight are dropped at once; queued mutations still go out,
+    // but quitting doesn't wait on a hung server for longer than this
+    stop_tx.send(true).ok();
+    cmd_tx.send(app.stamp(ApiCommand::Shutdown)).await.ok();
+    if tokio::time::timeout(SHUTDOWN_TIMEOUT, api_task).await.is_err() {
+        eprintln!("warning: quit with changes still being sent to the API");
+    }
 
     result
 }
 
-/// Run the API worker task
+/// Worker settings fixed at startup
+#[derive(Debug, Clone, Copy)]
+struct WorkerOptions {
+    /// Refuse every mutating command
+    read_only: bool,
+    /// Fetch one page at a time instead of whole collections
+    lazy_loading: bool,
+    /// Page size for lazy loading
+    page_size: i32,
+    /// Keep only one page of projects; refreshes reload that page
+    page_browsing: bool,
+    /// Project page currently browsed
+    browse_page: i32,
+    /// Request timeout for clients created after a server switch
+    timeout: Duration,
+}
+
+/// The worker's end of the message channel while it handles one command:
+/// every message goes out stamped with that command's request id
+struct Replies {
+    tx: mpsc::Sender<Response>,
+    request: Option<RequestId>,
+}
+
+impl Replies {
+    /// Send a message; a closed channel means the UI is gone and nobody cares
+    async fn send(&self, message: ApiMessage) {
+        self.tx.send(Response { request: self.request, message }).await.ok();
+    }
+
+    /// Send without waiting, dropping the message when the channel is full
+    fn try_send(&self, message: ApiMessage) {
+        self.tx.try_send(Response { request: self.request, message }).ok();
+    }
+}
+
+/// Run an API call, waiting out one 429 and trying again.
+///
+/// A rate-limited request was never applied, so repeating it is safe even for
+/// mutations. A second 429 is returned to the caller.
+async fn retry_rate_limited<T, F, Fut>(tx: &Replies, request: F) -> Result<T, ApiError>
+where
+    F: Fn() -> Fut,
+    Fut: Future<Output = Result<T, ApiError>>,
+{
+    match request().await {
+        Err(ApiError::RateLimited { retry_after }) => {
+            tx.send(ApiMessage::RateLimited(retry_after)).await;
+            tokio::time::sleep(retry_after).await;
+            request().await
+        }
+        result => result,
+    }
+}
+
+/// Hold a refresh back until the server's rate limit has passed
+async fn wait_out_rate_limit(client: &ApiClient, tx: &Replies) {
+    if let Some(wait) = client.rate_limit_remaining() {
+        tx.send(ApiMessage::RateLimited(wait)).await;
+        tokio::time::sleep(wait).await;
+    }
+}
+
+/// Fetch one entity collection: everything, or just the first page when lazy
+async fn refresh_entity(
+    client: &ApiClient,
+    tx: &Replies,
+    entity: EntityType,
+    options: WorkerOptions,
+) {
+    if options.page_browsing && entity == EntityType::Project {
+        return load_page(client, tx, entity, options.browse_page, options.page_size).await;
+    }
+    if options.lazy_loading {
+        return load_page(client, tx, entity, 1, options.page_size).await;
+    }
+
+    // Progress is best effort; the final Loaded message carries the data
+    let progress = |loaded, total| {
+        tx.try_send(ApiMessage::LoadProgress(entity, loaded, total));
+    };
+    // A load cut short by a failed page still delivers what arrived, then says what's missing
+    let (loaded, duration) = timed(async {
+        match entity {
+            EntityType::Project => retry_rate_limited(tx, || client.fetch_all_projects_with_progress(progress))
+                .await
+                .map(|all| (FetchSummary::of(&all, entity), ApiMessage::ProjectsLoaded(all))),
+            EntityType::Client => retry_rate_limited(tx, || client.fetch_all_clients_with_progress(progress))
+                .await
+                .map(|all| (FetchSummary::of(&all, entity), ApiMessage::ClientsLoaded(all))),
+            EntityType::User => retry_rate_limited(tx, || client.fetch_all_users_with_progress(progress))
+                .await
+                .map(|all| (FetchSummary::of(&all, entity), ApiMessage::UsersLoaded(all))),
+        }
+    })
+    .await;
+    match loaded {
+        Ok((summary, message)) => {
+            tx.send(message).await;
+            if let Some(warning) = summary.shortfall {
+                tx.send(ApiMessage::Warning(warning)).await;
+            }
+            let (status, items) = (summary.status, summary.items);
+            tx.send(ApiMessage::FetchStats { entity, status, duration, items }).await;
+        }
+        Err(e) => {
+            tx.send(ApiMessage::FetchStats { entity, status: e.status(), duration, items: 0 }).await;
+            tx.send(ApiMessage::Failed(ApiOperation::Load(entity), e)).await;
+        }
+    }
+}
+
+/// Run `fetch`, measuring how long it took
+async fn timed<T>(fetch: impl Future<Output = T>) -> (T, Duration) {
+    let started = Instant::now();
+    let output = fetch.await;
+    (output, started.elapsed())
+}
+
+/// What the worker reports about a full load besides the data itself
+struct FetchSummary {
+    shortfall: Option<String>,
+    /// Status of the page that stopped the load, or 200
+    status: Option<u16>,
+    items: usize,
+}
+
+impl FetchSummary {
+    fn of<T>(all: &FetchedAll<T>, entity: EntityType) -> Self {
+        Self {
+            shortfall: shortfall(all, entity),
+            status: all.failed.as_ref().map_or(Some(200), |f| f.error.status()),
+            items: all.items.len(),
+        }
+    }
+}
+
+/// "loaded 600 of 1,150 projects — pages 7–12 failed: …" for a load that
+/// stopped at a failed page, or "… — stopped, the server's paging looks
+/// wrong: …" for one stopped by a contradictory page
+fn shortfall<T>(all: &FetchedAll<T>, entity: EntityType) -> Option<String> {
+    let noun = format!("{}s", entity).to_lowercase();
+    let loaded = match all.total_count {
+        Some(total) => format!("{} of {}", format_count(all.items.len()), format_count(total)),
+        None => format_count(all.items.len()),
+    };
+    if let Some(anomaly) = &all.anomaly {
+        return Some(format!("Loaded {} {} — stopped, the server's paging looks wrong: {}", loaded, noun, anomaly));
+    }
+    let failed = all.failed.as_ref()?;
+    let pages = if failed.last_page > failed.page {
+        format!("pages {}–{}", failed.page, failed.last_page)
+    } else {
+        format!("page {}", failed.page)
+    };
+    Some(format!("Loaded {} {} — {} failed: {}", loaded, noun, pages, failed.error))
+}
+
+/// Fetch a single page of an entity collection
+async fn load_page(
+    client: &ApiClient,
+    tx: &Replies,
+    entity: EntityType,
+    page: i32,
+    page_size: i32,
+) {
+    let (message, duration) = timed(async {
+        match entity {
+            EntityType::Project => retry_rate_limited(tx, || client.fetch_projects(page, page_size))
+                .await
+                .map(|p| (p.items().len(), ApiMessage::ProjectsPage(p))),
+            EntityType::Client => retry_rate_limited(tx, || client.fetch_clients(page, page_size))
+                .await
+                .map(|p| (p.items().len(), ApiMessage::ClientsPage(p))),
+            EntityType::User => retry_rate_limited(tx, || client.fetch_users(page, page_size))
+                .await
+                .map(|p| (p.items().len(), ApiMessage::UsersPage(p))),
+        }
+    })
+    .await;
+    let (status, items) = match &message {
+        Ok((items, _)) => (Some(200), *items),
+        Err(e) => (e.status(), 0),
+    };
+    let message = message.map_or_else(|e| ApiMessage::Failed(ApiOperation::Load(entity), e), |(_, m)| m);
+    tx.send(message).await;
+    tx.send(ApiMessage::FetchStats { entity, status, duration, items }).await;
+}
+
+/// Fetch one entity of any type by id
+async fn fetch_one(
+    client: &ApiClient,
+    tx: &Replies,
+    entity: EntityType,
+    id: Uuid,
+) -> Result<FetchedEntity, ApiError> {
+    match entity {
+        EntityType::Client => retry_rate_limited(tx, || client.fetch_client(id))
+            .await
+            .map(FetchedEntity::Client),
+        EntityType::Project => retry_rate_limited(tx, || client.fetch_project(id))
+            .await
+            .map(FetchedEntity::Project),
+        EntityType::User => retry_rate_limited(tx, || client.fetch_user(id))
+            .await
+            .map(FetchedEntity::User),
+    }
+}
+
+/// Check the connection and, if reachable, fetch all entity collections
+async fn refresh_all(client: &ApiClient, tx: &Replies, options: WorkerOptions) {
+    // Check connection
+    let health = retry_rate_limited(tx, || client.health_check()).await;
+    tx.send(ApiMessage::ConnectionStatus(health.as_ref().ok().copied())).await;
+
+    match health {
+        Ok(_) => {
+            // Fetch all data concurrently
+            tokio::join!(
+                refresh_entity(client, tx, EntityType::Project, options),
+                refresh_entity(client, tx, EntityType::Client, options),
+                refresh_entity(client, tx, EntityType::User, options)
+            );
+        }
+        Err(e) => {
+            tx.send(ApiMessage::Failed(ApiOperation::Connect, e)).await;
+        }
+    }
+}
+
+/// Run the API worker task until `Shutdown` or quitting is signalled on
+/// `stop`. Queued and running reads are dropped as soon as `stop` is set;
+/// mutations still run to the end, since a request cut off midway may or
+/// may not have been applied. The last message says the worker stopped.
 async fn run_api_worker(
-    client: ApiClient,
-    tx: mpsc::Sender<ApiMessage>,
-    rx: &mut mpsc::Receiver<ApiCommand>,
+    mut client: ApiClient,
+    sender: mpsc::Sender<Response>,
+    rx: &mut mpsc::Receiver<Request>,
+    mut options: WorkerOptions,
+    mut stop: watch::Receiver<bool>,
 ) {
     loop {
-        tokio::select! {
-            Some(cmd) = rx.recv() => {
-                match cmd {
-                    ApiCommand::RefreshAll => {
-                        // Check connection
-                        let connected = client.health_check().await.unwrap_or(false);
-                        tx.send(ApiMessage::ConnectionStatus(connected)).await.ok();
-
-                        if connected {
-                            // Fetch all data concurrently
-                            let (projects, clients, users) = tokio::join!(
-                                client.fetch_all_projects(),
-                                client.fetch_all_clients(),
-                                client.fetch_all_users()
-                            );
-
-                            // Send results
-                            match projects {
-                                Ok(data) => { tx.send(ApiMessage::ProjectsLoaded(data)).await.ok(); }
-                                Err(e) => { tx.send(ApiMessage::Error(e.to_string())).await.ok(); }
-                            }
-                            match clients {
-                                Ok(data) => { tx.send(ApiMessage::ClientsLoaded(data)).await.ok(); }
-                                Err(e) => { tx.send(ApiMessage::Error(e.to_string())).await.ok(); }
-                            }
-                            match users {
-                                Ok(data) => { tx.send(ApiMessage::UsersLoaded(data)).await.ok(); }
-                                Err(e) => { tx.send(ApiMessage::Error(e.to_string())).await.ok(); }
-                            }
-                        } else {
-                            tx.send(ApiMessage::Error("Cannot connect to API".to_string())).await.ok();
-                        }
-                    }
-                    ApiCommand::RefreshProjects => {
-                        match client.fetch_all_projects().await {
-                            Ok(data) => { tx.send(ApiMessage::ProjectsLoaded(data)).await.ok(); }
-                            Err(e) => { tx.send(ApiMessage::Error(e.to_string())).await.ok(); }
-                        }
-                    }
-                    ApiCommand::RefreshClients => {
-                        match client.fetch_all_clients().await {
-                            Ok(data) => { tx.send(ApiMessage::ClientsLoaded(data)).await.ok(); }
-                            Err(e) => { tx.send(ApiMessage::Error(e.to_string())).await.ok(); }
-                        }
-                    }
-                    ApiCommand::RefreshUsers => {
-                        match client.fetch_all_users().await {
-                            Ok(data) => { tx.send(ApiMessage::UsersLoaded(data)).await.ok(); }
-                            Err(e) => { tx.send(ApiMessage::Error(e.to_string())).await.ok(); }
-                        }
-                    }
-                    ApiCommand::CheckConnection => {
-                        let connected = client.health_check().await.unwrap_or(false);
-                        tx.send(ApiMessage::ConnectionStatus(connected)).await.ok();
-                    }
-                    ApiCommand::Shutdown => {
-                        break;
-                    }
-                    // CRUD operations for Clients
-                    ApiCommand::CreateClient(dto) => {
-                        match client.create_client(&dto).await {
-                            Ok(id) => {
-                                tx.send(ApiMessage::Created(EntityType::Client, id)).await.ok();
-                            }
-                            Err(e) => {
-                                tx.send(ApiMessage::Error(format!("Create client failed: {}", e))).await.ok();
-                            }
-                        }
-                    }
-                    ApiCommand::UpdateClient(id, dto) => {
-                        match client.update_client(id, &dto).await {
-                            Ok(_) => {
-                                tx.send(ApiMessage::Updated(EntityType::Client)).await.ok();
-                            }
-                            Err(e) => {
-                                tx.send(ApiMessage::Error(format!("Update client failed: {}", e))).await.ok();
-                            }
-                        }
-                    }
-                    ApiCommand::DeleteClient(id) => {
-                        match client.delete_client(id).await {
-                            Ok(deleted_id) => {
-                                tx.send(ApiMessage::Deleted(EntityType::Client, deleted_id)).await.ok();
-                            }
-                            Err(e) => {
-                                tx.send(ApiMessage::Error(format!("Delete client failed: {}", e))).await.ok();
-                            }
-                        }
-                    }
-                    // CRUD operations for Projects
-                    ApiCommand::CreateProject(dto) => {
-                        match client.create_project(&dto).await {
-                            Ok(id) => {
-                                tx.send(ApiMessage::Created(EntityType::Project, id)).await.ok();
-                            }
-                            Err(e) => {
-                                tx.send(ApiMessage::Error(format!("Create project failed: {}", e))).await.ok();
-                            }
-                        }
-                    }
-                    ApiCommand::UpdateProject(id, dto) => {
-                        match client.update_project(id, &dto).await {
-                            Ok(_) => {
-                                tx.send(ApiMessage::Updated(EntityType::Project)).await.ok();
-                            }
-                            Err(e) => {
-                                tx.send(ApiMessage::Error(format!("Update project failed: {}", e))).await.ok();
-                            }
-                        }
-                    }
-                    ApiCommand::DeleteProject(id) => {
-                        match client.delete_project(id).await {
-                            Ok(deleted_id) => {
-                                tx.send(ApiMessage::Deleted(EntityType::Project, deleted_id)).await.ok();
-                            }
-                            Err(e) => {
-                                tx.send(ApiMessage::Error(format!("Delete project failed: {}", e))).await.ok();
-                            }
-                        }
-                    }
-                    // CRUD operations for Users
-                    ApiCommand::CreateUser(dto) => {
-                        match client.create_user(&dto).await {
-                            Ok(id) => {
-                                tx.send(ApiMessage::Created(EntityType::User, id)).await.ok();
-                            }
-                            Err(e) => {
-                                tx.send(ApiMessage::Error(format!("Create user failed: {}", e))).await.ok();
-                            }
-                        }
-                    }
-                    ApiCommand::UpdateUser(id, dto) => {
-                        match client.update_user(id, &dto).await {
-                            Ok(_) => {
-                                tx.send(ApiMessage::Updated(EntityType::User)).await.ok();
-                            }
-                            Err(e) => {
-                                tx.send(ApiMessage::Error(format!("Update user failed: {}", e))).await.ok();
-                            }
-                        }
-                    }
-                    ApiCommand::DeleteUser(id) => {
-                        match client.delete_user(id).await {
-                            Ok(deleted_id) => {
-                                tx.send(ApiMessage::Deleted(EntityType::User, deleted_id)).await.ok();
-                            }
-                            Err(e) => {
-                                tx.send(ApiMessage::Error(format!("Delete user failed: {}", e))).await.ok();
-                            }
-                        }
+        // Queued commands first, so mutations sent before quitting still go out
+        let request = tokio::select! {
+            biased;
+            request = rx.recv() => request,
+            _ = stopping(&mut stop) => None,
+        };
+        let Some(Request { id, command }) = request else { break };
+        if matches!(command, ApiCommand::Shutdown) {
+            break;
+        }
+        let tx = Replies { tx: sender.clone(), request: Some(id) };
+        if command.is_mutating() {
+            run_command(&mut client, &tx, &mut options, command).await;
+        } else if !*stop.borrow() {
+            tokio::select! {
+                _ = run_command(&mut client, &tx, &mut options, command) => {}
+                _ = stopping(&mut stop) => {}
+            }
+        }
+    }
+    sender.try_send(Response { request: None, message: ApiMessage::WorkerStopped }).ok();
+}
+
+/// Resolves once quitting is signalled, or the UI is gone
+async fn stopping(stop: &mut watch::Receiver<bool>) {
+    stop.wait_for(|stop| *stop).await.ok();
+}
+
+/// Carry out one command, sending its outcome through `tx`
+async fn run_command(client: &mut ApiClient, tx: &Replies, options: &mut WorkerOptions, cmd: ApiCommand) {
+    // Queued refreshes would only be refused again; run them once the limit lifts
+    let refresh = matches!(
+        cmd,
+        ApiCommand::RefreshAll
+            | ApiCommand::RefreshProjects
+            | ApiCommand::RefreshClients
+            | ApiCommand::RefreshUsers
+            | ApiCommand::FetchPage(..)
+            | ApiCommand::CheckConnection
+    );
+    if refresh {
+        wait_out_rate_limit(client, tx).await;
+    }
+
+    match cmd {
+        // The UI never sends these in read-only mode; refuse them anyway
+        ref cmd if options.read_only && cmd.is_mutating() => {
+            tx.send(ApiMessage::Refused("Read-only mode: refusing to modify data".to_string())).await;
+        }
+        ApiCommand::RefreshAll => {
+            refresh_all(client, tx, *options).await;
+        }
+        ApiCommand::RefreshProjects => {
+            refresh_entity(client, tx, EntityType::Project, *options).await;
+        }
+        ApiCommand::RefreshClients => {
+            refresh_entity(client, tx, EntityType::Client, *options).await;
+        }
+        ApiCommand::RefreshUsers => {
+            refresh_entity(client, tx, EntityType::User, *options).await;
+        }
+        ApiCommand::FetchOne(entity, id) => {
+            let message = match fetch_one(client, tx, entity, id).await {
+                Ok(entity) => ApiMessage::FetchedOne(entity),
+                Err(e) => ApiMessage::Failed(ApiOperation::Fetch(entity, id), e),
+            };
+            tx.send(message).await;
+        }
+        ApiCommand::RefreshOne(entity, id) => {
+            let message = match fetch_one(client, tx, entity, id).await {
+                Ok(entity) => ApiMessage::OneLoaded(entity),
+                Err(e) => ApiMessage::Failed(ApiOperation::Fetch(entity, id), e),
+            };
+            tx.send(message).await;
+        }
+        ApiCommand::FetchHistory(id) => {
+            let message = match retry_rate_limited(tx, || client.fetch_project_history(id)).await {
+                Ok(records) => ApiMessage::HistoryLoaded(id, records),
+                Err(e) => ApiMessage::Failed(ApiOperation::History(id), e),
+            };
+            tx.send(message).await;
+        }
+        ApiCommand::FetchPage(entity, page, size) => {
+            // Refreshes stay on the page being browsed
+            if options.page_browsing && entity == EntityType::Project {
+                options.browse_page = page;
+            }
+            load_page(client, tx, entity, page, size).await;
+        }
+        ApiCommand::CheckConnection => {
+            let latency = client.health_check().await.ok();
+            tx.send(ApiMessage::ConnectionStatus(latency)).await;
+        }
+        // Handled by the worker loop
+        ApiCommand::Shutdown => {}
+        ApiCommand::Import(rows) => {
+            let total = rows.len();
+            let entity_type = rows.first().map(|row| row.entity.entity_type());
+            // Progress is best effort; the final message carries the failures
+            let failures = import::execute(
+                client,
+                &rows,
+                |done, row, result| {
+                    tx.try_send(ApiMessage::ImportProgress {
+                        done,
+                        total,
+                        line: row.line,
+                        error: result.as_ref().err().map(|e| e.to_string()),
+                    });
+                },
+                |chunk| {
+                    if let Some(entity_type) = entity_type {
+                        tx.try_send(ApiMessage::BatchProgress(entity_type, *chunk));
                     }
+                },
+            )
+            .await;
+            if let Some(entity_type) = entity_type {
+                tx.send(ApiMessage::ImportFinished {
+                    entity_type,
+                    created: total - failures.len(),
+                    failures,
+                })
+                .await;
+            }
+        }
+        ApiCommand::CreateProjectsBatch(projects) => {
+            let results = client
+                .create_projects_chunked(&projects, |chunk, _| {
+                    tx.try_send(ApiMessage::BatchProgress(EntityType::Project, *chunk));
+                })
+                .await;
+            let failures: Vec<(usize, String)> = results
+                .iter()
+                .enumerate()
+                .filter_map(|(i, result)| result.as_ref().err().map(|e| (i + 1, e.to_string())))
+                .collect();
+            tx.send(ApiMessage::ImportFinished {
+                entity_type: EntityType::Project,
+                created: results.len() - failures.len(),
+                failures,
+            })
+            .await;
+        }
+        ApiCommand::SetBaseUrl { url, token } => {
+            // Same server settings, new address
+            match client.retarget(url, token.as_deref(), options.timeout) {
+                Ok(new_client) => {
+                    *client = new_client;
+                    options.browse_page = 1;
+                    refresh_all(client, tx, *options).await;
+                }
+                Err(e) => {
+                    tx.send(ApiMessage::Error(format!("Invalid API URL: {}", e))).await;
+                }
+            }
+        }
+        // CRUD operations for Clients
+        ApiCommand::CreateClient(dto) => {
+            match retry_rate_limited(tx, || client.create_client(&dto)).await {
+                Ok(id) => {
+                    tx.send(ApiMessage::Created(EntityType::Client, id)).await;
+                }
+                Err(e) => {
+                    tx.send(ApiMessage::Failed(ApiOperation::Create(EntityType::Client), e)).await;
+                }
+            }
+        }
+        ApiCommand::UpdateClient(id, dto) => {
+            match retry_rate_limited(tx, || client.update_client(id, &dto)).await {
+                Ok(_) => {
+                    tx.send(ApiMessage::Updated(EntityType::Client, id)).await;
+                }
+                Err(e) => {
+                    tx.send(ApiMessage::Failed(ApiOperation::Update(EntityType::Client), e)).await;
+                }
+            }
+        }
+        ApiCommand::DeleteClient(id) => {
+            match retry_rate_limited(tx, || client.delete_client(id)).await {
+                Ok(deleted_id) => {
+                    tx.send(ApiMessage::Deleted(EntityType::Client, deleted_id)).await;
+                }
+                Err(e) => {
+                    tx.send(ApiMessage::Failed(ApiOperation::Delete(EntityType::Client, id), e)).await;
+                }
+            }
+        }
+        // CRUD operations for Projects
+        ApiCommand::CreateProject(dto) => {
+            match retry_rate_limited(tx, || client.create_project(&dto)).await {
+                Ok(id) => {
+                    tx.send(ApiMessage::Created(EntityType::Project, id)).await;
+                }
+                Err(e) => {
+                    tx.send(ApiMessage::Failed(ApiOperation::Create(EntityType::Project), e)).await;
+                }
+            }
+        }
+        ApiCommand::UpdateProject(id, dto) => {
+            match retry_rate_limited(tx, || client.update_project(id, &dto)).await {
+                Ok(_) => {
+                    tx.send(ApiMessage::Updated(EntityType::Project, id)).await;
+                }
+                Err(e) => {
+                    tx.send(ApiMessage::Failed(ApiOperation::Update(EntityType::Project), e)).await;
+                }
+            }
+        }
+        ApiCommand::DeleteProject(id) => {
+            match retry_rate_limited(tx, || client.delete_project(id)).await {
+                Ok(deleted_id) => {
+                    tx.send(ApiMessage::Deleted(EntityType::Project, deleted_id)).await;
+                }
+                Err(e) => {
+                    tx.send(ApiMessage::Failed(ApiOperation::Delete(EntityType::Project, id), e)).await;
+                }
+            }
+        }
+        // CRUD operations for Users
+        ApiCommand::CreateUser(dto) => {
+            match retry_rate_limited(tx, || client.create_user(&dto)).await {
+                Ok(id) => {
+                    tx.send(ApiMessage::Created(EntityType::User, id)).await;
+                }
+                Err(e) => {
+                    tx.send(ApiMessage::Failed(ApiOperation::Create(EntityType::User), e)).await;
+                }
+            }
+        }
+        ApiCommand::UpdateUser(id, dto) => {
+            match retry_rate_limited(tx, || client.update_user(id, &dto)).await {
+                Ok(_) => {
+                    tx.send(ApiMessage::Updated(EntityType::User, id)).await;
+                }
+                Err(e) => {
+                    tx.send(ApiMessage::Failed(ApiOperation::Update(EntityType::User), e)).await;
+                }
+            }
+        }
+        ApiCommand::DeleteUser(id) => {
+            match retry_rate_limited(tx, || client.delete_user(id)).await {
+                Ok(deleted_id) => {
+                    tx.send(ApiMessage::Deleted(EntityType::User, deleted_id)).await;
+                }
+                Err(e) => {
+                    tx.send(ApiMessage::Failed(ApiOperation::Delete(EntityType::User, id), e)).await;
                 }
             }
         }
@@ -255,56 +871,97 @@ async fn run_api_worker(
 async fn run_event_loop(
     terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
     app: &mut App,
-    api_rx: &mut mpsc::Receiver<ApiMessage>,
-    cmd_tx: &mpsc::Sender<ApiCommand>,
+    api_rx: &mut mpsc::Receiver<Response>,
+    cmd_tx: &mpsc::Sender<Request>,
+    keys: &mut recording::Session,
 ) -> Result<()> {
+    let mut title = String::new();
     loop {
-        // Get terminal size for particle updates
-        let size = terminal.size()?;
+        // Particles fill the viewport, which inline is less than the screen
+        let size = terminal.get_frame().area();
 
-        // Update animations
-        app.tick(size.width, size.height);
+        // Update animations; data gone stale is refreshed
+        if let Some(cmd) = app.tick(size.width, size.height) {
+            cmd_tx.send(app.stamp(cmd)).await.ok();
+        }
 
-        // Render the UI
+        // Render the UI, timing it for the F12 overlay
+        let started = Instant::now();
+        ui::layout(app, size);
         terminal.draw(|frame| ui::render(frame, app))?;
+        app.frame_stats.record(started, started.elapsed());
+        app.frame_stats.api_queue = api_rx.len();
+        app.frame_stats.command_queue = cmd_tx.max_capacity() - cmd_tx.capacity();
+
+        // Text copied this frame goes to the clipboard through the terminal
+        if let Some(text) = app.clipboard.take() {
+            if let Err(e) = screen::copy_to_clipboard(terminal.backend_mut(), &text) {
+                app.log(LogEntry::warning(format!("Failed to copy to the clipboard: {}", e)));
+            }
+        }
+
+        // The window title is only rewritten when the counts or the connection change
+        if app.terminal_title {
+            let next = app.window_title(chrono::Local::now().date_naive(), Instant::now());
+            if next != title && screen::set_title(terminal.backend_mut(), &next).is_ok() {
+                title = next;
+            }
+        }
 
         // Check for API messages (non-blocking)
-        while let Ok(msg) = api_rx.try_recv() {
+        let mut stale = Vec::new();
+        while let Ok(response) = api_rx.try_recv() {
             // After CRUD operations, refresh the relevant data
-            let should_refresh = match &msg {
-                ApiMessage::Created(entity_type, _) | ApiMessage::Deleted(entity_type, _) => {
-                    Some(*entity_type)
-                }
-                ApiMessage::Updated(entity_type) => Some(*entity_type),
-                _ => None,
-            };
+            if let Some(entry) = response.message.stale() {
+                stale.push(entry);
+            }
 
-            app.handle_api_message(msg);
-
-            // Trigger data refresh after mutations
-            if let Some(entity_type) = should_refresh {
-                let refresh_cmd = match entity_type {
-                    EntityType::Client => ApiCommand::RefreshClients,
-                    EntityType::Project => ApiCommand::RefreshProjects,
-                    EntityType::User => ApiCommand::RefreshUsers,
-                };
-                cmd_tx.send(refresh_cmd).await.ok();
-                // Also refresh related entities for project dropdown updates
-                if entity_type == EntityType::Client || entity_type == EntityType::User {
-                    cmd_tx.send(ApiCommand::RefreshProjects).await.ok();
-                }
+            if let Some(request) = app.handle_response(response) {
+                cmd_tx.send(request).await.ok();
             }
         }
 
-        // Handle input events with timeout for animation
-        if event::poll(FRAME_DURATION)? {
-            if let Event::Key(key) = event::read()? {
+        // Mutations completing in the same frame share one refresh per collection
+        for cmd in app.refresh_after_mutations(&stale) {
+            cmd_tx.send(app.stamp(cmd)).await.ok();
+        }
+
+        // Names the frame couldn't resolve from the loaded lists
+        for cmd in app.lookup_fetches() {
+            cmd_tx.send(app.stamp(cmd)).await.ok();
+        }
+
+        // A replayed key takes the place of typing this frame
+        if let Some(key) = keys.replayed(app) {
+            if let Some(cmd) = app.handle_key(key) {
+                cmd_tx.send(app.stamp(cmd)).await.ok();
+            }
+        }
+
+        // Handle input events with timeout for animation; a still screen
+        // only needs redrawing for clocks and incoming data
+        let frame_duration = if app.needs_frames() {
+            config::frame_duration(app.frame_stats.target_fps)
+        } else {
+            IDLE_FRAME_DURATION
+        };
+        if event::poll(frame_duration)? {
+            match event::read()? {
                 // Only handle key press events (not release)
-                if key.kind == KeyEventKind::Press {
+                Event::Key(key) if key.kind == KeyEventKind::Press => {
+                    keys.record(app, key);
                     if let Some(cmd) = app.handle_key(key) {
-                        cmd_tx.send(cmd).await.ok();
+                        cmd_tx.send(app.stamp(cmd)).await.ok();
                     }
                 }
+                // Clear and re-layout so no stale cells survive a shrink
+                Event::Resize(width, height) => {
+                    terminal.resize(Rect::new(0, 0, width, height))?;
+                }
+                Event::Mouse(mouse) => app.handle_mouse(mouse),
+                Event::FocusGained => app.set_focus(true),
+                Event::FocusLost => app.set_focus(false),
+                _ => {}
             }
         }
 
@@ -316,3 +973,682 @@ async fn run_event_loop(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    use form::FormInput;
+    use mock_api::MockApi;
+    use test_support::{client, project, ClientFixture, ProjectFixture};
+
+    fn parse(args: &[&str]) -> Result<CliArgs> {
+        CliArgs::parse(args.iter().map(|s| s.to_string()))
+    }
+
+    #[test]
+    fn test_parse_url_and_seed() {
+        let args = parse(&["http://api:8080", "--seed", "7"]).unwrap();
+        assert_eq!(args.api_url.as_deref(), Some("http://api:8080"));
+        assert_eq!(args.seed, Some(7));
+
+        assert_eq!(parse(&[]).unwrap(), CliArgs::default());
+    }
+
+    #[test]
+    fn test_parse_profile() {
+        let args = parse(&["--profile", "staging"]).unwrap();
+        assert_eq!(args.profile.as_deref(), Some("staging"));
+        assert!(parse(&["--profile"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_read_only() {
+        assert!(parse(&["--read-only"]).unwrap().read_only);
+        assert!(!parse(&[]).unwrap().read_only);
+    }
+
+    #[test]
+    fn test_parse_trace_api() {
+        assert!(parse(&["--trace-api"]).unwrap().trace_api);
+        assert!(!parse(&[]).unwrap().trace_api);
+    }
+
+    #[test]
+    fn test_parse_no_altscreen() {
+        assert!(parse(&["--no-altscreen"]).unwrap().no_altscreen);
+        assert!(!parse(&[]).unwrap().no_altscreen);
+    }
+
+    #[test]
+    fn test_parse_record_and_replay() {
+        let args = parse(&["--record", "keys.jsonl"]).unwrap();
+        assert_eq!(args.record, Some(PathBuf::from("keys.jsonl")));
+        assert_eq!(parse(&["--replay", "keys.jsonl"]).unwrap().replay, Some(PathBuf::from("keys.jsonl")));
+        assert!(parse(&["--record"]).is_err());
+        assert!(parse(&["--record", "a.jsonl", "--replay", "b.jsonl"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_tls_options() {
+        let args = parse(&["--ca-cert", "/etc/ssl/internal.pem", "--insecure"]).unwrap();
+        assert_eq!(args.ca_cert, Some(PathBuf::from("/etc/ssl/internal.pem")));
+        assert!(args.insecure);
+        assert!(!parse(&[]).unwrap().insecure);
+        assert!(parse(&["--ca-cert"]).is_err());
+
+        // A CA file that can't be read stops startup, before the terminal is touched
+        let args = parse(&["--ca-cert", "/nonexistent/ca.pem"]).unwrap();
+        let err = Server::resolve(&args, &Config::default()).err().unwrap();
+        assert_eq!(err.to_string(), "Failed to read CA certificate /nonexistent/ca.pem");
+        let config = Config { insecure: true, ..Config::default() };
+        assert!(Server::resolve(&parse(&[]).unwrap(), &config).unwrap().tls.insecure);
+    }
+
+    #[test]
+    fn test_parse_subcommand_after_global_flags() {
+        let args = parse(&["--profile", "prod", "list", "projects", "--json"]).unwrap();
+        assert_eq!(args.profile.as_deref(), Some("prod"));
+        assert!(matches!(args.command, Some(cli::Command::List { json: true, .. })));
+        assert!(parse(&["list"]).is_err());
+
+        let args = parse(&["--report", "weekly.md"]).unwrap();
+        let report = |include_archived| Some(cli::Command::Report { path: "weekly.md".into(), include_archived });
+        assert_eq!(args.command, report(false));
+        let args = parse(&["--include-archived", "--report", "weekly.md"]).unwrap();
+        assert_eq!(args.command, report(true));
+        assert!(parse(&["--include-archived"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_fps() {
+        assert_eq!(parse(&["--fps", "15"]).unwrap().fps, Some(15));
+        assert_eq!(parse(&[]).unwrap().fps, None);
+        assert!(parse(&["--fps"]).is_err());
+        assert!(parse(&["--fps", "fast"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_rejects_bad_input() {
+        assert!(parse(&["--seed"]).is_err());
+        assert!(parse(&["--seed", "abc"]).is_err());
+        assert!(parse(&["--bogus"]).is_err());
+        assert!(parse(&["http://a", "http://b"]).is_err());
+    }
+
+    fn worker_options() -> WorkerOptions {
+        WorkerOptions {
+            read_only: false,
+            lazy_loading: false,
+            page_size: 100,
+            page_browsing: false,
+            browse_page: 1,
+            timeout: api::DEFAULT_TIMEOUT,
+        }
+    }
+
+    /// Run the worker against `url`, feed it `commands`, and collect its replies
+    async fn run_worker(url: &str, commands: Vec<ApiCommand>) -> Vec<ApiMessage> {
+        run_worker_with(url, worker_options(), commands).await
+    }
+
+    async fn run_worker_with(url: &str, options: WorkerOptions, commands: Vec<ApiCommand>) -> Vec<ApiMessage> {
+        let requests = commands.into_iter().map(|command| Request { id: RequestId::generate(), command });
+        run_requests(url, options, requests.collect()).await.into_iter().map(|r| r.message).collect()
+    }
+
+    /// Like `run_worker_with`, keeping the request ids on the replies
+    async fn run_requests(url: &str, options: WorkerOptions, requests: Vec<Request>) -> Vec<Response> {
+        let (tx, mut rx) = mpsc::channel(100);
+        let (cmd_tx, mut cmd_rx) = mpsc::channel(100);
+        let (_stop, stop_rx) = watch::channel(false);
+        let client = ApiClient::new(url).unwrap();
+        let worker = tokio::spawn(async move { run_api_worker(client, tx, &mut cmd_rx, options, stop_rx).await });
+
+        for request in requests {
+            cmd_tx.send(request).await.unwrap();
+        }
+        cmd_tx.send(Request { id: RequestId::generate(), command: ApiCommand::Shutdown }).await.unwrap();
+        worker.await.unwrap();
+
+        let mut responses = Vec::new();
+        while let Ok(response) = rx.try_recv() {
+            responses.push(response);
+        }
+        let last = responses.pop().map(|r| r.message);
+        assert!(matches!(last, Some(ApiMessage::WorkerStopped)), "ended with {:?}", last);
+        responses
+    }
+
+    #[tokio::test]
+    async fn test_stop_cancels_a_hung_read() {
+        let mock = MockApi::start_slow(Duration::from_secs(30), |_| (200, mock_api::page::<()>(&[]))).await;
+        let (tx, mut rx) = mpsc::channel(100);
+        let (cmd_tx, mut cmd_rx) = mpsc::channel(100);
+        let (stop, stop_rx) = watch::channel(false);
+        let client = ApiClient::new(&mock.url).unwrap();
+        let worker =
+            tokio::spawn(async move { run_api_worker(client, tx, &mut cmd_rx, worker_options(), stop_rx).await });
+        cmd_tx.send(Request { id: RequestId::generate(), command: ApiCommand::RefreshAll }).await.unwrap();
+        // Wait for the health check to reach the server
+        while mock.requests().is_empty() {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        let quit = Instant::now();
+        stop.send(true).unwrap();
+        tokio::time::timeout(SHUTDOWN_TIMEOUT, worker).await.expect("worker still running").unwrap();
+        assert!(quit.elapsed() < Duration::from_secs(1), "took {:?}", quit.elapsed());
+        let response = rx.recv().await.unwrap();
+        assert!(matches!(response.message, ApiMessage::WorkerStopped));
+        assert_eq!(response.request, None);
+    }
+
+    #[tokio::test]
+    async fn test_stop_still_sends_queued_mutations() {
+        let mock = MockApi::start_slow(Duration::from_millis(100), |req| match req.method.as_str() {
+            "POST" => (201, format!("\"{}\"", Uuid::from_u128(1))),
+            _ => (200, mock_api::page::<()>(&[])),
+        })
+        .await;
+        let (tx, mut rx) = mpsc::channel(100);
+        let (cmd_tx, mut cmd_rx) = mpsc::channel(100);
+        let (stop, stop_rx) = watch::channel(false);
+        let dto = models::CreateClientDto { name: Some("Acme".to_string()), ..Default::default() };
+        for command in [ApiCommand::RefreshProjects, ApiCommand::CreateClient(dto), ApiCommand::RefreshClients] {
+            cmd_tx.send(Request { id: RequestId::generate(), command }).await.unwrap();
+        }
+        // Quitting right away: the reads are dropped, the create still goes out
+        stop.send(true).unwrap();
+        let client = ApiClient::new(&mock.url).unwrap();
+        run_api_worker(client, tx, &mut cmd_rx, worker_options(), stop_rx).await;
+
+        let methods: Vec<String> = mock.requests().into_iter().map(|r| r.method).collect();
+        assert_eq!(methods, ["POST"]);
+        let mut messages = Vec::new();
+        while let Ok(response) = rx.try_recv() {
+            messages.push(response.message);
+        }
+        assert!(matches!(messages.as_slice(), [ApiMessage::Created(EntityType::Client, _), ApiMessage::WorkerStopped]));
+    }
+
+    #[tokio::test]
+    async fn test_timed_measures_a_slow_fetch() {
+        let mock = MockApi::start_slow(Duration::from_millis(120), |_| {
+            let body = r#"{"items":[],"page":1,"pageSize":10,"totalCount":0,"totalPages":1,"hasPrevious":false,"hasNext":false}"#;
+            (200, body.to_string())
+        })
+        .await;
+        let client = ApiClient::new(&mock.url).unwrap();
+        let (result, duration) = timed(client.fetch_projects(1, 10)).await;
+        assert!(result.is_ok());
+        assert!(duration >= Duration::from_millis(120), "{:?}", duration);
+        let ((), instant) = timed(async {}).await;
+        assert!(instant < Duration::from_millis(120));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_stats_follow_each_load() {
+        let mock = MockApi::start_slow(Duration::from_millis(60), |req| {
+            if req.path.starts_with("/users") {
+                return (503, r#"{"title":"Service Unavailable"}"#.to_string());
+            }
+            let items = if req.path.starts_with("/projects") {
+                serde_json::json!([project(1).named("Apollo"), project(2).named("Gemini")])
+            } else {
+                serde_json::json!([])
+            };
+            let body = serde_json::json!({
+                "items": items, "page": 1, "pageSize": 100, "totalCount": items.as_array().unwrap().len(),
+                "totalPages": 1, "hasPrevious": false, "hasNext": false,
+            });
+            (200, body.to_string())
+        })
+        .await;
+        let commands = vec![ApiCommand::RefreshProjects, ApiCommand::RefreshUsers];
+        let messages = run_worker(&mock.url, commands).await;
+        let stats: Vec<(EntityType, Option<u16>, Duration, usize)> = messages
+            .iter()
+            .filter_map(|m| match m {
+                ApiMessage::FetchStats { entity, status, duration, items } => {
+                    Some((*entity, *status, *duration, *items))
+                }
+                _ => None,
+            })
+            .collect();
+        let [(EntityType::Project, Some(200), projects, 2), (EntityType::User, Some(503), users, 0)] = stats.as_slice()
+        else {
+            panic!("unexpected stats: {:?}", stats)
+        };
+        assert!(*projects >= Duration::from_millis(60) && *users >= Duration::from_millis(60));
+
+        // Anything over the threshold is logged as slow
+        let mut app = App::new();
+        app.slow_fetch = Some(Duration::from_millis(50));
+        for message in messages {
+            app.handle_api_message(message);
+        }
+        let project_stats = app.fetch_stats[&EntityType::Project];
+        assert_eq!((project_stats.status, project_stats.items), (Some(200), 2));
+        let slow: Vec<&str> =
+            app.logs.iter().filter(|l| l.message.starts_with("Slow fetch: ")).map(|l| l.message.as_str()).collect();
+        assert_eq!(slow.len(), 2);
+        assert!(slow[0].starts_with("Slow fetch: projects: 200 OK · 0."), "{}", slow[0]);
+        assert!(slow[0].ends_with("s · 2 items"), "{}", slow[0]);
+        assert!(slow[1].starts_with("Slow fetch: users: 503 Service Unavailable · "), "{}", slow[1]);
+    }
+
+    #[tokio::test]
+    async fn test_set_base_url_reloads_from_the_new_server() {
+        let old = MockApi::start(|_| (500, r#"{"title":"Internal Server Error"}"#.to_string())).await;
+        let new = MockApi::start(|req| {
+            let body = if req.path.starts_with("/projects") {
+                serde_json::json!({
+                    "items": [project(1).named("Apollo")], "page": 1, "pageSize": 100,
+                    "totalCount": 1, "totalPages": 1, "hasPrevious": false, "hasNext": false,
+                })
+            } else {
+                serde_json::json!({
+                    "items": [], "page": 1, "pageSize": 100, "totalCount": 0, "totalPages": 1,
+                    "hasPrevious": false, "hasNext": false,
+                })
+            };
+            (200, body.to_string())
+        })
+        .await;
+        let command = ApiCommand::SetBaseUrl { url: new.url.clone(), token: None };
+        let messages = run_worker(&old.url, vec![command]).await;
+
+        assert!(old.requests().is_empty());
+        assert!(messages.iter().any(|m| matches!(m, ApiMessage::ProjectsLoaded(all) if all.items.len() == 1)));
+        assert!(messages.iter().any(|m| matches!(m, ApiMessage::ClientsLoaded(_))));
+        assert!(messages.iter().any(|m| matches!(m, ApiMessage::UsersLoaded(_))));
+    }
+
+    #[tokio::test]
+    async fn test_delete_of_vanished_project_refreshes_quietly() {
+        let mock = MockApi::start(|_| (404, r#"{"title":"Not Found"}"#.to_string())).await;
+        let id = Uuid::new_v4();
+        let messages = run_worker(&mock.url, vec![ApiCommand::DeleteProject(id)]).await;
+        let [msg] = messages.as_slice() else { panic!("unexpected replies: {:?}", messages) };
+        assert_eq!(msg.stale(), None);
+
+        let mut app = App::new();
+        app.confirm_dialog = Some(app::ConfirmDialog::new_delete(EntityType::Project, id, "Apollo"));
+        app.input_mode = app::InputMode::Confirming;
+        app.handle_api_message(msg.clone());
+        assert!(app.confirm_dialog.is_none());
+        assert!(app.error_popup.is_none());
+        assert!(app.logs.iter().any(|l| l.message == "Project already deleted elsewhere"));
+    }
+
+    #[tokio::test]
+    async fn test_batch_delete_skips_failures_against_the_server() {
+        let ids: Vec<Uuid> = (1..=3).map(Uuid::from_u128).collect();
+        let failing = format!("/clients/{}", ids[1]);
+        let mock = MockApi::start(move |req| match req.path == failing {
+            true => (500, r#"{"title":"Internal Server Error"}"#.to_string()),
+            false => (200, format!("\"{}\"", req.path.trim_start_matches("/clients/"))),
+        })
+        .await;
+
+        let mut app = App::new();
+        app.active_tab = app::Tab::Clients;
+        app.clients = ids.iter().map(|id| client(id.as_u128()).named(&id.to_string())).collect();
+        app.marked = ids.iter().copied().collect();
+        app.open_delete_confirm();
+        let mut command = app.handle_key(KeyEvent::new(KeyCode::Char('a'), KeyModifiers::NONE));
+        while let Some(next) = command.take() {
+            let messages = run_worker(&mock.url, vec![next]).await;
+            for message in messages {
+                command = command.or(app.handle_api_message(message));
+            }
+        }
+
+        let methods: Vec<String> = mock.requests().into_iter().map(|r| r.method).collect();
+        assert_eq!(methods, ["DELETE"; 3]);
+        assert_eq!(app.clients.iter().map(|c| c.id).collect::<Vec<_>>(), [ids[1]]);
+        assert_eq!(app.in_flight, 0);
+        let popup = app.error_popup.as_ref().unwrap();
+        assert!(popup.message.starts_with("2 deleted, 1 failed\n"), "{}", popup.message);
+    }
+
+    #[tokio::test]
+    async fn test_update_conflict_offers_reload() {
+        let mock = MockApi::start(|_| (409, r#"{"title":"Conflict"}"#.to_string())).await;
+        let id = Uuid::from_u128(1);
+        let original = project(1).named("Apollo");
+        let dto = models::UpdateProjectDto::from_project(&original);
+        let messages = run_worker(&mock.url, vec![ApiCommand::UpdateProject(id, dto)]).await;
+        let [msg] = messages.as_slice() else { panic!("unexpected replies: {:?}", messages) };
+
+        let mut app = App::new();
+        app.projects = vec![original];
+        app.radar_state.selected_index = Some(0);
+        app.active_tab = app::Tab::Timeline;
+        app.open_edit_form();
+        app.handle_api_message(msg.clone());
+        assert!(app.error_popup.is_none());
+        assert_eq!(app.form_state.as_ref().unwrap().nav().staleness, form::Staleness::Stale);
+
+        let reload = KeyEvent::new(KeyCode::Char('r'), KeyModifiers::CONTROL);
+        assert!(matches!(app.handle_key(reload), Some(ApiCommand::RefreshProjects)));
+
+        // The fresh copy replaces what was typed
+        app.handle_api_message(ApiMessage::ProjectsLoaded(vec![project(1).named("Apollo II")].into()));
+        let Some(form::FormState::Project(form)) = &app.form_state else { panic!("no project form") };
+        assert_eq!(form.nav.staleness, form::Staleness::Fresh);
+        assert_eq!(form.name.value(), "Apollo II");
+
+        // And a vanished item closes the form
+        app.form_state.as_mut().unwrap().nav_mut().staleness = form::Staleness::Reloading;
+        app.handle_api_message(ApiMessage::ProjectsLoaded(Vec::new().into()));
+        assert!(app.form_state.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_rate_limited_mutation_is_retried_once() {
+        let calls = std::sync::atomic::AtomicUsize::new(0);
+        let id = Uuid::new_v4();
+        let mock = MockApi::start(move |_| match calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst) {
+            0 => (429, String::new()),
+            _ => (201, format!("\"{}\"", id)),
+        })
+        .await;
+
+        let dto = models::CreateClientDto {
+            name: Some("Acme".to_string()),
+            ..Default::default()
+        };
+        let messages = run_worker(&mock.url, vec![ApiCommand::CreateClient(dto)]).await;
+
+        assert!(matches!(messages[0], ApiMessage::RateLimited(wait) if wait == Duration::from_secs(1)));
+        assert!(matches!(messages[1], ApiMessage::Created(EntityType::Client, created) if created == id));
+        assert_eq!(messages.len(), 2);
+        assert_eq!(mock.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_double_enter_creates_once() {
+        let id = Uuid::new_v4();
+        let mock = MockApi::start(move |_| (201, format!("\"{}\"", id))).await;
+        let mut app = App::new();
+        app.active_tab = app::Tab::Clients;
+        app.open_create_form();
+        for c in "Acme".chars() {
+            app.handle_key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+        }
+        let nav = app.form_state.as_mut().unwrap().nav_mut();
+        nav.focused_field = nav.fields.iter().position(|f| *f == form::FormField::SubmitButton).unwrap();
+
+        let enter = KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE);
+        let commands: Vec<ApiCommand> = (0..2).filter_map(|_| app.handle_key(enter)).collect();
+        let messages = run_worker(&mock.url, commands).await;
+
+        assert_eq!(mock.requests().len(), 1);
+        assert_eq!(mock.requests()[0].method, "POST");
+        for message in messages {
+            app.handle_api_message(message);
+        }
+        assert!(app.form_state.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_page_browsing_refresh_reloads_the_browsed_page() {
+        let body = r#"{"items":[],"page":3,"pageSize":10,"totalCount":30,"totalPages":3,"hasPrevious":true,"hasNext":false}"#;
+        let mock = MockApi::start(move |_| (200, body.to_string())).await;
+        let options = WorkerOptions { page_browsing: true, page_size: 10, ..worker_options() };
+        let commands = vec![ApiCommand::FetchPage(EntityType::Project, 3, 10), ApiCommand::RefreshProjects];
+        let messages = run_worker_with(&mock.url, options, commands).await;
+        let messages: Vec<ApiMessage> =
+            messages.into_iter().filter(|m| !matches!(m, ApiMessage::FetchStats { .. })).collect();
+
+        assert_eq!(messages.len(), 2);
+        assert!(messages.iter().all(|m| matches!(m, ApiMessage::ProjectsPage(p) if p.page == 3)));
+        let paths: Vec<String> = mock.requests().into_iter().map(|r| r.path).collect();
+        assert_eq!(paths, ["/projects?page=3&pageSize=10"; 2]);
+    }
+
+    #[tokio::test]
+    async fn test_failed_middle_page_keeps_what_loaded() {
+        let mock = MockApi::start(|req| {
+            let page: i32 = req.path.split("page=").nth(1).unwrap().split('&').next().unwrap().parse().unwrap();
+            if page == 3 {
+                return (500, r#"{"title":"Internal Server Error"}"#.to_string());
+            }
+            let n = page as u128 * 10;
+            let items = vec![project(n + 1).named("Apollo"), project(n + 2).named("Gemini")];
+            let body = serde_json::json!({
+                "items": items, "page": page, "pageSize": 2, "totalCount": 8, "totalPages": 4,
+                "hasPrevious": page > 1, "hasNext": page < 4,
+            });
+            (200, body.to_string())
+        })
+        .await;
+        let messages = run_worker(&mock.url, vec![ApiCommand::RefreshProjects]).await;
+        let messages: Vec<ApiMessage> =
+            messages.into_iter().filter(|m| !matches!(m, ApiMessage::LoadProgress(..))).collect();
+
+        let [ApiMessage::ProjectsLoaded(all), ApiMessage::Warning(warning), stats] = messages.as_slice() else {
+            panic!("unexpected replies: {:?}", messages)
+        };
+        assert_eq!((all.items.len(), all.pages), (4, 2));
+        let ApiMessage::FetchStats { status, items, .. } = stats else { panic!("unexpected reply: {:?}", stats) };
+        assert_eq!((*status, *items), (Some(500), 4));
+        assert_eq!(all.failed.as_ref().map(|f| (f.page, f.last_page)), Some((3, 4)));
+        assert!(warning.starts_with("Loaded 4 of 8 projects — pages 3–4 failed: "), "{}", warning);
+        // Page 4 was never asked for
+        assert_eq!(mock.requests().len(), 3);
+
+        let mut app = App::new();
+        for message in messages.clone() {
+            app.handle_api_message(message);
+        }
+        assert_eq!(app.projects.len(), 4);
+        assert!(app.paging.projects.partial);
+        assert!(app.error_popup.is_none());
+        let last = app.logs.back().unwrap();
+        assert_eq!((last.level, last.message.as_str()), (app::LogLevel::Warning, warning.as_str()));
+    }
+
+    /// Fully load three pages of two projects, letting `tamper` rewrite each
+    /// page's body, and return the load with the worker's warning
+    async fn load_tampered_pages(
+        tamper: fn(i32, &mut serde_json::Value),
+    ) -> (FetchedAll<models::ProjectDto>, String, usize) {
+        let mock = MockApi::start(move |req| {
+            let page: i32 = req.path.split("page=").nth(1).unwrap().split('&').next().unwrap().parse().unwrap();
+            let n = page as u128 * 10;
+            let items = vec![project(n).named("Apollo"), project(n + 1).named("Gemini")];
+            let mut body = serde_json::json!({
+                "items": items, "page": page, "pageSize": 2, "totalCount": 6, "totalPages": 3,
+                "hasPrevious": page > 1, "hasNext": page < 3,
+            });
+            tamper(page, &mut body);
+            (200, body.to_string())
+        })
+        .await;
+        let messages = run_worker(&mock.url, vec![ApiCommand::RefreshProjects]).await;
+        let mut loaded = None;
+        let mut warning = None;
+        for message in messages {
+            match message {
+                ApiMessage::ProjectsLoaded(all) => loaded = Some(all),
+                ApiMessage::Warning(w) => warning = Some(w),
+                _ => {}
+            }
+        }
+        (loaded.unwrap(), warning.unwrap_or_default(), mock.requests().len())
+    }
+
+    #[tokio::test]
+    async fn test_contradictory_pages_stop_a_full_load() {
+        use api::PageAnomaly;
+
+        // A server that ignores the page parameter keeps sending page 1
+        let (all, warning, requests) = load_tampered_pages(|page, body| {
+            if page > 1 {
+                body["page"] = 1.into();
+            }
+        })
+        .await;
+        assert_eq!(all.items.len(), 2);
+        assert_eq!(all.anomaly, Some(PageAnomaly::WrongPage { requested: 2, echoed: 1 }));
+        assert_eq!(
+            warning,
+            "Loaded 2 of 6 projects — stopped, the server's paging looks wrong: asked for page 2, the server sent page 1"
+        );
+        assert_eq!(requests, 2);
+
+        // One that echoes the page but repeats page 1's items
+        let (all, warning, _) = load_tampered_pages(|page, body| {
+            if page == 3 {
+                body["items"][1]["id"] = Uuid::from_u128(10).to_string().into();
+            }
+        })
+        .await;
+        assert_eq!(all.items.len(), 4);
+        assert_eq!(all.anomaly, Some(PageAnomaly::RepeatedItems { page: 3, repeated: 1 }));
+        assert!(warning.ends_with("page 3 repeated 1 item already loaded"), "{}", warning);
+
+        // One whose total shifts while paging
+        let (all, warning, _) = load_tampered_pages(|page, body| {
+            if page == 2 {
+                body["totalCount"] = 7.into();
+            }
+        })
+        .await;
+        assert_eq!(all.items.len(), 2);
+        assert_eq!(all.anomaly, Some(PageAnomaly::TotalChanged { page: 2, from: 6, to: 7 }));
+        assert!(warning.ends_with("the total changed from 6 to 7 at page 2"), "{}", warning);
+
+        let mut app = App::new();
+        app.handle_api_message(ApiMessage::ProjectsLoaded(all));
+        app.handle_api_message(ApiMessage::Warning(warning.clone()));
+        assert_eq!(app.projects.len(), 2);
+        assert!(app.paging.projects.partial);
+        let last = app.logs.back().unwrap();
+        assert_eq!((last.level, last.message.as_str()), (app::LogLevel::Warning, warning.as_str()));
+
+        // A well-behaved server loads all three pages without a warning
+        let (all, warning, _) = load_tampered_pages(|_, _| {}).await;
+        assert_eq!((all.items.len(), all.anomaly), (6, None));
+        assert!(warning.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_refresh_one_fetches_only_that_entity() {
+        let id = Uuid::from_u128(1);
+        let body = serde_json::to_string(&project(1).named("Apollo II")).unwrap();
+        let mock = MockApi::start(move |_| (200, body.clone())).await;
+        let messages = run_worker(&mock.url, vec![ApiCommand::RefreshOne(EntityType::Project, id)]).await;
+        let [msg] = messages.as_slice() else { panic!("unexpected replies: {:?}", messages) };
+        assert!(matches!(msg, ApiMessage::OneLoaded(FetchedEntity::Project(p)) if p.id == id));
+        assert_eq!(mock.requests().len(), 1);
+
+        let mut app = App::new();
+        app.projects = vec![project(2).named("Gemini"), project(1).named("Apollo")];
+        app.handle_api_message(msg.clone());
+        let refreshed = app.projects.iter().find(|p| p.id == id).unwrap();
+        assert_eq!(refreshed.display_name(), "Apollo II");
+    }
+
+    #[tokio::test]
+    async fn test_request_ids_round_trip_through_the_worker() {
+        let created = Uuid::new_v4();
+        let mock = MockApi::start(move |request| match request.method.as_str() {
+            "POST" => (201, format!("\"{}\"", created)),
+            _ => (400, r#"{"title":"Bad Request"}"#.to_string()),
+        })
+        .await;
+
+        let mut app = App::new();
+        let dto = models::CreateClientDto {
+            name: Some("Acme".to_string()),
+            ..Default::default()
+        };
+        let create = app.stamp(ApiCommand::CreateClient(dto));
+        let delete = app.stamp(ApiCommand::DeleteProject(Uuid::new_v4()));
+        assert_ne!(create.id, delete.id);
+
+        let responses = run_requests(&mock.url, worker_options(), vec![create.clone(), delete.clone()]).await;
+        let ids: Vec<Option<RequestId>> = responses.iter().map(|r| r.request).collect();
+        assert_eq!(ids, [Some(create.id), Some(delete.id)]);
+        assert!(matches!(responses[0].message, ApiMessage::Created(EntityType::Client, id) if id == created));
+        assert!(matches!(responses[1].message, ApiMessage::Failed(ApiOperation::Delete(..), _)));
+
+        // Each outcome lands in the log under its command's id
+        for response in responses {
+            app.handle_response(response);
+        }
+        let tagged = |id, level| app.logs.iter().any(|l| l.request == Some(id) && l.level == level);
+        assert!(tagged(create.id, app::LogLevel::Success));
+        assert!(tagged(delete.id, app::LogLevel::Error));
+    }
+
+    #[tokio::test]
+    async fn test_history_is_fetched_per_project() {
+        let id = Uuid::new_v4();
+        let body = r#"[{"timestamp": "2024-05-01T12:30:00Z", "user": "ada", "field": "name", "old": "A", "new": "B"}]"#;
+        let mock = MockApi::start(move |request| match request.path.ends_with("/history") {
+            true => (200, body.to_string()),
+            false => (500, String::new()),
+        })
+        .await;
+        let messages = run_worker(&mock.url, vec![ApiCommand::FetchHistory(id)]).await;
+        let [ApiMessage::HistoryLoaded(loaded, records)] = messages.as_slice() else {
+            panic!("unexpected replies: {:?}", messages)
+        };
+        assert_eq!((*loaded, records.len()), (id, 1));
+        assert_eq!(mock.requests()[0].path, format!("/projects/{}/history", id));
+
+        // A server without the endpoint answers 404
+        let mock = MockApi::start(|_| (404, r#"{"title":"Not Found"}"#.to_string())).await;
+        let messages = run_worker(&mock.url, vec![ApiCommand::FetchHistory(id)]).await;
+        let [ApiMessage::Failed(ApiOperation::History(failed), error)] = messages.as_slice() else {
+            panic!("unexpected replies: {:?}", messages)
+        };
+        assert_eq!((*failed, error.status()), (id, Some(404)));
+    }
+
+    #[tokio::test]
+    async fn test_splash_is_drawn_before_a_slow_api_answers() {
+        let mock = MockApi::start_slow(Duration::from_millis(300), |_| (200, mock_api::page::<()>(&[]))).await;
+        let screen = |terminal: &Terminal<ratatui::backend::TestBackend>| -> String {
+            terminal.backend().buffer().content().iter().map(|cell| cell.symbol()).collect()
+        };
+
+        // The splash goes up before the worker even exists
+        let mut terminal = Terminal::new(ratatui::backend::TestBackend::new(100, 30)).unwrap();
+        let splash = ui::Splash::new(mock.url.as_str());
+        terminal.draw(|frame| ui::render_splash(frame, &splash)).unwrap();
+        assert!(screen(&terminal).contains(&format!("connecting to {}…", mock.url)));
+
+        let (tx, mut rx) = mpsc::channel(100);
+        let (cmd_tx, mut cmd_rx) = mpsc::channel(100);
+        let client = ApiClient::new(&mock.url).unwrap();
+        let (_stop, stop_rx) = watch::channel(false);
+        let worker =
+            tokio::spawn(async move { run_api_worker(client, tx, &mut cmd_rx, worker_options(), stop_rx).await });
+        let mut app = App::new();
+        app.splash = Some(splash);
+        assert!(app.needs_frames(), "the spinner keeps turning");
+        cmd_tx.send(app.stamp(ApiCommand::RefreshAll)).await.unwrap();
+
+        // Frames drawn while the server is still thinking keep showing it
+        terminal.draw(|frame| ui::render(frame, &app)).unwrap();
+        assert!(screen(&terminal).contains("connecting to"));
+        assert!(rx.try_recv().is_err());
+
+        let response = tokio::time::timeout(Duration::from_secs(5), rx.recv()).await.unwrap().unwrap();
+        assert!(matches!(response.message, ApiMessage::ConnectionStatus(_)));
+        app.handle_response(response);
+        assert!(app.splash.is_none());
+        terminal.draw(|frame| ui::render(frame, &app)).unwrap();
+        let text = screen(&terminal);
+        assert!(!text.contains("connecting to") && text.contains("System Log"));
+
+        cmd_tx.send(app.stamp(ApiCommand::Shutdown)).await.unwrap();
+        worker.await.unwrap();
+    }
+}
@@ -2,17 +2,23 @@
 //!
 //! This module implements a lightweight particle system that creates
 //! a "Digital Rain" or "Starfield" effect in the background of the TUI.
+//! All randomness flows through a PRNG owned by the system, so a seeded
+//! system renders identical frames (useful for snapshot tests and `--seed`).
 
-use rand::Rng;
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
     style::{Color, Style},
     widgets::Widget,
 };
+use serde::{Deserialize, Serialize};
+
+use crate::theme::PROJECT_COLORS;
 
 /// Types of background animations
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub enum ParticleMode {
     /// Matrix-style digital rain effect
     #[default]
@@ -64,56 +70,52 @@ pub struct Particle {
 
 impl Particle {
     /// Create a new digital rain particle
-    pub fn new_rain(x: u16, _max_y: u16) -> Self {
-        let mut rng = rand::thread_rng();
+    pub fn new_rain<R: Rng>(rng: &mut R, x: u16, _max_y: u16) -> Self {
         Self {
             x: x as f32,
             y: 0.0,
             vy: rng.gen_range(0.3..1.5),
             vx: 0.0,
-            char: Self::random_rain_char(),
+            char: Self::random_rain_char(rng),
             brightness: 1.0,
             fade_rate: rng.gen_range(0.01..0.05),
         }
     }
 
     /// Create a new starfield particle
-    pub fn new_star(width: u16, height: u16) -> Self {
-        let mut rng = rand::thread_rng();
+    pub fn new_star<R: Rng>(rng: &mut R, width: u16, height: u16) -> Self {
         Self {
             x: rng.gen_range(0.0..width as f32),
             y: rng.gen_range(0.0..height as f32),
             vy: 0.0,
             vx: rng.gen_range(0.1..0.8),
-            char: Self::random_star_char(),
+            char: Self::random_star_char(rng),
             brightness: rng.gen_range(0.3..1.0),
             fade_rate: rng.gen_range(0.005..0.02),
         }
     }
 
     /// Get a random character for digital rain
-    fn random_rain_char() -> char {
-        let mut rng = rand::thread_rng();
+    fn random_rain_char<R: Rng>(rng: &mut R) -> char {
         let chars: Vec<char> = "01アイウエオカキクケコサシスセソタチツテト".chars().collect();
         chars[rng.gen_range(0..chars.len())]
     }
 
     /// Get a random character for starfield
-    fn random_star_char() -> char {
-        let mut rng = rand::thread_rng();
+    fn random_star_char<R: Rng>(rng: &mut R) -> char {
         let chars = ['·', '•', '∙', '○', '◦', '*', '+', '×'];
         chars[rng.gen_range(0..chars.len())]
     }
 
     /// Update particle position and state
-    pub fn update(&mut self) {
+    pub fn update<R: Rng>(&mut self, rng: &mut R) {
         self.y += self.vy;
         self.x += self.vx;
         self.brightness -= self.fade_rate;
 
         // Occasionally change the character (for rain effect)
-        if rand::thread_rng().gen_ratio(1, 10) {
-            self.char = Self::random_rain_char();
+        if rng.gen_ratio(1, 10) {
+            self.char = Self::random_rain_char(rng);
         }
     }
 
@@ -138,6 +140,9 @@ impl Particle {
     }
 }
 
+/// Default particle budget for the background animation
+pub const DEFAULT_MAX_PARTICLES: usize = 100;
+
 /// The particle system managing all particles
 #[derive(Debug, Clone)]
 pub struct ParticleSystem {
@@ -149,22 +154,34 @@ pub struct ParticleSystem {
     max_particles: usize,
     /// Frame counter for spawn timing
     frame_count: u64,
+    /// Random source for spawning and flicker
+    rng: SmallRng,
 }
 
 impl Default for ParticleSystem {
     fn default() -> Self {
-        Self::new(ParticleMode::DigitalRain, 100)
+        Self::new(ParticleMode::DigitalRain, DEFAULT_MAX_PARTICLES)
     }
 }
 
 impl ParticleSystem {
-    /// Create a new particle system
+    /// Create a new particle system seeded from entropy
     pub fn new(mode: ParticleMode, max_particles: usize) -> Self {
+        Self::with_rng(mode, max_particles, SmallRng::from_entropy())
+    }
+
+    /// Create a deterministic particle system from a fixed seed
+    pub fn with_seed(mode: ParticleMode, max_particles: usize, seed: u64) -> Self {
+        Self::with_rng(mode, max_particles, SmallRng::seed_from_u64(seed))
+    }
+
+    fn with_rng(mode: ParticleMode, max_particles: usize, rng: SmallRng) -> Self {
         Self {
             particles: Vec::with_capacity(max_particles),
             mode,
             max_particles,
             frame_count: 0,
+            rng,
         }
     }
 
@@ -176,6 +193,11 @@ impl ParticleSystem {
         }
     }
 
+    /// Particles currently alive
+    pub fn count(&self) -> usize {
+        self.particles.len()
+    }
+
     /// Get current mode
     pub fn mode(&self) -> ParticleMode {
         self.mode
@@ -196,7 +218,7 @@ impl ParticleSystem {
 
         // Update existing particles
         for particle in &mut self.particles {
-            particle.update();
+            particle.update(&mut self.rng);
         }
 
         // Remove dead particles
@@ -209,31 +231,34 @@ impl ParticleSystem {
 
     /// Spawn new particles based on mode
     fn spawn_particles(&mut self, width: u16, height: u16) {
-        let mut rng = rand::thread_rng();
+        if width == 0 || height == 0 {
+            return;
+        }
+        let rng = &mut self.rng;
 
         match self.mode {
             ParticleMode::DigitalRain => {
                 // Spawn a few new rain drops each frame
-                if self.frame_count % 3 == 0 && self.particles.len() < self.max_particles {
+                if self.frame_count.is_multiple_of(3) && self.particles.len() < self.max_particles {
                     let num_new = rng.gen_range(1..=3).min(self.max_particles - self.particles.len());
                     for _ in 0..num_new {
                         let x = rng.gen_range(0..width);
-                        self.particles.push(Particle::new_rain(x, height));
+                        self.particles.push(Particle::new_rain(rng, x, height));
                     }
                 }
             }
             ParticleMode::Starfield => {
                 // Maintain a steady number of stars
                 while self.particles.len() < self.max_particles / 2 {
-                    self.particles.push(Particle::new_star(width, height));
+                    self.particles.push(Particle::new_star(rng, width, height));
                 }
             }
             ParticleMode::None => {}
         }
     }
 
-    /// Render the particle system
-    pub fn render(&self, area: Rect, buf: &mut Buffer) {
+    /// Render the particle system, leaving the cells inside any of `exclude` untouched
+    pub fn render(&self, area: Rect, buf: &mut Buffer, exclude: &[Rect]) {
         if self.mode == ParticleMode::None {
             return;
         }
@@ -244,6 +269,9 @@ impl ParticleSystem {
 
             if x < area.width && y < area.height {
                 let pos = (area.x + x, area.y + y);
+                if exclude.iter().any(|r| r.contains(pos.into())) {
+                    continue;
+                }
                 let color = particle.get_color(self.mode);
                 buf[pos].set_char(particle.char);
                 buf[pos].set_style(Style::default().fg(color));
@@ -252,19 +280,148 @@ impl ParticleSystem {
     }
 }
 
+/// Frames a completion celebration lasts (about 1.5 seconds at 30 FPS)
+pub const CELEBRATION_FRAMES: u64 = 45;
+
+/// Particles in one confetti burst
+const CONFETTI_COUNT: usize = 12;
+
+/// Downward pull per frame, in radar canvas units
+const CONFETTI_GRAVITY: f64 = 0.3;
+
+/// One piece of confetti, in radar canvas coordinates (y grows upwards)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Confetti {
+    pub x: f64,
+    pub y: f64,
+    vx: f64,
+    vy: f64,
+    pub color: Color,
+}
+
+/// Confetti burst over the radar blip of a project that was just completed
+#[derive(Debug, Clone, PartialEq)]
+pub struct CelebrationState {
+    /// Frame on which the burst started
+    pub started_at: u64,
+    /// Blip position the confetti flies out of
+    pub origin: (f64, f64),
+    pub particles: Vec<Confetti>,
+}
+
+impl CelebrationState {
+    /// Fan the confetti out upwards from `origin`. The spread is fixed rather
+    /// than random so the burst looks the same in snapshots.
+    pub fn new(origin: (f64, f64), frame: u64) -> Self {
+        let particles = (0..CONFETTI_COUNT)
+            .map(|i| {
+                let angle = (20.0 + 140.0 * i as f64 / (CONFETTI_COUNT - 1) as f64).to_radians();
+                let speed = 2.5 + (i % 3) as f64 * 0.7;
+                Confetti {
+                    x: origin.0,
+                    y: origin.1,
+                    vx: speed * angle.cos(),
+                    vy: speed * angle.sin(),
+                    color: PROJECT_COLORS[i % PROJECT_COLORS.len()],
+                }
+            })
+            .collect();
+        Self {
+            started_at: frame,
+            origin,
+            particles,
+        }
+    }
+
+    /// Advance every particle one frame along its arc
+    pub fn update(&mut self) {
+        for p in &mut self.particles {
+            p.x += p.vx;
+            p.y += p.vy;
+            p.vx *= 0.95;
+            p.vy -= CONFETTI_GRAVITY;
+        }
+    }
+
+    /// Whether the burst is in its last third and should be drawn dimmed
+    pub fn fading(&self, frame: u64) -> bool {
+        frame.wrapping_sub(self.started_at) * 3 >= CELEBRATION_FRAMES * 2
+    }
+
+    pub fn is_finished(&self, frame: u64) -> bool {
+        frame.wrapping_sub(self.started_at) >= CELEBRATION_FRAMES
+    }
+}
+
 /// Widget wrapper for the particle system
 pub struct ParticleWidget<'a> {
     system: &'a ParticleSystem,
+    exclude: Vec<Rect>,
 }
 
 impl<'a> ParticleWidget<'a> {
     pub fn new(system: &'a ParticleSystem) -> Self {
-        Self { system }
+        Self { system, exclude: Vec::new() }
+    }
+
+    /// Areas to keep clear, such as open modals
+    pub fn exclude(mut self, areas: Vec<Rect>) -> Self {
+        self.exclude = areas;
+        self
     }
 }
 
 impl Widget for ParticleWidget<'_> {
     fn render(self, area: Rect, buf: &mut Buffer) {
-        self.system.render(area, buf);
+        self.system.render(area, buf, &self.exclude);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render_frames(system: &mut ParticleSystem, frames: usize) -> Buffer {
+        let area = Rect::new(0, 0, 60, 20);
+        for _ in 0..frames {
+            system.update(area.width, area.height);
+        }
+        let mut buf = Buffer::empty(area);
+        system.render(area, &mut buf, &[]);
+        buf
+    }
+
+    #[test]
+    fn test_same_seed_renders_identical_frames() {
+        for mode in [ParticleMode::DigitalRain, ParticleMode::Starfield] {
+            let mut a = ParticleSystem::with_seed(mode, DEFAULT_MAX_PARTICLES, 42);
+            let mut b = ParticleSystem::with_seed(mode, DEFAULT_MAX_PARTICLES, 42);
+            assert_eq!(render_frames(&mut a, 30), render_frames(&mut b, 30));
+        }
+    }
+
+    #[test]
+    fn test_different_seeds_diverge() {
+        let mut a = ParticleSystem::with_seed(ParticleMode::Starfield, DEFAULT_MAX_PARTICLES, 1);
+        let mut b = ParticleSystem::with_seed(ParticleMode::Starfield, DEFAULT_MAX_PARTICLES, 2);
+        assert_ne!(render_frames(&mut a, 5), render_frames(&mut b, 5));
+    }
+
+    #[test]
+    fn test_celebration_arcs_and_finishes() {
+        let mut burst = CelebrationState::new((10.0, -20.0), 100);
+        assert_eq!(burst.particles.len(), CONFETTI_COUNT);
+
+        for _ in 0..2 {
+            burst.update();
+        }
+        assert!(burst.particles.iter().all(|p| p.y > -20.0), "confetti rises first");
+        for _ in 2..CELEBRATION_FRAMES {
+            burst.update();
+        }
+        assert!(burst.particles.iter().all(|p| p.y < -20.0), "and falls back below the blip");
+
+        assert!(!burst.fading(110) && burst.fading(135));
+        assert!(!burst.is_finished(144) && burst.is_finished(145));
     }
 }
@@ -0,0 +1,76 @@
+//! Generated passwords for the user form (Ctrl+G).
+//!
+//! A password has at least one char of every class and none of the chars
+//! that are easily misread when it is read out or copied by hand (0/O,
+//! 1/l/I, quotes and the like).
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+/// Length used unless `password_length` says otherwise
+pub const DEFAULT_LENGTH: usize = 16;
+
+/// Shortest `password_length` the config accepts
+pub const MIN_LENGTH: usize = 8;
+
+/// Lowercase, uppercase, digits and symbols, the ambiguous ones left out
+const CLASSES: [&str; 4] = ["abcdefghijkmnpqrstuvwxyz", "ABCDEFGHJKLMNPQRSTUVWXYZ", "23456789", "!#$%&*+-=?@^_"];
+
+/// A random char of `class`
+fn pick(class: &str, rng: &mut impl Rng) -> char {
+    let chars: Vec<char> = class.chars().collect();
+    *chars.choose(rng).expect("password classes are not empty")
+}
+
+/// A password of `length` chars (at least one per class) with every class
+/// in it
+pub fn generate(length: usize, rng: &mut impl Rng) -> String {
+    let all = CLASSES.concat();
+    let mut chars: Vec<char> = CLASSES.iter().map(|class| pick(class, rng)).collect();
+    while chars.len() < length {
+        chars.push(pick(&all, rng));
+    }
+    // The class picks would otherwise always lead
+    chars.shuffle(rng);
+    chars.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::SmallRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_passwords_have_the_length_and_every_class() {
+        let mut rng = SmallRng::seed_from_u64(7);
+        for seed in 0..200 {
+            let password = generate(DEFAULT_LENGTH, &mut rng);
+            assert_eq!(password.chars().count(), DEFAULT_LENGTH, "seed {}", seed);
+            assert!(password.chars().any(|c| c.is_ascii_lowercase()), "{}", password);
+            assert!(password.chars().any(|c| c.is_ascii_uppercase()), "{}", password);
+            assert!(password.chars().any(|c| c.is_ascii_digit()), "{}", password);
+            assert!(password.chars().any(|c| c.is_ascii_punctuation()), "{}", password);
+        }
+        assert_eq!(generate(40, &mut rng).len(), 40);
+        // Too short for every class: the classes win
+        assert_eq!(generate(2, &mut rng).len(), CLASSES.len());
+    }
+
+    #[test]
+    fn test_passwords_leave_out_ambiguous_chars() {
+        let mut rng = SmallRng::seed_from_u64(1);
+        let text: String = (0..500).map(|_| generate(DEFAULT_LENGTH, &mut rng)).collect();
+        for c in "0O1lI|`'\" \\".chars() {
+            assert!(!text.contains(c), "{:?} generated", c);
+        }
+        // Everything else does turn up
+        assert!(CLASSES.concat().chars().all(|c| text.contains(c)));
+    }
+
+    #[test]
+    fn test_passwords_differ() {
+        let mut rng = SmallRng::seed_from_u64(3);
+        assert_ne!(generate(DEFAULT_LENGTH, &mut rng), generate(DEFAULT_LENGTH, &mut rng));
+    }
+}
@@ -0,0 +1,219 @@
+//! Saved project filters.
+//!
+//! A preset keeps the criteria of a `ProjectFilter` under a name; the archive
+//! and mine layers are not part of it. Presets live in `filter-presets.toml`
+//! next to the UI state and are written whenever the list changes, so they
+//! survive restarts. A client or manager that no longer exists shows as
+//! "(missing)" and its clause is left out when the preset is applied.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::filter::{FilterDeps, ProjectFilter};
+use crate::models::ProjectStatus;
+
+/// Shown for a client or manager a preset refers to that is gone
+pub const MISSING: &str = "(missing)";
+
+/// A named set of filter criteria
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FilterPreset {
+    pub name: String,
+    pub status: Option<ProjectStatus>,
+    pub client_id: Option<Uuid>,
+    pub manager_id: Option<Uuid>,
+    pub text: Option<String>,
+}
+
+impl FilterPreset {
+    /// The criteria of `filter` under `name`
+    pub fn new(name: impl Into<String>, filter: &ProjectFilter) -> Self {
+        Self {
+            name: name.into(),
+            status: filter.status,
+            client_id: filter.client_id,
+            manager_id: filter.manager_id,
+            text: filter.text.clone(),
+        }
+    }
+
+    /// The preset's criteria over `base`'s layers, and the clauses left out
+    /// because their client or manager is gone
+    pub fn apply(&self, base: &ProjectFilter, deps: &FilterDeps) -> (ProjectFilter, Vec<&'static str>) {
+        let mut skipped = Vec::new();
+        let client_id = self.client_id.filter(|id| {
+            let known = deps.clients.iter().any(|c| c.id == *id);
+            if !known {
+                skipped.push("client");
+            }
+            known
+        });
+        let manager_id = self.manager_id.filter(|id| {
+            let known = deps.users.iter().any(|u| u.id == *id);
+            if !known {
+                skipped.push("manager");
+            }
+            known
+        });
+        let filter = ProjectFilter {
+            status: self.status,
+            client_id,
+            manager_id,
+            text: self.text.clone(),
+            ..base.cleared()
+        };
+        (filter, skipped)
+    }
+
+    /// Short summary for the picker, e.g. "ACTIVE · Acme · \"apollo\""
+    pub fn describe(&self, deps: &FilterDeps) -> String {
+        let mut parts = Vec::new();
+        if let Some(status) = self.status {
+            parts.push(status.label().to_string());
+        }
+        if let Some(id) = self.client_id {
+            let client = deps.clients.iter().find(|c| c.id == id);
+            parts.push(client.map_or(format!("client {}", MISSING), |c| c.display_name().to_string()));
+        }
+        if let Some(id) = self.manager_id {
+            let manager = deps.users.iter().find(|u| u.id == id);
+            parts.push(manager.map_or(format!("manager {}", MISSING), |u| u.display_name().to_string()));
+        }
+        if let Some(text) = self.text.as_deref().filter(|t| !t.trim().is_empty()) {
+            parts.push(format!("\"{}\"", text.trim()));
+        }
+        if parts.is_empty() {
+            return "everything".to_string();
+        }
+        parts.join(" · ")
+    }
+}
+
+/// Every saved preset, in the order they were saved
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Presets {
+    #[serde(default, rename = "preset")]
+    pub presets: Vec<FilterPreset>,
+}
+
+impl Presets {
+    /// Location of the presets file, beside the UI state
+    pub fn path() -> Option<PathBuf> {
+        dirs::state_dir()
+            .or_else(dirs::data_local_dir)
+            .map(|dir| dir.join("sweem-tui").join("filter-presets.toml"))
+    }
+
+    /// Load presets from `path`; a missing file means none yet
+    pub fn load_from(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        Self::parse(&text).with_context(|| format!("Invalid filter presets in {}", path.display()))
+    }
+
+    pub fn parse(text: &str) -> Result<Self> {
+        Ok(toml::from_str(text)?)
+    }
+
+    /// Write presets to `path`, creating its directory if needed
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+        let text = toml::to_string(self).context("Failed to serialize filter presets")?;
+        std::fs::write(path, text).with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Add `preset`, replacing one with the same name (ignoring case)
+    pub fn save(&mut self, preset: FilterPreset) {
+        match self.presets.iter_mut().find(|p| p.name.eq_ignore_ascii_case(&preset.name)) {
+            Some(existing) => *existing = preset,
+            None => self.presets.push(preset),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.presets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.presets.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{client, user, ClientFixture, UserFixture};
+
+    fn id(n: u128) -> Uuid {
+        Uuid::from_u128(n)
+    }
+
+    fn acme_active() -> FilterPreset {
+        FilterPreset {
+            name: "Acme this quarter".to_string(),
+            status: Some(ProjectStatus::Active),
+            client_id: Some(id(1)),
+            manager_id: Some(id(10)),
+            text: Some("q3 \"launch\"".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_file_round_trip() {
+        let presets = Presets {
+            presets: vec![acme_active(), FilterPreset::new("everything", &ProjectFilter::default())],
+        };
+        let text = toml::to_string(&presets).unwrap();
+        assert!(text.contains("[[preset]]"));
+        assert!(text.contains("status = \"active\""));
+        assert_eq!(Presets::parse(&text).unwrap(), presets);
+
+        let path = std::env::temp_dir()
+            .join(format!("sweem-presets-{}", Uuid::new_v4()))
+            .join("filter-presets.toml");
+        assert_eq!(Presets::load_from(&path).unwrap(), Presets::default());
+        presets.save_to(&path).unwrap();
+        let loaded = Presets::load_from(&path);
+        std::fs::remove_dir_all(path.parent().unwrap()).ok();
+        assert_eq!(loaded.unwrap(), presets);
+
+        assert_eq!(Presets::parse("").unwrap(), Presets::default());
+        assert!(Presets::parse("[[preset]]\nname = 3").is_err());
+    }
+
+    #[test]
+    fn test_save_replaces_by_name() {
+        let mut presets = Presets::default();
+        presets.save(acme_active());
+        presets.save(FilterPreset { name: "ACME THIS QUARTER".to_string(), text: None, ..acme_active() });
+        assert_eq!(presets.len(), 1);
+        assert_eq!(presets.presets[0].text, None);
+    }
+
+    #[test]
+    fn test_missing_references_are_skipped() {
+        let clients = vec![client(1).named("Acme")];
+        let users = vec![user(11).named("Lee")];
+        let deps = FilterDeps { clients: &clients, users: &users, today: "2024-05-10".parse().unwrap() };
+        let preset = acme_active();
+        assert_eq!(preset.describe(&deps), "ACTIVE · Acme · manager (missing) · \"q3 \"launch\"\"");
+
+        let base = ProjectFilter { archive_after_days: Some(90), text: Some("old".to_string()), ..Default::default() };
+        let (filter, skipped) = preset.apply(&base, &deps);
+        assert_eq!(skipped, ["manager"]);
+        assert_eq!(filter.client_id, Some(id(1)));
+        assert_eq!(filter.manager_id, None);
+        assert_eq!(filter.text, preset.text);
+        assert_eq!(filter.archive_after_days, Some(90));
+        assert_eq!(FilterPreset::new("x", &filter).client_id, Some(id(1)));
+    }
+}
@@ -3,15 +3,53 @@
 //! Visualizes projects in polar coordinates.
 //! Improvements: Client Labels, Distance Rings, Distinct Markers.
 
+use std::collections::HashSet;
 use std::f64::consts::PI;
-use chrono::{Local, NaiveDate, Datelike};
+use std::time::{Duration, Instant};
+use chrono::{Local, NaiveDate};
 use ratatui::{
-    buffer::Buffer, layout::Rect, style::{Color, Modifier, Style}, symbols::Marker, text::Span, widgets::{Widget, canvas::{Canvas, Circle, Context, Line}}
+    buffer::Buffer, layout::Rect, style::{Modifier, Style}, symbols::Marker, text::Span, widgets::{Widget, canvas::{Canvas, Circle, Context, Line, Points}}
 };
 use uuid::Uuid;
 
-use crate::{models::{ClientDto, ProjectDto}, theme::styles}; // Добавили ClientDto
-use crate::theme::{colors, get_project_color};
+use crate::{models::{ClientDto, ProjectDto, ProjectStatus}, theme::styles}; // Добавили ClientDto
+use crate::diff::Badge;
+use crate::particles::CelebrationState;
+use crate::theme::{colors, palette_color, palette_color_dim, status_color};
+
+/// Range shown before any projects are loaded, in days
+pub const DEFAULT_RANGE_DAYS: f64 = 90.0;
+
+/// Zooming and fitting stay within these ranges, in days; only
+/// `expand_range` goes further out
+pub const MIN_RANGE_DAYS: f64 = 14.0;
+pub const MAX_RANGE_DAYS: f64 = 365.0;
+
+/// Where a jumped-to day sits between NOW and the rim
+pub const JUMP_FRACTION: f64 = 1.0 / 3.0;
+
+/// Key repeats closer together than this count as the key being held
+pub const REPEAT_GAP: Duration = Duration::from_millis(80);
+
+/// How long a key is held before each step up in `accelerated_step`
+pub const ACCELERATION_STAGE: Duration = Duration::from_millis(500);
+
+/// Days moved per key repeat after a key has been held for `held`:
+/// 1, then 3, 7 and 14 as each `ACCELERATION_STAGE` passes
+pub fn accelerated_step(held: Duration) -> i64 {
+    const STEPS: [i64; 4] = [1, 3, 7, 14];
+    let stage = (held.as_millis() / ACCELERATION_STAGE.as_millis()) as usize;
+    STEPS[stage.min(STEPS.len() - 1)]
+}
+
+/// A day-stepping key being held down
+#[derive(Debug, Clone, Copy)]
+pub struct HeldKey {
+    /// +1 or -1
+    pub direction: i64,
+    pub since: Instant,
+    pub last: Instant,
+}
 
 /// Radar State
 #[derive(Debug, Clone)]
@@ -19,6 +57,54 @@ pub struct RadarState {
     pub scan_angle: f64,
     pub selected_index: Option<usize>,
     pub range_days: f64,
+    /// Whether the range was widened to every deadline with `expand_range`
+    pub expanded: bool,
+    /// Date shift being previewed on the selected project, not yet saved
+    pub pending_edit: Option<PendingDateEdit>,
+    /// The day-step key being held, for repeat acceleration
+    pub held: Option<HeldKey>,
+}
+
+/// Unsaved new dates for one project, adjusted a day or a week at a time
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PendingDateEdit {
+    pub project_id: Uuid,
+    pub start_date: NaiveDate,
+    pub planned_end_date: NaiveDate,
+}
+
+impl PendingDateEdit {
+    /// Start from the project's saved dates
+    pub fn new(project: &ProjectDto) -> Self {
+        Self {
+            project_id: project.id,
+            start_date: project.start_date,
+            planned_end_date: project.planned_end_date,
+        }
+    }
+
+    /// Move the deadline, or with `whole` the start too, by `days`. A
+    /// deadline before the start is refused and leaves the dates unchanged.
+    pub fn shift(&mut self, days: i64, whole: bool) -> bool {
+        let delta = chrono::Duration::days(days);
+        let end = self.planned_end_date + delta;
+        let start = if whole { self.start_date + delta } else { self.start_date };
+        if end < start {
+            return false;
+        }
+        self.start_date = start;
+        self.planned_end_date = end;
+        true
+    }
+
+    /// The project as it would be saved
+    pub fn apply(&self, project: &ProjectDto) -> ProjectDto {
+        ProjectDto {
+            start_date: self.start_date,
+            planned_end_date: self.planned_end_date,
+            ..project.clone()
+        }
+    }
 }
 
 impl Default for RadarState {
@@ -26,12 +112,27 @@ impl Default for RadarState {
         Self {
             scan_angle: 0.0,
             selected_index: None,
-            range_days: 90.0, 
+            range_days: DEFAULT_RANGE_DAYS,
+            expanded: false,
+            pending_edit: None,
+            held: None,
         }
     }
 }
 
 impl RadarState {
+    /// Days to move for a day-step key pressed at `now` towards `direction`.
+    /// Repeats arriving within `REPEAT_GAP` of each other speed the step up;
+    /// a longer gap or a change of direction starts over from one day.
+    pub fn repeat_step(&mut self, direction: i64, now: Instant) -> i64 {
+        let since = match self.held {
+            Some(held) if held.direction == direction && now.duration_since(held.last) <= REPEAT_GAP => held.since,
+            _ => now,
+        };
+        self.held = Some(HeldKey { direction, since, last: now });
+        direction * accelerated_step(now.duration_since(since))
+    }
+
     pub fn tick(&mut self) {
         self.scan_angle += 0.05;
         if self.scan_angle > 2.0 * PI {
@@ -39,33 +140,65 @@ impl RadarState {
         }
     }
 
-    pub fn select_next(&mut self, total: usize) {
-        if total == 0 { return; }
-        self.selected_index = Some(match self.selected_index {
-            Some(i) => (i + 1) % total,
-            None => 0,
-        });
+    /// Select the next of the `visible` project indices, wrapping around
+    pub fn select_next(&mut self, visible: &[usize]) {
+        self.step(visible, 1);
+    }
+
+    /// Select the previous of the `visible` project indices, wrapping around
+    pub fn select_prev(&mut self, visible: &[usize]) {
+        self.step(visible, visible.len().saturating_sub(1));
     }
 
-    pub fn select_prev(&mut self, total: usize) {
-        if total == 0 { return; }
-        self.selected_index = Some(match self.selected_index {
-            Some(i) => (i + total - 1) % total,
-            None => 0,
+    fn step(&mut self, visible: &[usize], offset: usize) {
+        if visible.is_empty() { return; }
+        let position = self.selected_index.and_then(|i| visible.iter().position(|v| *v == i));
+        self.selected_index = Some(match position {
+            Some(p) => visible[(p + offset) % visible.len()],
+            None => visible[0],
         });
     }
     
     pub fn zoom_in(&mut self) {
-        if self.range_days > 14.0 { self.range_days -= 7.0; }
+        if self.range_days > MIN_RANGE_DAYS { self.range_days -= 7.0; }
+        self.expanded = false;
     }
     
     pub fn zoom_out(&mut self) {
-        if self.range_days < 365.0 { self.range_days += 7.0; }
+        if self.range_days < MAX_RANGE_DAYS { self.range_days += 7.0; }
+        self.expanded = false;
+    }
+
+    /// Zoom so every open project's deadline fits inside the rim. Finished,
+    /// unscheduled and overdue projects don't count: they sit in the centre
+    /// whatever the range, so a legacy project can't blow the zoom out.
+    pub fn fit_range<'a>(&mut self, projects: impl IntoIterator<Item = &'a ProjectDto>) {
+        let open = projects.into_iter().filter(|p| !p.is_completed());
+        self.range_days = range_for(open)
+            .map_or(DEFAULT_RANGE_DAYS, |days| days.clamp(MIN_RANGE_DAYS, MAX_RANGE_DAYS));
+        self.expanded = false;
+    }
+
+    /// Zoom out until every scheduled deadline is inside the rim, past `MAX_RANGE_DAYS` if need be
+    pub fn expand_range<'a>(&mut self, projects: impl IntoIterator<Item = &'a ProjectDto>) {
+        self.range_days = range_for(projects.into_iter()).unwrap_or(DEFAULT_RANGE_DAYS).max(MIN_RANGE_DAYS);
+        self.expanded = true;
+    }
+
+    /// Zoom so the day `days_left` ahead sits a third of the way from NOW to
+    /// the rim, with what comes after it in view too
+    pub fn show_day(&mut self, days_left: i64) {
+        self.range_days = (days_left as f64 / JUMP_FRACTION).max(MIN_RANGE_DAYS);
+        self.expanded = false;
     }
 
-    pub fn center_on_today(&mut self, _projects: &[ProjectDto], _width: u16) {
-        self.range_days = 90.0;
-        self.scan_angle = 0.0;
+    /// Zoom out just enough for `project`'s deadline to sit inside the rim
+    pub fn reveal(&mut self, project: &ProjectDto) {
+        if let Some(days) = range_for(std::iter::once(project)) {
+            if days > self.range_days {
+                self.range_days = days;
+            }
+        }
     }
 
     pub fn jump_to_project(&mut self, project: &ProjectDto, projects: &[ProjectDto], _width: u16) {
@@ -75,55 +208,226 @@ impl RadarState {
     }
 }
 
+/// Time in state under the selected blip: "LATE 12d", "ACTIVE today", "PLANNED in 3d"
+pub fn state_age_short(status: ProjectStatus, days: i64) -> String {
+    match (status, days) {
+        (ProjectStatus::Pending, 0) => format!("{} today", status.label()),
+        (ProjectStatus::Pending, d) => format!("{} in {}d", status.label(), d),
+        (_, 0) => format!("{} today", status.label()),
+        (_, d) => format!("{} {}d", status.label(), d),
+    }
+}
+
+/// Smallest whole-week range that holds the farthest upcoming deadline
+fn range_for<'a>(projects: impl Iterator<Item = &'a ProjectDto>) -> Option<f64> {
+    let today = Local::now().date_naive();
+    projects
+        .filter(|p| p.has_valid_dates())
+        .map(|p| (p.planned_end_date - today).num_days())
+        .filter(|days| *days > 0)
+        .max()
+        // One extra week keeps the farthest blip off the rim
+        .map(|days| ((days / 7 + 2) * 7) as f64)
+}
+
+/// Index of the active project with the nearest deadline, the natural place to start
+pub fn earliest_active(projects: &[ProjectDto]) -> Option<usize> {
+    let today = Local::now().date_naive();
+    projects
+        .iter()
+        .enumerate()
+        .filter(|(_, p)| p.status(today) == ProjectStatus::Active)
+        .min_by_key(|(_, p)| p.planned_end_date)
+        .map(|(i, _)| i)
+}
+
+/// Where a project's blip sits on the radar canvas (both axes span -100..100)
+pub fn blip_position(project: &ProjectDto, range_days: f64) -> (f64, f64) {
+    let (r, theta) = project_polar(project, range_days, Local::now().date_naive());
+    (r * theta.cos(), r * theta.sin())
+}
+
+/// Canvas point under the middle of cell (`column`, `row`) of a radar whose
+/// canvas fills `inner` (the area inside its border); `None` outside it
+pub fn canvas_point(inner: Rect, column: u16, row: u16) -> Option<(f64, f64)> {
+    if inner.is_empty() || !inner.contains(ratatui::layout::Position::new(column, row)) {
+        return None;
+    }
+    let x = -100.0 + (f64::from(column - inner.x) + 0.5) / f64::from(inner.width) * 200.0;
+    let y = 100.0 - (f64::from(row - inner.y) + 0.5) / f64::from(inner.height) * 200.0;
+    Some((x, y))
+}
+
+/// Index of the shown project whose blip is nearest `point`, if one is
+/// within `reach` canvas units; blips past the rim aren't drawn and can't
+/// be hit
+pub fn blip_at(
+    projects: &[ProjectDto],
+    visible: &[usize],
+    range_days: f64,
+    today: NaiveDate,
+    point: (f64, f64),
+    reach: f64,
+) -> Option<usize> {
+    visible
+        .iter()
+        .filter_map(|&i| {
+            let (r, theta) = project_polar(&projects[i], range_days, today);
+            let distance = (r * theta.cos() - point.0).hypot(r * theta.sin() - point.1);
+            (r <= 100.0 && distance <= reach).then_some((i, distance))
+        })
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(i, _)| i)
+}
+
+/// Radius of the ring `days_left` days ahead: overdue in the danger zone,
+/// the future from the NOW ring (20) out to the rim (95)
+pub fn day_radius(days_left: f64, range_days: f64) -> f64 {
+    if days_left < 0.0 {
+        // Overdue: kept together in the danger zone
+        10.0
+    } else {
+        let pct = (days_left / range_days).clamp(0.0, 1.0);
+        20.0 + (pct * 75.0)
+    }
+}
+
+/// Radius and angle of a project: distance to its deadline, sector by client
+fn project_polar(project: &ProjectDto, range_days: f64, today: NaiveDate) -> (f64, f64) {
+    // Для радара используем planned_end_date, чтобы видеть дедлайн
+    let target_date = project.planned_end_date;
+    
+    // Fix for "Year 1" bug
+    if !project.has_valid_dates() {
+         // Если дата сломана, кидаем в центр как "ошибку" или "просрочку"
+         return (5.0, client_angle(project.client_id));
+    }
+
+    let days_left = (target_date - today).num_days() as f64;
+    let r = day_radius(days_left, range_days);
+    let angle = client_angle(project.client_id);
+    (r, angle)
+}
+
+fn client_angle(id: Uuid) -> f64 {
+    let bytes = id.as_bytes();
+    let mut sum: u32 = 0;
+    // Simple hash to spread clients around the circle
+    for (i, b) in bytes.iter().enumerate() { 
+        sum = sum.wrapping_add((*b as u32).wrapping_mul(i as u32 + 1)); 
+    }
+    (sum as f64 % 360.0).to_radians()
+}
+
 pub struct RadarWidget<'a> {
     projects: &'a [ProjectDto],
     clients: &'a [ClientDto], // Добавили ссылку на клиентов для отображения имен
     state: &'a RadarState,
+    /// Confetti burst and the current frame, when a project was just completed
+    celebration: Option<(&'a CelebrationState, u64)>,
+    /// Block title, e.g. with a filter breadcrumb
+    title: String,
+    /// Sorted indices of the projects to draw; None draws them all
+    visible: Option<&'a [usize]>,
+    /// Day deadlines are measured from
+    today: NaiveDate,
+    /// Leave out the sweep line, for a still snapshot
+    still: bool,
+    /// Project drawn with a highlight ring this frame
+    flashing: Option<Uuid>,
+    /// Projects tagged "new" or "changed" since the last reload
+    badges: &'a [(Uuid, Badge)],
+    /// Projects marked for a multi-select delete
+    marked: Option<&'a HashSet<Uuid>>,
+    /// Draw the border as the focused pane's
+    focused: bool,
+    /// Day drawn as a highlighted ring, after a jump to it
+    date_mark: Option<NaiveDate>,
 }
 
 impl<'a> RadarWidget<'a> {
     pub fn new(projects: &'a [ProjectDto], clients: &'a [ClientDto], state: &'a RadarState) -> Self {
-        Self { projects, clients, state }
+        Self {
+            projects,
+            clients,
+            state,
+            celebration: None,
+            title: " Orbital Command ".to_string(),
+            visible: None,
+            today: Local::now().date_naive(),
+            still: false,
+            flashing: None,
+            badges: &[],
+            marked: None,
+            focused: false,
+            date_mark: None,
+        }
     }
 
-    fn get_project_coords(&self, project: &ProjectDto) -> (f64, f64) {
-        let today = Local::now().date_naive();
-        // Для радара используем planned_end_date, чтобы видеть дедлайн
-        let target_date = project.planned_end_date;
-        
-        // Fix for "Year 1" bug
-        if target_date.year() < 2000 {
-             // Если дата сломана, кидаем в центр как "ошибку" или "просрочку"
-             return (5.0, self.client_hash_to_angle(project.client_id));
-        }
+    /// Draw a confetti burst above the blips
+    pub fn celebration(mut self, celebration: Option<&'a CelebrationState>, frame: u64) -> Self {
+        self.celebration = celebration.map(|c| (c, frame));
+        self
+    }
 
-        let days_left = (target_date - today).num_days() as f64;
-        
-        // Map radius:
-        // < 0 (Overdue) -> 0..15
-        // 0..Range -> 15..90
-        let r = if days_left < 0.0 {
-             // Overdue: Closer to 0 means MORE overdue, but let's keep them in the "danger zone" (0-15)
-             // Let's clamp to 5.0-15.0 range randomly or fixed
-             10.0
-        } else {
-             // Future: 
-             let pct = (days_left / self.state.range_days).clamp(0.0, 1.0);
-             20.0 + (pct * 75.0)
-        };
+    /// Replace the block title
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = title.into();
+        self
+    }
+
+    /// Draw only the projects at these (sorted) indices
+    pub fn visible(mut self, visible: &'a [usize]) -> Self {
+        self.visible = Some(visible);
+        self
+    }
+
+    /// Measure deadlines from `today` instead of the current date
+    pub fn today(mut self, today: NaiveDate) -> Self {
+        self.today = today;
+        self
+    }
+
+    /// Draw without the sweep line, so the output depends only on the data
+    pub fn still(mut self) -> Self {
+        self.still = true;
+        self
+    }
+
+    /// Accent the border while the radar has the keyboard
+    pub fn focused(mut self, focused: bool) -> Self {
+        self.focused = focused;
+        self
+    }
 
-        let angle = self.client_hash_to_angle(project.client_id);
-        (r, angle)
+    /// Ring the blip of this project, e.g. one that was just created
+    pub fn flashing(mut self, id: Option<Uuid>) -> Self {
+        self.flashing = id;
+        self
+    }
+
+    /// Tag these projects' blips with their badges
+    pub fn date_mark(mut self, date: Option<NaiveDate>) -> Self {
+        self.date_mark = date;
+        self
+    }
+
+    pub fn badges(mut self, badges: &'a [(Uuid, Badge)]) -> Self {
+        self.badges = badges;
+        self
+    }
+
+    pub fn marked(mut self, marked: &'a HashSet<Uuid>) -> Self {
+        self.marked = Some(marked);
+        self
+    }
+
+    fn get_project_coords(&self, project: &ProjectDto) -> (f64, f64) {
+        project_polar(project, self.state.range_days, self.today)
     }
 
     fn client_hash_to_angle(&self, id: Uuid) -> f64 {
-        let bytes = id.as_bytes();
-        let mut sum: u32 = 0;
-        // Simple hash to spread clients around the circle
-        for (i, b) in bytes.iter().enumerate() { 
-            sum = sum.wrapping_add((*b as u32).wrapping_mul(i as u32 + 1)); 
-        }
-        (sum as f64 % 360.0).to_radians()
+        client_angle(id)
     }
 
     fn draw_radar(&self, ctx: &mut Context) {
@@ -155,25 +459,37 @@ impl<'a> RadarWidget<'a> {
             let name = client.display_name();
             let short = if name.len() > 8 { &name[0..8] } else { name };
             
-            ctx.print(x, y, Span::styled(short.to_string(), Style::default().fg(colors::BLUE_LIGHT).add_modifier(Modifier::DIM)));
+            ctx.print(x, y, Span::styled(short.to_string(), Style::default().fg(palette_color(client.id))));
             
-            // Draw faint spoke line
+            // Draw faint spoke line in the client's colour
             ctx.draw(&Line { 
                 x1: 20.0 * angle.cos(), 
                 y1: 20.0 * angle.sin(), 
                 x2: 90.0 * angle.cos(), 
                 y2: 90.0 * angle.sin(), 
-                color: colors::BG_HIGHLIGHT 
+                color: palette_color_dim(client.id) 
             });
         }
 
+        // Jumped-to day
+        if let Some(date) = self.date_mark {
+            let radius = day_radius((date - self.today).num_days() as f64, self.state.range_days);
+            ctx.draw(&Circle { x: 0.0, y: 0.0, radius, color: colors::YELLOW });
+            let label = date.format(" %d %b ").to_string();
+            ctx.print(2.0, -radius - 2.0, Span::styled(label, Style::default().fg(colors::BG_DARK).bg(colors::YELLOW)));
+        }
+
         // --- 3. Scanline ---
-        let scan_x = self.state.scan_angle.cos() * 95.0;
-        let scan_y = self.state.scan_angle.sin() * 95.0;
-        ctx.draw(&Line { x1: 0.0, y1: 0.0, x2: scan_x, y2: scan_y, color: colors::GREEN_LIGHT });
+        if !self.still {
+            let scan_x = self.state.scan_angle.cos() * 95.0;
+            let scan_y = self.state.scan_angle.sin() * 95.0;
+            ctx.draw(&Line { x1: 0.0, y1: 0.0, x2: scan_x, y2: scan_y, color: colors::GREEN_LIGHT });
+        }
 
         // --- 4. Projects ---
+        let today = self.today;
         for (i, project) in self.projects.iter().enumerate() {
+            if self.visible.is_some_and(|v| v.binary_search(&i).is_err()) { continue; }
             let (r, theta) = self.get_project_coords(project);
             let x = r * theta.cos();
             let y = r * theta.sin();
@@ -182,25 +498,22 @@ impl<'a> RadarWidget<'a> {
 
             let is_selected = self.state.selected_index == Some(i);
             
-            let mut color = get_project_color(i);
-            if project.is_completed() { 
-                color = colors::GREEN; 
-            } else if project.is_overdue() { 
-                color = colors::RED; 
-            } else if project.is_pending() {
-                color = colors::FG_DIM; 
-            }
+            let status = project.status(today);
+            let mut color = match status {
+                ProjectStatus::Active => palette_color(project.id),
+                other => status_color(other),
+            };
             if is_selected { color = colors::FG_PRIMARY; }
 
             // Marker Shape Logic
-            if project.is_completed() {
+            if status == ProjectStatus::Completed {
                 // Square-ish (4 lines)
                 let sz = 2.0;
                 ctx.draw(&Line { x1: x-sz, y1: y-sz, x2: x+sz, y2: y-sz, color });
                 ctx.draw(&Line { x1: x+sz, y1: y-sz, x2: x+sz, y2: y+sz, color });
                 ctx.draw(&Line { x1: x+sz, y1: y+sz, x2: x-sz, y2: y+sz, color });
                 ctx.draw(&Line { x1: x-sz, y1: y+sz, x2: x-sz, y2: y-sz, color });
-            } else if project.is_overdue() {
+            } else if status == ProjectStatus::Overdue {
                 // Cross
                 let sz = 2.0;
                 ctx.draw(&Line { x1: x-sz, y1: y-sz, x2: x+sz, y2: y+sz, color });
@@ -227,6 +540,40 @@ impl<'a> RadarWidget<'a> {
                 if let Some(name) = project.name.clone() {
                     ctx.print(x + 5.0, y, Span::styled(name, Style::default().fg(colors::YELLOW).add_modifier(Modifier::BOLD)));
                 }
+                if let Some(days) = project.days_in_state(today) {
+                    ctx.print(x + 5.0, y - 5.0, Span::styled(state_age_short(status, days), styles::text_dim()));
+                }
+            }
+
+            if let Some((_, badge)) = self.badges.iter().find(|(id, _)| *id == project.id) {
+                ctx.print(x - 2.0, y - 6.0, Span::styled(badge.label(), badge.style()));
+            }
+
+            if self.marked.is_some_and(|m| m.contains(&project.id)) {
+                ctx.draw(&Circle { x, y, radius: 3.0, color: colors::MAGENTA });
+            }
+
+            if self.flashing == Some(project.id) {
+                ctx.draw(&Circle { x, y, radius: 7.0, color: colors::YELLOW });
+            }
+
+            // Unsaved date shift: a hollow ghost where the blip would move to
+            if let Some(edit) = self.state.pending_edit.filter(|e| e.project_id == project.id) {
+                let (pr, ptheta) = self.get_project_coords(&edit.apply(project));
+                let (px, py) = (pr * ptheta.cos(), pr * ptheta.sin());
+                ctx.draw(&Line { x1: x, y1: y, x2: px, y2: py, color: colors::FG_DIM });
+                ctx.draw(&Circle { x: px, y: py, radius: 3.0, color: colors::PREVIEW });
+                let days = (edit.planned_end_date - project.planned_end_date).num_days();
+                ctx.print(px + 5.0, py - 6.0, Span::styled(format!("{:+}d", days), styles::preview()));
+            }
+        }
+
+        // --- 5. Celebration ---
+        if let Some((celebration, frame)) = self.celebration {
+            let fading = celebration.fading(frame);
+            for p in &celebration.particles {
+                let color = if fading { colors::FG_DIM } else { p.color };
+                ctx.draw(&Points { coords: &[(p.x, p.y)], color });
             }
         }
     }
@@ -237,8 +584,8 @@ impl Widget for RadarWidget<'_> {
         Canvas::default()
             .block(ratatui::widgets::Block::default()
                 .borders(ratatui::widgets::Borders::ALL)
-                .border_style(styles::border())
-                .title(" Orbital Command ")
+                .border_style(if self.focused { styles::border_focused() } else { styles::border() })
+                .title(self.title.as_str())
                 .title_style(styles::title_accent())
                 .style(Style::default().bg(colors::BG_DARK))
             )
@@ -249,10 +596,114 @@ impl Widget for RadarWidget<'_> {
             .render(area, buf);
             
         // Stats in corners
-        let count_txt = format!("TRACKING: {}", self.projects.len());
+        let count_txt = match self.visible {
+            Some(visible) if visible.len() < self.projects.len() => {
+                format!("TRACKING: {}/{}", visible.len(), self.projects.len())
+            }
+            _ => format!("TRACKING: {}", self.projects.len()),
+        };
         buf.set_string(area.x + 2, area.y + area.height - 2, count_txt, Style::default().fg(colors::FG_HINT));
 
         let zoom_txt = format!("SENSOR RANGE: {}d", self.state.range_days);
         buf.set_string(area.x + area.width - zoom_txt.len() as u16 - 2, area.y + area.height - 2, zoom_txt, Style::default().fg(colors::FG_HINT));
+
+        // Legend on the bottom border, when there's room for it
+        let legend = ratatui::text::Line::from(vec![
+            Span::styled(" ● open ", Style::default().fg(colors::FG_PRIMARY)),
+            Span::styled("□ done ", Style::default().fg(status_color(ProjectStatus::Completed))),
+            Span::styled("✕ overdue", Style::default().fg(status_color(ProjectStatus::Overdue))),
+            Span::raw(" · colour: project/client "),
+        ])
+        .style(Style::default().fg(colors::FG_HINT));
+        let width = legend.width() as u16;
+        if area.width >= width + 4 {
+            buf.set_line(area.x + (area.width - width) / 2, area.y + area.height - 1, &legend, width);
+        }
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{project, ProjectFixture};
+
+    #[test]
+    fn test_accelerated_step_curve() {
+        assert_eq!(accelerated_step(Duration::ZERO), 1);
+        assert_eq!(accelerated_step(Duration::from_millis(499)), 1);
+        assert_eq!(accelerated_step(Duration::from_millis(500)), 3);
+        assert_eq!(accelerated_step(Duration::from_millis(1200)), 7);
+        assert_eq!(accelerated_step(Duration::from_millis(1500)), 14);
+        assert_eq!(accelerated_step(Duration::from_secs(60)), 14);
+    }
+
+    #[test]
+    fn test_cells_resolve_to_canvas_points_and_blips() {
+        let inner = Rect::new(1, 1, 100, 50);
+        // Each cell spans 2 canvas units across and 4 down
+        assert_eq!(canvas_point(inner, 1, 1), Some((-99.0, 98.0)));
+        assert_eq!(canvas_point(inner, 51, 26), Some((1.0, -2.0)));
+        assert_eq!(canvas_point(inner, 100, 50), Some((99.0, -98.0)));
+        assert_eq!(canvas_point(inner, 0, 10), None);
+        assert_eq!(canvas_point(inner, 101, 10), None);
+        assert_eq!(canvas_point(Rect::default(), 0, 0), None);
+
+        let today: NaiveDate = "2024-05-10".parse().unwrap();
+        let projects = vec![
+            project(1).client(1).ends("2024-06-09"),
+            project(2).client(2).ends("2024-07-09"),
+            project(3).client(3).ends("2026-01-01"),
+        ];
+        let (r, theta) = project_polar(&projects[0], 60.0, today);
+        let blip = (r * theta.cos(), r * theta.sin());
+        assert_eq!(blip_at(&projects, &[0, 1, 2], 60.0, today, blip, 4.0), Some(0));
+        assert_eq!(blip_at(&projects, &[0, 1, 2], 60.0, today, (blip.0 + 3.0, blip.1), 4.0), Some(0));
+        assert_eq!(blip_at(&projects, &[1, 2], 60.0, today, blip, 4.0), None);
+        // Nothing near the empty middle of the canvas
+        assert_eq!(blip_at(&projects, &[0, 1, 2], 60.0, today, (0.0, 0.0), 4.0), None);
+    }
+
+    #[test]
+    fn test_a_shown_day_sits_a_third_of_the_way_out() {
+        let mut state = RadarState::default();
+        state.show_day(60);
+        assert_eq!(state.range_days, 180.0);
+        assert_eq!(day_radius(60.0, state.range_days), 45.0);
+        // Tomorrow still leaves the smallest range
+        state.show_day(1);
+        assert_eq!(state.range_days, MIN_RANGE_DAYS);
+        state.show_day(400);
+        assert_eq!(state.range_days, 1200.0, "past the zoom limit if need be");
+
+        assert_eq!(day_radius(-3.0, 90.0), 10.0);
+        assert_eq!(day_radius(0.0, 90.0), 20.0);
+        assert_eq!(day_radius(500.0, 90.0), 95.0);
+    }
+
+    #[test]
+    fn test_state_age_short() {
+        assert_eq!(state_age_short(ProjectStatus::Overdue, 12), "LATE 12d");
+        assert_eq!(state_age_short(ProjectStatus::Active, 0), "ACTIVE today");
+        assert_eq!(state_age_short(ProjectStatus::Pending, 3), "PLANNED in 3d");
+        assert_eq!(state_age_short(ProjectStatus::Completed, 1), "DONE 1d");
+    }
+
+    #[test]
+    fn test_repeat_step_speeds_up_while_held() {
+        let mut state = RadarState::default();
+        let start = Instant::now();
+        let at = |ms: u64| start + Duration::from_millis(ms);
+
+        assert_eq!(state.repeat_step(1, at(0)), 1);
+        // Repeats every 50ms for over half a second
+        let steps: Vec<i64> = (1..=12).map(|i| state.repeat_step(1, at(i * 50))).collect();
+        assert_eq!(steps[..9], [1; 9]);
+        assert_eq!(steps[10..], [3, 3]);
+
+        // Releasing the key (a long gap) starts over
+        assert_eq!(state.repeat_step(1, at(1000)), 1);
+        // So does turning around
+        assert_eq!(state.repeat_step(1, at(1050)), 1);
+        assert_eq!(state.repeat_step(-1, at(1100)), -1);
+        assert_eq!(state.held.unwrap().since, at(1100));
+    }
+}
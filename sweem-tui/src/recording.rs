@@ -0,0 +1,416 @@
+//! Key recordings for bug reports (`--record` and `--replay`).
+//!
+//! A recording is a JSONL file with one `RecordedKey` per handled key press:
+//! the key, the frame it came in on, the input mode and tab at the time, and
+//! the highlighted entity. Replaying feeds the keys back to the app faster
+//! than they were typed, once the data has loaded. The replayed server may
+//! hold different data, so before each key the highlight is moved to the
+//! recorded entity, by id or else by name; mismatches are logged rather
+//! than stopping the replay.
+
+use std::collections::VecDeque;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::app::{App, InputMode, LogEntry, Tab};
+
+/// Version written into every line; lines of any other version are refused
+pub const RECORDING_VERSION: u32 = 1;
+
+/// Recorded gaps between keys are shortened this many times on replay
+pub const REPLAY_SPEEDUP: u64 = 4;
+
+/// One key press as recorded
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedKey {
+    pub version: u32,
+    /// App frame counter when the key came in
+    pub frame: u64,
+    pub mode: InputMode,
+    pub tab: Tab,
+    /// e.g. "j", "ctrl+w" or "shift+backtab"
+    #[serde(with = "key_format")]
+    pub key: KeyEvent,
+    /// The active tab's highlighted entity
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub selected: Option<Selected>,
+}
+
+/// An entity, by id and by the name to fall back on when ids differ
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Selected {
+    pub id: Uuid,
+    pub name: String,
+}
+
+impl RecordedKey {
+    /// `key` as it arrives in `app`'s current state. Characters typed into a
+    /// password field are kept as '*'.
+    pub fn capture(app: &App, mut key: KeyEvent) -> Self {
+        if let KeyCode::Char(_) = key.code {
+            if app.typing_secret() && !key.modifiers.contains(KeyModifiers::CONTROL) {
+                key.code = KeyCode::Char('*');
+            }
+        }
+        Self {
+            version: RECORDING_VERSION,
+            frame: app.frame_count,
+            mode: app.input_mode,
+            tab: app.active_tab,
+            key,
+            selected: app.selected_item().map(|(id, name)| Selected { id, name }),
+        }
+    }
+
+    /// The JSONL line, without its newline
+    pub fn to_line(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+}
+
+/// Named key codes; any other key is a single character
+const KEY_NAMES: &[(&str, KeyCode)] = &[
+    ("enter", KeyCode::Enter),
+    ("esc", KeyCode::Esc),
+    ("tab", KeyCode::Tab),
+    ("backtab", KeyCode::BackTab),
+    ("backspace", KeyCode::Backspace),
+    ("delete", KeyCode::Delete),
+    ("insert", KeyCode::Insert),
+    ("home", KeyCode::Home),
+    ("end", KeyCode::End),
+    ("pageup", KeyCode::PageUp),
+    ("pagedown", KeyCode::PageDown),
+    ("up", KeyCode::Up),
+    ("down", KeyCode::Down),
+    ("left", KeyCode::Left),
+    ("right", KeyCode::Right),
+];
+
+const MODIFIER_NAMES: &[(&str, KeyModifiers)] =
+    &[("ctrl", KeyModifiers::CONTROL), ("alt", KeyModifiers::ALT), ("shift", KeyModifiers::SHIFT)];
+
+/// "ctrl+w", "f12", "J"; `None` for keys the TUI never handles
+pub fn key_name(key: &KeyEvent) -> Option<String> {
+    let code = match key.code {
+        KeyCode::Char(c) => c.to_string(),
+        KeyCode::F(n) => format!("f{}", n),
+        code => KEY_NAMES.iter().find(|(_, c)| *c == code)?.0.to_string(),
+    };
+    let mut parts: Vec<String> = MODIFIER_NAMES
+        .iter()
+        .filter(|(_, m)| key.modifiers.contains(*m))
+        .map(|(name, _)| name.to_string())
+        .collect();
+    parts.push(code);
+    Some(parts.join("+"))
+}
+
+/// The key a `key_name` stands for
+pub fn parse_key(name: &str) -> Option<KeyEvent> {
+    let mut rest = name;
+    let mut modifiers = KeyModifiers::NONE;
+    // A lone "+" is the plus key, not an empty modifier list
+    while let Some((modifier, tail)) = rest.split_once('+').filter(|(_, tail)| !tail.is_empty()) {
+        modifiers |= MODIFIER_NAMES.iter().find(|(n, _)| *n == modifier)?.1;
+        rest = tail;
+    }
+    let mut chars = rest.chars();
+    let code = match (chars.next(), chars.next()) {
+        (Some(c), None) => KeyCode::Char(c),
+        _ => match rest.strip_prefix('f').and_then(|n| n.parse().ok()) {
+            Some(n) => KeyCode::F(n),
+            None => KEY_NAMES.iter().find(|(n, _)| *n == rest)?.1,
+        },
+    };
+    Some(KeyEvent::new(code, modifiers))
+}
+
+mod key_format {
+    use super::*;
+    use serde::{de, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(key: &KeyEvent, serializer: S) -> Result<S::Ok, S::Error> {
+        let name = key_name(key).ok_or_else(|| serde::ser::Error::custom("key cannot be recorded"))?;
+        serializer.serialize_str(&name)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<KeyEvent, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        parse_key(&name).ok_or_else(|| de::Error::custom(format!("unknown key \"{}\"", name)))
+    }
+}
+
+/// Parse a recording; blank lines are skipped and errors name their line
+pub fn parse(text: &str) -> Result<Vec<RecordedKey>> {
+    let mut keys = Vec::new();
+    for (i, line) in text.lines().enumerate().filter(|(_, l)| !l.trim().is_empty()) {
+        let version = serde_json::from_str::<serde_json::Value>(line)
+            .with_context(|| format!("Line {}: not JSON", i + 1))?
+            .get("version")
+            .and_then(|v| v.as_u64());
+        if version != Some(u64::from(RECORDING_VERSION)) {
+            anyhow::bail!("Line {}: unsupported recording version (expected {})", i + 1, RECORDING_VERSION);
+        }
+        keys.push(serde_json::from_str(line).with_context(|| format!("Line {}", i + 1))?);
+    }
+    Ok(keys)
+}
+
+/// Load a recording to replay
+pub fn load(path: &Path) -> Result<Vec<RecordedKey>> {
+    let text = std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    parse(&text).with_context(|| format!("Invalid recording {}", path.display()))
+}
+
+/// Appends key presses to a recording file
+pub struct Recorder {
+    file: File,
+}
+
+impl Recorder {
+    /// Open `path` for appending, creating it if needed
+    pub fn create(path: &Path) -> Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Failed to open {} for recording", path.display()))?;
+        Ok(Self { file })
+    }
+
+    /// Write one line straight away, so a crash keeps the keys leading up to it
+    pub fn write(&mut self, key: &RecordedKey) -> std::io::Result<()> {
+        writeln!(self.file, "{}", key.to_line())
+    }
+}
+
+/// Recorded keys waiting to be fed back
+#[derive(Debug, Clone, Default)]
+pub struct Replay {
+    keys: VecDeque<RecordedKey>,
+    /// App frame and recorded frame of the first replayed key
+    clock: Option<(u64, u64)>,
+    /// No key before this frame, so every key gets a frame of its own
+    earliest: u64,
+}
+
+impl Replay {
+    pub fn new(keys: Vec<RecordedKey>) -> Self {
+        Self { keys: keys.into(), ..Self::default() }
+    }
+
+    /// The next key, once its turn has come at app frame `frame`; the first
+    /// key is due straight away and the rest keep their recorded spacing,
+    /// shortened `REPLAY_SPEEDUP` times
+    pub fn next_due(&mut self, frame: u64) -> Option<RecordedKey> {
+        let next = self.keys.front()?;
+        let (start, first) = *self.clock.get_or_insert((frame, next.frame));
+        let at = start + next.frame.saturating_sub(first) / REPLAY_SPEEDUP;
+        if frame < at.max(self.earliest) {
+            return None;
+        }
+        self.earliest = frame + 1;
+        self.keys.pop_front()
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.keys.is_empty()
+    }
+}
+
+/// Recording and replay for one run of the TUI
+#[derive(Default)]
+pub struct Session {
+    pub recorder: Option<Recorder>,
+    pub replay: Option<Replay>,
+}
+
+impl Session {
+    /// Record `key` before `app` handles it; a failed write stops recording
+    pub fn record(&mut self, app: &mut App, key: KeyEvent) {
+        let Some(recorder) = &mut self.recorder else { return };
+        // Keys the TUI has no use for aren't worth a line
+        if key_name(&key).is_none() {
+            return;
+        }
+        if let Err(e) = recorder.write(&RecordedKey::capture(app, key)) {
+            app.log(LogEntry::warning(format!("Recording stopped: {}", e)));
+            self.recorder = None;
+        }
+    }
+
+    /// The replayed key due this frame, once the first load is done, with
+    /// the highlight moved to the recorded entity
+    pub fn replayed(&mut self, app: &mut App) -> Option<KeyEvent> {
+        let replay = self.replay.as_mut()?;
+        if app.splash.is_some() || app.last_refresh.is_none() {
+            return None;
+        }
+        let recorded = replay.next_due(app.frame_count)?;
+        if replay.is_done() {
+            self.replay = None;
+        }
+        sync(app, &recorded);
+        if self.replay.is_none() {
+            app.log(LogEntry::info("Replay finished"));
+        }
+        Some(recorded.key)
+    }
+}
+
+/// Bring `app` in line with where `recorded` was typed, logging what differs
+fn sync(app: &mut App, recorded: &RecordedKey) {
+    if app.input_mode != recorded.mode || app.active_tab != recorded.tab {
+        app.log(LogEntry::warning(format!(
+            "Replay: frame {} was typed in {:?} mode on {:?}, now {:?} mode on {:?}",
+            recorded.frame, recorded.mode, recorded.tab, app.input_mode, app.active_tab
+        )));
+    }
+    let Some(selected) = &recorded.selected else { return };
+    if app.selected_item().is_some_and(|(id, _)| id == selected.id) {
+        return;
+    }
+    if !app.reselect(selected.id, &selected.name) {
+        app.log(LogEntry::warning(format!("Replay: \"{}\" is not loaded", selected.name)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::form::{FormField, FormState};
+    use crate::test_support::{project, ProjectFixture};
+
+    fn key(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    fn recorded(frame: u64, name: &str) -> RecordedKey {
+        RecordedKey {
+            version: RECORDING_VERSION,
+            frame,
+            mode: InputMode::Normal,
+            tab: Tab::Timeline,
+            key: parse_key(name).unwrap(),
+            selected: None,
+        }
+    }
+
+    #[test]
+    fn test_key_names_round_trip() {
+        let keys = [
+            (key(KeyCode::Char('j'), KeyModifiers::NONE), "j"),
+            (key(KeyCode::Char('w'), KeyModifiers::CONTROL), "ctrl+w"),
+            (key(KeyCode::Char('+'), KeyModifiers::NONE), "+"),
+            (key(KeyCode::Char('+'), KeyModifiers::CONTROL), "ctrl++"),
+            (key(KeyCode::BackTab, KeyModifiers::SHIFT), "shift+backtab"),
+            (key(KeyCode::F(12), KeyModifiers::NONE), "f12"),
+            (key(KeyCode::Char('f'), KeyModifiers::NONE), "f"),
+            (key(KeyCode::Left, KeyModifiers::CONTROL | KeyModifiers::ALT), "ctrl+alt+left"),
+        ];
+        for (event, name) in keys {
+            assert_eq!(key_name(&event).as_deref(), Some(name));
+            assert_eq!(parse_key(name), Some(event), "{}", name);
+        }
+        assert_eq!(key_name(&key(KeyCode::CapsLock, KeyModifiers::NONE)), None);
+        assert_eq!(parse_key("hyper+j"), None);
+        assert_eq!(parse_key("ctrl+"), None);
+        assert_eq!(parse_key("escape"), None);
+    }
+
+    #[test]
+    fn test_lines_round_trip_with_a_stable_schema() {
+        let mut entry = recorded(42, "ctrl+w");
+        entry.mode = InputMode::Editing;
+        entry.selected = Some(Selected { id: Uuid::from_u128(7), name: "Apollo".to_string() });
+        let line = entry.to_line();
+        assert_eq!(
+            line,
+            "{\"version\":1,\"frame\":42,\"mode\":\"editing\",\"tab\":\"Timeline\",\"key\":\"ctrl+w\",\
+             \"selected\":{\"id\":\"00000000-0000-0000-0000-000000000007\",\"name\":\"Apollo\"}}"
+        );
+        let plain = recorded(43, "j");
+        let text = format!("{}\n\n{}\n", line, plain.to_line());
+        assert_eq!(parse(&text).unwrap(), [entry, plain]);
+
+        let future = line.replace("\"version\":1", "\"version\":2");
+        assert!(parse(&future).unwrap_err().to_string().contains("unsupported recording version"));
+        let bad_key = format!("{}\n{}", line, line.replace("ctrl+w", "hyper+w"));
+        let err = format!("{:#}", parse(&bad_key).unwrap_err());
+        assert!(err.starts_with("Line 2") && err.contains("unknown key \"hyper+w\""), "{}", err);
+    }
+
+    #[test]
+    fn test_recorder_appends_lines() {
+        let path = std::env::temp_dir().join(format!("sweem-recording-{}.jsonl", Uuid::new_v4()));
+        for frame in [1, 2] {
+            let mut recorder = Recorder::create(&path).unwrap();
+            recorder.write(&recorded(frame, "j")).unwrap();
+        }
+        let loaded = load(&path);
+        std::fs::remove_file(&path).ok();
+        assert_eq!(loaded.unwrap().iter().map(|k| k.frame).collect::<Vec<_>>(), [1, 2]);
+    }
+
+    #[test]
+    fn test_replay_shortens_the_gaps() {
+        let mut replay = Replay::new(vec![recorded(100, "j"), recorded(140, "k"), recorded(141, "l")]);
+        assert_eq!(replay.next_due(500).unwrap().frame, 100);
+        // 40 recorded frames become 10
+        assert!(replay.next_due(509).is_none());
+        assert_eq!(replay.next_due(510).unwrap().frame, 140);
+        // Keys closer than that still get a frame each
+        assert!(replay.next_due(510).is_none());
+        assert_eq!(replay.next_due(511).unwrap().frame, 141);
+        assert!(replay.is_done() && replay.next_due(600).is_none());
+    }
+
+    #[test]
+    fn test_replay_matches_entities_by_name() {
+        let mut app = App::new();
+        app.splash = None;
+        app.projects = vec![project(1).named("Apollo"), project(2).named("Gemini")];
+        app.radar_state.selected_index = Some(0);
+        let mut typed = recorded(10, "e");
+        typed.selected = Some(Selected { id: Uuid::from_u128(99), name: "Gemini".to_string() });
+        let mut session = Session { recorder: None, replay: Some(Replay::new(vec![typed])) };
+
+        // Nothing is fed before the data is in
+        assert!(session.replayed(&mut app).is_none());
+        app.last_refresh = Some(std::time::Instant::now());
+        assert_eq!(session.replayed(&mut app).map(|k| k.code), Some(KeyCode::Char('e')));
+        assert_eq!(app.radar_state.selected_index, Some(1));
+        assert!(session.replay.is_none());
+        assert!(app.logs.back().unwrap().message.contains("Replay finished"));
+
+        let mut missing = recorded(10, "e");
+        missing.tab = Tab::Clients;
+        missing.selected = Some(Selected { id: Uuid::from_u128(99), name: "Mercury".to_string() });
+        sync(&mut app, &missing);
+        assert_eq!(app.radar_state.selected_index, Some(1));
+        let warnings: Vec<_> = app.logs.iter().rev().take(2).map(|l| l.message.clone()).collect();
+        assert!(warnings[0].contains("\"Mercury\" is not loaded"), "{:?}", warnings);
+        assert!(warnings[1].contains("Normal mode on Clients, now Normal mode on Timeline"), "{:?}", warnings);
+    }
+
+    #[test]
+    fn test_password_keys_are_masked() {
+        let mut app = App::new();
+        app.active_tab = Tab::Users;
+        app.open_create_form();
+        let Some(FormState::User(form)) = &mut app.form_state else { panic!("no user form") };
+        form.nav.focused_field = form.nav.fields.iter().position(|f| *f == FormField::UserPassword).unwrap();
+        let typed = RecordedKey::capture(&app, key(KeyCode::Char('s'), KeyModifiers::NONE));
+        assert_eq!(typed.key.code, KeyCode::Char('*'));
+        assert_eq!(typed.mode, InputMode::Editing);
+        let shortcut = RecordedKey::capture(&app, key(KeyCode::Char('s'), KeyModifiers::CONTROL));
+        assert_eq!(shortcut.key.code, KeyCode::Char('s'));
+    }
+}
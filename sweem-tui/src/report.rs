@@ -0,0 +1,297 @@
+//! Markdown status report.
+//!
+//! Renders the loaded projects, clients and users into a weekly-review
+//! summary: counts, a per-client project breakdown and an "at risk" list of
+//! projects due within the next week. Rendering takes `today` explicitly so
+//! the output is reproducible in tests.
+
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use uuid::Uuid;
+
+use crate::dates::{format_date, DateFormat};
+use crate::models::{is_valid_date, short_id, ClientDto, ProjectDto, ProjectStatus, UserDto};
+
+/// Projects due within this many days are listed as at risk
+pub const AT_RISK_DAYS: i64 = 7;
+
+/// Names for the clients and managers projects refer to
+pub trait Names {
+    fn client_name(&self, id: Uuid) -> String;
+    fn user_name(&self, id: Uuid) -> String;
+}
+
+/// Names from the loaded lists alone, for when nothing more can be fetched
+pub struct Loaded<'a> {
+    pub clients: &'a [ClientDto],
+    pub users: &'a [UserDto],
+}
+
+impl Names for Loaded<'_> {
+    fn client_name(&self, id: Uuid) -> String {
+        let client = self.clients.iter().find(|c| c.id == id);
+        client.map_or_else(|| format!("unknown ({})", short_id(id)), |c| c.display_name().to_string())
+    }
+
+    fn user_name(&self, id: Uuid) -> String {
+        let user = self.users.iter().find(|u| u.id == id);
+        user.map_or_else(|| format!("unknown ({})", short_id(id)), |u| u.display_name().to_string())
+    }
+}
+
+/// Default file name for a report generated on `today`
+pub fn default_path(today: NaiveDate) -> PathBuf {
+    PathBuf::from(format!("sweem-report-{}.md", today))
+}
+
+/// Render the report and write it to `path`
+pub fn write(
+    path: &Path,
+    projects: &[ProjectDto],
+    clients: &[ClientDto],
+    users: &[UserDto],
+    names: &dyn Names,
+    today: NaiveDate,
+    date_format: DateFormat,
+) -> Result<()> {
+    let markdown = render(projects, clients, users, names, today, date_format);
+    std::fs::write(path, markdown).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Render the report as Markdown; `names` covers ids the lists don't
+pub fn render(
+    projects: &[ProjectDto],
+    clients: &[ClientDto],
+    users: &[UserDto],
+    names: &dyn Names,
+    today: NaiveDate,
+    date_format: DateFormat,
+) -> String {
+    let mut out = String::new();
+    // Placeholder dates become empty cells rather than "0001-01-01"
+    let fmt = |date: &NaiveDate| if is_valid_date(*date) { format_date(date, date_format) } else { String::new() };
+    let count = |status: ProjectStatus| projects.iter().filter(|p| p.status(today) == status).count();
+
+    // Writing to a String cannot fail
+    let _ = writeln!(out, "# SWEeM Status Report\n");
+    let _ = writeln!(out, "Generated {}\n", fmt(&today));
+
+    let _ = writeln!(out, "## Summary\n");
+    let _ = writeln!(out, "| Metric | Count |");
+    let _ = writeln!(out, "| --- | ---: |");
+    let _ = writeln!(out, "| Clients | {} |", clients.len());
+    let _ = writeln!(out, "| Users | {} |", users.len());
+    let _ = writeln!(out, "| Projects | {} |", projects.len());
+    for (status, name) in [
+        (ProjectStatus::Active, "Active"),
+        (ProjectStatus::Pending, "Planned"),
+        (ProjectStatus::Overdue, "Late"),
+        (ProjectStatus::Completed, "Done"),
+    ] {
+        let _ = writeln!(out, "| {} | {} |", name, count(status));
+    }
+    if count(ProjectStatus::Unscheduled) > 0 {
+        let _ = writeln!(out, "| Unscheduled | {} |", count(ProjectStatus::Unscheduled));
+    }
+
+    let _ = writeln!(out, "\n## Projects by Client");
+    let mut client_sections: Vec<(String, Vec<&ProjectDto>)> = clients
+        .iter()
+        .map(|c| {
+            let owned = projects.iter().filter(|p| p.client_id == c.id).collect();
+            (c.display_name().to_string(), owned)
+        })
+        .collect();
+    // Projects of clients that aren't loaded get a section per client id
+    let mut orphaned: Vec<Uuid> = Vec::new();
+    for p in projects {
+        if !clients.iter().any(|c| c.id == p.client_id) && !orphaned.contains(&p.client_id) {
+            orphaned.push(p.client_id);
+        }
+    }
+    for id in orphaned {
+        let owned = projects.iter().filter(|p| p.client_id == id).collect();
+        client_sections.push((names.client_name(id), owned));
+    }
+
+    for (client_name, mut owned) in client_sections {
+        let _ = writeln!(out, "\n### {}\n", client_name);
+        if owned.is_empty() {
+            let _ = writeln!(out, "_No projects._");
+            continue;
+        }
+        owned.sort_by_key(|p| (p.planned_end_date, p.display_name().to_string()));
+        let _ = writeln!(out, "| Project | Status | Days in State | Start | Deadline | Manager | Schedule |");
+        let _ = writeln!(out, "| --- | --- | ---: | --- | --- | --- | --- |");
+        for p in owned {
+            let _ = writeln!(
+                out,
+                "| {} | {} | {} | {} | {} | {} | {} |",
+                escape(p.display_name()),
+                p.status(today),
+                p.days_in_state(today).map_or(String::new(), |d| d.to_string()),
+                fmt(&p.start_date),
+                fmt(&p.planned_end_date),
+                escape(&manager_name(users, names, p.manager_id)),
+                schedule(p, today),
+            );
+        }
+    }
+
+    let _ = writeln!(out, "\n## At Risk\n");
+    let mut at_risk: Vec<&ProjectDto> = projects
+        .iter()
+        .filter(|p| {
+            let days_left = (p.planned_end_date - today).num_days();
+            p.status(today) == ProjectStatus::Active && days_left <= AT_RISK_DAYS
+        })
+        .collect();
+    if at_risk.is_empty() {
+        let _ = writeln!(out, "_No projects due in the next {} days._", AT_RISK_DAYS);
+    } else {
+        at_risk.sort_by_key(|p| (p.planned_end_date, p.display_name().to_string()));
+        let _ = writeln!(out, "| Project | Client | Deadline | Days Left |");
+        let _ = writeln!(out, "| --- | --- | --- | ---: |");
+        for p in at_risk {
+            let client = clients
+                .iter()
+                .find(|c| c.id == p.client_id)
+                .map_or_else(|| names.client_name(p.client_id), |c| c.display_name().to_string());
+            let _ = writeln!(
+                out,
+                "| {} | {} | {} | {} |",
+                escape(p.display_name()),
+                escape(&client),
+                fmt(&p.planned_end_date),
+                (p.planned_end_date - today).num_days(),
+            );
+        }
+    }
+
+    out
+}
+
+/// Days over or under the planned deadline, in words
+fn schedule(project: &ProjectDto, today: NaiveDate) -> String {
+    if !is_valid_date(project.planned_end_date) || !(project.is_completed() || project.has_valid_dates()) {
+        return String::new();
+    }
+    let (reference, finished) = match project.actual_end_date {
+        Some(end) if project.is_completed() => (end, true),
+        _ => (today, false),
+    };
+    let delta = (reference - project.planned_end_date).num_days();
+    let days = |n: i64| if n.abs() == 1 { "1 day".to_string() } else { format!("{} days", n.abs()) };
+
+    match (finished, delta) {
+        (true, 0) => "on time".to_string(),
+        (true, d) if d < 0 => format!("{} early", days(d)),
+        (true, d) => format!("{} late", days(d)),
+        (false, d) if d > 0 => format!("{} overdue", days(d)),
+        (false, d) => format!("{} left", days(d)),
+    }
+}
+
+fn manager_name(users: &[UserDto], names: &dyn Names, id: Uuid) -> String {
+    users
+        .iter()
+        .find(|u| u.id == id)
+        .map_or_else(|| names.user_name(id), |u| u.display_name().to_string())
+}
+
+/// Keep user-entered names from breaking table rows
+fn escape(text: &str) -> String {
+    text.replace('|', "\\|").replace(['\r', '\n'], " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{client, date, project, user, ClientFixture, ProjectFixture, UserFixture};
+
+    fn fixture() -> (Vec<ProjectDto>, Vec<ClientDto>, Vec<UserDto>) {
+        let clients = vec![
+            ClientDto { projects_total: 3, projects_completed: 1, ..client(1).named("Acme") },
+            client(2).named("Globex"),
+        ];
+        let users = vec![user(100).named("Kim").login("kim")];
+        let project = |n, name, client, start, end| {
+            project(n).named(name).client(client).manager(100).starts(start).ends(end)
+        };
+        let projects = vec![
+            project(10, "Apollo", 1, "2024-04-01", "2024-05-13"),
+            project(11, "Gemini", 1, "2024-01-01", "2024-05-01"),
+            project(12, "Mercury", 1, "2024-01-01", "2024-03-01").done_on("2024-02-27"),
+            project(13, "Skylab | Phase 2", 1, "2024-06-01", "2024-09-01"),
+            project(14, "Orphan", 9, "2024-04-01", "2024-05-20"),
+        ];
+        (projects, clients, users)
+    }
+
+    #[test]
+    fn test_report_matches_golden_file() {
+        let (projects, clients, users) = fixture();
+        let report = render(&projects, &clients, &users, &Loaded { clients: &clients, users: &users }, date("2024-05-10"), DateFormat::Iso);
+
+        let golden = concat!(env!("CARGO_MANIFEST_DIR"), "/testdata/report.md");
+        if std::env::var_os("UPDATE_GOLDEN").is_some() {
+            std::fs::write(golden, &report).unwrap();
+        }
+        assert_eq!(report, std::fs::read_to_string(golden).unwrap());
+    }
+
+    #[test]
+    fn test_report_uses_date_format() {
+        let (projects, clients, users) = fixture();
+        let report = render(&projects, &clients, &users, &Loaded { clients: &clients, users: &users }, date("2024-05-10"), DateFormat::Eu);
+        assert!(report.contains("Generated 10.05.2024"));
+        assert!(report.contains("| 01.06.2024 | 01.09.2024 |"));
+        assert!(!report.contains("2024-06-01"));
+    }
+
+    #[test]
+    fn test_placeholder_dates_are_empty_cells() {
+        let (mut projects, clients, users) = fixture();
+        projects[3].start_date = date("0001-01-01");
+        projects[3].planned_end_date = date("0001-01-01");
+        let report = render(&projects, &clients, &users, &Loaded { clients: &clients, users: &users }, date("2024-05-10"), DateFormat::Iso);
+        assert!(report.contains("| Skylab \\| Phase 2 | UNSCHEDULED |  |  |  | Kim |  |"));
+        assert!(report.contains("| Unscheduled | 1 |"));
+        assert!(!report.contains("0001"));
+    }
+
+    #[test]
+    fn test_names_cannot_break_table_rows() {
+        assert_eq!(escape("Skylab | Phase 2"), "Skylab \\| Phase 2");
+        assert_eq!(escape("Two\r\nlines\n"), "Two  lines ");
+
+        let (mut projects, clients, users) = fixture();
+        projects[0].name = Some("Apollo\nrow".to_string());
+        let names = Loaded { clients: &clients, users: &users };
+        let report = render(&projects, &clients, &users, &names, date("2024-05-10"), DateFormat::Iso);
+        assert!(report.contains("| Apollo row |"));
+        assert!(report.lines().all(|line| !line.starts_with("row")));
+    }
+
+    #[test]
+    fn test_schedule_wording() {
+        let (projects, _, _) = fixture();
+        let today = date("2024-05-10");
+        assert_eq!(schedule(&projects[0], today), "3 days left");
+        assert_eq!(schedule(&projects[1], today), "9 days overdue");
+        assert_eq!(schedule(&projects[2], today), "3 days early");
+    }
+
+    #[test]
+    fn test_write_creates_file() {
+        let path = std::env::temp_dir().join(format!("sweem-report-{}.md", Uuid::new_v4()));
+        let names = Loaded { clients: &[], users: &[] };
+        write(&path, &[], &[], &[], &names, date("2024-05-10"), DateFormat::Iso).unwrap();
+        let text = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+        assert!(text.contains("_No projects due in the next 7 days._"));
+    }
+}
@@ -0,0 +1,145 @@
+//! Hardening for data coming from the API.
+//!
+//! Names, addresses and logins go from the server straight to the terminal.
+//! Before they are stored, control chars are taken out (an ESC would start
+//! an escape sequence, a CR would send the cursor back over the row) and
+//! text longer than `MAX_TEXT_CHARS` is cut with an ellipsis. An entity with
+//! a nil id can't be told apart from the others and is dropped.
+
+use crate::models::{ClientDto, ProjectDto, UserDto};
+
+/// Longest name, address or login kept as it is
+pub const MAX_TEXT_CHARS: usize = 200;
+
+/// What sanitizing did to an entity
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    /// Nothing to change
+    Clean,
+    /// Kept after the listed fixes ("name cut to 200 chars")
+    Fixed(Vec<String>),
+    /// A nil id: not to be stored
+    Dropped,
+}
+
+impl Outcome {
+    fn from_fixes(fixes: Vec<String>) -> Self {
+        if fixes.is_empty() {
+            Outcome::Clean
+        } else {
+            Outcome::Fixed(fixes)
+        }
+    }
+}
+
+/// Take the control chars out of `text` and cut it to `MAX_TEXT_CHARS`;
+/// line breaks and tabs become spaces so words don't run together
+fn clean(field: &str, text: &mut Option<String>, fixes: &mut Vec<String>) {
+    let Some(value) = text.as_mut() else { return };
+    if value.chars().any(char::is_control) {
+        *value = value
+            .chars()
+            .filter_map(|c| match c {
+                '\n' | '\r' | '\t' => Some(' '),
+                c if c.is_control() => None,
+                c => Some(c),
+            })
+            .collect();
+        fixes.push(format!("control chars removed from {}", field));
+    }
+    if value.chars().count() > MAX_TEXT_CHARS {
+        *value = value.chars().take(MAX_TEXT_CHARS - 1).chain(['…']).collect();
+        fixes.push(format!("{} cut to {} chars", field, MAX_TEXT_CHARS));
+    }
+}
+
+/// Clean a project's name; `Dropped` for a nil id
+pub fn sanitize_project(project: &mut ProjectDto) -> Outcome {
+    if project.id.is_nil() {
+        return Outcome::Dropped;
+    }
+    let mut fixes = Vec::new();
+    clean("name", &mut project.name, &mut fixes);
+    Outcome::from_fixes(fixes)
+}
+
+/// Clean a client's name and address; `Dropped` for a nil id
+pub fn sanitize_client(client: &mut ClientDto) -> Outcome {
+    if client.id.is_nil() {
+        return Outcome::Dropped;
+    }
+    let mut fixes = Vec::new();
+    clean("name", &mut client.name, &mut fixes);
+    clean("address", &mut client.address, &mut fixes);
+    Outcome::from_fixes(fixes)
+}
+
+/// Clean a user's name and login; `Dropped` for a nil id
+pub fn sanitize_user(user: &mut UserDto) -> Outcome {
+    if user.id.is_nil() {
+        return Outcome::Dropped;
+    }
+    let mut fixes = Vec::new();
+    clean("name", &mut user.name, &mut fixes);
+    clean("login", &mut user.login, &mut fixes);
+    Outcome::from_fixes(fixes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Role;
+    use crate::test_support::{client, project, user, ClientFixture, ProjectFixture, UserFixture};
+
+    #[test]
+    fn test_clean_entities_are_left_alone() {
+        let mut acme = client(1).named("Acme — Ünïcode").address("1 Main St");
+        assert_eq!(sanitize_client(&mut acme), Outcome::Clean);
+        assert_eq!(acme.name.as_deref(), Some("Acme — Ünïcode"));
+        assert_eq!(acme.address.as_deref(), Some("1 Main St"));
+        let mut nameless = ClientDto { name: None, ..client(2) };
+        assert_eq!(sanitize_client(&mut nameless), Outcome::Clean);
+    }
+
+    #[test]
+    fn test_oversized_text_is_cut() {
+        let long = "é".repeat(MAX_TEXT_CHARS + 50);
+        let mut acme = client(1).named(&long).address("1 Main St");
+        assert_eq!(sanitize_client(&mut acme), Outcome::Fixed(vec!["name cut to 200 chars".to_string()]));
+        let name = acme.name.unwrap();
+        assert_eq!(name.chars().count(), MAX_TEXT_CHARS);
+        assert!(name.ends_with("é…"));
+        // Exactly at the limit stays whole
+        let mut fits = client(1).named(&"x".repeat(MAX_TEXT_CHARS));
+        assert_eq!(sanitize_client(&mut fits), Outcome::Clean);
+    }
+
+    #[test]
+    fn test_control_chars_are_stripped() {
+        let mut eve = user(3).named("Eve\x1b[2J\x1b]0;pwned\x07").login("eve\r\nroot");
+        assert_eq!(
+            sanitize_user(&mut eve),
+            Outcome::Fixed(vec![
+                "control chars removed from name".to_string(),
+                "control chars removed from login".to_string(),
+            ])
+        );
+        assert_eq!(eve.name.as_deref(), Some("Eve[2J]0;pwned"));
+        assert_eq!(eve.login.as_deref(), Some("eve  root"));
+
+        let mut acme = client(1).named("Acme").address("1 Main St\tSuite 4\u{9b}");
+        sanitize_client(&mut acme);
+        assert_eq!(acme.address.as_deref(), Some("1 Main St Suite 4"));
+    }
+
+    #[test]
+    fn test_nil_ids_are_dropped() {
+        let mut ghost = project(0).client(1).manager(2);
+        assert_eq!(sanitize_project(&mut ghost), Outcome::Dropped);
+        // Only the entity's own id counts
+        assert_eq!(sanitize_project(&mut project(5)), Outcome::Clean);
+
+        assert_eq!(sanitize_client(&mut client(0)), Outcome::Dropped);
+        assert_eq!(sanitize_user(&mut user(0).role(Role::Admin)), Outcome::Dropped);
+    }
+}
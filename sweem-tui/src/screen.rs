@@ -0,0 +1,318 @@
+//! Terminal setup and teardown.
+//!
+//! `Screen::setup` applies the setup steps one at a time and, when one
+//! fails, undoes the ones already applied before returning the error, so a
+//! terminal without an alternate screen isn't left in raw mode. The steps
+//! are undone again when the `Screen` is dropped, which covers early
+//! returns between setup and cleanup. With `--no-altscreen` the UI draws in
+//! an inline viewport at the bottom of the normal screen instead.
+//!
+//! The window title is saved on the terminal's title stack before the TUI
+//! sets its own (`terminal_title`), and popped back the same way.
+
+use std::io::{self, Stdout, Write};
+
+use anyhow::{Context, Result};
+use crossterm::{
+    event::{DisableFocusChange, DisableMouseCapture, EnableFocusChange, EnableMouseCapture},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen, SetTitle},
+};
+use ratatui::prelude::*;
+use ratatui::{TerminalOptions, Viewport};
+
+use crate::ui::MIN_HEIGHT;
+
+/// One reversible change to the terminal
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Step {
+    RawMode,
+    AlternateScreen,
+    /// Terminals without focus reporting ignore this and keep the app "focused"
+    FocusChange,
+    MouseCapture,
+    /// Save the window title, to be put back on exit; terminals without a
+    /// title stack ignore it
+    SaveTitle,
+}
+
+impl Step {
+    /// What failed, for the error message
+    fn describe(self) -> &'static str {
+        match self {
+            Step::RawMode => "Failed to enable raw mode",
+            Step::AlternateScreen => "Failed to enter alternate screen (try --no-altscreen)",
+            Step::FocusChange => "Failed to enable focus events",
+            Step::MouseCapture => "Failed to enable mouse events",
+            Step::SaveTitle => "Failed to save the window title",
+        }
+    }
+
+    fn apply(self, out: &mut impl Write) -> io::Result<()> {
+        match self {
+            Step::RawMode => enable_raw_mode(),
+            Step::AlternateScreen => execute!(out, EnterAlternateScreen),
+            Step::FocusChange => execute!(out, EnableFocusChange),
+            Step::MouseCapture => execute!(out, EnableMouseCapture),
+            Step::SaveTitle => write_flushed(out, PUSH_TITLE),
+        }
+    }
+
+    fn undo(self, out: &mut impl Write) -> io::Result<()> {
+        match self {
+            Step::RawMode => disable_raw_mode(),
+            Step::AlternateScreen => execute!(out, LeaveAlternateScreen),
+            Step::FocusChange => execute!(out, DisableFocusChange),
+            Step::MouseCapture => execute!(out, DisableMouseCapture),
+            Step::SaveTitle => write_flushed(out, POP_TITLE),
+        }
+    }
+}
+
+/// How the TUI takes over the terminal
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScreenOptions {
+    /// Draw inline on the normal screen instead of the alternate one
+    pub inline: bool,
+    pub mouse: bool,
+    /// Set the window title (`terminal_title`), so save the old one first
+    pub title: bool,
+}
+
+impl ScreenOptions {
+    /// The setup steps, in the order they are applied
+    pub fn steps(&self) -> Vec<Step> {
+        let mut steps = vec![Step::RawMode];
+        if !self.inline {
+            steps.push(Step::AlternateScreen);
+        }
+        steps.push(Step::FocusChange);
+        if self.mouse {
+            steps.push(Step::MouseCapture);
+        }
+        if self.title {
+            steps.push(Step::SaveTitle);
+        }
+        steps
+    }
+}
+
+/// Rows of the inline viewport: two thirds of the screen, but never fewer
+/// than the UI needs when the screen has them
+pub fn inline_height(screen_rows: u16) -> u16 {
+    (screen_rows / 3 * 2).max(MIN_HEIGHT).min(screen_rows)
+}
+
+/// Escape sequence asking the terminal to put `text` on the system
+/// clipboard (OSC 52); terminals that don't support it ignore it
+pub fn clipboard_sequence(text: &str) -> String {
+    use base64::Engine;
+    format!("\x1b]52;c;{}\x07", base64::engine::general_purpose::STANDARD.encode(text))
+}
+
+/// Put `text` on the clipboard through the terminal
+pub fn copy_to_clipboard(out: &mut impl Write, text: &str) -> io::Result<()> {
+    write_flushed(out, &clipboard_sequence(text))
+}
+
+/// Push the window title onto the terminal's title stack (XTWINOPS 22)
+const PUSH_TITLE: &str = "\x1b[22;0t";
+
+/// Pop the saved window title back (XTWINOPS 23)
+const POP_TITLE: &str = "\x1b[23;0t";
+
+fn write_flushed(out: &mut impl Write, sequence: &str) -> io::Result<()> {
+    out.write_all(sequence.as_bytes())?;
+    out.flush()
+}
+
+/// Set the window title
+pub fn set_title(out: &mut impl Write, title: &str) -> io::Result<()> {
+    execute!(out, SetTitle(title))
+}
+
+/// Apply `steps` in order. On the first failure the steps already applied
+/// are undone in reverse and the error is returned; otherwise the applied
+/// steps, for undoing later.
+pub fn apply_all(
+    steps: &[Step],
+    mut apply: impl FnMut(Step) -> io::Result<()>,
+    mut undo: impl FnMut(Step) -> io::Result<()>,
+) -> Result<Vec<Step>> {
+    let mut applied = Vec::new();
+    for &step in steps {
+        if let Err(e) = apply(step) {
+            undo_all(&mut applied, &mut undo).ok();
+            return Err(e).context(step.describe());
+        }
+        applied.push(step);
+    }
+    Ok(applied)
+}
+
+/// Undo `applied` in reverse, carrying on past failures; the first one is
+/// returned
+fn undo_all(applied: &mut Vec<Step>, mut undo: impl FnMut(Step) -> io::Result<()>) -> io::Result<()> {
+    let mut first = Ok(());
+    while let Some(step) = applied.pop() {
+        if let Err(e) = undo(step) {
+            first = first.and(Err(e));
+        }
+    }
+    first
+}
+
+/// The terminal as set up for the TUI
+pub struct Screen {
+    applied: Vec<Step>,
+    inline: bool,
+}
+
+impl Screen {
+    /// Set up the terminal and create the ratatui terminal on it; nothing
+    /// is left changed when this fails
+    pub fn setup(options: ScreenOptions) -> Result<(Self, Terminal<CrosstermBackend<Stdout>>)> {
+        let mut out = io::stdout();
+        let applied = apply_all(&options.steps(), |step| step.apply(&mut out), |step| step.undo(&mut io::stdout()))?;
+        // From here on, dropping the screen undoes the steps
+        let screen = Self { applied, inline: options.inline };
+
+        let backend = CrosstermBackend::new(io::stdout());
+        let terminal = if options.inline {
+            let rows = crossterm::terminal::size().context("Failed to read the terminal size")?.1;
+            let viewport = Viewport::Inline(inline_height(rows));
+            Terminal::with_options(backend, TerminalOptions { viewport })
+        } else {
+            Terminal::new(backend)
+        };
+        let terminal = terminal.context("Failed to create terminal")?;
+        Ok((screen, terminal))
+    }
+
+    /// Hand the terminal back: an inline viewport is cleared so the shell
+    /// prompt continues where it began, then the setup is undone
+    pub fn restore(mut self, terminal: &mut Terminal<CrosstermBackend<Stdout>>) -> Result<()> {
+        if self.inline {
+            terminal.clear().context("Failed to clear the inline viewport")?;
+        }
+        undo_all(&mut self.applied, |step| step.undo(terminal.backend_mut()))
+            .context("Failed to restore the terminal")?;
+        terminal.show_cursor().context("Failed to show cursor")
+    }
+}
+
+impl Drop for Screen {
+    fn drop(&mut self) {
+        undo_all(&mut self.applied, |step| step.undo(&mut io::stdout())).ok();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::backend::TestBackend;
+
+    use crate::app::App;
+
+    /// Apply `steps`, failing at `fail`, and log every apply and undo
+    fn run(steps: &[Step], fail: Option<Step>) -> (Result<Vec<Step>>, Vec<String>) {
+        let log = std::cell::RefCell::new(Vec::new());
+        let result = apply_all(
+            steps,
+            |step| {
+                log.borrow_mut().push(format!("apply {:?}", step));
+                match Some(step) == fail {
+                    true => Err(io::Error::other("unsupported")),
+                    false => Ok(()),
+                }
+            },
+            |step| {
+                log.borrow_mut().push(format!("undo {:?}", step));
+                Ok(())
+            },
+        );
+        (result, log.into_inner())
+    }
+
+    #[test]
+    fn test_failed_step_undoes_the_earlier_ones() {
+        let steps = ScreenOptions { inline: false, mouse: true, title: false }.steps();
+        let (result, log) = run(&steps, Some(Step::FocusChange));
+        assert_eq!(
+            log,
+            ["apply RawMode", "apply AlternateScreen", "apply FocusChange", "undo AlternateScreen", "undo RawMode"]
+        );
+        assert_eq!(result.unwrap_err().to_string(), "Failed to enable focus events");
+
+        let (result, log) = run(&steps, Some(Step::AlternateScreen));
+        assert_eq!(log, ["apply RawMode", "apply AlternateScreen", "undo RawMode"]);
+        assert!(result.unwrap_err().to_string().contains("--no-altscreen"));
+
+        let (result, log) = run(&steps, None);
+        assert_eq!(result.unwrap(), steps);
+        assert_eq!(log.len(), 4);
+    }
+
+    #[test]
+    fn test_undo_carries_on_past_failures() {
+        let mut applied = vec![Step::RawMode, Step::FocusChange, Step::MouseCapture];
+        let mut undone = Vec::new();
+        let result = undo_all(&mut applied, |step| {
+            undone.push(step);
+            match step {
+                Step::FocusChange => Err(io::Error::other("gone")),
+                _ => Ok(()),
+            }
+        });
+        assert_eq!(result.unwrap_err().to_string(), "gone");
+        assert_eq!(undone, [Step::MouseCapture, Step::FocusChange, Step::RawMode]);
+        assert!(applied.is_empty());
+    }
+
+    #[test]
+    fn test_clipboard_goes_out_base64_encoded() {
+        assert_eq!(clipboard_sequence("hunter2"), "\x1b]52;c;aHVudGVyMg==\x07");
+        let mut out = Vec::new();
+        copy_to_clipboard(&mut out, "pässwort").unwrap();
+        assert_eq!(out, b"\x1b]52;c;cMOkc3N3b3J0\x07");
+    }
+
+    #[test]
+    fn test_window_title_is_saved_and_restored() {
+        let steps = ScreenOptions { inline: false, mouse: false, title: true }.steps();
+        assert_eq!(steps.last(), Some(&Step::SaveTitle));
+        let mut out = Vec::new();
+        Step::SaveTitle.apply(&mut out).unwrap();
+        set_title(&mut out, "SWEeM").unwrap();
+        Step::SaveTitle.undo(&mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "\x1b[22;0t\x1b]0;SWEeM\x07\x1b[23;0t");
+    }
+
+    #[test]
+    fn test_inline_mode_skips_the_alternate_screen() {
+        let steps = ScreenOptions { inline: true, mouse: false, title: false }.steps();
+        assert_eq!(steps, [Step::RawMode, Step::FocusChange]);
+        assert_eq!(inline_height(60), 40);
+        assert_eq!(inline_height(24), MIN_HEIGHT);
+        assert_eq!(inline_height(12), 12);
+    }
+
+    #[test]
+    fn test_inline_viewport_draws_below_the_shell_output() {
+        let mut backend = TestBackend::new(100, 50);
+        backend.set_cursor_position(Position::new(0, 5)).unwrap();
+        let viewport = Viewport::Inline(inline_height(50));
+        let mut terminal = Terminal::with_options(backend, TerminalOptions { viewport }).unwrap();
+        let mut app = App::new();
+        app.splash = None;
+        assert_eq!(terminal.get_frame().area(), Rect::new(0, 5, 100, 32));
+        terminal.draw(|frame| crate::ui::render(frame, &app)).unwrap();
+
+        let buffer = terminal.backend().buffer();
+        let row = |y: u16| (0..100).map(|x| buffer[(x, y)].symbol()).collect::<String>();
+        // The shell's lines above the viewport are left alone
+        assert!((0..5).all(|y| row(y).trim().is_empty()));
+        assert!((5..37).any(|y| row(y).contains("System Log")));
+        assert!((37..50).all(|y| row(y).trim().is_empty()));
+    }
+}
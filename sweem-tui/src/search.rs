@@ -0,0 +1,170 @@
+//! Global search (Ctrl+F) across projects, clients and users.
+//!
+//! Matching is a case-insensitive substring search over names, addresses
+//! and logins, plus a prefix match on ids. Each entity shows up once, with
+//! its best match; results are grouped by entity type and ranked by how early
+//! the match starts.
+
+use std::ops::Range;
+
+use uuid::Uuid;
+
+use crate::api::EntityType;
+use crate::models::{ClientDto, ProjectDto, UserDto};
+
+/// Most hits kept per entity type
+pub const MAX_HITS_PER_TYPE: usize = 20;
+
+/// One matching entity
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hit {
+    pub entity_type: EntityType,
+    /// Index into the entity's list in the App
+    pub index: usize,
+    pub id: Uuid,
+    /// The entity's display name
+    pub name: String,
+    /// Which field matched: "name", "address", "login" or "id"
+    pub field: &'static str,
+    /// The matching field's text
+    pub text: String,
+    /// Byte range of the match within `text`
+    pub range: Range<usize>,
+}
+
+/// Byte range of the first case-insensitive occurrence of `needle` in `text`
+pub fn find_ignore_case(text: &str, needle: &str) -> Option<Range<usize>> {
+    let needle: Vec<char> = needle.chars().collect();
+    if needle.is_empty() {
+        return None;
+    }
+    let same = |a: char, b: char| a == b || a.to_lowercase().eq(b.to_lowercase());
+    text.char_indices().find_map(|(start, _)| {
+        let mut end = start;
+        let mut chars = text[start..].chars();
+        for &n in &needle {
+            let c = chars.next().filter(|&c| same(c, n))?;
+            end += c.len_utf8();
+        }
+        Some(start..end)
+    })
+}
+
+/// Best match among an entity's searchable fields: earliest start wins, ties
+/// go to the field listed first
+fn best_match(fields: &[(&'static str, &str)], query: &str) -> Option<(&'static str, String, Range<usize>)> {
+    fields
+        .iter()
+        .filter_map(|&(field, text)| find_ignore_case(text, query).map(|range| (field, text, range)))
+        .min_by_key(|(_, _, range)| range.start)
+        .map(|(field, text, range)| (field, text.to_string(), range))
+}
+
+/// Match on the hyphenated id, only from its first character
+fn id_match(id: Uuid, query: &str) -> Option<(&'static str, String, Range<usize>)> {
+    let text = id.to_string();
+    let range = find_ignore_case(&text, query).filter(|r| r.start == 0)?;
+    Some(("id", text, range))
+}
+
+/// Rank one group's hits by match position, then by the shorter name
+fn ranked(mut hits: Vec<Hit>) -> Vec<Hit> {
+    hits.sort_by(|a, b| {
+        a.range
+            .start
+            .cmp(&b.range.start)
+            .then(a.name.len().cmp(&b.name.len()))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    hits.truncate(MAX_HITS_PER_TYPE);
+    hits
+}
+
+/// Every entity matching `query`, projects first, then clients, then users
+pub fn search(query: &str, projects: &[ProjectDto], clients: &[ClientDto], users: &[UserDto]) -> Vec<Hit> {
+    let query = query.trim();
+    if query.is_empty() {
+        return Vec::new();
+    }
+    let hit = |entity_type, index, id, name: &str, found: Option<(&'static str, String, Range<usize>)>| {
+        found.map(|(field, text, range)| Hit { entity_type, index, id, name: name.to_string(), field, text, range })
+    };
+
+    let projects = projects.iter().enumerate().filter_map(|(i, p)| {
+        let found = best_match(&[("name", p.display_name())], query).or_else(|| id_match(p.id, query));
+        hit(EntityType::Project, i, p.id, p.display_name(), found)
+    });
+    let clients = clients.iter().enumerate().filter_map(|(i, c)| {
+        let fields = [("name", c.display_name()), ("address", c.address.as_deref().unwrap_or_default())];
+        let found = best_match(&fields, query).or_else(|| id_match(c.id, query));
+        hit(EntityType::Client, i, c.id, c.display_name(), found)
+    });
+    let users = users.iter().enumerate().filter_map(|(i, u)| {
+        let fields = [("name", u.display_name()), ("login", u.login.as_deref().unwrap_or_default())];
+        let found = best_match(&fields, query).or_else(|| id_match(u.id, query));
+        hit(EntityType::User, i, u.id, u.display_name(), found)
+    });
+
+    let mut hits = ranked(projects.collect());
+    hits.extend(ranked(clients.collect()));
+    hits.extend(ranked(users.collect()));
+    hits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{client, project, user, ClientFixture, ProjectFixture, UserFixture};
+
+    #[test]
+    fn test_find_ignore_case() {
+        assert_eq!(find_ignore_case("Orbital Station", "STAT"), Some(8..12));
+        assert_eq!(find_ignore_case("Straße Nord", "nord"), Some(8..12));
+        assert_eq!(find_ignore_case("ÄRGER", "ärg"), Some(0..4));
+        assert_eq!(find_ignore_case("Apollo", "apollo 2"), None);
+        assert_eq!(find_ignore_case("Apollo", ""), None);
+    }
+
+    #[test]
+    fn test_results_are_grouped_and_ranked_by_position() {
+        let projects = vec![project(1).named("Moon Base"), project(2).named("Base Camp"), project(3).named("Orbit")];
+        let clients = vec![
+            client(10).named("Acme").address("12 Base Street"),
+            client(11).named("Basel AG").address("Zürich"),
+        ];
+        let users = vec![user(20).named("Ada").login("abase"), user(21).named("Kim").login("kim")];
+        let hits = search(" base ", &projects, &clients, &users);
+
+        let found: Vec<(EntityType, &str, &str)> =
+            hits.iter().map(|h| (h.entity_type, h.name.as_str(), h.field)).collect();
+        assert_eq!(
+            found,
+            [
+                (EntityType::Project, "Base Camp", "name"),
+                (EntityType::Project, "Moon Base", "name"),
+                (EntityType::Client, "Basel AG", "name"),
+                (EntityType::Client, "Acme", "address"),
+                (EntityType::User, "Ada", "login"),
+            ]
+        );
+        assert_eq!(hits[3].text, "12 Base Street");
+        assert_eq!(hits[3].range, 3..7);
+        assert_eq!(hits[3].index, 0);
+        assert!(search("  ", &projects, &clients, &users).is_empty());
+    }
+
+    #[test]
+    fn test_ids_match_by_prefix_only() {
+        let id = Uuid::parse_str("3f2a9c1e-0000-4000-8000-000000000001").unwrap();
+        let mut apollo = project(1).named("Apollo");
+        apollo.id = id;
+        let projects = vec![apollo];
+
+        let hits = search("3F2A", &projects, &[], &[]);
+        assert_eq!(hits.len(), 1);
+        assert_eq!((hits[0].field, hits[0].range.clone()), ("id", 0..4));
+        assert!(search("9c1e", &projects, &[], &[]).is_empty());
+        // A name match beats the id
+        assert_eq!(search("apo", &projects, &[], &[])[0].field, "name");
+    }
+}
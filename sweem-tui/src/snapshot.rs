@@ -0,0 +1,245 @@
+//! Radar snapshots for pasting into tickets.
+//!
+//! The radar is drawn into an offscreen buffer instead of the terminal, with
+//! the sweep line and confetti left out, so the same data always gives the
+//! same picture. The buffer is then written as plain text or with ANSI colour
+//! escapes.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Modifier};
+use ratatui::widgets::Widget;
+use serde::Deserialize;
+
+use crate::models::{ClientDto, ProjectDto};
+use crate::radar::{RadarState, RadarWidget};
+
+/// Snapshot width when the config doesn't set one, in columns
+pub const DEFAULT_WIDTH: u16 = 200;
+
+/// Narrowest snapshot that still shows the client labels
+pub const MIN_WIDTH: u16 = 40;
+
+/// How a snapshot is written
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SnapshotFormat {
+    /// UTF-8 text with the colours stripped
+    #[default]
+    Text,
+    /// UTF-8 text with ANSI colour escapes, for `cat` or `less -R`
+    Ansi,
+}
+
+impl SnapshotFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            SnapshotFormat::Text => "txt",
+            SnapshotFormat::Ansi => "ans",
+        }
+    }
+}
+
+/// Default file name for a snapshot taken on `today`
+pub fn default_path(today: NaiveDate, format: SnapshotFormat) -> PathBuf {
+    PathBuf::from(format!("sweem-radar-{}.{}", today, format.extension()))
+}
+
+/// Draw every project at `range_days` into a buffer `width` columns wide.
+/// Terminal cells are about twice as tall as they are wide, so half as many
+/// rows keeps the rings round.
+pub fn render(
+    projects: &[ProjectDto],
+    clients: &[ClientDto],
+    range_days: f64,
+    today: NaiveDate,
+    width: u16,
+) -> Buffer {
+    let width = width.max(MIN_WIDTH);
+    let area = Rect::new(0, 0, width, width / 2);
+    let state = RadarState { range_days, ..RadarState::default() };
+    let mut buf = Buffer::empty(area);
+    RadarWidget::new(projects, clients, &state).today(today).still().render(area, &mut buf);
+    buf
+}
+
+/// The buffer as lines of text, without colours or trailing spaces
+pub fn to_text(buf: &Buffer) -> String {
+    let mut out = String::new();
+    for y in 0..buf.area.height {
+        let line: String = (0..buf.area.width).map(|x| buf[(x, y)].symbol()).collect();
+        out.push_str(line.trim_end());
+        out.push('\n');
+    }
+    out
+}
+
+/// The buffer as lines of text with ANSI SGR escapes; styles are only
+/// emitted where they change and reset at the end of every line
+pub fn to_ansi(buf: &Buffer) -> String {
+    let mut out = String::new();
+    for y in 0..buf.area.height {
+        let mut current = None;
+        for x in 0..buf.area.width {
+            let cell = &buf[(x, y)];
+            let style = (cell.fg, cell.bg, cell.modifier);
+            if current != Some(style) {
+                out.push_str(&sgr(cell.fg, cell.bg, cell.modifier));
+                current = Some(style);
+            }
+            out.push_str(cell.symbol());
+        }
+        out.push_str("\x1b[0m\n");
+    }
+    out
+}
+
+/// Escape sequence selecting exactly this style
+fn sgr(fg: Color, bg: Color, modifier: Modifier) -> String {
+    let mut codes = vec!["0".to_string()];
+    for (flag, code) in [
+        (Modifier::BOLD, "1"),
+        (Modifier::DIM, "2"),
+        (Modifier::ITALIC, "3"),
+        (Modifier::UNDERLINED, "4"),
+        (Modifier::REVERSED, "7"),
+    ] {
+        if modifier.contains(flag) {
+            codes.push(code.to_string());
+        }
+    }
+    codes.extend(color_code(fg, 30));
+    codes.extend(color_code(bg, 40));
+    format!("\x1b[{}m", codes.join(";"))
+}
+
+/// SGR parameters for a colour; `base` is 30 for foreground, 40 for background
+fn color_code(color: Color, base: u8) -> Option<String> {
+    let named = |offset: u8| Some((base + offset).to_string());
+    let bright = |offset: u8| Some((base + 60 + offset).to_string());
+    match color {
+        Color::Reset => None,
+        Color::Black => named(0),
+        Color::Red => named(1),
+        Color::Green => named(2),
+        Color::Yellow => named(3),
+        Color::Blue => named(4),
+        Color::Magenta => named(5),
+        Color::Cyan => named(6),
+        Color::Gray => named(7),
+        Color::DarkGray => bright(0),
+        Color::LightRed => bright(1),
+        Color::LightGreen => bright(2),
+        Color::LightYellow => bright(3),
+        Color::LightBlue => bright(4),
+        Color::LightMagenta => bright(5),
+        Color::LightCyan => bright(6),
+        Color::White => bright(7),
+        Color::Indexed(i) => Some(format!("{};5;{}", base + 8, i)),
+        Color::Rgb(r, g, b) => Some(format!("{};2;{};{};{}", base + 8, r, g, b)),
+    }
+}
+
+/// Render a snapshot and write it to `path`
+pub fn write(
+    path: &Path,
+    projects: &[ProjectDto],
+    clients: &[ClientDto],
+    range_days: f64,
+    today: NaiveDate,
+    width: u16,
+    format: SnapshotFormat,
+) -> Result<()> {
+    let buf = render(projects, clients, range_days, today, width);
+    let text = match format {
+        SnapshotFormat::Text => to_text(&buf),
+        SnapshotFormat::Ansi => to_ansi(&buf),
+    };
+    std::fs::write(path, text).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{client, date, project, ClientFixture, ProjectFixture};
+    use uuid::Uuid;
+
+    fn fixture() -> (Vec<ProjectDto>, Vec<ClientDto>) {
+        let clients = vec![ClientDto { projects_total: 3, projects_completed: 1, ..client(1).named("Acme") }];
+        let project = |n, name| project(n).named(name).client(1).starts("2024-04-01");
+        let projects = vec![
+            project(10, "Apollo").ends("2024-06-10"),
+            project(11, "Gemini").ends("2024-05-01"),
+            project(12, "Mercury").ends("2024-05-05").done_on("2024-05-04"),
+        ];
+        (projects, clients)
+    }
+
+    #[test]
+    fn test_text_snapshot_matches_golden_file() {
+        let (projects, clients) = fixture();
+        let buf = render(&projects, &clients, 60.0, date("2024-05-10"), 60);
+        let text = to_text(&buf);
+
+        let golden = concat!(env!("CARGO_MANIFEST_DIR"), "/testdata/radar.txt");
+        if std::env::var_os("UPDATE_GOLDEN").is_some() {
+            std::fs::write(golden, &text).unwrap();
+        }
+        assert_eq!(text, std::fs::read_to_string(golden).unwrap());
+    }
+
+    #[test]
+    fn test_snapshot_is_deterministic() {
+        let (projects, clients) = fixture();
+        let today = date("2024-05-10");
+        let first = render(&projects, &clients, 60.0, today, 80);
+        let second = render(&projects, &clients, 60.0, today, 80);
+        assert_eq!(first, second);
+        assert_eq!((first.area.width, first.area.height), (80, 40));
+        assert_eq!(render(&projects, &clients, 60.0, today, 10).area.width, MIN_WIDTH);
+    }
+
+    #[test]
+    fn test_ansi_keeps_colours_and_text() {
+        let (projects, clients) = fixture();
+        let buf = render(&projects, &clients, 60.0, date("2024-05-10"), 60);
+        let ansi = to_ansi(&buf);
+        assert!(ansi.contains("\x1b[0;38;2;"));
+        assert!(ansi.lines().all(|line| line.ends_with("\x1b[0m")));
+
+        let stripped: String = ansi
+            .split('\x1b')
+            .map(|part| part.split_once('m').map_or(part, |(_, rest)| rest))
+            .collect();
+        let lines: Vec<&str> = stripped.lines().map(str::trim_end).collect();
+        assert_eq!(lines.join("\n") + "\n", to_text(&buf));
+    }
+
+    #[test]
+    fn test_color_codes() {
+        assert_eq!(color_code(Color::Rgb(1, 2, 3), 30).as_deref(), Some("38;2;1;2;3"));
+        assert_eq!(color_code(Color::Indexed(200), 40).as_deref(), Some("48;5;200"));
+        assert_eq!(color_code(Color::Red, 30).as_deref(), Some("31"));
+        assert_eq!(color_code(Color::White, 40).as_deref(), Some("107"));
+        assert_eq!(color_code(Color::Reset, 30), None);
+        assert_eq!(sgr(Color::Reset, Color::Reset, Modifier::BOLD), "\x1b[0;1m");
+    }
+
+    #[test]
+    fn test_client_sector_and_active_blips_use_palette_colours() {
+        use crate::theme::palette_color;
+        let (projects, clients) = fixture();
+        let buf = render(&projects, &clients, 60.0, date("2024-05-10"), 60);
+        let cells: Vec<_> = buf.content().iter().collect();
+        let label = cells.windows(4).find(|w| w.iter().map(|c| c.symbol()).collect::<String>() == "Acme").unwrap();
+        assert_eq!(label[0].fg, palette_color(clients[0].id));
+
+        // Apollo is the only active project; the done and overdue ones keep their status colours
+        assert!(cells.iter().any(|c| c.fg == palette_color(projects[0].id)));
+        assert_eq!(palette_color(projects[0].id), palette_color(Uuid::from_u128(10)));
+    }
+}
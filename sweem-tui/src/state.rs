@@ -0,0 +1,133 @@
+//! UI state kept between runs.
+//!
+//! On quit the active tab, radar range, particle mode and selections are
+//! written to `ui-state.toml` in the platform state directory
+//! (`~/.local/state/sweem-tui/` on Linux). Selections are stored by entity id
+//! rather than index, so they survive the server reordering its lists.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::app::Tab;
+use crate::particles::ParticleMode;
+
+/// Bumped whenever the file layout changes; other versions are ignored
+pub const STATE_VERSION: u32 = 1;
+
+/// Snapshot of the parts of the UI worth restoring
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UiState {
+    pub version: u32,
+    pub active_tab: Tab,
+    /// Radar zoom, in days
+    pub range_days: f64,
+    pub particle_mode: ParticleMode,
+    #[serde(default)]
+    pub selections: Selections,
+}
+
+/// Selected entity per tab
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Selections {
+    pub project: Option<Uuid>,
+    pub client: Option<Uuid>,
+    pub user: Option<Uuid>,
+}
+
+impl UiState {
+    /// Location of the state file; platforms without a state directory use local data
+    pub fn path() -> Option<PathBuf> {
+        dirs::state_dir()
+            .or_else(dirs::data_local_dir)
+            .map(|dir| dir.join("sweem-tui").join("ui-state.toml"))
+    }
+
+    /// Load the saved state, or None on first run
+    pub fn load() -> Result<Option<Self>> {
+        match Self::path() {
+            Some(path) if path.exists() => Self::load_from(&path).map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    /// Load state from `path`
+    pub fn load_from(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        Self::parse(&text).with_context(|| format!("Invalid UI state in {}", path.display()))
+    }
+
+    /// Parse state from TOML text, rejecting files written by another version
+    pub fn parse(text: &str) -> Result<Self> {
+        #[derive(Deserialize)]
+        struct Versioned {
+            version: u32,
+        }
+
+        // Check the version first so a layout change reads as a mismatch, not corruption
+        let Versioned { version } = toml::from_str(text)?;
+        if version != STATE_VERSION {
+            anyhow::bail!("version {} is not supported (expected {})", version, STATE_VERSION);
+        }
+        Ok(toml::from_str(text)?)
+    }
+
+    /// Write the state file, creating its directory if needed
+    pub fn save(&self) -> Result<()> {
+        let path = Self::path().context("No state directory on this platform")?;
+        self.save_to(&path)
+    }
+
+    /// Write state to `path`
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+        let text = toml::to_string(self).context("Failed to serialize UI state")?;
+        std::fs::write(path, text).with_context(|| format!("Failed to write {}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state() -> UiState {
+        UiState {
+            version: STATE_VERSION,
+            active_tab: Tab::Users,
+            range_days: 180.0,
+            particle_mode: ParticleMode::Starfield,
+            selections: Selections {
+                project: Some(Uuid::from_u128(1)),
+                client: None,
+                user: Some(Uuid::from_u128(3)),
+            },
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let path = std::env::temp_dir()
+            .join(format!("sweem-state-{}", Uuid::new_v4()))
+            .join("ui-state.toml");
+        state().save_to(&path).unwrap();
+        let loaded = UiState::load_from(&path);
+        std::fs::remove_dir_all(path.parent().unwrap()).ok();
+        assert_eq!(loaded.unwrap(), state());
+    }
+
+    #[test]
+    fn test_rejects_corrupt_and_other_versions() {
+        assert!(UiState::parse("active_tab = ").is_err());
+        assert!(UiState::parse("").is_err());
+
+        let future = toml::to_string(&state()).unwrap().replace("version = 1", "version = 99");
+        let err = UiState::parse(&future).unwrap_err();
+        assert!(err.to_string().contains("version 99"));
+    }
+}
@@ -0,0 +1,107 @@
+//! Name suggestions for the project form.
+//!
+//! While a project name is typed, `suggest` offers the rest of a name that
+//! starts with what is there: first the client's next phase ("Acme — Phase
+//! 3", one past the highest phase among its projects), then the client's
+//! existing project names. The form shows it as dim ghost text that Tab
+//! accepts.
+
+use crate::models::ProjectDto;
+
+/// The phase number in a name such as "Acme — Phase 2" or "phase-12 rollout"
+pub fn phase_number(name: &str) -> Option<u32> {
+    let lower = name.to_lowercase();
+    lower.match_indices("phase").find_map(|(at, word)| {
+        let rest = lower[at + word.len()..].trim_start_matches([' ', '-', '_', ':', '#']);
+        let digits: String = rest.chars().take_while(char::is_ascii_digit).collect();
+        digits.parse().ok()
+    })
+}
+
+/// "<Client> — Phase N", N one past the highest phase among `projects`
+pub fn next_phase_name(client: &str, projects: &[&ProjectDto]) -> String {
+    let last = projects.iter().filter_map(|p| p.name.as_deref().and_then(phase_number)).max().unwrap_or(0);
+    format!("{} — Phase {}", client, last + 1)
+}
+
+/// What completes `input` to a suggested name: the next phase of `client`,
+/// else one of its `projects`' names, most recently started first. Matching
+/// ignores case; nothing is offered for an empty input or an exact match.
+pub fn suggest(input: &str, client: Option<&str>, projects: &[&ProjectDto]) -> Option<String> {
+    if input.trim().is_empty() {
+        return None;
+    }
+    let mut names: Vec<&ProjectDto> = projects.to_vec();
+    names.sort_by_key(|p| std::cmp::Reverse(p.start_date));
+    let candidates = client
+        .map(|client| next_phase_name(client, projects))
+        .into_iter()
+        .chain(names.iter().filter_map(|p| p.name.clone()));
+
+    let typed = input.chars().count();
+    let input = input.to_lowercase();
+    candidates
+        .filter(|name| name.chars().count() > typed)
+        .find(|name| name.to_lowercase().starts_with(&input))
+        .map(|name| name.chars().skip(typed).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{project, ProjectFixture};
+
+    #[test]
+    fn test_phase_numbers() {
+        assert_eq!(phase_number("Acme — Phase 2"), Some(2));
+        assert_eq!(phase_number("PHASE 14: rollout"), Some(14));
+        assert_eq!(phase_number("phase-3"), Some(3));
+        assert_eq!(phase_number("Phase #7 (late)"), Some(7));
+        // The first phase with a number counts
+        assert_eq!(phase_number("Rephased phase 4"), Some(4));
+        assert_eq!(phase_number("Phase two"), None);
+        assert_eq!(phase_number("Website relaunch"), None);
+        assert_eq!(phase_number("Phase"), None);
+    }
+
+    #[test]
+    fn test_next_phase_follows_the_highest() {
+        let projects = [
+            project(1).named("Acme — Phase 1").starts("2024-01-01"),
+            project(2).named("Acme — Phase 3").starts("2024-03-01"),
+            project(3).named("Acme website").starts("2024-02-01"),
+        ];
+        let refs: Vec<&ProjectDto> = projects.iter().collect();
+        assert_eq!(next_phase_name("Acme", &refs), "Acme — Phase 4");
+        assert_eq!(next_phase_name("Acme", &refs[2..]), "Acme — Phase 1");
+        assert_eq!(next_phase_name("Acme", &[]), "Acme — Phase 1");
+    }
+
+    #[test]
+    fn test_suggestions_complete_the_input() {
+        let projects = [
+            project(1).named("Acme — Phase 2").starts("2024-01-01"),
+            project(2).named("Website relaunch").starts("2023-01-01"),
+            project(3).named("Website audit").starts("2024-06-01"),
+        ];
+        let refs: Vec<&ProjectDto> = projects.iter().collect();
+        assert_eq!(suggest("Ac", Some("Acme"), &refs).as_deref(), Some("me — Phase 3"));
+        assert_eq!(suggest("acme — phase ", Some("Acme"), &refs).as_deref(), Some("3"));
+        // The most recently started project wins
+        assert_eq!(suggest("web", Some("Acme"), &refs).as_deref(), Some("site audit"));
+        assert_eq!(suggest("Website r", Some("Acme"), &refs).as_deref(), Some("elaunch"));
+        assert_eq!(suggest("Web", None, &refs).as_deref(), Some("site audit"));
+    }
+
+    #[test]
+    fn test_no_suggestion() {
+        let projects = [project(1).named("Website audit").starts("2024-06-01")];
+        let refs: Vec<&ProjectDto> = projects.iter().collect();
+        assert_eq!(suggest("", Some("Acme"), &refs), None);
+        assert_eq!(suggest("  ", Some("Acme"), &refs), None);
+        assert_eq!(suggest("Zeta", Some("Acme"), &refs), None);
+        // Already complete
+        assert_eq!(suggest("website audit", Some("Acme"), &refs), None);
+        assert_eq!(suggest("Acme", None, &[]), None);
+    }
+}
@@ -0,0 +1,137 @@
+//! Fixtures shared by the unit tests.
+//!
+//! `project(n)`, `client(n)` and `user(n)` give an entity with id `n` and
+//! plain defaults; the `*Fixture` methods change what a test cares about:
+//!
+//! ```ignore
+//! project(1).named("Apollo").ends("2024-06-15").done_on("2024-06-10")
+//! ```
+
+use chrono::NaiveDate;
+use uuid::Uuid;
+
+use crate::models::{ClientDto, ProjectDto, Role, UserDto};
+
+/// A date written as `YYYY-MM-DD`
+pub fn date(s: &str) -> NaiveDate {
+    s.parse().unwrap()
+}
+
+/// Project "P`n`", open, 2024-01-01 to 2024-06-01, no client or manager
+pub fn project(n: u128) -> ProjectDto {
+    ProjectDto {
+        id: Uuid::from_u128(n),
+        client_id: Uuid::nil(),
+        name: Some(format!("P{}", n)),
+        start_date: date("2024-01-01"),
+        planned_end_date: date("2024-06-01"),
+        actual_end_date: None,
+        manager_id: Uuid::nil(),
+    }
+}
+
+/// Client "C`n`" without an address or projects
+pub fn client(n: u128) -> ClientDto {
+    ClientDto {
+        id: Uuid::from_u128(n),
+        name: Some(format!("C{}", n)),
+        address: None,
+        projects_total: 0,
+        projects_completed: 0,
+    }
+}
+
+/// Manager "U`n`" without a login
+pub fn user(n: u128) -> UserDto {
+    UserDto {
+        id: Uuid::from_u128(n),
+        name: Some(format!("U{}", n)),
+        login: None,
+        role: Role::Manager,
+    }
+}
+
+pub trait ProjectFixture {
+    fn named(self, name: &str) -> Self;
+    fn client(self, n: u128) -> Self;
+    fn manager(self, n: u128) -> Self;
+    fn starts(self, start: &str) -> Self;
+    fn ends(self, end: &str) -> Self;
+    /// Running from `start` to `end` days relative to today
+    fn around_today(self, start: i64, end: i64) -> Self;
+    /// Completed on `day`
+    fn done_on(self, day: &str) -> Self;
+    /// Completed on its planned end
+    fn done(self) -> Self;
+}
+
+impl ProjectFixture for ProjectDto {
+    fn named(self, name: &str) -> Self {
+        ProjectDto { name: Some(name.to_string()), ..self }
+    }
+
+    fn client(self, n: u128) -> Self {
+        ProjectDto { client_id: Uuid::from_u128(n), ..self }
+    }
+
+    fn manager(self, n: u128) -> Self {
+        ProjectDto { manager_id: Uuid::from_u128(n), ..self }
+    }
+
+    fn starts(self, start: &str) -> Self {
+        ProjectDto { start_date: date(start), ..self }
+    }
+
+    fn ends(self, end: &str) -> Self {
+        ProjectDto { planned_end_date: date(end), ..self }
+    }
+
+    fn around_today(self, start: i64, end: i64) -> Self {
+        let today = chrono::Local::now().date_naive();
+        let day = |offset: i64| today + chrono::Duration::days(offset);
+        ProjectDto { start_date: day(start), planned_end_date: day(end), ..self }
+    }
+
+    fn done_on(self, day: &str) -> Self {
+        ProjectDto { actual_end_date: Some(date(day)), ..self }
+    }
+
+    fn done(self) -> Self {
+        ProjectDto { actual_end_date: Some(self.planned_end_date), ..self }
+    }
+}
+
+pub trait ClientFixture {
+    fn named(self, name: &str) -> Self;
+    fn address(self, address: &str) -> Self;
+}
+
+impl ClientFixture for ClientDto {
+    fn named(self, name: &str) -> Self {
+        ClientDto { name: Some(name.to_string()), ..self }
+    }
+
+    fn address(self, address: &str) -> Self {
+        ClientDto { address: Some(address.to_string()), ..self }
+    }
+}
+
+pub trait UserFixture {
+    fn named(self, name: &str) -> Self;
+    fn login(self, login: &str) -> Self;
+    fn role(self, role: Role) -> Self;
+}
+
+impl UserFixture for UserDto {
+    fn named(self, name: &str) -> Self {
+        UserDto { name: Some(name.to_string()), ..self }
+    }
+
+    fn login(self, login: &str) -> Self {
+        UserDto { login: Some(login.to_string()), ..self }
+    }
+
+    fn role(self, role: Role) -> Self {
+        UserDto { role, ..self }
+    }
+}
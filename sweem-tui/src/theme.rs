@@ -6,6 +6,9 @@
 #![allow(dead_code)]
 
 use ratatui::style::Color;
+use uuid::Uuid;
+
+use crate::models::ProjectStatus;
 
 /// Kanagawa Dragon color palette
 /// Low-contrast, warm, dark theme inspired by traditional Japanese ink wash painting
@@ -81,10 +84,12 @@ pub mod colors {
     pub const PROJECT_COMPLETED: Color = GREEN;
     /// In-progress project bar
     pub const PROJECT_ACTIVE: Color = BLUE;
+    /// Unsaved date change being previewed
+    pub const PREVIEW: Color = ORANGE;
 
 }
 
-/// Color palette for project bars in the timeline
+/// Color palette for projects and client sectors, picked by `palette_color`
 /// Vibrant, distinct colors for easy project differentiation
 /// Uses a rainbow-like progression for maximum visual clarity
 pub const PROJECT_COLORS: &[Color] = &[
@@ -102,13 +107,48 @@ pub const PROJECT_COLORS: &[Color] = &[
     Color::Rgb(0x89, 0xDD, 0xFF), // Sky blue - Project 12
 ];
 
-/// Get a dimmed version of a project color (for secondary elements)
-pub fn get_project_color_dim(index: usize) -> Color {
-    let base = PROJECT_COLORS[index % PROJECT_COLORS.len()];
-    if let Color::Rgb(r, g, b) = base {
-        Color::Rgb(r / 2, g / 2, b / 2)
-    } else {
-        base
+/// Heatmap backgrounds from idle to overloaded, picked by `heat_color`
+pub const HEAT_RAMP: &[Color] = &[
+    colors::BG_HIGHLIGHT,
+    Color::Rgb(0x2F, 0x45, 0x3A),
+    Color::Rgb(0x4E, 0x6B, 0x4A),
+    Color::Rgb(0x8A, 0x7F, 0x45),
+    Color::Rgb(0xA8, 0x63, 0x3D),
+    Color::Rgb(0xB0, 0x3E, 0x3E),
+];
+
+/// Background of a heatmap cell holding `count`, where `max` is the busiest cell
+pub fn heat_color(count: usize, max: usize) -> Color {
+    if count == 0 {
+        return HEAT_RAMP[0];
+    }
+    let steps = HEAT_RAMP.len() - 1;
+    let step = (count * steps).div_ceil(max.max(count));
+    HEAT_RAMP[step.clamp(1, steps)]
+}
+
+/// Color for a project status, as used by the details panel and radar markers
+pub fn status_color(status: ProjectStatus) -> Color {
+    match status {
+        ProjectStatus::Completed => colors::GREEN,
+        ProjectStatus::Overdue => colors::RED,
+        ProjectStatus::Pending | ProjectStatus::Unscheduled => colors::FG_DIM,
+        ProjectStatus::Active => colors::BLUE,
+    }
+}
+
+/// Palette colour for a project or client, derived from its id so it stays
+/// the same across reloads, sort orders and views
+pub fn palette_color(id: Uuid) -> Color {
+    let hash = id.as_bytes().iter().fold(0u32, |h, &b| h.wrapping_mul(31).wrapping_add(b as u32));
+    PROJECT_COLORS[hash as usize % PROJECT_COLORS.len()]
+}
+
+/// Get a dimmed version of a palette color (for secondary elements)
+pub fn palette_color_dim(id: Uuid) -> Color {
+    match palette_color(id) {
+        Color::Rgb(r, g, b) => Color::Rgb(r / 2, g / 2, b / 2),
+        base => base,
     }
 }
 
@@ -152,6 +192,11 @@ pub mod styles {
         Style::default().fg(colors::BLUE)
     }
 
+    /// Style for values that are previewed but not saved yet
+    pub fn preview() -> Style {
+        Style::default().fg(colors::PREVIEW).add_modifier(Modifier::ITALIC)
+    }
+
     /// Style for selected/highlighted items
     pub fn selected() -> Style {
         Style::default()
@@ -253,8 +298,3 @@ pub mod styles {
         Style::default().bg(colors::BG_MEDIUM)
     }
 }
-
-/// Get a project color by index (cycles through available colors)
-pub fn get_project_color(index: usize) -> Color {
-    PROJECT_COLORS[index % PROJECT_COLORS.len()]
-}
@@ -3,52 +3,287 @@
 //! This module handles all the TUI rendering using ratatui,
 //! implementing the Kanagawa Dragon aesthetic with CRUD forms.
 
-use chrono::{Datelike, NaiveDate};
+use std::rc::Rc;
+use std::time::Instant;
+
+use chrono::{Datelike, NaiveDate, Weekday};
 use ratatui::{
-    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    layout::{Alignment, Constraint, Direction, Layout, Margin, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Clear, List, ListItem, Paragraph, Tabs, Wrap},
+    widgets::{
+        Block, Borders, Clear, List, ListItem, ListState, Padding, Paragraph, Scrollbar, ScrollbarOrientation,
+        ScrollbarState, Tabs, Wrap,
+    },
     Frame,
 };
 
-use crate::app::{App, FormField, FormState, FormType, LogLevel, Tab};
-use crate::models::Role;
+use crate::agenda::AgendaWidget;
+use crate::api::{self, EntityType};
+use crate::calendar::{self, weekday_style, CalendarWidget, EventKind};
+use crate::diff::Badge;
+use crate::form::{ClientForm, FormField, FormInput, FormState, FormType, ProjectForm, Staleness, UserForm};
+use crate::app::{
+    format_count, App, ConfirmButton, ConfirmDialog, EditReview, ErrorPopup, FilterPopup, HistoryPopup, LoadState,
+    LogLevel, PageInfo, Pane, LogEntry, PopupScroll, PresetPicker, Tab, Toast, TOAST_FRAMES,
+};
+use crate::config;
+use crate::dates::{format_date, parse_date, DateFormat, WeekStart};
+use crate::models::{client_project_counts, is_valid_date, ChangeRecord, ProjectStatus, Role, UserDto};
 use crate::particles::ParticleWidget;
-use crate::theme::{colors, styles};
+use crate::presets::MISSING;
+use crate::theme::{colors, status_color, styles};
 use crate::radar::RadarWidget;
+use crate::upcoming::{UpcomingWidget, UPCOMING};
+use crate::user_groups::{self, UserRow};
+use crate::widgets::{TextInput, TextInputWidget};
+use crate::workload::WorkloadWidget;
+
+/// Minimum terminal width the normal UI is laid out for
+pub const MIN_WIDTH: u16 = 60;
+/// Minimum terminal height the normal UI is laid out for
+pub const MIN_HEIGHT: u16 = 20;
+/// Below this width the Timeline and Clients tabs stack their two panes
+pub const NARROW_WIDTH: u16 = 100;
+/// Below this height the System Log shrinks to its latest line (`v` expands it)
+pub const SHORT_HEIGHT: u16 = 30;
+/// Height of the System Log, borders included
+const LOG_HEIGHT: u16 = 5;
+
+/// Drawn on the startup splash
+const LOGO: [&str; 5] = [
+    "╔═╗ ╦ ╦ ╔═╗ ╔═╗ ╔╦╗",
+    "╚═╗ ║║║ ║╣  ║╣  ║║║",
+    "╚═╝ ╚╩╝ ╚═╝ ╚═╝ ╩ ╩",
+    "",
+    "Management Console",
+];
+
+/// Startup screen, drawn as soon as the terminal is ready and until the API
+/// first answers
+#[derive(Debug, Clone)]
+pub struct Splash {
+    pub url: String,
+    started: Instant,
+}
+
+impl Splash {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self { url: url.into(), started: Instant::now() }
+    }
+
+    /// Spinner frame by wall time, so it turns however often frames come
+    fn spinner(&self) -> char {
+        SPINNER[(self.started.elapsed().as_millis() / 80) as usize % SPINNER.len()]
+    }
+}
+
+/// Rows of the screen: tabs, main content, System Log and status bar
+fn screen_layout(app: &App, area: Rect) -> Rc<[Rect]> {
+    let log_height = if area.height < SHORT_HEIGHT && !app.log_expanded { 1 } else { LOG_HEIGHT };
+    Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),          // Tabs
+            Constraint::Min(10),            // Main content
+            Constraint::Length(log_height), // Log area
+            Constraint::Length(1),          // Status bar
+        ])
+        .split(area)
+}
+
+/// The breadcrumb row, when one is shown, and the tab's content below it
+fn content_rows(breadcrumb: bool, area: Rect) -> (Option<Rect>, Rect) {
+    if !breadcrumb {
+        return (None, area);
+    }
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(0)])
+        .split(area);
+    (Some(rows[0]), rows[1])
+}
+
+/// The radar and, when the agenda is shown, its row under the radar
+fn radar_rows(app: &App, pane: Rect) -> (Rect, Option<Rect>) {
+    if !app.agenda_shown() {
+        return (pane, None);
+    }
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(1)])
+        .split(pane);
+    (rows[0], Some(rows[1]))
+}
+
+/// Inside of the radar's border on a screen of `area`; None when the radar
+/// is not drawn there
+fn radar_area(app: &App, area: Rect) -> Option<Rect> {
+    let drawn = area.width >= MIN_WIDTH
+        && area.height >= MIN_HEIGHT
+        && app.splash.is_none()
+        && app.setup.is_none()
+        && app.active_tab == Tab::Timeline
+        && app.calendar.is_none();
+    if !drawn {
+        return None;
+    }
+    let (_, content) = content_rows(app.breadcrumb().is_some(), screen_layout(app, area)[1]);
+    let (radar, _) = radar_rows(app, split_panes(content)[0]);
+    Some(radar.inner(Margin::new(1, 1)))
+}
+
+/// Store what input needs from a screen of `area` on `app`, ahead of
+/// `render` drawing it
+pub fn layout(app: &mut App, area: Rect) {
+    app.radar_area = radar_area(app, area);
+
+    // Fit each open popup's scroll to its text, laid out as render draws it
+    if let Some(popup) = &mut app.history {
+        let text_area = history_text_area(area);
+        let lines = history_text(popup, app.date_format).line_count(text_area.width);
+        popup.scroll.fit(lines, text_area.height);
+    }
+    if let Some(dialog) = &mut app.confirm_dialog {
+        let text_area = confirm_rows(confirm_area(dialog, area))[0];
+        let lines = confirm_message(dialog).line_count(text_area.width);
+        dialog.scroll.fit(lines, text_area.height);
+    }
+    if let Some(popup) = &mut app.error_popup {
+        let text_area = error_popup_area(popup, area).inner(Margin::new(1, 1));
+        let lines = error_message(popup).line_count(text_area.width);
+        popup.scroll.fit(lines, text_area.height);
+    }
+    if app.show_help {
+        let text_area = help_text_area(area);
+        let lines = help_text(app).line_count(text_area.width);
+        app.help_scroll.fit(lines, text_area.height);
+    }
+
+    // Text inputs scroll along with their cursor; give each open one its width
+    let chunks = screen_layout(app, area);
+    let inside = |popup: Rect| popup.inner(Margin::new(1, 1)).width;
+    let warning_rows = app.form_state.as_ref().map_or(0, |form| form_warning_lines(app, form).len() as u16);
+    let preset_area = preset_picker_area(app, area);
+    // A project is renamed in the details header, inside its border and margin
+    let details = if app.details_popup {
+        details_popup_area(area)
+    } else {
+        split_panes(content_rows(app.breadcrumb().is_some(), chunks[1]).1)[1]
+    };
+    if let Some(setup) = &mut app.setup {
+        let row = setup_rows(setup_area(chunks[0].union(chunks[1])))[2];
+        setup.url.set_width(field_text_width(row.width));
+    }
+    if let Some(form) = &mut app.form_state {
+        let width = field_text_width(form_area(form, warning_rows, area).inner(Margin::new(2, 2)).width);
+        if let Some(input) = form.text_mut(form.current_field()) {
+            input.set_width(width);
+        }
+    }
+    if let Some(edit) = &mut app.inline_edit {
+        let width = match edit.entity {
+            EntityType::Project => details.width.saturating_sub(4) as usize,
+            _ => NAME_COLUMNS,
+        };
+        edit.buffer.set_width(width);
+    }
+    if let Some(prompt) = &mut app.url_prompt {
+        prompt.url.set_width(field_text_width(inside(url_prompt_area(area))));
+    }
+    if let Some(input) = &mut app.date_jump {
+        input.set_width(field_text_width(inside(date_jump_area(area))));
+    }
+    if let Some(input) = &mut app.import_prompt {
+        input.set_width(field_text_width(inside(import_prompt_area(area))));
+    }
+    if let Some(name) = app.preset_picker.as_mut().and_then(|picker| picker.naming.as_mut()) {
+        name.set_width(field_text_width(inside(preset_area)));
+    }
+    if let Some(popup) = &mut app.filter_popup {
+        popup.text.set_width(filter_text_width(filter_popup_area(area)));
+    }
+    if let Some(search) = &mut app.search {
+        search.query.set_width(query_area(search_area(area).inner(Margin::new(1, 1))).width as usize);
+    }
+    if let Some(picker) = &mut app.move_picker {
+        picker.query.set_width(query_area(move_picker_area(area).inner(Margin::new(1, 1))).width as usize);
+    }
+}
+
+/// A tab's main and side panes: side by side, or stacked when narrow
+fn split_panes(area: Rect) -> Rc<[Rect]> {
+    let (direction, constraints) = if area.width < NARROW_WIDTH {
+        (Direction::Vertical, [Constraint::Percentage(60), Constraint::Percentage(40)])
+    } else {
+        (Direction::Horizontal, [Constraint::Percentage(65), Constraint::Percentage(35)])
+    };
+    Layout::default().direction(direction).constraints(constraints).split(area)
+}
 
 /// Render the entire UI
 pub fn render(frame: &mut Frame, app: &App) {
     let area = frame.area();
 
+    if area.width < MIN_WIDTH || area.height < MIN_HEIGHT {
+        render_too_small(frame, area);
+        return;
+    }
+
+    if let Some(splash) = &app.splash {
+        render_splash(frame, splash);
+        return;
+    }
+
     // Fill background with theme color
     let bg_block = Block::default().style(Style::default().bg(colors::BG_DARK));
     frame.render_widget(bg_block, area);
 
-    // Render background particles
-    frame.render_widget(ParticleWidget::new(&app.particle_system), area);
+    // Render background particles, kept off the open overlays and their edges
+    let overlays = overlay_areas(app, area);
+    frame.render_widget(ParticleWidget::new(&app.particle_system).exclude(overlays), area);
 
     // Create main layout
-    let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Length(3),  // Status bar / tabs
-            Constraint::Min(10),    // Main content
-            Constraint::Length(5),  // Log area
-        ])
-        .split(area);
+    let chunks = screen_layout(app, area);
+
+    // The setup screen replaces tabs and content until the API is reachable
+    if app.setup.is_some() {
+        let content = chunks[0].union(chunks[1]);
+        render_setup_screen(frame, app, content);
+        render_logs(frame, app, chunks[2]);
+        render_status_bar(frame, app, chunks[3]);
+        if app.error_popup.is_some() {
+            render_error_popup(frame, app, area);
+        }
+        if app.profile_switcher.is_some() {
+            render_profile_switcher(frame, app, area);
+        }
+        return;
+    }
 
     // Render components
     render_tabs(frame, app, chunks[0]);
-    render_main_content(frame, app, chunks[1]);
+    let crumb = app.breadcrumb();
+    let (crumb_row, content) = content_rows(crumb.is_some(), chunks[1]);
+    if let (Some(crumb), Some(row)) = (crumb, crumb_row) {
+        render_breadcrumb(frame, crumb, row);
+    }
+    render_main_content(frame, app, content);
     render_logs(frame, app, chunks[2]);
+    render_status_bar(frame, app, chunks[3]);
+
+    // Toasts sit above the content but below every modal
+    render_toasts(frame, app, content);
 
     // Render overlays (modals, dialogs)
     if app.form_state.is_some() {
         render_form_modal(frame, app, area);
     }
 
+    if let Some(review) = &app.edit_review {
+        render_edit_review(frame, review, area);
+    }
+
     if app.confirm_dialog.is_some() {
         render_confirm_dialog(frame, app, area);
     }
@@ -57,14 +292,198 @@ pub fn render(frame: &mut Frame, app: &App) {
         render_error_popup(frame, app, area);
     }
 
+    if app.details_popup {
+        render_details_popup(frame, app, area);
+    }
+
+    if let Some(popup) = &app.history {
+        render_history_popup(frame, popup, app.date_format, area);
+    }
+
     if app.show_help {
-        render_help_overlay(frame, area);
+        render_help_overlay(frame, app, area);
+    }
+
+    if app.profile_switcher.is_some() {
+        render_profile_switcher(frame, app, area);
+    }
+
+    if app.import_prompt.is_some() {
+        render_import_prompt(frame, app, area);
+    }
+
+    if app.url_prompt.is_some() {
+        render_url_prompt(frame, app, area);
+    }
+
+    if let Some(input) = &app.date_jump {
+        render_date_jump(frame, input, area);
+    }
+
+    if app.filter_popup.is_some() {
+        render_filter_popup(frame, app, area);
+    }
+
+    if let Some(picker) = &app.preset_picker {
+        render_preset_picker(frame, app, picker, area);
+    }
+
+    if app.search.is_some() {
+        render_search_overlay(frame, app, area);
+    }
+
+    if app.move_picker.is_some() {
+        render_move_picker(frame, app, area);
+    }
+
+    if app.show_frame_stats {
+        render_frame_stats(frame, app, chunks[1]);
+    }
+}
+
+/// Debug overlay (F12): measured frame rate, render time, particle count,
+/// channel depths and the latest fetches in the bottom-right corner of `area`
+fn render_frame_stats(frame: &mut Frame, app: &App, area: Rect) {
+    let stats = &app.frame_stats;
+    let fps = stats.fps().map_or("-".to_string(), |fps| format!("{:.1}", fps));
+    let mut lines = vec![
+        Line::from(format!(" fps {} / {}", fps, stats.target_fps)),
+        Line::from(format!(" render {:.1} ms", stats.render_time.as_secs_f64() * 1000.0)),
+        Line::from(format!(" particles {}", app.particle_system.count())),
+        Line::from(format!(" queue api {} · cmd {}", stats.api_queue, stats.command_queue)),
+    ];
+    // The latest fetch of each collection, to tell a slow server from a slow TUI
+    for entity in [EntityType::Project, EntityType::Client, EntityType::User] {
+        if let Some(fetch) = app.fetch_stats.get(&entity) {
+            lines.push(Line::from(format!(" {}", fetch.describe(entity))));
+        }
+    }
+    let widest = lines.iter().map(|l| l.width() as u16 + 3).max().unwrap_or(0);
+    let width = widest.max(26).min(area.width);
+    let height = (lines.len() as u16 + 2).min(area.height);
+    let corner = Rect::new(area.right() - width, area.bottom() - height, width, height);
+    frame.render_widget(Clear, corner);
+    let panel = Paragraph::new(lines).style(styles::text_dim()).block(
+        Block::default()
+            .title(" F12 ")
+            .borders(Borders::ALL)
+            .border_style(styles::border_dim())
+            .style(Style::default().bg(colors::BG_DARK)),
+    );
+    frame.render_widget(panel, corner);
+}
+
+/// Render the guard screen shown when the terminal is below the minimum size
+fn render_too_small(frame: &mut Frame, area: Rect) {
+    let bg_block = Block::default().style(Style::default().bg(colors::BG_DARK));
+    frame.render_widget(bg_block, area);
+
+    let message = format!(
+        "Terminal too small (needs {}x{}, have {}x{})",
+        MIN_WIDTH, MIN_HEIGHT, area.width, area.height
+    );
+    let paragraph = Paragraph::new(vec![
+        Line::from(Span::styled(message, styles::warning())),
+        Line::from(Span::styled("Resize the window to continue", styles::text_hint())),
+    ])
+    .alignment(Alignment::Center)
+    .wrap(Wrap { trim: true });
+
+    let y = area.y + area.height.saturating_sub(2) / 2;
+    let message_area = Rect::new(area.x, y, area.width, area.height.saturating_sub(y - area.y));
+    frame.render_widget(paragraph, message_area);
+}
+
+/// The startup splash: logo, the URL being connected to and a spinner
+pub fn render_splash(frame: &mut Frame, splash: &Splash) {
+    let area = frame.area();
+    frame.render_widget(Block::default().style(Style::default().bg(colors::BG_DARK)), area);
+
+    let mut lines: Vec<Line> = LOGO.iter().map(|row| Line::styled(*row, styles::title_accent())).collect();
+    lines.push(Line::from(""));
+    lines.push(Line::from(vec![
+        Span::styled(format!("{} ", splash.spinner()), styles::info()),
+        Span::styled(format!("connecting to {}…", splash.url), styles::text()),
+    ]));
+    lines.push(Line::styled("q to quit", styles::text_hint()));
+
+    let height = lines.len() as u16;
+    let y = area.y + area.height.saturating_sub(height) / 2;
+    let text_area = Rect::new(area.x, y, area.width, height.min(area.height));
+    frame.render_widget(Paragraph::new(lines).alignment(Alignment::Center), text_area);
+}
+
+/// Render the first-run setup screen (API unreachable)
+fn render_setup_screen(frame: &mut Frame, app: &App, area: Rect) {
+    let setup = match &app.setup {
+        Some(s) => s,
+        None => return,
+    };
+
+    let popup_area = setup_area(area);
+    frame.render_widget(Clear, popup_area);
+
+    let block = Block::default()
+        .title(" Connect to the SWEeM API ")
+        .title_style(styles::title_accent())
+        .borders(Borders::ALL)
+        .border_style(styles::border_focused())
+        .style(Style::default().bg(colors::BG_MEDIUM));
+    frame.render_widget(block, popup_area);
+
+    let chunks = setup_rows(popup_area);
+
+    let intro = vec![
+        Line::from(Span::styled("Could not reach the API at:", styles::text())),
+        Line::from(Span::styled(app.api_url.as_str(), styles::info())),
+    ];
+    frame.render_widget(Paragraph::new(intro), chunks[0]);
+
+    if let Some(error) = &setup.error {
+        frame.render_widget(Paragraph::new(error.as_str()).style(styles::error()), chunks[1]);
     }
+
+    render_text_field(frame, "API URL:", &setup.url, !setup.retry_focused, chunks[2]);
+
+    let (label, style) = if setup.retrying {
+        (" Connecting... ", styles::warning())
+    } else if setup.retry_focused {
+        ("  [ Retry ]  ", styles::button_focused())
+    } else {
+        ("  [ Retry ]  ", styles::button())
+    };
+    frame.render_widget(
+        Paragraph::new(label).style(style).alignment(Alignment::Center),
+        centered_rect(14, 1, chunks[3]),
+    );
+
+    let config_hint = match config::Config::path() {
+        Some(path) => format!("Or set api_url in {}", path.display()),
+        None => "Or set api_url in the config file".to_string(),
+    };
+    let hints = vec![
+        Line::from(Span::styled(
+            "Tip: pass the URL as an argument: sweem-tui http://host:port",
+            styles::text_hint(),
+        )),
+        Line::from(Span::styled(config_hint, styles::text_hint())),
+    ];
+    frame.render_widget(Paragraph::new(hints).wrap(Wrap { trim: true }), chunks[5]);
 }
 
 /// Render the tab bar
+/// The non-default view settings under the tab bar, with the reset key
+fn render_breadcrumb(frame: &mut Frame, crumb: String, area: Rect) {
+    let line = Line::from(vec![
+        Span::styled(format!(" {}", crumb), styles::info()),
+        Span::styled("  ^X reset", styles::text_hint()),
+    ]);
+    frame.render_widget(Paragraph::new(line).style(Style::default().bg(colors::BG_MEDIUM)), area);
+}
+
 fn render_tabs(frame: &mut Frame, app: &App, area: Rect) {
-    let titles: Vec<Line> = [Tab::Clients, Tab::Timeline, Tab::Users]
+    let visible = Tab::visible(app.role());
+    let titles: Vec<Line> = visible
         .iter()
         .map(|tab| {
             let style = if *tab == app.active_tab {
@@ -85,11 +504,7 @@ fn render_tabs(frame: &mut Frame, app: &App, area: Rect) {
                 .border_style(styles::border())
                 .style(Style::default().bg(colors::BG_MEDIUM)),
         )
-        .select(match app.active_tab {
-            Tab::Clients => 0,
-            Tab::Timeline => 1,
-            Tab::Users => 2,
-        })
+        .select(visible.iter().position(|tab| *tab == app.active_tab).unwrap_or(0))
         .style(styles::text())
         .highlight_style(styles::tab_active())
         .divider(Span::styled(" | ", styles::border_dim()));
@@ -107,19 +522,214 @@ fn render_main_content(frame: &mut Frame, app: &App, area: Rect) {
 }
 
 fn render_timeline_view(frame: &mut Frame, app: &App, area: Rect) {
-    let chunks = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([
-            Constraint::Percentage(65),
-            Constraint::Percentage(35),
-        ])
-        .split(area);
+    let chunks = split_panes(area);
 
     // FIX: Pass clients to radar for labels
-    let radar = RadarWidget::new(&app.projects, &app.clients, &app.radar_state);
+    let visible = app.visible_projects();
+    let badges = app.active_badges();
+    let mut title = " Orbital Command ".to_string();
+    if let Some(page) = app.project_page_label() {
+        title = format!(" Orbital Command — {} ", page);
+    }
+    if app.project_filter.is_active() {
+        title.push_str(&format!("— {} ✕ Esc to clear ", app.project_filter.describe(&app.filter_deps())));
+    }
+    if let Some(calendar) = &app.calendar {
+        let today = chrono::Local::now().date_naive();
+        let widget = CalendarWidget::new(&app.projects, &visible, calendar)
+            .week_start(app.week_start)
+            .today(today);
+        frame.render_widget(widget, chunks[0]);
+        render_side_panel(frame, app, chunks[1]);
+        if let Some(selected) = calendar.day_popup {
+            render_day_popup(frame, app, calendar.cursor, selected, today, area);
+        }
+        return;
+    }
+    let (radar_row, agenda_row) = radar_rows(app, chunks[0]);
+    if let Some(row) = agenda_row {
+        let days = app.agenda();
+        let cursor = (app.focused_pane() == Pane::Agenda).then_some(app.agenda_day);
+        frame.render_widget(AgendaWidget::new(&days).cursor(cursor), row);
+    }
+    let chunks = [radar_row, chunks[1]];
+    let radar = RadarWidget::new(&app.projects, &app.clients, &app.radar_state)
+        .title(title)
+        .visible(&visible)
+        .flashing(app.flashing())
+        .focused(app.focused_pane() == Pane::Primary)
+        .badges(&badges)
+        .marked(&app.marked)
+        .date_mark(app.jump_mark())
+        .celebration(app.celebration.as_ref(), app.frame_count);
     frame.render_widget(radar, chunks[0]);
+    if app.projects.is_empty() {
+        render_empty_state(frame, chunks[0], empty_state(app, EntityType::Project, "No projects found"));
+    } else if visible.is_empty() {
+        let message = "No projects match the filter (F to change)";
+        render_empty_state(frame, chunks[0], (message.to_string(), styles::text_dim()));
+    }
+
+    render_side_panel(frame, app, chunks[1]);
+}
+
+/// Target Analysis, with the upcoming deadlines under it when there's room
+fn render_side_panel(frame: &mut Frame, app: &App, area: Rect) {
+    let list_height = UPCOMING as u16 + 2;
+    if area.height < list_height + 16 {
+        render_project_details(frame, app, area);
+        return;
+    }
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(16), Constraint::Length(list_height)])
+        .split(area);
+    render_project_details(frame, app, chunks[0]);
+
+    let entries = app.upcoming();
+    let list = UpcomingWidget::new(&app.projects, &app.clients, &entries, chrono::Local::now().date_naive())
+        .thresholds(app.deadline_thresholds)
+        .day_count(app.day_count)
+        .cursor((app.focused_pane() == Pane::Secondary).then_some(app.upcoming_row));
+    frame.render_widget(list, chunks[1]);
+}
+
+/// Every start and deadline on the calendar's day, with the selected one
+/// highlighted for jumping to
+fn render_day_popup(frame: &mut Frame, app: &App, day: NaiveDate, selected: usize, today: NaiveDate, area: Rect) {
+    let events = app.calendar_events(day);
+    let lines: Vec<Line> = events
+        .iter()
+        .enumerate()
+        .map(|(row, event)| {
+            let project = &app.projects[event.project];
+            let color = calendar::project_color(&app.projects, event.project, today);
+            let (marker_style, name_style) = if row == selected {
+                let style = styles::selected();
+                (style, style)
+            } else {
+                (Style::default().fg(color), styles::text())
+            };
+            let other_end = match event.kind {
+                EventKind::Start => format!("due {}", format_date(&project.planned_end_date, app.date_format)),
+                EventKind::Deadline => format!("since {}", format_date(&project.start_date, app.date_format)),
+            };
+            Line::from(vec![
+                Span::styled(format!(" {} ", event.kind.marker()), marker_style),
+                Span::styled(format!("{} {} ", project.display_name(), event.kind.label()), name_style),
+                Span::styled(format!("({})", other_end), styles::text_dim()),
+            ])
+        })
+        .collect();
+
+    let height = (lines.len() as u16 + 2).min(area.height);
+    let popup_area = centered_rect(56, height, area);
+    frame.render_widget(Clear, popup_area);
+    // Busy days can hold more events than fit; keep the selected one in view
+    let first = (selected as u16).saturating_sub(height.saturating_sub(3));
+    let popup = Paragraph::new(lines).scroll((first, 0)).block(
+        Block::default()
+            .title(format!(" {} ", format_date(&day, app.date_format)))
+            .title_style(styles::title())
+            .title_bottom(Line::from(" Enter jump · Esc close ").style(styles::text_hint()))
+            .borders(Borders::ALL)
+            .border_style(styles::border_focused())
+            .style(Style::default().bg(colors::BG_MEDIUM)),
+    );
+    frame.render_widget(popup, popup_area);
+}
+
+/// Shown in place of placeholder dates
+const NOT_SCHEDULED: &str = "Not scheduled";
+
+/// A project date for the details panel, or "Not scheduled" for a placeholder
+fn schedule_date(date: NaiveDate, date_format: DateFormat) -> String {
+    if is_valid_date(date) {
+        format_date(&date, date_format)
+    } else {
+        NOT_SCHEDULED.to_string()
+    }
+}
+
+/// The details panel as a popup over the radar (Enter on the Timeline)
+fn render_details_popup(frame: &mut Frame, app: &App, area: Rect) {
+    let popup_area = details_popup_area(area);
+    frame.render_widget(Clear, popup_area);
+    render_project_details(frame, app, popup_area);
+}
+
+/// A project's change history, newest first ('i' on the Timeline)
+fn render_history_popup(frame: &mut Frame, popup: &HistoryPopup, date_format: DateFormat, area: Rect) {
+    let popup_area = history_area(area);
+    frame.render_widget(Clear, popup_area);
+    let block = Block::default()
+        .title(format!(" History: {} ", popup.name))
+        .title_style(styles::title_accent())
+        .borders(Borders::ALL)
+        .border_style(styles::border_focused())
+        .style(Style::default().bg(colors::BG_MEDIUM));
+    frame.render_widget(block, popup_area);
+    let text = history_text(popup, date_format);
+    render_scrolled(frame, text, &popup.scroll, history_text_area(area), popup_area);
+}
 
-    render_project_details(frame, app, chunks[1]);
+/// The history popup's records, or what stands in for them
+fn history_text(popup: &HistoryPopup, date_format: DateFormat) -> Paragraph<'static> {
+    let lines = match &popup.records {
+        None => vec![Line::styled("Loading…", styles::text_hint())],
+        Some(Err(reason)) => vec![Line::styled(format!("Could not load history ({})", reason), styles::error())],
+        Some(Ok(records)) if records.is_empty() => vec![Line::styled("No changes recorded", styles::text_hint())],
+        Some(Ok(records)) => records.iter().map(|record| history_line(record, date_format)).collect(),
+    };
+    Paragraph::new(lines).wrap(Wrap { trim: false })
+}
+
+/// "2024-05-01 14:02 ada  name: Old → New", the old value struck out in red
+/// and the new one in green
+fn history_line(record: &ChangeRecord, date_format: DateFormat) -> Line<'static> {
+    let at = record.timestamp.with_timezone(&chrono::Local);
+    let when = format!("{} {}", format_date(&at.date_naive(), date_format), at.format("%H:%M"));
+    let value = |v: &Option<String>| v.clone().unwrap_or_else(|| "∅".to_string());
+    Line::from(vec![
+        Span::styled(when, styles::text_dim()),
+        Span::styled(format!(" {} ", record.user.as_deref().unwrap_or("unknown")), styles::text()),
+        Span::styled(format!("{}: ", record.field), Style::default().fg(colors::BLUE)),
+        Span::styled(value(&record.old), Style::default().fg(colors::RED).add_modifier(Modifier::CROSSED_OUT)),
+        Span::styled(" → ", styles::text_dim()),
+        Span::styled(value(&record.new), Style::default().fg(colors::GREEN)),
+    ])
+}
+
+/// The fields a submitted edit form changes, over the form, until Enter
+/// sends it or Esc goes back to editing
+fn render_edit_review(frame: &mut Frame, review: &EditReview, area: Rect) {
+    let popup_area = centered_rect(64, review.changes.len() as u16 + 4, area);
+    frame.render_widget(Clear, popup_area);
+    let block = Block::default()
+        .title(format!(" {} ", review.title))
+        .title_style(styles::title_accent())
+        .borders(Borders::ALL)
+        .border_style(styles::border_focused())
+        .style(Style::default().bg(colors::BG_MEDIUM));
+    let inner = block.inner(popup_area).inner(Margin::new(1, 0));
+    frame.render_widget(block, popup_area);
+
+    let value = |v: &str| if v.is_empty() { "∅".to_string() } else { v.to_string() };
+    let mut lines: Vec<Line> = review
+        .changes
+        .iter()
+        .map(|change| {
+            Line::from(vec![
+                Span::styled(format!("{}: ", change.field), Style::default().fg(colors::BLUE)),
+                Span::styled(value(&change.old), Style::default().fg(colors::RED).add_modifier(Modifier::CROSSED_OUT)),
+                Span::styled(" → ", styles::text_dim()),
+                Span::styled(value(&change.new), Style::default().fg(colors::GREEN)),
+            ])
+        })
+        .collect();
+    lines.push(Line::default());
+    lines.push(Line::styled("Enter send · Esc back to the form", styles::text_hint()));
+    frame.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }), inner);
 }
 
 fn render_project_details(frame: &mut Frame, app: &App, area: Rect) {
@@ -150,11 +760,18 @@ fn render_project_details(frame: &mut Frame, app: &App, area: Rect) {
             .split(inner_area);
 
         // -- Header --
-        let mut text = vec![
-            Line::from(Span::styled(
-                p.display_name(), 
-                Style::default().fg(colors::FG_PRIMARY).add_modifier(Modifier::BOLD | Modifier::UNDERLINED)
-            )),
+        let mut name = Line::from(match app.inline_edit.as_ref().filter(|e| e.id == p.id) {
+            Some(edit) => edit.buffer.spans(details_chunks[0].width as usize, true, styles::form_input_focused()),
+            None => vec![Span::styled(
+                p.display_name(),
+                Style::default().fg(colors::FG_PRIMARY).add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+            )],
+        });
+        if let Some(badge) = app.badge(p.id) {
+            name.push_span(badge_span(badge));
+        }
+        let text = vec![
+            name,
             Line::from(Span::styled(
                 format!("UUID: {}", p.id), 
                 styles::text_dim()
@@ -167,67 +784,52 @@ fn render_project_details(frame: &mut Frame, app: &App, area: Rect) {
         
         // 1. Deadline Math: Always use planned_end_date for deadline countdown
         let deadline_date = p.planned_end_date;
-        let days_until_deadline = (deadline_date - today).num_days();
+        let days_until_deadline = app.day_count.between(today, deadline_date);
 
         // 2. Formatting Deadline with Sanity Check
-        let (deadline_str, deadline_style) = if deadline_date.year() < 2000 {
-            ("Not Set".to_string(), styles::text_dim())
-        } else if p.is_completed() {
-            ("Completed".to_string(), styles::success())
-        } else if days_until_deadline < 0 {
-            (format!("{} days OVERDUE", days_until_deadline.abs()), styles::error())
-        } else {
-            (format!("{} days left", days_until_deadline), styles::info())
+        let status = p.status(today);
+        let (deadline_str, deadline_style) = match status {
+            ProjectStatus::Completed => ("Completed".to_string(), styles::success()),
+            ProjectStatus::Unscheduled => (NOT_SCHEDULED.to_string(), styles::text_dim()),
+            ProjectStatus::Overdue => {
+                (format!("{} OVERDUE", app.day_count.label(days_until_deadline.abs())), styles::error())
+            }
+            ProjectStatus::Pending | ProjectStatus::Active => {
+                (format!("{} left", app.day_count.label(days_until_deadline)), styles::info())
+            }
         };
 
         // 3. Progress Math
         // If completed, 100%. Else calculate time elapsed vs planned duration.
         let total_duration = (p.planned_end_date - p.start_date).num_days().max(1);
         let elapsed = (today - p.start_date).num_days().max(0);
-        let raw_pct = (elapsed as f64 / total_duration as f64).clamp(0.0, 1.0);
-        
-        let progress_pct = if p.is_completed() { 1.0 } else { raw_pct };
-        let progress_bar_width = 20 as usize;
-        let filled = (progress_pct * progress_bar_width as f64) as usize;
-        let empty = progress_bar_width.saturating_sub(filled);
-        let bar_str = format!("[{}{}]", "█".repeat(filled), "░".repeat(empty));
-
-        let status_text = if p.is_completed() { "DONE" } 
-                          else if p.is_overdue() { "LATE" } 
-                          else { "ACTIVE" };
-        
-        let status_color = if p.is_completed() { colors::GREEN }
-                           else if p.is_overdue() { colors::RED }
-                           else { colors::BLUE };
-
-        let total_duration = (p.planned_end_date - p.start_date).num_days().max(1);
-        let elapsed = (today - p.start_date).num_days().max(0);
         
         // Если проект будущий, прогресс 0%
-        let raw_pct = if p.is_pending() {
-            0.0
-        } else {
-            (elapsed as f64 / total_duration as f64).clamp(0.0, 1.0)
+        let progress_pct = match status {
+            ProjectStatus::Completed => 1.0,
+            ProjectStatus::Pending | ProjectStatus::Unscheduled => 0.0,
+            ProjectStatus::Active | ProjectStatus::Overdue => {
+                (elapsed as f64 / total_duration as f64).clamp(0.0, 1.0)
+            }
         };
-        
-        let progress_pct = if p.is_completed() { 1.0 } else { raw_pct };
         let progress_bar_width = 20usize;
         let filled = (progress_pct * progress_bar_width as f64) as usize;
         let empty = progress_bar_width.saturating_sub(filled);
         let bar_str = format!("[{}{}]", "█".repeat(filled), "░".repeat(empty));
 
-        // Logic fix: Handle Pending state
-        let (status_text, status_color) = if p.is_completed() { 
-            ("DONE", colors::GREEN)
-        } else if p.is_overdue() { 
-            ("LATE", colors::RED)
-        } else if p.is_pending() {
-            ("PLANNED", colors::FG_DIM) // Новый статус
-        } else { 
-            ("ACTIVE", colors::BLUE)
+        let (status_text, status_color) = (p.state_age_label(today), status_color(status));
+
+        // Previewed dates replace the saved ones until Enter or Esc
+        let edit = app.radar_state.pending_edit.filter(|e| e.project_id == p.id);
+        let scheduled = |saved: NaiveDate, pending: Option<NaiveDate>| match pending {
+            Some(date) if date != saved => Span::styled(
+                format!("{} → {}", schedule_date(saved, app.date_format), schedule_date(date, app.date_format)),
+                styles::preview(),
+            ),
+            _ => Span::styled(schedule_date(saved, app.date_format), styles::text_hint()),
         };
 
-        let stats = vec![
+        let mut stats = vec![
             Line::from(vec![
                 Span::raw("Status:   "),
                 Span::styled(status_text, Style::default().fg(status_color).add_modifier(Modifier::BOLD)),
@@ -243,20 +845,21 @@ fn render_project_details(frame: &mut Frame, app: &App, area: Rect) {
             ]),
             Line::from(vec![
                 Span::raw("Start:    "),
-                Span::styled(p.start_date.format("%Y-%m-%d").to_string(), styles::text_hint()),
+                scheduled(p.start_date, edit.map(|e| e.start_date)),
             ]),
             Line::from(vec![
                 Span::raw("Plan End: "),
-                Span::styled(p.planned_end_date.format("%Y-%m-%d").to_string(), styles::text_hint()),
+                scheduled(p.planned_end_date, edit.map(|e| e.planned_end_date)),
             ]),
         ];
+        if edit.is_some() {
+            stats.push(Line::from(Span::styled("Unsaved: Enter to save, Esc to discard", styles::preview())));
+        }
         frame.render_widget(Paragraph::new(stats), details_chunks[1]);
 
         // -- Relations --
-        let client_name = app.clients.iter().find(|c| c.id == p.client_id)
-            .map(|c| c.display_name()).unwrap_or("Unknown ID");
-        let manager_name = app.users.iter().find(|u| u.id == p.manager_id)
-            .map(|u| u.display_name()).unwrap_or("Unknown ID");
+        let client_name = app.resolve_client_name(p.client_id);
+        let manager_name = app.resolve_user_name(p.manager_id);
 
         let relations = vec![
             Line::from(Span::styled("Personnel & Client:", styles::title())),
@@ -278,25 +881,55 @@ fn render_project_details(frame: &mut Frame, app: &App, area: Rect) {
     }
 }
 
+/// " NEW" / " CHANGED" tag after a list row or name
+fn badge_span(badge: Badge) -> Span<'static> {
+    Span::styled(format!(" {}", badge.label()), badge.style().add_modifier(Modifier::BOLD))
+}
+
+/// Width of a list row's name column
+const NAME_COLUMNS: usize = 20;
+
+/// A list row's name column, or the inline rename field while it's open;
+/// items marked for a multi-select delete get a leading dot
+fn name_cell(app: &App, id: uuid::Uuid, name: &str, style: Style) -> Vec<Span<'static>> {
+    match app.inline_edit.as_ref().filter(|e| e.id == id) {
+        Some(edit) => {
+            let style = styles::form_input_focused();
+            let mut spans = edit.buffer.spans(NAME_COLUMNS, true, style);
+            let width: usize = spans.iter().map(Span::width).sum();
+            spans.push(Span::styled(" ".repeat(NAME_COLUMNS.saturating_sub(width)), style));
+            spans
+        }
+        None if app.marked.contains(&id) => vec![
+            Span::styled("● ", style.fg(colors::MAGENTA)),
+            Span::styled(format!("{:18}", name), style),
+        ],
+        None => vec![Span::styled(format!("{:20}", name), style)],
+    }
+}
+
 /// Render the clients list view
 fn render_clients_view(frame: &mut Frame, app: &App, area: Rect) {
+    let chunks = split_panes(area);
+
     let items: Vec<ListItem> = app
         .clients
         .iter()
         .enumerate()
         .map(|(i, client)| {
             let is_selected = i == app.list_selected;
+            let flashing = app.flashing() == Some(client.id);
             let style = if is_selected {
                 Style::default()
                     .fg(colors::BG_DARK)
-                    .bg(colors::BLUE)
+                    .bg(if flashing { colors::YELLOW } else { colors::BLUE })
                     .add_modifier(Modifier::BOLD)
             } else {
                 styles::text()
             };
 
             // Calculate project counts from actual projects data
-            let (completed, total) = calculate_client_project_counts(&app.projects, client.id);
+            let (completed, total) = client_project_counts(&app.projects, client.id);
 
             // Create a visual progress bar for projects
             let progress_bar = if total > 0 {
@@ -320,11 +953,8 @@ fn render_clients_view(frame: &mut Frame, app: &App, area: Rect) {
                 Style::default().fg(colors::ORANGE)
             };
 
-            let content = Line::from(vec![
-                Span::styled(
-                    format!("{:20}", client.display_name()),
-                    style,
-                ),
+            let mut content = Line::from(name_cell(app, client.id, client.display_name(), style));
+            content.spans.extend([
                 Span::styled(" │ ", styles::border_dim()),
                 Span::styled(
                     format!("{:30}", client.address.as_deref().unwrap_or("-")),
@@ -338,6 +968,9 @@ fn render_clients_view(frame: &mut Frame, app: &App, area: Rect) {
                     progress_style,
                 ),
             ]);
+            if let Some(badge) = app.badge(client.id) {
+                content.push_span(badge_span(badge));
+            }
 
             ListItem::new(content)
         })
@@ -349,59 +982,128 @@ fn render_clients_view(frame: &mut Frame, app: &App, area: Rect) {
                 .title(" Clients ")
                 .title_style(styles::title_accent())
                 .borders(Borders::ALL)
-                .border_style(styles::border())
+                .border_style(if app.focused_pane() == Pane::Primary { styles::border_focused() } else { styles::border() })
                 .style(Style::default().bg(colors::BG_DARK)),
         )
         .style(styles::text());
 
-    frame.render_widget(list, area);
+    // A stateful list scrolls to keep the selection (e.g. a new client) in view
+    let mut state = ListState::default().with_selected(Some(app.list_selected));
+    frame.render_stateful_widget(list, chunks[0], &mut state);
+    render_client_projects(frame, app, chunks[1]);
 
     // Render empty state
     if app.clients.is_empty() {
-        render_empty_state(frame, area, "No clients found", app.is_loading);
+        render_empty_state(frame, chunks[0], empty_state(app, EntityType::Client, "No clients found"));
     }
 }
 
-/// Render the users list view
-fn render_users_view(frame: &mut Frame, app: &App, area: Rect) {
-    let items: Vec<ListItem> = app
-        .users
+/// The selected client's projects, with a cursor while the pane has focus
+fn render_client_projects(frame: &mut Frame, app: &App, area: Rect) {
+    let focused = app.focused_pane() == Pane::Secondary;
+    let today = chrono::Local::now().date_naive();
+    let indices = app.client_projects();
+    let items: Vec<ListItem> = indices
         .iter()
-        .enumerate()
-        .map(|(i, user)| {
-            let is_selected = i == app.list_selected;
-            let style = if is_selected {
-                Style::default()
-                    .fg(colors::BG_DARK)
-                    .bg(colors::PURPLE)
-                    .add_modifier(Modifier::BOLD)
-            } else {
-                styles::text()
-            };
-
-            let role_color = match user.role {
-                Role::Admin => colors::YELLOW,
-                Role::Manager => colors::GREEN,
-            };
-
-            let content = Line::from(vec![
-                Span::styled(
-                    format!("{:20}", user.display_name()),
-                    style,
-                ),
-                Span::styled(" | ", styles::border_dim()),
-                Span::styled(
-                    format!("{:20}", user.login.as_deref().unwrap_or("-")),
-                    if is_selected { style } else { styles::text_dim() },
-                ),
-                Span::styled(" | ", styles::border_dim()),
+        .map(|&i| {
+            let project = &app.projects[i];
+            let status = project.status(today);
+            ListItem::new(Line::from(vec![
+                Span::styled(" ● ", Style::default().fg(status_color(status))),
+                Span::styled(project.display_name().to_string(), styles::text()),
                 Span::styled(
-                    format!("{:10}", user.role),
-                    if is_selected { style } else { Style::default().fg(role_color) },
+                    format!(" · {}", schedule_date(project.planned_end_date, app.date_format)),
+                    styles::text_dim(),
                 ),
-            ]);
+            ]))
+        })
+        .collect();
 
-            ListItem::new(content)
+    let title = match app.clients.get(app.list_selected) {
+        Some(client) => format!(" {} · Projects ", client.display_name()),
+        None => " Projects ".to_string(),
+    };
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .title(title)
+                .title_style(styles::title_accent())
+                .borders(Borders::ALL)
+                .border_style(if focused { styles::border_focused() } else { styles::border() })
+                .style(Style::default().bg(colors::BG_MEDIUM)),
+        )
+        .highlight_style(styles::selected());
+
+    let selected = focused.then(|| app.client_project_row.min(indices.len().saturating_sub(1)));
+    let mut state = ListState::default().with_selected(selected);
+    frame.render_stateful_widget(list, area, &mut state);
+
+    if indices.is_empty() && !app.clients.is_empty() {
+        let inner = area.inner(Margin::new(2, 1));
+        frame.render_widget(Paragraph::new("No projects").style(styles::text_hint()), inner);
+    }
+}
+
+fn role_color(role: Role) -> Color {
+    match role {
+        Role::Admin => colors::YELLOW,
+        Role::Manager => colors::GREEN,
+    }
+}
+
+/// Render the users list view, split into role sections when grouped
+fn render_users_view(frame: &mut Frame, app: &App, area: Rect) {
+    if let Some(cursor) = app.workload_cursor {
+        render_workload(frame, app, area, cursor);
+        return;
+    }
+
+    let user_item = |i: usize, user: &UserDto| {
+        let is_selected = i == app.list_selected;
+        let flashing = app.flashing() == Some(user.id);
+        let style = if is_selected {
+            Style::default()
+                .fg(colors::BG_DARK)
+                .bg(if flashing { colors::YELLOW } else { colors::PURPLE })
+                .add_modifier(Modifier::BOLD)
+        } else {
+            styles::text()
+        };
+
+        let mut content = Line::from(name_cell(app, user.id, user.display_name(), style));
+        content.spans.extend([
+            Span::styled(" | ", styles::border_dim()),
+            Span::styled(
+                format!("{:20}", user.login.as_deref().unwrap_or("-")),
+                if is_selected { style } else { styles::text_dim() },
+            ),
+            Span::styled(" | ", styles::border_dim()),
+            Span::styled(
+                format!("{:10}", user.role),
+                if is_selected { style } else { Style::default().fg(role_color(user.role)) },
+            ),
+        ]);
+        if let Some(badge) = app.badge(user.id) {
+            content.push_span(badge_span(badge));
+        }
+
+        ListItem::new(content)
+    };
+
+    // Ungrouped, the rows are the users in list order
+    let rows = if app.users_grouped {
+        user_groups::group_rows(&app.users)
+    } else {
+        (0..app.users.len()).map(UserRow::User).collect()
+    };
+    let items: Vec<ListItem> = rows
+        .iter()
+        .map(|row| match *row {
+            UserRow::User(i) => user_item(i, &app.users[i]),
+            UserRow::Header { role, count } => ListItem::new(Span::styled(
+                user_groups::group_label(role, count),
+                Style::default().fg(role_color(role)).add_modifier(Modifier::BOLD),
+            )),
         })
         .collect();
 
@@ -416,35 +1118,154 @@ fn render_users_view(frame: &mut Frame, app: &App, area: Rect) {
         )
         .style(styles::text());
 
-    frame.render_widget(list, area);
+    let mut state = ListState::default().with_selected(user_groups::row_of(&rows, app.list_selected));
+    frame.render_stateful_widget(list, area, &mut state);
 
     // Render empty state
     if app.users.is_empty() {
-        render_empty_state(frame, area, "No users found", app.is_loading);
+        render_empty_state(frame, area, empty_state(app, EntityType::User, "No users found"));
     }
 }
 
-/// Render the log area
-fn render_logs(frame: &mut Frame, app: &App, area: Rect) {
-    let items: Vec<ListItem> = app
-        .logs
-        .iter()
-        .rev()
-        .take(area.height.saturating_sub(2) as usize)
-        .map(|entry| {
-            let (prefix, color) = match entry.level {
-                LogLevel::Info => ("i", colors::BLUE),
-                LogLevel::Success => ("+", colors::GREEN),
-                LogLevel::Warning => ("!", colors::YELLOW),
-                LogLevel::Error => ("x", colors::RED),
-            };
+/// Workload heatmap with the projects behind the cursor cell beside it
+fn render_workload(frame: &mut Frame, app: &App, area: Rect, cursor: (usize, usize)) {
+    let workload = app.workload();
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(65), Constraint::Percentage(35)])
+        .split(area);
+    frame.render_widget(WorkloadWidget::new(&workload, cursor), chunks[0]);
 
-            ListItem::new(Line::from(vec![
-                Span::styled(format!("[{}] ", prefix), Style::default().fg(color)),
-                Span::styled(&entry.message, styles::text_dim()),
-            ]))
+    let title = match (workload.rows.get(cursor.0), workload.months.get(cursor.1)) {
+        (Some(row), Some(month)) => format!(" {} · {} ", row.name, month.format("%B %Y")),
+        _ => " Projects ".to_string(),
+    };
+    let mut lines: Vec<Line> = workload
+        .cell(cursor.0, cursor.1)
+        .iter()
+        .filter_map(|&i| app.projects.get(i))
+        .flat_map(|project| {
+            let dates = format!(
+                "  {} → {}",
+                format_date(&project.start_date, app.date_format),
+                format_date(&project.planned_end_date, app.date_format)
+            );
+            [
+                Line::from(Span::styled(project.display_name().to_string(), styles::text())),
+                Line::from(Span::styled(dates, styles::text_dim())),
+            ]
         })
         .collect();
+    if lines.is_empty() {
+        lines.push(Line::from(Span::styled("No open projects", styles::text_hint())));
+    }
+    let panel = Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+        Block::default()
+            .title(title)
+            .title_style(styles::title_accent())
+            .borders(Borders::ALL)
+            .border_style(styles::border())
+            .style(Style::default().bg(colors::BG_DARK)),
+    );
+    frame.render_widget(panel, chunks[1]);
+}
+
+/// Frames a new toast takes to slide in from the right edge
+const TOAST_SLIDE_FRAMES: u64 = 6;
+/// Frames before expiry during which a toast is drawn dimmed
+const TOAST_FADE_FRAMES: u64 = 15;
+/// Widest a toast box gets, borders included
+const TOAST_MAX_WIDTH: u16 = 40;
+
+/// Render toasts stacked in the top-right corner of `area`
+fn render_toasts(frame: &mut Frame, app: &App, area: Rect) {
+    let mut y = area.y + 1;
+    for toast in app.toasts.iter() {
+        if y + 3 > area.bottom() {
+            break;
+        }
+        let toast_area = toast_rect(toast, app.frame_count, app.animations(), area, y);
+        y += 3;
+        let Some(toast_area) = toast_area else {
+            continue;
+        };
+
+        let color = match toast.level {
+            LogLevel::Info => colors::BLUE,
+            LogLevel::Success => colors::GREEN,
+            LogLevel::Warning => colors::YELLOW,
+            LogLevel::Error => colors::RED,
+        };
+        let fading = toast.age(app.frame_count) + TOAST_FADE_FRAMES >= TOAST_FRAMES;
+        let (border, text) = if fading {
+            (colors::BORDER_DIM, styles::text_hint())
+        } else {
+            (color, styles::text())
+        };
+
+        frame.render_widget(Clear, toast_area);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(border))
+            .padding(Padding::horizontal(1))
+            .style(Style::default().bg(colors::BG_MEDIUM));
+        let message = Paragraph::new(toast.message.as_str()).style(text).block(block);
+        frame.render_widget(message, toast_area);
+    }
+}
+
+/// Where a toast is drawn on this frame: sliding in from the right (when
+/// animating), then resting against the right edge. None while it is still
+/// fully off screen.
+fn toast_rect(toast: &Toast, frame_count: u64, animate: bool, area: Rect, y: u16) -> Option<Rect> {
+    let width = (toast.message.chars().count() as u16 + 4)
+        .min(TOAST_MAX_WIDTH)
+        .min(area.width.saturating_sub(2));
+    let step = width / TOAST_SLIDE_FRAMES as u16 + 1;
+    let slide = if animate {
+        TOAST_SLIDE_FRAMES.saturating_sub(toast.age(frame_count)) as u16 * step
+    } else {
+        0
+    };
+    let x = area.right().saturating_sub(width + 1) + slide;
+    let visible = width.min(area.right().saturating_sub(x));
+    (visible > 2).then(|| Rect::new(x, y, visible, 3))
+}
+
+/// Render the log area
+fn render_logs(frame: &mut Frame, app: &App, area: Rect) {
+    // Collapsed on a short terminal: just the latest line
+    if area.height < 3 {
+        let mut line = Line::from(Span::styled("System Log (v) ", Style::default().fg(colors::FG_DIM)));
+        if let Some(entry) = app.logs.back() {
+            line.spans.extend(log_spans(entry));
+        }
+        frame.render_widget(Paragraph::new(line).style(Style::default().bg(colors::BG_DARK)), area);
+        return;
+    }
+
+    // Newest first; expanded, each line is followed by what was folded into it
+    let mut lines: Vec<Line> = Vec::new();
+    if let Some(group) = &app.log_group {
+        let progress = format!("{}: {} lines so far", group.title, group.entries.len());
+        let mut line = vec![Span::styled(progress, styles::text_hint())];
+        if let Some(last) = group.entries.back() {
+            line.push(Span::styled(format!(" — {}", last.text()), styles::text_hint()));
+        }
+        lines.push(Line::from(line));
+    }
+    for entry in app.logs.iter().rev() {
+        lines.push(Line::from(log_spans(entry)));
+        if app.log_expanded {
+            for detail in entry.details.iter().rev() {
+                let mut spans = vec![Span::raw("    ")];
+                spans.extend(log_spans(detail));
+                lines.push(Line::from(spans));
+            }
+        }
+    }
+    let items: Vec<ListItem> =
+        lines.into_iter().take(area.height.saturating_sub(2) as usize).map(ListItem::new).collect();
 
     let list = List::new(items)
         .block(
@@ -459,16 +1280,200 @@ fn render_logs(frame: &mut Frame, app: &App, area: Rect) {
     frame.render_widget(list, area);
 }
 
-/// Render empty state message
-fn render_empty_state(frame: &mut Frame, area: Rect, message: &str, is_loading: bool) {
-    let text = if is_loading {
-        "Loading..."
-    } else {
-        message
+/// One System Log line: time, level, request id and message
+fn log_spans(entry: &LogEntry) -> Vec<Span<'_>> {
+    let (prefix, color) = match entry.level {
+        LogLevel::Info => ("i", colors::BLUE),
+        LogLevel::Success => ("+", colors::GREEN),
+        LogLevel::Warning => ("!", colors::YELLOW),
+        LogLevel::Error => ("x", colors::RED),
+    };
+
+    let mut spans = vec![
+        Span::styled(format!("{} ", entry.clock()), Style::default().fg(colors::FG_HINT)),
+        Span::styled(format!("[{}] ", prefix), Style::default().fg(color)),
+    ];
+    if let Some(request) = entry.request {
+        spans.push(Span::styled(format!("{} ", request), Style::default().fg(colors::FG_HINT)));
+    }
+    spans.push(Span::styled(entry.text(), styles::text_dim()));
+    spans
+}
+
+/// Render the bottom status bar: connection, tab, counts, refresh age and key hints
+fn render_status_bar(frame: &mut Frame, app: &App, area: Rect) {
+    let separator = || Span::styled(" │ ", styles::border_dim());
+
+    let (dot_color, connection) = match (app.api_connected, app.disconnect_reason) {
+        (true, _) => match app.latency {
+            Some(latency) => (colors::STATUS_CONNECTED, format!("Connected ({}ms)", latency.as_millis())),
+            None => (colors::STATUS_CONNECTED, "Connected".to_string()),
+        },
+        (false, Some(reason)) => (colors::STATUS_DISCONNECTED, format!("Disconnected ({})", reason)),
+        (false, None) => (colors::STATUS_DISCONNECTED, "Disconnected".to_string()),
+    };
+
+    let mut left = vec![
+        Span::styled(" ● ", Style::default().fg(dot_color)),
+        Span::styled(connection, styles::text()),
+    ];
+    if app.is_loading {
+        let label = match load_progress_label(app, app.active_entity_type()) {
+            Some(progress) => format!(" [Loading {}]", progress),
+            None => " [Loading...]".to_string(),
+        };
+        left.push(Span::styled(label, Style::default().fg(colors::STATUS_PENDING)));
+    }
+    if let Some(wait) = app.rate_limit_remaining() {
+        // Round up so the countdown never shows "0s" while still waiting
+        let seconds = wait.as_millis().div_ceil(1000);
+        left.push(Span::styled(format!(" rate limited — retrying in {}s", seconds), styles::warning()));
+    }
+    left.push(separator());
+    left.push(profile_span(app.active_profile.as_deref()));
+    if let Some(host) = api::url_host(&app.api_url) {
+        left.push(Span::styled(format!(" @ {}", host), styles::text_dim()));
+    }
+    if app.read_only {
+        left.push(Span::styled(" 🔒 read-only", styles::warning()));
+    }
+    if app.insecure {
+        left.push(Span::raw(" "));
+        left.push(Span::styled(
+            " ⚠ INSECURE: certificates not verified ",
+            Style::default().fg(colors::BG_DARK).bg(colors::RED_LIGHT).add_modifier(Modifier::BOLD),
+        ));
+    }
+    if let Some(user) = &app.current_user {
+        left.push(separator());
+        left.push(Span::styled(user.display_name().to_string(), styles::text()));
+        left.push(Span::styled(format!(" ({})", user.role), styles::text_dim()));
+    }
+    left.push(separator());
+    left.push(Span::styled(app.active_tab.name(), styles::title_accent()));
+    left.push(separator());
+    // Partially loaded collections show how much of the server total is in
+    // memory; a full load that came up short is flagged
+    let count = |loaded: usize, info: &PageInfo, noun: &str| {
+        if info.partial {
+            let text = format!("⚠ {} of {} {}", format_count(loaded), format_count(info.total_count), noun);
+            Span::styled(text, styles::warning())
+        } else if info.has_next {
+            let text = format!("{} of {} {}", format_count(loaded), format_count(info.total_count), noun);
+            Span::styled(text, styles::text_dim())
+        } else {
+            Span::styled(format!("{} {}", format_count(loaded), noun), styles::text_dim())
+        }
     };
+    // A browsed page says where it is rather than how much is loaded
+    let projects = match app.project_page_label() {
+        Some(page) => Span::styled(format!("projects {}", page), styles::text_dim()),
+        None => count(app.projects.len(), &app.paging.projects, "projects"),
+    };
+    let dot = || Span::styled(" · ", styles::text_dim());
+    left.extend([
+        projects,
+        dot(),
+        count(app.clients.len(), &app.paging.clients, "clients"),
+        dot(),
+        count(app.users.len(), &app.paging.users, "users"),
+    ]);
+    if let Some((active, overdue)) = app.my_project_counts() {
+        left.push(separator());
+        let marker = if app.project_filter.mine.is_some() { "★ mine" } else { "mine" };
+        left.push(Span::styled(format!("{}: {} active", marker, active), styles::info()));
+        if overdue > 0 {
+            left.push(Span::styled(format!(", {} overdue", overdue), styles::error()));
+        }
+    }
+    if app.project_filter.is_active() {
+        left.push(separator());
+        let summary = app.project_filter.describe(&app.filter_deps());
+        left.push(Span::styled(format!("filter: {}", summary), styles::info()));
+    }
+    let archived = app.project_filter.archived_hidden(&app.projects, &app.filter_deps());
+    if archived > 0 {
+        left.push(Span::styled(format!(" +{} archived hidden", format_count(archived)), styles::text_dim()));
+    }
+    if let Some(age) = app.refresh_age() {
+        left.push(separator());
+        // Stale data stands out, and while disconnected it's all there is
+        left.push(match app.stale_age(Instant::now()) {
+            Some(_) if !app.api_connected => Span::styled(format!("⚠ offline, data from {}", age), styles::warning()),
+            Some(_) => Span::styled(format!("⚠ {}", age), styles::warning()),
+            None => Span::styled(age, styles::text_dim()),
+        });
+    }
+
+    // Left segments take priority; hints fill whatever width remains
+    let left = Line::from(left);
+    let hints = app.key_hints();
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Length(left.width() as u16 + 2), Constraint::Min(0)])
+        .split(area);
+
+    let bar_style = Style::default().bg(colors::BG_MEDIUM);
+    frame.render_widget(Paragraph::new(left).style(bar_style), chunks[0]);
+    frame.render_widget(
+        Paragraph::new(Span::styled(
+            fit_hints(hints, chunks[1].width.saturating_sub(1) as usize),
+            styles::text_hint(),
+        ))
+        .alignment(Alignment::Right)
+        .style(bar_style),
+        chunks[1],
+    );
+}
+
+/// Active profile name, shouted in red when it points at production
+fn profile_span(name: Option<&str>) -> Span<'static> {
+    match name {
+        Some(name) if config::is_production(name) => Span::styled(
+            format!(" {} ", name),
+            Style::default()
+                .fg(colors::BG_DARK)
+                .bg(colors::RED_LIGHT)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Some(name) => Span::styled(name.to_string(), styles::info()),
+        None => Span::styled("default", styles::text_dim()),
+    }
+}
+
+/// Drop trailing " · "-separated hints until the rest fits in `width` columns
+fn fit_hints(hints: &str, width: usize) -> String {
+    let mut parts: Vec<&str> = hints.split(" · ").collect();
+    while !parts.is_empty() && parts.join(" · ").chars().count() > width {
+        parts.pop();
+    }
+    let mut fitted = parts.join(" · ");
+    if !fitted.is_empty() {
+        fitted.push(' ');
+    }
+    fitted
+}
 
+/// What an empty collection says: the load progress while fetching, a
+/// failed load in error colours, or `message` once it loaded with nothing
+fn empty_state(app: &App, entity_type: EntityType, message: &str) -> (String, Style) {
+    if let Some(text) = loading_text(app, entity_type) {
+        return (text, styles::text_dim());
+    }
+    match app.load_state(entity_type) {
+        LoadState::NotLoaded => ("Loading…".to_string(), styles::text_dim()),
+        LoadState::Failed(reason) => (
+            format!("Failed to load {}s — press r to retry ({})", entity_type.to_string().to_lowercase(), reason),
+            styles::error(),
+        ),
+        LoadState::Loaded(_) => (message.to_string(), styles::text_dim()),
+    }
+}
+
+/// Render an empty-state message centered in a bordered area
+fn render_empty_state(frame: &mut Frame, area: Rect, (text, style): (String, Style)) {
     let paragraph = Paragraph::new(text)
-        .style(styles::text_dim())
+        .style(style)
         .alignment(Alignment::Center);
 
     // Center the message
@@ -479,6 +1484,34 @@ fn render_empty_state(frame: &mut Frame, area: Rect, message: &str, is_loading:
     frame.render_widget(paragraph, centered);
 }
 
+/// "projects 300/1,250" for a full load in progress
+fn load_progress_label(app: &App, entity_type: EntityType) -> Option<String> {
+    let (loaded, total) = app.paging.get(entity_type).fetched?;
+    let noun = format!("{}s", entity_type).to_lowercase();
+    Some(match total {
+        Some(total) => format!("{} {}/{}", noun, format_count(loaded), format_count(total)),
+        None => format!("{} {}", noun, format_count(loaded)),
+    })
+}
+
+/// Empty-state loading text with a small gauge when the total is known
+fn loading_text(app: &App, entity_type: EntityType) -> Option<String> {
+    if !app.is_loading {
+        return None;
+    }
+    let Some(label) = load_progress_label(app, entity_type) else {
+        return Some("Loading...".to_string());
+    };
+    let gauge = match app.paging.get(entity_type).fetched {
+        Some((loaded, Some(total))) if total > 0 => {
+            let filled = (loaded * 10 / total).min(10);
+            format!(" [{}{}]", "█".repeat(filled), "░".repeat(10 - filled))
+        }
+        _ => String::new(),
+    };
+    Some(format!("Loading {}{}", label, gauge))
+}
+
 /// Render the form modal
 fn render_form_modal(frame: &mut Frame, app: &App, area: Rect) {
     let form = match &app.form_state {
@@ -486,21 +1519,14 @@ fn render_form_modal(frame: &mut Frame, app: &App, area: Rect) {
         None => return,
     };
 
-    // Determine form size based on type
-    // Heights calculated as: fields * 3 + spacer(1) + buttons(1) + margin(2) + borders(2)
-    let (popup_width, popup_height) = match form.form_type {
-        FormType::CreateClient | FormType::EditClient(_) => (50, 12),
-        FormType::CreateProject | FormType::EditProject(_) => (55, 22), // 5 fields
-        FormType::CreateUser | FormType::EditUser(_) => (50, 18), // 4 fields
-    };
-
-    let popup_area = centered_rect(popup_width, popup_height, area);
+    let warning_lines = form_warning_lines(app, form);
+    let popup_area = form_area(form, warning_lines.len() as u16, area);
 
     // Dim background
     frame.render_widget(Clear, popup_area);
 
     // Form title
-    let title = match &form.form_type {
+    let title = match form.form_type() {
         FormType::CreateClient => " New Client ",
         FormType::EditClient(_) => " Edit Client ",
         FormType::CreateProject => " New Project ",
@@ -520,21 +1546,36 @@ fn render_form_modal(frame: &mut Frame, app: &App, area: Rect) {
     frame.render_widget(block, popup_area);
 
     // Render form fields
-    match &form.form_type {
-        FormType::CreateClient | FormType::EditClient(_) => {
-            render_client_form(frame, form, inner);
-        }
-        FormType::CreateProject | FormType::EditProject(_) => {
-            render_project_form(frame, form, app, inner);
-        }
-        FormType::CreateUser | FormType::EditUser(_) => {
-            render_user_form(frame, form, inner);
-        }
+    // A still spinner when animations are off
+    let saving = form.nav().submitting.then_some(if app.animations() { app.frame_count } else { 0 });
+    match form {
+        FormState::Client(form) => render_client_form(frame, form, saving, inner),
+        FormState::Project(form) => render_project_form(frame, form, app, &warning_lines, saving, inner),
+        FormState::User(form) => render_user_form(frame, form, saving, inner),
+    }
+
+    // Conflict banner on the top margin row
+    let banner = match form.nav().staleness {
+        Staleness::Fresh => None,
+        Staleness::Stale => Some("Changed or removed elsewhere — ^R reload"),
+        Staleness::Reloading => Some("Reloading..."),
+    };
+    if let Some(banner) = banner {
+        let banner_area = Rect::new(inner.x, inner.y, inner.width, 1.min(inner.height));
+        frame.render_widget(
+            Paragraph::new(banner).style(styles::warning()).alignment(Alignment::Center),
+            banner_area,
+        );
     }
 
     // Render error message if any
-    if let Some(ref error) = form.error {
-        let error_area = Rect::new(inner.x, inner.y + inner.height - 2, inner.width, 1);
+    if let Some(ref error) = form.nav().error {
+        let error_area = Rect::new(
+            inner.x,
+            inner.y + inner.height.saturating_sub(2),
+            inner.width,
+            1.min(inner.height),
+        );
         let error_text = Paragraph::new(error.as_str())
             .style(styles::error())
             .alignment(Alignment::Center);
@@ -542,18 +1583,13 @@ fn render_form_modal(frame: &mut Frame, app: &App, area: Rect) {
     }
 
     // Render mini calendar popup if a date field is focused
-    if form.current_field().is_date_picker() {
-        let date_str = match form.current_field() {
-            FormField::ProjectStartDate => &form.project_start_date,
-            FormField::ProjectEndDate => &form.project_end_date,
-            _ => return,
-        };
-        render_mini_calendar(frame, date_str, area, popup_area);
+    if let Some((date_str, date_format)) = focused_date(form) {
+        render_mini_calendar(frame, date_str, date_format, app.week_start, area, popup_area);
     }
 }
 
 /// Render client form fields
-fn render_client_form(frame: &mut Frame, form: &FormState, area: Rect) {
+fn render_client_form(frame: &mut Frame, form: &ClientForm, saving: Option<u64>, area: Rect) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
@@ -569,9 +1605,8 @@ fn render_client_form(frame: &mut Frame, form: &FormState, area: Rect) {
     render_text_field(
         frame,
         "Name:",
-        &form.client_name,
+        &form.name,
         form.current_field() == FormField::ClientName,
-        false,
         chunks[0],
     );
 
@@ -579,9 +1614,8 @@ fn render_client_form(frame: &mut Frame, form: &FormState, area: Rect) {
     render_text_field(
         frame,
         "Address:",
-        &form.client_address,
+        &form.address,
         form.current_field() == FormField::ClientAddress,
-        false,
         chunks[1],
     );
 
@@ -590,12 +1624,55 @@ fn render_client_form(frame: &mut Frame, form: &FormState, area: Rect) {
         frame,
         form.current_field() == FormField::SubmitButton,
         form.current_field() == FormField::CancelButton,
+        saving,
         chunks[3],
     );
 }
 
+/// Soft date warnings for under the project dates, one line each, and the
+/// confirm prompt once a Save has pointed them out
+fn form_warning_lines(app: &App, form: &FormState) -> Vec<Line<'static>> {
+    let warnings = form.project_warnings(&app.clients, &app.users, &app.projects);
+    let mut lines: Vec<Line> = warnings
+        .iter()
+        .map(|w| Line::from(Span::styled(format!("⚠ {}", w), styles::warning())))
+        .collect();
+    if matches!(form, FormState::Project(form) if !warnings.is_empty() && form.acknowledged == warnings) {
+        lines.push(Line::from(Span::styled("Press Save again to confirm", styles::warning())));
+    }
+    lines
+}
+
+/// Where the form modal goes, grown by `warning_rows`
+fn form_area(form: &FormState, warning_rows: u16, area: Rect) -> Rect {
+    // Heights calculated as: fields * 3 + spacer(1) + buttons(1) + margin(2) + borders(2)
+    let (popup_width, popup_height) = match form {
+        FormState::Client(_) => (50, 12),
+        FormState::Project(_) => (55, 22 + warning_rows), // 5 fields
+        FormState::User(_) => (50, 18), // 4 fields
+    };
+    centered_rect(popup_width, popup_height, area)
+}
+
+/// Text and format of the focused date field, while the mini calendar shows
+fn focused_date(form: &FormState) -> Option<(&str, DateFormat)> {
+    let FormState::Project(form) = form else { return None };
+    match form.current_field() {
+        FormField::ProjectStartDate => Some((&form.start_date, form.date_format)),
+        FormField::ProjectEndDate => Some((&form.end_date, form.date_format)),
+        _ => None,
+    }
+}
+
 /// Render project form fields
-fn render_project_form(frame: &mut Frame, form: &FormState, app: &App, area: Rect) {
+fn render_project_form(
+    frame: &mut Frame,
+    form: &ProjectForm,
+    app: &App,
+    warnings: &[Line],
+    saving: Option<u64>,
+    area: Rect,
+) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
@@ -604,25 +1681,27 @@ fn render_project_form(frame: &mut Frame, form: &FormState, app: &App, area: Rec
             Constraint::Length(3), // Manager
             Constraint::Length(3), // Start Date
             Constraint::Length(3), // End Date
+            Constraint::Length(warnings.len() as u16), // Soft warnings
             Constraint::Length(1), // Spacer
             Constraint::Length(1), // Buttons
         ])
         .margin(1)
         .split(area);
 
-    // Name field
-    render_text_field(
+    // Name field, with the suggested rest of the name
+    let suggestion = app.name_suggestion();
+    render_suggesting_field(
         frame,
         "Name:",
-        &form.project_name,
+        &form.name,
+        suggestion.as_deref(),
         form.current_field() == FormField::ProjectName,
-        false,
         chunks[0],
     );
 
     // Client selector
     let client_name = app.clients
-        .get(form.project_client_idx)
+        .get(form.client_idx)
         .map(|c| c.display_name().to_string())
         .unwrap_or_else(|| "(Select client)".to_string());
     render_selector_field(
@@ -635,7 +1714,7 @@ fn render_project_form(frame: &mut Frame, form: &FormState, app: &App, area: Rec
 
     // Manager selector
     let manager_name = app.users
-        .get(form.project_manager_idx)
+        .get(form.manager_idx)
         .map(|u| u.display_name().to_string())
         .unwrap_or_else(|| "(Select manager)".to_string());
     render_selector_field(
@@ -650,7 +1729,7 @@ fn render_project_form(frame: &mut Frame, form: &FormState, app: &App, area: Rec
     render_date_picker_field(
         frame,
         "Start Date:",
-        &form.project_start_date,
+        &form.start_date,
         form.current_field() == FormField::ProjectStartDate,
         chunks[3],
     );
@@ -659,22 +1738,25 @@ fn render_project_form(frame: &mut Frame, form: &FormState, app: &App, area: Rec
     render_date_picker_field(
         frame,
         "End Date:",
-        &form.project_end_date,
+        &form.end_date,
         form.current_field() == FormField::ProjectEndDate,
         chunks[4],
     );
 
+    frame.render_widget(Paragraph::new(warnings.to_vec()).alignment(Alignment::Center), chunks[5]);
+
     // Buttons
     render_form_buttons(
         frame,
         form.current_field() == FormField::SubmitButton,
         form.current_field() == FormField::CancelButton,
-        chunks[6],
+        saving,
+        chunks[7],
     );
 }
 
 /// Render user form fields
-fn render_user_form(frame: &mut Frame, form: &FormState, area: Rect) {
+fn render_user_form(frame: &mut Frame, form: &UserForm, saving: Option<u64>, area: Rect) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
@@ -692,9 +1774,8 @@ fn render_user_form(frame: &mut Frame, form: &FormState, area: Rect) {
     render_text_field(
         frame,
         "Name:",
-        &form.user_name,
+        &form.name,
         form.current_field() == FormField::UserName,
-        false,
         chunks[0],
     );
 
@@ -702,9 +1783,8 @@ fn render_user_form(frame: &mut Frame, form: &FormState, area: Rect) {
     render_text_field(
         frame,
         "Login:",
-        &form.user_login,
+        &form.login,
         form.current_field() == FormField::UserLogin,
-        false,
         chunks[1],
     );
 
@@ -712,9 +1792,8 @@ fn render_user_form(frame: &mut Frame, form: &FormState, area: Rect) {
     render_text_field(
         frame,
         "Password:",
-        &form.user_password,
+        &form.password,
         form.current_field() == FormField::UserPassword,
-        true,
         chunks[2],
     );
 
@@ -722,7 +1801,7 @@ fn render_user_form(frame: &mut Frame, form: &FormState, area: Rect) {
     render_selector_field(
         frame,
         "Role:",
-        &form.user_role.to_string(),
+        &form.role.to_string(),
         form.current_field() == FormField::UserRole,
         chunks[3],
     );
@@ -732,6 +1811,7 @@ fn render_user_form(frame: &mut Frame, form: &FormState, area: Rect) {
         frame,
         form.current_field() == FormField::SubmitButton,
         form.current_field() == FormField::CancelButton,
+        saving,
         chunks[5],
     );
 }
@@ -740,16 +1820,24 @@ fn render_user_form(frame: &mut Frame, form: &FormState, area: Rect) {
 fn render_text_field(
     frame: &mut Frame,
     label: &str,
-    value: &str,
+    input: &TextInput,
     is_focused: bool,
-    is_password: bool,
     area: Rect,
 ) {
-    // Use 14 characters for label column to accommodate "Start Date:" and "End Date:" with padding
-    let chunks = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([Constraint::Length(14), Constraint::Min(10)])
-        .split(area);
+    render_suggesting_field(frame, label, input, None, is_focused, area);
+}
+
+/// `render_text_field` with `ghost`, a suggested rest of the text, drawn dim
+/// after it
+fn render_suggesting_field(
+    frame: &mut Frame,
+    label: &str,
+    input: &TextInput,
+    ghost: Option<&str>,
+    is_focused: bool,
+    area: Rect,
+) {
+    let chunks = field_columns(area);
 
     // Label
     let label_text = Paragraph::new(label)
@@ -758,31 +1846,43 @@ fn render_text_field(
     frame.render_widget(label_text, chunks[0]);
 
     // Input field
-    let display_value = if is_password {
-        "*".repeat(value.len())
-    } else {
-        value.to_string()
-    };
-
     let input_style = if is_focused {
         styles::form_input_focused()
     } else {
         styles::form_input()
     };
 
-    let cursor = if is_focused { "█" } else { "" };
-    let input = Paragraph::new(format!(" {}{}", display_value, cursor))
-        .style(input_style)
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .border_style(if is_focused {
-                    styles::border_focused()
-                } else {
-                    styles::border_dim()
-                }),
-        );
-    frame.render_widget(input, chunks[1]);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(if is_focused {
+            styles::border_focused()
+        } else {
+            styles::border_dim()
+        })
+        .style(input_style);
+    frame.render_widget(block, chunks[1]);
+    let widget = TextInputWidget::new(input).focused(is_focused).style(input_style).ghost(ghost);
+    frame.render_widget(widget, field_text_area(area));
+}
+
+/// A field row's label and input box
+fn field_columns(row: Rect) -> Rc<[Rect]> {
+    // Use 14 characters for label column to accommodate "Start Date:" and "End Date:" with padding
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Length(14), Constraint::Min(10)])
+        .split(row)
+}
+
+/// Where the text goes in a field row: inside the input box's border, after
+/// one column of padding
+fn field_text_area(row: Rect) -> Rect {
+    field_columns(row)[1].inner(Margin::new(2, 1))
+}
+
+/// Columns for the text of a field row `width` columns wide
+fn field_text_width(width: u16) -> usize {
+    field_text_area(Rect::new(0, 0, width, 3)).width as usize
 }
 
 /// Render a date picker field with mini calendar
@@ -873,11 +1973,15 @@ fn render_selector_field(
     frame.render_widget(input, chunks[1]);
 }
 
-/// Render form buttons
+/// Spinner frames for a save in flight
+const SPINNER: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+
+/// Render form buttons; `saving` holds the frame count while a save is in flight
 fn render_form_buttons(
     frame: &mut Frame,
     save_focused: bool,
     cancel_focused: bool,
+    saving: Option<u64>,
     area: Rect,
 ) {
     let chunks = Layout::default()
@@ -897,7 +2001,11 @@ fn render_form_buttons(
     } else {
         styles::button()
     };
-    let save_btn = Paragraph::new("  [ Save ]  ")
+    let save_label = match saving {
+        Some(frame_count) => format!("{} Saving…", SPINNER[(frame_count / 2) as usize % SPINNER.len()]),
+        None => "  [ Save ]  ".to_string(),
+    };
+    let save_btn = Paragraph::new(save_label)
         .style(save_style)
         .alignment(Alignment::Center);
     frame.render_widget(save_btn, chunks[1]);
@@ -914,6 +2022,35 @@ fn render_form_buttons(
     frame.render_widget(cancel_btn, chunks[3]);
 }
 
+fn confirm_message(dialog: &ConfirmDialog) -> Paragraph<'_> {
+    Paragraph::new(dialog.message.as_str())
+        .style(styles::text())
+        .wrap(Wrap { trim: true })
+        .alignment(Alignment::Center)
+}
+
+/// Where the confirm dialog goes; it grows with the message, with borders,
+/// margins, spacer and buttons taking 6 rows
+fn confirm_area(dialog: &ConfirmDialog, area: Rect) -> Rect {
+    let lines = confirm_message(dialog).line_count(CONFIRM_WIDTH - 4) as u16;
+    centered_rect(CONFIRM_WIDTH, (lines + 6).max(10), area)
+}
+
+const CONFIRM_WIDTH: u16 = 45;
+
+/// Message, spacer and button rows inside the confirm dialog's border
+fn confirm_rows(popup_area: Rect) -> Rc<[Rect]> {
+    Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(3),    // Message
+            Constraint::Length(1), // Spacer
+            Constraint::Length(1), // Buttons
+        ])
+        .margin(1)
+        .split(popup_area.inner(Margin::new(1, 1)))
+}
+
 /// Render confirmation dialog
 fn render_confirm_dialog(frame: &mut Frame, app: &App, area: Rect) {
     let dialog = match &app.confirm_dialog {
@@ -921,7 +2058,8 @@ fn render_confirm_dialog(frame: &mut Frame, app: &App, area: Rect) {
         None => return,
     };
 
-    let popup_area = centered_rect(45, 10, area);
+    let message = confirm_message(dialog);
+    let popup_area = confirm_area(dialog, area);
     frame.render_widget(Clear, popup_area);
 
     let block = Block::default()
@@ -931,67 +2069,69 @@ fn render_confirm_dialog(frame: &mut Frame, app: &App, area: Rect) {
         .border_style(Style::default().fg(colors::RED))
         .style(Style::default().bg(colors::BG_MEDIUM));
 
-    let inner = block.inner(popup_area);
     frame.render_widget(block, popup_area);
 
-    let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Min(3),    // Message
-            Constraint::Length(1), // Spacer
-            Constraint::Length(1), // Buttons
-        ])
-        .margin(1)
-        .split(inner);
-
-    // Message
-    let message = Paragraph::new(dialog.message.as_str())
-        .style(styles::text())
-        .wrap(Wrap { trim: true })
-        .alignment(Alignment::Center);
-    frame.render_widget(message, chunks[0]);
+    let chunks = confirm_rows(popup_area);
+    render_scrolled(frame, message, &dialog.scroll, chunks[0], popup_area);
 
     // Buttons
-    let button_chunks = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([
+    let buttons = dialog.buttons();
+    let constraints = match buttons.len() {
+        2 => vec![
             Constraint::Percentage(25),
             Constraint::Length(10),
             Constraint::Percentage(10),
             Constraint::Length(10),
             Constraint::Percentage(25),
-        ])
+        ],
+        _ => vec![
+            Constraint::Fill(1),
+            Constraint::Length(8),
+            Constraint::Length(2),
+            Constraint::Length(9),
+            Constraint::Length(2),
+            Constraint::Length(16),
+            Constraint::Fill(1),
+        ],
+    };
+    let button_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(constraints)
         .split(chunks[2]);
 
-    let no_style = if !dialog.yes_focused {
-        styles::button_focused()
-    } else {
-        styles::button()
-    };
-    let no_btn = Paragraph::new("  [ No ]  ")
-        .style(no_style)
-        .alignment(Alignment::Center);
-    frame.render_widget(no_btn, button_chunks[1]);
+    for (i, &button) in buttons.iter().enumerate() {
+        let style = match button {
+            _ if button != dialog.focus => styles::button(),
+            ConfirmButton::No => styles::button_focused(),
+            ConfirmButton::Yes | ConfirmButton::All => styles::button_danger(),
+        };
+        let label = Paragraph::new(format!(" {} ", button.label()))
+            .style(style)
+            .alignment(Alignment::Center);
+        frame.render_widget(label, button_chunks[2 * i + 1]);
+    }
+}
 
-    let yes_style = if dialog.yes_focused {
-        styles::button_danger()
-    } else {
-        styles::button()
-    };
-    let yes_btn = Paragraph::new(" [ Yes ]  ")
-        .style(yes_style)
-        .alignment(Alignment::Center);
-    frame.render_widget(yes_btn, button_chunks[3]);
+fn error_message(popup: &ErrorPopup) -> Paragraph<'_> {
+    Paragraph::new(popup.message.as_str())
+        .style(styles::text())
+        .wrap(Wrap { trim: true })
+}
+
+/// Where the error popup goes; it grows with the message and centered_rect
+/// keeps it on screen
+fn error_popup_area(popup: &ErrorPopup, area: Rect) -> Rect {
+    let popup_width = (area.width * 60 / 100).clamp(30, 60);
+    let popup_height = (error_message(popup).line_count(popup_width - 2) as u16 + 2).max(7);
+    centered_rect(popup_width, popup_height, area)
 }
 
 /// Render error popup
 fn render_error_popup(frame: &mut Frame, app: &App, area: Rect) {
     let popup = app.error_popup.as_ref().unwrap();
 
-    let popup_width = (area.width * 60 / 100).min(60).max(30);
-    let popup_height = 7;
-
-    let popup_area = centered_rect(popup_width, popup_height, area);
+    let text = error_message(popup);
+    let popup_area = error_popup_area(popup, area);
 
     // Clear the area behind the popup
     frame.render_widget(Clear, popup_area);
@@ -1009,14 +2149,9 @@ fn render_error_popup(frame: &mut Frame, app: &App, area: Rect) {
         .border_style(Style::default().fg(colors::RED))
         .style(Style::default().bg(Color::Rgb(0x2A, 0x18, 0x18)));
 
-    let inner = block.inner(popup_area);
     frame.render_widget(block, popup_area);
 
-    let text = Paragraph::new(popup.message.as_str())
-        .style(styles::text())
-        .wrap(Wrap { trim: true });
-
-    frame.render_widget(text, inner);
+    render_scrolled(frame, text, &popup.scroll, popup_area.inner(Margin::new(1, 1)), popup_area);
 
     // Dismiss hint
     let hint = Paragraph::new("Press ESC or ENTER to dismiss")
@@ -1025,22 +2160,27 @@ fn render_error_popup(frame: &mut Frame, app: &App, area: Rect) {
 
     let hint_area = Rect::new(
         popup_area.x,
-        popup_area.y + popup_area.height - 1,
+        popup_area.y + popup_area.height.saturating_sub(1),
         popup_area.width,
         1,
     );
     frame.render_widget(hint, hint_area);
 }
 
-/// Render help overlay
-fn render_help_overlay(frame: &mut Frame, area: Rect) {
-    let popup_width = 60;
-    let popup_height = 29;
-    let popup_area = centered_rect(popup_width, popup_height, area);
-
-    frame.render_widget(Clear, popup_area);
+/// The help overlay's text; CRUD keys are dimmed in read-only mode
+fn help_text(app: &App) -> Paragraph<'_> {
+    let read_only = app.read_only;
+    let (crud_header, crud_key, crud_text) = if read_only {
+        (styles::text_hint(), styles::text_hint(), styles::text_hint())
+    } else {
+        (
+            Style::default().fg(colors::PURPLE).add_modifier(Modifier::BOLD),
+            Style::default().fg(colors::BLUE),
+            styles::text(),
+        )
+    };
 
-    let help_text = vec![
+    let lines = vec![
         Line::from(Span::styled(
             "Keyboard Shortcuts",
             Style::default()
@@ -1063,21 +2203,130 @@ fn render_help_overlay(frame: &mut Frame, area: Rect) {
             Span::styled("  h/l or Left/Right", Style::default().fg(colors::BLUE)),
             Span::raw("Scroll timeline"),
         ]),
+        Line::from(vec![
+            Span::styled("  z             ", Style::default().fg(colors::BLUE)),
+            Span::raw("Fit radar to open projects / show all"),
+        ]),
+        Line::from(vec![
+            Span::styled("  F             ", Style::default().fg(colors::BLUE)),
+            Span::raw("Filter projects by status, client, manager"),
+        ]),
+        Line::from(vec![
+            Span::styled("  Enter         ", Style::default().fg(colors::BLUE)),
+            Span::raw(app.enter_action.describe()),
+        ]),
+        Line::from(vec![
+            Span::styled("  .             ", Style::default().fg(colors::BLUE)),
+            Span::raw("Zoom out to the selected project"),
+        ]),
+        Line::from(vec![
+            Span::styled("  g / G         ", Style::default().fg(colors::BLUE)),
+            Span::raw("Select the first/last project and zoom out to it"),
+        ]),
+        Line::from(vec![
+            Span::styled("  0 / $         ", Style::default().fg(colors::BLUE)),
+            Span::raw("Earliest start / latest deadline (Home/End)"),
+        ]),
+        Line::from(vec![
+            Span::styled("  o             ", Style::default().fg(colors::BLUE)),
+            Span::raw("Toggle zooming out to follow the selection"),
+        ]),
+        Line::from(vec![
+            Span::styled("  u             ", Style::default().fg(colors::BLUE)),
+            Span::raw("Focus the upcoming deadlines list"),
+        ]),
+        Line::from(vec![
+            Span::styled("  Ctrl+W        ", Style::default().fg(colors::BLUE)),
+            Span::raw("Cycle the tab's panes (Ctrl+H/L: left/right)"),
+        ]),
+        Line::from(vec![
+            Span::styled("  Enter         ", Style::default().fg(colors::BLUE)),
+            Span::raw("Show a client's projects (Clients tab)"),
+        ]),
+        Line::from(vec![
+            Span::styled("  Ctrl+F        ", Style::default().fg(colors::BLUE)),
+            Span::raw("Search projects, clients and users"),
+        ]),
+        Line::from(vec![
+            Span::styled("  F12           ", Style::default().fg(colors::BLUE)),
+            Span::raw("Frame rate and render time overlay"),
+        ]),
+        Line::from(vec![
+            Span::styled("  m             ", Style::default().fg(colors::BLUE)),
+            Span::raw("Month calendar of starts/deadlines (< > month)"),
+        ]),
+        Line::from(vec![
+            Span::styled("  J             ", Style::default().fg(colors::BLUE)),
+            Span::raw("Jump the radar to a date (2025-03-10, +2w, tomorrow)"),
+        ]),
+        Line::from(vec![
+            Span::styled("  w             ", Style::default().fg(colors::BLUE)),
+            Span::raw("Manager workload heatmap (Users tab)"),
+        ]),
+        Line::from(vec![
+            Span::styled("  b             ", Style::default().fg(colors::BLUE)),
+            Span::raw("Group users by role (Users tab)"),
+        ]),
+        Line::from(vec![
+            Span::styled("  Ctrl+G        ", Style::default().fg(colors::BLUE)),
+            Span::raw("Generate a password (Password field)"),
+        ]),
+        Line::from(vec![
+            Span::styled("  Alt+Left/Right", crud_key),
+            Span::styled("Shift deadline (Shift: week, Ctrl: whole)", crud_text),
+        ]),
+        Line::from(vec![
+            Span::styled("  i             ", Style::default().fg(colors::BLUE)),
+            Span::raw("Change history of the selected project"),
+        ]),
+        Line::from(vec![
+            Span::styled("  H             ", Style::default().fg(colors::BLUE)),
+            Span::raw("Show/hide long-completed (archived) projects"),
+        ]),
+        Line::from(vec![
+            Span::styled("  P             ", Style::default().fg(colors::BLUE)),
+            Span::raw("Saved filter presets (s save, d delete)"),
+        ]),
+        Line::from(vec![
+            Span::styled("  Ctrl+X        ", Style::default().fg(colors::BLUE)),
+            Span::raw("Reset filter, archive and sort to the defaults"),
+        ]),
+        Line::from(vec![
+            Span::styled("  ~             ", Style::default().fg(colors::BLUE)),
+            Span::raw("Only the projects you manage (needs a login)"),
+        ]),
+        Line::from(vec![
+            Span::styled("  PgDn/PgUp     ", Style::default().fg(colors::BLUE)),
+            Span::raw("Next/previous project page (page_browsing)"),
+        ]),
         Line::from(""),
         Line::from(vec![
-            Span::styled("CRUD Operations", Style::default().fg(colors::PURPLE).add_modifier(Modifier::BOLD)),
+            Span::styled("CRUD Operations", crud_header),
+            Span::styled(if read_only { " (disabled: read-only)" } else { "" }, styles::text_hint()),
+        ]),
+        Line::from(vec![
+            Span::styled("  c             ", crud_key),
+            Span::styled("Create new item", crud_text),
+        ]),
+        Line::from(vec![
+            Span::styled("  e             ", crud_key),
+            Span::styled("Edit selected item", crud_text),
+        ]),
+        Line::from(vec![
+            Span::styled("  n             ", crud_key),
+            Span::styled("Rename selected item in place", crud_text),
         ]),
         Line::from(vec![
-            Span::styled("  c             ", Style::default().fg(colors::BLUE)),
-            Span::raw("Create new item"),
+            Span::styled("  d / Delete    ", crud_key),
+            Span::styled("Delete selected item", crud_text),
         ]),
         Line::from(vec![
-            Span::styled("  e             ", Style::default().fg(colors::BLUE)),
-            Span::raw("Edit selected item"),
+            Span::styled("  I             ", crud_key),
+            Span::styled("Import from CSV/JSON file", crud_text),
         ]),
         Line::from(vec![
-            Span::styled("  d / Delete    ", Style::default().fg(colors::BLUE)),
-            Span::raw("Delete selected item"),
+            Span::styled("  x             ", crud_key),
+            Span::styled("Mark project complete (Timeline)", crud_text),
         ]),
         Line::from(""),
         Line::from(vec![
@@ -1111,32 +2360,594 @@ fn render_help_overlay(frame: &mut Frame, area: Rect) {
         Line::from(vec![
             Span::styled("General", Style::default().fg(colors::PURPLE).add_modifier(Modifier::BOLD)),
         ]),
+        Line::from(vec![
+            Span::styled("  Esc           ", Style::default().fg(colors::BLUE)),
+            Span::raw("Close the topmost popup, pane or filter (one per press)"),
+        ]),
         Line::from(vec![
             Span::styled("  r             ", Style::default().fg(colors::BLUE)),
             Span::raw("Refresh data"),
         ]),
+        Line::from(vec![
+            Span::styled("  M             ", Style::default().fg(colors::BLUE)),
+            Span::raw("Load more (lazy loading)"),
+        ]),
+        Line::from(vec![
+            Span::styled("  R             ", Style::default().fg(colors::BLUE)),
+            Span::raw("Write Markdown status report"),
+        ]),
+        Line::from(vec![
+            Span::styled("  S             ", Style::default().fg(colors::BLUE)),
+            Span::raw("Save radar snapshot to a text file"),
+        ]),
+        Line::from(vec![
+            Span::styled("  Space         ", Style::default().fg(colors::BLUE)),
+            Span::raw("Mark item for a multi-select delete (Esc clears)"),
+        ]),
+        Line::from(vec![
+            Span::styled("  v             ", Style::default().fg(colors::BLUE)),
+            Span::raw("Expand the System Log: the whole log, and the lines folded into each"),
+        ]),
+        Line::from(vec![
+            Span::styled("  L             ", Style::default().fg(colors::BLUE)),
+            Span::raw("Export the System Log to a file"),
+        ]),
         Line::from(vec![
             Span::styled("  p             ", Style::default().fg(colors::BLUE)),
             Span::raw("Toggle particles"),
         ]),
+        Line::from(vec![
+            Span::styled("  A             ", Style::default().fg(colors::BLUE)),
+            Span::raw("Toggle all animations"),
+        ]),
+        Line::from(vec![
+            Span::styled("  Ctrl+E        ", Style::default().fg(colors::BLUE)),
+            Span::raw("Switch server profile"),
+        ]),
+        Line::from(vec![
+            Span::styled("  U             ", Style::default().fg(colors::BLUE)),
+            Span::raw("Set API URL"),
+        ]),
         Line::from(vec![
             Span::styled("  q/Ctrl+C      ", Style::default().fg(colors::BLUE)),
             Span::raw("Quit"),
         ]),
     ];
+    Paragraph::new(lines).style(styles::text())
+}
+
+/// Render help overlay (CRUD keys are grayed out in read-only mode)
+fn render_help_overlay(frame: &mut Frame, app: &App, area: Rect) {
+    let popup_area = help_area(area);
+    frame.render_widget(Clear, popup_area);
+
+    let block = Block::default()
+        .title(" Help ")
+        .title_style(styles::title())
+        .borders(Borders::ALL)
+        .border_style(styles::border())
+        .style(Style::default().bg(colors::BG_MEDIUM));
+    frame.render_widget(block, popup_area);
+    render_scrolled(frame, help_text(app), &app.help_scroll, help_text_area(area), popup_area);
+}
+
+/// Draw `text` into `area` at the popup's scroll position, with a scrollbar
+/// on the right border of `popup_area` when it doesn't all fit
+fn render_scrolled(frame: &mut Frame, text: Paragraph, scroll: &PopupScroll, area: Rect, popup_area: Rect) {
+    let hidden = text.line_count(area.width).saturating_sub(area.height as usize);
+    let offset = scroll.offset().min(u16::try_from(hidden).unwrap_or(u16::MAX));
+    frame.render_widget(text.scroll((offset, 0)), area);
+    if hidden > 0 {
+        let mut state = ScrollbarState::new(hidden).position(offset as usize);
+        let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
+            .begin_symbol(None)
+            .end_symbol(None)
+            .style(styles::border_focused());
+        frame.render_stateful_widget(scrollbar, popup_area.inner(Margin::new(0, 1)), &mut state);
+    }
+}
+
+/// Render the "Set API URL" prompt ('U')
+fn render_url_prompt(frame: &mut Frame, app: &App, area: Rect) {
+    let Some(prompt) = &app.url_prompt else {
+        return;
+    };
+    let popup_area = url_prompt_area(area);
+    frame.render_widget(Clear, popup_area);
+
+    let block = Block::default()
+        .title(" Set API URL ")
+        .title_style(styles::title_accent())
+        .borders(Borders::ALL)
+        .border_style(styles::border_focused())
+        .style(Style::default().bg(colors::BG_MEDIUM));
+    let inner = block.inner(popup_area);
+    frame.render_widget(block, popup_area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(0)])
+        .split(inner);
+    render_text_field(frame, "URL:", &prompt.url, true, chunks[0]);
+    let note = match &prompt.error {
+        Some(error) => Span::styled(error.as_str(), styles::error()),
+        None => Span::styled("Reloads everything from the new server", styles::text_hint()),
+    };
+    frame.render_widget(Paragraph::new(note), chunks[1]);
+}
+
+/// The jump-to-date prompt ('J')
+fn render_date_jump(frame: &mut Frame, input: &TextInput, area: Rect) {
+    let popup_area = date_jump_area(area);
+    frame.render_widget(Clear, popup_area);
+
+    let block = Block::default()
+        .title(" Jump to Date ")
+        .title_style(styles::title_accent())
+        .borders(Borders::ALL)
+        .border_style(styles::border_focused())
+        .style(Style::default().bg(colors::BG_MEDIUM));
+    let inner = block.inner(popup_area);
+    frame.render_widget(block, popup_area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(0)])
+        .split(inner);
+    render_text_field(frame, "Date:", input, true, chunks[0]);
+    let hint = "A date, today, tomorrow or +10d / -2w / +3m";
+    frame.render_widget(Paragraph::new(Span::styled(hint, styles::text_hint())), chunks[1]);
+}
+
+/// The saved filter presets ('P'), with a name field while saving one
+fn render_preset_picker(frame: &mut Frame, app: &App, picker: &PresetPicker, area: Rect) {
+    let popup_area = preset_picker_area(app, area);
+    frame.render_widget(Clear, popup_area);
+    let block = Block::default()
+        .title(" Filter Presets ")
+        .title_style(styles::title_accent())
+        .borders(Borders::ALL)
+        .border_style(styles::border_focused())
+        .style(Style::default().bg(colors::BG_MEDIUM));
+    let inner = block.inner(popup_area);
+    frame.render_widget(block, popup_area);
+
+    let name_height = if picker.naming.is_some() { 3 } else { 0 };
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(name_height)])
+        .split(inner);
+    if let Some(name) = &picker.naming {
+        render_text_field(frame, "Name:", name, true, chunks[1]);
+    }
+
+    if app.presets.is_empty() {
+        let hint = Paragraph::new(" No presets yet · s saves the current filter").style(styles::text_hint());
+        frame.render_widget(hint, chunks[0]);
+        return;
+    }
+    let deps = app.filter_deps();
+    let items: Vec<ListItem> = app
+        .presets
+        .presets
+        .iter()
+        .map(|preset| {
+            let summary = preset.describe(&deps);
+            let summary_style = if summary.contains(MISSING) { styles::warning() } else { styles::text_dim() };
+            ListItem::new(Line::from(vec![
+                Span::styled(format!(" {:<20} ", preset.name), styles::text()),
+                Span::styled(summary, summary_style),
+            ]))
+        })
+        .collect();
+    let list = List::new(items).highlight_style(styles::selected());
+    let mut state = ListState::default().with_selected(Some(picker.selected));
+    frame.render_stateful_widget(list, chunks[0], &mut state);
+}
+
+/// Render the import file prompt ('I')
+fn render_import_prompt(frame: &mut Frame, app: &App, area: Rect) {
+    let path = match &app.import_prompt {
+        Some(p) => p,
+        None => return,
+    };
+
+    let entity = match app.active_tab {
+        Tab::Clients => "clients",
+        Tab::Timeline => "projects",
+        Tab::Users => "users",
+    };
+    let popup_area = import_prompt_area(area);
+    frame.render_widget(Clear, popup_area);
+
+    let block = Block::default()
+        .title(format!(" Import {} ", entity))
+        .title_style(styles::title_accent())
+        .borders(Borders::ALL)
+        .border_style(styles::border_focused())
+        .style(Style::default().bg(colors::BG_MEDIUM));
+    let inner = block.inner(popup_area);
+    frame.render_widget(block, popup_area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(0)])
+        .split(inner);
+    render_text_field(frame, "File:", path, true, chunks[0]);
+    frame.render_widget(
+        Paragraph::new(Span::styled(
+            "CSV or JSON; nothing is created until the dry run is confirmed",
+            styles::text_hint(),
+        )),
+        chunks[1],
+    );
+}
+
+/// Render the project filter popup (F)
+fn render_filter_popup(frame: &mut Frame, app: &App, area: Rect) {
+    let Some(popup) = &app.filter_popup else {
+        return;
+    };
+    let draft = &popup.draft;
+
+    let popup_area = filter_popup_area(area);
+    frame.render_widget(Clear, popup_area);
+    let block = Block::default()
+        .title(" Filter Projects ")
+        .title_style(styles::title_accent())
+        .borders(Borders::ALL)
+        .border_style(styles::border_focused())
+        .style(Style::default().bg(colors::BG_MEDIUM));
+    let inner = block.inner(popup_area);
+    frame.render_widget(block, popup_area);
+
+    let values = [
+        draft.status.map_or("Any", |s| s.label()).to_string(),
+        draft.client_id.map_or_else(|| "Any".to_string(), |id| app.resolve_client_name(id)),
+        draft.manager_id.map_or_else(|| "Any".to_string(), |id| app.resolve_user_name(id)),
+        String::new(),
+    ];
+
+    let mut lines: Vec<Line> = FilterPopup::ROWS
+        .iter()
+        .zip(values)
+        .enumerate()
+        .map(|(row, (label, value))| {
+            let focused = row == popup.row;
+            let label_style = if focused { styles::title_accent() } else { styles::text_dim() };
+            let value_style = if focused { styles::text() } else { styles::text_dim() };
+            let mut line = Line::from(Span::styled(format!(" {:<9}", label), label_style));
+            if row == FilterPopup::TEXT_ROW {
+                line.spans.extend(popup.text.spans(filter_text_width(popup_area), focused, value_style));
+            } else if focused {
+                line.push_span(Span::styled(format!("◀ {} ▶", value), value_style));
+            } else {
+                line.push_span(Span::styled(value, value_style));
+            }
+            line
+        })
+        .collect();
+    let shown = popup.draft.apply(&app.projects, &app.filter_deps()).len();
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        format!(" {} of {} projects match", shown, app.projects.len()),
+        styles::text_hint(),
+    )));
+    frame.render_widget(Paragraph::new(lines), inner);
+}
+
+/// `text` with the `range` part highlighted
+fn highlighted(text: &str, range: std::ops::Range<usize>, style: Style) -> Vec<Span<'static>> {
+    let mark = style.fg(colors::YELLOW).add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+    vec![
+        Span::styled(text[..range.start].to_string(), style),
+        Span::styled(text[range.clone()].to_string(), mark),
+        Span::styled(text[range.end..].to_string(), style),
+    ]
+}
+
+/// The " / query" row at the top of a picker
+fn render_query(frame: &mut Frame, query: &TextInput, area: Rect) {
+    frame.render_widget(Paragraph::new(Span::styled(" / ", styles::title_accent())), Rect { height: 1, ..area });
+    let widget = TextInputWidget::new(query).focused(true).style(styles::form_input_focused());
+    frame.render_widget(widget, query_area(area));
+}
+
+/// Where a picker's query is typed, after the " / "
+fn query_area(area: Rect) -> Rect {
+    Rect { x: area.x + 3, width: area.width.saturating_sub(3), height: 1, ..area }
+}
+
+/// Render the search overlay (Ctrl+F): the query, then the hits under one
+/// heading per entity type
+fn render_search_overlay(frame: &mut Frame, app: &App, area: Rect) {
+    let Some(overlay) = &app.search else {
+        return;
+    };
+    let hits = app.search_hits();
+
+    let popup_area = search_area(area);
+    frame.render_widget(Clear, popup_area);
+    let block = Block::default()
+        .title(" Search ")
+        .title_style(styles::title_accent())
+        .borders(Borders::ALL)
+        .border_style(styles::border_focused())
+        .style(Style::default().bg(colors::BG_MEDIUM));
+    let inner = block.inner(popup_area);
+    frame.render_widget(block, popup_area);
+    if inner.height < 3 {
+        return;
+    }
+
+    render_query(frame, &overlay.query, inner);
+    let results_area = Rect { y: inner.y + 2, height: inner.height - 2, ..inner };
+
+    if hits.is_empty() {
+        let hint = if overlay.query.value().trim().is_empty() {
+            " Type to search names, addresses, logins and ids"
+        } else {
+            " No matches"
+        };
+        frame.render_widget(Paragraph::new(Span::styled(hint, styles::text_hint())), results_area);
+        return;
+    }
+
+    let mut items = Vec::new();
+    let mut selected_item = 0;
+    let mut group = None;
+    for (i, hit) in hits.iter().enumerate() {
+        if group != Some(hit.entity_type) {
+            group = Some(hit.entity_type);
+            items.push(ListItem::new(Span::styled(format!(" {}s", hit.entity_type), styles::title())));
+        }
+        let style = if i == overlay.selected { styles::selected() } else { styles::text() };
+        let mut spans = vec![Span::styled("   ", style)];
+        if hit.field == "name" {
+            spans.extend(highlighted(&hit.name, hit.range.clone(), style));
+        } else {
+            spans.push(Span::styled(hit.name.clone(), style));
+            spans.push(Span::styled(format!("  {}: ", hit.field), styles::text_dim()));
+            spans.extend(highlighted(&hit.text, hit.range.clone(), styles::text_dim()));
+        }
+        if i == overlay.selected {
+            selected_item = items.len();
+        }
+        items.push(ListItem::new(Line::from(spans)));
+    }
+    let mut state = ListState::default().with_selected(Some(selected_item));
+    frame.render_stateful_widget(List::new(items), results_area, &mut state);
+}
+
+/// Render the move-to-client picker (m on a client's project)
+fn render_move_picker(frame: &mut Frame, app: &App, area: Rect) {
+    let Some(picker) = &app.move_picker else {
+        return;
+    };
+    let targets = app.move_targets();
+    let project = app.projects.iter().find(|p| p.id == picker.project).map_or("Project", |p| p.display_name());
+
+    let popup_area = move_picker_area(area);
+    frame.render_widget(Clear, popup_area);
+    let block = Block::default()
+        .title(format!(" Move {} to ", project))
+        .title_style(styles::title_accent())
+        .borders(Borders::ALL)
+        .border_style(styles::border_focused())
+        .style(Style::default().bg(colors::BG_MEDIUM));
+    let inner = block.inner(popup_area);
+    frame.render_widget(block, popup_area);
+    if inner.height < 3 {
+        return;
+    }
+
+    render_query(frame, &picker.query, inner);
+    let results_area = Rect { y: inner.y + 2, height: inner.height - 2, ..inner };
+
+    if targets.is_empty() {
+        frame.render_widget(Paragraph::new(Span::styled(" No other client matches", styles::text_hint())), results_area);
+        return;
+    }
+    let items: Vec<ListItem> = targets
+        .iter()
+        .enumerate()
+        .map(|(i, (client, range))| {
+            let style = if i == picker.selected { styles::selected() } else { styles::text() };
+            let mut spans = vec![Span::styled(" ", style)];
+            spans.extend(highlighted(app.clients[*client].display_name(), range.clone(), style));
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+    let mut state = ListState::default().with_selected(Some(picker.selected));
+    frame.render_stateful_widget(List::new(items), results_area, &mut state);
+}
+
+/// Render the server profile switcher (Ctrl+E)
+fn render_profile_switcher(frame: &mut Frame, app: &App, area: Rect) {
+    let selected = match app.profile_switcher {
+        Some(s) => s,
+        None => return,
+    };
+
+    let popup_area = profile_switcher_area(app, area);
+    frame.render_widget(Clear, popup_area);
 
-    let paragraph = Paragraph::new(help_text)
+    let items: Vec<ListItem> = app
+        .profiles
+        .iter()
+        .map(|(name, profile)| {
+            let active = app.active_profile.as_deref() == Some(name.as_str());
+            let name_style = if config::is_production(name) {
+                Style::default().fg(colors::RED_LIGHT).add_modifier(Modifier::BOLD)
+            } else {
+                styles::text()
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(if active { "● " } else { "  " }, styles::success()),
+                Span::styled(format!("{:<12}", name), name_style),
+                Span::styled(profile.url.as_str(), styles::text_dim()),
+            ]))
+        })
+        .collect();
+
+    let list = List::new(items)
         .block(
             Block::default()
-                .title(" Help ")
-                .title_style(styles::title())
+                .title(" Switch Profile ")
+                .title_style(styles::title_accent())
                 .borders(Borders::ALL)
-                .border_style(styles::border())
+                .border_style(styles::border_focused())
                 .style(Style::default().bg(colors::BG_MEDIUM)),
         )
-        .style(styles::text());
+        .highlight_style(styles::selected());
+
+    let mut state = ListState::default().with_selected(Some(selected));
+    frame.render_stateful_widget(list, popup_area, &mut state);
+}
 
-    frame.render_widget(paragraph, popup_area);
+fn setup_area(content: Rect) -> Rect {
+    centered_rect(70, 16, content)
+}
+
+/// Rows of the setup screen inside its border
+fn setup_rows(popup_area: Rect) -> Rc<[Rect]> {
+    Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(2), // Attempted URL
+            Constraint::Length(1), // Error
+            Constraint::Length(3), // URL field
+            Constraint::Length(1), // Retry button
+            Constraint::Length(1), // Spacer
+            Constraint::Min(0),    // Hints
+        ])
+        .margin(1)
+        .split(popup_area.inner(Margin::new(1, 1)))
+}
+
+fn details_popup_area(area: Rect) -> Rect {
+    centered_rect(64, 24, area)
+}
+
+fn history_area(area: Rect) -> Rect {
+    centered_rect(76, area.height.saturating_sub(6).min(20), area)
+}
+
+fn history_text_area(area: Rect) -> Rect {
+    history_area(area).inner(Margin::new(2, 1))
+}
+
+fn help_area(area: Rect) -> Rect {
+    centered_rect(60, 50, area)
+}
+
+fn help_text_area(area: Rect) -> Rect {
+    help_area(area).inner(Margin::new(1, 1))
+}
+
+fn import_prompt_area(area: Rect) -> Rect {
+    centered_rect(64, 7, area)
+}
+
+fn url_prompt_area(area: Rect) -> Rect {
+    centered_rect(64, 7, area)
+}
+
+fn date_jump_area(area: Rect) -> Rect {
+    centered_rect(52, 7, area)
+}
+
+fn preset_picker_area(app: &App, area: Rect) -> Rect {
+    let naming = app.preset_picker.as_ref().is_some_and(|p| p.naming.is_some());
+    let rows = app.presets.len().max(1) as u16 + if naming { 3 } else { 0 };
+    centered_rect(72, rows.saturating_add(2).min(area.height), area)
+}
+
+fn filter_popup_area(area: Rect) -> Rect {
+    centered_rect(56, 8, area)
+}
+
+/// Columns for the filter's text row, after its label
+fn filter_text_width(popup_area: Rect) -> usize {
+    (popup_area.inner(Margin::new(1, 1)).width as usize).saturating_sub(10)
+}
+
+fn search_area(area: Rect) -> Rect {
+    centered_rect(70, area.height.saturating_sub(6).min(24), area)
+}
+
+fn move_picker_area(area: Rect) -> Rect {
+    centered_rect(50, area.height.saturating_sub(6).min(16), area)
+}
+
+fn profile_switcher_area(app: &App, area: Rect) -> Rect {
+    let popup_height = (app.profiles.len() as u16).saturating_add(2).min(area.height);
+    centered_rect(56, popup_height, area)
+}
+
+/// Every open overlay's area, each grown by a cell so particles stay off
+/// its edges; the same helpers place the overlays when they are drawn
+fn overlay_areas(app: &App, area: Rect) -> Vec<Rect> {
+    let mut areas = Vec::new();
+    if app.setup.is_some() {
+        // Tabs and content, as laid out in `render`
+        let chunks = screen_layout(app, area);
+        areas.push(setup_area(chunks[0].union(chunks[1])));
+    }
+    if let Some(form) = &app.form_state {
+        let form_rect = form_area(form, form_warning_lines(app, form).len() as u16, area);
+        areas.push(form_rect);
+        if let Some((date_str, date_format)) = focused_date(form) {
+            let date = parse_date(date_str, date_format).unwrap_or_else(|| chrono::Local::now().date_naive());
+            areas.extend(mini_calendar_area(date, app.week_start, area, form_rect));
+        }
+    }
+    if let Some(dialog) = &app.confirm_dialog {
+        areas.push(confirm_area(dialog, area));
+    }
+    if let Some(popup) = &app.error_popup {
+        areas.push(error_popup_area(popup, area));
+    }
+    if app.details_popup {
+        areas.push(details_popup_area(area));
+    }
+    if app.history.is_some() {
+        areas.push(history_area(area));
+    }
+    if app.show_help {
+        areas.push(help_area(area));
+    }
+    if app.profile_switcher.is_some() {
+        areas.push(profile_switcher_area(app, area));
+    }
+    if app.import_prompt.is_some() {
+        areas.push(import_prompt_area(area));
+    }
+    if app.url_prompt.is_some() {
+        areas.push(url_prompt_area(area));
+    }
+    if app.date_jump.is_some() {
+        areas.push(date_jump_area(area));
+    }
+    if app.preset_picker.is_some() {
+        areas.push(preset_picker_area(app, area));
+    }
+    if app.filter_popup.is_some() {
+        areas.push(filter_popup_area(area));
+    }
+    if app.search.is_some() {
+        areas.push(search_area(area));
+    }
+    if app.move_picker.is_some() {
+        areas.push(move_picker_area(area));
+    }
+    areas.into_iter().map(|r| grown(r).intersection(area)).collect()
+}
+
+/// `rect` with one more cell on every side
+fn grown(rect: Rect) -> Rect {
+    let (x, y) = (rect.x.saturating_sub(1), rect.y.saturating_sub(1));
+    Rect::new(x, y, rect.right() + 1 - x, rect.bottom() + 1 - y)
 }
 
 /// Helper to create a centered rectangle
@@ -1146,33 +2957,55 @@ fn centered_rect(width: u16, height: u16, area: Rect) -> Rect {
     Rect::new(x, y, width.min(area.width), height.min(area.height))
 }
 
-/// Render a mini calendar popup next to the form
-fn render_mini_calendar(frame: &mut Frame, date_str: &str, screen_area: Rect, form_area: Rect) {
-    // Parse the date string
-    let date = NaiveDate::parse_from_str(date_str, "%Y-%m-%d")
-        .unwrap_or_else(|_| chrono::Local::now().date_naive());
-
-    // Calendar dimensions
-    let cal_width = 24;
-    let cal_height = 10;
+/// Where the mini calendar goes: beside the form if there's room, else
+/// centered; always wholly inside `screen`, or nowhere if it doesn't fit
+fn mini_calendar_rect(screen: Rect, form: Rect, width: u16, height: u16) -> Option<Rect> {
+    // A cropped calendar is misleading; leave it out on tiny screens
+    if screen.width < width || screen.height < height {
+        return None;
+    }
 
     // Position calendar to the right of the form if space, otherwise to the left
-    let cal_x = if form_area.x + form_area.width + cal_width + 2 < screen_area.width {
-        form_area.x + form_area.width + 1
-    } else if form_area.x >= cal_width + 2 {
-        form_area.x - cal_width - 1
+    let x = if form.right() + width + 2 < screen.right() {
+        form.right() + 1
+    } else if form.x >= screen.x + width + 2 {
+        form.x - width - 1
     } else {
         // Center below
-        (screen_area.width.saturating_sub(cal_width)) / 2
+        screen.x + (screen.width - width) / 2
     };
 
-    let cal_y = form_area.y + 2;
-    let cal_area = Rect::new(
-        cal_x,
-        cal_y.min(screen_area.height.saturating_sub(cal_height)),
-        cal_width,
-        cal_height,
-    );
+    Some(Rect::new(
+        x.clamp(screen.x, screen.right() - width),
+        (form.y + 2).clamp(screen.y, screen.bottom() - height),
+        width,
+        height,
+    ))
+}
+
+/// Where the mini calendar for `date` goes, sized for its month: borders,
+/// month, weekday names and hint around the weeks
+fn mini_calendar_area(date: NaiveDate, week_start: WeekStart, screen: Rect, form: Rect) -> Option<Rect> {
+    let cal_height = calendar::weeks(date, week_start).len() as u16 + 5;
+    mini_calendar_rect(screen, form, 24, cal_height)
+}
+
+/// Render a mini calendar popup next to the form
+fn render_mini_calendar(
+    frame: &mut Frame,
+    date_str: &str,
+    date_format: DateFormat,
+    week_start: WeekStart,
+    screen_area: Rect,
+    form_area: Rect,
+) {
+    // Parse the date string
+    let date = parse_date(date_str, date_format).unwrap_or_else(|| chrono::Local::now().date_naive());
+    let weeks = calendar::weeks(date, week_start);
+
+    let Some(cal_area) = mini_calendar_area(date, week_start, screen_area, form_area) else {
+        return;
+    };
 
     frame.render_widget(Clear, cal_area);
 
@@ -1184,17 +3017,6 @@ fn render_mini_calendar(frame: &mut Frame, date_str: &str, screen_area: Rect, fo
     let month_name = month_names[date.month0() as usize];
     let year = date.year();
 
-    // Get first day of month and number of days
-    let first_of_month = NaiveDate::from_ymd_opt(year, date.month(), 1).unwrap();
-    let days_in_month = if date.month() == 12 {
-        NaiveDate::from_ymd_opt(year + 1, 1, 1)
-    } else {
-        NaiveDate::from_ymd_opt(year, date.month() + 1, 1)
-    }.unwrap().pred_opt().unwrap().day();
-
-    // Day of week for first day (0 = Monday, 6 = Sunday)
-    let first_weekday = first_of_month.weekday().num_days_from_monday() as usize;
-
     let mut lines = Vec::new();
 
     // Header with month/year
@@ -1207,14 +3029,18 @@ fn render_mini_calendar(frame: &mut Frame, date_str: &str, screen_area: Rect, fo
     ]));
 
     // Day of week headers
-    lines.push(Line::from(vec![
-        Span::styled(" Mo Tu We Th Fr ", styles::text_dim()),
-        Span::styled("Sa ", Style::default().fg(colors::BLUE)),
-        Span::styled("Su", Style::default().fg(colors::RED)),
-    ]));
+    let mut names = vec![Span::raw(" ")];
+    for column in 0..7 {
+        let weekday = week_start.weekday(column);
+        let style = match weekday {
+            Weekday::Sat | Weekday::Sun => weekday_style(weekday),
+            _ => styles::text_dim(),
+        };
+        names.push(Span::styled(format!("{:.2} ", weekday.to_string()), style));
+    }
+    lines.push(Line::from(names));
 
     // Build week rows
-    let mut day = 1u32;
     let selected_day = date.day();
     let today = chrono::Local::now().date_naive();
     let today_day = if today.year() == year && today.month() == date.month() {
@@ -1223,45 +3049,30 @@ fn render_mini_calendar(frame: &mut Frame, date_str: &str, screen_area: Rect, fo
         None
     };
 
-    for week in 0..6 {
+    for week in weeks {
         let mut spans = Vec::new();
         spans.push(Span::raw(" "));
 
-        for weekday in 0..7 {
-            if (week == 0 && weekday < first_weekday) || day > days_in_month {
+        for (column, day) in week.into_iter().enumerate() {
+            let Some(day) = day else {
                 spans.push(Span::raw("   "));
+                continue;
+            };
+            let style = if day == selected_day {
+                Style::default()
+                    .fg(colors::BG_DARK)
+                    .bg(colors::BLUE)
+                    .add_modifier(Modifier::BOLD)
+            } else if today_day == Some(day) {
+                Style::default()
+                    .fg(colors::YELLOW)
+                    .add_modifier(Modifier::BOLD)
             } else {
-                let is_selected = day == selected_day;
-                let is_today = today_day == Some(day);
-                let is_weekend = weekday >= 5;
-
-                let style = if is_selected {
-                    Style::default()
-                        .fg(colors::BG_DARK)
-                        .bg(colors::BLUE)
-                        .add_modifier(Modifier::BOLD)
-                } else if is_today {
-                    Style::default()
-                        .fg(colors::YELLOW)
-                        .add_modifier(Modifier::BOLD)
-                } else if is_weekend {
-                    if weekday == 5 {
-                        Style::default().fg(colors::BLUE)
-                    } else {
-                        Style::default().fg(colors::RED)
-                    }
-                } else {
-                    styles::text()
-                };
-
-                spans.push(Span::styled(format!("{:2} ", day), style));
-                day += 1;
-            }
-        }
-
-        if day <= days_in_month || week == 0 {
-            lines.push(Line::from(spans));
+                weekday_style(week_start.weekday(column))
+            };
+            spans.push(Span::styled(format!("{:2} ", day), style));
         }
+        lines.push(Line::from(spans));
     }
 
     // Instructions
@@ -1282,10 +3093,682 @@ fn render_mini_calendar(frame: &mut Frame, date_str: &str, screen_area: Rect, fo
     frame.render_widget(calendar, cal_area);
 }
 
-/// Calculate the number of projects (completed/total) for a client
-fn calculate_client_project_counts(projects: &[crate::models::ProjectDto], client_id: uuid::Uuid) -> (i32, i32) {
-    let client_projects: Vec<_> = projects.iter().filter(|p| p.client_id == client_id).collect();
-    let total = client_projects.len() as i32;
-    let completed = client_projects.iter().filter(|p| p.is_completed()).count() as i32;
-    (completed, total)
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ClientDto, ProjectDto};
+    use crate::particles::{ParticleMode, ParticleSystem, DEFAULT_MAX_PARTICLES};
+    use crate::test_support::{client, date, project, user, ClientFixture, ProjectFixture, UserFixture};
+    use ratatui::{backend::TestBackend, buffer::Buffer, Terminal};
+
+    fn seeded_app(seed: u64) -> App {
+        let mut app = App::new();
+        app.particle_system =
+            ParticleSystem::with_seed(ParticleMode::DigitalRain, DEFAULT_MAX_PARTICLES, seed);
+        app
+    }
+
+    fn buffer_text(buffer: &Buffer) -> String {
+        buffer.content().iter().map(|cell| cell.symbol()).collect()
+    }
+
+    fn draw(app: &mut App, width: u16, height: u16, frames: usize) -> Buffer {
+        let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+        for _ in 0..frames {
+            app.tick(width, height);
+            layout(app, Rect::new(0, 0, width, height));
+            terminal.draw(|frame| render(frame, app)).unwrap();
+        }
+        terminal.backend().buffer().clone()
+    }
+
+    /// Column and row where `text` first appears
+    fn find(buffer: &Buffer, text: &str) -> Option<(u16, u16)> {
+        (0..buffer.area.height).find_map(|y| {
+            let row: String = (0..buffer.area.width).map(|x| buffer[(x, y)].symbol()).collect();
+            row.find(text).map(|at| (row[..at].chars().count() as u16, y))
+        })
+    }
+
+    #[test]
+    fn test_small_terminals_stack_panes_and_collapse_the_log() {
+        let mut app = App::new();
+        app.active_tab = Tab::Timeline;
+        app.log(LogEntry::info("Latest line"));
+
+        // 200x50: radar beside the details, five-row log
+        let wide = draw(&mut app, 200, 50, 1);
+        let (radar, details) = (find(&wide, "Orbital Command").unwrap(), find(&wide, "Target Analysis").unwrap());
+        assert_eq!(radar.1, details.1);
+        assert!(details.0 > radar.0 + 100);
+        assert_eq!(find(&wide, " System Log ").map(|(_, y)| y), Some(50 - 1 - LOG_HEIGHT));
+
+        // 80x24: radar above the details, log down to its latest line
+        let small = draw(&mut app, 80, 24, 1);
+        let (radar, details) = (find(&small, "Orbital Command").unwrap(), find(&small, "Target Analysis").unwrap());
+        assert!(details.1 > radar.1);
+        assert!(details.0 < 10);
+        assert_eq!(find(&small, "System Log (v)"), Some((0, 22)));
+        assert_eq!(find(&small, "Latest line").map(|(_, y)| y), Some(22));
+
+        // 'v' brings the whole log back
+        app.handle_key(crossterm::event::KeyEvent::from(crossterm::event::KeyCode::Char('v')));
+        let expanded = draw(&mut app, 80, 24, 1);
+        assert_eq!(find(&expanded, " System Log ").map(|(_, y)| y), Some(24 - 1 - LOG_HEIGHT));
+
+        // Expanded, folded lines show under their summary
+        let mut summary = LogEntry::success("Import finished: 1 ok, 0 failed");
+        summary.details = vec![LogEntry::info("Imported 1/1")];
+        app.log(summary);
+        let expanded = draw(&mut app, 80, 24, 1);
+        let (_, at) = find(&expanded, "Import finished").unwrap();
+        assert_eq!(find(&expanded, "Imported 1/1").map(|(_, y)| y), Some(at + 1));
+        app.handle_key(crossterm::event::KeyEvent::from(crossterm::event::KeyCode::Char('v')));
+        assert!(find(&draw(&mut app, 200, 50, 1), "Imported 1/1").is_none());
+
+        // The Clients tab stacks the same way
+        app.active_tab = Tab::Clients;
+        let clients = draw(&mut app, 80, 24, 1);
+        assert!(find(&clients, "┌ Projects").unwrap().1 > find(&clients, "┌ Clients").unwrap().1);
+    }
+
+    #[test]
+    fn test_seeded_ui_snapshots_are_identical() {
+        let mut a = seeded_app(1234);
+        let mut b = seeded_app(1234);
+        assert_eq!(draw(&mut a, 100, 30, 2), draw(&mut b, 100, 30, 2));
+        assert_eq!(draw(&mut a, 100, 30, 1), draw(&mut b, 100, 30, 1));
+    }
+
+    #[test]
+    fn test_particles_stay_off_open_modals() {
+        let mut rain = seeded_app(99);
+        let mut still = seeded_app(99);
+        still.particle_system.set_mode(ParticleMode::None);
+        for app in [&mut rain, &mut still] {
+            app.active_tab = crate::app::Tab::Clients;
+            app.open_create_form();
+        }
+        let screen = Rect::new(0, 0, 100, 30);
+        let modal = grown(form_area(rain.form_state.as_ref().unwrap(), 0, screen));
+
+        let mut elsewhere = false;
+        for _ in 0..40 {
+            let (a, b) = (draw(&mut rain, 100, 30, 1), draw(&mut still, 100, 30, 1));
+            for (x, y) in screen.positions().map(|p| (p.x, p.y)) {
+                if modal.contains((x, y).into()) {
+                    assert_eq!(a[(x, y)], b[(x, y)], "particle at ({}, {}) over the form", x, y);
+                } else {
+                    elsewhere |= a[(x, y)] != b[(x, y)];
+                }
+            }
+        }
+        assert!(elsewhere, "particles still show outside the modal");
+    }
+
+    #[test]
+    fn test_small_terminals_show_guard_screen() {
+        for (width, height) in [(40, 10), (59, 19)] {
+            let mut app = seeded_app(7);
+            let text = buffer_text(&draw(&mut app, width, height, 2));
+            assert!(text.contains("Terminal too small"), "{}x{}: {}", width, height, text);
+            assert!(text.contains(&format!("{}x{})", width, height)));
+        }
+    }
+
+    #[test]
+    fn test_minimum_size_renders_overlays_without_panic() {
+        let mut app = seeded_app(7);
+        app.open_create_form();
+        app.form_state.as_mut().unwrap().nav_mut().focused_field = 3;
+        app.show_error("API Error", "x".repeat(400));
+        app.show_help = true;
+        let text = buffer_text(&draw(&mut app, MIN_WIDTH, MIN_HEIGHT, 2));
+        assert!(!text.contains("Terminal too small"));
+    }
+
+    #[test]
+    fn test_toasts_slide_in_and_stay_under_modals() {
+        let mut app = seeded_app(7);
+        app.toast(LogLevel::Success, "Project created");
+        assert!(!buffer_text(&draw(&mut app, MIN_WIDTH, 30, 1)).contains("Project created"));
+        assert!(buffer_text(&draw(&mut app, MIN_WIDTH, 30, TOAST_SLIDE_FRAMES as usize)).contains("Project created"));
+
+        // The full-width help overlay covers the corner
+        app.show_help = true;
+        assert!(!buffer_text(&draw(&mut app, MIN_WIDTH, 30, 1)).contains("Project created"));
+    }
+
+    #[test]
+    fn test_click_selects_a_blip_and_ctrl_wheel_zooms() {
+        use crossterm::event::{KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+        let mut app = App::new();
+        app.projects = vec![project(1).around_today(-10, 10).client(1), project(2).around_today(-10, 60).client(2)];
+        app.radar_state.selected_index = Some(0);
+        draw(&mut app, 200, 50, 1);
+        let inner = app.radar_area.unwrap();
+
+        // The cell over the second blip
+        let (x, y) = crate::radar::blip_position(&app.projects[1], app.radar_state.range_days);
+        let column = inner.x + ((x + 100.0) / 200.0 * f64::from(inner.width)) as u16;
+        let row = inner.y + ((100.0 - y) / 200.0 * f64::from(inner.height)) as u16;
+        let mouse = |kind, modifiers| MouseEvent { kind, column, row, modifiers };
+        app.handle_mouse(mouse(MouseEventKind::Down(MouseButton::Left), KeyModifiers::NONE));
+        assert_eq!(app.radar_state.selected_index, Some(1));
+
+        let range = app.radar_state.range_days;
+        app.handle_mouse(mouse(MouseEventKind::ScrollDown, KeyModifiers::NONE));
+        assert_eq!(app.radar_state.range_days, range);
+        app.handle_mouse(mouse(MouseEventKind::ScrollDown, KeyModifiers::CONTROL));
+        assert_eq!(app.radar_state.range_days, range + 7.0);
+        app.handle_mouse(mouse(MouseEventKind::ScrollUp, KeyModifiers::CONTROL));
+        assert_eq!(app.radar_state.range_days, range);
+
+        // Nothing under a popup, nor on another tab
+        app.radar_state.selected_index = Some(0);
+        app.show_help = true;
+        app.handle_mouse(mouse(MouseEventKind::Down(MouseButton::Left), KeyModifiers::NONE));
+        assert_eq!(app.radar_state.selected_index, Some(0));
+        app.show_help = false;
+        app.active_tab = Tab::Users;
+        draw(&mut app, 200, 50, 1);
+        assert_eq!(app.radar_area, None);
+    }
+
+    #[test]
+    fn test_details_preview_a_pending_date_shift() {
+        let mut app = App::new();
+        let project = ProjectDto { start_date: date("2024-01-01"), ..project(1).named("Apollo").around_today(0, 10) };
+        let end = project.planned_end_date;
+        let mut edit = crate::radar::PendingDateEdit::new(&project);
+        edit.shift(3, false);
+        app.projects = vec![project];
+        app.radar_state.selected_index = Some(0);
+        app.radar_state.pending_edit = Some(edit);
+
+        let text = buffer_text(&draw(&mut app, 160, 40, 1));
+        let dates = format!("{} → {}", end, end + chrono::Duration::days(3));
+        assert!(text.contains(&dates));
+        assert!(text.contains("Enter to save, Esc to discard"));
+        assert!(text.contains("+3d"));
+    }
+
+    #[test]
+    fn test_details_show_placeholder_dates_as_not_scheduled() {
+        let mut app = App::new();
+        app.projects = vec![project(1).named("Legacy").starts("0001-01-01").ends("0001-01-01")];
+        app.radar_state.selected_index = Some(0);
+
+        let text = buffer_text(&draw(&mut app, 120, 40, 1));
+        assert!(text.contains("Deadline: Not scheduled"));
+        assert!(text.contains("Start:    Not scheduled"));
+        assert!(text.contains("UNSCHEDULED"));
+        assert!(!text.contains("OVERDUE"));
+    }
+
+    #[test]
+    fn test_reloaded_clients_show_badges() {
+        use crate::api::ApiMessage;
+        let mut app = App::new();
+        app.active_tab = Tab::Clients;
+        app.handle_api_message(ApiMessage::ClientsLoaded(vec![client(1).named("Acme")].into()));
+        let reloaded = vec![client(1).named("Acme Corp"), client(2).named("Globex")];
+        app.handle_api_message(ApiMessage::ClientsLoaded(reloaded.into()));
+
+        let text = buffer_text(&draw(&mut app, 140, 40, 1));
+        assert!(text.contains("Acme Corp"));
+        assert!(text.contains(" CHANGED"));
+        assert!(text.contains(" NEW"));
+    }
+
+    #[test]
+    fn test_empty_list_says_whether_it_loaded() {
+        let mut app = App::new();
+        app.active_tab = Tab::Clients;
+        app.is_loading = false;
+        assert!(buffer_text(&draw(&mut app, 140, 40, 1)).contains("Loading…"));
+
+        use crate::api::{ApiError, ApiMessage, ApiOperation};
+        let refused = ApiError::Connection("Connection refused".to_string());
+        app.handle_api_message(ApiMessage::Failed(ApiOperation::Load(EntityType::Client), refused));
+        app.error_popup = None;
+        let text = buffer_text(&draw(&mut app, 140, 40, 1));
+        assert!(text.contains("Failed to load clients — press r to retry (connection refused)"));
+        assert!(!text.contains("No clients found"));
+
+        app.handle_api_message(ApiMessage::ClientsLoaded(Vec::new().into()));
+        assert!(buffer_text(&draw(&mut app, 140, 40, 1)).contains("No clients found"));
+    }
+
+    #[test]
+    fn test_filtered_out_radar_shows_empty_state() {
+        let mut app = App::new();
+        app.projects = vec![project(1).named("Apollo").ends("2099-01-01")];
+        app.project_filter.text = Some("gemini".to_string());
+
+        let text = buffer_text(&draw(&mut app, 140, 40, 1));
+        assert!(text.contains("No projects match the filter"));
+        assert!(text.contains("filter: \"gemini\""));
+        assert!(text.contains("Orbital Command — \"gemini\" ✕ Esc to clear"));
+    }
+
+    #[test]
+    fn test_calendar_header_follows_week_start() {
+        let mut app = App::new();
+        app.week_start = WeekStart::Sunday;
+        app.date_format = DateFormat::Eu;
+        app.open_create_form();
+        let Some(FormState::Project(form)) = &mut app.form_state else { panic!("no project form") };
+        form.start_date = "15.06.2024".to_string();
+        form.nav.focused_field = form.nav.fields.iter().position(|f| *f == FormField::ProjectStartDate).unwrap();
+
+        let text = buffer_text(&draw(&mut app, 100, 30, 1));
+        assert!(text.contains("June 2024"));
+        assert!(text.contains("Su Mo Tu We Th Fr Sa"));
+        // The month's last day is on its own row rather than cut off
+        assert!(text.contains(" 30 "));
+    }
+
+    #[test]
+    fn test_frozen_animations_render_identical_frames() {
+        let mut app = seeded_app(7);
+        app.set_animations(false);
+        app.toast(LogLevel::Success, "Project created");
+        let first = draw(&mut app, 100, 30, 1);
+        assert!(buffer_text(&first).contains("Project created"), "no slide-in while frozen");
+        assert_eq!(first, draw(&mut app, 100, 30, 1));
+
+        // Running animations do change from frame to frame
+        app.set_animations(true);
+        let moving = draw(&mut app, 100, 30, 1);
+        assert_ne!(moving, draw(&mut app, 100, 30, 1));
+    }
+
+    #[test]
+    fn test_workload_heatmap_lists_the_cursor_cell() {
+        let mut app = App::new();
+        app.active_tab = Tab::Users;
+        app.projects = vec![project(1).named("Apollo").around_today(0, 0)];
+        app.workload_cursor = Some((0, 0));
+        let text = buffer_text(&draw(&mut app, 120, 30, 1));
+        assert!(text.contains("Workload"));
+        assert!(text.contains("Unknown manager"));
+        assert!(text.contains("Apollo"));
+
+        app.workload_cursor = Some((0, 1));
+        let text = buffer_text(&draw(&mut app, 120, 30, 1));
+        assert!(text.contains("No open projects"));
+    }
+
+    #[test]
+    fn test_stale_data_is_flagged_in_the_status_bar() {
+        let mut app = App::new();
+        app.last_refresh = Some(Instant::now());
+        assert!(buffer_text(&draw(&mut app, 200, 30, 1)).contains("│ 0s ago"));
+
+        app.stale_after = Some(std::time::Duration::ZERO);
+        let buffer = draw(&mut app, 200, 30, 1);
+        assert!(buffer_text(&buffer).contains("⚠ offline, data from 0s ago"));
+        app.api_connected = true;
+        let buffer = draw(&mut app, 200, 30, 1);
+        let (x, y) = find(&buffer, "⚠ 0s ago").unwrap();
+        assert_eq!(buffer[(x, y)].fg, styles::warning().fg.unwrap());
+    }
+
+    #[test]
+    fn test_generated_password_shows_until_focus_leaves() {
+        use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+        let mut app = App::new();
+        app.active_tab = Tab::Users;
+        app.handle_key(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::NONE));
+        for _ in 0..2 {
+            app.handle_key(KeyEvent::new(KeyCode::Tab, KeyModifiers::NONE));
+        }
+        app.handle_key(KeyEvent::new(KeyCode::Char('g'), KeyModifiers::CONTROL));
+        let password = app.clipboard.clone().unwrap();
+        assert!(buffer_text(&draw(&mut app, 100, 30, 1)).contains(&password));
+
+        app.handle_key(KeyEvent::new(KeyCode::Tab, KeyModifiers::NONE));
+        let text = buffer_text(&draw(&mut app, 100, 30, 1));
+        assert!(!text.contains(&password));
+        assert!(text.contains(&"*".repeat(16)));
+    }
+
+    #[test]
+    fn test_grouped_users_have_role_headers() {
+        let mut app = App::new();
+        app.active_tab = Tab::Users;
+        app.users = vec![user(1).named("Zed"), user(2).named("Ann").role(Role::Admin), user(3).named("Bea")];
+        app.list_selected = 2;
+        let text = buffer_text(&draw(&mut app, 100, 30, 1));
+        assert!(!text.contains("Admins"));
+
+        app.users_grouped = true;
+        let buffer = draw(&mut app, 100, 30, 1);
+        let text = buffer_text(&buffer);
+        let at = |needle: &str| text.find(needle).unwrap();
+        assert!(at("Admins (1)") < at("Ann") && at("Ann") < at("Managers (2)"));
+        assert!(at("Managers (2)") < at("Bea") && at("Bea") < at("Zed"));
+        let (x, y) = find(&buffer, "Managers (2)").unwrap();
+        assert!(buffer[(x, y)].modifier.contains(Modifier::BOLD));
+        // The highlight follows the selected user to its grouped row
+        let (x, y) = find(&buffer, "Bea").unwrap();
+        assert_eq!(buffer[(x, y)].bg, colors::PURPLE);
+    }
+
+    #[test]
+    fn test_day_popup_lists_the_days_events() {
+        let mut app = App::new();
+        app.active_tab = Tab::Timeline;
+        let today = chrono::Local::now().date_naive();
+        app.projects = vec![project(1).named("Apollo").around_today(-5, 0)];
+        app.calendar = Some(calendar::CalendarState::new(today));
+        let text = buffer_text(&draw(&mut app, 140, 40, 1));
+        assert!(text.contains(&today.format("%B %Y").to_string()));
+        assert!(text.contains("⚑ Apollo"));
+
+        app.calendar.as_mut().unwrap().day_popup = Some(0);
+        let text = buffer_text(&draw(&mut app, 140, 40, 1));
+        assert!(text.contains("Apollo due (since"));
+        assert!(text.contains("Enter jump · Esc close"));
+    }
+
+    #[test]
+    fn test_search_overlay_groups_hits() {
+        let mut app = App::new();
+        app.users = vec![user(2).named("Ada").login("orbiter")];
+        app.projects = vec![project(1).named("Orbital Station")];
+        app.search = Some(crate::app::SearchOverlay { query: TextInput::new("orbit"), selected: 1 });
+        let text = buffer_text(&draw(&mut app, 100, 30, 1));
+        assert!(text.contains(" / orbit█"));
+        assert!(text.contains(" Projects"));
+        assert!(text.contains("Orbital Station"));
+        assert!(text.contains(" Users"));
+        assert!(text.contains("Ada  login: orbiter"));
+
+        app.search = Some(crate::app::SearchOverlay { query: TextInput::new("zzz"), selected: 0 });
+        assert!(buffer_text(&draw(&mut app, 100, 30, 1)).contains("No matches"));
+    }
+
+    #[test]
+    fn test_inline_rename_replaces_the_name_cell() {
+        let mut app = App::new();
+        app.active_tab = Tab::Clients;
+        app.clients = vec![client(1).named("Acme")];
+        app.inline_edit = Some(crate::app::InlineEdit {
+            entity: EntityType::Client,
+            id: uuid::Uuid::from_u128(1),
+            buffer: TextInput::new("Acme Ltd"),
+        });
+        let text = buffer_text(&draw(&mut app, 160, 30, 1));
+        assert!(text.contains("Acme Ltd█"));
+        assert!(text.contains("Enter rename · Esc cancel"));
+    }
+
+    #[test]
+    fn test_frame_stats_overlay() {
+        let mut app = seeded_app(3);
+        let text = buffer_text(&draw(&mut app, 100, 30, 1));
+        assert!(!text.contains("render "));
+
+        app.show_frame_stats = true;
+        app.frame_stats.target_fps = 20;
+        app.frame_stats.api_queue = 2;
+        let text = buffer_text(&draw(&mut app, 100, 30, 1));
+        assert!(text.contains("fps - / 20"));
+        assert!(text.contains("queue api 2 · cmd 0"));
+        assert!(text.contains("particles "));
+        assert!(!text.contains("projects: "));
+
+        use crate::app::FetchStats;
+        use std::time::Duration;
+        let stats = FetchStats { status: Some(200), duration: Duration::from_millis(1400), items: 300 };
+        app.fetch_stats.insert(EntityType::Project, stats);
+        let stats = FetchStats { status: None, duration: Duration::from_secs(30), items: 0 };
+        app.fetch_stats.insert(EntityType::User, stats);
+        let text = buffer_text(&draw(&mut app, 100, 30, 1));
+        assert!(text.contains("projects: 200 OK · 1.4s · 300 items"));
+        assert!(text.contains("users: no response · 30.0s · 0 items"));
+    }
+
+    #[test]
+    fn test_long_error_scrolls_on_a_small_terminal() {
+        use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+        let mut app = App::new();
+        let words: Vec<String> = (1..=300).map(|n| format!("w{:03}", n)).collect();
+        app.show_error("Server Error", words.join(" "));
+
+        let text = buffer_text(&draw(&mut app, 80, 20, 1));
+        assert!(text.contains("w001"));
+        assert!(!text.contains("w300"));
+        assert!(text.contains('█'), "no scrollbar:\n{}", text);
+        assert_eq!(app.key_hints(), "j/k PgUp/PgDn scroll · Esc/Enter dismiss");
+
+        for _ in 0..10 {
+            app.handle_key(KeyEvent::new(KeyCode::PageDown, KeyModifiers::NONE));
+        }
+        let text = buffer_text(&draw(&mut app, 80, 20, 1));
+        assert!(text.contains("w300"));
+        assert!(!text.contains("w001"));
+        // Reading on keeps the popup up past its auto-dismiss time
+        assert_eq!(app.error_popup.as_ref().unwrap().auto_dismiss, None);
+    }
+
+    #[test]
+    fn test_help_scrolls_to_its_last_line() {
+        use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+        let mut app = App::new();
+        app.show_help = true;
+        assert!(!buffer_text(&draw(&mut app, 80, 20, 1)).contains("Switch server profile"));
+
+        for _ in 0..5 {
+            app.handle_key(KeyEvent::new(KeyCode::PageDown, KeyModifiers::NONE));
+        }
+        let text = buffer_text(&draw(&mut app, 80, 20, 1));
+        assert!(text.contains("Switch server profile"));
+        app.handle_key(KeyEvent::new(KeyCode::Char('k'), KeyModifiers::NONE));
+        assert!(app.show_help);
+    }
+
+    #[test]
+    fn test_mini_calendar_stays_inside_the_frame() {
+        let screen = Rect::new(0, 0, 60, 20);
+        for form in [Rect::new(5, 2, 50, 18), Rect::new(0, 15, 60, 5), Rect::new(30, 0, 30, 20)] {
+            let cal = mini_calendar_rect(screen, form, 24, 11).unwrap();
+            assert_eq!(cal.intersection(screen), cal, "{:?} leaves the frame", cal);
+        }
+        // Beside the form when there is room
+        let wide = Rect::new(0, 0, 140, 40);
+        assert_eq!(mini_calendar_rect(wide, Rect::new(40, 5, 60, 30), 24, 11), Some(Rect::new(101, 7, 24, 11)));
+        assert_eq!(mini_calendar_rect(Rect::new(0, 0, 20, 20), screen, 24, 11), None);
+    }
+
+    #[test]
+    fn test_details_popup_covers_the_radar() {
+        let mut app = seeded_app(7);
+        app.radar_state.selected_index = Some(0);
+        app.details_popup = true;
+        let text = buffer_text(&draw(&mut app, 140, 40, 1));
+        assert_eq!(text.matches("Target Analysis").count(), 2);
+    }
+
+    #[test]
+    fn test_history_popup_draws_each_change() {
+        let mut app = seeded_app(7);
+        let record = ChangeRecord {
+            timestamp: "2024-05-01T12:30:00Z".parse().unwrap(),
+            user: Some("ada".to_string()),
+            field: "name".to_string(),
+            old: Some("Apollo".to_string()),
+            new: Some("Artemis".to_string()),
+        };
+        let popup = |records| HistoryPopup {
+            project_id: uuid::Uuid::nil(),
+            name: "Apollo".to_string(),
+            records,
+            scroll: PopupScroll::default(),
+        };
+        app.history = Some(popup(None));
+        assert!(buffer_text(&draw(&mut app, 140, 40, 1)).contains("Loading…"));
+        app.history = Some(popup(Some(Ok(Vec::new()))));
+        assert!(buffer_text(&draw(&mut app, 140, 40, 1)).contains("No changes recorded"));
+
+        app.history = Some(popup(Some(Ok(vec![record]))));
+        let buffer = draw(&mut app, 140, 40, 1);
+        let text = buffer_text(&buffer);
+        assert!(text.contains("History: Apollo"));
+        assert!(text.contains("ada name: Apollo → Artemis"), "{}", text);
+        let (x, y) = find(&buffer, "Artemis").unwrap();
+        assert_eq!(buffer[(x, y)].fg, colors::GREEN);
+        let (x, y) = find(&buffer, "Apollo →").unwrap();
+        assert!(buffer[(x, y)].modifier.contains(Modifier::CROSSED_OUT));
+    }
+
+    #[test]
+    fn test_preset_picker_marks_missing_references() {
+        let mut app = seeded_app(7);
+        app.preset_picker = Some(PresetPicker::default());
+        assert!(buffer_text(&draw(&mut app, 140, 40, 1)).contains("No presets yet"));
+
+        let client = client(1).named("Acme");
+        app.clients = vec![client.clone()];
+        let filter = crate::filter::ProjectFilter { client_id: Some(client.id), ..Default::default() };
+        app.presets.save(crate::presets::FilterPreset::new("Mine", &filter));
+        let gone = crate::filter::ProjectFilter { client_id: Some(uuid::Uuid::nil()), ..Default::default() };
+        app.presets.save(crate::presets::FilterPreset::new("Old", &gone));
+        let text = buffer_text(&draw(&mut app, 140, 40, 1));
+        assert!(text.contains(&format!("{:<20} {}", "Mine", client.display_name())), "{}", text);
+        assert!(text.contains("client (missing)"));
+    }
+
+    #[test]
+    fn test_breadcrumb_sits_under_the_tabs() {
+        let mut app = seeded_app(7);
+        app.project_filter.archive_after_days = Some(app.archive_after_days);
+        let text = buffer_text(&draw(&mut app, 140, 40, 1));
+        assert!(!text.contains("^X reset"));
+
+        app.project_sort = crate::models::ProjectSort::Name;
+        let buffer = draw(&mut app, 140, 40, 1);
+        assert_eq!(find(&buffer, "Sort: name"), Some((1, 3)));
+        assert!(buffer_text(&buffer).contains("Sort: name  ^X reset"));
+    }
+
+    #[test]
+    fn test_status_bar_flags_a_short_full_load() {
+        let mut app = App::new();
+        let users = crate::api::FetchedAll {
+            items: vec![user(1).named("Ada").login("ada").role(Role::Admin)],
+            total_count: Some(14),
+            total_pages: 2,
+            pages: 2,
+            failed: None,
+            anomaly: None,
+        };
+        app.handle_api_message(crate::api::ApiMessage::UsersLoaded(users));
+        app.handle_api_message(crate::api::ApiMessage::ClientsLoaded(Vec::new().into()));
+        assert!(app.paging.users.partial && !app.paging.clients.partial);
+        assert!(app.logs.iter().any(|l| l.message == "Loaded 1 of 14 users; the server reports more"));
+
+        let buffer = draw(&mut app, 200, 30, 1);
+        let text = buffer_text(&buffer);
+        assert!(text.contains("0 projects · 0 clients · ⚠ 1 of 14 users"));
+        let start = text.chars().position(|c| c == '⚠').unwrap();
+        assert_eq!(buffer.content()[start].fg, colors::YELLOW);
+    }
+
+    #[test]
+    fn test_status_bar_shows_the_api_host() {
+        let mut app = App::new();
+        app.api_url = "https://abc123.tunnel.example.com:8443/api".to_string();
+        let text = buffer_text(&draw(&mut app, 200, 30, 1));
+        assert!(text.contains("default @ abc123.tunnel.example.com:8443"), "{}", text);
+
+        let error = Some("Invalid URL".to_string());
+        app.url_prompt = Some(crate::app::UrlPrompt { url: TextInput::new("nope"), error });
+        let text = buffer_text(&draw(&mut app, 200, 30, 1));
+        assert!(text.contains(" Set API URL ") && text.contains("Invalid URL"));
+    }
+
+    #[test]
+    fn test_status_bar_flags_insecure_tls() {
+        let mut app = App::new();
+        assert!(!buffer_text(&draw(&mut app, 200, 30, 1)).contains("INSECURE"));
+        app.insecure = true;
+        let buffer = draw(&mut app, 200, 30, 1);
+        let text = buffer_text(&buffer);
+        let start = text.find("⚠ INSECURE: certificates not verified").unwrap();
+        let cell = text[..start].chars().count();
+        assert_eq!(buffer.content()[cell].bg, colors::RED_LIGHT);
+    }
+
+    #[test]
+    fn test_jump_to_date_rings_the_day() {
+        use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+        let mut app = App::new();
+        app.handle_key(KeyEvent::new(KeyCode::Char('J'), KeyModifiers::NONE));
+        let text = buffer_text(&draw(&mut app, 120, 40, 1));
+        assert!(text.contains("Jump to Date"));
+        assert!(text.contains("+10d / -2w / +3m"));
+
+        app.date_jump = Some(TextInput::new("+6w"));
+        app.handle_key(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+        let day = chrono::Local::now().date_naive() + chrono::Duration::weeks(6);
+        let text = buffer_text(&draw(&mut app, 120, 40, 1));
+        assert!(!text.contains("Jump to Date"));
+        assert!(text.contains(&day.format(" %d %b ").to_string()));
+    }
+
+    #[test]
+    fn test_prompt_field_keeps_its_scroll_as_the_cursor_moves() {
+        use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+        let press = |app: &mut App, code| app.handle_key(KeyEvent::new(code, KeyModifiers::NONE));
+        let mut app = App::new();
+        press(&mut app, KeyCode::Char('U'));
+        let url = format!("https://sweem.example.com/{}", "x".repeat(40));
+        app.url_prompt.as_mut().unwrap().url.set_value(url);
+        assert!(buffer_text(&draw(&mut app, 120, 40, 1)).contains("xxxx█"));
+
+        // Back at the start, moving right stays within the field
+        press(&mut app, KeyCode::Home);
+        for _ in 0..5 {
+            press(&mut app, KeyCode::Right);
+        }
+        assert!(buffer_text(&draw(&mut app, 120, 40, 1)).contains("https://sweem"));
+    }
+
+    #[test]
+    fn test_days_left_in_working_days() {
+        let mut app = App::new();
+        let today = chrono::Local::now().date_naive();
+        let end = crate::dates::add_working_days(today, 14);
+        app.projects = vec![ProjectDto { planned_end_date: end, ..project(1).named("Apollo").around_today(-5, 0) }];
+        app.radar_state.selected_index = Some(0);
+        let text = buffer_text(&draw(&mut app, 140, 40, 1));
+        assert!(text.contains(&format!("{} days left", (end - today).num_days())));
+
+        app.day_count = crate::dates::DayCount::Working;
+        let text = buffer_text(&draw(&mut app, 140, 40, 1));
+        assert!(text.contains("14 wd left"));
+        assert!(text.contains("14wd Apollo"));
+    }
+
+    #[test]
+    fn test_upcoming_deadlines_under_the_details() {
+        let mut app = App::new();
+        app.clients = vec![ClientDto { projects_total: 1, ..client(9).named("Acme") }];
+        app.projects = vec![project(1).named("Apollo").client(9).around_today(-5, 2)];
+        let text = buffer_text(&draw(&mut app, 140, 40, 1));
+        assert!(text.contains("Upcoming Deadlines"));
+        assert!(text.contains("2d Apollo · Acme"));
+
+        // Short terminals keep the whole panel for the details
+        assert!(!buffer_text(&draw(&mut app, 140, 24, 1)).contains("Upcoming Deadlines"));
+
+        // The Clients tab lists the selected client's projects beside it
+        app.active_tab = crate::app::Tab::Clients;
+        let text = buffer_text(&draw(&mut app, 140, 30, 1));
+        assert!(text.contains("Acme · Projects"));
+        assert!(text.contains("● Apollo ·"));
+    }
 }
@@ -0,0 +1,216 @@
+//! "Upcoming deadlines" list under the Target Analysis panel.
+//!
+//! `upcoming` picks the open projects with the nearest planned end dates and
+//! `Thresholds::urgency` grades the days left; both are pure so the ordering
+//! and the colour cut-offs can be tested. `UpcomingWidget` draws the list.
+
+use chrono::NaiveDate;
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Widget};
+
+use crate::dates::DayCount;
+use crate::models::{ClientDto, ProjectDto};
+use crate::theme::{colors, styles};
+
+/// Entries in the list
+pub const UPCOMING: usize = 8;
+
+/// Default days left at or below which a deadline shows red
+pub const DEFAULT_URGENT_DAYS: i64 = 3;
+
+/// Default days left at or below which a deadline shows yellow
+pub const DEFAULT_SOON_DAYS: i64 = 7;
+
+/// How close a deadline is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Urgency {
+    Urgent,
+    Soon,
+    Normal,
+}
+
+/// Colour cut-offs, in days left (`deadline_urgent_days` and `deadline_soon_days`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Thresholds {
+    pub urgent: i64,
+    pub soon: i64,
+}
+
+impl Default for Thresholds {
+    fn default() -> Self {
+        Self { urgent: DEFAULT_URGENT_DAYS, soon: DEFAULT_SOON_DAYS }
+    }
+}
+
+impl Thresholds {
+    /// Grade a deadline `days_left` days away; past deadlines are urgent
+    pub fn urgency(&self, days_left: i64) -> Urgency {
+        if days_left <= self.urgent {
+            Urgency::Urgent
+        } else if days_left <= self.soon {
+            Urgency::Soon
+        } else {
+            Urgency::Normal
+        }
+    }
+}
+
+/// Colour of the days-left figure
+pub fn urgency_color(urgency: Urgency) -> Color {
+    match urgency {
+        Urgency::Urgent => colors::RED,
+        Urgency::Soon => colors::YELLOW,
+        Urgency::Normal => colors::FG_PRIMARY,
+    }
+}
+
+/// Indices of up to `limit` shown, scheduled, unfinished projects, nearest
+/// planned end first; overdue ones lead. Ties go by name, then id.
+pub fn upcoming(projects: &[ProjectDto], visible: &[usize], limit: usize) -> Vec<usize> {
+    let mut open: Vec<usize> = visible
+        .iter()
+        .copied()
+        .filter(|&i| projects[i].has_valid_dates() && !projects[i].is_completed())
+        .collect();
+    open.sort_by_cached_key(|&i| {
+        let p = &projects[i];
+        (p.planned_end_date, p.display_name().to_lowercase(), p.id)
+    });
+    open.truncate(limit);
+    open
+}
+
+/// "today", "3d" or "2d late" ("3wd", "2wd late" counting working days)
+pub fn days_left_label(days: i64, count: DayCount) -> String {
+    match days {
+        0 => "today".to_string(),
+        d if d < 0 => format!("{}{} late", -d, count.suffix()),
+        d => format!("{}{}", d, count.suffix()),
+    }
+}
+
+/// The list, with an optional highlighted row while it has focus
+pub struct UpcomingWidget<'a> {
+    projects: &'a [ProjectDto],
+    clients: &'a [ClientDto],
+    entries: &'a [usize],
+    today: NaiveDate,
+    thresholds: Thresholds,
+    day_count: DayCount,
+    cursor: Option<usize>,
+}
+
+impl<'a> UpcomingWidget<'a> {
+    pub fn new(projects: &'a [ProjectDto], clients: &'a [ClientDto], entries: &'a [usize], today: NaiveDate) -> Self {
+        Self {
+            projects,
+            clients,
+            entries,
+            today,
+            thresholds: Thresholds::default(),
+            day_count: DayCount::default(),
+            cursor: None,
+        }
+    }
+
+    pub fn thresholds(mut self, thresholds: Thresholds) -> Self {
+        self.thresholds = thresholds;
+        self
+    }
+
+    /// How the days left are counted; the colours always go by calendar days
+    pub fn day_count(mut self, day_count: DayCount) -> Self {
+        self.day_count = day_count;
+        self
+    }
+
+    /// Highlight this row and the border, for when the list has focus
+    pub fn cursor(mut self, cursor: Option<usize>) -> Self {
+        self.cursor = cursor;
+        self
+    }
+}
+
+impl Widget for UpcomingWidget<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let border = if self.cursor.is_some() { styles::border_focused() } else { styles::border() };
+        let block = Block::default()
+            .title(" Upcoming Deadlines ")
+            .title_style(styles::title_accent())
+            .borders(Borders::ALL)
+            .border_style(border)
+            .style(Style::default().bg(colors::BG_MEDIUM));
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        if self.entries.is_empty() {
+            buf.set_string(inner.x + 1, inner.y, "No open deadlines", styles::text_hint());
+            return;
+        }
+
+        for (row, &i) in self.entries.iter().enumerate().take(inner.height as usize) {
+            let project = &self.projects[i];
+            let days = (project.planned_end_date - self.today).num_days();
+            let left = self.day_count.between(self.today, project.planned_end_date);
+            let client = self
+                .clients
+                .iter()
+                .find(|c| c.id == project.client_id)
+                .map_or("Unknown client", |c| c.display_name());
+            let selected = self.cursor == Some(row);
+            let name_style = if selected { styles::selected() } else { styles::text() };
+            let line = Line::from(vec![
+                Span::styled(
+                    format!(" {:>8} ", days_left_label(left, self.day_count)),
+                    Style::default().fg(urgency_color(self.thresholds.urgency(days))),
+                ),
+                Span::styled(project.display_name().to_string(), name_style),
+                Span::styled(format!(" · {}", client), styles::text_dim()),
+            ]);
+            buf.set_line(inner.x, inner.y + row as u16, &line, inner.width);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{project, ProjectFixture};
+
+    #[test]
+    fn test_upcoming_orders_open_projects_by_deadline() {
+        let projects = vec![
+            project(1).named("Zeta").ends("2024-06-01"),
+            project(2).named("Done").ends("2024-05-01").done(),
+            project(3).named("Alpha").ends("2024-06-01"),
+            project(4).named("Late").ends("2024-04-20"),
+            project(5).named("Hidden").ends("2024-05-02"),
+            project(6).named("Later").ends("2024-09-01"),
+        ];
+        let visible = [0, 1, 2, 3, 5];
+        assert_eq!(upcoming(&projects, &visible, UPCOMING), [3, 2, 0, 5]);
+        assert_eq!(upcoming(&projects, &visible, 2), [3, 2]);
+        assert!(upcoming(&projects, &[], UPCOMING).is_empty());
+    }
+
+    #[test]
+    fn test_urgency_thresholds() {
+        let default = Thresholds::default();
+        assert_eq!(default.urgency(-4), Urgency::Urgent);
+        assert_eq!(default.urgency(3), Urgency::Urgent);
+        assert_eq!(default.urgency(4), Urgency::Soon);
+        assert_eq!(default.urgency(7), Urgency::Soon);
+        assert_eq!(default.urgency(8), Urgency::Normal);
+
+        let strict = Thresholds { urgent: 1, soon: 1 };
+        assert_eq!(strict.urgency(2), Urgency::Normal);
+        assert_eq!(days_left_label(0, DayCount::Calendar), "today");
+        assert_eq!(days_left_label(-2, DayCount::Calendar), "2d late");
+        assert_eq!(days_left_label(12, DayCount::Calendar), "12d");
+        assert_eq!(days_left_label(12, DayCount::Working), "12wd");
+        assert_eq!(days_left_label(-1, DayCount::Working), "1wd late");
+    }
+}
@@ -0,0 +1,152 @@
+//! Users tab grouped by role ('b').
+//!
+//! `group_rows` lays the users out as display rows: a header per role, then
+//! that role's users sorted by name. The selection and every CRUD shortcut
+//! work on indices into the user list, so the mapping between display rows
+//! and user indices lives here where it can be tested.
+
+use crate::models::{Role, UserDto};
+
+/// Roles in the order their groups are shown
+const GROUP_ORDER: [Role; 2] = [Role::Admin, Role::Manager];
+
+/// One row of the grouped list
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UserRow {
+    Header { role: Role, count: usize },
+    /// Index into the user list
+    User(usize),
+}
+
+/// "Admins (3)"
+pub fn group_label(role: Role, count: usize) -> String {
+    let name = match role {
+        Role::Admin => "Admins",
+        Role::Manager => "Managers",
+    };
+    format!("{} ({})", name, count)
+}
+
+/// Header and users of each role that has any, users by name (ignoring
+/// case), then by id
+pub fn group_rows(users: &[UserDto]) -> Vec<UserRow> {
+    let mut rows = Vec::new();
+    for role in GROUP_ORDER {
+        let mut members: Vec<usize> = (0..users.len()).filter(|&i| users[i].role == role).collect();
+        if members.is_empty() {
+            continue;
+        }
+        members.sort_by_cached_key(|&i| (users[i].display_name().to_lowercase(), users[i].id));
+        rows.push(UserRow::Header { role, count: members.len() });
+        rows.extend(members.into_iter().map(UserRow::User));
+    }
+    rows
+}
+
+/// Display row of the user at `index`
+pub fn row_of(rows: &[UserRow], index: usize) -> Option<usize> {
+    rows.iter().position(|row| *row == UserRow::User(index))
+}
+
+/// User index shown on display row `row`; `None` on a header
+pub fn user_at(rows: &[UserRow], row: usize) -> Option<usize> {
+    match rows.get(row)? {
+        UserRow::User(index) => Some(*index),
+        UserRow::Header { .. } => None,
+    }
+}
+
+/// User indices in display order, headers left out
+fn order(rows: &[UserRow]) -> Vec<usize> {
+    (0..rows.len()).filter_map(|row| user_at(rows, row)).collect()
+}
+
+/// The user `delta` rows from `index` in display order, skipping headers and
+/// wrapping around; a user not in `rows` steps in from either end
+pub fn step(rows: &[UserRow], index: usize, delta: isize) -> Option<usize> {
+    let order = order(rows);
+    if order.is_empty() {
+        return None;
+    }
+    let len = order.len() as isize;
+    let next = match order.iter().position(|&i| i == index) {
+        Some(at) => (at as isize + delta).rem_euclid(len),
+        None if delta < 0 => len - 1,
+        None => 0,
+    };
+    Some(order[next as usize])
+}
+
+/// First (`last` false) or last user in display order
+pub fn edge(rows: &[UserRow], last: bool) -> Option<usize> {
+    let order = order(rows);
+    if last { order.last().copied() } else { order.first().copied() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{user, UserFixture};
+
+    fn users() -> Vec<UserDto> {
+        vec![
+            user(1).named("zoe"),
+            user(2).named("Ada").role(Role::Admin),
+            user(3).named("bob"),
+            user(4).named("Cy").role(Role::Admin),
+            user(5).named("Al"),
+        ]
+    }
+
+    #[test]
+    fn test_groups_are_headed_and_sorted_by_name() {
+        let rows = group_rows(&users());
+        assert_eq!(
+            rows,
+            [
+                UserRow::Header { role: Role::Admin, count: 2 },
+                UserRow::User(1),
+                UserRow::User(3),
+                UserRow::Header { role: Role::Manager, count: 3 },
+                UserRow::User(4),
+                UserRow::User(2),
+                UserRow::User(0),
+            ]
+        );
+        assert_eq!(group_label(Role::Manager, 3), "Managers (3)");
+
+        // A role nobody has gets no header
+        let managers = vec![user(1).named("zoe")];
+        assert_eq!(group_rows(&managers), [UserRow::Header { role: Role::Manager, count: 1 }, UserRow::User(0)]);
+        assert!(group_rows(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_rows_map_to_user_indices() {
+        let rows = group_rows(&users());
+        assert_eq!(row_of(&rows, 0), Some(6));
+        assert_eq!(row_of(&rows, 3), Some(2));
+        assert_eq!(row_of(&rows, 9), None);
+        assert_eq!(user_at(&rows, 4), Some(4));
+        assert_eq!(user_at(&rows, 3), None);
+        assert_eq!(user_at(&rows, 7), None);
+        for index in 0..users().len() {
+            assert_eq!(user_at(&rows, row_of(&rows, index).unwrap()), Some(index));
+        }
+    }
+
+    #[test]
+    fn test_stepping_skips_headers_and_wraps() {
+        let rows = group_rows(&users());
+        // Cy is the last admin; the Managers header is stepped over
+        assert_eq!(step(&rows, 3, 1), Some(4));
+        assert_eq!(step(&rows, 4, -1), Some(3));
+        assert_eq!(step(&rows, 0, 1), Some(1));
+        assert_eq!(step(&rows, 1, -1), Some(0));
+        // An index outside the list starts at either end
+        assert_eq!(step(&rows, 9, 1), Some(1));
+        assert_eq!(step(&rows, 9, -1), Some(0));
+        assert_eq!(step(&[], 0, 1), None);
+        assert_eq!((edge(&rows, false), edge(&rows, true)), (Some(1), Some(0)));
+    }
+}
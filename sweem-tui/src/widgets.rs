@@ -0,0 +1,374 @@
+//! Single-line text input shared by the forms, prompts and overlays.
+//!
+//! `TextInput` holds the text, a cursor and how far the text is scrolled,
+//! and handles the editing keys. Values wider than the field scroll sideways
+//! as the cursor moves to keep it in view, measured in terminal columns so
+//! wide characters fit. `TextInputWidget` draws it.
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::Widget;
+use unicode_width::UnicodeWidthChar;
+
+/// Drawn after the last character while the cursor sits there
+const END_CURSOR: &str = "█";
+
+/// Editable text with a cursor
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TextInput {
+    value: String,
+    /// Cursor position, in chars
+    cursor: usize,
+    /// First char shown, moved along with the cursor
+    scroll: usize,
+    /// Columns of the field it is drawn in, as set by `ui::layout`
+    width: usize,
+    /// Draw every char as '*'
+    masked: bool,
+    /// Show a masked text as it is for now, e.g. a generated password
+    revealed: bool,
+}
+
+impl TextInput {
+    /// `value` with the cursor after its last char
+    pub fn new(value: impl Into<String>) -> Self {
+        let value = value.into();
+        Self { cursor: value.chars().count(), value, ..Self::default() }
+    }
+
+    /// Hide the text behind '*', for passwords
+    pub fn masked(mut self) -> Self {
+        self.masked = true;
+        self
+    }
+
+    /// Show the text of a masked input until `hide`
+    pub fn reveal(&mut self) {
+        self.revealed = true;
+    }
+
+    pub fn hide(&mut self) {
+        self.revealed = false;
+    }
+
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.value.is_empty()
+    }
+
+    /// Whether the cursor is after the last char, where typing appends
+    pub fn cursor_at_end(&self) -> bool {
+        self.cursor == self.value.chars().count()
+    }
+
+    /// Replace the text and put the cursor at its end
+    pub fn set_value(&mut self, value: impl Into<String>) {
+        self.value = value.into();
+        self.cursor = self.value.chars().count();
+        self.scroll = self.fit(self.width);
+    }
+
+    /// The text, leaving the input empty
+    pub fn take(&mut self) -> String {
+        self.cursor = 0;
+        self.scroll = 0;
+        std::mem::take(&mut self.value)
+    }
+
+    /// Scroll for a field `width` columns wide from now on
+    pub fn set_width(&mut self, width: usize) {
+        self.width = width;
+        self.scroll = self.fit(width);
+    }
+
+    /// Byte offset of the char at `index`, or the length at the end
+    fn byte_at(&self, index: usize) -> usize {
+        self.value.char_indices().nth(index).map_or(self.value.len(), |(i, _)| i)
+    }
+
+    pub fn insert(&mut self, c: char) {
+        let at = self.byte_at(self.cursor);
+        self.value.insert(at, c);
+        self.cursor += 1;
+        self.scroll = self.fit(self.width);
+    }
+
+    /// Remove the char before the cursor; false at the start
+    pub fn backspace(&mut self) -> bool {
+        if self.cursor == 0 {
+            return false;
+        }
+        self.cursor -= 1;
+        let at = self.byte_at(self.cursor);
+        self.value.remove(at);
+        self.scroll = self.fit(self.width);
+        true
+    }
+
+    /// Remove the char under the cursor; false at the end
+    pub fn delete(&mut self) -> bool {
+        let at = self.byte_at(self.cursor);
+        if at == self.value.len() {
+            return false;
+        }
+        self.value.remove(at);
+        self.scroll = self.fit(self.width);
+        true
+    }
+
+    /// Typing, Backspace/Delete, ←→ and Home/End; returns whether the key
+    /// was used. Ctrl chords are left to the caller.
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        let len = self.value.chars().count();
+        match key.code {
+            KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => self.insert(c),
+            KeyCode::Backspace => {
+                self.backspace();
+            }
+            KeyCode::Delete => {
+                self.delete();
+            }
+            KeyCode::Left => self.cursor = self.cursor.saturating_sub(1),
+            KeyCode::Right => self.cursor = (self.cursor + 1).min(len),
+            KeyCode::Home => self.cursor = 0,
+            KeyCode::End => self.cursor = len,
+            _ => return false,
+        }
+        self.scroll = self.fit(self.width);
+        true
+    }
+
+    /// The chars as drawn
+    fn shown(&self) -> Vec<char> {
+        match self.masked && !self.revealed {
+            true => vec!['*'; self.value.chars().count()],
+            false => self.value.chars().collect(),
+        }
+    }
+
+    /// First char to draw in `width` columns with the cursor in view.
+    /// Keeps the current scroll when it still works, so the text doesn't
+    /// jump about as the cursor moves.
+    pub fn fit(&self, width: usize) -> usize {
+        let chars = self.shown();
+        let cols = |range: &[char]| range.iter().map(|c| c.width().unwrap_or(0)).sum::<usize>();
+        let cursor_cols = chars.get(self.cursor).map_or(1, |c| c.width().unwrap_or(0).max(1));
+
+        let mut scroll = self.scroll.min(self.cursor);
+        while scroll < self.cursor && cols(&chars[scroll..self.cursor]) + cursor_cols > width {
+            scroll += 1;
+        }
+        // Pull earlier text back in when the end has room for it
+        let tail = |from: usize| cols(&chars[from..]) + usize::from(self.cursor == chars.len());
+        while scroll > 0 && tail(scroll - 1) <= width {
+            scroll -= 1;
+        }
+        scroll
+    }
+
+    /// The visible part of the text for `width` columns; with `focused`, the
+    /// cursor is drawn reversed, or as a block at the end
+    pub fn spans(&self, width: usize, focused: bool, style: Style) -> Vec<Span<'static>> {
+        let chars = self.shown();
+        let scroll = self.fit(width);
+        // As many chars of `range` as fit in `room` columns
+        let fitting = |range: &[char], mut room: usize| {
+            range
+                .iter()
+                .take_while(|c| {
+                    let w = c.width().unwrap_or(0);
+                    let fits = w <= room;
+                    room = room.saturating_sub(w);
+                    fits
+                })
+                .collect::<String>()
+        };
+        if !focused {
+            return vec![Span::styled(fitting(&chars[scroll..], width), style)];
+        }
+        let before: String = chars[scroll..self.cursor].iter().collect();
+        let (cursor, cursor_cols) = match chars.get(self.cursor) {
+            Some(c) => (Span::styled(c.to_string(), style.add_modifier(Modifier::REVERSED)), c.width().unwrap_or(0)),
+            None => (Span::styled(END_CURSOR, style), 1),
+        };
+        let room = width.saturating_sub(before.chars().map(|c| c.width().unwrap_or(0)).sum::<usize>() + cursor_cols);
+        let after = fitting(chars.get(self.cursor + 1..).unwrap_or_default(), room);
+        vec![Span::styled(before, style), cursor, Span::styled(after, style)]
+    }
+}
+
+/// Draws a `TextInput` on the first row of its area
+pub struct TextInputWidget<'a> {
+    input: &'a TextInput,
+    focused: bool,
+    style: Style,
+    ghost: Option<&'a str>,
+}
+
+impl<'a> TextInputWidget<'a> {
+    pub fn new(input: &'a TextInput) -> Self {
+        Self { input, focused: false, style: Style::default(), ghost: None }
+    }
+
+    /// Suggested rest of the text, drawn dim after it while the cursor is
+    /// at the end
+    pub fn ghost(mut self, ghost: Option<&'a str>) -> Self {
+        self.ghost = ghost;
+        self
+    }
+
+    /// Show the cursor
+    pub fn focused(mut self, focused: bool) -> Self {
+        self.focused = focused;
+        self
+    }
+
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+}
+
+impl Widget for TextInputWidget<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.is_empty() {
+            return;
+        }
+        let mut spans = self.input.spans(area.width as usize, self.focused, self.style);
+        let ghost = self.ghost.filter(|g| self.focused && self.input.cursor_at_end() && !g.is_empty());
+        if let Some(ghost) = ghost {
+            // The cursor moves from the end block onto the first suggested char
+            spans.truncate(1);
+            let dim = self.style.add_modifier(Modifier::DIM);
+            let mut chars = ghost.chars();
+            let first = chars.next().map(String::from).unwrap_or_default();
+            spans.push(Span::styled(first, dim.add_modifier(Modifier::REVERSED)));
+            spans.push(Span::styled(chars.as_str().to_string(), dim));
+        }
+        buf.set_line(area.x, area.y, &Line::from(spans), area.width);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    /// What a `width`-column field shows, cursor included
+    fn view(input: &TextInput, width: usize) -> String {
+        input.spans(width, true, Style::default()).iter().map(|s| s.content.as_ref()).collect()
+    }
+
+    #[test]
+    fn test_cursor_editing() {
+        let mut input = TextInput::new("héllo");
+        assert_eq!(input.cursor, 5);
+        input.handle_key(key(KeyCode::Home));
+        input.handle_key(key(KeyCode::Right));
+        input.handle_key(key(KeyCode::Right));
+        assert!(input.handle_key(key(KeyCode::Backspace)));
+        input.insert('e');
+        assert_eq!((input.value(), input.cursor), ("hello", 2));
+        input.handle_key(key(KeyCode::Delete));
+        assert_eq!(input.value(), "helo");
+
+        // Nothing to remove at either end
+        input.handle_key(key(KeyCode::End));
+        assert!(!input.delete());
+        assert!(!input.handle_key(KeyEvent::new(KeyCode::Char('a'), KeyModifiers::CONTROL)));
+        input.handle_key(key(KeyCode::Home));
+        assert!(!input.backspace());
+        input.handle_key(key(KeyCode::Left));
+        assert_eq!((input.value(), input.cursor), ("helo", 0));
+        assert!(!input.handle_key(key(KeyCode::Up)));
+        assert_eq!(input.take(), "helo");
+        assert!(input.is_empty());
+    }
+
+    #[test]
+    fn test_long_text_scrolls_to_the_cursor() {
+        let mut input = TextInput::new("abcdefghij");
+        // The end cursor needs a column of its own
+        assert_eq!(view(&input, 10), "bcdefghij█");
+        assert_eq!(view(&input, 11), "abcdefghij█");
+        assert_eq!(view(&input, 5), "ghij█");
+
+        // Moving left keeps the field full until the cursor reaches its edge
+        input.set_width(5);
+        for _ in 0..5 {
+            input.handle_key(key(KeyCode::Left));
+        }
+        assert_eq!(view(&input, 5), "fghij");
+        input.handle_key(key(KeyCode::Left));
+        assert_eq!(view(&input, 5), "efghi");
+        // and stays put as it moves back right within the field
+        input.handle_key(key(KeyCode::Right));
+        assert_eq!(view(&input, 5), "efghi");
+        input.handle_key(key(KeyCode::Home));
+        assert_eq!((view(&input, 5), input.fit(5)), ("abcde".to_string(), 0));
+        input.handle_key(key(KeyCode::End));
+        assert_eq!(view(&input, 5), "ghij█");
+
+        // Deleting from the end pulls the start back in
+        for _ in 0..6 {
+            input.backspace();
+        }
+        assert_eq!(view(&input, 5), "abcd█");
+        // Unfocused, the window still ends at the cursor
+        assert_eq!(input.spans(2, false, Style::default())[0].content, "d");
+    }
+
+    #[test]
+    fn test_wide_chars_scroll_by_columns() {
+        let input = TextInput::new("日本語テキスト");
+        assert_eq!(view(&input, 7), "キスト█");
+        assert_eq!(input.fit(7), 4);
+        // A wide char under the cursor takes both of its columns
+        let mut input = TextInput::new("ab日");
+        input.handle_key(key(KeyCode::Left));
+        assert_eq!(view(&input, 3), "b日");
+        assert_eq!(view(&input, 0), "日");
+    }
+
+    #[test]
+    fn test_ghost_text_follows_the_cursor_at_the_end() {
+        let draw = |input: &TextInput, focused: bool| {
+            let area = Rect::new(0, 0, 12, 1);
+            let mut buf = Buffer::empty(area);
+            TextInputWidget::new(input).focused(focused).ghost(Some("me Ltd")).render(area, &mut buf);
+            buf
+        };
+        let row = |buf: &Buffer| (0..12).map(|x| buf[(x, 0)].symbol().to_string()).collect::<String>();
+        let mut input = TextInput::new("Ac");
+        let buf = draw(&input, true);
+        assert_eq!(row(&buf), "Acme Ltd    ");
+        assert!(buf[(2, 0)].modifier.contains(Modifier::DIM | Modifier::REVERSED));
+        assert!(buf[(4, 0)].modifier.contains(Modifier::DIM));
+        assert!(!buf[(1, 0)].modifier.contains(Modifier::DIM));
+
+        // Only while focused with the cursor at the end
+        assert_eq!(row(&draw(&input, false)), "Ac          ");
+        input.handle_key(key(KeyCode::Left));
+        assert_eq!(row(&draw(&input, true)), "Ac          ");
+    }
+
+    #[test]
+    fn test_masked_input_hides_every_char() {
+        let mut input = TextInput::new("pässwort").masked();
+        assert_eq!(view(&input, 20), "********█");
+        assert_eq!(input.value(), "pässwort");
+        input.reveal();
+        assert_eq!(view(&input, 20), "pässwort█");
+        input.hide();
+        assert_eq!(view(&input, 20), "********█");
+    }
+}
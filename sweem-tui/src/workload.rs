@@ -0,0 +1,228 @@
+//! Manager workload heatmap.
+//!
+//! `Workload::compute` counts, per manager and calendar month, the open
+//! projects whose schedule overlaps the month; it is pure so the month
+//! boundaries can be tested. `WorkloadWidget` draws the counts as
+//! background-coloured cells.
+
+use chrono::{Datelike, Months, NaiveDate};
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Modifier, Style};
+use ratatui::widgets::{Block, Borders, Widget};
+use uuid::Uuid;
+
+use crate::models::{ProjectDto, Role, UserDto};
+use crate::theme::{colors, heat_color, styles};
+
+/// Months shown, starting with the current one
+pub const MONTHS: usize = 6;
+
+/// Width of the manager name column
+const NAME_WIDTH: u16 = 16;
+
+/// First day of the month `offset` months after the one holding `date`
+pub fn month_start(date: NaiveDate, offset: u32) -> NaiveDate {
+    date.with_day(1).unwrap_or(date) + Months::new(offset)
+}
+
+/// Whether a project running from `start` through `end` overlaps the
+/// half-open month `[from, to)`
+pub fn overlaps(start: NaiveDate, end: NaiveDate, from: NaiveDate, to: NaiveDate) -> bool {
+    start < to && end >= from
+}
+
+/// One manager's row of the heatmap
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorkloadRow {
+    pub manager_id: Uuid,
+    pub name: String,
+    /// Indices into the project list, one entry per month
+    pub cells: Vec<Vec<usize>>,
+}
+
+/// Open projects per manager and month
+#[derive(Debug, Clone, PartialEq)]
+pub struct Workload {
+    /// First day of each column's month
+    pub months: Vec<NaiveDate>,
+    pub rows: Vec<WorkloadRow>,
+}
+
+impl Workload {
+    /// Count open, scheduled projects over `months` months from `today`'s.
+    /// Rows are every manager plus anyone else who manages an open project.
+    pub fn compute(projects: &[ProjectDto], users: &[UserDto], today: NaiveDate, months: usize) -> Self {
+        let months: Vec<NaiveDate> = (0..months as u32).map(|m| month_start(today, m)).collect();
+        let open: Vec<usize> = (0..projects.len())
+            .filter(|&i| projects[i].has_valid_dates() && !projects[i].is_completed())
+            .collect();
+
+        let manages = |id: Uuid| open.iter().any(|&i| projects[i].manager_id == id);
+        let mut managers: Vec<(Uuid, String)> = users
+            .iter()
+            .filter(|u| u.role == Role::Manager || manages(u.id))
+            .map(|u| (u.id, u.display_name().to_string()))
+            .collect();
+        let unknown = open.iter().any(|&i| !users.iter().any(|u| u.id == projects[i].manager_id));
+        if unknown {
+            managers.push((Uuid::nil(), "Unknown manager".to_string()));
+        }
+
+        let rows = managers
+            .into_iter()
+            .map(|(manager_id, name)| {
+                let theirs = |&&i: &&usize| {
+                    let id = projects[i].manager_id;
+                    if manager_id.is_nil() {
+                        !users.iter().any(|u| u.id == id)
+                    } else {
+                        id == manager_id
+                    }
+                };
+                let cells = months
+                    .iter()
+                    .map(|&from| {
+                        let to = from + Months::new(1);
+                        open.iter()
+                            .filter(theirs)
+                            .filter(|&&i| overlaps(projects[i].start_date, projects[i].planned_end_date, from, to))
+                            .copied()
+                            .collect()
+                    })
+                    .collect();
+                WorkloadRow { manager_id, name, cells }
+            })
+            .collect();
+        Self { months, rows }
+    }
+
+    /// Project indices in a cell; empty outside the grid
+    pub fn cell(&self, row: usize, col: usize) -> &[usize] {
+        self.rows
+            .get(row)
+            .and_then(|r| r.cells.get(col))
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Count in the busiest cell
+    pub fn max(&self) -> usize {
+        self.rows.iter().flat_map(|r| &r.cells).map(Vec::len).max().unwrap_or(0)
+    }
+}
+
+/// The heatmap, with a cursor on one cell
+pub struct WorkloadWidget<'a> {
+    workload: &'a Workload,
+    cursor: (usize, usize),
+}
+
+impl<'a> WorkloadWidget<'a> {
+    pub fn new(workload: &'a Workload, cursor: (usize, usize)) -> Self {
+        Self { workload, cursor }
+    }
+}
+
+impl Widget for WorkloadWidget<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let block = Block::default()
+            .title(" Workload — open projects per month ")
+            .title_style(styles::title_accent())
+            .borders(Borders::ALL)
+            .border_style(styles::border())
+            .style(Style::default().bg(colors::BG_DARK));
+        let inner = block.inner(area);
+        block.render(area, buf);
+        if inner.width <= NAME_WIDTH || inner.height < 2 {
+            return;
+        }
+
+        let months = self.workload.months.len().max(1) as u16;
+        let cell_width = ((inner.width - NAME_WIDTH) / months).clamp(4, 10);
+        let column = |col: usize| inner.x + NAME_WIDTH + col as u16 * cell_width;
+        for (col, month) in self.workload.months.iter().enumerate() {
+            let label = month.format("%b %y").to_string();
+            buf.set_stringn(column(col), inner.y, label, cell_width as usize, styles::text_dim());
+        }
+
+        // Keep the cursor row on screen
+        let visible = (inner.height - 1) as usize;
+        let first = self.cursor.0.saturating_sub(visible.saturating_sub(1));
+        let max = self.workload.max();
+        for (line, (r, row)) in self.workload.rows.iter().enumerate().skip(first).take(visible).enumerate() {
+            let y = inner.y + 1 + line as u16;
+            let name_style = if r == self.cursor.0 { styles::title() } else { styles::text() };
+            buf.set_stringn(inner.x, y, &row.name, NAME_WIDTH as usize - 1, name_style);
+            for (col, cell) in row.cells.iter().enumerate() {
+                let x = column(col);
+                if x + cell_width > inner.x + inner.width {
+                    break;
+                }
+                let mut style = Style::default().fg(colors::FG_PRIMARY).bg(heat_color(cell.len(), max));
+                if (r, col) == self.cursor {
+                    style = style.fg(colors::YELLOW).add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+                }
+                let text = if cell.is_empty() { "·".to_string() } else { cell.len().to_string() };
+                let width = cell_width as usize - 1;
+                buf.set_string(x, y, format!("{:^width$}", text), style);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::theme::HEAT_RAMP;
+    use crate::test_support::{date, project, user, ProjectFixture, UserFixture};
+
+    #[test]
+    fn test_month_overlap_is_half_open() {
+        let (may, june) = (date("2024-05-01"), date("2024-06-01"));
+        assert!(overlaps(date("2024-05-31"), date("2024-05-31"), may, june));
+        assert!(overlaps(date("2024-04-30"), date("2024-05-01"), may, june));
+        assert!(overlaps(date("2024-01-01"), date("2024-12-31"), may, june));
+        // Starting on the first of the next month doesn't count
+        assert!(!overlaps(date("2024-06-01"), date("2024-06-30"), may, june));
+        assert!(!overlaps(date("2024-03-01"), date("2024-04-30"), may, june));
+
+        assert_eq!(month_start(date("2024-11-17"), 0), date("2024-11-01"));
+        assert_eq!(month_start(date("2024-11-17"), 3), date("2025-02-01"));
+    }
+
+    #[test]
+    fn test_projects_spanning_months_count_in_each() {
+        let users = vec![user(1).named("Kim"), user(2).named("Ada").role(Role::Admin), user(3).named("Lee")];
+        let projects = vec![
+            project(10).manager(1).starts("2024-04-15").ends("2024-06-01"),
+            project(11).manager(1).starts("2024-05-10").ends("2024-05-12"),
+            project(12).manager(2).starts("2024-07-01").ends("2024-07-31"),
+            project(13).manager(9).starts("2024-10-01").ends("2025-03-01"),
+            project(14).manager(1).starts("2024-05-01").ends("2024-05-20").done(),
+        ];
+        let workload = Workload::compute(&projects, &users, date("2024-05-10"), MONTHS);
+
+        assert_eq!(workload.months.len(), 6);
+        assert_eq!(workload.months[5], date("2024-10-01"));
+        let names: Vec<&str> = workload.rows.iter().map(|r| r.name.as_str()).collect();
+        // Admins only appear with open projects; unknown managers get their own row
+        assert_eq!(names, ["Kim", "Ada", "Lee", "Unknown manager"]);
+
+        let counts = |row: usize| -> Vec<usize> { workload.rows[row].cells.iter().map(Vec::len).collect() };
+        assert_eq!(counts(0), [2, 1, 0, 0, 0, 0]);
+        assert_eq!(workload.cell(0, 1), &[0]);
+        assert_eq!(counts(1), [0, 0, 1, 0, 0, 0]);
+        assert_eq!(counts(2), [0; 6]);
+        assert_eq!(counts(3), [0, 0, 0, 0, 0, 1]);
+        assert_eq!(workload.max(), 2);
+        assert!(workload.cell(7, 0).is_empty());
+    }
+
+    #[test]
+    fn test_heat_ramp() {
+        assert_eq!(heat_color(0, 4), HEAT_RAMP[0]);
+        assert_eq!(heat_color(1, 1), HEAT_RAMP[HEAT_RAMP.len() - 1]);
+        assert_eq!(heat_color(1, 100), HEAT_RAMP[1]);
+        assert_eq!(heat_color(50, 100), HEAT_RAMP[3]);
+    }
+}